@@ -206,8 +206,9 @@ namespace RealmImporter
             foreach (var asset in import.Assets)
             {
                 // if this asset’s hash matches one of our level‐icon hashes,
-                // then use it as its own AsMainlineIconHash
-                if (iconHashes.Contains(asset.AssetHash))
+                // then use it as its own AsMainlineIconHash, unless the export
+                // already points it at a converted PNG
+                if (iconHashes.Contains(asset.AssetHash) && string.IsNullOrEmpty(asset.AsMainlineIconHash))
                 {
                     asset.AsMainlineIconHash = asset.AssetHash;
                 }
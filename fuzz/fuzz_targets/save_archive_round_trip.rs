@@ -0,0 +1,42 @@
+//! Random resources written as a save archive, sealed into chunks and read
+//! back must come out the same, with a valid hashinate.
+
+#![no_main]
+
+use std::collections::BTreeMap;
+
+use archive_dl::resource_parse::ResrcRevision;
+use archive_dl::save::{FarKind, extract, join_chunks, parse_far};
+use archive_dl::serializers::lbp::{build_archive, seal_chunks};
+use libfuzzer_sys::fuzz_target;
+use sha1::{Digest, Sha1};
+
+fuzz_target!(|input: ((u32, u16, u16), [u8; 20], u8, Vec<Vec<u8>>)| {
+    // `chunk_words` is small so the archive spans several chunks
+    let ((head, branch_id, branch_revision), slt_hash, chunk_words, blobs) = input;
+    let revision = ResrcRevision {
+        head,
+        branch_id,
+        branch_revision,
+    };
+    let resources: BTreeMap<[u8; 20], Vec<u8>> = blobs
+        .into_iter()
+        .map(|blob| (Sha1::digest(&blob).into(), blob))
+        .collect();
+    let chunk_size = (chunk_words as usize + 1) * 4;
+
+    let plain = build_archive(&revision, slt_hash, &resources).unwrap();
+    let mut sealed = plain.clone();
+    seal_chunks(&mut sealed, chunk_size);
+    let chunks: Vec<Vec<u8>> = sealed.chunks(chunk_size).map(<[u8]>::to_vec).collect();
+    assert!(chunks.last().unwrap().ends_with(b"FAR4"));
+
+    let joined = join_chunks(chunks);
+    assert!(joined == plain, "chunks didn't decrypt back");
+    let archive = parse_far(&joined).unwrap();
+    assert_eq!(archive.kind, FarKind::Far4);
+    assert_eq!(archive.hashinate_ok, Some(true));
+    let extracted = extract(&joined, &archive);
+    assert_eq!((extracted.out_of_range, extracted.bad_hash), (0, 0));
+    assert!(extracted.resources == resources);
+});
@@ -0,0 +1,13 @@
+//! Damaged saves have to be reported, not crash the reader.
+
+#![no_main]
+
+use archive_dl::save::{extract, join_chunks, parse_far};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|chunks: Vec<Vec<u8>>| {
+    let data = join_chunks(chunks);
+    if let Ok(archive) = parse_far(&data) {
+        extract(&data, &archive);
+    }
+});
@@ -0,0 +1,109 @@
+// src/adventure.rs
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::{Result, anyhow, bail};
+
+use crate::resource_parse::{ResrcData, ResrcDescriptor, ResrcMethod};
+
+/// resource magics that hold an adventure's shared state (quests, items,
+/// adventure-wide settings) rather than belonging to a single level
+const SHARED_DATA_MAGICS: [[u8; 3]; 2] = [*b"ADS", *b"QST"];
+
+/// resources we walk through while looking for shared data, we don't
+/// descend into the levels themselves since those are huge
+const WALK_MAGICS: [[u8; 3]; 3] = [*b"ADC", *b"ADS", *b"QST"];
+
+pub struct AdventureSharedData {
+    /// shared data resources with their declared resource type, ready to be
+    /// appended to the slotlist dependency table
+    pub dependencies: Vec<(ResrcDescriptor, u32)>,
+    pub quest_count: usize,
+    /// hashes referenced by the adventure or its shared data that weren't downloaded
+    pub missing: Vec<[u8; 20]>,
+    /// resources of the walk that don't parse, with why. What they refer
+    /// to isn't looked at
+    pub unreadable: Vec<([u8; 20], String)>,
+}
+
+fn magic(data: &[u8]) -> Option<[u8; 3]> {
+    data.get(..3).and_then(|m| m.try_into().ok())
+}
+
+/// Walk the adventure root and its shared data resources, collecting every
+/// shared data resource the game needs loaded alongside the adventure slot.
+/// Fails only if the root isn't there or isn't an adventure, resources of
+/// the walk that are missing or don't parse are listed and skipped
+pub fn collect_shared_data(
+    root: [u8; 20],
+    resources: &BTreeMap<[u8; 20], Vec<u8>>,
+) -> Result<AdventureSharedData> {
+    let root_data = resources
+        .get(&root)
+        .ok_or_else(|| anyhow!("adventure root is missing from the downloaded resources"))?;
+    if magic(root_data) != Some(*b"ADC") {
+        bail!("adventure root is not an ADC resource");
+    }
+
+    let mut dependencies = Vec::new();
+    let mut quest_count = 0;
+    let mut missing = Vec::new();
+    let mut unreadable = Vec::new();
+
+    let mut visited = BTreeSet::new();
+    let mut queue = vec![root];
+    visited.insert(root);
+
+    while let Some(hash) = queue.pop() {
+        let Some(data) = resources.get(&hash) else {
+            continue;
+        };
+        let method = match ResrcData::new(data, false) {
+            Ok(resrc) => resrc.method,
+            Err(e) => {
+                unreadable.push((hash, e.to_string()));
+                continue;
+            }
+        };
+        let ResrcMethod::Binary {
+            dependencies: deps, ..
+        } = method
+        else {
+            continue;
+        };
+
+        for dep in deps {
+            let ResrcDescriptor::Sha1(sha1) = dep.desc else {
+                continue;
+            };
+            if !visited.insert(sha1) {
+                continue;
+            }
+
+            let Some(dep_data) = resources.get(&sha1) else {
+                missing.push(sha1);
+                continue;
+            };
+            let Some(dep_magic) = magic(dep_data) else {
+                continue;
+            };
+
+            if SHARED_DATA_MAGICS.contains(&dep_magic) {
+                dependencies.push((dep.desc, dep.resrc_type));
+                if dep_magic == *b"QST" {
+                    quest_count += 1;
+                }
+            }
+            if WALK_MAGICS.contains(&dep_magic) {
+                queue.push(sha1);
+            }
+        }
+    }
+
+    Ok(AdventureSharedData {
+        dependencies,
+        quest_count,
+        missing,
+        unreadable,
+    })
+}
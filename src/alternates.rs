@@ -0,0 +1,168 @@
+// src/alternates.rs
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Result, anyhow, bail};
+use sha1::{Digest, Sha1};
+
+use crate::messages::Msg;
+use crate::resource_parse::{ResrcData, ResrcDescriptor, ResrcMethod, replace_sha1_references};
+
+/// Resources known under more than one hash, re-serialized but the same
+/// content, so a missing one can be swapped for another.
+///
+/// The file has one group of equivalent hashes per line, separated by spaces
+/// or commas, `h` in front of a hash is fine, `#` starts a comment. The
+/// others of a group are tried in the order they're listed
+#[derive(Debug, Default)]
+pub struct AlternateTable {
+    groups: Vec<Vec<[u8; 20]>>,
+    /// hash → index into `groups`
+    group_of: BTreeMap<[u8; 20], usize>,
+}
+
+impl AlternateTable {
+    pub fn read(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path)
+            .map_err(|e| anyhow!("failed to open {}: {}", path.display(), e))?;
+        let mut table = Self::default();
+        for (i, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default();
+            let mut group = Vec::new();
+            for word in line.split([' ', '\t', ',']).filter(|w| !w.is_empty()) {
+                let hash: [u8; 20] = hex::decode(word.strip_prefix('h').unwrap_or(word))
+                    .ok()
+                    .and_then(|bytes| bytes.try_into().ok())
+                    .ok_or_else(|| {
+                        anyhow!(
+                            "`{}` on line {} of {} isn't a SHA1",
+                            word,
+                            i + 1,
+                            path.display()
+                        )
+                    })?;
+                if !group.contains(&hash) {
+                    group.push(hash);
+                }
+            }
+            match group.len() {
+                0 => continue,
+                1 => bail!("line {} of {} has only one hash", i + 1, path.display()),
+                _ => {}
+            }
+            for hash in &group {
+                if table.group_of.insert(*hash, table.groups.len()).is_some() {
+                    bail!(
+                        "{} is in more than one group of {}",
+                        hex::encode(hash),
+                        path.display()
+                    );
+                }
+            }
+            table.groups.push(group);
+        }
+        Ok(table)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+
+    /// The hashes `hash` can be swapped for, in the order to try them
+    pub fn alternates(&self, hash: &[u8; 20]) -> impl Iterator<Item = &[u8; 20]> {
+        self.group_of
+            .get(hash)
+            .map(|&group| &self.groups[group][..])
+            .unwrap_or_default()
+            .iter()
+            .filter(move |other| *other != hash)
+    }
+}
+
+/// Point the resources of a level at the alternates fetched for missing
+/// hashes, `swapped` is missing → alternate. A resource that's rewritten gets
+/// a new hash, so whatever refers to it is rewritten too, up to the root.
+///
+/// Returns every hash that changed, old → new, the swapped ones included.
+/// A resource that can't be rewritten keeps its hash and the missing one,
+/// with a warning
+pub fn patch_references(
+    resources: &mut BTreeMap<[u8; 20], Vec<u8>>,
+    swapped: &BTreeMap<[u8; 20], [u8; 20]>,
+) -> BTreeMap<[u8; 20], [u8; 20]> {
+    let dependencies: BTreeMap<[u8; 20], BTreeSet<[u8; 20]>> = resources
+        .iter()
+        .map(|(hash, blob)| (*hash, sha1_dependencies(blob)))
+        .collect();
+    // everything that refers to a swapped hash, directly or not
+    let mut stale: BTreeSet<[u8; 20]> = BTreeSet::new();
+    loop {
+        let before = stale.len();
+        for (hash, deps) in &dependencies {
+            if deps
+                .iter()
+                .any(|dep| swapped.contains_key(dep) || stale.contains(dep))
+            {
+                stale.insert(*hash);
+            }
+        }
+        if stale.len() == before {
+            break;
+        }
+    }
+
+    // a resource is rewritten once its stale dependencies are, so it's
+    // rewritten only once
+    let mut replaced = swapped.clone();
+    while !stale.is_empty() {
+        let ready: Vec<[u8; 20]> = stale
+            .iter()
+            .filter(|hash| dependencies[*hash].is_disjoint(&stale))
+            .copied()
+            .collect();
+        // only a malformed resource can refer to itself
+        if ready.is_empty() {
+            break;
+        }
+        for old in ready {
+            stale.remove(&old);
+            let blob = &resources[&old];
+            match replace_sha1_references(blob, &replaced) {
+                Ok(Some(patched)) => {
+                    let new: [u8; 20] = Sha1::digest(&patched).into();
+                    resources.remove(&old);
+                    resources.insert(new, patched);
+                    replaced.insert(old, new);
+                }
+                Ok(None) => {}
+                Err(e) => eprintln!(
+                    "⚠️ {}",
+                    Msg::AlternatesNotPointed {
+                        hash: &hex::encode(old),
+                        error: &e.to_string(),
+                    }
+                ),
+            }
+        }
+    }
+    replaced
+}
+
+fn sha1_dependencies(blob: &[u8]) -> BTreeSet<[u8; 20]> {
+    let Ok(ResrcData {
+        method: ResrcMethod::Binary { dependencies, .. },
+        ..
+    }) = ResrcData::new(blob, false)
+    else {
+        return BTreeSet::new();
+    };
+    dependencies
+        .iter()
+        .filter_map(|d| match d.desc {
+            ResrcDescriptor::Sha1(sha1) => Some(sha1),
+            ResrcDescriptor::Guid(_) => None,
+        })
+        .collect()
+}
@@ -0,0 +1,732 @@
+// src/annotate.rs
+
+use std::fmt::Write;
+
+use anyhow::{Result, anyhow, bail};
+use serde::{Deserialize, Serialize};
+
+use crate::resource_parse::{
+    Branch, COMPRESSED_INTEGERS, ResrcDescriptor, ResrcRevision, binary_payload, resrc_type_name,
+};
+
+/// One field of a resource, where it is and what it says. Structs have
+/// their fields in `fields` and no value of their own
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Field {
+    pub name: String,
+    pub offset: usize,
+    pub size: usize,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub value: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<Field>,
+}
+
+/// The header, the serialized data or the dependency table of a resource
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Section {
+    pub name: String,
+    /// the offsets are in the inflated data, not in the file
+    #[serde(default)]
+    pub inflated: bool,
+    pub fields: Vec<Field>,
+    /// why decoding stopped early, the fields up to there are kept
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// A resource taken apart field by field, for `annotate-resource`
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Annotation {
+    /// `SLTb`, `PLNb`, `TEX `, …
+    pub magic: String,
+    pub size: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revision: Option<String>,
+    pub sections: Vec<Section>,
+}
+
+/// Reads fields of a resource and records them. Ints are LEB128 in
+/// resources with compressed integers, like `SlotReader` in `save_slots`
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+    /// added to `pos` for the offsets, where `data` is in the file
+    base: usize,
+    compressed: bool,
+    revision: ResrcRevision,
+    fields: Vec<Field>,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8], base: usize, compressed: bool, revision: ResrcRevision) -> Self {
+        Self {
+            data,
+            pos: 0,
+            base,
+            compressed,
+            revision,
+            fields: Vec::new(),
+        }
+    }
+
+    fn version(&self) -> u16 {
+        self.revision.get_version()
+    }
+
+    fn subversion(&self) -> u16 {
+        self.revision.get_subversion()
+    }
+
+    fn take(&mut self, len: usize, name: &str) -> Result<&'a [u8]> {
+        let bytes = self
+            .pos
+            .checked_add(len)
+            .and_then(|end| self.data.get(self.pos..end))
+            .ok_or_else(|| {
+                anyhow!(
+                    "the data ends at {:#06x}, in the middle of {}",
+                    self.base + self.data.len(),
+                    name
+                )
+            })?;
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    /// Record the field that started at `start` and ends here
+    fn push(&mut self, name: &str, start: usize, value: String) {
+        self.fields.push(Field {
+            name: name.to_string(),
+            offset: self.base + start,
+            size: self.pos - start,
+            value,
+            fields: Vec::new(),
+        });
+    }
+
+    fn read_u32(&mut self, name: &str) -> Result<u32> {
+        if !self.compressed {
+            let bytes = self.take(4, name)?;
+            return Ok(u32::from_be_bytes(bytes.try_into().unwrap()));
+        }
+        let mut value: u32 = 0;
+        for i in 0..5 {
+            let byte = self.take(1, name)?[0];
+            value |= ((byte & 0x7f) as u32)
+                .checked_shl(7 * i)
+                .ok_or_else(|| anyhow!("{} is too big for 32 bits", name))?;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        bail!("{} is too big for 32 bits", name)
+    }
+
+    fn u8(&mut self, name: &str) -> Result<u8> {
+        let start = self.pos;
+        let value = self.take(1, name)?[0];
+        self.push(name, start, value.to_string());
+        Ok(value)
+    }
+
+    fn bool(&mut self, name: &str) -> Result<bool> {
+        let start = self.pos;
+        let value = self.take(1, name)?[0] != 0;
+        self.push(name, start, value.to_string());
+        Ok(value)
+    }
+
+    fn u32(&mut self, name: &str) -> Result<u32> {
+        self.u32_as(name, |_| None)
+    }
+
+    /// A u32 with the name of its value, if `names` knows it
+    fn u32_as(&mut self, name: &str, names: fn(u32) -> Option<&'static str>) -> Result<u32> {
+        let start = self.pos;
+        let value = self.read_u32(name)?;
+        let text = match names(value) {
+            Some(meaning) => format!("{} ({})", value, meaning),
+            None => value.to_string(),
+        };
+        self.push(name, start, text);
+        Ok(value)
+    }
+
+    fn f32(&mut self, name: &str) -> Result<f32> {
+        let start = self.pos;
+        let value = f32::from_be_bytes(self.take(4, name)?.try_into().unwrap());
+        self.push(name, start, value.to_string());
+        Ok(value)
+    }
+
+    fn str(&mut self, name: &str) -> Result<String> {
+        let start = self.pos;
+        let len = self.read_u32(name)? as usize;
+        let value = String::from_utf8_lossy(self.take(len, name)?).into_owned();
+        self.push(name, start, format!("{:?}", value));
+        Ok(value)
+    }
+
+    fn wstr(&mut self, name: &str) -> Result<String> {
+        let start = self.pos;
+        let len = self.read_u32(name)? as usize;
+        let units: Vec<u16> = self
+            .take(len.saturating_mul(2), name)?
+            .chunks_exact(2)
+            .map(|c| u16::from_be_bytes([c[0], c[1]]))
+            .collect();
+        let value = String::from_utf16_lossy(&units);
+        self.push(name, start, format!("{:?}", value));
+        Ok(value)
+    }
+
+    /// A run of bytes that isn't decoded further, described by `value`
+    fn bytes(&mut self, name: &str, len: usize, value: String) -> Result<&'a [u8]> {
+        let start = self.pos;
+        let bytes = self.take(len, name)?;
+        self.push(name, start, value);
+        Ok(bytes)
+    }
+
+    /// A resource descriptor, `kind` is what the resource is expected to be
+    fn descriptor(&mut self, name: &str, kind: &str) -> Result<Option<ResrcDescriptor>> {
+        let start = self.pos;
+        // before 0x191 the two kinds were numbered the other way round
+        let (hash, guid) = match self.version() < 0x191 {
+            true => (2, 1),
+            false => (1, 2),
+        };
+        let (descriptor, value) = match self.take(1, name)?[0] {
+            0 => (None, "none".to_string()),
+            t if t == hash => {
+                let sha1: [u8; 20] = self.take(20, name)?.try_into().unwrap();
+                (
+                    Some(ResrcDescriptor::Sha1(sha1)),
+                    format!("{} sha1 {}", kind, hex::encode(sha1)),
+                )
+            }
+            t if t == guid => {
+                let g = self.read_u32(name)?;
+                (
+                    Some(ResrcDescriptor::Guid(g)),
+                    format!("{} guid g{}", kind, g),
+                )
+            }
+            t => bail!("{} has descriptor type {}, not 0, 1 or 2", name, t),
+        };
+        self.push(name, start, value);
+        Ok(descriptor)
+    }
+
+    /// A struct, its fields are what `read` records. They're kept if it fails
+    fn group(&mut self, name: &str, read: impl FnOnce(&mut Self) -> Result<()>) -> Result<()> {
+        let start = self.pos;
+        let outer = std::mem::take(&mut self.fields);
+        let result = read(self);
+        let fields = std::mem::replace(&mut self.fields, outer);
+        self.fields.push(Field {
+            name: name.to_string(),
+            offset: self.base + start,
+            size: self.pos - start,
+            value: String::new(),
+            fields,
+        });
+        result
+    }
+
+    /// A SlotID, a slot type and a number
+    fn slot_id(&mut self, name: &str) -> Result<()> {
+        self.group(name, |r| {
+            r.u32_as("type", slot_type_name)?;
+            r.u32("id")?;
+            Ok(())
+        })
+    }
+
+    /// The section of what was read, with why it stopped if it did
+    fn section(self, name: &str, inflated: bool, result: Result<()>) -> Section {
+        let mut fields = self.fields;
+        let mut error = result.err().map(|e| e.to_string());
+        if error.is_none() && self.pos < self.data.len() {
+            let rest = self.data.len() - self.pos;
+            fields.push(Field {
+                name: "(not decoded)".to_string(),
+                offset: self.base + self.pos,
+                size: rest,
+                value: format!("{} bytes", rest),
+                fields: Vec::new(),
+            });
+            error = Some(format!("{} bytes after the last field", rest));
+        }
+        Section {
+            name: name.to_string(),
+            inflated,
+            fields,
+            error,
+        }
+    }
+}
+
+/// the slot types the tool writes itself
+fn slot_type_name(value: u32) -> Option<&'static str> {
+    match value {
+        0 => Some("DEVELOPER"),
+        6 => Some("FAKE"),
+        _ => None,
+    }
+}
+
+fn level_type_name(value: u32) -> Option<&'static str> {
+    match value {
+        0 => Some("MAIN_PATH"),
+        6 => Some("VERSUS"),
+        7 => Some("CUTSCENE"),
+        _ => None,
+    }
+}
+
+fn revision_label(revision: &ResrcRevision) -> String {
+    let mut label = format!(
+        "{:#x} ({})",
+        revision.head,
+        revision.get_gameversion().get_short_title()
+    );
+    if !revision.is_mainline() {
+        let branch = revision
+            .get_branch()
+            .map_or("unknown branch".to_string(), |b| b.get_name().to_string());
+        let _ = write!(
+            label,
+            ", {} {:#x} revision {:#x}",
+            branch, revision.branch_id, revision.branch_revision
+        );
+    }
+    label
+}
+
+/// Where the header ends and how the data after it is stored
+struct Header {
+    revision: ResrcRevision,
+    table: Option<usize>,
+    data_start: usize,
+    is_compressed: bool,
+    flags: u8,
+}
+
+/// The resource header, the same fields `payload_span` and `ResrcData` read
+fn read_header(reader: &mut Reader, magic: &[u8]) -> Result<Header> {
+    reader.bytes("magic", 4, format!("{:?}", String::from_utf8_lossy(magic)))?;
+    let start = reader.pos;
+    let head = u32::from_be_bytes(reader.take(4, "revision")?.try_into().unwrap());
+    reader.push("revision", start, format!("{:#x}", head));
+    let mut header = Header {
+        revision: ResrcRevision {
+            head,
+            branch_id: 0,
+            branch_revision: 0,
+        },
+        table: None,
+        data_start: 0,
+        is_compressed: true,
+        flags: 0,
+    };
+    if head >= 0x109 {
+        header.table = Some(reader.read_u32_field("dependency table offset")? as usize);
+    }
+    let is_smh = magic.starts_with(b"SMH");
+    if head >= 0x189 && !is_smh {
+        if head >= 0x271 {
+            header.revision.branch_id = reader.read_u16_field("branch id")?;
+            header.revision.branch_revision = reader.read_u16_field("branch revision")?;
+        }
+        if head >= 0x297
+            || (head == 0x272
+                && Branch::from_id(header.revision.branch_id) == Some(Branch::Leerdammer)
+                && header.revision.branch_revision >= 0x2)
+        {
+            header.flags = reader.u8("compression flags")?;
+        }
+        header.is_compressed = reader.bool("is compressed")?;
+    }
+    header.data_start = reader.pos;
+    if header.is_compressed && !is_smh {
+        reader.group("chunks", |r| {
+            r.read_u16_field("unused")?;
+            let count = r.read_u16_field("count")?;
+            for i in 0..count {
+                r.group(&format!("chunk[{}]", i), |r| {
+                    r.read_u16_field("compressed size")?;
+                    r.read_u16_field("inflated size")?;
+                    Ok(())
+                })?;
+            }
+            Ok(())
+        })?;
+    }
+    Ok(header)
+}
+
+impl Reader<'_> {
+    /// a header u32, never compressed
+    fn read_u32_field(&mut self, name: &str) -> Result<u32> {
+        let start = self.pos;
+        let value = u32::from_be_bytes(self.take(4, name)?.try_into().unwrap());
+        self.push(name, start, format!("{:#x}", value));
+        Ok(value)
+    }
+
+    fn read_u16_field(&mut self, name: &str) -> Result<u16> {
+        let start = self.pos;
+        let value = u16::from_be_bytes(self.take(2, name)?.try_into().unwrap());
+        self.push(name, start, format!("{:#x}", value));
+        Ok(value)
+    }
+}
+
+/// The dependency table at `table`, as `ResrcDependency::parse_table` reads it
+fn read_dependencies(reader: &mut Reader) -> Result<()> {
+    let count = reader.read_u32_field("count")?;
+    for i in 0..count {
+        reader.group(&format!("dependency[{}]", i), |r| {
+            let start = r.pos;
+            let (kind, value) = match r.take(1, "kind")?[0] {
+                0 => ("none", None),
+                1 => ("sha1", Some(hex::encode(r.take(20, "sha1")?))),
+                2 => {
+                    let guid = u32::from_be_bytes(r.take(4, "guid")?.try_into().unwrap());
+                    ("guid", Some(format!("g{}", guid)))
+                }
+                t => bail!("dependency {} has kind {}, not 0, 1 or 2", i, t),
+            };
+            r.push(kind, start, value.unwrap_or_default());
+            let start = r.pos;
+            let resrc_type = u32::from_be_bytes(r.take(4, "type")?.try_into().unwrap());
+            let value = match resrc_type_name(resrc_type) {
+                Some(name) => format!("{} ({})", resrc_type, name),
+                None => resrc_type.to_string(),
+            };
+            r.push("type", start, value);
+            Ok(())
+        })?;
+    }
+    Ok(())
+}
+
+/// A slot list, the way `make_slotlist` writes it
+fn read_slot_list(reader: &mut Reader) -> Result<()> {
+    let count = reader.u32("slots")?;
+    for i in 0..count {
+        reader.group(&format!("slot[{}]", i), read_slot)?;
+    }
+    if reader.version() >= 0x3b6 {
+        reader.bool("fromProductionBuild")?;
+    }
+    Ok(())
+}
+
+/// One `Slot` struct, the fields and revisions of `make_slot_struct`
+fn read_slot(r: &mut Reader) -> Result<()> {
+    let version = r.version();
+    let subversion = r.subversion();
+    r.slot_id("id")?;
+    r.descriptor("rootLevel", "level")?;
+    if subversion >= 0x145 {
+        r.descriptor("adventure", "adventure")?;
+    }
+    r.descriptor("icon", "texture")?;
+    r.group("location", |r| {
+        for axis in ["x", "y", "z", "w"] {
+            r.f32(axis)?;
+        }
+        Ok(())
+    })?;
+    r.group("authorID", |r| {
+        let length_prefixed = r.version() < 0x234;
+        if length_prefixed {
+            r.u32("length")?;
+        }
+        let start = r.pos;
+        let data = r.take(16, "handle")?;
+        let end = data.iter().position(|&b| b == 0).unwrap_or(data.len());
+        let handle = String::from_utf8_lossy(&data[..end]).into_owned();
+        r.push("handle", start, format!("{:?}", handle));
+        r.u8("term")?;
+        if length_prefixed {
+            r.u32("length")?;
+        }
+        r.bytes("dummy", 3, String::new())?;
+        Ok(())
+    })?;
+    if version >= 0x13b {
+        r.wstr("authorName")?;
+    }
+    r.str("translationTag")?;
+    r.wstr("name")?;
+    r.wstr("description")?;
+    r.slot_id("primaryLinkLevel")?;
+    if version >= 0x134 {
+        r.slot_id("group")?;
+    }
+    r.bool("initiallyLocked")?;
+    if version > 0x237 {
+        r.bool("shareable")?;
+        r.u32("backgroundGUID")?;
+    }
+    if version > 0x333 {
+        r.descriptor("planetDecorations", "plan")?;
+    }
+    if version < 0x188 {
+        r.u8("unknown")?;
+    }
+    if version > 0x1de {
+        r.u32_as("developerLevelType", level_type_name)?;
+    } else {
+        r.bool("sideMission")?;
+    }
+    if version > 0x1ad && version < 0x1b9 {
+        r.u8("unknown")?;
+    }
+    if version > 0x1b8 && version < 0x36c {
+        r.u32_as("gameProgressionState", |v| (v == 0).then_some("NEW_GAME"))?;
+    }
+    if version <= 0x2c3 {
+        return Ok(());
+    }
+
+    if version >= 0x33c {
+        let count = r.u32("labels")?;
+        for i in 0..count {
+            r.group(&format!("label[{}]", i), |r| {
+                r.u32("key")?;
+                r.u32("order")?;
+                Ok(())
+            })?;
+        }
+    }
+    let collectabubbles = |r: &mut Reader, name: &str| -> Result<()> {
+        let count = r.u32(name)?;
+        for i in 0..count {
+            r.group(&format!("{}[{}]", name, i), |r| {
+                r.descriptor("plan", "plan")?;
+                r.u32("count")?;
+                Ok(())
+            })?;
+        }
+        Ok(())
+    };
+    if version >= 0x2ea {
+        collectabubbles(r, "collectabubblesRequired")?;
+    }
+    if version >= 0x2f4 {
+        collectabubbles(r, "collectabubblesContained")?;
+    }
+    if version >= 0x352 {
+        r.bool("isSubLevel")?;
+    }
+    if version < 0x3d0 {
+        return Ok(());
+    }
+
+    r.u8("minPlayers")?;
+    r.u8("maxPlayers")?;
+    if subversion >= 0x215 {
+        r.bool("enforceMinMaxPlayers")?;
+    }
+    r.bool("moveRecommended")?;
+    if version >= 0x3e9 {
+        r.bool("crossCompatible")?;
+    }
+    if version >= 0x3d1 {
+        r.bool("showOnPlanet")?;
+    }
+    if version >= 0x3d2 {
+        r.u8("livesOverride")?;
+    }
+    if !r.revision.is_lbp3() {
+        return Ok(());
+    }
+
+    if subversion >= 0x12 {
+        r.u8("gameMode")?;
+    }
+    if subversion >= 0xd2 {
+        r.bool("isGameKit")?;
+    }
+    if subversion >= 0x11b {
+        r.wstr("entranceName")?;
+        r.slot_id("originalSlotID")?;
+    }
+    if subversion >= 0x153 {
+        r.u8("customBadgeSize")?;
+    }
+    if subversion >= 0x192 {
+        r.str("localPath")?;
+        if subversion >= 0x206 {
+            r.str("thumbPath")?;
+        }
+    }
+    Ok(())
+}
+
+/// A plan: the things it places, serialized with their own revision, and
+/// from 0x197 the details the inventory shows. Neither is taken apart yet
+fn read_plan(r: &mut Reader) -> Result<()> {
+    if r.subversion() >= 0xcc {
+        r.bool("isUsedForStreaming")?;
+    }
+    let start = r.pos;
+    let head = r.read_u32("revision")?;
+    let revision = ResrcRevision { head, ..r.revision };
+    r.push("revision", start, revision_label(&revision));
+    let start = r.pos;
+    let len = r.read_u32("thingData")? as usize;
+    r.take(len, "thingData")?;
+    r.push("thingData", start, format!("{} bytes of things", len));
+    if r.version() >= 0x197 {
+        let rest = r.data.len() - r.pos;
+        r.bytes(
+            "inventoryData",
+            rest,
+            format!("{} bytes, not decoded", rest),
+        )?;
+    }
+    Ok(())
+}
+
+/// Take `res` apart: the header and dependency table of any binary
+/// resource, and the data of slot lists and plans field by field.
+/// Other resources only get their magic
+pub fn annotate(res: &[u8]) -> Result<Annotation> {
+    let magic = res
+        .get(..4)
+        .ok_or_else(|| anyhow!("{} bytes is too short for a resource", res.len()))?;
+    let mut annotation = Annotation {
+        magic: String::from_utf8_lossy(magic).into_owned(),
+        size: res.len(),
+        ..Default::default()
+    };
+    if magic[3] != b'b' {
+        let why = match magic[3] {
+            b'e' => "encrypted, only its magic is decoded",
+            _ => "not a binary resource, only its magic is decoded",
+        };
+        annotation.sections.push(Section {
+            name: "header".to_string(),
+            error: Some(why.to_string()),
+            ..Default::default()
+        });
+        return Ok(annotation);
+    }
+
+    let mut reader = Reader::new(
+        res,
+        0,
+        false,
+        ResrcRevision {
+            head: 0,
+            branch_id: 0,
+            branch_revision: 0,
+        },
+    );
+    let header = match read_header(&mut reader, magic) {
+        Ok(header) => header,
+        Err(e) => {
+            annotation
+                .sections
+                .push(reader.section("header", false, Err(e)));
+            return Ok(annotation);
+        }
+    };
+    annotation.revision = Some(revision_label(&header.revision));
+    // the chunk table is counted with the header, the data is what follows it
+    annotation.sections.push(Section {
+        name: "header".to_string(),
+        fields: reader.fields,
+        ..Default::default()
+    });
+
+    let data_end = header.table.unwrap_or(res.len()).min(res.len());
+    let compressed_ints = header.flags & COMPRESSED_INTEGERS != 0;
+    let (data, base) = match header.is_compressed {
+        true => match binary_payload(res)? {
+            Some((data, _)) => (data, 0),
+            // static meshes keep their data to themselves
+            None => return Ok(annotation),
+        },
+        false => (
+            res[header.data_start.min(data_end)..data_end].to_vec(),
+            header.data_start,
+        ),
+    };
+    let mut reader = Reader::new(&data, base, compressed_ints, header.revision);
+    let result = match &magic[..3] {
+        b"SLT" => read_slot_list(&mut reader),
+        b"PLN" => read_plan(&mut reader),
+        _ => reader
+            .bytes(
+                "data",
+                data.len(),
+                format!("{} bytes, not decoded", data.len()),
+            )
+            .map(|_| ()),
+    };
+    annotation
+        .sections
+        .push(reader.section("data", header.is_compressed, result));
+
+    if let Some(table) = header.table {
+        let mut reader = Reader::new(
+            res.get(table..).unwrap_or_default(),
+            table,
+            false,
+            header.revision,
+        );
+        let result = read_dependencies(&mut reader);
+        annotation
+            .sections
+            .push(reader.section("dependencies", false, result));
+    }
+    Ok(annotation)
+}
+
+impl Annotation {
+    /// The nested text view, one field per line as offset, name and value
+    pub fn render(&self) -> String {
+        let mut out = format!("{}, {} bytes", self.magic, self.size);
+        if let Some(revision) = &self.revision {
+            let _ = write!(out, ", revision {}", revision);
+        }
+        out.push('\n');
+        for section in &self.sections {
+            let where_ = match section.inflated {
+                true => "offsets in the inflated data",
+                false => "offsets in the file",
+            };
+            let _ = writeln!(out, "{} ({})", section.name, where_);
+            render_fields(&mut out, &section.fields, 1);
+            if let Some(error) = &section.error {
+                let _ = writeln!(out, "  ! {}", error);
+            }
+        }
+        out
+    }
+}
+
+fn render_fields(out: &mut String, fields: &[Field], depth: usize) {
+    const NAME_WIDTH: usize = 28;
+    for field in fields {
+        let indent = "  ".repeat(depth);
+        let width = NAME_WIDTH.saturating_sub(indent.len());
+        let _ = writeln!(
+            out,
+            "{}{:#06x}  {:<width$}  {}",
+            indent,
+            field.offset,
+            field.name,
+            field.value,
+            width = width
+        );
+        render_fields(out, &field.fields, depth + 1);
+    }
+}
@@ -0,0 +1,376 @@
+// src/archive_layout.rs
+
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{BufReader, ErrorKind, Read};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::UNIX_EPOCH;
+
+use anyhow::{Result, anyhow, bail};
+
+use crate::resource_dl::{zip_entry_name, zip_location};
+
+/// How the archive below `archive_path` is packaged, see `detect`
+pub enum ArchiveLayout {
+    /// the original dump, 256 `dryXX.zip` in their `res` folders
+    Split(PathBuf),
+    /// every blob in one ZIP, as `ab/cd/<sha1>`
+    SingleZip(PathBuf),
+    /// uncompressed tars holding the blobs, found through an index of where
+    /// each one starts
+    Tars(TarIndex),
+}
+
+/// Where one blob is stored
+pub enum Location {
+    Zip {
+        path: PathBuf,
+        entry: String,
+    },
+    Tar {
+        path: PathBuf,
+        offset: u64,
+        size: u64,
+    },
+}
+
+impl Location {
+    /// the ZIP or tar the blob is in
+    pub fn container(&self) -> &Path {
+        match self {
+            Self::Zip { path, .. } | Self::Tar { path, .. } => path,
+        }
+    }
+}
+
+/// the first piece of a ZIP cut into `.zip.001`, `.zip.002`, …
+fn zip_pieces(zip: &Path) -> PathBuf {
+    let mut piece = zip.as_os_str().to_owned();
+    piece.push(".001");
+    PathBuf::from(piece)
+}
+
+fn has_extension(path: &Path, ext: &str) -> bool {
+    path.extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case(ext))
+}
+
+impl ArchiveLayout {
+    /// Work out the layout from what's at `root`:
+    /// - a `.zip` or `.tar` file is the whole archive, so is a `.zip` cut
+    ///   into `.zip.001`, `.zip.002`, … pieces
+    /// - a folder with the original `res` folders is the split layout
+    /// - otherwise a folder holding `.tar` files, or exactly one `.zip`
+    ///
+    /// Anything else, remote sources included, is taken for the split layout
+    pub fn detect(root: &Path, index_dir: &Path) -> Result<Self> {
+        if root.is_file() {
+            if has_extension(root, "zip") {
+                return Ok(Self::SingleZip(root.to_path_buf()));
+            }
+            if has_extension(root, "tar") {
+                return Ok(Self::Tars(TarIndex::build(
+                    vec![root.to_path_buf()],
+                    index_dir,
+                )?));
+            }
+            bail!(
+                "archive_path {} is a file, but neither a .zip nor a .tar",
+                root.display()
+            );
+        }
+        if has_extension(root, "zip") && zip_pieces(root).is_file() {
+            return Ok(Self::SingleZip(root.to_path_buf()));
+        }
+        let Ok(dir) = fs::read_dir(root) else {
+            return Ok(Self::Split(root.to_path_buf()));
+        };
+
+        let mut zips = Vec::new();
+        let mut tars = Vec::new();
+        for entry in dir.flatten() {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if path.is_dir() && name.starts_with("LBP online levels 2023") {
+                return Ok(Self::Split(root.to_path_buf()));
+            }
+            if !path.is_file() {
+                continue;
+            }
+            if has_extension(&path, "zip") {
+                zips.push(path);
+            } else if let Some(zip) = name.strip_suffix(".001").filter(|zip| zip.ends_with(".zip")) {
+                zips.push(root.join(zip));
+            } else if has_extension(&path, "tar") {
+                tars.push(path);
+            } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+                bail!(
+                    "{} is compressed, blobs can't be read out of it directly. Unpack it to a plain .tar first",
+                    path.display()
+                );
+            }
+        }
+
+        if !tars.is_empty() {
+            tars.sort();
+            return Ok(Self::Tars(TarIndex::build(tars, index_dir)?));
+        }
+        match zips.len() {
+            1 => Ok(Self::SingleZip(zips.remove(0))),
+            _ => Ok(Self::Split(root.to_path_buf())),
+        }
+    }
+
+    /// Where the blob `hex` would be, `None` if an index says it isn't there
+    pub fn locate(&self, hex: &str) -> Option<Location> {
+        match self {
+            Self::Split(root) => Some(Location::Zip {
+                path: root.join(zip_location(&hex[0..2])),
+                entry: zip_entry_name(hex),
+            }),
+            Self::SingleZip(path) => Some(Location::Zip {
+                path: path.clone(),
+                entry: zip_entry_name(hex),
+            }),
+            Self::Tars(index) => index.locate(hex),
+        }
+    }
+
+    /// one line about the layout, for the log
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Split(_) => "split into per-prefix ZIPs".to_string(),
+            Self::SingleZip(path) => format!("a single ZIP, {}", path.display()),
+            Self::Tars(index) => format!(
+                "{} tar(s) with {} resources indexed",
+                index.tars.len(),
+                index.entries.len()
+            ),
+        }
+    }
+}
+
+/// Where every blob in a set of tars starts
+pub struct TarIndex {
+    tars: Vec<PathBuf>,
+    /// SHA1 → (tar, offset of the data, size)
+    entries: HashMap<[u8; 20], (usize, u64, u64)>,
+}
+
+/// start of a saved tar index, bumped if the format changes
+const INDEX_MAGIC: &[u8; 8] = b"TARIDX01";
+const BLOCK: u64 = 512;
+/// GNU long names and pax headers are paths, anything bigger is a corrupt tar
+const MAX_LONG_HEADER: u64 = 64 * 1024;
+
+impl TarIndex {
+    /// Index the tars, reusing indexes saved in `index_dir` by earlier runs
+    /// as long as the tar hasn't changed since
+    fn build(tars: Vec<PathBuf>, index_dir: &Path) -> Result<Self> {
+        fs::create_dir_all(index_dir).map_err(|e| {
+            anyhow!(
+                "couldn't create tar index dir `{}`: {}",
+                index_dir.display(),
+                e
+            )
+        })?;
+        let mut entries = HashMap::new();
+        for (i, tar) in tars.iter().enumerate() {
+            let saved = index_dir.join(index_name(tar)?);
+            let blobs = match read_saved_index(&saved) {
+                Some(blobs) => blobs,
+                None => {
+                    eprintln!("▶ Indexing {}, this only happens once", tar.display());
+                    let blobs = scan_tar(tar)?;
+                    write_saved_index(&saved, &blobs)?;
+                    blobs
+                }
+            };
+            for (hash, offset, size) in blobs {
+                entries.insert(hash, (i, offset, size));
+            }
+        }
+        Ok(Self { tars, entries })
+    }
+
+    fn locate(&self, hex: &str) -> Option<Location> {
+        let hash: [u8; 20] = hex::decode(hex).ok()?.try_into().ok()?;
+        let &(tar, offset, size) = self.entries.get(&hash)?;
+        Some(Location::Tar {
+            path: self.tars[tar].clone(),
+            offset,
+            size,
+        })
+    }
+}
+
+/// saved indexes are named after the tar (or ZIP), its size and
+/// modification time, so a replaced one gets indexed again
+pub(crate) fn index_name(tar: &Path) -> Result<String> {
+    let meta = fs::metadata(tar).map_err(|e| anyhow!("couldn't open {}: {}", tar.display(), e))?;
+    let modified = meta
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |d| d.as_secs());
+    let name = tar.file_name().unwrap_or_default().to_string_lossy();
+    Ok(format!("{}-{}-{}.idx", name, meta.len(), modified))
+}
+
+fn read_saved_index(path: &Path) -> Option<Vec<([u8; 20], u64, u64)>> {
+    let data = fs::read(path).ok()?;
+    let body = data.strip_prefix(INDEX_MAGIC)?;
+    if body.len() % 36 != 0 {
+        return None;
+    }
+    Some(
+        body.chunks_exact(36)
+            .map(|e| {
+                let hash = e[..20].try_into().unwrap();
+                let offset = u64::from_le_bytes(e[20..28].try_into().unwrap());
+                let size = u64::from_le_bytes(e[28..].try_into().unwrap());
+                (hash, offset, size)
+            })
+            .collect(),
+    )
+}
+
+fn write_saved_index(path: &Path, blobs: &[([u8; 20], u64, u64)]) -> Result<()> {
+    let mut data = Vec::with_capacity(INDEX_MAGIC.len() + blobs.len() * 36);
+    data.extend_from_slice(INDEX_MAGIC);
+    for (hash, offset, size) in blobs {
+        data.extend_from_slice(hash);
+        data.extend_from_slice(&offset.to_le_bytes());
+        data.extend_from_slice(&size.to_le_bytes());
+    }
+    let tmp = path.with_extension("idx.tmp");
+    fs::write(&tmp, data)?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+/// a size or number field of a tar header, octal text or GNU base-256
+fn header_number(field: &[u8]) -> Result<u64> {
+    if field[0] & 0x80 != 0 {
+        let mut value = (field[0] & 0x7f) as u64;
+        for &b in &field[1..] {
+            value = value
+                .checked_mul(256)
+                .and_then(|v| v.checked_add(b as u64))
+                .ok_or_else(|| anyhow!("tar header number too large"))?;
+        }
+        return Ok(value);
+    }
+    let text = String::from_utf8_lossy(field);
+    let text = text.trim_matches(|c: char| c == '\0' || c == ' ');
+    if text.is_empty() {
+        return Ok(0);
+    }
+    u64::from_str_radix(text, 8).map_err(|_| anyhow!("bad number `{}` in tar header", text))
+}
+
+fn header_text(field: &[u8]) -> String {
+    let end = field.iter().position(|&b| b == 0).unwrap_or(field.len());
+    String::from_utf8_lossy(&field[..end]).into_owned()
+}
+
+/// the `path` of a pax extended header
+fn pax_path(data: &[u8]) -> Option<String> {
+    let text = String::from_utf8_lossy(data);
+    text.lines().find_map(|record| {
+        let (_, kv) = record.split_once(' ')?;
+        kv.strip_prefix("path=").map(str::to_string)
+    })
+}
+
+/// Every blob in a tar, named by its SHA1 in hex (in any folder), and where
+/// its data starts. Only the headers are read
+fn scan_tar(path: &Path) -> Result<Vec<([u8; 20], u64, u64)>> {
+    let file = File::open(path).map_err(|e| anyhow!("couldn't open {}: {}", path.display(), e))?;
+    let mut reader = BufReader::new(file);
+    let mut blobs = Vec::new();
+    let mut header = [0u8; BLOCK as usize];
+    let mut pos: u64 = 0;
+    // name from a GNU long name or pax header, for the entry after it
+    let mut long_name: Option<String> = None;
+
+    loop {
+        match reader.read_exact(&mut header) {
+            Ok(()) => {}
+            Err(e) if e.kind() == ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(anyhow!("couldn't read {}: {}", path.display(), e)),
+        }
+        // two zero blocks end the archive, one is enough to stop
+        if header.iter().all(|&b| b == 0) {
+            break;
+        }
+        let size = header_number(&header[124..136])
+            .map_err(|e| anyhow!("{} at offset {}: {}", path.display(), pos, e))?;
+        let data_start = pos + BLOCK;
+        let padded = size.div_ceil(BLOCK) * BLOCK;
+
+        match header[156] {
+            b'L' | b'x' => {
+                if size > MAX_LONG_HEADER {
+                    bail!(
+                        "{} at offset {}: a long name header of {} bytes, the tar is corrupt",
+                        path.display(),
+                        pos,
+                        size
+                    );
+                }
+                let mut data = vec![0u8; size as usize];
+                reader.read_exact(&mut data)?;
+                reader.seek_relative((padded - size) as i64)?;
+                long_name = match header[156] {
+                    b'L' => Some(header_text(&data)),
+                    _ => pax_path(&data),
+                };
+                pos = data_start + padded;
+                continue;
+            }
+            b'0' | 0 => {
+                let name = long_name.take().unwrap_or_else(|| {
+                    let name = header_text(&header[..100]);
+                    match &header[257..262] == b"ustar" {
+                        true => match header_text(&header[345..500]) {
+                            prefix if prefix.is_empty() => name,
+                            prefix => format!("{}/{}", prefix, name),
+                        },
+                        false => name,
+                    }
+                });
+                let file_name = name.rsplit('/').next().unwrap_or_default();
+                if file_name.len() == 40
+                    && let Ok(hash) = hex::decode(file_name)
+                {
+                    blobs.push((hash.try_into().unwrap(), data_start, size));
+                }
+            }
+            _ => long_name = None,
+        }
+        reader.seek_relative(padded as i64)?;
+        pos = data_start + padded;
+    }
+    Ok(blobs)
+}
+
+static LAYOUTS: OnceLock<Mutex<HashMap<PathBuf, Arc<ArchiveLayout>>>> = OnceLock::new();
+
+/// The layout of the archive at `root`, detected (and indexed) on first use
+pub fn layout(root: &Path, index_dir: &Path) -> Result<Arc<ArchiveLayout>> {
+    let layouts = LAYOUTS.get_or_init(Default::default);
+    let mut layouts = layouts
+        .lock()
+        .map_err(|_| anyhow!("archive layout lock poisoned"))?;
+    if let Some(layout) = layouts.get(root) {
+        return Ok(layout.clone());
+    }
+    let layout = Arc::new(ArchiveLayout::detect(root, index_dir)?);
+    if !matches!(*layout, ArchiveLayout::Split(_)) {
+        eprintln!("▶ Archive is {}", layout.describe());
+    }
+    layouts.insert(root.to_path_buf(), layout.clone());
+    Ok(layout)
+}
@@ -0,0 +1,127 @@
+// src/archive_verify.rs
+
+use std::fs::File;
+use std::io;
+use std::path::Path;
+
+use anyhow::{Result, anyhow, bail};
+use sha1::{Digest, Sha1};
+use zip::ZipArchive;
+
+use crate::archive_layout::ArchiveLayout;
+use crate::models::ZipVerification;
+
+/// One of `count` equal slices of the 256 archive ZIPs, by hash prefix, so
+/// a verification can be split across machines
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Shard {
+    /// 1-based
+    pub index: usize,
+    pub count: usize,
+}
+
+impl Shard {
+    pub const ALL: Shard = Shard { index: 1, count: 1 };
+
+    /// Parse `<index>/<count>`, like `3/8`
+    pub fn parse(text: &str) -> Result<Self> {
+        let (index, count) = text
+            .split_once('/')
+            .ok_or_else(|| anyhow!("`{}` isn't a shard like 3/8", text))?;
+        let parse = |n: &str| {
+            n.trim()
+                .parse::<usize>()
+                .map_err(|_| anyhow!("`{}` isn't a shard like 3/8", text))
+        };
+        let shard = Shard {
+            index: parse(index)?,
+            count: parse(count)?,
+        };
+        if shard.count == 0 || shard.count > 256 {
+            bail!("the shard count must be 1 to 256, not {}", shard.count);
+        }
+        if shard.index == 0 || shard.index > shard.count {
+            bail!(
+                "shard {} doesn't exist, there are {}",
+                shard.index,
+                shard.count
+            );
+        }
+        Ok(shard)
+    }
+
+    /// The 2 hex digit ZIP prefixes of this shard, a run of consecutive ones
+    pub fn prefixes(&self) -> Vec<String> {
+        (0..256)
+            .filter(|prefix| prefix * self.count / 256 == self.index - 1)
+            .map(|prefix| format!("{:02x}", prefix))
+            .collect()
+    }
+}
+
+impl std::fmt::Display for Shard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.index, self.count)
+    }
+}
+
+/// Verification goes through the 256 `dryXX.zip` of the original dump by
+/// prefix, an archive packaged another way can't be verified like that
+pub fn check_layout(layout: &ArchiveLayout) -> Result<()> {
+    match layout {
+        ArchiveLayout::Split(_) => Ok(()),
+        other => bail!(
+            "the archive is {}, only the original dump split into dryXX.zip can be verified",
+            other.describe()
+        ),
+    }
+}
+
+fn sha1_of_name(name: &str) -> Option<[u8; 20]> {
+    let file_name = name.rsplit('/').next()?;
+    hex::decode(file_name).ok()?.try_into().ok()
+}
+
+/// Hash every SHA1-named entry of the ZIP at `path` against its name. A
+/// ZIP that can't be opened is recorded as such rather than failing
+pub fn verify_zip(path: &Path) -> ZipVerification {
+    let mut result = ZipVerification::default();
+    let archive = File::open(path)
+        .map_err(|e| anyhow!("couldn't open {}: {}", path.display(), e))
+        .and_then(|file| {
+            ZipArchive::new(file).map_err(|e| anyhow!("{} not a zip: {}", path.display(), e))
+        });
+    let mut archive = match archive {
+        Ok(archive) => archive,
+        Err(e) => {
+            result.error = Some(e.to_string());
+            return result;
+        }
+    };
+
+    for i in 0..archive.len() {
+        let mut entry = match archive.by_index(i) {
+            Ok(entry) => entry,
+            Err(e) => {
+                result.unreadable.push(format!("entry {}: {}", i, e));
+                continue;
+            }
+        };
+        let Some(expected) = sha1_of_name(entry.name()).filter(|_| entry.is_file()) else {
+            continue;
+        };
+        let name = hex::encode(expected);
+        let mut hasher = Sha1::new();
+        match io::copy(&mut entry, &mut hasher) {
+            Ok(size) => {
+                result.entries += 1;
+                result.bytes += size;
+                if hasher.finalize().as_slice() != expected {
+                    result.bad_hash.push(name);
+                }
+            }
+            Err(e) => result.unreadable.push(format!("{}: {}", name, e)),
+        }
+    }
+    result
+}
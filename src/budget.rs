@@ -0,0 +1,63 @@
+// src/budget.rs
+
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use anyhow::{Result, anyhow, bail};
+
+use crate::messages::Msg;
+
+/// exit code when the time limit stopped a run before it was done
+pub const PARTIAL_EXIT_CODE: i32 = 3;
+
+static DEADLINE: OnceLock<Instant> = OnceLock::new();
+static EXCEEDED: AtomicBool = AtomicBool::new(false);
+
+/// Stop starting new work `limit` from now, call once at startup
+pub fn set_time_limit(limit: Duration) {
+    let _ = DEADLINE.set(Instant::now() + limit);
+}
+
+/// Whether the time limit is up, new levels or resources shouldn't be started.
+/// Checking is what marks the run as partial, so only check right before
+/// starting something
+pub fn exceeded() -> bool {
+    let Some(deadline) = DEADLINE.get() else {
+        return false;
+    };
+    if Instant::now() < *deadline {
+        return false;
+    }
+    if !EXCEEDED.swap(true, Ordering::Relaxed) {
+        eprintln!("⏱️ {}", Msg::TimeLimitStopping);
+    }
+    true
+}
+
+/// Whether anything was left undone because of the time limit
+pub fn was_exceeded() -> bool {
+    EXCEEDED.load(Ordering::Relaxed)
+}
+
+/// `90`, `90s`, `30m` or `2h`
+pub fn parse_time_limit(text: &str) -> Result<Duration> {
+    let text = text.trim();
+    let (number, unit) = match text.find(|c: char| !c.is_ascii_digit()) {
+        Some(i) => text.split_at(i),
+        None => (text, "s"),
+    };
+    let number: u64 = number
+        .parse()
+        .map_err(|_| anyhow!("`{}` isn't a duration like 90s, 30m or 2h", text))?;
+    let seconds = match unit {
+        "s" => number,
+        "m" => number * 60,
+        "h" => number * 60 * 60,
+        _ => bail!("unknown unit `{}` in `{}`, use s, m or h", unit, text),
+    };
+    if seconds == 0 {
+        bail!("the time limit can't be zero");
+    }
+    Ok(Duration::from_secs(seconds))
+}
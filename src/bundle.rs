@@ -0,0 +1,97 @@
+// src/bundle.rs
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+
+use anyhow::{Result, anyhow};
+use serde_json::to_string_pretty;
+use zip::write::FileOptions;
+use zip::{CompressionMethod, DateTime, ZipWriter};
+
+use crate::models::BundleMeta;
+
+pub const BUNDLE_FORMAT_VERSION: u32 = 1;
+pub const BUNDLE_META_FILE: &str = "level.json";
+pub const BUNDLE_ICON_FILE: &str = "icon.png";
+pub const BUNDLE_README_FILE: &str = "README.txt";
+/// folder of the resource blobs, each named by its SHA1
+pub const BUNDLE_RESOURCE_DIR: &str = "resources";
+
+fn readme(meta: &BundleMeta, has_icon: bool) -> String {
+    let mut out = String::new();
+    writeln!(out, "{} by {} ({})", meta.name, meta.creator, meta.game).unwrap();
+    writeln!(out, "slot {}, rootLevel {}", meta.slot_id, meta.root_level).unwrap();
+    writeln!(out).unwrap();
+    writeln!(out, "Bundle format version {}", meta.format_version).unwrap();
+    writeln!(out).unwrap();
+    writeln!(
+        out,
+        "{}  level metadata, the rootLevel and icon are SHA1s of files in {}/, GUIDs are g<guid>",
+        BUNDLE_META_FILE, BUNDLE_RESOURCE_DIR
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "{}/  {} resources, each named by its SHA1",
+        BUNDLE_RESOURCE_DIR,
+        meta.resources.len()
+    )
+    .unwrap();
+    if has_icon {
+        writeln!(out, "{}  the level icon as PNG", BUNDLE_ICON_FILE).unwrap();
+    }
+    if !meta.missing_resources.is_empty() {
+        writeln!(out).unwrap();
+        writeln!(
+            out,
+            "{} resources the level needs aren't included, they're listed in {}",
+            meta.missing_resources.len(),
+            BUNDLE_META_FILE
+        )
+        .unwrap();
+    }
+    out
+}
+
+/// Write a level's resources, metadata, icon and a readme into one zip at
+/// `path`, entries in a fixed order so the same level gives the same layout
+pub fn write_bundle(
+    path: &Path,
+    meta: &BundleMeta,
+    resources: &BTreeMap<[u8; 20], Vec<u8>>,
+    icon_png: Option<&[u8]>,
+) -> Result<()> {
+    let dir = path
+        .parent()
+        .ok_or_else(|| anyhow!("{} has no parent directory", path.display()))?;
+    fs::create_dir_all(dir)?;
+    let tmp_path = path.with_extension(format!("zip.{}.tmp", std::process::id()));
+
+    let options = FileOptions::default()
+        .compression_method(CompressionMethod::Deflated)
+        .last_modified_time(DateTime::default())
+        .unix_permissions(0o644);
+    let mut writer = ZipWriter::new(File::create(&tmp_path)?);
+    writer.start_file(BUNDLE_README_FILE, options)?;
+    writer.write_all(readme(meta, icon_png.is_some()).as_bytes())?;
+    writer.start_file(BUNDLE_META_FILE, options)?;
+    writer.write_all(to_string_pretty(meta)?.as_bytes())?;
+    if let Some(png) = icon_png {
+        writer.start_file(BUNDLE_ICON_FILE, options)?;
+        writer.write_all(png)?;
+    }
+    for (hash, data) in resources {
+        writer.start_file(
+            format!("{}/{}", BUNDLE_RESOURCE_DIR, hex::encode(hash)),
+            options,
+        )?;
+        writer.write_all(data)?;
+    }
+    writer.finish()?;
+
+    fs::rename(&tmp_path, path)?;
+    Ok(())
+}
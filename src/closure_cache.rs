@@ -0,0 +1,126 @@
+// src/closure_cache.rs
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Mutex;
+
+use anyhow::{Result, anyhow};
+use rusqlite::{Connection, OptionalExtension, params};
+
+/// Kept in the resource cache folder, next to the blobs it lists
+pub const CLOSURE_CACHE_FILE: &str = "closures.db";
+
+/// Every resource a level needs and its size
+pub type Closure = Vec<([u8; 20], u64)>;
+
+/// bytes per member in the `members` column, SHA1 then size
+const MEMBER_LEN: usize = 28;
+
+/// The dependency closures of levels fetched before, keyed by their
+/// rootLevel and slot icon, so they don't need walking again
+pub struct ClosureCache {
+    conn: Mutex<Connection>,
+}
+
+impl ClosureCache {
+    pub fn open(cache_dir: &Path) -> Result<Self> {
+        // the first fetch into a new cache dir gets here before the blobs do
+        std::fs::create_dir_all(cache_dir)?;
+        let path = cache_dir.join(CLOSURE_CACHE_FILE);
+        let conn = Connection::open(&path)
+            .map_err(|e| anyhow!("couldn't open {}: {}", path.display(), e))?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS closure (
+                root BLOB NOT NULL,
+                icon BLOB NOT NULL,
+                members BLOB NOT NULL,
+                PRIMARY KEY (root, icon)
+            )",
+        )?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    fn conn(&self) -> Result<std::sync::MutexGuard<'_, Connection>> {
+        self.conn
+            .lock()
+            .map_err(|_| anyhow!("closure cache lock poisoned"))
+    }
+
+    pub fn get(&self, root: [u8; 20], icon: Option<[u8; 20]>) -> Result<Option<Closure>> {
+        let members: Option<Vec<u8>> = self
+            .conn()?
+            .query_row(
+                "SELECT members FROM closure WHERE root = ?1 AND icon = ?2",
+                params![&root[..], icon_key(&icon)],
+                |r| r.get(0),
+            )
+            .optional()?;
+        Ok(members.and_then(|members| decode_members(&members)))
+    }
+
+    /// Every recorded closure by its rootLevel, a root fetched with
+    /// different icons has the closure of one of them
+    pub fn by_root(&self) -> Result<BTreeMap<[u8; 20], Closure>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare("SELECT root, members FROM closure")?;
+        let mut rows = stmt.query([])?;
+        let mut closures = BTreeMap::new();
+        while let Some(row) = rows.next()? {
+            let root: Vec<u8> = row.get(0)?;
+            let members: Vec<u8> = row.get(1)?;
+            if let (Ok(root), Some(closure)) = (root.try_into(), decode_members(&members)) {
+                closures.insert(root, closure);
+            }
+        }
+        Ok(closures)
+    }
+
+    pub fn put(&self, root: [u8; 20], icon: Option<[u8; 20]>, closure: &Closure) -> Result<()> {
+        let mut members = Vec::with_capacity(closure.len() * MEMBER_LEN);
+        for (hash, size) in closure {
+            members.extend_from_slice(hash);
+            members.extend_from_slice(&size.to_le_bytes());
+        }
+        self.conn()?.execute(
+            "INSERT OR REPLACE INTO closure (root, icon, members) VALUES (?1, ?2, ?3)",
+            params![&root[..], icon_key(&icon), members],
+        )?;
+        Ok(())
+    }
+
+    /// drop a closure whose members aren't all available anymore
+    pub fn forget(&self, root: [u8; 20], icon: Option<[u8; 20]>) -> Result<()> {
+        self.conn()?.execute(
+            "DELETE FROM closure WHERE root = ?1 AND icon = ?2",
+            params![&root[..], icon_key(&icon)],
+        )?;
+        Ok(())
+    }
+}
+
+fn decode_members(members: &[u8]) -> Option<Closure> {
+    if !members.len().is_multiple_of(MEMBER_LEN) {
+        return None;
+    }
+    Some(
+        members
+            .chunks_exact(MEMBER_LEN)
+            .map(|m| {
+                let hash = m[..20].try_into().unwrap();
+                let size = u64::from_le_bytes(m[20..].try_into().unwrap());
+                (hash, size)
+            })
+            .collect(),
+    )
+}
+
+/// levels without a slot icon are stored with an empty one, NULLs never
+/// compare equal in a key
+fn icon_key(icon: &Option<[u8; 20]>) -> &[u8] {
+    match icon {
+        Some(icon) => icon,
+        None => &[],
+    }
+}
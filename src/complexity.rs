@@ -0,0 +1,142 @@
+// src/complexity.rs
+
+use std::collections::BTreeMap;
+
+use anyhow::{Result, anyhow};
+
+use crate::resource_parse::{
+    ResrcData, ResrcDescriptor, ResrcMethod, binary_payload, resrc_type_name,
+};
+use crate::scan::{Tally, classify};
+
+/// Where each estimate ends for the rootLevel's inflated data, which grows
+/// with the things placed in the level. Rough, taken from how big levels of
+/// each kind tend to be rather than from the game's thermometer
+const LEVEL_DATA_TIERS: [u64; 3] = [512 << 10, 2 << 20, 6 << 20];
+
+/// Where each estimate ends for the meshes and textures the level loads
+const ASSET_TIERS: [u64; 3] = [8 << 20, 32 << 20, 96 << 20];
+
+/// Where each estimate ends for the distinct meshes and materials the level
+/// uses, each is loaded once however often it's placed
+const UNIQUE_ASSET_TIERS: [u64; 3] = [64, 256, 768];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Estimate {
+    Light,
+    Moderate,
+    Heavy,
+    Extreme,
+}
+
+impl Estimate {
+    fn from_tiers(value: u64, tiers: &[u64; 3]) -> Self {
+        match tiers.iter().position(|end| value < *end) {
+            Some(0) => Estimate::Light,
+            Some(1) => Estimate::Moderate,
+            Some(_) => Estimate::Heavy,
+            None => Estimate::Extreme,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Estimate::Light => "light",
+            Estimate::Moderate => "moderate",
+            Estimate::Heavy => "heavy",
+            Estimate::Extreme => "extreme",
+        }
+    }
+
+    pub fn hint(&self) -> &'static str {
+        match self {
+            Estimate::Light => "well under a full thermometer",
+            Estimate::Moderate => "an ordinary level",
+            Estimate::Heavy => "close to a full thermometer, may load slowly",
+            Estimate::Extreme => "likely a full thermometer, try it on the target hardware first",
+        }
+    }
+}
+
+/// References of one resource type in the rootLevel's dependency table
+#[derive(Debug, Default, Clone, Copy)]
+pub struct References {
+    /// to resources the level brings along
+    pub sha1: usize,
+    /// to the games' own assets
+    pub guid: usize,
+}
+
+/// What a level is made of, as far as can be told without reading the
+/// things in the rootLevel one by one, which would need the whole level
+/// format of every revision
+#[derive(Debug)]
+pub struct Complexity {
+    /// bytes of the rootLevel's serialized data once inflated
+    pub level_data: u64,
+    pub meshes: Tally,
+    /// texture resources and the images they're made from
+    pub textures: Tally,
+    /// the level's own gfx materials, the ones of the games aren't counted
+    pub materials: Tally,
+    pub plans: Tally,
+    /// everything the level needs, the rootLevel included
+    pub resources: Tally,
+    /// the rootLevel's references by resource type name, this is where
+    /// materials show up
+    pub references: BTreeMap<&'static str, References>,
+    pub estimate: Estimate,
+}
+
+/// Estimate how demanding the level with rootLevel `root` is from its
+/// downloaded resources
+pub fn estimate(root: [u8; 20], resources: &BTreeMap<[u8; 20], Vec<u8>>) -> Result<Complexity> {
+    let level = resources
+        .get(&root)
+        .ok_or_else(|| anyhow!("the rootLevel {} wasn't downloaded", hex::encode(root)))?;
+    let level_data = match binary_payload(level)? {
+        Some((data, _)) => data.len() as u64,
+        None => level.len() as u64,
+    };
+
+    let mut references: BTreeMap<&'static str, References> = BTreeMap::new();
+    if let ResrcMethod::Binary { dependencies, .. } = ResrcData::new(level, false)?.method {
+        for dependency in dependencies {
+            let name = resrc_type_name(dependency.resrc_type).unwrap_or("unknown");
+            let references = references.entry(name).or_default();
+            match dependency.desc {
+                ResrcDescriptor::Sha1(_) => references.sha1 += 1,
+                ResrcDescriptor::Guid(_) => references.guid += 1,
+            }
+        }
+    }
+
+    let mut complexity = Complexity {
+        level_data,
+        meshes: Tally::default(),
+        textures: Tally::default(),
+        materials: Tally::default(),
+        plans: Tally::default(),
+        resources: Tally::default(),
+        references,
+        estimate: Estimate::Light,
+    };
+    for blob in resources.values() {
+        let bytes = blob.len() as u64;
+        complexity.resources.add(bytes);
+        let (key, _) = classify(&blob[..blob.len().min(16)]);
+        match key.get(..3).unwrap_or(&key) {
+            "MSH" => complexity.meshes.add(bytes),
+            "TEX" | "GTF" | "PNG" | "JPE" => complexity.textures.add(bytes),
+            "GMT" => complexity.materials.add(bytes),
+            "PLN" => complexity.plans.add(bytes),
+            _ => {}
+        }
+    }
+    let assets = complexity.meshes.bytes + complexity.textures.bytes;
+    let unique_assets = complexity.meshes.count + complexity.materials.count;
+    complexity.estimate = Estimate::from_tiers(level_data, &LEVEL_DATA_TIERS)
+        .max(Estimate::from_tiers(assets, &ASSET_TIERS))
+        .max(Estimate::from_tiers(unique_assets, &UNIQUE_ASSET_TIERS));
+    Ok(complexity)
+}
@@ -1,7 +1,25 @@
-use std::{fs::File, io::Write, path::{Path, PathBuf}};
-use anyhow::{Context, Result};
+use std::{collections::HashMap, fs::File, io::Write, path::{Path, PathBuf}};
+use anyhow::{anyhow, bail, Context, Result};
 use serde::Deserialize;
 
+use crate::contents::parse_size;
+use crate::db::RelationFilter;
+use crate::extract_pool;
+use crate::language::Language;
+use crate::messages::{self, Msg};
+use crate::models::LEVEL_QUEUE_FILE;
+use crate::resource_dl::DownloadOrder;
+use crate::serializers::lbp::MAX_ARCHIVE_SIZE;
+use crate::serializers::ps3::SfoFields;
+use crate::serializers::text::TextLimits;
+
+/// read from the folder the tool is run in
+pub const CONFIG_FILE: &str = "config.yml";
+
+/// what a missing config.yml is created with, and what `init` fills in
+pub const DEFAULT_CONFIG: &str = include_str!("assets/default_config.yml");
+
+#[allow(dead_code)]
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum DownloadServer {
@@ -20,28 +38,329 @@ impl DownloadServer {
     // }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct Config {
     pub database_path: PathBuf,
+    /// open the database as immutable, for read-only mounts of a WAL database.
+    /// Only safe while nothing is writing to it
+    #[serde(default)]
+    pub database_immutable: bool,
     pub backup_directory: PathBuf,
     // pub download_server: DownloadServer,
     pub archive_path: PathBuf,
     pub max_parallel_downloads: usize,
     pub fix_backup_version: bool,
     pub force_lbp3_backups: bool,
+    /// where downloaded resources are cached, defaults to `resource_cache` next to the exe
+    #[serde(default)]
+    pub cache_directory: Option<PathBuf>,
+    /// don't keep fetched resources in the cache directory, `--no-cache` sets it too
+    #[serde(default)]
+    pub disable_cache: bool,
+    /// in what order a level's resources are fetched
+    #[serde(default)]
+    pub download_order: DownloadOrder,
+    /// password of encrypted archive ZIPs
+    #[serde(default)]
+    pub archive_password: Option<String>,
+    /// where `ingest` stores recovered blobs, in the archive's `ab/cd/<sha1>`
+    /// layout, defaults to `ingested` next to the exe
+    #[serde(default)]
+    pub ingest_directory: Option<PathBuf>,
+    /// threads reading from the archive ZIPs, defaults to a few for the disk's sake
+    #[serde(default)]
+    pub extraction_threads: Option<usize>,
+    /// threads inflating, hashing and parsing resources, defaults to the CPU count
+    #[serde(default)]
+    pub threads: Option<usize>,
+    /// also export GUID dependencies (as `g<guid>`) in the Refresh relations
+    #[serde(default)]
+    pub export_guid_dependencies: bool,
+    /// also export typed `DependencyEdges` (declared resource type, GUID flag,
+    /// what the blob really is) next to the relations in import.json
+    #[serde(default)]
+    pub export_dependency_types: bool,
+    /// relations left out of the Refresh export to keep it small
+    #[serde(default)]
+    pub relation_filter: RelationFilter,
+    /// largest save archive a backup may have, like `1GiB`. Bigger levels
+    /// fail before anything is written, instead of giving a save the PS3
+    /// can't import. FAR4's own 4 GiB limit always applies
+    #[serde(default)]
+    max_backup_size: Option<String>,
+    /// PARAM.SFO templates and parental level for the backups
+    #[serde(default)]
+    pub sfo: SfoFields,
+    /// longest level name and description in the backups
+    #[serde(default)]
+    pub text_limits: TextLimits,
+    /// language of the messages, `--ui-language` overrides it
+    #[serde(default)]
+    pub ui_language: Option<Language>,
+    /// named overrides selected with `--profile`
+    #[serde(default)]
+    profiles: HashMap<String, ConfigProfile>,
+}
+
+/// Any subset of the config, applied on top of the top-level settings.
+/// Unknown keys are refused, a misspelled or missing setting would do nothing
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ConfigProfile {
+    database_path: Option<PathBuf>,
+    database_immutable: Option<bool>,
+    backup_directory: Option<PathBuf>,
+    archive_path: Option<PathBuf>,
+    max_parallel_downloads: Option<usize>,
+    fix_backup_version: Option<bool>,
+    force_lbp3_backups: Option<bool>,
+    cache_directory: Option<PathBuf>,
+    disable_cache: Option<bool>,
+    download_order: Option<DownloadOrder>,
+    archive_password: Option<String>,
+    ingest_directory: Option<PathBuf>,
+    extraction_threads: Option<usize>,
+    threads: Option<usize>,
+    export_guid_dependencies: Option<bool>,
+    export_dependency_types: Option<bool>,
+    relation_filter: Option<RelationFilter>,
+    max_backup_size: Option<String>,
+    sfo: Option<SfoFields>,
+    text_limits: Option<TextLimits>,
+    ui_language: Option<Language>,
 }
 
 impl Config {
-    pub fn read() -> Result<Self> {
-        let config_path = Path::new("config.yml");
+    /// Read config.yml, with the settings of `profile` applied if given
+    pub fn read(profile: Option<&str>) -> Result<Self> {
+        let config_path = Path::new(CONFIG_FILE);
         if !config_path.exists() {
-            println!("config.yml is missing, writing default config");
+            println!("{}", Msg::ConfigMissing);
             let mut new_file = File::create(config_path)?;
-            new_file.write_all(include_bytes!("assets/default_config.yml"))?;
+            new_file.write_all(DEFAULT_CONFIG.as_bytes())?;
         }
 
         let file = File::open(config_path).context("Couldn't open config file")?;
-        let config: Self = serde_yaml::from_reader(file).context("Couldn't parse config")?;
+        let mut config: Self = serde_yaml::from_reader(file).context("Couldn't parse config")?;
+        if let Some(name) = profile {
+            config.apply_profile(name)?;
+        }
+        // so the warnings below are in it too, `--ui-language` was set before and wins
+        if let Some(language) = config.ui_language {
+            messages::set_ui_language(language);
+        }
+        // relative paths are relative to config.yml, not to wherever the tool is run from
+        let config_path = std::path::absolute(config_path).context("Couldn't find config file")?;
+        let base = config_path.parent().ok_or_else(|| anyhow!("config file has no parent directory"))?;
+        config.resolve_paths(base)?;
+        config.validate()?;
         Ok(config)
     }
+
+    /// Parse and check a config.yml before it's written, relative paths are
+    /// taken from the folder the tool is run in as they will be once it is
+    pub fn check(text: &str) -> Result<()> {
+        let mut config: Self = serde_yaml::from_str(text).context("Couldn't parse config")?;
+        config.resolve_paths(&std::path::absolute(".").context("Couldn't find the current folder")?)?;
+        config.validate()
+    }
+
+    fn resolve_paths(&mut self, base: &Path) -> Result<()> {
+        self.database_path = resolve_path(base, &self.database_path)?;
+        self.backup_directory = resolve_path(base, &self.backup_directory)?;
+        self.archive_path = resolve_path(base, &self.archive_path)?;
+        for dir in [&mut self.cache_directory, &mut self.ingest_directory].into_iter().flatten() {
+            *dir = resolve_path(base, dir)?;
+        }
+        Ok(())
+    }
+
+    /// Check the settings up front, so a bad value fails before anything is
+    /// fetched. Folders the tool writes to are created when they're needed,
+    /// the files and folders it reads only warn if they're missing since not
+    /// every command reads them
+    fn validate(&self) -> Result<()> {
+        if self.max_parallel_downloads == 0 {
+            bail!("max_parallel_downloads cannot be zero");
+        }
+        self.extraction_threads()?;
+        self.cpu_threads()?;
+        self.max_backup_size()?;
+        self.text_limits.validate()?;
+        self.relation_filter.validate()?;
+
+        if self.database_path.is_dir() {
+            bail!("database_path `{}` is a folder, not a database file", self.database_path.display());
+        }
+        for (key, dir) in [
+            ("backup_directory", Some(&self.backup_directory)),
+            ("cache_directory", self.cache_directory.as_ref()),
+            ("ingest_directory", self.ingest_directory.as_ref()),
+        ] {
+            if let Some(dir) = dir && dir.exists() && !dir.is_dir() {
+                bail!("{} `{}` is a file, not a folder", key, dir.display());
+            }
+        }
+        for (key, path) in [("database_path", &self.database_path), ("archive_path", &self.archive_path)] {
+            if !path.exists() {
+                eprintln!("{}", Msg::Warning(&Msg::ConfigPathMissing { key, path }));
+            }
+        }
+        Ok(())
+    }
+
+    /// The settings in use after profiles, path resolution and defaults, one
+    /// `key: value` per line in a fixed order so two runs can be diffed
+    pub fn effective(&self) -> Result<String> {
+        let or_none = |v: Option<String>| v.unwrap_or_else(|| "none".to_string());
+        let lines = [
+            ("database_path", self.database_path.display().to_string()),
+            ("database_immutable", self.database_immutable.to_string()),
+            ("backup_directory", self.backup_directory.display().to_string()),
+            ("archive_path", self.archive_path.display().to_string()),
+            ("max_parallel_downloads", self.max_parallel_downloads.to_string()),
+            ("fix_backup_version", self.fix_backup_version.to_string()),
+            ("force_lbp3_backups", self.force_lbp3_backups.to_string()),
+            ("cache_directory", self.cache_dir()?.display().to_string()),
+            ("disable_cache", self.disable_cache.to_string()),
+            ("download_order", self.download_order.get_name().to_string()),
+            // it could be pasted somewhere, so only whether there's one
+            ("archive_password", self.archive_password.as_ref().map_or("none", |_| "set").to_string()),
+            ("ingest_directory", self.ingest_dir()?.display().to_string()),
+            ("extraction_threads", self.extraction_threads()?.to_string()),
+            ("threads", self.cpu_threads()?.to_string()),
+            ("export_guid_dependencies", self.export_guid_dependencies.to_string()),
+            ("export_dependency_types", self.export_dependency_types.to_string()),
+            ("relation_filter.skip_types", match self.relation_filter.skip_types.is_empty() {
+                true => "none".to_string(),
+                false => self.relation_filter.skip_types.join(", "),
+            }),
+            ("relation_filter.collapse_textures", self.relation_filter.collapse_textures.to_string()),
+            ("max_backup_size", or_none(self.max_backup_size()?.map(|size| size.to_string()))),
+            ("sfo.sub_title", or_none(self.sfo.sub_title.clone())),
+            ("sfo.detail", or_none(self.sfo.detail.clone())),
+            ("sfo.parental_level", or_none(self.sfo.parental_level.map(|l| l.to_string()))),
+            ("sfo.target_console", or_none(self.sfo.target_console.map(|t| t.get_name().to_string()))),
+            ("sfo.copy_protected", (self.sfo.attribute() != 0).to_string()),
+            ("sfo.account_id", self.sfo.account_binding().unwrap_or("not set").to_string()),
+            ("text_limits.name_max", or_none(self.text_limits.name_max.map(|n| n.to_string()))),
+            ("text_limits.description_max", or_none(self.text_limits.description_max.map(|n| n.to_string()))),
+            ("ui_language", format!("{:?}", self.ui_language.unwrap_or_default()).to_lowercase()),
+        ];
+        Ok(lines.iter().map(|(key, value)| format!("{}: {}\n", key, value)).collect())
+    }
+
+    fn apply_profile(&mut self, name: &str) -> Result<()> {
+        let Some(profile) = self.profiles.remove(name) else {
+            let mut known: Vec<&str> = self.profiles.keys().map(String::as_str).collect();
+            known.sort();
+            bail!("No profile `{}` in config.yml (known profiles: {})", name, known.join(", "));
+        };
+
+        if let Some(v) = profile.database_path { self.database_path = v }
+        if let Some(v) = profile.database_immutable { self.database_immutable = v }
+        if let Some(v) = profile.backup_directory { self.backup_directory = v }
+        if let Some(v) = profile.archive_path { self.archive_path = v }
+        if let Some(v) = profile.max_parallel_downloads { self.max_parallel_downloads = v }
+        if let Some(v) = profile.fix_backup_version { self.fix_backup_version = v }
+        if let Some(v) = profile.force_lbp3_backups { self.force_lbp3_backups = v }
+        if let Some(v) = profile.disable_cache { self.disable_cache = v }
+        if let Some(v) = profile.download_order { self.download_order = v }
+        if let Some(v) = profile.archive_password { self.archive_password = Some(v) }
+        if let Some(v) = profile.extraction_threads { self.extraction_threads = Some(v) }
+        if let Some(v) = profile.threads { self.threads = Some(v) }
+        if let Some(v) = profile.export_guid_dependencies { self.export_guid_dependencies = v }
+        if let Some(v) = profile.export_dependency_types { self.export_dependency_types = v }
+        if let Some(v) = profile.relation_filter { self.relation_filter = v }
+        if let Some(v) = profile.max_backup_size { self.max_backup_size = Some(v) }
+        if let Some(v) = profile.sfo { self.sfo = v }
+        if let Some(v) = profile.text_limits { self.text_limits = v }
+        if let Some(v) = profile.ui_language { self.ui_language = Some(v) }
+        // ingested blobs are content addressed, so profiles share them unless told otherwise
+        if let Some(v) = profile.ingest_directory { self.ingest_directory = Some(v) }
+
+        // profiles get their own cache unless they say otherwise, so an
+        // LBP1-only mirror doesn't fill up the cache of the full dump
+        self.cache_directory = match profile.cache_directory {
+            Some(dir) => Some(dir),
+            None => Some(default_cache_dir()?.join(name)),
+        };
+        Ok(())
+    }
+
+    pub fn cache_dir(&self) -> Result<PathBuf> {
+        match &self.cache_directory {
+            Some(dir) => Ok(dir.clone()),
+            None => default_cache_dir(),
+        }
+    }
+
+    pub fn extraction_threads(&self) -> Result<usize> {
+        match self.extraction_threads {
+            Some(0) => bail!("extraction_threads cannot be zero"),
+            Some(n) => Ok(n),
+            None => Ok(extract_pool::default_threads()),
+        }
+    }
+
+    pub fn cpu_threads(&self) -> Result<usize> {
+        match self.threads {
+            Some(0) => bail!("threads cannot be zero"),
+            Some(n) => Ok(n),
+            None => Ok(extract_pool::default_cpu_threads()),
+        }
+    }
+
+    /// `max_backup_size` in bytes, capped at what FAR4 can address
+    pub fn max_backup_size(&self) -> Result<Option<u64>> {
+        let Some(text) = &self.max_backup_size else { return Ok(None) };
+        let size = parse_size(text).map_err(|e| anyhow!("max_backup_size: {}", e))?;
+        if size == 0 {
+            bail!("max_backup_size cannot be zero");
+        }
+        Ok(Some(size.min(MAX_ARCHIVE_SIZE)))
+    }
+
+    pub fn ingest_dir(&self) -> Result<PathBuf> {
+        match &self.ingest_directory {
+            Some(dir) => Ok(dir.clone()),
+            None => Ok(exe_dir()?.join("ingested")),
+        }
+    }
+
+    /// where `queue` keeps its levels, next to the exe
+    pub fn queue_path(&self) -> Result<PathBuf> {
+        Ok(exe_dir()?.join(LEVEL_QUEUE_FILE))
+    }
+}
+
+/// `path` with a leading `~` expanded to the home folder and made absolute
+/// against `base` if it's relative
+fn resolve_path(base: &Path, path: &Path) -> Result<PathBuf> {
+    let path = match path.strip_prefix("~") {
+        Ok(rest) => {
+            let home = std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE"))
+                .ok_or_else(|| anyhow!("can't expand `~` in `{}`, no home folder is set", path.display()))?;
+            PathBuf::from(home).join(rest)
+        }
+        Err(_) => path.to_path_buf(),
+    };
+    Ok(match path.is_absolute() {
+        true => path,
+        false => base.join(path),
+    })
+}
+
+fn exe_dir() -> Result<PathBuf> {
+    let exe_path = std::env::current_exe()
+        .map_err(|e| anyhow!("couldn't find exe path: {}", e))?;
+    let exe_dir = exe_path
+        .parent()
+        .ok_or_else(|| anyhow!("exe has no parent directory"))?;
+    Ok(exe_dir.to_path_buf())
+}
+
+/// cache next to exe
+fn default_cache_dir() -> Result<PathBuf> {
+    Ok(exe_dir()?.join("resource_cache"))
 }
\ No newline at end of file
@@ -0,0 +1,193 @@
+// src/contents.rs
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write as _;
+
+use anyhow::{Result, anyhow, bail};
+use chrono::{DateTime, Utc};
+
+use crate::resource_parse::{ResrcData, ResrcDescriptor, ResrcMethod, resrc_type_name};
+use crate::scan::{Tally, classify, resource_name};
+
+/// Written into every `bkp` backup folder, next to backup.meta.json
+pub const CONTENTS_FILE: &str = "contents.txt";
+
+/// What goes into a backup's contents.txt
+pub struct BackupContents<'a> {
+    pub name: &'a str,
+    pub creator: &'a str,
+    pub slot_id: i64,
+    /// short title of the game the backup is written for
+    pub game: &'a str,
+    pub written_at: DateTime<Utc>,
+    pub resources: &'a BTreeMap<[u8; 20], Vec<u8>>,
+    /// resources left out with `--partial-ok` and why
+    pub missing: &'a [([u8; 20], String)],
+    /// where ICON0.PNG came from, see `make_icon`
+    pub icon_source: &'a str,
+}
+
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    match unit {
+        0 => format!("{} B", bytes),
+        _ => format!("{:.1} {}", size, UNITS[unit]),
+    }
+}
+
+/// Parse a size like `512MiB`, `1.5GiB` or a bare number of bytes
+pub fn parse_size(text: &str) -> Result<u64> {
+    let text = text.trim();
+    let split = text
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+    let number: f64 = number
+        .parse()
+        .map_err(|_| anyhow!("`{}` isn't a size like 512MiB or 2GiB", text))?;
+    let scale: u64 = match unit.trim().to_ascii_lowercase().as_str() {
+        "" | "b" => 1,
+        "kib" => 1 << 10,
+        "mib" => 1 << 20,
+        "gib" => 1 << 30,
+        _ => bail!(
+            "unknown unit `{}` in `{}`, use B, KiB, MiB or GiB",
+            unit.trim(),
+            text
+        ),
+    };
+    Ok((number * scale as f64) as u64)
+}
+
+/// GUIDs of game and DLC content the resources refer to, by resource type
+pub fn guid_requirements(resources: &BTreeMap<[u8; 20], Vec<u8>>) -> BTreeMap<u32, BTreeSet<u32>> {
+    let mut guids: BTreeMap<u32, BTreeSet<u32>> = BTreeMap::new();
+    for blob in resources.values() {
+        let Ok(ResrcData {
+            method: ResrcMethod::Binary { dependencies, .. },
+            ..
+        }) = ResrcData::new(blob, false)
+        else {
+            continue;
+        };
+        for dep in dependencies {
+            // GUID 0 is an empty reference, not content
+            if let ResrcDescriptor::Guid(guid) = dep.desc
+                && guid != 0
+            {
+                guids.entry(dep.resrc_type).or_default().insert(guid);
+            }
+        }
+    }
+    guids
+}
+
+/// A plain text summary of what a backup holds, for players to read before
+/// installing it
+pub fn render(contents: &BackupContents) -> String {
+    let mut out = String::new();
+    writeln!(out, "{} by {}", contents.name, contents.creator).unwrap();
+    writeln!(
+        out,
+        "{} backup of slot {}, written {}",
+        contents.game,
+        contents.slot_id,
+        contents.written_at.format("%Y-%m-%d %H:%M UTC")
+    )
+    .unwrap();
+
+    let mut types: BTreeMap<String, Tally> = BTreeMap::new();
+    let mut total = Tally::default();
+    for data in contents.resources.values() {
+        let (magic, _) = classify(&data[..data.len().min(16)]);
+        let name = resource_name(magic.as_bytes()).map_or(magic, str::to_string);
+        types.entry(name).or_default().add(data.len() as u64);
+        total.add(data.len() as u64);
+    }
+    let mut types: Vec<_> = types.into_iter().collect();
+    types.sort_by_key(|(_, t)| std::cmp::Reverse(t.bytes));
+    writeln!(out).unwrap();
+    writeln!(
+        out,
+        "Resources: {}, {}",
+        total.count,
+        format_size(total.bytes)
+    )
+    .unwrap();
+    for (name, tally) in types {
+        writeln!(
+            out,
+            "  {:<26} {:>8} {:>12}",
+            name,
+            tally.count,
+            format_size(tally.bytes)
+        )
+        .unwrap();
+    }
+
+    writeln!(out).unwrap();
+    match contents.missing.len() {
+        0 => writeln!(
+            out,
+            "Missing: nothing, every resource the level uses is included"
+        )
+        .unwrap(),
+        n => {
+            writeln!(
+                out,
+                "Missing: {} resources couldn't be fetched and were left out, some textures or models may not show up",
+                n
+            )
+            .unwrap();
+            for (hash, error) in contents.missing {
+                writeln!(out, "  {}  {}", hex::encode(hash), error).unwrap();
+            }
+        }
+    }
+    match contents.icon_source {
+        "slot icon" => {}
+        "icon file" => writeln!(
+            out,
+            "Substituted: ICON0.PNG is the image given with `--icon`"
+        )
+        .unwrap(),
+        "placeholder" => writeln!(
+            out,
+            "Substituted: the level has no usable icon, ICON0.PNG is a placeholder"
+        )
+        .unwrap(),
+        source => writeln!(
+            out,
+            "Substituted: the slot icon isn't available, ICON0.PNG is the {}",
+            source
+        )
+        .unwrap(),
+    }
+
+    let guids = guid_requirements(contents.resources);
+    writeln!(out).unwrap();
+    match guids.values().map(BTreeSet::len).sum::<usize>() {
+        0 => writeln!(out, "Game content: the level doesn't use any by GUID").unwrap(),
+        n => {
+            writeln!(
+                out,
+                "Game content: the level uses {} GUIDs from the game or DLC, anything from a pack you don't own won't load",
+                n
+            )
+            .unwrap();
+            for (resrc_type, guids) in guids {
+                let name = resrc_type_name(resrc_type)
+                    .map_or(format!("type {}", resrc_type), str::to_string);
+                let list: Vec<String> = guids.iter().map(|g| format!("g{}", g)).collect();
+                writeln!(out, "  {:<26} {}", name, list.join(" ")).unwrap();
+            }
+        }
+    }
+    out
+}
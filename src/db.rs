@@ -1,22 +1,34 @@
 // src/db.rs
 
-use std::collections::BTreeMap;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fmt;
 use std::path::Path;
+use std::str::FromStr;
+use std::sync::OnceLock;
+use std::thread;
 
 use anyhow::{Result, anyhow};
 use bitvec::{order::Lsb0, view::BitView};
-use rusqlite::{Connection, params};
+use clap::ValueEnum;
+use rusqlite::{Connection, ErrorCode, OpenFlags, OptionalExtension, params};
+use sha1::{Digest, Sha1};
+use tokio::sync::{mpsc, oneshot};
 
+use crate::messages::Msg;
+use crate::resource_parse::Branch;
 use crate::resource_parse::ResrcData;
 use crate::resource_parse::ResrcMethod;
+use crate::resource_parse::{resrc_type_id, resrc_type_magics, resrc_type_name};
+use crate::scan::classify;
 use crate::{ResrcDescriptor, labels::LABEL_LAMS_KEY_IDS, resource_parse::ResrcRevision};
 
-use crate::models::{AssetDependencyRelation, GameAsset, GameLevel, GameUser};
+use crate::language::Language;
+use crate::models::{AssetDependencyRelation, DependencyEdge, GameAsset, GameLevel, GameUser};
 use bson::oid::ObjectId;
 use chrono::{DateTime, TimeZone, Utc};
+use serde::Deserialize;
 
-#[derive(Debug, PartialEq, Clone, Copy)]
+#[derive(Debug, PartialEq, Clone, Copy, ValueEnum)]
 pub enum GameVersion {
     Lbp1,
     Lbp2,
@@ -45,13 +57,53 @@ impl GameVersion {
             Self::Lbp3 => "BCES01663",
         }
     }
+    /// Title IDs of the disc and PSN releases of the game, `get_titleid` first
+    pub fn get_known_titleids(&self) -> &'static [&'static str] {
+        match self {
+            Self::Lbp1 => &[
+                "BCES00141",
+                "BCUS98148",
+                "BCJS30018",
+                "BCAS20058",
+                "NPEA00241",
+                "NPUA80472",
+            ],
+            Self::Lbp2 => &[
+                "BCES00850",
+                "BCUS98245",
+                "BCJS70009",
+                "BCAS20113",
+                "NPEA00324",
+                "NPUA80662",
+            ],
+            Self::Lbp3 => &[
+                "BCES01663",
+                "BCUS98362",
+                "BCJS30095",
+                "BCAS20322",
+                "NPEA00437",
+                "NPUA81116",
+            ],
+        }
+    }
+    /// The game as stored in `slot.game`
+    pub fn from_db(game: i64) -> Option<Self> {
+        match game {
+            0 => Some(Self::Lbp1),
+            1 => Some(Self::Lbp2),
+            2 => Some(Self::Lbp3),
+            _ => None,
+        }
+    }
+    /// The game a title ID is a release of, as far as `get_known_titleids` knows
+    pub fn of_titleid(title_id: &str) -> Option<Self> {
+        [Self::Lbp1, Self::Lbp2, Self::Lbp3]
+            .into_iter()
+            .find(|game| game.get_known_titleids().contains(&title_id))
+    }
     pub fn get_latest_revision(&self) -> ResrcRevision {
         match self {
-            Self::Lbp1 => ResrcRevision {
-                head: 0x272,
-                branch_id: 0x4c44,
-                branch_revision: 0x17,
-            },
+            Self::Lbp1 => Branch::Leerdammer.get_latest_revision(),
             Self::Lbp2 => ResrcRevision {
                 head: 0x3f8,
                 branch_id: 0x0,
@@ -73,8 +125,223 @@ pub enum LevelType {
     Cutscene,
 }
 
+impl LevelType {
+    /// name as stored in `slot.levelType`, "cooperative" for NULL
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Self::Cooperative => "cooperative",
+            Self::Versus => "versus",
+            Self::Cutscene => "cutscene",
+        }
+    }
+}
+
+/// How a slot is addressed on the command line, either its numeric `id` or
+/// `guid:<n>` for story/DLC community slots that are keyed by GUID slot number
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SlotRef {
+    Id(i64),
+    Guid(u32),
+}
+
+impl FromStr for SlotRef {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.strip_prefix("guid:") {
+            Some(guid) => guid
+                .parse()
+                .map(Self::Guid)
+                .map_err(|e| format!("invalid GUID slot number `{}`: {}", guid, e)),
+            None => s
+                .parse()
+                .map(Self::Id)
+                .map_err(|e| format!("invalid level ID `{}`: {}", s, e)),
+        }
+    }
+}
+
+impl fmt::Display for SlotRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Id(id) => write!(f, "{}", id),
+            Self::Guid(guid) => write!(f, "guid:{}", guid),
+        }
+    }
+}
+
+/// How the database is opened. It's usually a shared community dump, so
+/// it's always opened read-only
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DbOpenOptions {
+    /// promise SQLite that nothing changes the file while we read it, so it
+    /// takes no locks and ignores the WAL. Needed on read-only network mounts
+    pub immutable: bool,
+}
+
+static DB_OPEN_OPTIONS: OnceLock<DbOpenOptions> = OnceLock::new();
+
+/// Set how `open_db` opens the database, call once at startup
+pub fn set_open_options(options: DbOpenOptions) {
+    let _ = DB_OPEN_OPTIONS.set(options);
+}
+
+/// `file:` URI for `path`, only the characters SQLite treats specially are escaped
+fn db_uri(path: &Path, immutable: bool) -> String {
+    let mut path = path.to_string_lossy().into_owned();
+    if cfg!(windows) {
+        path = path.replace('\\', "/");
+    }
+    for (c, escaped) in [("%", "%25"), ("?", "%3f"), ("#", "%23")] {
+        path = path.replace(c, escaped);
+    }
+    // absolute and UNC paths keep their leading slashes behind an empty authority
+    let authority = match path.starts_with('/') {
+        true => "//",
+        false => "",
+    };
+    let mut uri = format!("file:{}{}?mode=ro", authority, path);
+    if immutable {
+        uri.push_str("&immutable=1");
+    }
+    uri
+}
+
+fn open_db_with(db_path: &Path, immutable: bool) -> rusqlite::Result<Connection> {
+    let flags = OpenFlags::SQLITE_OPEN_READ_ONLY
+        | OpenFlags::SQLITE_OPEN_URI
+        | OpenFlags::SQLITE_OPEN_NO_MUTEX;
+    let conn = Connection::open_with_flags(db_uri(db_path, immutable), flags)?;
+    // the WAL is only looked at on the first read, so fail here rather than mid-query
+    conn.query_row("SELECT count(*) FROM sqlite_master", [], |r| {
+        r.get::<_, i64>(0)
+    })?;
+    Ok(conn)
+}
+
+/// Open the database read-only. A WAL-mode database on a location we can't
+/// write to can't be read normally (SQLite needs to create the `-shm` file),
+/// so that falls back to an immutable open with a warning.
+pub fn open_db(db_path: &Path) -> Result<Connection> {
+    if !db_path.exists() {
+        return Err(anyhow!(
+            "Database file is missing, download it or check if the path in config.yml is correct"
+        ));
+    }
+    let options = DB_OPEN_OPTIONS.get().copied().unwrap_or_default();
+    match open_db_with(db_path, options.immutable) {
+        Ok(conn) => Ok(conn),
+        Err(e)
+            if !options.immutable
+                && matches!(
+                    e.sqlite_error_code(),
+                    Some(ErrorCode::CannotOpen | ErrorCode::ReadOnly)
+                ) =>
+        {
+            static WARNED: OnceLock<()> = OnceLock::new();
+            if WARNED.set(()).is_ok() {
+                eprintln!(
+                    "{}",
+                    Msg::Warning(&Msg::DbOpenedImmutable {
+                        path: db_path,
+                        error: &e.to_string(),
+                    })
+                );
+            }
+            open_db_with(db_path, true)
+                .map_err(|e| anyhow!("Failed to open DB {}: {}", db_path.display(), e))
+        }
+        Err(e) => Err(anyhow!("Failed to open DB {}: {}", db_path.display(), e)),
+    }
+}
+
+/// queries that may wait for the database thread before callers have to
+const DB_QUEUE: usize = 16;
+
+type DbJob = Box<dyn FnOnce(&Connection) + Send>;
+
+/// A connection on a thread of its own, so queries from async code don't
+/// stall the runtime the downloads run on. Clones share the thread, which
+/// closes the connection once the last one is dropped
+#[derive(Clone)]
+pub struct Db {
+    jobs: mpsc::Sender<DbJob>,
+}
+
+impl Db {
+    /// Open the database with `open_db` and start its thread
+    pub fn open(db_path: &Path) -> Result<Self> {
+        let conn = open_db(db_path)?;
+        let (jobs, mut rx) = mpsc::channel::<DbJob>(DB_QUEUE);
+        thread::Builder::new()
+            .name("db".into())
+            .spawn(move || {
+                while let Some(job) = rx.blocking_recv() {
+                    job(&conn);
+                }
+            })
+            .map_err(|e| anyhow!("couldn't start database thread: {}", e))?;
+        Ok(Self { jobs })
+    }
+
+    /// Run `f` with the connection on the database thread
+    pub async fn call<T, F>(&self, f: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Connection) -> Result<T> + Send + 'static,
+    {
+        let (tx, rx) = oneshot::channel();
+        self.jobs
+            .send(Box::new(move |conn| {
+                let _ = tx.send(f(conn));
+            }))
+            .await
+            .map_err(|_| anyhow!("database thread has shut down"))?;
+        rx.await.map_err(|_| anyhow!("database query panicked"))?
+    }
+}
+
+/// column holding the GUID slot number of story/DLC slots, older dumps don't have it
+const SLOT_GUID_COLUMN: &str = "guid";
+
+/// player constraint flags of the slot, older dumps don't have these either
+const SLOT_ENFORCE_MIN_MAX_COLUMN: &str = "enforceMinMaxPlayers";
+const SLOT_SAME_SCREEN_COLUMN: &str = "sameScreenGame";
+/// user columns only some dumps have, UNIX ms like the slot dates
+pub const USER_JOIN_DATE_COLUMN: &str = "joinDate";
+pub const USER_LAST_LOGIN_COLUMN: &str = "lastLogin";
+/// hash of the plan a user's earth is decorated with, not in every dump
+pub const USER_DECORATIONS_COLUMN: &str = "planetDecorations";
+/// hash of a user's pod, not in every dump
+pub const USER_POD_COLUMN: &str = "pod";
+
+/// `column` to select it if `table` has it, otherwise a constant 0
+fn column_or_zero(conn: &Connection, table: &str, column: &'static str) -> Result<&'static str> {
+    Ok(match column_exists(conn, table, column)? {
+        true => column,
+        false => "0",
+    })
+}
+
+fn column_or_null(conn: &Connection, table: &str, column: &'static str) -> Result<&'static str> {
+    Ok(match column_exists(conn, table, column)? {
+        true => column,
+        false => "NULL",
+    })
+}
+
+/// A UNIX ms timestamp, None if there's none: NULL, 0 or out of range
+fn ms_to_datetime(ms: Option<i64>) -> Option<DateTime<Utc>> {
+    ms.filter(|ms| *ms > 0)
+        .and_then(DateTime::from_timestamp_millis)
+}
+
 #[derive(Debug)]
 pub struct SlotInfo {
+    /// numeric `slot.id` of the row, also for slots looked up by GUID
+    pub id: i64,
+    /// GUID slot number of story/DLC slots
+    pub guid: Option<u32>,
     pub name: String,
     pub description: String,
     pub np_handle: String,
@@ -89,23 +356,38 @@ pub struct SlotInfo {
     pub leveltype: LevelType,
     pub min_players: Option<u8>,
     pub max_players: Option<u8>,
+    /// whether the game holds players to `min_players`/`max_players`
+    pub enforce_min_max_players: bool,
+    /// local multiplayer, everyone plays on one screen
+    pub same_screen_game: bool,
     pub is_adventure_planet: bool,
+    /// plan the game decorates the slot's planet with, None for a bare
+    /// default planet
+    pub planet_decorations: Option<[u8; 20]>,
+    /// extra slotlist dependencies for adventure shared data, filled in after download
+    pub adventure_shared: Vec<(ResrcDescriptor, u32)>,
 }
 
-pub fn get_slot_info(id: i64, db_path: &Path) -> Result<SlotInfo> {
-    // 1) make sure file exists
-    if !db_path.exists() {
-        return Err(anyhow!(
-            "Database file is missing, download it or check if the path in config.yml is correct"
-        ));
-    }
-
-    // 2) open with rusqlite
-    let conn = Connection::open(db_path)
-        .map_err(|e| anyhow!("Failed to open DB {}: {}", db_path.display(), e))?;
-
-    // 3) prepare & execute exactly one row
-    let mut stmt = conn.prepare(
+pub fn get_slot_info(slot: SlotRef, conn: &Connection) -> Result<SlotInfo> {
+    // 1) prepare & execute exactly one row
+    let has_guid = column_exists(conn, "slot", SLOT_GUID_COLUMN)?;
+    let (filter, key) = match slot {
+        SlotRef::Id(id) => ("id", id),
+        SlotRef::Guid(_) if !has_guid => {
+            return Err(anyhow!(
+                "This database has no `slot.{}` column, GUID slots can't be looked up",
+                SLOT_GUID_COLUMN
+            ));
+        }
+        SlotRef::Guid(guid) => (SLOT_GUID_COLUMN, guid as i64),
+    };
+    let guid_select = match has_guid {
+        true => SLOT_GUID_COLUMN,
+        false => "NULL",
+    };
+    let enforce_select = column_or_zero(conn, "slot", SLOT_ENFORCE_MIN_MAX_COLUMN)?;
+    let same_screen_select = column_or_zero(conn, "slot", SLOT_SAME_SCREEN_COLUMN)?;
+    let mut stmt = conn.prepare(&format!(
         "SELECT
             name,
             description,
@@ -121,11 +403,15 @@ pub fn get_slot_info(id: i64, db_path: &Path) -> Result<SlotInfo> {
             leveltype,
             minPlayers,
             maxPlayers,
-            isAdventurePlanet
-         FROM slot WHERE id = ?1",
-    )?;
+            isAdventurePlanet,
+            id,
+            {guid_select},
+            {enforce_select},
+            {same_screen_select}
+         FROM slot WHERE {filter} = ?1"
+    ))?;
 
-    let mut rows = stmt.query(params![id])?;
+    let mut rows = stmt.query(params![key])?;
     let row = rows.next()?.ok_or_else(|| anyhow!("Level not found"))?;
 
     // 4) pull out every column just like before
@@ -157,19 +443,15 @@ pub fn get_slot_info(id: i64, db_path: &Path) -> Result<SlotInfo> {
 
     // game version
     let game_int: i64 = row.get(5)?;
-    let game = match game_int {
-        0 => GameVersion::Lbp1,
-        1 => GameVersion::Lbp2,
-        2 => GameVersion::Lbp3,
-        other => return Err(anyhow!("invalid game version `{}` in db", other)),
-    };
+    let game = GameVersion::from_db(game_int)
+        .ok_or_else(|| anyhow!("invalid game version `{}` in db", game_int))?;
 
     // bool flags
     let initially_locked: bool = row.get::<_, i64>(6)? != 0;
     let is_sub_level: bool = row.get::<_, i64>(7)? != 0;
 
     // optional background
-    let background_guid: Option<u32> = row.get::<_, Option<i64>>(8)?.map(|i| i as u32);
+    let background_guid = checked_background(row.get(15)?, row.get(8)?);
 
     let shareable: bool = row.get::<_, i64>(9)? != 0;
 
@@ -200,7 +482,15 @@ pub fn get_slot_info(id: i64, db_path: &Path) -> Result<SlotInfo> {
 
     let is_adventure_planet: bool = row.get::<_, i64>(14)? != 0;
 
+    let id: i64 = row.get(15)?;
+    let guid: Option<u32> = row.get::<_, Option<i64>>(16)?.map(|i| i as u32);
+
+    let enforce_min_max_players: bool = row.get::<_, Option<i64>>(17)?.unwrap_or(0) != 0;
+    let same_screen_game: bool = row.get::<_, Option<i64>>(18)?.unwrap_or(0) != 0;
+
     Ok(SlotInfo {
+        id,
+        guid,
         name,
         description,
         np_handle,
@@ -215,12 +505,581 @@ pub fn get_slot_info(id: i64, db_path: &Path) -> Result<SlotInfo> {
         leveltype,
         min_players,
         max_players,
+        enforce_min_max_players,
+        same_screen_game,
         is_adventure_planet,
+        planet_decorations: None,
+        adventure_shared: Vec::new(),
     })
 }
 
-pub fn fetch_all_users(conn: &Connection, level_id: u32) -> Result<Vec<GameUser>> {
+/// `slot.background` as a GUID. NULL and 0 are the default background,
+/// values that aren't a GUID are dropped with a warning instead of ending
+/// up truncated in the slot list
+fn checked_background(slot_id: i64, background: Option<i64>) -> Option<u32> {
+    match background {
+        None | Some(0) => None,
+        Some(guid) => match u32::try_from(guid) {
+            Ok(guid) => Some(guid),
+            Err(_) => {
+                eprintln!(
+                    "{}",
+                    Msg::Warning(&Msg::BackgroundNotGuid {
+                        slot: slot_id,
+                        background: guid,
+                    })
+                );
+                None
+            }
+        },
+    }
+}
+
+/// The slot columns `fetch-level` needs, batch callers prefetch these for
+/// every level up front instead of querying level by level
+#[derive(Debug)]
+pub struct LevelRecord {
+    pub id: u32,
+    pub root_level: [u8; 20],
+    pub published_in: Option<String>,
+    pub np_handle: String,
+    pub icon: Option<[u8; 20]>,
+    /// None if `slot.game` is NULL or not a known game
+    pub game: Option<GameVersion>,
+    /// empty if the slot has none
+    pub name: String,
+}
+
+/// The user columns `fetch-level` needs for the creator of a level
+#[derive(Debug)]
+pub struct CreatorRecord {
+    pub icon: Vec<u8>,
+    pub planets: Vec<u8>,
+}
+
+const LEVEL_RECORD_COLUMNS: &str = "id, rootLevel, publishedIn, npHandle, icon, game, name";
+
+fn level_record_from_row(row: &rusqlite::Row) -> rusqlite::Result<Result<LevelRecord>> {
+    let id: u32 = row.get(0)?;
+    let root_blob: Vec<u8> = row.get(1)?;
+    let icon_blob: Option<Vec<u8>> = row.get(4)?;
+    let published_in: Option<String> = row.get(2)?;
+    let np_handle: String = row.get(3)?;
+    let game = row.get::<_, Option<i64>>(5)?.and_then(GameVersion::from_db);
+    let name: Option<String> = row.get(6)?;
+
+    Ok(root_blob
+        .try_into()
+        .map_err(|b: Vec<u8>| anyhow!("slot {} rootLevel is {} bytes, expected 20", id, b.len()))
+        .map(|root_level| LevelRecord {
+            id,
+            root_level,
+            published_in,
+            np_handle,
+            icon: icon_blob.and_then(|i| i.try_into().ok()),
+            game,
+            name: name.unwrap_or_default(),
+        }))
+}
+
+pub fn get_level_record(conn: &Connection, level_id: u32) -> Result<LevelRecord> {
+    let mut stmt = conn.prepare_cached(&format!(
+        "SELECT {LEVEL_RECORD_COLUMNS} FROM slot WHERE id = ?1"
+    ))?;
+    stmt.query_row([level_id], level_record_from_row)
+        .optional()?
+        .ok_or_else(|| anyhow!("Level {} not found", level_id))?
+}
+
+/// Every level of a creator in one query, levels with broken rows are skipped with a warning
+pub fn get_creator_levels(conn: &Connection, np_handle: &str) -> Result<Vec<LevelRecord>> {
+    let mut stmt = conn.prepare_cached(&format!(
+        "SELECT {LEVEL_RECORD_COLUMNS} FROM slot WHERE npHandle = ?1 ORDER BY id"
+    ))?;
+    let mut levels: Vec<LevelRecord> = Vec::new();
+    for record in stmt.query_map([np_handle], level_record_from_row)? {
+        match record? {
+            Ok(record) => levels.push(record),
+            Err(e) => eprintln!("{}", Msg::Warning(&e)),
+        }
+    }
+    levels.dedup_by_key(|l| l.id);
+    Ok(levels)
+}
+
+/// Ids of up to `limit` levels whose name has `text` in it, any case
+pub fn search_slots(conn: &Connection, text: &str, limit: usize) -> Result<Vec<i64>> {
+    let pattern = format!(
+        "%{}%",
+        text.replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_")
+    );
+    let mut stmt = conn.prepare_cached(
+        r"SELECT id FROM slot WHERE name LIKE ?1 ESCAPE '\' ORDER BY id LIMIT ?2",
+    )?;
+    let ids = stmt
+        .query_map(params![pattern, limit as i64], |row| row.get(0))?
+        .collect::<rusqlite::Result<_>>()?;
+    Ok(ids)
+}
+
+/// Up to `limit` levels with an id above `after`, to go through the whole
+/// table a page at a time. Also returns the last id looked at, broken rows
+/// are skipped with a warning and story slots get an empty npHandle
+pub fn get_level_records_after(
+    conn: &Connection,
+    after: u32,
+    limit: usize,
+) -> Result<(Vec<LevelRecord>, Option<u32>)> {
+    let mut stmt = conn.prepare_cached(
+        "SELECT id, rootLevel, publishedIn, COALESCE(npHandle, ''), icon, game, name
+           FROM slot WHERE id > ?1 AND rootLevel IS NOT NULL ORDER BY id LIMIT ?2",
+    )?;
+    let mut levels = Vec::new();
+    let mut last = None;
+    for record in stmt.query_map(params![after, limit as i64], |row| {
+        Ok((row.get::<_, u32>(0)?, level_record_from_row(row)?))
+    })? {
+        let (id, record) = record?;
+        last = Some(id);
+        match record {
+            Ok(record) => levels.push(record),
+            Err(e) => eprintln!("{}", Msg::Warning(&e)),
+        }
+    }
+    Ok((levels, last))
+}
+
+pub fn get_creator_record(conn: &Connection, np_handle: &str) -> Result<CreatorRecord> {
+    let mut stmt =
+        conn.prepare_cached(r#"SELECT icon, planets FROM "user" WHERE npHandle = ?1"#)?;
+    let creator = stmt
+        .query_row([np_handle], |r| {
+            Ok(CreatorRecord {
+                icon: r.get::<_, Option<Vec<u8>>>(0)?.unwrap_or_default(),
+                planets: r.get::<_, Option<Vec<u8>>>(1)?.unwrap_or_default(),
+            })
+        })
+        .optional()?;
+    Ok(creator.unwrap_or(CreatorRecord {
+        icon: Vec::new(),
+        planets: Vec::new(),
+    }))
+}
+
+/// (npHandle, icon SHA1) of the creators of `level_ids`, or of every user
+/// in the database if no levels are given. Users without an icon are skipped.
+pub fn get_user_icons(
+    conn: &Connection,
+    level_ids: Option<&[u32]>,
+) -> Result<Vec<(String, [u8; 20])>> {
+    let mut icons = BTreeMap::new();
+    let mut add = |row: &rusqlite::Row| -> rusqlite::Result<()> {
+        let np_handle: String = row.get(0)?;
+        let icon: Option<Vec<u8>> = row.get(1)?;
+        if let Some(icon) = icon.and_then(|i| <[u8; 20]>::try_from(i).ok()) {
+            icons.insert(np_handle, icon);
+        }
+        Ok(())
+    };
+
+    match level_ids {
+        Some(level_ids) => {
+            let mut stmt = conn.prepare_cached(
+                r#"SELECT u.npHandle, u.icon
+                     FROM "user" AS u
+                     JOIN slot AS s ON s.npHandle = u.npHandle
+                    WHERE s.id = ?1"#,
+            )?;
+            for id in level_ids {
+                let mut rows = stmt.query([id])?;
+                while let Some(row) = rows.next()? {
+                    add(row)?;
+                }
+            }
+        }
+        None => {
+            let mut stmt = conn.prepare(r#"SELECT npHandle, icon FROM "user""#)?;
+            let mut rows = stmt.query([])?;
+            while let Some(row) = rows.next()? {
+                add(row)?;
+            }
+        }
+    }
+
+    Ok(icons.into_iter().collect())
+}
+
+/// Slot tables of official content some dumps have besides `slot`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlotTableKind {
+    Developer,
+    Story,
+    Moon,
+}
+
+impl SlotTableKind {
+    const ALL: [Self; 3] = [Self::Developer, Self::Story, Self::Moon];
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Self::Developer => "developer",
+            Self::Story => "story",
+            Self::Moon => "moon",
+        }
+    }
+
+    /// what the table is called in the dumps that have it
+    fn table_names(&self) -> [&'static str; 2] {
+        match self {
+            Self::Developer => ["developer", "developer_slot"],
+            Self::Story => ["story", "story_slot"],
+            Self::Moon => ["moon", "moon_slot"],
+        }
+    }
+}
+
+/// columns the extra slot tables may have, in the order they're selected
+const EXTRA_SLOT_COLUMNS: [&str; 6] =
+    ["id", "name", "description", "npHandle", "rootLevel", "icon"];
+
+/// An extra slot table found in the database, with the columns it has
+pub struct ExtraSlotTable {
+    pub kind: SlotTableKind,
+    pub table: &'static str,
+    columns: Vec<&'static str>,
+}
+
+/// A row of an extra slot table, with whatever of the slot columns it has
+#[derive(Debug, Clone, Default)]
+pub struct ExtraSlot {
+    pub id: Option<i64>,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub np_handle: Option<String>,
+    pub root_level: Option<[u8; 20]>,
+    pub icon: Option<[u8; 20]>,
+}
+
+/// The extra slot tables this database has. Their layout differs between
+/// dumps, so only tables with an `id` or `rootLevel` column are used
+pub fn extra_slot_tables(conn: &Connection) -> Result<Vec<ExtraSlotTable>> {
+    let mut tables = Vec::new();
+    for kind in SlotTableKind::ALL {
+        for table in kind.table_names() {
+            if !table_exists(conn, table)? {
+                continue;
+            }
+            let mut columns = Vec::new();
+            for column in EXTRA_SLOT_COLUMNS {
+                if column_exists(conn, table, column)? {
+                    columns.push(column);
+                }
+            }
+            if columns.contains(&"id") || columns.contains(&"rootLevel") {
+                tables.push(ExtraSlotTable {
+                    kind,
+                    table,
+                    columns,
+                });
+                break;
+            }
+        }
+    }
+    Ok(tables)
+}
+
+/// Every row of an extra slot table
+pub fn extra_slots(conn: &Connection, table: &ExtraSlotTable) -> Result<Vec<ExtraSlot>> {
+    let select: Vec<String> = table.columns.iter().map(|c| format!("\"{}\"", c)).collect();
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM \"{}\"",
+        select.join(", "),
+        table.table
+    ))?;
+    let mut rows = stmt.query([])?;
+    let mut slots = Vec::new();
+    while let Some(row) = rows.next()? {
+        let mut slot = ExtraSlot::default();
+        for (i, column) in table.columns.iter().enumerate() {
+            let hash = || -> rusqlite::Result<Option<[u8; 20]>> {
+                Ok(row
+                    .get::<_, Option<Vec<u8>>>(i)?
+                    .and_then(|h| h.try_into().ok()))
+            };
+            match *column {
+                "id" => slot.id = row.get(i)?,
+                "name" => slot.name = row.get(i)?,
+                "description" => slot.description = row.get(i)?,
+                "npHandle" => slot.np_handle = row.get(i)?,
+                "rootLevel" => slot.root_level = hash()?,
+                "icon" => slot.icon = hash()?,
+                _ => unreachable!("not in EXTRA_SLOT_COLUMNS"),
+            }
+        }
+        slots.push(slot);
+    }
+    Ok(slots)
+}
+
+/// A slot pointing at one of the hashes `slots_referencing` looked for
+pub struct SlotReference {
+    /// where the slot is from, `slot` or one of the extra slot tables
+    pub table: &'static str,
+    pub slot_id: i64,
+    pub name: String,
+    pub np_handle: String,
+    /// the hash the slot points at, and whether as its `rootLevel` or `icon`
+    pub hash: [u8; 20],
+    pub column: &'static str,
+}
+
+/// Every slot whose rootLevel or icon is one of `hashes`. One pass over the
+/// slot table, since neither column is indexed in the community dumps, and
+/// over the extra slot tables if there are any
+pub fn slots_referencing(
+    conn: &Connection,
+    hashes: &BTreeSet<[u8; 20]>,
+) -> Result<Vec<SlotReference>> {
+    let mut stmt = conn.prepare("SELECT id, name, npHandle, rootLevel, icon FROM slot")?;
+    let mut rows = stmt.query([])?;
+    let mut refs = Vec::new();
+    while let Some(row) = rows.next()? {
+        for (index, column) in [(3, "rootLevel"), (4, "icon")] {
+            let Some(hash) = row
+                .get::<_, Option<Vec<u8>>>(index)?
+                .and_then(|h| <[u8; 20]>::try_from(h).ok())
+                .filter(|h| hashes.contains(h))
+            else {
+                continue;
+            };
+            refs.push(SlotReference {
+                table: "slot",
+                slot_id: row.get(0)?,
+                name: row.get::<_, Option<String>>(1)?.unwrap_or_default(),
+                np_handle: row.get(2)?,
+                hash,
+                column,
+            });
+        }
+    }
+    for table in extra_slot_tables(conn)? {
+        for slot in extra_slots(conn, &table)? {
+            for (hash, column) in [(slot.root_level, "rootLevel"), (slot.icon, "icon")] {
+                if let Some(hash) = hash.filter(|h| hashes.contains(h)) {
+                    refs.push(SlotReference {
+                        table: table.kind.get_name(),
+                        slot_id: slot.id.unwrap_or_default(),
+                        name: slot.name.clone().unwrap_or_default(),
+                        np_handle: slot.np_handle.clone().unwrap_or_default(),
+                        hash,
+                        column,
+                    });
+                }
+            }
+        }
+    }
+    Ok(refs)
+}
+
+/// Slots whose rootLevel is also the rootLevel of another creator's slot,
+/// by rootLevel and then id. Story slots count as a creator of their own
+pub fn slots_sharing_root(conn: &Connection) -> Result<Vec<SlotReference>> {
     let mut stmt = conn.prepare(
+        "SELECT id, name, COALESCE(npHandle, ''), rootLevel FROM slot
+          WHERE rootLevel IN (
+                SELECT rootLevel FROM slot WHERE rootLevel IS NOT NULL
+                 GROUP BY rootLevel HAVING count(DISTINCT COALESCE(npHandle, '')) > 1)
+          ORDER BY rootLevel, id",
+    )?;
+    let mut rows = stmt.query([])?;
+    let mut refs = Vec::new();
+    while let Some(row) = rows.next()? {
+        let Ok(hash) = <[u8; 20]>::try_from(row.get::<_, Vec<u8>>(3)?) else {
+            continue;
+        };
+        refs.push(SlotReference {
+            table: "slot",
+            slot_id: row.get(0)?,
+            name: row.get::<_, Option<String>>(1)?.unwrap_or_default(),
+            np_handle: row.get(2)?,
+            hash,
+            column: "rootLevel",
+        });
+    }
+    Ok(refs)
+}
+
+/// A slot another level (possibly) links to
+#[derive(Debug, Clone)]
+pub struct LinkedLevel {
+    pub slot_id: i64,
+    pub name: String,
+    pub np_handle: String,
+}
+
+/// The slots among `candidates` that exist, leaving out `own_id`
+pub fn linked_levels(
+    conn: &Connection,
+    candidates: &BTreeSet<u32>,
+    own_id: i64,
+) -> Result<Vec<LinkedLevel>> {
+    let mut stmt = conn.prepare_cached("SELECT name, npHandle FROM slot WHERE id = ?1")?;
+    let mut levels = Vec::new();
+    for &id in candidates {
+        if id as i64 == own_id {
+            continue;
+        }
+        let row = stmt
+            .query_row([id], |r| {
+                Ok((r.get::<_, Option<String>>(0)?, r.get::<_, String>(1)?))
+            })
+            .optional()?;
+        if let Some((name, np_handle)) = row {
+            levels.push(LinkedLevel {
+                slot_id: id as i64,
+                name: name.unwrap_or_default(),
+                np_handle,
+            });
+        }
+    }
+    Ok(levels)
+}
+
+/// SHA1 of a user's profile icon, if they have one
+pub fn get_user_icon(np_handle: &str, conn: &Connection) -> Result<Option<[u8; 20]>> {
+    let icon: Option<Vec<u8>> = conn
+        .query_row(
+            r#"SELECT icon FROM "user" WHERE npHandle = ?1"#,
+            [np_handle],
+            |r| r.get(0),
+        )
+        .optional()?
+        .flatten();
+    Ok(icon.and_then(|i| i.try_into().ok()))
+}
+
+/// npHandle of the user whose planets `planets` is, if any
+pub fn get_planet_owner(conn: &Connection, planets: [u8; 20]) -> Result<Option<String>> {
+    // a user's planets column can hold the planets of several games
+    let mut stmt = conn
+        .prepare_cached(r#"SELECT npHandle, planets FROM "user" WHERE instr(planets, ?1) > 0"#)?;
+    let mut rows = stmt.query([planets.as_slice()])?;
+    while let Some(row) = rows.next()? {
+        let owned: Vec<u8> = row.get(1)?;
+        if owned.chunks_exact(20).any(|c| c == planets) {
+            return Ok(Some(row.get(0)?));
+        }
+    }
+    Ok(None)
+}
+
+/// What a user's planets are dressed with besides their levels
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct PlanetExtras {
+    pub decorations: Option<[u8; 20]>,
+    pub pod: Option<[u8; 20]>,
+}
+
+/// The decorations and pod the `user` record of `np_handle` names. None
+/// where the database has no such column or the value isn't a SHA1
+pub fn get_planet_extras(conn: &Connection, np_handle: &str) -> Result<PlanetExtras> {
+    let decorations = column_or_null(conn, "user", USER_DECORATIONS_COLUMN)?;
+    let pod = column_or_null(conn, "user", USER_POD_COLUMN)?;
+    let sha1 = |value: Option<Vec<u8>>| value.and_then(|v| <[u8; 20]>::try_from(v).ok());
+    let extras = conn
+        .query_row(
+            &format!(r#"SELECT {decorations}, {pod} FROM "user" WHERE npHandle = ?1"#),
+            [np_handle],
+            |r| {
+                Ok(PlanetExtras {
+                    decorations: sha1(r.get(0)?),
+                    pod: sha1(r.get(1)?),
+                })
+            },
+        )
+        .optional()?;
+    Ok(extras.unwrap_or_default())
+}
+
+/// Which of `root_levels` are the rootLevel of a published slot
+pub fn published_root_levels(
+    root_levels: &[[u8; 20]],
+    conn: &Connection,
+) -> Result<BTreeSet<[u8; 20]>> {
+    let mut stmt = conn.prepare("SELECT EXISTS(SELECT 1 FROM slot WHERE rootLevel = ?1)")?;
+    let mut published = BTreeSet::new();
+    for root in root_levels {
+        if stmt.query_row([root.as_slice()], |r| r.get(0))? {
+            published.insert(*root);
+        }
+    }
+    Ok(published)
+}
+
+/// optional table with translated slot names, only some community dumps have it
+const SLOT_TRANSLATION_TABLE: &str = "slot_translation";
+
+pub fn table_exists(conn: &Connection, table: &str) -> Result<bool> {
+    Ok(conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?1)",
+        [table],
+        |r| r.get(0),
+    )?)
+}
+
+pub fn column_exists(conn: &Connection, table: &str, column: &str) -> Result<bool> {
+    Ok(conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM pragma_table_info(?1) WHERE name = ?2)",
+        [table, column],
+        |r| r.get(0),
+    )?)
+}
+
+/// Swap the slot name/description for their translation in `language`,
+/// returns false if the database has no translation for this slot
+pub fn apply_slot_translation(
+    id: i64,
+    conn: &Connection,
+    language: Language,
+    slot_info: &mut SlotInfo,
+) -> Result<bool> {
+    // the names in the slot table are already the originals
+    if language == Language::En {
+        return Ok(false);
+    }
+
+    if !table_exists(conn, SLOT_TRANSLATION_TABLE)? {
+        return Ok(false);
+    }
+
+    let translation: Option<(Option<String>, Option<String>)> = conn
+        .query_row(
+            &format!(
+                "SELECT name, description FROM {SLOT_TRANSLATION_TABLE} WHERE id = ?1 AND language = ?2"
+            ),
+            params![id, language.get_code()],
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        )
+        .optional()?;
+
+    let Some((name, description)) = translation else {
+        return Ok(false);
+    };
+    if let Some(name) = name.filter(|n| !n.is_empty()) {
+        slot_info.name = name;
+    }
+    if let Some(description) = description.filter(|d| !d.is_empty()) {
+        slot_info.description = description;
+    }
+    Ok(true)
+}
+
+pub fn fetch_all_users(conn: &Connection, level_id: u32) -> Result<Vec<GameUser>> {
+    let join_date_select = column_or_null(conn, "user", USER_JOIN_DATE_COLUMN)?;
+    let last_login_select = column_or_null(conn, "user", USER_LAST_LOGIN_COLUMN)?;
+    let mut stmt = conn.prepare_cached(&format!(
         r#"
         SELECT
           u.npHandle,
@@ -228,13 +1087,15 @@ pub fn fetch_all_users(conn: &Connection, level_id: u32) -> Result<Vec<GameUser>
           u.locationX,
           u.locationY,
           u.commentsEnabled,
-          u.planets
+          u.planets,
+          {join_date_select},
+          {last_login_select}
         FROM "user" AS u
         INNER JOIN slot AS s
           ON s.npHandle = u.npHandle
         WHERE s.id = ?1
-        "#,
-    )?;
+        "#
+    ))?;
 
     let users = stmt
         .query_map(params![level_id], |row| {
@@ -247,9 +1108,9 @@ pub fn fetch_all_users(conn: &Connection, level_id: u32) -> Result<Vec<GameUser>
             let icon_blob: Vec<u8> = row.get(1)?;
             let icon_hash = hex::encode(&icon_blob);
 
-            // location
-            let location_x = row.get::<_, u16>(2)? as i64;
-            let location_y = row.get::<_, u16>(3)? as i64;
+            // location, as stored: the columns aren't 16 bit in every dump
+            let location_x = row.get::<_, Option<i64>>(2)?.unwrap_or(0);
+            let location_y = row.get::<_, Option<i64>>(3)?.unwrap_or(0);
 
             // commentsEnabled
             let allow_ip_auth = row.get::<_, i64>(4)? != 0;
@@ -275,7 +1136,7 @@ pub fn fetch_all_users(conn: &Connection, level_id: u32) -> Result<Vec<GameUser>
                 description: String::new(),
                 location_x,
                 location_y,
-                join_date: Utc.timestamp_opt(0, 0).unwrap(),
+                join_date: ms_to_datetime(row.get(6)?),
                 pins: Default::default(),
                 beta_planets_hash: String::new(),
                 lbp2_planets_hash, // ← filled now!
@@ -287,7 +1148,7 @@ pub fn fetch_all_users(conn: &Connection, level_id: u32) -> Result<Vec<GameUser>
                 allow_ip_authentication: allow_ip_auth,
                 ban_reason: None,
                 ban_expiry_date: None,
-                last_login_date: Utc.timestamp_opt(0, 0).unwrap(),
+                last_login_date: ms_to_datetime(row.get(7)?),
                 rpcn_authentication_allowed: false,
                 psn_authentication_allowed: false,
                 _profile_visibility: 0,
@@ -305,10 +1166,18 @@ pub fn fetch_all_users(conn: &Connection, level_id: u32) -> Result<Vec<GameUser>
 }
 
 /// Fetch exactly this one GameLevel
-
-pub fn fetch_all_levels(conn: &Connection, level_id: u32) -> Result<Vec<GameLevel>> {
+///
+/// `original_publisher` credits someone other than the uploader, for levels
+/// re-published on behalf of their creator
+pub fn fetch_all_levels(
+    conn: &Connection,
+    level_id: u32,
+    original_publisher: Option<&str>,
+) -> Result<Vec<GameLevel>> {
     // 1) pull exactly this slot row
-    let mut stmt = conn.prepare(
+    let enforce_select = column_or_zero(conn, "slot", SLOT_ENFORCE_MIN_MAX_COLUMN)?;
+    let same_screen_select = column_or_zero(conn, "slot", SLOT_SAME_SCREEN_COLUMN)?;
+    let mut stmt = conn.prepare_cached(&format!(
         r#"
         SELECT 
             id, 
@@ -322,11 +1191,14 @@ pub fn fetch_all_levels(conn: &Connection, level_id: u32) -> Result<Vec<GameLeve
             firstPublished,
             lastUpdated,
             minPlayers,
-            maxPlayers
+            maxPlayers,
+            {enforce_select},
+            {same_screen_select},
+            background
         FROM slot
         WHERE id = ?1
-    "#,
-    )?;
+    "#
+    ))?;
 
     let level = stmt.query_row(params![level_id], |row| {
         // helper to turn UNIX‐ms → chrono DateTime<Utc>
@@ -343,13 +1215,16 @@ pub fn fetch_all_levels(conn: &Connection, level_id: u32) -> Result<Vec<GameLeve
         let name: Option<String> = row.get(2)?;
         let icon_blob: Vec<u8> = row.get(3)?;
         let desc: Option<String> = row.get(4)?;
-        let lx: u16 = row.get(5)?;
-        let ly: u16 = row.get(6)?;
+        let lx: i64 = row.get::<_, Option<i64>>(5)?.unwrap_or(0);
+        let ly: i64 = row.get::<_, Option<i64>>(6)?.unwrap_or(0);
         let root_blob: Vec<u8> = row.get(7)?;
         let first_pub: Option<u64> = row.get(8)?;
         let last_upd: Option<u64> = row.get(9)?;
         let min_p: Option<u8> = row.get::<_, Option<i64>>(10)?.map(|i| i as u8);
         let max_p: Option<u8> = row.get::<_, Option<i64>>(11)?.map(|i| i as u8);
+        let enforce_p: bool = row.get::<_, Option<i64>>(12)?.unwrap_or(0) != 0;
+        let same_screen: bool = row.get::<_, Option<i64>>(13)?.unwrap_or(0) != 0;
+        let background = checked_background(id as i64, row.get(14)?);
 
         // map into your RealmObject struct
         Ok(GameLevel {
@@ -358,18 +1233,19 @@ pub fn fetch_all_levels(conn: &Connection, level_id: u32) -> Result<Vec<GameLeve
             title: name.unwrap_or_default(),
             icon_hash: hex::encode(icon_blob),
             description: desc.unwrap_or_default(),
-            location_x: lx as i64,
-            location_y: ly as i64,
+            location_x: lx,
+            location_y: ly,
             root_resource: hex::encode(root_blob),
             publish_date: ms_to_dt(first_pub),
             update_date: ms_to_dt(last_upd),
             min_players: min_p.unwrap_or(0) as i64,
             max_players: max_p.unwrap_or(0) as i64,
-            enforce_min_max_players: false, // placeholder
-            same_screen_game: false,
+            enforce_min_max_players: enforce_p,
+            same_screen_game: same_screen,
             date_team_picked: None,
             is_modded: false,
-            background_guid: None,
+            // the same `g<guid>` form as in server XML
+            background_guid: background.map(|guid| format!("g{}", guid)),
             _game_version: 0,
             _level_type: 0,
             story_id: 0,
@@ -380,44 +1256,171 @@ pub fn fetch_all_levels(conn: &Connection, level_id: u32) -> Result<Vec<GameLeve
             skill_rewards: Vec::new(),
             reviews: Vec::new(),
             publisher_id: ObjectId::new(), // we’ll wire this up from fetch_all_users
-            original_publisher: Some(String::new()),
-            is_re_upload: false,
+            original_publisher: Some(original_publisher.unwrap_or_default().to_string()),
+            is_re_upload: original_publisher.is_some(),
         })
     })?;
 
     Ok(vec![level])
 }
 
+/// prefix of GUID dependencies in exported relations, hashes are plain hex
+pub const GUID_DEPENDENCY_PREFIX: &str = "g";
+
+/// type id of textures in dependency tables
+const TEXTURE_TYPE: u32 = 1;
+
+/// Which relations go into a Refresh export, `relation_filter` in config.yml
+#[derive(Debug, Default, Clone, Deserialize)]
+pub struct RelationFilter {
+    /// resource type names, like `texture`. Relations into resources
+    /// declared as one of them are left out
+    #[serde(default)]
+    pub skip_types: Vec<String>,
+    /// keep one relation into each texture of a level instead of one from
+    /// every resource using it. The one kept is from the user with the
+    /// lowest SHA1, the others' relations to the texture are gone, not just
+    /// those of its mipmaps: LBP keeps a texture's mips in the one resource
+    #[serde(default)]
+    pub collapse_textures: bool,
+}
+
+impl RelationFilter {
+    pub fn validate(&self) -> Result<()> {
+        self.skip_type_ids().map(|_| ())
+    }
+
+    fn skip_type_ids(&self) -> Result<BTreeSet<u32>> {
+        self.skip_types
+            .iter()
+            .map(|name| {
+                resrc_type_id(name).ok_or_else(|| {
+                    anyhow!(
+                        "relation_filter.skip_types: `{}` isn't a resource type, like `texture` or `gfx material`",
+                        name
+                    )
+                })
+            })
+            .collect()
+    }
+}
+
+/// Flatten the dependency tables of every resource into relations.
+///
+/// GUID dependencies point at official content rather than a blob in the
+/// archive, they're only exported (as `g<guid>`) when `include_guids` is set.
+/// `filter` leaves out the relations Refresh doesn't need.
 pub fn fetch_all_relations(
     resources: &BTreeMap<[u8; 20], Vec<u8>>,
-) -> Vec<AssetDependencyRelation> {
+    include_guids: bool,
+    filter: &RelationFilter,
+) -> Result<Vec<AssetDependencyRelation>> {
+    let skip_types = filter.skip_type_ids()?;
     let mut rels = Vec::new();
+    let mut textures = HashSet::new();
 
     for (parent_sha, blob) in resources {
         // try to parse it as a ResrcData
-        if let Ok(resrc) = ResrcData::new(blob, /* do_decompress */ false) {
-            if let ResrcMethod::Binary { dependencies, .. } = resrc.method {
-                for dep in dependencies {
-                    // only Sha1‐desc dependencies are real blobs
-                    if let ResrcDescriptor::Sha1(child_sha) = dep.desc {
-                        rels.push(AssetDependencyRelation {
-                            dependent: hex::encode(parent_sha),
-                            dependency: hex::encode(&child_sha),
-                        });
-                    }
+        if let Ok(resrc) = ResrcData::new(blob, /* do_decompress */ false)
+            && let ResrcMethod::Binary { dependencies, .. } = resrc.method
+        {
+            for dep in dependencies {
+                if skip_types.contains(&dep.resrc_type)
+                    || (filter.collapse_textures
+                        && dep.resrc_type == TEXTURE_TYPE
+                        && !textures.insert(dep.desc))
+                {
+                    continue;
                 }
+                let dependency = match dep.desc {
+                    ResrcDescriptor::Sha1(child_sha) => hex::encode(child_sha),
+                    ResrcDescriptor::Guid(guid) if include_guids => {
+                        format!("{}{}", GUID_DEPENDENCY_PREFIX, guid)
+                    }
+                    ResrcDescriptor::Guid(_) => continue,
+                };
+                rels.push(AssetDependencyRelation {
+                    dependent: hex::encode(parent_sha),
+                    dependency,
+                });
             }
         }
     }
 
-    rels
+    Ok(rels)
+}
+
+/// Every dependency table entry as a typed edge, with SHA1 dependencies
+/// checked against the blob they point at when it was downloaded
+pub fn fetch_dependency_edges(resources: &BTreeMap<[u8; 20], Vec<u8>>) -> Vec<DependencyEdge> {
+    let mut edges = Vec::new();
+    for (parent_sha, blob) in resources {
+        let Ok(ResrcData {
+            method: ResrcMethod::Binary { dependencies, .. },
+            ..
+        }) = ResrcData::new(blob, /* do_decompress */ false)
+        else {
+            continue;
+        };
+        for dep in dependencies {
+            let (dependency, is_guid, child) = match dep.desc {
+                ResrcDescriptor::Sha1(sha) => (hex::encode(sha), false, resources.get(&sha)),
+                ResrcDescriptor::Guid(guid) => {
+                    (format!("{}{}", GUID_DEPENDENCY_PREFIX, guid), true, None)
+                }
+            };
+            let expected = resrc_type_magics(dep.resrc_type);
+            let type_mismatch = child.is_some_and(|data| {
+                !expected.is_empty() && !expected.iter().any(|m| data.starts_with(m))
+            });
+            edges.push(DependencyEdge {
+                dependent: hex::encode(parent_sha),
+                dependency,
+                is_guid,
+                declared_type: dep.resrc_type,
+                declared_type_name: resrc_type_name(dep.resrc_type).map(str::to_string),
+                classified_as: child.map(|data| classify(data).0),
+                type_mismatch,
+            });
+        }
+    }
+    edges
 }
 
 /// Fetch all GameAsset rows *for* this level
-pub fn fetch_all_assets(resources: &BTreeMap<[u8; 20], Vec<u8>>) -> Vec<GameAsset> {
+///
+/// `converted_icons` maps TEX/GTF icon hashes to their PNG conversion, which
+/// gets its own asset and is referenced through `AsMainlineIconHash`
+pub fn fetch_all_assets(
+    resources: &BTreeMap<[u8; 20], Vec<u8>>,
+    converted_icons: &BTreeMap<[u8; 20], Vec<u8>>,
+) -> Vec<GameAsset> {
+    let mut png_hashes = BTreeMap::new();
+    for (icon_sha, png) in converted_icons {
+        let mut hasher = Sha1::new();
+        hasher.update(png);
+        let png_sha: [u8; 20] = hasher.finalize().into();
+        png_hashes.insert(*icon_sha, png_sha);
+    }
+
+    let png_assets = png_hashes.values().map(|png_sha| GameAsset {
+        asset_hash: hex::encode(png_sha),
+        original_uploader_id: ObjectId::new(),
+        upload_date: Utc.timestamp_opt(0, 0).unwrap(),
+        is_psp: false,
+        size_in_bytes: 0,
+        _asset_type: 0,
+        _asset_serialization_method: 0,
+        dependencies: Vec::new(),
+        // already a PNG, so it's its own icon
+        as_mainline_icon_hash: Some(hex::encode(png_sha)),
+        as_mip_icon_hash: Some(String::new()),
+        as_mainline_photo_hash: Some(String::new()),
+    });
+
     resources
-        .iter()
-        .map(|(sha, _blob)| {
+        .keys()
+        .map(|sha| {
             GameAsset {
                 asset_hash: hex::encode(sha),
                 // dry.db doesn’t have uploader ObjectIds, so just make a new one:
@@ -430,10 +1433,13 @@ pub fn fetch_all_assets(resources: &BTreeMap<[u8; 20], Vec<u8>>) -> Vec<GameAsse
                 _asset_serialization_method: 0,
                 // will fill from your relations map:
                 dependencies: Vec::new(),
-                as_mainline_icon_hash: Some(String::new()),
+                as_mainline_icon_hash: Some(
+                    png_hashes.get(sha).map(hex::encode).unwrap_or_default(),
+                ),
                 as_mip_icon_hash: Some(String::new()),
                 as_mainline_photo_hash: Some(String::new()),
             }
         })
+        .chain(png_assets)
         .collect()
 }
@@ -0,0 +1,199 @@
+// src/db_diff.rs
+
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use rusqlite::Connection;
+
+use crate::db::column_exists;
+
+/// What happened to a level between two databases
+#[derive(Debug, PartialEq)]
+pub enum LevelChange {
+    Added,
+    Removed,
+    /// `lastUpdated` differs, in UNIX ms
+    Updated {
+        old: Option<i64>,
+        new: Option<i64>,
+    },
+}
+
+#[derive(Debug)]
+pub struct ChangedLevel {
+    pub id: i64,
+    pub name: String,
+    pub change: LevelChange,
+}
+
+/// Whether a creator's user row is in one database only
+#[derive(Debug, Default, PartialEq)]
+pub enum CreatorChange {
+    Added,
+    Removed,
+    /// in both, or in neither and only known from their slots
+    #[default]
+    Kept,
+}
+
+#[derive(Debug, Default)]
+pub struct CreatorDiff {
+    pub change: CreatorChange,
+    pub levels: Vec<ChangedLevel>,
+}
+
+/// The creators whose user row or levels differ between two databases,
+/// by npHandle. Slots without a creator are under the empty handle
+#[derive(Debug, Default)]
+pub struct DbDiff {
+    pub creators: BTreeMap<String, CreatorDiff>,
+}
+
+impl DbDiff {
+    /// Levels with this kind of change, by any creator
+    pub fn count(&self, matches: impl Fn(&LevelChange) -> bool) -> usize {
+        self.creators
+            .values()
+            .flat_map(|creator| &creator.levels)
+            .filter(|level| matches(&level.change))
+            .count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.creators.is_empty()
+    }
+}
+
+struct Level {
+    name: String,
+    np_handle: String,
+    last_updated: Option<i64>,
+}
+
+/// Walk two result sets sorted by key side by side, calling `f` with the
+/// row of each side for every key, so neither has to fit in memory
+fn merge<K: Ord, A, B>(
+    mut old: impl Iterator<Item = rusqlite::Result<(K, A)>>,
+    mut new: impl Iterator<Item = rusqlite::Result<(K, B)>>,
+    mut f: impl FnMut(K, Option<A>, Option<B>),
+) -> Result<()> {
+    let mut next_old = old.next().transpose()?;
+    let mut next_new = new.next().transpose()?;
+    loop {
+        match (next_old.take(), next_new.take()) {
+            (None, None) => return Ok(()),
+            (Some((key, a)), None) => {
+                f(key, Some(a), None);
+                next_old = old.next().transpose()?;
+            }
+            (None, Some((key, b))) => {
+                f(key, None, Some(b));
+                next_new = new.next().transpose()?;
+            }
+            (Some((old_key, a)), Some((new_key, b))) => match old_key.cmp(&new_key) {
+                std::cmp::Ordering::Less => {
+                    f(old_key, Some(a), None);
+                    next_old = old.next().transpose()?;
+                    next_new = Some((new_key, b));
+                }
+                std::cmp::Ordering::Greater => {
+                    f(new_key, None, Some(b));
+                    next_old = Some((old_key, a));
+                    next_new = new.next().transpose()?;
+                }
+                std::cmp::Ordering::Equal => {
+                    f(old_key, Some(a), Some(b));
+                    next_old = old.next().transpose()?;
+                    next_new = new.next().transpose()?;
+                }
+            },
+        }
+    }
+}
+
+fn level_query(conn: &Connection) -> Result<String> {
+    // older dumps have no lastUpdated, their levels never count as updated
+    let last_updated = match column_exists(conn, "slot", "lastUpdated")? {
+        true => "lastUpdated",
+        false => "NULL",
+    };
+    Ok(format!(
+        "SELECT id, name, npHandle, {} FROM slot ORDER BY id",
+        last_updated
+    ))
+}
+
+fn level_row(row: &rusqlite::Row) -> rusqlite::Result<(i64, Level)> {
+    Ok((
+        row.get(0)?,
+        Level {
+            name: row.get::<_, Option<String>>(1)?.unwrap_or_default(),
+            np_handle: row.get::<_, Option<String>>(2)?.unwrap_or_default(),
+            last_updated: row.get(3)?,
+        },
+    ))
+}
+
+// BINARY collation orders like `str`, which `merge` needs
+const CREATOR_QUERY: &str =
+    "SELECT DISTINCT npHandle FROM user WHERE npHandle IS NOT NULL ORDER BY npHandle";
+
+/// Compare the creators and levels of `old` with those of `new`. Levels
+/// are matched by slot id, a level that changed creator is removed from
+/// the old one and added to the new one
+pub fn compare(old: &Connection, new: &Connection) -> Result<DbDiff> {
+    let mut diff = DbDiff::default();
+
+    let mut old_users = old.prepare(CREATOR_QUERY)?;
+    let mut new_users = new.prepare(CREATOR_QUERY)?;
+    merge(
+        old_users.query_map([], |row| Ok((row.get::<_, String>(0)?, ())))?,
+        new_users.query_map([], |row| Ok((row.get::<_, String>(0)?, ())))?,
+        |np_handle, a, b| {
+            let change = match (a, b) {
+                (Some(_), None) => CreatorChange::Removed,
+                (None, Some(_)) => CreatorChange::Added,
+                _ => return,
+            };
+            diff.creators.entry(np_handle).or_default().change = change;
+        },
+    )?;
+
+    let mut old_levels = old.prepare(&level_query(old)?)?;
+    let mut new_levels = new.prepare(&level_query(new)?)?;
+    merge(
+        old_levels.query_map([], level_row)?,
+        new_levels.query_map([], level_row)?,
+        |id, a, b| {
+            let mut record = |level: Level, change| {
+                diff.creators
+                    .entry(level.np_handle)
+                    .or_default()
+                    .levels
+                    .push(ChangedLevel {
+                        id,
+                        name: level.name,
+                        change,
+                    });
+            };
+            match (a, b) {
+                (Some(a), Some(b)) if a.np_handle != b.np_handle => {
+                    record(a, LevelChange::Removed);
+                    record(b, LevelChange::Added);
+                }
+                (Some(a), Some(b)) if a.last_updated != b.last_updated => {
+                    let change = LevelChange::Updated {
+                        old: a.last_updated,
+                        new: b.last_updated,
+                    };
+                    record(b, change);
+                }
+                (Some(a), None) => record(a, LevelChange::Removed),
+                (None, Some(b)) => record(b, LevelChange::Added),
+                _ => {}
+            }
+        },
+    )?;
+
+    Ok(diff)
+}
@@ -0,0 +1,100 @@
+// src/dlc.rs
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Result, anyhow, bail};
+
+/// The GUIDs of one DLC pack. The tool has no list of what's in which
+/// pack, it comes from a file with one GUID per line, `123` or `g123`,
+/// `#` starting a comment. The pack is named after the file
+pub struct DlcPack {
+    pub name: String,
+    pub guids: BTreeSet<u32>,
+}
+
+impl DlcPack {
+    pub fn read(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path)
+            .map_err(|e| anyhow!("failed to open {}: {}", path.display(), e))?;
+        let mut guids = BTreeSet::new();
+        for line in text.lines() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let number = line.strip_prefix('g').unwrap_or(line);
+            guids.insert(
+                number
+                    .parse()
+                    .map_err(|e| anyhow!("invalid GUID `{}` in {}: {}", line, path.display(), e))?,
+            );
+        }
+        if guids.is_empty() {
+            bail!("{} lists no GUIDs", path.display());
+        }
+        let name = path.file_stem().map_or_else(
+            || path.display().to_string(),
+            |s| s.to_string_lossy().into_owned(),
+        );
+        Ok(Self { name, guids })
+    }
+}
+
+/// A level that needs the pack
+pub struct LevelUse {
+    pub id: i64,
+    pub name: Option<String>,
+    /// how many of the pack's GUIDs it uses
+    pub guids: usize,
+}
+
+/// Which creators' levels need a pack, by npHandle. Levels without a
+/// creator are under the empty handle
+#[derive(Default)]
+pub struct DlcUsage {
+    pub creators: BTreeMap<String, Vec<LevelUse>>,
+    /// GUID of the pack → how many levels use it
+    pub guid_levels: BTreeMap<u32, usize>,
+}
+
+impl DlcUsage {
+    /// Record a level whose resources use `guids`, those outside the pack
+    /// are ignored and so is a level that uses none of the pack
+    pub fn add_level(
+        &mut self,
+        pack: &DlcPack,
+        id: i64,
+        name: Option<String>,
+        np_handle: &str,
+        guids: &BTreeSet<u32>,
+    ) {
+        let used: Vec<u32> = guids.intersection(&pack.guids).copied().collect();
+        if used.is_empty() {
+            return;
+        }
+        for guid in &used {
+            *self.guid_levels.entry(*guid).or_default() += 1;
+        }
+        self.creators
+            .entry(np_handle.to_string())
+            .or_default()
+            .push(LevelUse {
+                id,
+                name,
+                guids: used.len(),
+            });
+    }
+
+    pub fn levels(&self) -> usize {
+        self.creators.values().map(Vec::len).sum()
+    }
+
+    /// Creators by how many of their levels need the pack, most first
+    pub fn by_level_count(&self) -> Vec<(&String, &Vec<LevelUse>)> {
+        let mut creators: Vec<_> = self.creators.iter().collect();
+        creators.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then(a.0.cmp(b.0)));
+        creators
+    }
+}
@@ -0,0 +1,268 @@
+// src/dump_gc.rs
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Result, anyhow};
+
+use crate::models::{
+    CREATOR_ICON_HASH_FILE, ImportData, PLANET_HASH_FILE, PLANET_STATE_FILE, PlanetFetchState,
+    REFRESH_BSON_IMPORT_FILE, REFRESH_IMPORT_FILE,
+};
+use crate::resource_parse::{ResrcData, ResrcDescriptor, ResrcMethod};
+use crate::sink::{CAS_OBJECTS_DIR, ZIP_SINK_FILE, is_blob_name};
+
+/// A blob file of a dump
+pub struct Blob {
+    pub path: PathBuf,
+    pub size: u64,
+}
+
+/// A dump folder and the blobs in it that none of its manifests reach
+pub struct DumpGarbage {
+    pub dir: PathBuf,
+    /// every blob in the folder
+    pub blobs: usize,
+    pub unreachable: Vec<Blob>,
+}
+
+impl DumpGarbage {
+    pub fn unreachable_bytes(&self) -> u64 {
+        self.unreachable.iter().map(|blob| blob.size).sum()
+    }
+}
+
+/// What `analyze` found below the folder it was given
+#[derive(Default)]
+pub struct GcReport {
+    pub dumps: Vec<DumpGarbage>,
+    /// folders with blobs but no manifest, nothing in them is known to be garbage
+    pub without_manifest: Vec<PathBuf>,
+    /// dumps written with `--format zip`, a ZIP can't have entries removed in place
+    pub zipped: Vec<PathBuf>,
+}
+
+/// The hashes the manifests of a dump start from and the dependencies they
+/// record, as far as they record them
+#[derive(Default)]
+struct Manifests {
+    roots: HashSet<[u8; 20]>,
+    edges: HashMap<[u8; 20], Vec<[u8; 20]>>,
+}
+
+fn parse_hex(hex: &str) -> Option<[u8; 20]> {
+    hex::decode(hex).ok()?.try_into().ok()
+}
+
+/// Read the manifests in `dir`, `None` if it has none
+fn read_manifests(dir: &Path) -> Result<Option<Manifests>> {
+    let mut manifests = Manifests::default();
+    let mut found = false;
+
+    for name in [REFRESH_IMPORT_FILE, REFRESH_BSON_IMPORT_FILE] {
+        let path = dir.join(name);
+        if !path.exists() {
+            continue;
+        }
+        found = true;
+        let import = ImportData::read(&path)?;
+        let level_hashes = import
+            .levels
+            .iter()
+            .flat_map(|level| [&level.root_resource, &level.icon_hash]);
+        let user_hashes = import
+            .users
+            .iter()
+            .flat_map(|user| [&user.icon_hash, &user.psp_icon_hash, &user.vita_icon_hash]);
+        // converted PNG icons are only referenced through their asset
+        let asset_hashes = import.assets.iter().flat_map(|asset| {
+            [
+                Some(&asset.asset_hash),
+                asset.as_mainline_icon_hash.as_ref(),
+                asset.as_mip_icon_hash.as_ref(),
+                asset.as_mainline_photo_hash.as_ref(),
+            ]
+            .into_iter()
+            .flatten()
+        });
+        manifests.roots.extend(
+            level_hashes
+                .chain(user_hashes)
+                .chain(asset_hashes)
+                .filter_map(|h| parse_hex(h)),
+        );
+        for relation in &import.relations {
+            if let (Some(dependent), Some(dependency)) = (
+                parse_hex(&relation.dependent),
+                parse_hex(&relation.dependency),
+            ) {
+                manifests
+                    .edges
+                    .entry(dependent)
+                    .or_default()
+                    .push(dependency);
+            }
+        }
+    }
+
+    // `planets.json` alone doesn't make a dump: `fetch-level` leaves one
+    // next to a level whose own root no manifest names
+    let state_path = dir.join(PLANET_STATE_FILE);
+    if state_path.exists() {
+        // the closures of fetched sub-levels are complete, everything in them is kept
+        let state = PlanetFetchState::read(&state_path)?;
+        for (planet, sub_levels) in &state.planets {
+            manifests.roots.extend(parse_hex(planet));
+            for (sub_level, closure) in sub_levels {
+                manifests.roots.extend(parse_hex(sub_level));
+                manifests
+                    .roots
+                    .extend(closure.iter().filter_map(|h| parse_hex(h)));
+            }
+        }
+    }
+
+    for name in [PLANET_HASH_FILE, CREATOR_ICON_HASH_FILE] {
+        if let Ok(hex) = fs::read_to_string(dir.join(name)) {
+            found = true;
+            manifests.roots.extend(parse_hex(hex.trim()));
+        }
+    }
+
+    Ok(found.then_some(manifests))
+}
+
+/// The blobs of the dump in `dir`, by hash: loose ones and those of a CAS tree
+fn list_blobs(dir: &Path) -> Result<HashMap<[u8; 20], Blob>> {
+    let mut blobs = HashMap::new();
+    let mut dirs = vec![dir.to_path_buf()];
+    let objects = dir.join(CAS_OBJECTS_DIR);
+    if objects.is_dir() {
+        dirs.push(objects);
+    }
+    while let Some(next) = dirs.pop() {
+        let in_cas = next != dir;
+        for entry in fs::read_dir(&next)? {
+            let entry = entry?;
+            let file_type = entry.file_type()?;
+            if in_cas && file_type.is_dir() {
+                dirs.push(entry.path());
+                continue;
+            }
+            let name = entry.file_name();
+            let Some(hash) = name
+                .to_str()
+                .filter(|n| is_blob_name(n))
+                .and_then(parse_hex)
+            else {
+                continue;
+            };
+            if file_type.is_file() {
+                let size = entry.metadata()?.len();
+                blobs.insert(
+                    hash,
+                    Blob {
+                        path: entry.path(),
+                        size,
+                    },
+                );
+            }
+        }
+    }
+    Ok(blobs)
+}
+
+/// Every blob of the dump reachable from its manifests, following the
+/// recorded relations and the dependency table of each blob
+fn reachable(manifests: &Manifests, blobs: &HashMap<[u8; 20], Blob>) -> HashSet<[u8; 20]> {
+    let mut seen: HashSet<[u8; 20]> = manifests.roots.clone();
+    let mut queue: Vec<[u8; 20]> = seen.iter().copied().collect();
+    while let Some(hash) = queue.pop() {
+        let mut next: Vec<[u8; 20]> = manifests.edges.get(&hash).cloned().unwrap_or_default();
+        if let Some(blob) = blobs.get(&hash)
+            && let Ok(data) = fs::read(&blob.path)
+            && let Ok(ResrcData {
+                method: ResrcMethod::Binary { dependencies, .. },
+                ..
+            }) = ResrcData::new(&data, false)
+        {
+            next.extend(dependencies.iter().filter_map(|dep| match dep.desc {
+                ResrcDescriptor::Sha1(sha1) => Some(sha1),
+                ResrcDescriptor::Guid(_) => None,
+            }));
+        }
+        for dep in next {
+            if seen.insert(dep) {
+                queue.push(dep);
+            }
+        }
+    }
+    seen
+}
+
+/// Look for blobs no level closure needs in every dump folder below `root`,
+/// `root` included. A folder is a dump if it has a manifest, an import or
+/// a planet's hash files, `planets.json` only adds to those. Folders with
+/// blobs but no manifest are left alone, there's nothing to tell what they
+/// need
+pub fn analyze(root: &Path) -> Result<GcReport> {
+    let mut report = GcReport::default();
+    let mut dirs = vec![root.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        for entry in
+            fs::read_dir(&dir).map_err(|e| anyhow!("couldn't read {}: {}", dir.display(), e))?
+        {
+            let entry = entry?;
+            if entry.file_type()?.is_dir() && entry.file_name() != CAS_OBJECTS_DIR {
+                dirs.push(entry.path());
+            }
+        }
+
+        let blobs = list_blobs(&dir)?;
+        let manifests = read_manifests(&dir)?;
+        if dir.join(ZIP_SINK_FILE).exists() {
+            report.zipped.push(dir.clone());
+        }
+        let Some(manifests) = manifests else {
+            if !blobs.is_empty() {
+                report.without_manifest.push(dir);
+            }
+            continue;
+        };
+        if blobs.is_empty() {
+            continue;
+        }
+
+        let keep = reachable(&manifests, &blobs);
+        let count = blobs.len();
+        let mut unreachable: Vec<Blob> = blobs
+            .into_iter()
+            .filter(|(hash, _)| !keep.contains(hash))
+            .map(|(_, blob)| blob)
+            .collect();
+        unreachable.sort_by(|a, b| a.path.cmp(&b.path));
+        report.dumps.push(DumpGarbage {
+            dir,
+            blobs: count,
+            unreachable,
+        });
+    }
+    report.dumps.sort_by(|a, b| a.dir.cmp(&b.dir));
+    report.without_manifest.sort();
+    report.zipped.sort();
+    Ok(report)
+}
+
+/// Delete the unreachable blobs of a dump, returns how many bytes were
+/// freed. Blobs hard linked elsewhere only free their space with their
+/// last link
+pub fn delete(garbage: &DumpGarbage) -> Result<u64> {
+    let mut freed = 0;
+    for blob in &garbage.unreachable {
+        fs::remove_file(&blob.path)
+            .map_err(|e| anyhow!("couldn't delete {}: {}", blob.path.display(), e))?;
+        freed += blob.size;
+    }
+    Ok(freed)
+}
@@ -0,0 +1,259 @@
+// src/duplicates.rs
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+/// see the note on versioning in `models`
+pub const DUPLICATES_SCHEMA_VERSION: u32 = 1;
+
+/// Resources more levels than this use are shared assets or popular
+/// stickers, using them doesn't make a level a copy of another
+pub const COMMON_RESOURCE_LEVELS: usize = 20;
+
+/// Levels with fewer resources of their own than this can't be told apart
+/// from levels that merely use the same few stickers
+pub const MIN_FINGERPRINT: usize = 3;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateLevel {
+    pub id: i64,
+    pub name: String,
+    /// npHandle, empty for story slots
+    pub creator: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchKind {
+    /// the same rootLevel
+    Identical,
+    /// rootLevels that need mostly the same resources
+    Similar,
+}
+
+/// Two rootLevels of a `similar` group and how much of their content is
+/// the same, 0 to 1
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimilarPair {
+    pub a: String,
+    pub b: String,
+    pub similarity: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateGroup {
+    pub kind: MatchKind,
+    pub root_levels: Vec<String>,
+    /// the lowest similarity of the pairs that put the group together, 1
+    /// for `identical`
+    pub similarity: f64,
+    /// empty for `identical`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pairs: Vec<SimilarPair>,
+    /// by ID, so usually the original comes first
+    pub levels: Vec<DuplicateLevel>,
+}
+
+/// The `find-duplicates` report
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DuplicateReport {
+    #[serde(default = "crate::models::first_schema_version")]
+    pub schema_version: u32,
+    pub min_similarity: f64,
+    /// rootLevels whose content was compared, the others only by hash
+    pub compared: usize,
+    pub groups: Vec<DuplicateGroup>,
+}
+
+impl Default for DuplicateReport {
+    fn default() -> Self {
+        Self {
+            schema_version: DUPLICATES_SCHEMA_VERSION,
+            min_similarity: 0.0,
+            compared: 0,
+            groups: Vec::new(),
+        }
+    }
+}
+
+impl DuplicateReport {
+    pub fn write(&self, path: &Path) -> Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+/// two rootLevels and their similarity
+type Pair = ([u8; 20], [u8; 20], f64);
+
+/// Slots by rootLevel, and the resources rootLevels need where known
+#[derive(Default)]
+pub struct Catalog {
+    levels: BTreeMap<[u8; 20], Vec<DuplicateLevel>>,
+    contents: BTreeMap<[u8; 20], BTreeSet<[u8; 20]>>,
+}
+
+impl Catalog {
+    pub fn add_level(&mut self, root: [u8; 20], level: DuplicateLevel) {
+        let levels = self.levels.entry(root).or_default();
+        if !levels.iter().any(|l| l.id == level.id) {
+            levels.push(level);
+        }
+    }
+
+    /// What a rootLevel needs, itself left out since it's what differs
+    /// between a level and its re-saved copy
+    pub fn add_content(&mut self, root: [u8; 20], resources: impl IntoIterator<Item = [u8; 20]>) {
+        let content = self.contents.entry(root).or_default();
+        content.extend(resources);
+        content.remove(&root);
+    }
+
+    pub fn content_roots(&self) -> BTreeSet<[u8; 20]> {
+        self.contents.keys().copied().collect()
+    }
+
+    fn creators(&self, root: &[u8; 20]) -> BTreeSet<&str> {
+        self.levels
+            .get(root)
+            .into_iter()
+            .flatten()
+            .map(|l| l.creator.as_str())
+            .collect()
+    }
+
+    /// Groups of levels by different creators with the same rootLevel, or
+    /// content at least `min_similarity` alike
+    pub fn report(&self, min_similarity: f64) -> DuplicateReport {
+        let mut groups = Vec::new();
+        for (root, levels) in &self.levels {
+            if self.creators(root).len() > 1 {
+                groups.push(DuplicateGroup {
+                    kind: MatchKind::Identical,
+                    root_levels: vec![hex::encode(root)],
+                    similarity: 1.0,
+                    pairs: Vec::new(),
+                    levels: sorted(levels.clone()),
+                });
+            }
+        }
+
+        let pairs = self.similar_pairs(min_similarity);
+        // pairs that share a rootLevel are one group
+        let mut group_of: BTreeMap<[u8; 20], usize> = BTreeMap::new();
+        let mut similar: Vec<(BTreeSet<[u8; 20]>, Vec<Pair>)> = Vec::new();
+        for (a, b, similarity) in pairs {
+            let index = match (group_of.get(&a).copied(), group_of.get(&b).copied()) {
+                (Some(i), Some(j)) if i != j => {
+                    let (keep, merge) = (i.min(j), i.max(j));
+                    let merged = std::mem::take(&mut similar[merge]);
+                    for root in &merged.0 {
+                        group_of.insert(*root, keep);
+                    }
+                    similar[keep].0.extend(merged.0);
+                    similar[keep].1.extend(merged.1);
+                    keep
+                }
+                (Some(i), _) | (_, Some(i)) => i,
+                (None, None) => {
+                    similar.push(Default::default());
+                    similar.len() - 1
+                }
+            };
+            for root in [a, b] {
+                group_of.insert(root, index);
+                similar[index].0.insert(root);
+            }
+            similar[index].1.push((a, b, similarity));
+        }
+        for (roots, pairs) in similar.into_iter().filter(|(roots, _)| !roots.is_empty()) {
+            let levels = roots
+                .iter()
+                .flat_map(|root| self.levels.get(root).into_iter().flatten().cloned())
+                .collect();
+            groups.push(DuplicateGroup {
+                kind: MatchKind::Similar,
+                root_levels: roots.iter().map(hex::encode).collect(),
+                similarity: pairs.iter().map(|p| p.2).fold(1.0, f64::min),
+                pairs: pairs
+                    .into_iter()
+                    .map(|(a, b, similarity)| SimilarPair {
+                        a: hex::encode(a),
+                        b: hex::encode(b),
+                        similarity,
+                    })
+                    .collect(),
+                levels: sorted(levels),
+            });
+        }
+        groups.sort_by_key(|g| (g.kind == MatchKind::Similar, g.levels.first().map(|l| l.id)));
+
+        DuplicateReport {
+            min_similarity,
+            compared: self.contents.len(),
+            groups,
+            ..Default::default()
+        }
+    }
+
+    /// Pairs of rootLevels of different creators whose resources, leaving
+    /// out the common ones, are at least `min_similarity` alike (Jaccard)
+    fn similar_pairs(&self, min_similarity: f64) -> Vec<Pair> {
+        let mut users: BTreeMap<[u8; 20], Vec<[u8; 20]>> = BTreeMap::new();
+        for (root, content) in &self.contents {
+            for hash in content {
+                users.entry(*hash).or_default().push(*root);
+            }
+        }
+        let fingerprints: BTreeMap<[u8; 20], BTreeSet<[u8; 20]>> = self
+            .contents
+            .iter()
+            .map(|(root, content)| {
+                let own = content
+                    .iter()
+                    .filter(|hash| users[*hash].len() <= COMMON_RESOURCE_LEVELS)
+                    .copied()
+                    .collect();
+                (*root, own)
+            })
+            .filter(|(root, own): &(_, BTreeSet<_>)| {
+                own.len() >= MIN_FINGERPRINT && self.levels.contains_key(root)
+            })
+            .collect();
+
+        let mut pairs = Vec::new();
+        for (a, own) in &fingerprints {
+            // only rootLevels that share a resource with `a` can be alike
+            let mut shared: BTreeMap<[u8; 20], usize> = BTreeMap::new();
+            for hash in own {
+                for b in users[hash].iter().filter(|b| *b > a) {
+                    *shared.entry(*b).or_default() += 1;
+                }
+            }
+            for (b, shared) in shared {
+                let Some(other) = fingerprints.get(&b) else {
+                    continue;
+                };
+                let similarity = shared as f64 / (own.len() + other.len() - shared) as f64;
+                let creators: BTreeSet<&str> = self
+                    .creators(a)
+                    .union(&self.creators(&b))
+                    .copied()
+                    .collect();
+                if similarity >= min_similarity && creators.len() > 1 {
+                    pairs.push((*a, b, similarity));
+                }
+            }
+        }
+        pairs
+    }
+}
+
+fn sorted(mut levels: Vec<DuplicateLevel>) -> Vec<DuplicateLevel> {
+    levels.sort_by_key(|l| l.id);
+    levels
+}
@@ -0,0 +1,106 @@
+// src/extract_pool.rs
+
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread;
+
+use anyhow::{Result, anyhow};
+use tokio::sync::{mpsc, oneshot};
+
+/// jobs that may wait for a free thread, per thread. Submitters wait once
+/// the queue is full, so a big level can't pile up reads in memory
+pub const QUEUE_PER_THREAD: usize = 2;
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// ZIP read threads when `extraction_threads` isn't set. They mostly wait on
+/// the disk, and a few reads at once keep it busy without making a spinning
+/// disk seek back and forth between them
+pub const DEFAULT_IO_THREADS: usize = 4;
+
+/// Threads of our own for blocking work, so it doesn't compete with
+/// everything else on tokio's blocking pool. There's one pool for ZIP reads
+/// and one for CPU work (inflating, hashing, parsing), sized separately
+pub struct ExtractPool {
+    jobs: mpsc::Sender<Job>,
+    threads: usize,
+}
+
+impl ExtractPool {
+    pub fn new(name: &str, threads: usize) -> Self {
+        let threads = threads.max(1);
+        let (jobs, rx) = mpsc::channel::<Job>(threads * QUEUE_PER_THREAD);
+        let rx = Arc::new(Mutex::new(rx));
+        for i in 0..threads {
+            let rx = rx.clone();
+            thread::Builder::new()
+                .name(format!("{}-{}", name, i))
+                .spawn(move || {
+                    loop {
+                        // the lock is only held while waiting for the next job
+                        let job = match rx.lock() {
+                            Ok(mut rx) => rx.blocking_recv(),
+                            Err(_) => None,
+                        };
+                        let Some(job) = job else { break };
+                        // a panicking job only fails its own caller
+                        let _ = catch_unwind(AssertUnwindSafe(job));
+                    }
+                })
+                .expect("couldn't start pool thread");
+        }
+        Self { jobs, threads }
+    }
+
+    /// how many threads the pool runs, for work split up outside of it
+    pub fn threads(&self) -> usize {
+        self.threads
+    }
+
+    /// Run `f` on one of the pool's threads, waiting for room in the queue first
+    pub async fn run<T, F>(&self, f: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> T + Send + 'static,
+    {
+        let (tx, rx) = oneshot::channel();
+        self.jobs
+            .send(Box::new(move || {
+                let _ = tx.send(f());
+            }))
+            .await
+            .map_err(|_| anyhow!("thread pool has shut down"))?;
+        rx.await.map_err(|_| anyhow!("pool job panicked"))
+    }
+}
+
+static POOL: OnceLock<ExtractPool> = OnceLock::new();
+static CPU_POOL: OnceLock<ExtractPool> = OnceLock::new();
+
+/// Size the ZIP read pool, call once at startup before any download
+pub fn set_threads(threads: usize) {
+    let _ = POOL.set(ExtractPool::new("extract", threads));
+}
+
+/// The ZIP read pool, `DEFAULT_IO_THREADS` threads unless `set_threads` was called
+pub fn shared() -> &'static ExtractPool {
+    POOL.get_or_init(|| ExtractPool::new("extract", default_threads()))
+}
+
+pub fn default_threads() -> usize {
+    DEFAULT_IO_THREADS
+}
+
+/// Size the CPU pool, call once at startup before any download
+pub fn set_cpu_threads(threads: usize) {
+    let _ = CPU_POOL.set(ExtractPool::new("cpu", threads));
+}
+
+/// The CPU pool, one thread per CPU unless `set_cpu_threads` was called
+pub fn cpu() -> &'static ExtractPool {
+    CPU_POOL.get_or_init(|| ExtractPool::new("cpu", default_cpu_threads()))
+}
+
+pub fn default_cpu_threads() -> usize {
+    thread::available_parallelism().map_or(1, |n| n.get())
+}
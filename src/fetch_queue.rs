@@ -0,0 +1,112 @@
+// src/fetch_queue.rs
+
+//! The order a level's resources are fetched in and how far a breadth
+//! first walk is through its depth, kept apart from the fetching itself.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::ops::Bound;
+use std::path::{Path, PathBuf};
+
+use crate::archive_layout::Location;
+use crate::resource_dl::DownloadOrder;
+
+/// Resources waiting to be fetched, by the ZIP or tar they're in. One
+/// container's batch is fetched before the next, sweeping through them in
+/// name order, so an archive on a hard disk isn't read back and forth
+#[derive(Default)]
+pub struct Batches {
+    by_container: BTreeMap<PathBuf, Batch>,
+    current: Option<PathBuf>,
+    len: usize,
+}
+
+#[derive(Default)]
+struct Batch {
+    zip: bool,
+    hashes: VecDeque<[u8; 20]>,
+}
+
+impl Batches {
+    pub fn push(&mut self, hash: [u8; 20], location: Option<Location>) {
+        // one the archive doesn't have fails when it's fetched, whenever that is
+        let (container, zip) = match location {
+            Some(location) => (
+                location.container().to_path_buf(),
+                matches!(location, Location::Zip { .. }),
+            ),
+            None => (PathBuf::new(), false),
+        };
+        let batch = self.by_container.entry(container).or_default();
+        batch.zip = zip;
+        batch.hashes.push_back(hash);
+        self.len += 1;
+    }
+
+    /// The next resource of the current batch, moving on to the next batch
+    /// when it's done. A ZIP the next batch after that is in is returned too,
+    /// to be opened ahead
+    pub fn pop(&mut self, order: DownloadOrder) -> Option<([u8; 20], Option<PathBuf>)> {
+        let mut open_ahead = None;
+        if self
+            .current
+            .as_ref()
+            .is_none_or(|c| !self.by_container.contains_key(c))
+        {
+            let next = self.after(self.current.as_deref())?.clone();
+            open_ahead = self
+                .after(Some(&next))
+                .filter(|c| self.by_container[*c].zip && **c != next)
+                .cloned();
+            self.current = Some(next);
+        }
+        let current = self.current.as_ref()?;
+        let batch = self.by_container.get_mut(current)?;
+        let hash = match order {
+            DownloadOrder::Depth => batch.hashes.pop_back(),
+            DownloadOrder::Discovered | DownloadOrder::Breadth => batch.hashes.pop_front(),
+        }?;
+        if batch.hashes.is_empty() {
+            self.by_container.remove(current);
+        }
+        self.len -= 1;
+        Some((hash, open_ahead))
+    }
+
+    /// the container after `current` in name order, wrapping around
+    fn after(&self, current: Option<&Path>) -> Option<&PathBuf> {
+        current
+            .and_then(|c| {
+                self.by_container
+                    .range::<Path, _>((Bound::Excluded(c), Bound::Unbounded))
+                    .next()
+            })
+            .or_else(|| self.by_container.iter().next())
+            .map(|(container, _)| container)
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+/// How far the breadth first walk is through the depth it's on
+#[derive(Default)]
+pub struct Frontier {
+    /// resources of the depth, alternates tried in it included
+    pub total: usize,
+    pub done: usize,
+}
+
+impl Frontier {
+    /// Count a finished fetch, the percentage when it reaches the next tenth
+    pub fn advance(&mut self) -> Option<usize> {
+        let before = self.done * 10 / self.total.max(1);
+        self.done += 1;
+        let percent = self.done * 100 / self.total.max(1);
+        (percent / 10 > before || self.done == self.total).then_some(percent.min(100))
+    }
+}
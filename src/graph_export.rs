@@ -0,0 +1,298 @@
+// src/graph_export.rs
+
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Result, anyhow};
+use clap::ValueEnum;
+use rusqlite::{Connection, OptionalExtension, params};
+
+use crate::db::LevelRecord;
+use crate::resource_parse::{ResrcData, ResrcDescriptor, ResrcMethod};
+use crate::scan::classify;
+
+/// What `export-graph` writes
+#[derive(Debug, Default, PartialEq, Clone, Copy, ValueEnum)]
+pub enum GraphFormat {
+    /// a folder of CSVs for `neo4j-admin database import`
+    #[default]
+    Neo4j,
+    /// one SQLite file with node, edge and level tables
+    Sqlite,
+}
+
+/// the CSVs of `GraphFormat::Neo4j` and their header lines
+const NEO4J_FILES: [(&str, &str); 6] = [
+    ("resources.csv", "sha1:ID(Resource),type,:LABEL"),
+    ("guids.csv", "guid:ID(Guid),:LABEL"),
+    ("levels.csv", "slot:ID(Level),creator,:LABEL"),
+    (
+        "depends_on.csv",
+        ":START_ID(Resource),:END_ID(Resource),declared_type:int,:TYPE",
+    ),
+    (
+        "depends_on_guid.csv",
+        ":START_ID(Resource),:END_ID(Guid),declared_type:int,:TYPE",
+    ),
+    (
+        "level_resources.csv",
+        ":START_ID(Level),:END_ID(Resource),:TYPE",
+    ),
+];
+
+const SQLITE_SCHEMA: &str = "
+    CREATE TABLE node (
+        id INTEGER PRIMARY KEY,
+        sha1 BLOB UNIQUE,
+        guid INTEGER UNIQUE,
+        -- what the blob is, NULL for GUIDs and blobs the archive doesn't have
+        type TEXT
+    );
+    CREATE TABLE edge (
+        dependent INTEGER NOT NULL REFERENCES node(id),
+        dependency INTEGER NOT NULL REFERENCES node(id),
+        declared_type INTEGER NOT NULL,
+        PRIMARY KEY (dependent, dependency)
+    ) WITHOUT ROWID;
+    CREATE INDEX edge_dependency ON edge(dependency);
+    CREATE TABLE level (
+        slot_id INTEGER PRIMARY KEY,
+        creator TEXT NOT NULL,
+        root INTEGER NOT NULL REFERENCES node(id),
+        icon INTEGER REFERENCES node(id)
+    );
+";
+
+/// How much of the graph was written
+#[derive(Debug, Default)]
+pub struct GraphStats {
+    pub levels: usize,
+    pub nodes: usize,
+    pub edges: usize,
+}
+
+/// Writes the dependency graph one level at a time. Everything goes into
+/// the SQLite tables as it's read, for `GraphFormat::Neo4j` a staging file
+/// the CSVs are written from at `finish`, so nothing but the current level
+/// is kept in memory. A resource's dependencies are the same in every level,
+/// so each is written once, and a level added twice is one row
+pub struct GraphWriter {
+    conn: Connection,
+    /// the CSV folder and the staging file with `GraphFormat::Neo4j`
+    neo4j: Option<(PathBuf, PathBuf)>,
+}
+
+/// a CSV field, quoted if it needs to be
+fn csv_field(value: &str) -> String {
+    match value.contains([',', '"', '\n']) {
+        true => format!("\"{}\"", value.replace('"', "\"\"")),
+        false => value.to_string(),
+    }
+}
+
+/// the staging file of `GraphFormat::Neo4j` in the CSV folder
+const STAGING_FILE: &str = "graph.staging.sqlite";
+
+impl GraphWriter {
+    /// Start a graph at `output`, a folder for Neo4j CSVs or a SQLite file.
+    /// Files of an earlier export there are replaced
+    pub fn create(output: &Path, format: GraphFormat) -> Result<Self> {
+        let (db_path, neo4j) = match format {
+            GraphFormat::Neo4j => {
+                fs::create_dir_all(output).map_err(|e| {
+                    anyhow!("couldn't create output dir `{}`: {}", output.display(), e)
+                })?;
+                let staging = output.join(STAGING_FILE);
+                (staging.clone(), Some((output.to_path_buf(), staging)))
+            }
+            GraphFormat::Sqlite => (output.to_path_buf(), None),
+        };
+        if db_path.exists() {
+            fs::remove_file(&db_path)
+                .map_err(|e| anyhow!("couldn't replace {}: {}", db_path.display(), e))?;
+        }
+        let conn = Connection::open(&db_path)
+            .map_err(|e| anyhow!("couldn't create {}: {}", db_path.display(), e))?;
+        conn.execute_batch("PRAGMA journal_mode = OFF; PRAGMA synchronous = OFF;")?;
+        conn.execute_batch(SQLITE_SCHEMA)?;
+        Ok(Self { conn, neo4j })
+    }
+
+    /// Add a level and the dependencies of its resources not written yet
+    pub fn add_level(
+        &mut self,
+        level: &LevelRecord,
+        resources: &BTreeMap<[u8; 20], Vec<u8>>,
+    ) -> Result<()> {
+        let tx = self.conn.transaction()?;
+        for (hash, data) in resources {
+            // a node gets its type when its blob is read, its dependencies
+            // were written then
+            let typed: Option<bool> = tx
+                .prepare_cached("SELECT type IS NOT NULL FROM node WHERE sha1 = ?1")?
+                .query_row([&hash[..]], |r| r.get(0))
+                .optional()?;
+            if typed == Some(true) {
+                continue;
+            }
+            let from = sqlite_node(&tx, hash, Some(&classify(data).0))?;
+            let Ok(ResrcData {
+                method: ResrcMethod::Binary { dependencies, .. },
+                ..
+            }) = ResrcData::new(data, false)
+            else {
+                continue;
+            };
+            for dep in dependencies {
+                let to = match dep.desc {
+                    // untyped until a level that has it is read
+                    ResrcDescriptor::Sha1(sha1) => sqlite_node(&tx, &sha1, None)?,
+                    ResrcDescriptor::Guid(guid) => tx
+                        .prepare_cached(
+                            "INSERT INTO node (guid) VALUES (?1)
+                             ON CONFLICT (guid) DO UPDATE SET guid = excluded.guid RETURNING id",
+                        )?
+                        .query_row([guid], |r| r.get(0))?,
+                };
+                tx.prepare_cached(
+                    "INSERT OR IGNORE INTO edge (dependent, dependency, declared_type) VALUES (?1, ?2, ?3)",
+                )?
+                .execute(params![from, to, dep.resrc_type])?;
+            }
+        }
+        let root = sqlite_node(&tx, &level.root_level, None)?;
+        let icon = match &level.icon {
+            Some(icon) => Some(sqlite_node(&tx, icon, None)?),
+            None => None,
+        };
+        tx.execute(
+            "INSERT OR REPLACE INTO level (slot_id, creator, root, icon) VALUES (?1, ?2, ?3, ?4)",
+            params![level.id, level.np_handle, root, icon],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Write the CSVs out for `GraphFormat::Neo4j`, returns what was written
+    pub fn finish(self) -> Result<GraphStats> {
+        let count = |table: &str| -> Result<usize> {
+            let n: i64 =
+                self.conn
+                    .query_row(&format!("SELECT count(*) FROM {}", table), [], |r| r.get(0))?;
+            Ok(n as usize)
+        };
+        let stats = GraphStats {
+            levels: count("level")?,
+            nodes: count("node")?,
+            edges: count("edge")?,
+        };
+        if let Some((dir, staging)) = &self.neo4j {
+            for (name, header) in NEO4J_FILES {
+                let path = dir.join(name);
+                let mut writer = BufWriter::new(
+                    File::create(&path)
+                        .map_err(|e| anyhow!("couldn't create {}: {}", path.display(), e))?,
+                );
+                writeln!(writer, "{}", header)?;
+                write_csv_rows(&self.conn, name, &mut writer)?;
+                writer.flush()?;
+            }
+            drop(self.conn);
+            fs::remove_file(staging)
+                .map_err(|e| anyhow!("couldn't remove {}: {}", staging.display(), e))?;
+        }
+        Ok(stats)
+    }
+}
+
+/// The rows of the CSV `name` of `NEO4J_FILES`, from the tables
+fn write_csv_rows(conn: &Connection, name: &str, out: &mut impl Write) -> Result<()> {
+    let query = match name {
+        "resources.csv" => "SELECT sha1, type FROM node WHERE sha1 IS NOT NULL ORDER BY id",
+        "guids.csv" => "SELECT guid FROM node WHERE guid IS NOT NULL ORDER BY id",
+        "levels.csv" => "SELECT slot_id, creator FROM level ORDER BY slot_id",
+        "depends_on.csv" => {
+            "SELECT a.sha1, b.sha1, e.declared_type FROM edge e
+             JOIN node a ON a.id = e.dependent JOIN node b ON b.id = e.dependency
+             WHERE b.sha1 IS NOT NULL"
+        }
+        "depends_on_guid.csv" => {
+            "SELECT a.sha1, b.guid, e.declared_type FROM edge e
+             JOIN node a ON a.id = e.dependent JOIN node b ON b.id = e.dependency
+             WHERE b.guid IS NOT NULL"
+        }
+        "level_resources.csv" => {
+            "SELECT l.slot_id, n.sha1, 'ROOT_LEVEL' FROM level l JOIN node n ON n.id = l.root
+             UNION ALL
+             SELECT l.slot_id, n.sha1, 'ICON' FROM level l JOIN node n ON n.id = l.icon"
+        }
+        _ => return Err(anyhow!("no query for {}", name)),
+    };
+    let mut stmt = conn.prepare(query)?;
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        match name {
+            "resources.csv" => {
+                let sha1: Vec<u8> = row.get(0)?;
+                let kind: Option<String> = row.get(1)?;
+                writeln!(
+                    out,
+                    "{},{},Resource",
+                    hex::encode(sha1),
+                    kind.unwrap_or_default()
+                )?;
+            }
+            "guids.csv" => writeln!(out, "{},Guid", row.get::<_, u32>(0)?)?,
+            "levels.csv" => {
+                let creator: String = row.get(1)?;
+                writeln!(
+                    out,
+                    "{},{},Level",
+                    row.get::<_, u32>(0)?,
+                    csv_field(&creator)
+                )?;
+            }
+            "depends_on.csv" => {
+                let (from, to): (Vec<u8>, Vec<u8>) = (row.get(0)?, row.get(1)?);
+                let declared_type: u32 = row.get(2)?;
+                writeln!(
+                    out,
+                    "{},{},{},DEPENDS_ON",
+                    hex::encode(from),
+                    hex::encode(to),
+                    declared_type
+                )?;
+            }
+            "depends_on_guid.csv" => {
+                let from: Vec<u8> = row.get(0)?;
+                let (guid, declared_type): (u32, u32) = (row.get(1)?, row.get(2)?);
+                writeln!(
+                    out,
+                    "{},{},{},DEPENDS_ON",
+                    hex::encode(from),
+                    guid,
+                    declared_type
+                )?;
+            }
+            _ => {
+                let (slot, sha1, kind): (u32, Vec<u8>, String) =
+                    (row.get(0)?, row.get(1)?, row.get(2)?);
+                writeln!(out, "{},{},{}", slot, hex::encode(sha1), kind)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// id of the node for `sha1`, added if it's new. A `kind` sets the type of
+/// a node that had none
+fn sqlite_node(conn: &Connection, sha1: &[u8; 20], kind: Option<&str>) -> Result<i64> {
+    Ok(conn
+        .prepare_cached(
+            "INSERT INTO node (sha1, type) VALUES (?1, ?2)
+             ON CONFLICT (sha1) DO UPDATE SET type = coalesce(node.type, excluded.type) RETURNING id",
+        )?
+        .query_row(params![&sha1[..], kind], |r| r.get(0))?)
+}
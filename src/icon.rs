@@ -1,9 +1,13 @@
 use std::{collections::BTreeMap, fs::File, io::{Cursor, Write}, path::Path};
 
-use crate::{gtf_texture::make_dds_header, resource_parse::{ResrcData, ResrcMethod}};
+use crate::{gtf_texture::{make_dds_header, CellGcmEnumForGtf, CellGcmTexture}, resource_parse::{compress_chunks, ResrcData, ResrcDescriptor, ResrcMethod}};
+use crate::messages::Msg;
 
-use image::{imageops::FilterType, ImageReader, DynamicImage, ImageBuffer, ImageFormat, Rgba};
-use anyhow::Result;
+use font8x8::{UnicodeFonts, BASIC_FONTS, GREEK_FONTS, HIRAGANA_FONTS, LATIN_FONTS};
+use image::{imageops::FilterType, ImageReader, DynamicImage, ImageBuffer, ImageFormat, Rgba, RgbaImage};
+use anyhow::{anyhow, Result};
+
+const PLACEHOLDER_ICON: &[u8] = include_bytes!("assets/placeholder_icon.png");
 
 const MAX_WIDTH: u32 = 320;
 const MAX_HEIGHT: u32 = 176;
@@ -36,40 +40,358 @@ fn img_resize_with_padding(img: DynamicImage) -> ImageBuffer<Rgba<u8>, Vec<u8>>
     img
 }
 
-pub fn make_icon(bkp_path: &Path, icon_hash: Option<[u8; 20]>, hashes: &mut BTreeMap<[u8; 20], Vec<u8>>) -> Result<()> {
-    let mut icon_data = None;
-    let mut icon_gcm_info = None;
+/// Where ICON0.PNG can come from, tried in this order until one of them decodes:
+/// an image file the user gave, the slot icon, a texture from the rootLevel
+/// (usually the level badge), the creator's icon, and finally the placeholder icon
+pub struct IconSources<'a> {
+    /// a PNG from `bkp --icon`, for when the archived icon is broken
+    pub image: Option<&'a [u8]>,
+    pub slot_icon: Option<[u8; 20]>,
+    pub root_level: Option<[u8; 20]>,
+    /// the creator icon isn't part of the level, so it's fetched separately
+    pub creator_icon: Option<&'a [u8]>,
+}
+
+/// Text drawn over ICON0.PNG so backups can be told apart on the XMB
+pub struct IconOverlay<'a> {
+    pub title: &'a str,
+    pub creator: &'a str,
+}
+
+const RESRC_TYPE_TEXTURE: u32 = 1;
 
-    if let Some(hash) = icon_hash {
-        if let Some(icon_resrc) = hashes.get(&hash) {
-            let icon_resrc_id = ResrcData::new(icon_resrc, true)?;
-            if let ResrcMethod::Texture { data, gcm_info } = icon_resrc_id.method {
-                icon_data = Some(data);
-                icon_gcm_info = gcm_info;
+/// width and height of the slot icon textures `image_to_texture` makes
+const ICON_TEXTURE_SIZE: u32 = 128;
+
+const GLYPH_SIZE: u32 = 8;
+const OVERLAY_MARGIN: u32 = 6;
+
+fn glyph(c: char) -> [u8; 8] {
+    BASIC_FONTS.get(c)
+        .or_else(|| LATIN_FONTS.get(c))
+        .or_else(|| GREEK_FONTS.get(c))
+        .or_else(|| HIRAGANA_FONTS.get(c))
+        .or_else(|| BASIC_FONTS.get('?'))
+        .unwrap_or([0; 8])
+}
+
+/// Cut `text` to what fits in `max_width` pixels at `scale`, ending with "..." if it was cut
+fn fit_text(text: &str, scale: u32, max_width: u32) -> String {
+    let max_chars = (max_width / (GLYPH_SIZE * scale)) as usize;
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let mut fitted: String = text.chars().take(max_chars.saturating_sub(3)).collect();
+    fitted.push_str("...");
+    fitted
+}
+
+/// Draw `text` with a 1px drop shadow, (x, y) is the top left corner
+fn draw_text(img: &mut ImageBuffer<Rgba<u8>, Vec<u8>>, text: &str, x: u32, y: u32, scale: u32, color: Rgba<u8>) {
+    for (shadow, color) in [(1, Rgba([0, 0, 0, 255])), (0, color)] {
+        for (i, c) in text.chars().enumerate() {
+            let rows = glyph(c);
+            let gx = x + i as u32 * GLYPH_SIZE * scale + shadow;
+            for (row, bits) in rows.iter().enumerate() {
+                for col in 0..GLYPH_SIZE {
+                    // bit 0 is the leftmost pixel
+                    if bits >> col & 1 == 0 {
+                        continue;
+                    }
+                    for dy in 0..scale {
+                        for dx in 0..scale {
+                            let px = gx + col * scale + dx;
+                            let py = y + shadow + row as u32 * scale + dy;
+                            if px < img.width() && py < img.height() {
+                                img.put_pixel(px, py, color);
+                            }
+                        }
+                    }
+                }
             }
         }
     }
+}
 
-    let mut icon_file = File::create(bkp_path.join("ICON0.PNG"))?;
+/// Darken the bottom of the icon and write the title and creator over it
+fn draw_overlay(img: &mut ImageBuffer<Rgba<u8>, Vec<u8>>, overlay: &IconOverlay) {
+    let max_width = img.width() - OVERLAY_MARGIN * 2;
+    // the title gets double size if it's short enough to read that way
+    let title_scale = if overlay.title.chars().count() as u32 * GLYPH_SIZE * 2 <= max_width { 2 } else { 1 };
+    let title = fit_text(overlay.title, title_scale, max_width);
+    let creator = fit_text(overlay.creator, 1, max_width);
 
-    match icon_data {
-        None => Ok(icon_file.write_all(include_bytes!("assets/placeholder_icon.png"))?),
-        Some(mut data) => {
-            if let Some(gcm_info) = icon_gcm_info {
-                let mut dds = Vec::with_capacity(0x80 + data.len());
-                make_dds_header(&mut dds, &gcm_info)?;
-                dds.write_all(&data)?;
+    let band_height = OVERLAY_MARGIN * 3 + GLYPH_SIZE * (title_scale + 1);
+    let band_top = img.height().saturating_sub(band_height);
+    for y in band_top..img.height() {
+        for x in 0..img.width() {
+            let Rgba([r, g, b, a]) = *img.get_pixel(x, y);
+            // fully transparent padding becomes a solid band, the rest is darkened
+            img.put_pixel(x, y, Rgba([r / 3, g / 3, b / 3, a.max(200)]));
+        }
+    }
 
-                data = dds;
-            }
+    let title_y = band_top + OVERLAY_MARGIN;
+    draw_text(img, &title, OVERLAY_MARGIN, title_y, title_scale, Rgba([255, 255, 255, 255]));
+    let creator_y = title_y + GLYPH_SIZE * title_scale + OVERLAY_MARGIN;
+    draw_text(img, &creator, OVERLAY_MARGIN, creator_y, 1, Rgba([200, 200, 200, 255]));
+}
+
+fn decode_icon(icon_resrc: &[u8]) -> Result<Option<DynamicImage>> {
+    let icon_resrc_id = ResrcData::new(icon_resrc, true)?;
+    let ResrcMethod::Texture { mut data, gcm_info } = icon_resrc_id.method else {
+        return Ok(None);
+    };
+
+    if let Some(gcm_info) = gcm_info {
+        let mut dds = Vec::with_capacity(0x80 + data.len());
+        make_dds_header(&mut dds, &gcm_info)?;
+        dds.write_all(&data)?;
+
+        data = dds;
+    }
+
+    let mut img = ImageReader::new(Cursor::new(data));
+    img.set_format(ImageFormat::Dds);
+    Ok(Some(img.decode()?))
+}
+
+/// An image file like a PNG, whatever format its content turns out to be
+pub fn decode_image(data: &[u8]) -> Result<DynamicImage> {
+    Ok(ImageReader::new(Cursor::new(data)).with_guessed_format()?.decode()?)
+}
+
+fn rgb565(p: &[u8; 4]) -> u16 {
+    ((p[0] as u16 >> 3) << 11) | ((p[1] as u16 >> 2) << 5) | (p[2] as u16 >> 3)
+}
+
+fn rgb_of_565(c: u16) -> [i32; 3] {
+    let (r, g, b) = ((c >> 11) as i32, ((c >> 5) & 0x3f) as i32, (c & 0x1f) as i32);
+    [r * 255 / 31, g * 255 / 63, b * 255 / 31]
+}
+
+/// One 4×4 block as DXT5: the alpha and color of each pixel as the nearest
+/// of what the block's darkest and brightest ends give
+fn dxt5_block(pixels: &[[u8; 4]; 16], out: &mut Vec<u8>) {
+    let a0 = pixels.iter().map(|p| p[3]).max().unwrap_or(0);
+    let a1 = pixels.iter().map(|p| p[3]).min().unwrap_or(0);
+    let mut alphas = [a0 as i32, a1 as i32, 0, 0, 0, 0, 0, 0];
+    for (i, alpha) in alphas.iter_mut().enumerate().skip(2) {
+        *alpha = ((8 - i as i32) * a0 as i32 + (i as i32 - 1) * a1 as i32) / 7;
+    }
+    let mut alpha_bits = 0u64;
+    if a0 > a1 {
+        for (i, p) in pixels.iter().enumerate() {
+            let nearest = (0..8).min_by_key(|&k| (alphas[k] - p[3] as i32).abs()).unwrap();
+            alpha_bits |= (nearest as u64) << (3 * i);
+        }
+    }
+    out.extend([a0, a1]);
+    out.extend(&alpha_bits.to_le_bytes()[..6]);
+
+    let mut max = [0u8; 4];
+    let mut min = [255u8; 4];
+    for p in pixels {
+        for c in 0..3 {
+            max[c] = max[c].max(p[c]);
+            min[c] = min[c].min(p[c]);
+        }
+    }
+    let (mut c0, mut c1) = (rgb565(&max), rgb565(&min));
+    if c0 < c1 {
+        std::mem::swap(&mut c0, &mut c1);
+    }
+    let (e0, e1) = (rgb_of_565(c0), rgb_of_565(c1));
+    let palette: [[i32; 3]; 4] = [e0, e1,
+        [0, 1, 2].map(|c| (2 * e0[c] + e1[c]) / 3),
+        [0, 1, 2].map(|c| (e0[c] + 2 * e1[c]) / 3)];
+    let mut color_bits = 0u32;
+    if c0 != c1 {
+        for (i, p) in pixels.iter().enumerate() {
+            let distance = |e: &[i32; 3]| (0..3).map(|c| (e[c] - p[c] as i32).pow(2)).sum::<i32>();
+            let nearest = (0..4).min_by_key(|&k| distance(&palette[k])).unwrap();
+            color_bits |= (nearest as u32) << (2 * i);
+        }
+    }
+    out.extend(c0.to_le_bytes());
+    out.extend(c1.to_le_bytes());
+    out.extend(color_bits.to_le_bytes());
+}
+
+/// A slot icon TEX resource of an image file, scaled to fit a square
+/// texture and padded with transparency, compressed as DXT5 like the
+/// games' own icons
+pub fn image_to_texture(data: &[u8]) -> Result<Vec<u8>> {
+    let img = decode_image(data)?
+        .resize(ICON_TEXTURE_SIZE, ICON_TEXTURE_SIZE, FilterType::Triangle);
+    let mut square = RgbaImage::new(ICON_TEXTURE_SIZE, ICON_TEXTURE_SIZE);
+    let x = (ICON_TEXTURE_SIZE - img.width()) / 2;
+    let y = (ICON_TEXTURE_SIZE - img.height()) / 2;
+    image::imageops::overlay(&mut square, &img.to_rgba8(), x.into(), y.into());
+
+    let gcm = CellGcmTexture {
+        format: CellGcmEnumForGtf::DXT5,
+        mipmap: 1,
+        dimension: 2,
+        cubemap: 0,
+        remap: 0,
+        width: ICON_TEXTURE_SIZE as u16,
+        height: ICON_TEXTURE_SIZE as u16,
+        depth: 1,
+        location: 0,
+        flags: 0,
+        pitch: 0,
+        offset: 0,
+    };
+    let mut dds = Vec::new();
+    make_dds_header(&mut dds, &gcm)?;
+    for by in (0..ICON_TEXTURE_SIZE).step_by(4) {
+        for bx in (0..ICON_TEXTURE_SIZE).step_by(4) {
+            let pixels = std::array::from_fn(|i| square.get_pixel(bx + i as u32 % 4, by + i as u32 / 4).0);
+            dxt5_block(&pixels, &mut dds);
+        }
+    }
+
+    let mut tex = b"TEX ".to_vec();
+    tex.extend(compress_chunks(&dds));
+    // what the backup writes has to be an icon the tool reads back itself
+    decode_icon(&tex)?.ok_or_else(|| anyhow!("the icon texture doesn't decode"))?;
+    Ok(tex)
+}
+
+/// Convert a TEX/GTF icon resource to a full size PNG, the way Refresh stores
+/// icons for its web UI. Returns None if the resource isn't a texture.
+pub fn icon_to_png(icon_resrc: &[u8]) -> Result<Option<Vec<u8>>> {
+    icon_to_png_sized(icon_resrc, None)
+}
+
+/// Like `icon_to_png`, but scaled to fit in a `size`×`size` square, keeping
+/// the aspect ratio. Icons smaller than that are scaled up
+pub fn icon_to_png_sized(icon_resrc: &[u8], size: Option<u32>) -> Result<Option<Vec<u8>>> {
+    let Some(mut img) = decode_icon(icon_resrc)? else { return Ok(None) };
+    if let Some(size) = size {
+        img = img.resize(size, size, FilterType::Triangle);
+    }
+    let mut png = Vec::new();
+    img.write_to(&mut Cursor::new(&mut png), ImageFormat::Png)?;
+    Ok(Some(png))
+}
+
+/// The icon scaled to fit in a `size`×`size` square, for a sprite sheet.
+/// Returns None if the resource isn't a texture
+pub fn icon_thumbnail(icon_resrc: &[u8], size: u32) -> Result<Option<RgbaImage>> {
+    let Some(img) = decode_icon(icon_resrc)? else { return Ok(None) };
+    Ok(Some(img.resize(size, size, FilterType::Triangle).to_rgba8()))
+}
+
+pub struct SpriteSheet {
+    pub png: Vec<u8>,
+    /// top left corner of each thumbnail, in the order they were given
+    pub corners: Vec<(u32, u32)>,
+}
+
+/// Pack thumbnails into one PNG, `columns` cells of `cell`×`cell` per row,
+/// each centered in its cell
+pub fn make_sprite_sheet(thumbnails: &[RgbaImage], cell: u32, columns: u32) -> Result<SpriteSheet> {
+    let columns = columns.max(1);
+    let rows = (thumbnails.len() as u32).div_ceil(columns).max(1);
+    let mut sheet = RgbaImage::new(columns * cell, rows * cell);
+    let mut corners = Vec::with_capacity(thumbnails.len());
+    for (i, thumbnail) in thumbnails.iter().enumerate() {
+        let (column, row) = (i as u32 % columns, i as u32 / columns);
+        let x = column * cell + (cell - thumbnail.width().min(cell)) / 2;
+        let y = row * cell + (cell - thumbnail.height().min(cell)) / 2;
+        image::imageops::overlay(&mut sheet, thumbnail, x.into(), y.into());
+        corners.push((x, y));
+    }
+    let mut png = Vec::new();
+    DynamicImage::ImageRgba8(sheet).write_to(&mut Cursor::new(&mut png), ImageFormat::Png)?;
+    Ok(SpriteSheet { png, corners })
+}
+
+/// texture dependencies of the rootLevel, in dependency table order
+fn root_textures(root: [u8; 20], hashes: &BTreeMap<[u8; 20], Vec<u8>>) -> Vec<&[u8]> {
+    let Some(root_data) = hashes.get(&root) else { return Vec::new() };
+    let Ok(ResrcData { method: ResrcMethod::Binary { dependencies, .. }, .. }) = ResrcData::new(root_data, false) else {
+        return Vec::new();
+    };
+
+    dependencies.iter()
+        .filter(|d| d.resrc_type == RESRC_TYPE_TEXTURE)
+        .filter_map(|d| match d.desc {
+            ResrcDescriptor::Sha1(sha1) => hashes.get(&sha1).map(Vec::as_slice),
+            ResrcDescriptor::Guid(_) => None,
+        })
+        .collect()
+}
 
-            let mut img = ImageReader::new(Cursor::new(data));
-            img.set_format(ImageFormat::Dds);
-            let img = img.decode()?;
-            let img = img_resize_with_padding(img);
+/// Write ICON0.PNG from the first usable of `sources`, returns which one it
+/// was: "slot icon", "level badge", "creator icon" or "placeholder"
+pub fn make_icon(
+    bkp_path: &Path,
+    sources: &IconSources,
+    hashes: &BTreeMap<[u8; 20], Vec<u8>>,
+    overlay: Option<&IconOverlay>,
+) -> Result<&'static str> {
+    let mut candidates: Vec<(&str, &[u8])> = Vec::new();
+    if let Some(data) = sources.slot_icon.and_then(|h| hashes.get(&h)) {
+        candidates.push(("slot icon", data));
+    }
+    if let Some(root) = sources.root_level {
+        candidates.extend(root_textures(root, hashes).into_iter().map(|d| ("level badge", d)));
+    }
+    if let Some(data) = sources.creator_icon {
+        candidates.push(("creator icon", data));
+    }
+
+    let mut icon = None;
+    let mut used = "placeholder";
+    if let Some(data) = sources.image {
+        match decode_image(data) {
+            Ok(img) => {
+                icon = Some(img);
+                used = "icon file";
+                candidates.clear();
+            },
+            Err(e) => eprintln!("{}", Msg::Warning(&Msg::IconFileUndecodable(&e.to_string()))),
+        }
+    }
+    for (source, data) in candidates {
+        match decode_icon(data) {
+            Ok(Some(img)) => {
+                if source != "slot icon" {
+                    eprintln!("{}", Msg::Warning(&Msg::SlotIconUnavailable(source)));
+                }
+                icon = Some(img);
+                used = source;
+                break;
+            },
+            Ok(None) => {},
+            Err(e) => eprintln!("{}", Msg::Warning(&Msg::IconUndecodable { source, error: &e.to_string() })),
+        }
+    }
+
+    let mut icon_file = File::create(bkp_path.join("ICON0.PNG"))?;
+
+    match (icon, overlay) {
+        (None, None) => {
+            eprintln!("{}", Msg::Warning(&Msg::PlaceholderIcon));
+            icon_file.write_all(PLACEHOLDER_ICON)?;
+        },
+        (None, Some(overlay)) => {
+            eprintln!("{}", Msg::Warning(&Msg::PlaceholderIcon));
+            // the placeholder is already ICON0 sized
+            let mut img = image::load_from_memory_with_format(PLACEHOLDER_ICON, ImageFormat::Png)?.into_rgba8();
+            draw_overlay(&mut img, overlay);
+            img.write_to(&mut icon_file, ImageFormat::Png)?;
+        },
+        (Some(img), overlay) => {
+            let mut img = img_resize_with_padding(img);
+            if let Some(overlay) = overlay {
+                draw_overlay(&mut img, overlay);
+            }
             img.write_to(&mut icon_file, ImageFormat::Png)?;
-            
-            Ok(())
         }
     }
-}
\ No newline at end of file
+    Ok(used)
+}
@@ -0,0 +1,65 @@
+// src/ingest.rs
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Result, anyhow};
+use sha1::{Digest, Sha1};
+use walkdir::WalkDir;
+
+use crate::resource_dl::zip_entry_name;
+
+#[derive(Debug, Default)]
+pub struct IngestReport {
+    pub added: usize,
+    /// blobs that were already ingested
+    pub known: usize,
+    /// files named like a SHA1 whose content hashes to something else
+    pub mismatched: Vec<PathBuf>,
+    pub unreadable: usize,
+}
+
+fn sha1_name(path: &Path) -> Option<String> {
+    let name = path.file_name()?.to_str()?.to_ascii_lowercase();
+    (name.len() == 40 && name.bytes().all(|b| b.is_ascii_hexdigit())).then_some(name)
+}
+
+/// Copy every file below `src` into `ingest_dir` under its SHA1, in the
+/// archive's `ab/cd/<sha1>` layout. Files that are named like a SHA1 must
+/// hash to that name, anything else is stored under whatever it hashes to.
+pub fn ingest_dir(src: &Path, ingest_dir: &Path) -> Result<IngestReport> {
+    if !src.is_dir() {
+        return Err(anyhow!("{} is not a folder", src.display()));
+    }
+    let mut report = IngestReport::default();
+    for entry in WalkDir::new(src).into_iter().filter_map(|e| e.ok()) {
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let Ok(data) = fs::read(entry.path()) else {
+            report.unreadable += 1;
+            continue;
+        };
+
+        let hex = hex::encode(Sha1::digest(&data));
+        if sha1_name(entry.path()).is_some_and(|name| name != hex) {
+            report.mismatched.push(entry.into_path());
+            continue;
+        }
+
+        let dst = ingest_dir.join(zip_entry_name(&hex));
+        if dst.exists() {
+            report.known += 1;
+            continue;
+        }
+        let dst_dir = dst
+            .parent()
+            .ok_or_else(|| anyhow!("{} has no parent directory", dst.display()))?;
+        fs::create_dir_all(dst_dir)?;
+        let tmp = dst.with_extension(format!("{}.tmp", std::process::id()));
+        fs::write(&tmp, &data)?;
+        fs::rename(&tmp, &dst)?;
+        report.added += 1;
+    }
+    Ok(report)
+}
@@ -0,0 +1,85 @@
+// src/language.rs
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+/// Language used for backup metadata (SFO strings) and localized level names,
+/// and for the messages of the tool itself
+#[derive(Debug, Default, PartialEq, Clone, Copy, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    #[default]
+    En,
+    Fr,
+    De,
+    Es,
+    It,
+    Pt,
+    Nl,
+    Ja,
+}
+
+impl Language {
+    /// code used by the translation table in the database
+    pub fn get_code(&self) -> &'static str {
+        match self {
+            Self::En => "en",
+            Self::Fr => "fr",
+            Self::De => "de",
+            Self::Es => "es",
+            Self::It => "it",
+            Self::Pt => "pt",
+            Self::Nl => "nl",
+            Self::Ja => "ja",
+        }
+    }
+    pub fn get_level_backup_title(&self) -> &'static str {
+        match self {
+            Self::En => "Dry Archive Level Backup",
+            Self::Fr => "Sauvegarde de niveau Dry Archive",
+            Self::De => "Dry Archive Level-Sicherung",
+            Self::Es => "Copia de nivel de Dry Archive",
+            Self::It => "Backup livello Dry Archive",
+            Self::Pt => "Backup de nível Dry Archive",
+            Self::Nl => "Dry Archive level-back-up",
+            Self::Ja => "Dry Archive レベルバックアップ",
+        }
+    }
+    pub fn get_adventure_backup_title(&self) -> &'static str {
+        match self {
+            Self::En => "Dry Archive Adventure Backup",
+            Self::Fr => "Sauvegarde d'aventure Dry Archive",
+            Self::De => "Dry Archive Abenteuer-Sicherung",
+            Self::Es => "Copia de aventura de Dry Archive",
+            Self::It => "Backup avventura Dry Archive",
+            Self::Pt => "Backup de aventura Dry Archive",
+            Self::Nl => "Dry Archive avontuur-back-up",
+            Self::Ja => "Dry Archive アドベンチャーバックアップ",
+        }
+    }
+    /// Name of a creator's planet backup
+    pub fn get_planet_name(&self, creator: &str) -> String {
+        match self {
+            Self::En => format!("{creator}'s Planet"),
+            Self::Fr => format!("Planète de {creator}"),
+            Self::De => format!("Planet von {creator}"),
+            Self::Es => format!("Planeta de {creator}"),
+            Self::It => format!("Pianeta di {creator}"),
+            Self::Pt => format!("Planeta de {creator}"),
+            Self::Nl => format!("Planeet van {creator}"),
+            Self::Ja => format!("{creator}の惑星"),
+        }
+    }
+    /// "<level> by <creator>" line shown under the title
+    pub fn get_subtitle(&self, name: &str, creator: &str) -> String {
+        match self {
+            Self::En => format!("{name} by {creator}"),
+            Self::Fr => format!("{name} par {creator}"),
+            Self::De => format!("{name} von {creator}"),
+            Self::Es | Self::Pt => format!("{name} por {creator}"),
+            Self::It => format!("{name} di {creator}"),
+            Self::Nl => format!("{name} door {creator}"),
+            Self::Ja => format!("{name}（{creator}）"),
+        }
+    }
+}
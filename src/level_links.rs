@@ -0,0 +1,78 @@
+// src/level_links.rs
+
+use std::collections::BTreeSet;
+
+use byteorder::{BigEndian, ByteOrder};
+
+use crate::resource_parse::{COMPRESSED_INTEGERS, ResrcData, ResrcMethod, binary_payload};
+
+/// `SlotType` of published community levels, what level link gadgets point
+/// at. 1 is a level on the player's own moon, which the archive doesn't have
+pub(crate) const SLOT_TYPE_USER_CREATED_ON_SERVER: u32 = 2;
+
+/// small numbers are everywhere in level data, while community slot IDs start
+/// well above this, so anything below is never taken for a link
+const MIN_SLOT_ID: u32 = 1000;
+
+/// The LEB128 at the start of `data` and its length. Only the shortest
+/// encoding of a value counts, the serializer never writes another
+fn read_leb128(data: &[u8]) -> Option<(u32, usize)> {
+    let mut value: u32 = 0;
+    for (i, &byte) in data.iter().take(5).enumerate() {
+        value |= ((byte & 0x7f) as u32).checked_shl(7 * i as u32)?;
+        if byte & 0x80 == 0 {
+            return (i == 0 || byte != 0).then_some((value, i + 1));
+        }
+    }
+    None
+}
+
+/// `SlotID {type, number}` of a published level at `at`, which the
+/// serializer writes as two LEB128s with compressed integers and as two
+/// big endian u32 without
+fn slot_id_at(data: &[u8], at: usize, compressed: bool) -> Option<u32> {
+    let id = match compressed {
+        true => {
+            let (slot_type, len) = read_leb128(&data[at..])?;
+            if slot_type != SLOT_TYPE_USER_CREATED_ON_SERVER {
+                return None;
+            }
+            read_leb128(&data[at + len..])?.0
+        }
+        false => {
+            let pair = data.get(at..at + 8)?;
+            if BigEndian::read_u32(&pair[..4]) != SLOT_TYPE_USER_CREATED_ON_SERVER {
+                return None;
+            }
+            BigEndian::read_u32(&pair[4..])
+        }
+    };
+    (id >= MIN_SLOT_ID).then_some(id)
+}
+
+/// Slot IDs a level may link to. Level links are `SlotID {type, number}`
+/// pairs in the scripts of link gadgets, which we can't decode, so this is
+/// every whole SlotID of a published level in the level data: the type is
+/// `USER_CREATED_ON_SERVER`, a compressed one starts a value of its own
+/// rather than sitting inside the one before, and the number is a slot ID.
+/// Some of them are noise, check them against the slots in the database.
+pub fn candidate_slot_ids(level: &[u8]) -> BTreeSet<u32> {
+    // LBP1 has no level links
+    match ResrcData::new(level, false) {
+        Ok(ResrcData {
+            method: ResrcMethod::Binary { revision, .. },
+            ..
+        }) if !revision.is_lbp1() => {}
+        _ => return BTreeSet::new(),
+    }
+    let Ok(Some((data, flags))) = binary_payload(level) else {
+        return BTreeSet::new();
+    };
+
+    let compressed = flags & COMPRESSED_INTEGERS != 0;
+    (0..data.len())
+        // the last byte of a compressed value has no continuation bit
+        .filter(|&at| !compressed || at == 0 || data[at - 1] & 0x80 == 0)
+        .filter_map(|at| slot_id_at(&data, at, compressed))
+        .collect()
+}
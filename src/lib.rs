@@ -0,0 +1,55 @@
+//! Everything but the command line: database access, resource downloading
+//! and parsing, and the backup serializers. Split out of the binary so the
+//! integration tests can reach the serializers. Frontends follow the
+//! progress of a download or backup through `progress`.
+
+pub mod adventure;
+pub mod alternates;
+pub mod annotate;
+pub mod archive_layout;
+pub mod archive_verify;
+pub mod budget;
+pub mod bundle;
+pub mod closure_cache;
+pub mod complexity;
+pub mod config;
+pub mod contents;
+pub mod db;
+pub mod db_diff;
+pub mod dlc;
+pub mod dump_gc;
+pub mod duplicates;
+pub mod extract_pool;
+pub mod fetch_queue;
+pub mod graph_export;
+pub mod gtf_texture;
+pub mod icon;
+pub mod ingest;
+pub mod labels;
+pub mod language;
+pub mod level_links;
+pub mod link;
+pub mod lint;
+pub mod manpage;
+pub mod messages;
+pub mod metrics;
+pub mod models;
+pub mod progress;
+pub mod repack;
+pub mod resource_dl;
+pub mod resource_parse;
+pub mod run_lock;
+pub mod save;
+pub mod save_slots;
+pub mod scan;
+pub mod script;
+pub mod self_test;
+pub mod serializers;
+pub mod setup;
+pub mod sink;
+pub mod slot_xml;
+pub mod working_db;
+pub mod xxtea;
+pub mod zip_index;
+
+pub use resource_parse::ResrcDescriptor;
@@ -0,0 +1,44 @@
+// src/link.rs
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Result, anyhow};
+use clap::ValueEnum;
+
+/// How dumped blobs are placed into aggregate folders (a creator's folder,
+/// fileDumpN). Blobs are never modified after download, so sharing them
+/// between folders is safe.
+#[derive(Debug, Default, PartialEq, Clone, Copy, ValueEnum)]
+pub enum LinkMode {
+    /// reflink if the filesystem supports it, then hard link, then copy
+    #[default]
+    Auto,
+    /// copy-on-write clone, fails on filesystems without reflink support
+    Reflink,
+    /// hard link, fails if source and destination are on different volumes
+    Hardlink,
+    /// always make a full copy
+    Copy,
+}
+
+/// Place `src` at `dst` according to `mode`
+pub fn link_or_copy(src: &Path, dst: &Path, mode: LinkMode) -> Result<()> {
+    let result = match mode {
+        LinkMode::Auto => reflink_copy::reflink(src, dst)
+            .or_else(|_| fs::hard_link(src, dst))
+            .or_else(|_| fs::copy(src, dst).map(|_| ())),
+        LinkMode::Reflink => reflink_copy::reflink(src, dst),
+        LinkMode::Hardlink => fs::hard_link(src, dst),
+        LinkMode::Copy => fs::copy(src, dst).map(|_| ()),
+    };
+    result.map_err(|e| {
+        anyhow!(
+            "failed to place {} → {} ({:?}): {}",
+            src.display(),
+            dst.display(),
+            mode,
+            e
+        )
+    })
+}
@@ -0,0 +1,254 @@
+// src/lint.rs
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use crate::db::{GameVersion, LinkedLevel};
+use crate::messages::Msg;
+use crate::resource_parse::{Branch, ResrcData, ResrcDescriptor, ResrcMethod};
+
+const RESRC_TYPE_TEXTURE: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// the backup loads, but something in the level won't work
+    Warning,
+    /// the game will likely refuse to load the backup
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Warning => write!(f, "warning"),
+            Self::Error => write!(f, "error"),
+        }
+    }
+}
+
+pub struct LintIssue {
+    pub severity: Severity,
+    /// short name of the check, like `missing-resource`
+    pub check: &'static str,
+    pub message: String,
+}
+
+#[derive(Default)]
+pub struct LintReport {
+    pub issues: Vec<LintIssue>,
+}
+
+impl LintReport {
+    fn push(&mut self, severity: Severity, check: &'static str, message: String) {
+        self.issues.push(LintIssue {
+            severity,
+            check,
+            message,
+        });
+    }
+
+    pub fn count(&self, severity: Severity) -> usize {
+        self.issues
+            .iter()
+            .filter(|i| i.severity == severity)
+            .count()
+    }
+
+    pub fn print(&self) {
+        if self.issues.is_empty() {
+            println!("{}", Msg::LintClean);
+            return;
+        }
+        println!(
+            "{}",
+            Msg::LintSummary {
+                errors: self.count(Severity::Error),
+                warnings: self.count(Severity::Warning),
+            }
+        );
+        let mut issues: Vec<&LintIssue> = self.issues.iter().collect();
+        issues.sort_by_key(|i| std::cmp::Reverse(i.severity));
+        for issue in issues {
+            println!(
+                "  {:<8} [{}] {}",
+                issue.severity, issue.check, issue.message
+            );
+        }
+    }
+}
+
+fn magic(data: &[u8]) -> Option<[u8; 3]> {
+    data.get(..3).and_then(|m| m.try_into().ok())
+}
+
+fn sha1_dependencies(data: &[u8]) -> Vec<([u8; 20], u32)> {
+    match ResrcData::new(data, false) {
+        Ok(ResrcData {
+            method: ResrcMethod::Binary { dependencies, .. },
+            ..
+        }) => dependencies
+            .into_iter()
+            .filter_map(|d| match d.desc {
+                ResrcDescriptor::Sha1(h) => Some((h, d.resrc_type)),
+                ResrcDescriptor::Guid(_) => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Level resources other than `root`, these are sub-levels the level links to
+pub fn referenced_levels(root: [u8; 20], resources: &BTreeMap<[u8; 20], Vec<u8>>) -> Vec<[u8; 20]> {
+    resources
+        .iter()
+        .filter(|(hash, data)| **hash != root && magic(data) == Some(*b"LVL"))
+        .map(|(hash, _)| *hash)
+        .collect()
+}
+
+fn is_loadable_texture(data: &[u8]) -> bool {
+    if data.starts_with(b"\x89PNG") || data.starts_with(&[0xff, 0xd8]) {
+        return true;
+    }
+    match magic(data) {
+        Some(m) if m == *b"TEX" || m == *b"GTF" => ResrcData::new(data, true).is_ok(),
+        _ => false,
+    }
+}
+
+/// Look for things that stop a level from loading or working once restored as `target`.
+/// `published_levels` are the referenced levels that are published as slots,
+/// `linked_levels` the slots the level seems to have level links to.
+pub fn lint_level(
+    root: [u8; 20],
+    resources: &BTreeMap<[u8; 20], Vec<u8>>,
+    target: GameVersion,
+    published_levels: &BTreeSet<[u8; 20]>,
+    linked_levels: &[LinkedLevel],
+) -> LintReport {
+    let mut report = LintReport::default();
+    if !resources.contains_key(&root) {
+        report.push(
+            Severity::Error,
+            "missing-resource",
+            format!("rootLevel {} wasn't downloaded", hex::encode(root)),
+        );
+        return report;
+    }
+
+    let latest = target.get_latest_revision();
+    let mut missing = BTreeSet::new();
+    let mut broken_textures = BTreeSet::new();
+    // resources on a branch, by branch_id
+    let mut branched: BTreeMap<u16, usize> = BTreeMap::new();
+    for (hash, data) in resources {
+        if let Ok(ResrcData {
+            resrc_type,
+            method: ResrcMethod::Binary { revision, .. },
+        }) = ResrcData::new(data, false)
+        {
+            if revision.head > latest.head {
+                report.push(
+                    Severity::Error,
+                    "revision-too-new",
+                    format!(
+                        "{} {} has revision 0x{:x}, newer than {} supports (0x{:x})",
+                        String::from_utf8_lossy(&resrc_type),
+                        hex::encode(hash),
+                        revision.head,
+                        target.get_short_title(),
+                        latest.head
+                    ),
+                );
+            }
+            if !revision.is_mainline() {
+                *branched.entry(revision.branch_id).or_insert(0) += 1;
+            }
+        }
+
+        for (dep, resrc_type) in sha1_dependencies(data) {
+            match resources.get(&dep) {
+                None => {
+                    if missing.insert(dep) {
+                        report.push(
+                            Severity::Error,
+                            "missing-resource",
+                            format!(
+                                "{} (needed by {}) wasn't downloaded",
+                                hex::encode(dep),
+                                hex::encode(hash)
+                            ),
+                        );
+                    }
+                }
+                Some(dep_data)
+                    if resrc_type == RESRC_TYPE_TEXTURE && !is_loadable_texture(dep_data) =>
+                {
+                    if broken_textures.insert(dep) {
+                        report.push(
+                            Severity::Warning,
+                            "broken-photo",
+                            format!(
+                                "{} is referenced as a photo/texture by {} but isn't a readable image",
+                                hex::encode(dep),
+                                hex::encode(hash)
+                            ),
+                        );
+                    }
+                }
+                Some(_) => {}
+            }
+        }
+    }
+
+    // one finding per branch, a branched level has all its resources on it
+    for (branch_id, count) in branched {
+        match Branch::from_id(branch_id) {
+            Some(branch) if branch.loads_on_ps3() => {}
+            Some(branch) => report.push(
+                Severity::Error,
+                "unloadable-branch",
+                format!(
+                    "{} resources are on the {} branch, which {} on PS3 can't load",
+                    count,
+                    branch.get_name(),
+                    target.get_short_title()
+                ),
+            ),
+            None => report.push(
+                Severity::Warning,
+                "unknown-branch",
+                format!(
+                    "{} resources are on branch 0x{:04x}, which isn't known, {} may not load them",
+                    count,
+                    branch_id,
+                    target.get_short_title()
+                ),
+            ),
+        }
+    }
+
+    for level in referenced_levels(root, resources) {
+        if !published_levels.contains(&level) {
+            report.push(
+                Severity::Warning,
+                "unpublished-sub-level",
+                format!(
+                    "links to level {} which isn't published as a slot, it can't be reached once restored",
+                    hex::encode(level)
+                ),
+            );
+        }
+    }
+    for level in linked_levels {
+        report.push(
+            Severity::Warning,
+            "level-link",
+            format!(
+                "may link to slot {} ({} by {}), it needs a backup of its own for the link to work",
+                level.slot_id, level.name, level.np_handle
+            ),
+        );
+    }
+    report
+}
@@ -1,90 +1,894 @@
 use anyhow::bail;
 use anyhow::{Result, anyhow};
-use clap::{Parser, Subcommand};
-use config::Config;
+use chrono::{DateTime, Utc};
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
+use clap_complete::Shell;
+use config::{CONFIG_FILE, Config};
+use futures_util::{StreamExt, future, stream};
 use hex::encode as hex_encode;
-use hmac::Hmac;
-use icon::make_icon;
-use models::ImportData;
-use rusqlite::Connection;
+use icon::{IconOverlay, IconSources, icon_to_png, make_icon};
+use link::{LinkMode, link_or_copy};
+use lint::Severity;
+use models::{
+    ARCHIVE_VERIFY_FILE, BACKUP_META_FILE, BACKUP_META_SCHEMA_VERSION, BackupMeta, BundleMeta,
+    CREATOR_ICON_HASH_FILE, CreatorDiffEntry, CreatorState, CreatorStatus, CreatorsReport,
+    DUMP_STATUS_FILE, DiffChange, DlcCreator, DlcLevel, DlcUsageReport, DumpStatus, HashUsers,
+    ImportData, JsonOutput, LevelDiff, LevelQueue, LevelStats, OmittedResource, PLANET_HASH_FILE,
+    PLANET_STATE_FILE, PlanetFetchState, QueueState, REFRESH_BSON_IMPORT_FILE, REFRESH_IMPORT_FILE,
+    ReferenceCount, SPRITE_SHEET_SCHEMA_VERSION, SlotModification, SlotUse, SpriteRect,
+    SpriteSheetMap, StatsReport, VerifyState,
+};
+use rusqlite::OptionalExtension;
 use serde_json::to_string_pretty;
 use sha1::Digest;
 use sha1::Sha1;
+use tokio::sync::Mutex as AsyncMutex;
+use tokio::task::spawn_blocking;
 
-pub type HmacSha1 = Hmac<Sha1>;
-use std::collections::HashMap;
-use std::fs::{File, OpenOptions};
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque, btree_map};
+use std::fs::File;
 use std::io::{BufRead, BufReader};
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 // if you’re on sha1 ≥0.9 you can keep `use digest::Digest;`
 use std::{
     fs,
     io::{Write, stdout},
 };
 
-mod config;
-mod db;
-mod gtf_texture;
-mod icon;
-mod labels;
-mod models;
-mod resource_dl;
-mod resource_parse;
-mod serializers;
-mod xxtea;
-
-use crate::resource_dl::{DownloadResult, download_level};
+use archive_dl::{
+    adventure, alternates, annotate, archive_verify, budget, bundle, complexity, config, contents,
+    db, db_diff, dlc, dump_gc, duplicates, extract_pool, graph_export, icon, ingest, labels,
+    language, level_links, link, lint, manpage, messages, metrics, models, progress, repack,
+    resource_dl, resource_parse, run_lock, save, save_slots, scan, script, self_test, serializers,
+    setup, sink, slot_xml, working_db,
+};
+
+use adventure::collect_shared_data;
+use alternates::AlternateTable;
+use complexity::Complexity;
+use contents::BackupContents;
 use db::{
-    GameVersion, LevelType, SlotInfo, fetch_all_assets, fetch_all_levels, fetch_all_relations,
-    fetch_all_users, get_slot_info,
+    CreatorRecord, Db, DbOpenOptions, GUID_DEPENDENCY_PREFIX, GameVersion, LevelRecord, LevelType,
+    LinkedLevel, RelationFilter, SlotInfo, SlotRef, apply_slot_translation, fetch_all_assets,
+    fetch_all_levels, fetch_all_relations, fetch_all_users, fetch_dependency_edges,
+    get_creator_levels, get_creator_record, get_level_record, get_planet_owner, get_slot_info,
+    get_user_icon, get_user_icons, linked_levels, open_db, published_root_levels,
+};
+use db_diff::{CreatorChange, DbDiff, LevelChange};
+use dlc::{DlcPack, DlcUsage};
+use duplicates::{Catalog, DuplicateLevel, MatchKind};
+use graph_export::{GraphFormat, GraphWriter};
+use language::Language;
+use messages::Msg;
+use resource_dl::{
+    DownloadPool, DownloadResult, clamp_parallel, download_level, download_level_with_pool,
+    fetch_resource, zip_location,
 };
-use resource_parse::{ResrcData, ResrcDescriptor, ResrcMethod};
-use serializers::lbp::{make_savearchive, make_slotlist};
-use serializers::ps3::{make_pfd, make_sfo};
+use resource_parse::{ResrcData, ResrcDescriptor, ResrcMethod, ResrcRevision};
+use run_lock::RunLock;
+use save::EntryDamage;
+use save_slots::SaveSlot;
+use script::{ScriptResult, script_error};
+use serializers::lbp::{MAX_ARCHIVE_SIZE, archive_size, make_savearchive, make_slotlist};
+use serializers::ps3::{TargetConsole, make_pfd, make_sfo};
+use setup::{ArchiveProbe, InitAnswers};
+use sink::{DumpFormat, OutputSink, is_blob_name};
+use slot_xml::XmlSlot;
+use working_db::WORKING_DB_FILE;
 
 #[derive(Parser)]
 #[command(version, about, long_about = None)]
 #[command(propagate_version = true)]
+#[command(arg_required_else_help = true)]
 struct Cli {
+    /// Use the settings of this profile from config.yml
+    #[arg(long, global = true)]
+    profile: Option<String>,
+    /// Use exactly this many parallel downloads, overriding config.yml and
+    /// the cap for the archive source (10 remote, CPU count local)
+    #[arg(long, global = true)]
+    max_parallel_downloads: Option<usize>,
+    /// Threads for CPU work like inflating, hashing and parsing resources,
+    /// separate from the parallel downloads (default: CPU count)
+    #[arg(long, global = true)]
+    threads: Option<usize>,
+    /// Stop starting new levels or resources after this long (`90s`, `30m`,
+    /// `2h`), finish what's in flight and exit with code 3
+    #[arg(long, global = true, value_parser = budget::parse_time_limit)]
+    time_limit: Option<Duration>,
+    /// Also fetch the resources in this file, one SHA1 per line, and their
+    /// dependencies into every backup or dump, e.g. photos a level only
+    /// references from outside or replacements for a missing icon
+    #[arg(long, global = true, value_name = "FILE")]
+    seed_hashes: Option<PathBuf>,
+    /// Groups of hashes that are the same resource re-serialized, one group
+    /// per line. A missing resource is swapped for one of its group and the
+    /// resources that use it are rewritten to point at that one
+    #[arg(long, global = true, value_name = "FILE")]
+    alternates: Option<PathBuf>,
+    /// Print the settings in use, after profiles, path resolution and
+    /// defaults, before running the command
+    #[arg(long, global = true)]
+    verbose: bool,
+    /// Cache fetched resources in this folder, e.g. on a RAM disk, overriding
+    /// `cache_directory` in config.yml
+    #[arg(long, global = true, value_name = "DIR")]
+    cache_dir: Option<PathBuf>,
+    /// Don't read or write fetched resources in the cache, like
+    /// `disable_cache` in config.yml
+    #[arg(long, global = true)]
+    no_cache: bool,
+    /// Write the backups' PARAM.SFO for importing on this setup, overriding
+    /// `sfo.target_console` in config.yml
+    #[arg(long, global = true, value_enum)]
+    target_console: Option<TargetConsole>,
+    /// Print the result of informational commands (`stats`,
+    /// `levels-of-hash`, `dlc usage`, `find-duplicates`, `compare-creators`,
+    /// `annotate-resource`) as one JSON document instead of text. The other
+    /// commands refuse it
+    #[arg(long, global = true)]
+    json: bool,
+    /// Language of the messages, overriding `ui_language` in config.yml
+    #[arg(long, global = true, value_enum)]
+    ui_language: Option<Language>,
+    /// Write the archive counters of the run to this file when it's done,
+    /// in the OpenMetrics text format, for a Prometheus textfile collector
+    #[arg(long, global = true, value_name = "FILE")]
+    metrics_file: Option<PathBuf>,
+    /// Write to backup_directory even if another run seems to be, for a
+    /// lock a run on another machine left behind. With `init`, replace a
+    /// config.yml that's already there
+    #[arg(long, global = true)]
+    force: bool,
+    /// Check the backup serializers against known-good output and exit
+    #[arg(long, exclusive = true)]
+    self_test: bool,
     #[command(subcommand)]
-    command: Commands,
+    command: Option<Commands>,
+}
+
+#[derive(Subcommand)]
+enum DbAction {
+    /// Copy the database into a cleaned up working copy with typed columns
+    /// and indexes, which the other commands then use instead
+    Upgrade {
+        /// Write the copy here instead of the cache folder. It's then only
+        /// used if database_path points at it
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum SaveAction {
+    /// Decrypt a save and extract its resources as SHA1-named blobs
+    Decrypt {
+        /// Backup folder with the encrypted chunks (`0`, `1`, …), or an
+        /// unencrypted `.farc`/FAR4 file
+        path: PathBuf,
+        /// Where to write the blobs, defaults to `<backup dir>/save_<name>`
+        #[arg(long)]
+        output: Option<PathBuf>,
+        /// Also put the blobs in the resource cache, so the other commands can use them
+        #[arg(long)]
+        into_cache: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum ArchiveAction {
+    /// Hash every entry of the archive ZIPs against its name. Progress is
+    /// saved after every ZIP, so running it again picks up where it stopped
+    Verify {
+        /// Only verify this slice of the 256 ZIPs, like `3/8`, to split the
+        /// work across machines. Each shard needs its own progress file
+        #[arg(long, value_parser = archive_verify::Shard::parse, conflicts_with = "prefix")]
+        shard: Option<archive_verify::Shard>,
+        /// Only verify the ZIPs of this 1 or 2 hex digit prefix
+        #[arg(long)]
+        prefix: Option<String>,
+        /// Where to keep the progress and findings
+        #[arg(long, default_value = ARCHIVE_VERIFY_FILE)]
+        state: PathBuf,
+        /// Start over, discarding the progress in the state file
+        #[arg(long)]
+        restart: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum DlcAction {
+    /// List the creators and levels that need a DLC pack, to see which DLC
+    /// a community would miss most
+    Usage {
+        /// File with the GUIDs of the pack, one per line
+        pack: PathBuf,
+        /// Refresh import.json or import.bson exported with
+        /// export_guid_dependencies, whose relations are followed instead of
+        /// fetching every level
+        #[arg(long)]
+        relations: Option<PathBuf>,
+        /// Only look at the level IDs in this file, one per line
+        #[arg(long, conflicts_with = "relations")]
+        levels: Option<PathBuf>,
+        /// Fetch this many levels at once, they share maxParallelDownloads
+        #[arg(long, default_value_t = 1)]
+        parallel_levels: usize,
+    },
+}
+
+#[derive(Subcommand)]
+enum FarAction {
+    /// Unpack a FARC, FAR4 or FAR5 archive into SHA1-named blobs
+    Extract {
+        /// A `.farc` or other unencrypted archive file, or a save folder of
+        /// encrypted chunks (`0`, `1`, …)
+        path: PathBuf,
+        /// Where to write the blobs, defaults to `<name>_extracted` next to the archive
+        #[arg(long)]
+        output: Option<PathBuf>,
+        /// How to lay out the blobs
+        #[arg(long, value_enum, default_value_t)]
+        format: DumpFormat,
+    },
+}
+
+#[derive(Subcommand)]
+enum QueueAction {
+    /// Queue levels for a later `queue run`, each level once
+    Add {
+        /// Level IDs from database
+        #[arg(required_unless_present = "levels")]
+        level_ids: Vec<u32>,
+        /// Also queue the level IDs in this file, one per line
+        #[arg(long)]
+        levels: Option<PathBuf>,
+    },
+    /// Show the queued levels and how their last run went
+    List,
+    /// Back up or fetch the pending levels one by one, marking each done or
+    /// failed as it goes, so running it again picks up the rest
+    Run {
+        /// What to do with each level
+        #[arg(long = "as", value_enum, default_value_t)]
+        command: QueueCommand,
+        /// Also run the levels that failed before
+        #[arg(long)]
+        retry_failed: bool,
+        /// With `--as bkp`, replace backups that are already there instead
+        /// of counting them as done
+        #[arg(long)]
+        overwrite: bool,
+    },
+    /// Take levels out of the queue
+    Remove {
+        /// Level IDs to take out
+        #[arg(required_unless_present = "done")]
+        level_ids: Vec<u32>,
+        /// Take out every level that's done
+        #[arg(long)]
+        done: bool,
+    },
+}
+
+/// What `queue run` does with each level
+#[derive(Debug, Default, Clone, Copy, PartialEq, ValueEnum)]
+enum QueueCommand {
+    /// like `bkp`
+    #[default]
+    Bkp,
+    /// like `fetch-level`
+    FetchLevel,
+}
+
+#[derive(Subcommand)]
+enum IconAction {
+    /// Pack the icons of many levels into one sprite sheet PNG, with a JSON
+    /// map of where each one is
+    Sheet {
+        /// Level IDs from database, or `guid:<n>` for story/DLC slots
+        #[arg(required_unless_present = "levels")]
+        level_ids: Vec<SlotRef>,
+        /// Also add the level IDs in this file, one per line
+        #[arg(long)]
+        levels: Option<PathBuf>,
+        /// Width and height of each icon's cell, in pixels
+        #[arg(long, default_value_t = 64, value_parser = clap::value_parser!(u32).range(1..=1024))]
+        size: u32,
+        /// Cells per row, defaults to a roughly square sheet
+        #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+        columns: Option<u32>,
+        /// Where to write the PNG, the map goes next to it as `<name>.json`.
+        /// Defaults to `<backup dir>/icons/sheet.png`
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+    /// Write the icon of a level, or a user's profile icon, as a PNG
+    Export {
+        /// Level ID, `guid:<n>` for story/DLC slots, or the npHandle of a user
+        target: String,
+        /// Take the target as an npHandle even if it looks like a level ID
+        #[arg(long)]
+        user: bool,
+        /// Scale the icon to fit in a square this many pixels wide
+        #[arg(long, value_parser = clap::value_parser!(u32).range(1..=4096))]
+        size: Option<u32>,
+        /// Where to write the PNG, `-` for stdout. Defaults to
+        /// `<backup dir>/icons/<target>.png`
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
 }
 
 #[derive(Subcommand)]
 enum Commands {
     /// Download level and save as level backup
     Bkp {
-        /// Level ID from database
-        level_id: i64,
+        /// Level ID from database, or `guid:<n>` for story/DLC slots
+        level_id: SlotRef,
         /// Force LBP3 backup
         #[arg(short, long)]
         lbp3: bool,
+        /// Language for the backup title and translated level names
+        #[arg(long, value_enum, default_value_t)]
+        language: Language,
+        /// Credit this handle as the creator instead of the one in the database
+        #[arg(long)]
+        publisher_name: Option<String>,
+        /// Write the level name and creator over ICON0.PNG
+        #[arg(long)]
+        icon_overlay: bool,
+        /// Make ICON0.PNG from this PNG instead of the archived icon, for
+        /// levels whose icon is broken or missing
+        #[arg(long)]
+        icon: Option<PathBuf>,
+        /// Also make the `--icon` image the slot's icon in the game, as a
+        /// texture in the backup
+        #[arg(long, requires = "icon")]
+        icon_texture: bool,
+        /// Don't write the backup if the lint finds errors
+        #[arg(long)]
+        strict: bool,
+        /// Leave out textures, meshes and animations that can't be fetched
+        /// instead of failing, they're listed in backup.meta.json
+        #[arg(long, conflicts_with = "strict")]
+        partial_ok: bool,
+        /// Mark the level as copyable in the slot list, even if its creator
+        /// didn't allow copying. Recorded in backup.meta.json
+        #[arg(long)]
+        force_copyable: bool,
+        /// Also back up the levels this one links to, and theirs, this many
+        /// links deep. Links are guessed from the level data, see the lint
+        #[arg(long, default_value_t = 0)]
+        follow_links: usize,
+        /// Write the backup here instead of the backup directory from config.yml
+        #[arg(long)]
+        output_dir: Option<PathBuf>,
+        /// Replace a backup of the level that's already there, by default
+        /// an existing backup is an error
+        #[arg(long, conflicts_with = "skip_existing")]
+        overwrite: bool,
+        /// Leave a backup of the level that's already there alone and move on
+        #[arg(long)]
+        skip_existing: bool,
+        /// Only report how many resources the level needs and their total
+        /// size, without writing a backup
+        #[arg(long, conflicts_with = "follow_links")]
+        dry_run: bool,
+        /// Write the backup for this title ID instead of the main one of
+        /// the game, like `BCUS98245` for a US copy of LBP2
+        #[arg(long, value_parser = parse_title_id)]
+        title_id: Option<String>,
+        /// Write the slot list and save key with this revision instead of
+        /// the one the level's format calls for: `0x3f8`, or
+        /// `0x272:4c44:0x17` with a branch id and branch revision. The
+        /// backup is for the game of the revision
+        #[arg(long, value_parser = parse_revision, conflicts_with = "lbp3")]
+        revision: Option<ResrcRevision>,
     },
 
     Planet {
         /// 40‐hex SHA1 of the planet rootLevel
-        hash: String,
+        #[arg(required_unless_present = "creator")]
+        hash: Option<String>,
+        /// Look up the planets hash of this npHandle instead
+        #[arg(long, conflicts_with = "hash")]
+        creator: Option<String>,
+        /// Which game's planets to pick when the creator has several
+        #[arg(long, value_enum, requires = "creator")]
+        game: Option<GameVersion>,
+        /// How the blobs are laid out in the planet folder
+        #[arg(long, value_enum, default_value_t)]
+        format: DumpFormat,
+    },
+
+    /// Back up a creator's planet as a level backup, named and credited
+    /// after its owner in the database
+    PlanetBkp {
+        /// 40-hex SHA1 of the planet rootLevel, or the npHandle of its owner
+        target: String,
+        /// Which game's planets to back up when the owner has several
+        #[arg(long, value_enum)]
+        game: Option<GameVersion>,
+        /// Language for the backup title and the planet's name
+        #[arg(long, value_enum, default_value_t)]
+        language: Language,
+        /// Replace a backup of the planet that's already there
+        #[arg(long)]
+        overwrite: bool,
     },
 
-    // FetchPlanet {
-    //     /// 40-hex SHA1 of the planet rootLevel
-    //     hash: String,
-    // },
     FetchLevel {
         /// Numeric level ID from database
         level_id: i64,
+        /// Credit this handle as the original publisher in the Refresh export
+        #[arg(long)]
+        publisher_name: Option<String>,
     },
     FetchEntirePlanet {
         /// npHandle of the user whose entire “planet” you want
         np_handle: String,
+        /// How level dumps are placed into the creator folder
+        #[arg(long, value_enum, default_value_t)]
+        link_mode: LinkMode,
+        /// Fetch this many levels at once, they share maxParallelDownloads
+        #[arg(long, default_value_t = 1)]
+        parallel_levels: usize,
+        /// Also write one combined import for all levels into the creator folder
+        #[arg(long, value_enum)]
+        export: Option<ExportFormat>,
+        /// Put each level's blobs into a subfolder for its game (`LBP1`,
+        /// `LBP2`, `LBP3`), with one import per game
+        #[arg(long)]
+        by_game: bool,
+        /// Only fetch the creator's levels published for this game
+        #[arg(long, value_enum)]
+        filter_game: Option<GameVersion>,
+        /// List the creator's levels and ask which of them to fetch
+        #[arg(long)]
+        pick: bool,
+        /// How the blobs are laid out in the creator folder
+        #[arg(long, value_enum, default_value_t)]
+        format: DumpFormat,
     },
 
     #[command(name = "read-from-file")]
-    ReadFromFile,
+    ReadFromFile {
+        /// How creator folders are placed into the fileDump folder
+        #[arg(long, value_enum, default_value_t)]
+        link_mode: LinkMode,
+        /// Continue an earlier run in this fileDump folder, skipping
+        /// creators already marked done or skipped in its status file
+        #[arg(long)]
+        resume: Option<PathBuf>,
+        /// Wait this many milliseconds between creators
+        #[arg(long, default_value_t = 0)]
+        delay_ms: u64,
+        /// Fetch this many levels of a creator at once
+        #[arg(long, default_value_t = 1)]
+        parallel_levels: usize,
+        /// Also write one combined import for every creator into the fileDump folder
+        #[arg(long, value_enum)]
+        export: Option<ExportFormat>,
+        /// Sort every creator's blobs into subfolders by game, see `fetch-entire-planet`
+        #[arg(long)]
+        by_game: bool,
+        /// Only fetch the levels of every creator published for this game
+        #[arg(long, value_enum)]
+        filter_game: Option<GameVersion>,
+        /// How the blobs are laid out in the fileDump folder, creator
+        /// folders are always flat
+        #[arg(long, value_enum, default_value_t)]
+        format: DumpFormat,
+    },
+
+    /// Package a level for re-hosting on a private server: its resources,
+    /// server-neutral metadata, the icon as PNG and a readme, in one zip
+    Bundle {
+        /// Level ID from database, or `guid:<n>` for story/DLC slots
+        level_id: SlotRef,
+        /// Language of the level name and description
+        #[arg(long, value_enum, default_value_t)]
+        language: Language,
+        /// Where to write the zip, defaults to `<backup dir>/bundle_<id>.zip`
+        #[arg(long)]
+        output: Option<PathBuf>,
+        /// Leave out textures, meshes and animations that can't be fetched
+        /// instead of failing, they're listed in level.json
+        #[arg(long)]
+        partial_ok: bool,
+    },
+
+    /// Write slot metadata as LBP server XML (a `<slots>` document)
+    ExportXml {
+        /// Level IDs from database, or `guid:<n>` for story/DLC slots
+        #[arg(required_unless_present = "levels")]
+        level_ids: Vec<SlotRef>,
+        /// Also export the level IDs in this file, one per line
+        #[arg(long)]
+        levels: Option<PathBuf>,
+        /// Language of the level names and descriptions
+        #[arg(long, value_enum, default_value_t)]
+        language: Language,
+        /// Where to write the XML, defaults to stdout
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Export the dependency graph of levels, as CSVs for a Neo4j bulk import
+    /// or as a SQLite edge table
+    ExportGraph {
+        /// Level IDs from database, every level in it if none are given
+        level_ids: Vec<u32>,
+        /// Also export the level IDs in this file, one per line
+        #[arg(long)]
+        levels: Option<PathBuf>,
+        #[arg(long, value_enum, default_value_t)]
+        format: GraphFormat,
+        /// Folder for the CSVs, or the SQLite file
+        #[arg(long)]
+        output: PathBuf,
+        /// Fetch this many levels at once, they share maxParallelDownloads
+        #[arg(long, default_value_t = 1)]
+        parallel_levels: usize,
+    },
+
+    /// List the slots that use a resource, to find the backups a broken blob affects
+    LevelsOfHash {
+        /// 40-hex SHA1 of the resource
+        #[arg(value_parser = parse_sha1)]
+        hash: [u8; 20],
+        /// Refresh import.json or import.bson (e.g. from `--export refresh`)
+        /// whose relations are followed to find the levels that depend on the resource
+        #[arg(long)]
+        relations: Option<PathBuf>,
+    },
+
+    /// Print a resource field by field with offsets, for working on the
+    /// serializers. Slot lists and plans are decoded, other types only
+    /// their header and dependencies
+    AnnotateResource {
+        /// A resource file, or the 40-hex SHA1 of one to fetch from the archive
+        resource: String,
+    },
+
+    /// Find out what needs DLC content
+    Dlc {
+        #[command(subcommand)]
+        action: DlcAction,
+    },
+
+    /// Find levels different creators published with the same or almost
+    /// the same content, such as stolen re-uploads
+    FindDuplicates {
+        /// How alike the resources of two levels have to be, 0 to 1
+        #[arg(long, default_value_t = 0.9)]
+        min_similarity: f64,
+        /// File with one level ID per line to fetch and compare too, besides
+        /// the levels whose resources are known from earlier fetches
+        #[arg(long)]
+        levels: Option<PathBuf>,
+        /// Fetch this many levels of `--levels` at once
+        #[arg(long, default_value_t = 1, requires = "levels")]
+        parallel_levels: usize,
+        /// Also write the groups as JSON to this file
+        #[arg(long)]
+        json_file: Option<PathBuf>,
+    },
+
+    /// Estimate how demanding levels are from what they're made of, to pick
+    /// levels that will run on the target hardware
+    Stats {
+        /// Level IDs from database
+        #[arg(required_unless_present = "levels")]
+        level_ids: Vec<u32>,
+        /// Also estimate the level IDs in this file, one per line
+        #[arg(long)]
+        levels: Option<PathBuf>,
+        /// Fetch this many levels at once, they share maxParallelDownloads
+        #[arg(long, default_value_t = 1)]
+        parallel_levels: usize,
+    },
+
+    /// List the creators and levels a newer database adds, removes or updates
+    /// compared to an older one, e.g. before merging a new dump
+    CompareCreators {
+        /// The older database
+        old: PathBuf,
+        /// The newer database
+        new: PathBuf,
+    },
+
+    /// Classify the blobs in every archive ZIP and write per-type statistics
+    ScanTypes {
+        /// Only look at every Nth entry of each ZIP, 1 scans everything
+        #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u64).range(1..))]
+        sample: u64,
+        /// Where to write the JSON report
+        #[arg(long, default_value = "resource_types.json")]
+        output: PathBuf,
+    },
+
+    /// Rebuild archive ZIPs from their readable entries and loose blobs,
+    /// re-verifying every hash
+    Repack {
+        /// 2 hex digits for one ZIP (`ab` → dryab.zip), or 1 for all 16 ZIPs of a range
+        #[arg(long)]
+        prefix: String,
+        /// Extra folders to take loose blobs (files named by their SHA1) from,
+        /// the resource cache and ingested blobs are always used
+        #[arg(long = "from")]
+        from: Vec<PathBuf>,
+        /// Archive root to write the rebuilt ZIPs into, keeping the archive layout
+        #[arg(long, default_value = "repacked")]
+        output: PathBuf,
+    },
+
+    /// Check the archive ZIPs themselves
+    Archive {
+        #[command(subcommand)]
+        action: ArchiveAction,
+    },
+
+    /// Add recovered blobs to the resources the other commands can use
+    Ingest {
+        /// Folders to take blobs from, every file in them (and their subfolders) is added
+        #[arg(required = true)]
+        dirs: Vec<PathBuf>,
+    },
+
+    /// Find the blobs of dump folders no level needs anymore, leftovers of
+    /// failed or earlier runs, and optionally delete them
+    Gc {
+        /// Folder to look through, every dump below it is checked (default: backup directory)
+        dir: Option<PathBuf>,
+        /// Delete the unreachable blobs instead of only listing them
+        #[arg(long)]
+        delete: bool,
+    },
+
+    /// Maintain the local copy of the database
+    Db {
+        #[command(subcommand)]
+        action: DbAction,
+    },
+
+    /// Work with FAR archives: game caches, `.farc` files and saves
+    Far {
+        #[command(subcommand)]
+        action: FarAction,
+    },
+
+    /// Work with a player's own save or profile backup
+    Save {
+        #[command(subcommand)]
+        action: SaveAction,
+    },
+
+    /// Convert level and user icons to PNG
+    Icon {
+        #[command(subcommand)]
+        action: IconAction,
+    },
+
+    /// Back up the levels of your own save or profile backup, moon levels
+    /// included, named after the slots the save keeps for them
+    RebuildFromSave {
+        /// Backup folder with the encrypted chunks (`0`, `1`, …), or an
+        /// unencrypted `.farc`/FAR4 file
+        path: PathBuf,
+        /// Force LBP3 backups
+        #[arg(short, long)]
+        lbp3: bool,
+        /// Language for the backup titles
+        #[arg(long, value_enum, default_value_t)]
+        language: Language,
+        /// Credit this handle as the creator instead of the one in the save
+        #[arg(long)]
+        publisher_name: Option<String>,
+        /// Write the level name and creator over ICON0.PNG
+        #[arg(long)]
+        icon_overlay: bool,
+        /// Don't write a backup if the lint finds errors
+        #[arg(long)]
+        strict: bool,
+        /// Leave out textures, meshes and animations that can't be found in
+        /// the save or the archive instead of failing
+        #[arg(long, conflicts_with = "strict")]
+        partial_ok: bool,
+        /// Also back up levels the save has no slot for, under a placeholder name
+        #[arg(long)]
+        include_unnamed: bool,
+        /// Write the backups here instead of the backup directory from config.yml
+        #[arg(long)]
+        output_dir: Option<PathBuf>,
+        /// Replace backups that are already there
+        #[arg(long, conflicts_with = "skip_existing")]
+        overwrite: bool,
+        /// Leave backups that are already there alone
+        #[arg(long)]
+        skip_existing: bool,
+        /// Only list the levels found and their size, without writing backups
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Write PARAM.SFO and PARAM.PFD of a backup again from its slot list
+    /// and the current config.yml, leaving the save archive alone
+    RefreshParams {
+        /// The backup folder, like `BCES01663LEVEL00001234`
+        backup_dir: PathBuf,
+        /// Language for the backup title. Defaults to the one the backup
+        /// was made with, as `backup.meta.json` has it
+        #[arg(long, value_enum)]
+        language: Option<Language>,
+    },
+
+    /// Run a Rhai script that searches, fetches, backs up and exports
+    /// levels, for batch jobs that don't fit one command
+    RunScript {
+        /// The script file
+        script: PathBuf,
+        /// Passed to the script as `ARGS`
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+
+    /// Collect level IDs over time and back them up in one batch later
+    Queue {
+        #[command(subcommand)]
+        action: QueueAction,
+    },
+
+    /// Extract profile icons of users into `<backup dir>/user_icons`
+    FetchUserIcons {
+        /// File with one level ID per line, only the creators of these levels
+        /// are fetched. Without it, every user in the database is fetched
+        #[arg(long)]
+        levels: Option<PathBuf>,
+    },
+
+    /// Set up config.yml by answering a few questions, checking the
+    /// database and archive as you go
+    Init {
+        /// Database file, asked for if not given
+        #[arg(long)]
+        database_path: Option<PathBuf>,
+        /// Archive folder, ZIP or tar, asked for if not given
+        #[arg(long)]
+        archive_path: Option<PathBuf>,
+        /// Where backups go, asked for if not given
+        #[arg(long)]
+        backup_directory: Option<PathBuf>,
+        /// Don't ask anything, take the given paths or the defaults and
+        /// write the config even if something looks wrong
+        #[arg(long)]
+        yes: bool,
+    },
+
+    /// Print a shell completion script
+    Completions { shell: Shell },
+
+    /// Print the man page, or write the pages of every command to a folder
+    Manpage {
+        /// Write `archive_dl.1` and a page for every subcommand, like
+        /// `archive_dl-bkp.1`, into this folder instead of printing the
+        /// main page
+        #[arg(long, value_name = "DIR")]
+        output_dir: Option<PathBuf>,
+    },
+}
+
+impl Commands {
+    /// Whether the command writes into backup_directory, so one run at a
+    /// time gets to, see `RunLock`
+    fn writes_backups(&self) -> bool {
+        matches!(
+            self,
+            Commands::Bkp { .. }
+                | Commands::Planet { .. }
+                | Commands::PlanetBkp { .. }
+                | Commands::FetchLevel { .. }
+                | Commands::FetchEntirePlanet { .. }
+                | Commands::ReadFromFile { .. }
+                | Commands::Bundle { .. }
+                | Commands::Gc { .. }
+                | Commands::Save { .. }
+                | Commands::Icon { .. }
+                | Commands::RebuildFromSave { .. }
+                | Commands::RefreshParams { .. }
+                | Commands::RunScript { .. }
+                | Commands::Queue {
+                    action: QueueAction::Run { .. }
+                }
+                | Commands::FetchUserIcons { .. }
+        )
+    }
+
+    /// Whether the command writes a PARAM.SFO, the only ones the `sfo`
+    /// settings are checked for
+    fn writes_sfo(&self) -> bool {
+        matches!(
+            self,
+            Commands::Bkp { .. }
+                | Commands::PlanetBkp { .. }
+                | Commands::RebuildFromSave { .. }
+                | Commands::RefreshParams { .. }
+                | Commands::RunScript { .. }
+                | Commands::Queue {
+                    action: QueueAction::Run {
+                        command: QueueCommand::Bkp,
+                        ..
+                    }
+                }
+        )
+    }
+
+    /// Whether the command prints its result as a `JsonOutput` with `--json`
+    fn prints_json(&self) -> bool {
+        matches!(
+            self,
+            Commands::Stats { .. }
+                | Commands::LevelsOfHash { .. }
+                | Commands::Dlc { .. }
+                | Commands::FindDuplicates { .. }
+                | Commands::CompareCreators { .. }
+                | Commands::AnnotateResource { .. }
+        )
+    }
+
+    /// Whether the command writes backups whose slot points at the rewritten
+    /// root. Swapping in alternates changes hashes, a dump or export would
+    /// name blobs that aren't there
+    fn takes_alternates(&self) -> bool {
+        matches!(
+            self,
+            Commands::Bkp { .. }
+                | Commands::PlanetBkp { .. }
+                | Commands::Bundle { .. }
+                | Commands::RebuildFromSave { .. }
+                | Commands::Queue {
+                    action: QueueAction::Run {
+                        command: QueueCommand::Bkp,
+                        ..
+                    }
+                }
+        )
+    }
+}
+
+/// Fetch `hash` and what it needs into the `resources` of a planet backup,
+/// returns its hash there
+async fn fetch_planet_extra(
+    hash: [u8; 20],
+    config: &Config,
+    resources: &mut BTreeMap<[u8; 20], Vec<u8>>,
+) -> Result<[u8; 20]> {
+    let download = download_level(
+        hash,
+        None,
+        config.archive_path.to_string_lossy().into_owned(),
+        config.max_parallel_downloads,
+        false,
+        progress::silent(),
+    )
+    .await?;
+    let hash = download.hash_of(hash);
+    resources.extend(download.resources);
+    Ok(hash)
 }
 
-async fn dl_as_planet(hash: &str, config: &Config) -> Result<()> {
+/// Back up the planet with rootLevel `hash` as a level, named after and
+/// credited to the user it belongs to, with their icon
+async fn dl_as_planet(
+    hash: &str,
+    language: Language,
+    overwrite: bool,
+    config: &Config,
+) -> Result<()> {
     // 1) parse hex → [u8;20]
     let raw = hex::decode(hash).map_err(|e| anyhow!("invalid hex for hash: {}", e))?;
     if raw.len() != 20 {
@@ -93,27 +897,67 @@ async fn dl_as_planet(hash: &str, config: &Config) -> Result<()> {
     let mut root_hash = [0u8; 20];
     root_hash.copy_from_slice(&raw);
 
-    // 2) grab all resources
-    let DownloadResult {
-        resources,
-        success_count,
-        error_count,
-    } = download_level(
+    // 2) whose planet it is
+    let db = Db::open(&config.database_path)?;
+    let owner = db
+        .call(move |conn| get_planet_owner(conn, root_hash))
+        .await?
+        .ok_or_else(|| anyhow!("no user in the database has the planets {}", hash))?;
+    println!("{}", Msg::PlanetsOf(&owner));
+
+    // 2b) the folder is named after the game, which the rootLevel alone
+    //     tells, so a backup that's there is refused before the rest is fetched
+    let planet_folder = |gameversion: GameVersion| {
+        // e.g. Backups/BCES01663PLANET3622E8...
+        let bkp_name = format!("{}PLANET{}", gameversion.get_titleid(), hash.to_uppercase());
+        let bkp_path = config.backup_directory.join(&bkp_name);
+        (bkp_name, bkp_path)
+    };
+    let refuse_existing = |bkp_path: &Path| match bkp_path.exists() && !overwrite {
+        true => Err(anyhow!(
+            "{} already exists, pass --overwrite to replace it",
+            bkp_path.display()
+        )),
+        false => Ok(()),
+    };
+    let archive = config.archive_path.to_string_lossy().into_owned();
+    if let Ok(root) = resource_dl::fetch_resource(root_hash, archive).await
+        && let Ok(ResrcData {
+            method: ResrcMethod::Binary { revision, .. },
+            ..
+        }) = ResrcData::new(&root, false)
+    {
+        refuse_existing(&planet_folder(revision.get_gameversion()).1)?;
+    }
+
+    // 3) grab all resources
+    let download = download_level(
         root_hash,
         /* icon_sha1 = */ None,
         config.archive_path.to_string_lossy().into_owned(),
         config.max_parallel_downloads,
+        false,
+        progress::silent(),
     )
     .await?;
+    let root_hash = download.hash_of(root_hash);
+    let DownloadResult {
+        mut resources,
+        success_count,
+        error_count,
+        ..
+    } = download;
 
     println!(
-        "Done fetching {} resources ({}/{})",
-        root_hash.iter().count(),
-        success_count,
-        error_count
+        "{}",
+        Msg::PlanetFetched {
+            resources: resources.len(),
+            fetched: success_count,
+            failed: error_count,
+        }
     );
 
-    // 3) inspect root to discover revision & game version
+    // 4) inspect root to discover revision & game version
     let root_data = resources
         .get(&root_hash)
         .ok_or_else(|| anyhow!("rootLevel missing from archive"))?;
@@ -124,20 +968,75 @@ async fn dl_as_planet(hash: &str, config: &Config) -> Result<()> {
     };
     let gameversion = revision.get_gameversion();
 
-    // 4) choose backup folder name
-    let hash_up = hash.to_uppercase();
-    // e.g. Backups/BCES01663PLANET3622E8...
-    let bkp_name = format!("{}PLANET{}", gameversion.get_titleid(), hash_up);
-    let bkp_path = config.backup_directory.join(&bkp_name);
-    fs::create_dir_all(&bkp_path)?;
+    // 5) the pod and earth decorations the owner's user record names come
+    //    along with what they need. The slot points at the decorations so
+    //    the planet isn't restored as a bare default one
+    let owner_handle = owner.clone();
+    let extras = db
+        .call(move |conn| db::get_planet_extras(conn, &owner_handle))
+        .await?;
+    let mut decorations = None;
+    if let Some(hash) = extras.decorations {
+        match fetch_planet_extra(hash, config, &mut resources).await {
+            Ok(fetched) => decorations = Some(fetched),
+            Err(e) => eprintln!(
+                "{}",
+                Msg::Warning(&Msg::DecorationsFailed {
+                    hash: &hex_encode(hash),
+                    error: &e.to_string(),
+                })
+            ),
+        }
+    }
+    match decorations {
+        Some(hash) if revision.get_version() > 0x333 => {
+            println!("{}", Msg::Decorations(&hex_encode(hash)))
+        }
+        Some(_) => println!(
+            "{}",
+            Msg::DecorationsUnsupported(gameversion.get_short_title())
+        ),
+        None => println!("{}", Msg::NoDecorations),
+    }
+    if let Some(hash) = extras.pod {
+        match fetch_planet_extra(hash, config, &mut resources).await {
+            Ok(pod) => println!("{}", Msg::Pod(&hex_encode(pod))),
+            Err(e) => eprintln!(
+                "{}",
+                Msg::Warning(&Msg::PodFailed {
+                    hash: &hex_encode(hash),
+                    error: &e.to_string(),
+                })
+            ),
+        }
+    }
+
+    // 6) choose backup folder name
+    //    checked again, the rootLevel may not have been readable on its own
+    let (bkp_name, bkp_path) = planet_folder(gameversion);
+    refuse_existing(&bkp_path)?;
+
+    // 7) the owner's icon is the planet's
+    let icon = fetch_creator_icon(&owner, &db, config).await.map(|icon| {
+        let hash: [u8; 20] = Sha1::digest(&icon).into();
+        resources.insert(hash, icon);
+        hash
+    });
+    check_backup_size(&resources, config)?;
+    // written beside an old backup and swapped in when done, a failure
+    // leaves the old one alone
+    let staged = StagedBackup::create(&bkp_path)?;
+    let out = staged.path();
 
-    // 5) build a dummy SlotInfo for a planet
+    // 8) the planet is a level of its own, so every game can load it
     let slot_info = SlotInfo {
-        name: format!("Planet {}", hash_up),
+        id: 0,
+        guid: None,
+        name: language.get_planet_name(&owner),
         description: String::new(),
-        np_handle: String::new(),
+        np_handle: owner,
         root_level: root_hash,
-        icon: ResrcDescriptor::Guid(0), // no icon
+        icon: icon.map_or(ResrcDescriptor::Guid(0), ResrcDescriptor::Sha1),
         game: gameversion,
         initially_locked: false,
         is_sub_level: false,
@@ -147,405 +1046,2494 @@ async fn dl_as_planet(hash: &str, config: &Config) -> Result<()> {
         leveltype: LevelType::Cooperative,
         min_players: None,
         max_players: None,
-        is_adventure_planet: true,
+        enforce_min_max_players: false,
+        same_screen_game: false,
+        is_adventure_planet: false,
+        planet_decorations: decorations,
+        adventure_shared: Vec::new(),
     };
 
-    // 6) slotlist
-    let slt = make_slotlist(&revision, &slot_info)?;
+    // 9) slotlist
+    let slt = make_slotlist(&revision, &slot_info, &config.text_limits)?;
     let slt_hash: [u8; 20] = {
         let mut h = Sha1::new();
         h.update(&slt);
         h.finalize().into()
     };
 
-    // 7) write ICON0.PNG (none) and archive chunks
+    // 10) write ICON0.PNG and archive chunks
     let mut all_resources = resources;
     all_resources.insert(slt_hash, slt.clone());
-    make_icon(&bkp_path, None, &mut all_resources)?;
-    make_savearchive(&revision, slt_hash, all_resources, &bkp_path)?;
+    let icon_sources = IconSources {
+        image: None,
+        slot_icon: icon,
+        root_level: Some(root_hash),
+        creator_icon: None,
+    };
+    make_icon(out, &icon_sources, &all_resources, None)?;
+    make_savearchive(&revision, slt_hash, all_resources, out, &progress::Silent)?;
 
-    // 8) PARAM.SFO + PARAM.PFD
-    let sfo = make_sfo(&slot_info, &bkp_name, &bkp_path, &gameversion)?;
+    // 11) PARAM.SFO + PARAM.PFD
+    let sfo = make_sfo(
+        &slot_info,
+        &bkp_name,
+        out,
+        &gameversion,
+        language,
+        &config.sfo,
+        &config.text_limits,
+    )?;
     let pfd_version = if gameversion == GameVersion::Lbp3 {
         4
     } else {
         3
     };
-    make_pfd(pfd_version, sfo, &bkp_path)?;
+    make_pfd(pfd_version, sfo, out)?;
+    staged.commit()?;
 
-    println!("Backup written to {}", bkp_path.display());
+    println!("{}", Msg::BackupWritten(&bkp_path));
     Ok(())
 }
 
-async fn dl_as_backup(level_id: i64, config: Config, force_lbp3: bool) -> Result<()> {
-    let slot_info = get_slot_info(level_id, &config.database_path)?;
-
-    println!("Level found!");
-    println!("  Name:    {}", &slot_info.name);
-    println!("  Creator: {}", &slot_info.np_handle);
-    println!("  Game:    {}", slot_info.game.get_short_title());
-
-    // clamp parallelism
-    let mut max_parallel = config.max_parallel_downloads;
-    if max_parallel > 10 {
-        eprintln!("WARNING: max_parallel_downloads is too high, reverting to 10");
-        max_parallel = 10;
-    } else if max_parallel == 0 {
-        return Err(anyhow!("max_parallel_downloads cannot be zero"));
-    }
-
-    print!("Gathering resources from local archive…");
-    stdout().flush()?;
-
-    // extract icon hash if present
-    let icon_sha1 = match slot_info.icon {
-        ResrcDescriptor::Sha1(h) => Some(h),
-        _ => None,
+/// Fetch a creator's profile icon blob, used when a level has no icon of its own.
+/// Failures only warn, since there's always the placeholder icon.
+async fn fetch_creator_icon(np_handle: &str, db: &Db, config: &Config) -> Option<Vec<u8>> {
+    let np_handle = np_handle.to_string();
+    let icon_hash = match db.call(move |conn| get_user_icon(&np_handle, conn)).await {
+        Ok(Some(h)) => h,
+        Ok(None) => return None,
+        Err(e) => {
+            eprintln!(
+                "{}",
+                Msg::Warning(&Msg::CreatorIconLookupFailed(&e.to_string()))
+            );
+            return None;
+        }
     };
 
-    // call your local-archive-backed downloader
-    let DownloadResult {
-        resources: mut resources,
-        success_count: dl_count,
-        error_count: fail_count,
-    } = download_level(
-        slot_info.root_level,
-        icon_sha1,
-        config.archive_path.to_string_lossy().into_owned(), // your local archive root
-        max_parallel,
+    match download_level(
+        icon_hash,
+        None,
+        config.archive_path.to_string_lossy().into_owned(),
+        1,
+        false,
+        progress::silent(),
     )
-    .await?;
+    .await
+    {
+        // the icon has another hash if an alternate was swapped in
+        Ok(mut result) => {
+            let hash = result.hash_of(icon_hash);
+            result.resources.remove(&hash)
+        }
+        Err(e) => {
+            eprintln!(
+                "{}",
+                Msg::Warning(&Msg::CreatorIconFailed {
+                    hash: &hex_encode(icon_hash),
+                    error: &e.to_string(),
+                })
+            );
+            None
+        }
+    }
+}
 
-    println!("\nDone!  {dl_count} fetched, {fail_count} missing.");
+/// Per-invocation settings for `bkp`
+#[derive(Clone)]
+struct BackupOptions {
+    force_lbp3: bool,
+    language: Language,
+    publisher_name: Option<String>,
+    icon_overlay: bool,
+    /// `--icon`, the image ICON0.PNG is made from
+    icon: Option<PathBuf>,
+    /// `--icon-texture`, the image is the slot icon too
+    icon_texture: bool,
+    strict: bool,
+    partial_ok: bool,
+    /// `--force-copyable`, the slot is shareable whatever the creator set
+    force_copyable: bool,
+    output_dir: Option<PathBuf>,
+    existing: ExistingBackup,
+    dry_run: bool,
+    /// `--title-id`, the backup folder's title ID instead of the game's
+    title_id: Option<String>,
+    /// `--revision`, written instead of the format's revision
+    revision: Option<ResrcRevision>,
+}
 
-    use crate::resource_parse::{ResrcData, ResrcMethod};
-    use std::fs::OpenOptions;
-    let mut dbg = OpenOptions::new()
-        .create(true)
-        .write(true)
-        .truncate(true)
-        .open("output.txt")?;
-    writeln!(dbg, "parent_sha1 <- dependency_sha1")?;
-    for (parent_sha, blob) in &resources {
-        if let Ok(res) = ResrcData::new(blob, false) {
-            if let ResrcMethod::Binary { dependencies, .. } = res.method {
-                for dep in dependencies {
-                    if let ResrcDescriptor::Sha1(child_sha) = dep.desc {
-                        writeln!(
-                            dbg,
-                            "{} <- {}",
-                            hex_encode(parent_sha),
-                            hex_encode(&child_sha)
-                        )?;
-                    }
-                }
+/// What `bkp` does when the backup folder is already there
+#[derive(Clone, Copy, PartialEq)]
+enum ExistingBackup {
+    Fail,
+    Overwrite,
+    Skip,
+}
+
+/// What `bkp --dry-run` prints: the size of the level's closure, from the
+/// closure cache when an earlier run recorded it, otherwise walking only
+/// the dependency tables
+async fn dry_run_backup(
+    slot_info: &SlotInfo,
+    icon_sha1: Option<[u8; 20]>,
+    config: &Config,
+) -> Result<()> {
+    let start = Instant::now();
+    let (closure, missing, from_cache) =
+        match resource_dl::cached_closure(slot_info.root_level, icon_sha1)? {
+            Some(closure) => (closure, 0, true),
+            None => {
+                let (closure, missing) = resource_dl::closure_sizes(
+                    slot_info.root_level,
+                    icon_sha1,
+                    config.archive_path.to_string_lossy().into_owned(),
+                    config.max_parallel_downloads,
+                )
+                .await?;
+                (closure, missing, false)
             }
+        };
+    let total: u64 = closure.iter().map(|(_, size)| size).sum();
+    println!(
+        "{}",
+        Msg::DryRun {
+            resources: closure.len(),
+            size: &contents::format_size(total),
+            from_cache,
+            elapsed: start.elapsed(),
         }
+    );
+    if missing > 0 {
+        println!("{}", Msg::DryRunMissing(missing));
     }
-
-    // pull out the root-level resource for version inspection
-    let root_data = resources
-        .get(&slot_info.root_level)
-        .ok_or_else(|| anyhow!("rootLevel is missing from the archive"))?;
-    let root_resrc = ResrcData::new(root_data, false)?;
-    let mut revision = match root_resrc.method {
-        ResrcMethod::Binary { revision, .. } => revision,
-        _ => return Err(anyhow!("rootLevel uses non-binary serialization method")),
-    };
-
-    // optionally force to LBP3 revision, or warn/fix mismatches
-    let mut gameversion = revision.get_gameversion();
-    if force_lbp3 && gameversion != GameVersion::Lbp3 {
-        eprintln!("WARNING: forcing LBP3 backup format");
-        gameversion = GameVersion::Lbp3;
-        revision = gameversion.get_latest_revision();
-    } else if slot_info.game != gameversion {
+    if let Some(limit) = config.max_backup_size()?
+        && total > limit
+    {
         eprintln!(
-            "WARNING: this is a {} level in {} format",
-            slot_info.game.get_short_title(),
-            gameversion.get_short_title(),
+            "{}",
+            Msg::Warning(&Msg::OverMaxBackupSize(&contents::format_size(limit)))
         );
-        if config.fix_backup_version {
-            eprintln!(
-                "WARNING: writing backup as {}",
-                gameversion.get_short_title()
-            );
-        } else {
-            eprintln!(
-                "WARNING: writing as {}, you may need to backport this level",
-                slot_info.game.get_short_title()
-            );
-            gameversion = slot_info.game;
-            revision = gameversion.get_latest_revision();
-        }
     }
+    Ok(())
+}
 
-    // prepare output folder
-    let slot_id_str = hex::encode_upper(u32::to_be_bytes(level_id as u32));
-    let bkp_name = if slot_info.is_adventure_planet {
-        format!("{}ADVLBP3AAZ{}", gameversion.get_titleid(), slot_id_str)
-    } else {
-        format!("{}LEVEL{}", gameversion.get_titleid(), slot_id_str)
+/// Fail before anything is written if the save archive of `resources` would
+/// be bigger than `max_backup_size` or than FAR4 can address
+fn check_backup_size(resources: &BTreeMap<[u8; 20], Vec<u8>>, config: &Config) -> Result<()> {
+    let size = archive_size(resources);
+    let (limit, what) = match config.max_backup_size()? {
+        Some(limit) => (limit, "max_backup_size"),
+        None => (MAX_ARCHIVE_SIZE, "FAR4 limit"),
     };
-    let bkp_path = config.backup_directory.join(&bkp_name);
-    fs::create_dir_all(&bkp_path)?;
-
-    // build and insert the slotlist resource
-    let slt = make_slotlist(&revision, &slot_info)?;
+    if size > limit {
+        bail!(
+            "the backup would be {} ({} resources), over the {} of {}. A save that big can't be imported: raise max_backup_size in config.yml if your setup copes with it, or package the level for a private server with `bundle` instead",
+            contents::format_size(size),
+            resources.len(),
+            what,
+            contents::format_size(limit)
+        );
+    }
+    Ok(())
+}
 
-    // hash into [u8;20]
-    let slt_hash: [u8; 20] = {
-        let mut hasher = Sha1::new();
-        hasher.update(&slt);
-        // if sha1 ≥ 0.9:
-        hasher.finalize().into()
-        // if sha1 ≤ 0.8:
-        // let d = hasher.digest();
-        // d.bytes()
+/// A PS3 title ID, 4 letters and 5 digits like `BCES00850`
+fn parse_title_id(text: &str) -> Result<String, String> {
+    let title_id = text.trim().to_ascii_uppercase();
+    // split_at would cut into a multibyte character
+    let (letters, digits) = match title_id.is_ascii() {
+        true => title_id.split_at(title_id.len().min(4)),
+        false => ("", ""),
     };
+    if letters.len() != 4
+        || !letters.bytes().all(|b| b.is_ascii_uppercase())
+        || digits.len() != 5
+        || !digits.bytes().all(|b| b.is_ascii_digit())
+    {
+        return Err(format!(
+            "`{}` isn't a title ID, those are 4 letters and 5 digits like BCES00850",
+            text
+        ));
+    }
+    Ok(title_id)
+}
 
-    resources.insert(slt_hash, slt);
-
-    // generate ICON0.PNG
-    make_icon(&bkp_path, icon_sha1, &mut resources)?;
-
-    // write the save-archive chunks
-    make_savearchive(&revision, slt_hash, resources, &bkp_path)?;
-
-    // write PARAM.SFO and PARAM.PFD
-    let sfo = make_sfo(&slot_info, &bkp_name, &bkp_path, &gameversion)?;
-    let pfd_version = if gameversion == GameVersion::Lbp3 {
-        4
-    } else {
-        3
+/// `HEAD` or `HEAD:BRANCH_ID:BRANCH_REVISION`, all hex with or without `0x`
+fn parse_revision(text: &str) -> Result<ResrcRevision, String> {
+    let hex = |part: &str, max: u64| {
+        let digits = part
+            .trim()
+            .trim_start_matches("0x")
+            .trim_start_matches("0X");
+        u64::from_str_radix(digits, 16)
+            .ok()
+            .filter(|n| *n <= max)
+            .ok_or_else(|| format!("`{}` isn't a hex number up to {:#x}", part, max))
+    };
+    let parts: Vec<&str> = text.split(':').collect();
+    let (head, branch_id, branch_revision) = match parts[..] {
+        [head] => (hex(head, u32::MAX as u64)?, 0, 0),
+        [head, id, rev] => (
+            hex(head, u32::MAX as u64)?,
+            hex(id, u16::MAX as u64)?,
+            hex(rev, u16::MAX as u64)?,
+        ),
+        _ => {
+            return Err(format!(
+                "`{}` isn't a revision like 0x3f8 or 0x272:4c44:0x17",
+                text
+            ));
+        }
     };
-    make_pfd(pfd_version, sfo, &bkp_path)?;
+    Ok(ResrcRevision {
+        head: head as u32,
+        branch_id: branch_id as u16,
+        branch_revision: branch_revision as u16,
+    })
+}
 
-    println!("Backup written to {}", bkp_path.display());
-    Ok(())
+fn format_revision(revision: &ResrcRevision) -> String {
+    match revision.branch_id {
+        0 => format!("{:#x}", revision.head),
+        _ => format!(
+            "{:#x}:{:x}:{:#x}",
+            revision.head, revision.branch_id, revision.branch_revision
+        ),
+    }
 }
 
-async fn fetch_planet_resources(hash: &str, config: &Config) -> Result<()> {
-    // 1) hex → [u8;20]
-    let raw = hex::decode(hash)?;
-    if raw.len() != 20 {
-        bail!("hash must be 20 bytes (40 hex chars)");
+/// Why the games may not load a backup written with `revision`, if they may not
+fn revision_warning(revision: &ResrcRevision) -> Option<String> {
+    if revision.branch_id != 0 {
+        let Some(branch) = revision.get_branch() else {
+            return Some(Msg::UnknownBranchId(revision.branch_id).to_string());
+        };
+        let latest = branch.get_latest_revision();
+        if revision.head != latest.head || revision.branch_revision > latest.branch_revision {
+            return Some(
+                Msg::NotABranchRevision {
+                    branch: branch.get_name(),
+                    latest: &format_revision(&latest),
+                }
+                .to_string(),
+            );
+        }
+        return None;
     }
-    let mut planet_hash = [0u8; 20];
-    planet_hash.copy_from_slice(&raw);
+    let game = revision.get_gameversion();
+    let latest = game.get_latest_revision().head;
+    if revision.head > latest {
+        return Some(
+            Msg::RevisionTooNew {
+                revision: &format_revision(revision),
+                game: game.get_short_title(),
+                latest,
+            }
+            .to_string(),
+        );
+    }
+    None
+}
 
-    // 2) download the SLTb blob (no icon)
-    let DownloadResult {
-        mut resources,
-        success_count,
-        error_count,
-    } = download_level(
-        planet_hash,
-        None,
-        config.archive_path.to_string_lossy().into_owned(),
-        config.max_parallel_downloads,
-    )
-    .await?;
+/// The author handle ends up in a NetworkOnlineID, which only has room for a PSN handle
+fn check_publisher_name(name: &str) -> Result<()> {
+    if name.is_empty() || name.len() > 16 {
+        bail!("publisher name must be 1-16 bytes long, like a PSN handle");
+    }
+    Ok(())
+}
 
-    // 3) parse the SLTb to extract each level’s root hash
-    let slt_buf = resources
-        .get(&planet_hash)
-        .ok_or_else(|| anyhow!("planet SLTb missing"))?;
-    let slt_meta = ResrcData::new(slt_buf, false)?;
-    let mut level_hashes = Vec::new();
-    if let ResrcMethod::Binary { dependencies, .. } = slt_meta.method {
-        for dep in dependencies {
-            if let ResrcDescriptor::Sha1(h) = dep.desc {
-                level_hashes.push(h);
+/// Back up `slot`, and with `follow_links` the levels it links to, that
+/// many hops deep. Linked levels are credited to their own creators.
+async fn dl_with_links(
+    slot: SlotRef,
+    config: &Config,
+    options: BackupOptions,
+    follow_links: usize,
+) -> Result<()> {
+    let db = Db::open(&config.database_path)?;
+    let mut queue = VecDeque::from([(slot, 0)]);
+    let mut done = BTreeSet::new();
+    let mut failed = Vec::new();
+    while let Some((slot, depth)) = queue.pop_front() {
+        if budget::exceeded() {
+            eprintln!("{}", Msg::Warning(&Msg::LinksOutOfTime(queue.len() + 1)));
+            break;
+        }
+        let options = match depth {
+            0 => options.clone(),
+            // the icon is the one of the level asked for
+            _ => BackupOptions {
+                publisher_name: None,
+                icon: None,
+                icon_texture: false,
+                ..options.clone()
+            },
+        };
+        let linked = match dl_as_backup(slot, &db, config, options).await {
+            Ok(linked) => linked,
+            // the level asked for has to work, linked ones are best effort
+            Err(e) if depth > 0 => {
+                eprintln!(
+                    "{}",
+                    Msg::Warning(&Msg::LinkedLevelFailed {
+                        slot: &slot.to_string(),
+                        error: &e.to_string(),
+                    })
+                );
+                failed.push(slot);
+                continue;
             }
+            Err(e) => return Err(e),
+        };
+        if let SlotRef::Id(id) = slot {
+            done.insert(id);
         }
+        if depth >= follow_links {
+            continue;
+        }
+        for level in linked {
+            if done.insert(level.slot_id) {
+                println!(
+                    "{}",
+                    Msg::FollowingLink {
+                        slot_id: level.slot_id,
+                        name: &level.name,
+                        creator: &level.np_handle,
+                    }
+                );
+                queue.push_back((SlotRef::Id(level.slot_id), depth + 1));
+            }
+        }
+    }
+    if !failed.is_empty() {
+        eprintln!("{}", Msg::Warning(&Msg::LinkedLevelsFailed(failed.len())));
     }
+    Ok(())
+}
 
-    // 4) for each level, pull _all_ of its blobs
-    for level_hash in level_hashes {
-        let DownloadResult {
-            resources: lvl_res, ..
-        } = download_level(
-            level_hash,
-            None,
-            config.archive_path.to_string_lossy().into_owned(),
-            config.max_parallel_downloads,
-        )
-        .await?;
-        for (sha, blob) in lvl_res {
-            resources.insert(sha, blob);
+/// Look up `slot`, with its name and description in `language` if the
+/// database has a translation. Also says whether it had one
+async fn load_slot(db: &Db, slot: SlotRef, language: Language) -> Result<(SlotInfo, bool)> {
+    db.call(move |conn| {
+        let mut slot_info = get_slot_info(slot, conn)?;
+        let translated = apply_slot_translation(slot_info.id, conn, language, &mut slot_info)?;
+        Ok((slot_info, translated))
+    })
+    .await
+}
+
+/// Write the backup, returns the levels it seems to link to
+async fn dl_as_backup(
+    slot: SlotRef,
+    db: &Db,
+    config: &Config,
+    options: BackupOptions,
+) -> Result<Vec<LinkedLevel>> {
+    let (mut slot_info, translated) = load_slot(db, slot, options.language).await?;
+    if translated {
+        println!("{}", Msg::UsingTranslation(options.language.get_code()));
+    }
+    // the icon fallback is still the creator's, not the publisher's
+    let creator = slot_info.np_handle.clone();
+    if let Some(publisher_name) = &options.publisher_name {
+        check_publisher_name(publisher_name)?;
+        slot_info.np_handle = publisher_name.clone();
+    }
+
+    println!("{}", Msg::LevelFound);
+    println!("{}", Msg::LevelName(&slot_info.name));
+    println!("{}", Msg::LevelCreator(&slot_info.np_handle));
+    println!("{}", Msg::LevelGame(slot_info.game.get_short_title()));
+
+    // GUID slots are named after their row id too, so they can't collide
+    let folder_id = hex::encode_upper(u32::to_be_bytes(slot_info.id as u32));
+    backup_slot(slot_info, &creator, &folder_id, db, config, &options).await
+}
+
+/// The revision and game a backup of `slot_info` is written for, from the
+/// revision of its rootLevel and the options, with what to warn about
+fn backup_format(
+    mut revision: ResrcRevision,
+    slot_info: &SlotInfo,
+    options: &BackupOptions,
+    config: &Config,
+) -> Result<(ResrcRevision, GameVersion, Vec<String>)> {
+    let mut warnings = Vec::new();
+    // optionally force to LBP3 revision, or warn/fix mismatches
+    let mut gameversion = revision.get_gameversion();
+    if let Some(forced) = options.revision {
+        if let Some(branch) = forced.get_branch()
+            && !branch.loads_on_ps3()
+        {
+            bail!(
+                "{} revisions can't be written, the PS3 games don't load them",
+                branch.get_name()
+            );
+        }
+        revision = forced;
+        gameversion = forced.get_gameversion();
+        warnings.push(
+            Msg::WritingWithRevision {
+                revision: &format_revision(&forced),
+                game: gameversion.get_short_title(),
+            }
+            .to_string(),
+        );
+        warnings.extend(revision_warning(&forced));
+    } else if options.force_lbp3 && gameversion != GameVersion::Lbp3 {
+        warnings.push(Msg::ForcingLbp3.to_string());
+        gameversion = GameVersion::Lbp3;
+        revision = revision.compatible_revision(gameversion);
+    } else if slot_info.game != gameversion {
+        warnings.push(
+            Msg::LevelInFormat {
+                level_game: slot_info.game.get_short_title(),
+                format: gameversion.get_short_title(),
+            }
+            .to_string(),
+        );
+        if config.fix_backup_version {
+            warnings.push(Msg::WritingBackupAs(gameversion.get_short_title()).to_string());
+        } else {
+            warnings.push(Msg::WritingForBackport(slot_info.game.get_short_title()).to_string());
+            gameversion = slot_info.game;
+            revision = revision.compatible_revision(gameversion);
         }
-        println!("  → added level {}", hex::encode(level_hash));
     }
 
-    // 5) write them all out as <hex>.bin
-    let out_dir = config
-        .backup_directory
-        .join(format!("planet_{}", hash.to_uppercase()));
-    fs::create_dir_all(&out_dir)?;
-    for (sha, data) in &resources {
-        let fname = format!("{}", hex::encode(sha));
-        fs::write(out_dir.join(&fname), data)?;
+    // adventures only exist in LBP3
+    if slot_info.is_adventure_planet && gameversion != GameVersion::Lbp3 {
+        if options.revision.is_some() {
+            bail!("adventures can only be loaded by LBP3, --revision has to be an LBP3 revision");
+        }
+        warnings.push(Msg::AdventureAsLbp3.to_string());
+        gameversion = GameVersion::Lbp3;
+        revision = revision.compatible_revision(gameversion);
     }
-    println!("wrote {} files to {}", success_count, out_dir.display());
+    // a level on a branch the PS3 games don't load is written as mainline
+    if options.revision.is_none() {
+        revision = revision.compatible_revision(gameversion);
+    }
+    Ok((revision, gameversion, warnings))
+}
 
-    // 6) write the planet root‐hash itself
-    let planet_hex = hex::encode(planet_hash);
-    fs::write(out_dir.join("planet_hash.txt"), &planet_hex)?;
-    println!("wrote planet_hash.txt → {}", planet_hex);
-
-    // 7) lookup & write the creator’s icon SHA1
-    let conn = Connection::open(&config.database_path)?;
-    let icon_blob: Vec<u8> = conn.query_row(
-        // find slot row whose rootLevel equals our planet hash
-        "SELECT u.icon
-           FROM slot AS s
-           JOIN \"user\" AS u ON s.npHandle = u.npHandle
-          WHERE s.rootLevel = ?1",
-        [&planet_hash],
-        |r| r.get(0),
-    )?;
-    let icon_hex = hex::encode(&icon_blob);
-    fs::write(out_dir.join("creator_icon_hash.txt"), &icon_hex)?;
-    println!("wrote creator_icon_hash.txt → {}", icon_hex);
+/// The folder name and path of a backup of `slot_info` with `title_id`
+fn backup_folder(
+    slot_info: &SlotInfo,
+    title_id: &str,
+    folder_id: &str,
+    options: &BackupOptions,
+    config: &Config,
+) -> (String, PathBuf) {
+    let bkp_name = if slot_info.is_adventure_planet {
+        format!("{}ADVLBP3AAZ{}", title_id, folder_id)
+    } else {
+        format!("{}LEVEL{}", title_id, folder_id)
+    };
+    let bkp_path = options
+        .output_dir
+        .as_ref()
+        .unwrap_or(&config.backup_directory)
+        .join(&bkp_name);
+    (bkp_name, bkp_path)
+}
 
-    Ok(())
+/// Whether to go on with a backup into `bkp_path`, false to skip it because
+/// it's there already. Fails if it's there and mustn't be replaced
+fn check_existing(bkp_path: &Path, existing: ExistingBackup) -> Result<bool> {
+    if !bkp_path.exists() {
+        return Ok(true);
+    }
+    match existing {
+        ExistingBackup::Fail => bail!("{}", Msg::BackupExists(bkp_path)),
+        ExistingBackup::Skip => {
+            println!("{}", Msg::SkippingExisting(bkp_path));
+            Ok(false)
+        }
+        ExistingBackup::Overwrite => Ok(true),
+    }
 }
 
-async fn fetch_planet_resources_helper_function(
-    planet_hash_str: &str,
-    creator_handle: &str,
+/// A backup folder written under a temporary name next to where it goes,
+/// and moved there once it's complete. The backup it replaces stays until
+/// then, one that fails halfway is removed and leaves the old one as it was
+struct StagedBackup {
+    staging: PathBuf,
+    target: PathBuf,
+}
+
+impl StagedBackup {
+    fn create(target: &Path) -> Result<Self> {
+        let name = target.file_name().unwrap_or_default().to_string_lossy();
+        let staging = target.with_file_name(format!("{}.partial", name));
+        if staging.exists() {
+            fs::remove_dir_all(&staging)?;
+        }
+        fs::create_dir_all(&staging)?;
+        Ok(Self {
+            staging,
+            target: target.to_path_buf(),
+        })
+    }
+
+    fn path(&self) -> &Path {
+        &self.staging
+    }
+
+    /// Put the finished backup in place of the old one, if there's one
+    fn commit(self) -> Result<()> {
+        let name = self
+            .target
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy();
+        let old = self.target.with_file_name(format!("{}.old", name));
+        if self.target.exists() {
+            if old.exists() {
+                fs::remove_dir_all(&old)?;
+            }
+            fs::rename(&self.target, &old)?;
+        }
+        fs::rename(&self.staging, &self.target)?;
+        if old.exists() {
+            fs::remove_dir_all(&old)?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for StagedBackup {
+    fn drop(&mut self) {
+        // gone already if it was committed
+        let _ = fs::remove_dir_all(&self.staging);
+    }
+}
+
+/// Fetch everything `slot_info` needs and write its backup into a folder
+/// named with `folder_id`, returns the levels it seems to link to. The
+/// icon of `creator` is the fallback for ICON0.PNG, `slot_info` may credit
+/// a publisher instead
+async fn backup_slot(
+    mut slot_info: SlotInfo,
+    creator: &str,
+    folder_id: &str,
+    db: &Db,
     config: &Config,
-    level_out_dir: &Path,
-) -> Result<()> {
-    // decode the planet‐hash
-    let raw = hex::decode(planet_hash_str)
-        .map_err(|e| anyhow!("invalid hex for planet {}: {}", planet_hash_str, e))?;
-    if raw.len() != 20 {
-        bail!("planet hash must be 20 bytes, got {}", raw.len());
+    options: &BackupOptions,
+) -> Result<Vec<LinkedLevel>> {
+    // extract icon hash if present
+    let icon_sha1 = match slot_info.icon {
+        ResrcDescriptor::Sha1(h) => Some(h),
+        _ => None,
+    };
+
+    // a bad image fails before anything is fetched
+    let icon_image = match &options.icon {
+        Some(path) => {
+            let image =
+                fs::read(path).map_err(|e| anyhow!("couldn't read {}: {}", path.display(), e))?;
+            icon::decode_image(&image)
+                .map_err(|e| anyhow!("{} isn't an image: {}", path.display(), e))?;
+            Some(image)
+        }
+        None => None,
+    };
+
+    if options.dry_run {
+        dry_run_backup(&slot_info, icon_sha1, config).await?;
+        return Ok(Vec::new());
     }
-    let mut planet_hash = [0u8; 20];
-    planet_hash.copy_from_slice(&raw);
 
-    // 1) download SLTb
-    let DownloadResult {
-        mut resources,
-        success_count,
-        error_count,
-    } = download_level(
-        planet_hash,
-        None,
-        config.archive_path.to_string_lossy().into_owned(),
+    // the folder is named after the game the backup is for, which the
+    // rootLevel alone tells. So a backup that's there is skipped or refused
+    // before the rest of the level is fetched
+    let archive = config.archive_path.to_string_lossy().into_owned();
+    if let Ok(root) = resource_dl::fetch_resource(slot_info.root_level, archive).await
+        && let Ok(ResrcData {
+            method: ResrcMethod::Binary { revision, .. },
+            ..
+        }) = ResrcData::new(&root, false)
+    {
+        let (_, gameversion, _) = backup_format(revision, &slot_info, options, config)?;
+        let title_id = options
+            .title_id
+            .as_deref()
+            .unwrap_or(gameversion.get_titleid());
+        let (_, bkp_path) = backup_folder(&slot_info, title_id, folder_id, options, config);
+        if !check_existing(&bkp_path, options.existing)? {
+            let candidates = level_links::candidate_slot_ids(&root);
+            let own_id = slot_info.id;
+            return db
+                .call(move |conn| linked_levels(conn, &candidates, own_id))
+                .await;
+        }
+    }
+
+    print!("{}", Msg::Gathering);
+    stdout().flush()?;
+
+    // call your local-archive-backed downloader
+    let download = download_level(
+        slot_info.root_level,
+        icon_sha1,
+        config.archive_path.to_string_lossy().into_owned(), // your local archive root
         config.max_parallel_downloads,
+        options.partial_ok,
+        progress::silent(),
     )
     .await?;
+    // the root and icon have other hashes if alternates were swapped in
+    slot_info.root_level = download.hash_of(slot_info.root_level);
+    let icon_sha1 = icon_sha1.map(|h| download.hash_of(h));
+    if let ResrcDescriptor::Sha1(h) = &mut slot_info.icon {
+        *h = download.hash_of(*h);
+    }
+    let DownloadResult {
+        mut resources,
+        success_count: dl_count,
+        error_count: fail_count,
+        missing,
+        ..
+    } = download;
+
     println!(
-        "Fetched planet {} SLTb: {}/{} blobs",
-        planet_hash_str, success_count, error_count
+        "\n{}",
+        Msg::Fetched {
+            fetched: dl_count,
+            missing: fail_count,
+        }
     );
 
-    // 2) parse SLTb for sub‐levels
-    let slt_buf = resources
-        .get(&planet_hash)
-        .ok_or_else(|| anyhow!("planet SLTb missing for {}", planet_hash_str))?;
-    let slt_meta = ResrcData::new(slt_buf, false)?;
-    let mut deps = Vec::new();
-    if let ResrcMethod::Binary { dependencies, .. } = slt_meta.method {
-        for d in dependencies {
-            if let ResrcDescriptor::Sha1(h) = d.desc {
-                deps.push(h);
+    use std::fs::OpenOptions;
+    let mut dbg = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .truncate(true)
+        .open("output.txt")?;
+    writeln!(dbg, "parent_sha1 <- dependency_sha1")?;
+    for (parent_sha, blob) in &resources {
+        if let Ok(res) = ResrcData::new(blob, false)
+            && let ResrcMethod::Binary { dependencies, .. } = res.method
+        {
+            for dep in dependencies {
+                if let ResrcDescriptor::Sha1(child_sha) = dep.desc {
+                    writeln!(
+                        dbg,
+                        "{} <- {}",
+                        hex_encode(parent_sha),
+                        hex_encode(child_sha)
+                    )?;
+                }
             }
         }
     }
 
-    // 3) fetch each sub‐level
-    for h in deps {
-        let DownloadResult {
-            resources: lvl_res, ..
-        } = download_level(
-            h,
-            None,
-            config.archive_path.to_string_lossy().into_owned(),
-            config.max_parallel_downloads,
-        )
+    // pull out the root-level resource for version inspection
+    let root_data = resources
+        .get(&slot_info.root_level)
+        .ok_or_else(|| anyhow!("rootLevel is missing from the archive"))?;
+    let root_revision = match ResrcData::new(root_data, false)?.method {
+        ResrcMethod::Binary { revision, .. } => revision,
+        _ => return Err(anyhow!("rootLevel uses non-binary serialization method")),
+    };
+    let (revision, gameversion, warnings) =
+        backup_format(root_revision, &slot_info, options, config)?;
+    for warning in warnings {
+        eprintln!("{}", Msg::Warning(&warning));
+    }
+
+    // adventures need their shared data (quests etc) to work
+    if slot_info.is_adventure_planet {
+        match collect_shared_data(slot_info.root_level, &resources) {
+            Ok(shared) => {
+                println!(
+                    "{}",
+                    Msg::AdventureSharedData {
+                        resources: shared.dependencies.len(),
+                        quests: shared.quest_count,
+                    }
+                );
+                for hash in &shared.missing {
+                    eprintln!(
+                        "{}",
+                        Msg::Warning(&Msg::AdventureResourceMissing(&hex_encode(hash)))
+                    );
+                }
+                for (hash, error) in &shared.unreadable {
+                    eprintln!(
+                        "{}",
+                        Msg::Warning(&Msg::AdventureResourceUnreadable {
+                            hash: &hex_encode(hash),
+                            error,
+                        })
+                    );
+                }
+                slot_info.adventure_shared = shared.dependencies;
+            }
+            Err(e) => eprintln!(
+                "{}",
+                Msg::Warning(&Msg::AdventureSharedDataSkipped(&e.to_string()))
+            ),
+        }
+    }
+
+    // check for restore blockers before writing anything
+    let levels = lint::referenced_levels(slot_info.root_level, &resources);
+    let candidates = match resources.get(&slot_info.root_level) {
+        Some(root) => level_links::candidate_slot_ids(root),
+        None => BTreeSet::new(),
+    };
+    let own_id = slot_info.id;
+    let (published, linked) = db
+        .call(move |conn| {
+            Ok((
+                published_root_levels(&levels, conn)?,
+                linked_levels(conn, &candidates, own_id)?,
+            ))
+        })
         .await?;
-        for (sha, blob) in lvl_res {
-            resources.insert(sha, blob);
+    let report = lint::lint_level(
+        slot_info.root_level,
+        &resources,
+        gameversion,
+        &published,
+        &linked,
+    );
+    report.print();
+    let errors = report.count(Severity::Error);
+    if options.strict && errors > 0 {
+        bail!(
+            "lint found {} errors, not writing the backup (--strict)",
+            errors
+        );
+    }
+
+    check_backup_size(&resources, config)?;
+
+    // prepare output folder
+    let title_id = match &options.title_id {
+        Some(title_id) => {
+            match GameVersion::of_titleid(title_id) {
+                Some(game) if game != gameversion => eprintln!(
+                    "{}",
+                    Msg::Warning(&Msg::TitleIdOfOtherGame {
+                        title_id,
+                        game: game.get_short_title(),
+                        backup: gameversion.get_short_title(),
+                    })
+                ),
+                Some(_) => {}
+                None => eprintln!(
+                    "{}",
+                    Msg::Warning(&Msg::UnknownTitleId {
+                        title_id,
+                        game: gameversion.get_short_title(),
+                    })
+                ),
+            }
+            title_id.as_str()
         }
-        println!("  → added sub‐level {}", hex_encode(h));
+        None => gameversion.get_titleid(),
+    };
+    let (bkp_name, bkp_path) = backup_folder(&slot_info, title_id, folder_id, options, config);
+    // checked again, the rootLevel couldn't be read on its own or another
+    // run wrote the backup meanwhile
+    if !check_existing(&bkp_path, options.existing)? {
+        return Ok(linked);
     }
+    // written next to it and moved in place when done, so nothing of an old
+    // backup is left over, like a stale backup.meta.json, and a failure
+    // leaves the old one alone
+    let staged = StagedBackup::create(&bkp_path)?;
+    let out = staged.path();
 
-    // 4) dump all planet + sub‐level blobs
-    for (sha, data) in &resources {
-        fs::write(level_out_dir.join(hex_encode(sha)), data)?;
+    // the image as a texture the slot list points at, instead of the archived icon
+    if let Some(image) = icon_image.as_deref().filter(|_| options.icon_texture) {
+        let texture = icon::image_to_texture(image)?;
+        let hash: [u8; 20] = Sha1::digest(&texture).into();
+        println!("{}", Msg::SlotIconFromImage(&hex_encode(hash)));
+        resources.insert(hash, texture);
+        slot_info.icon = ResrcDescriptor::Sha1(hash);
+    }
+
+    // the backup isn't the slot as published anymore, backup.meta.json says so
+    let mut modifications = Vec::new();
+    // only slot lists after 0x237 have the flag, revisions before it come
+    // from before LBP1 shipped, like a forced `--revision`
+    if options.force_copyable && revision.get_version() <= 0x237 {
+        eprintln!(
+            "{}",
+            Msg::Warning(&Msg::CopyableNotWritten(&format_revision(&revision)))
+        );
+    } else if options.force_copyable && !slot_info.shareable {
+        println!("{}", Msg::MarkingCopyable);
+        slot_info.shareable = true;
+        modifications.push(SlotModification {
+            field: "shareable".to_string(),
+            original: "false".to_string(),
+            written: "true".to_string(),
+            reason: "--force-copyable".to_string(),
+        });
     }
 
-    // 5) write SLTb itself as `<planet_hash>`
-    fs::write(
-        level_out_dir.join(planet_hash_str),
-        resources.get(&planet_hash).unwrap(),
+    // build and insert the slotlist resource
+    let slt = make_slotlist(&revision, &slot_info, &config.text_limits)?;
+
+    // hash into [u8;20]
+    let slt_hash: [u8; 20] = {
+        let mut hasher = Sha1::new();
+        hasher.update(&slt);
+        // if sha1 ≥ 0.9:
+        hasher.finalize().into()
+        // if sha1 ≤ 0.8:
+        // let d = hasher.digest();
+        // d.bytes()
+    };
+
+    resources.insert(slt_hash, slt);
+
+    // generate ICON0.PNG, falling back to the creator's icon if the level has none
+    let creator_icon = match icon_sha1.filter(|h| resources.contains_key(h)) {
+        Some(_) => None,
+        None => fetch_creator_icon(creator, db, config).await,
+    };
+    let icon_sources = IconSources {
+        image: icon_image.as_deref(),
+        slot_icon: icon_sha1,
+        root_level: Some(slot_info.root_level),
+        creator_icon: creator_icon.as_deref(),
+    };
+    let overlay = IconOverlay {
+        title: &slot_info.name,
+        creator: &slot_info.np_handle,
+    };
+    let icon_source = make_icon(
+        out,
+        &icon_sources,
+        &resources,
+        options.icon_overlay.then_some(&overlay),
     )?;
-    println!("→ wrote planet SLTb blob as {}", planet_hash_str);
 
-    // 6) fetch the creator’s icon BLOB from the user table
-    // let (user_icon_blob,): (Vec<u8>,) = Connection::open(&config.database_path)?.query_row(
-    //     "SELECT icon FROM \"user\" WHERE npHandle = ?1",
-    //     [creator_handle],
-    //     |r| Ok((r.get(0)?,)),
-    // )?;
-    // // name it by its own SHA1 hex
-    // let icon_hex = hex_encode(&user_icon_blob);
-    // fs::write(level_out_dir.join(&icon_hex), &user_icon_blob)?;
-    // println!("→ wrote creator’s icon blob as {}", icon_hex);
+    // summarized before the resources go into the save archive
+    let written_at = Utc::now();
+    let summary = contents::render(&BackupContents {
+        name: &slot_info.name,
+        creator: &slot_info.np_handle,
+        slot_id: slot_info.id,
+        game: gameversion.get_short_title(),
+        written_at,
+        resources: &resources,
+        missing: &missing,
+        icon_source,
+    });
 
-    Ok(())
-}
+    // write the save-archive chunks
+    make_savearchive(&revision, slt_hash, resources, out, &progress::Silent)?;
 
-async fn fetch_level(level_id: u32, config: &Config) -> Result<()> {
-    // 1) Open DB and pull rootLevel, publishedIn, and npHandle
-    let conn = Connection::open(&config.database_path)?;
-    let (root_blob, published_in, np_handle): (Vec<u8>, Option<String>, String) = conn.query_row(
-        r#"
-        SELECT rootLevel
-             , publishedIn
-             , npHandle
-          FROM slot
-         WHERE id = ?1
-        "#,
-        [level_id],
-        |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)),
+    // write PARAM.SFO and PARAM.PFD
+    let sfo = make_sfo(
+        &slot_info,
+        &bkp_name,
+        out,
+        &gameversion,
+        options.language,
+        &config.sfo,
+        &config.text_limits,
     )?;
+    let pfd_version = if gameversion == GameVersion::Lbp3 {
+        4
+    } else {
+        3
+    };
+    make_pfd(pfd_version, sfo, out)?;
 
-    // 2) Sanity-check root_blob
-    if root_blob.len() != 20 {
-        bail!("slot.rootLevel is {} bytes, expected 20", root_blob.len());
+    // not part of the save data, PARAM.PFD only protects PARAM.SFO
+    let meta = BackupMeta {
+        schema_version: BACKUP_META_SCHEMA_VERSION,
+        slot_id: slot_info.id,
+        name: slot_info.name.clone(),
+        creator: slot_info.np_handle.clone(),
+        game: gameversion.get_short_title().to_string(),
+        written_at,
+        omitted_resources: missing
+            .iter()
+            .map(|(hash, error)| OmittedResource {
+                sha1: hex_encode(hash),
+                error: error.clone(),
+            })
+            .collect(),
+        modifications,
+        language: Some(options.language),
+    };
+    fs::write(out.join(BACKUP_META_FILE), to_string_pretty(&meta)?)?;
+    fs::write(out.join(contents::CONTENTS_FILE), summary)?;
+    if !missing.is_empty() {
+        eprintln!(
+            "{}",
+            Msg::Warning(&Msg::ResourcesLeftOut {
+                missing: missing.len(),
+                meta: BACKUP_META_FILE,
+            })
+        );
     }
-    let mut root_hash = [0u8; 20];
-    root_hash.copy_from_slice(&root_blob);
-
-    // 3) Pull slot.icon SHA1
-    let icon_sha1_opt: Option<[u8; 20]> =
-        conn.query_row("SELECT icon FROM slot WHERE id = ?1", [level_id], |r| {
-            let v: Vec<u8> = r.get(0)?;
-            if v.len() == 20 {
-                let mut a = [0u8; 20];
-                a.copy_from_slice(&v);
-                Ok(Some(a))
-            } else {
-                Ok(None)
-            }
-        })?;
 
-    // 4) Download level blobs (including level-icon)
-    let DownloadResult {
-        resources,
-        success_count,
-        error_count,
-    } = download_level(
-        root_hash,
-        icon_sha1_opt,
+    staged.commit()?;
+    println!("{}", Msg::BackupWritten(&bkp_path));
+    Ok(linked)
+}
+
+async fn bundle_level(
+    slot: SlotRef,
+    language: Language,
+    output: Option<PathBuf>,
+    partial_ok: bool,
+    config: &Config,
+) -> Result<()> {
+    let db = Db::open(&config.database_path)?;
+    let (mut slot_info, _) = load_slot(&db, slot, language).await?;
+    println!(
+        "{}",
+        Msg::Bundling {
+            name: &slot_info.name,
+            creator: &slot_info.np_handle,
+            game: slot_info.game.get_short_title(),
+        }
+    );
+
+    let icon_sha1 = match slot_info.icon {
+        ResrcDescriptor::Sha1(h) => Some(h),
+        ResrcDescriptor::Guid(_) => None,
+    };
+    let download = download_level(
+        slot_info.root_level,
+        icon_sha1,
         config.archive_path.to_string_lossy().into_owned(),
         config.max_parallel_downloads,
+        partial_ok,
+        progress::silent(),
+    )
+    .await?;
+    slot_info.root_level = download.hash_of(slot_info.root_level);
+    let icon_sha1 = icon_sha1.map(|h| download.hash_of(h));
+    if let ResrcDescriptor::Sha1(h) = &mut slot_info.icon {
+        *h = download.hash_of(*h);
+    }
+    let DownloadResult {
+        resources, missing, ..
+    } = download;
+    if !resources.contains_key(&slot_info.root_level) {
+        bail!("rootLevel is missing from the archive");
+    }
+
+    let icon_png = match icon_sha1.and_then(|h| resources.get(&h)) {
+        Some(icon) => icon_to_png(icon).unwrap_or_else(|e| {
+            eprintln!("{}", Msg::Warning(&Msg::IconNotConverted(&e.to_string())));
+            None
+        }),
+        None => None,
+    };
+
+    let meta = BundleMeta {
+        format_version: bundle::BUNDLE_FORMAT_VERSION,
+        slot_id: slot_info.id,
+        guid: slot_info.guid,
+        name: slot_info.name.clone(),
+        description: slot_info.description.clone(),
+        creator: slot_info.np_handle.clone(),
+        game: slot_info.game.get_short_title().to_string(),
+        root_level: hex_encode(slot_info.root_level),
+        icon: match slot_info.icon {
+            ResrcDescriptor::Sha1(h) => Some(hex_encode(h)),
+            ResrcDescriptor::Guid(g) => Some(format!("{}{}", GUID_DEPENDENCY_PREFIX, g)),
+        },
+        icon_png: icon_png.as_ref().map(|png| hex_encode(Sha1::digest(png))),
+        labels: slot_info
+            .author_labels
+            .iter()
+            .filter_map(|id| labels::label_name(*id))
+            .map(str::to_string)
+            .collect(),
+        level_type: slot_info.leveltype.get_name().to_string(),
+        min_players: slot_info.min_players,
+        max_players: slot_info.max_players,
+        enforce_min_max_players: slot_info.enforce_min_max_players,
+        same_screen_game: slot_info.same_screen_game,
+        is_sub_level: slot_info.is_sub_level,
+        is_adventure: slot_info.is_adventure_planet,
+        initially_locked: slot_info.initially_locked,
+        shareable: slot_info.shareable,
+        resources: resources.keys().map(hex_encode).collect(),
+        missing_resources: missing
+            .iter()
+            .map(|(hash, error)| OmittedResource {
+                sha1: hex_encode(hash),
+                error: error.clone(),
+            })
+            .collect(),
+        written_at: Utc::now(),
+    };
+
+    let path = output.unwrap_or_else(|| {
+        config
+            .backup_directory
+            .join(format!("bundle_{}.zip", slot_info.id))
+    });
+    bundle::write_bundle(&path, &meta, &resources, icon_png.as_deref())?;
+    if !missing.is_empty() {
+        eprintln!(
+            "{}",
+            Msg::Warning(&Msg::BundleResourcesLeftOut {
+                missing: missing.len(),
+                meta: bundle::BUNDLE_META_FILE,
+            })
+        );
+    }
+    println!(
+        "{}",
+        Msg::BundleWritten {
+            resources: resources.len(),
+            path: &path,
+        }
+    );
+    Ok(())
+}
+
+async fn export_xml(
+    mut slots: Vec<SlotRef>,
+    levels: Option<&Path>,
+    language: Language,
+    output: Option<&Path>,
+    config: &Config,
+) -> Result<()> {
+    if let Some(path) = levels {
+        slots.extend(
+            read_level_list(path)?
+                .into_iter()
+                .map(|id| SlotRef::Id(id as i64)),
+        );
+    }
+    let db = Db::open(&config.database_path)?;
+    let mut xml_slots = Vec::with_capacity(slots.len());
+    for slot in slots {
+        let (info, _) = load_slot(&db, slot, language)
+            .await
+            .map_err(|e| anyhow!("{:?}: {}", slot, e))?;
+        let np_handle = info.np_handle.clone();
+        let creator_icon = db.call(move |conn| get_user_icon(&np_handle, conn)).await?;
+        xml_slots.push(XmlSlot { info, creator_icon });
+    }
+
+    let xml = slot_xml::render_slots(&xml_slots);
+    match output {
+        Some(path) => {
+            fs::write(path, xml)?;
+            eprintln!(
+                "{}",
+                Msg::SlotsWritten {
+                    slots: xml_slots.len(),
+                    path,
+                }
+            );
+        }
+        None => stdout().write_all(xml.as_bytes())?,
+    }
+    Ok(())
+}
+
+/// levels read from the database at a time when looking at all of them
+const GRAPH_PAGE: usize = 1000;
+
+/// Fetch `level_ids`, or every level in the database a page at a time if
+/// there are none, and hand each level's resources to `f`. The disk cache
+/// is only read, nothing of it is kept. Levels that can't be fetched are
+/// skipped with an error, returns how many were
+async fn for_each_level(
+    level_ids: Vec<u32>,
+    parallel_levels: usize,
+    config: &Config,
+    mut f: impl FnMut(&LevelRecord, &BTreeMap<[u8; 20], Vec<u8>>) -> Result<()>,
+) -> Result<usize> {
+    let db = Db::open(&config.database_path)?;
+    let pool = DownloadPool::new(config.max_parallel_downloads).read_only();
+    let mut failed = 0;
+
+    // the given levels, or every level a page at a time
+    let paged = level_ids.is_empty();
+    let mut given = Some(level_ids);
+    let mut after = 0;
+    loop {
+        let page = match (paged, given.take()) {
+            (false, Some(ids)) => {
+                db.call(move |conn| {
+                    ids.into_iter()
+                        .map(|id| get_level_record(conn, id))
+                        .collect::<Result<Vec<_>>>()
+                })
+                .await?
+            }
+            (false, None) => break,
+            (true, _) => {
+                let (page, last) = db
+                    .call(move |conn| db::get_level_records_after(conn, after, GRAPH_PAGE))
+                    .await?;
+                let Some(last) = last else {
+                    break;
+                };
+                after = last;
+                page
+            }
+        };
+
+        let mut fetches = stream::iter(&page)
+            .take_while(|_| future::ready(!budget::exceeded()))
+            .map(|level| {
+                let archive = config.archive_path.to_string_lossy().into_owned();
+                let pool = &pool;
+                async move {
+                    let result =
+                        download_level_with_pool(level.root_level, level.icon, archive, pool, true)
+                            .await;
+                    (level, result)
+                }
+            })
+            .buffer_unordered(parallel_levels.max(1));
+        while let Some((level, result)) = fetches.next().await {
+            match result {
+                Ok(DownloadResult { resources, .. }) => f(level, &resources)?,
+                Err(e) => {
+                    eprintln!(
+                        "❌ {}",
+                        Msg::LevelSkipped {
+                            id: level.id,
+                            error: &e.to_string(),
+                        }
+                    );
+                    failed += 1;
+                }
+            }
+        }
+        if budget::exceeded() {
+            break;
+        }
+    }
+
+    Ok(failed)
+}
+async fn export_graph(
+    mut level_ids: Vec<u32>,
+    levels: Option<&Path>,
+    format: GraphFormat,
+    output: &Path,
+    parallel_levels: usize,
+    config: &Config,
+) -> Result<()> {
+    if let Some(path) = levels {
+        level_ids.extend(read_level_list(path)?);
+    }
+    // a level given twice would be fetched twice for the same row
+    level_ids.sort_unstable();
+    level_ids.dedup();
+    let mut graph = GraphWriter::create(output, format)?;
+    let start = Instant::now();
+    let failed = for_each_level(level_ids, parallel_levels, config, |level, resources| {
+        graph.add_level(level, resources)
+    })
+    .await?;
+
+    let stats = graph.finish()?;
+    println!(
+        "{}",
+        Msg::GraphWritten {
+            levels: stats.levels,
+            nodes: stats.nodes,
+            edges: stats.edges,
+            path: output,
+            elapsed: start.elapsed(),
+        }
+    );
+    if failed > 0 {
+        println!("{}", Msg::LevelsUnreadableMissing(failed));
+    }
+    Ok(())
+}
+
+fn parse_sha1(hex: &str) -> Result<[u8; 20], String> {
+    hex::decode(hex)
+        .map_err(|e| format!("invalid hex: {}", e))?
+        .try_into()
+        .map_err(|_| "a SHA1 is 40 hex characters".to_string())
+}
+
+/// The dependents of every dependency in the relations of a Refresh import
+fn dependents_index(import: &ImportData) -> HashMap<&str, Vec<&str>> {
+    let mut dependents: HashMap<&str, Vec<&str>> = HashMap::new();
+    for r in &import.relations {
+        dependents
+            .entry(r.dependency.as_str())
+            .or_default()
+            .push(r.dependent.as_str());
+    }
+    dependents
+}
+
+/// The resources that depend on `start`, a hash or a `g<guid>`, directly or
+/// through other resources, and `start` itself if it's a hash
+fn dependents_of(start: String, dependents: &HashMap<&str, Vec<&str>>) -> BTreeSet<[u8; 20]> {
+    let mut seen = BTreeSet::from([start.clone()]);
+    let mut queue = vec![start];
+    while let Some(next) = queue.pop() {
+        for parent in dependents.get(next.as_str()).into_iter().flatten() {
+            if seen.insert(parent.to_string()) {
+                queue.push(parent.to_string());
+            }
+        }
+    }
+    seen.iter().filter_map(|h| parse_sha1(h).ok()).collect()
+}
+
+fn levels_of_hash(
+    hash: [u8; 20],
+    relations: Option<&Path>,
+    json: bool,
+    config: &Config,
+) -> Result<()> {
+    let hashes = match relations {
+        Some(path) => {
+            let import = ImportData::read(path)?;
+            dependents_of(hex_encode(hash), &dependents_index(&import))
+        }
+        None => BTreeSet::from([hash]),
+    };
+
+    let conn = open_db(&config.database_path)?;
+    let mut refs = db::slots_referencing(&conn, &hashes)?;
+    refs.sort_by_key(|r| r.slot_id);
+    let users = HashUsers {
+        hash: hex_encode(hash),
+        dependents: hashes.len() - 1,
+        slots: refs
+            .into_iter()
+            .map(|r| SlotUse {
+                id: r.slot_id,
+                name: r.name,
+                creator: r.np_handle,
+                table: r.table.to_string(),
+                column: r.column.to_string(),
+                through: (r.hash != hash).then(|| hex_encode(r.hash)),
+            })
+            .collect(),
+    };
+    if json {
+        return JsonOutput::print("levels-of-hash", users);
+    }
+
+    if let Some(path) = relations {
+        println!(
+            "{}",
+            Msg::Dependents {
+                dependents: users.dependents,
+                hash: &users.hash,
+                path,
+            }
+        );
+    }
+    if users.slots.is_empty() {
+        println!("{}", Msg::NoSlotUses(&users.hash));
+        return Ok(());
+    }
+    for slot in &users.slots {
+        let via = match &slot.through {
+            None => String::new(),
+            Some(through) => format!(", through {}", through),
+        };
+        let table = match slot.table.as_str() {
+            "slot" => String::new(),
+            table => format!("{} slot, ", table),
+        };
+        // story and moon slots have no creator
+        let by = match slot.creator.is_empty() {
+            true => String::new(),
+            false => format!(" by {}", slot.creator),
+        };
+        println!(
+            "{:>10}  {}{} ({}{}{})",
+            slot.id, slot.name, by, table, slot.column, via
+        );
+    }
+    println!("{}", Msg::SlotCount(users.slots.len()));
+    Ok(())
+}
+
+async fn annotate_resource(resource: &str, json: bool, config: &Config) -> Result<()> {
+    // blobs are named after their SHA1, a file of that name wins
+    let data = match parse_sha1(resource) {
+        Ok(hash) if !Path::new(resource).exists() => {
+            fetch_resource(hash, config.archive_path.to_string_lossy().into_owned()).await?
+        }
+        _ => fs::read(resource).map_err(|e| anyhow!("couldn't read {}: {}", resource, e))?,
+    };
+    let annotation = annotate::annotate(&data)?;
+    if json {
+        return JsonOutput::print("annotate-resource", annotation);
+    }
+    print!("{}", annotation.render());
+    Ok(())
+}
+
+async fn dlc_usage(
+    pack: &Path,
+    relations: Option<&Path>,
+    levels: Option<&Path>,
+    parallel_levels: usize,
+    json: bool,
+    config: &Config,
+) -> Result<()> {
+    let pack = DlcPack::read(pack)?;
+    let mut usage = DlcUsage::default();
+    let mut failed = 0;
+    match relations {
+        Some(path) => {
+            let import = ImportData::read(path)?;
+            if !import
+                .relations
+                .iter()
+                .any(|r| r.dependency.starts_with(GUID_DEPENDENCY_PREFIX))
+            {
+                bail!(
+                    "{} has no GUID relations, export it with export_guid_dependencies: true",
+                    path.display()
+                );
+            }
+            let dependents = dependents_index(&import);
+            let mut hash_guids: BTreeMap<[u8; 20], BTreeSet<u32>> = BTreeMap::new();
+            for guid in &pack.guids {
+                let start = format!("{}{}", GUID_DEPENDENCY_PREFIX, guid);
+                for hash in dependents_of(start, &dependents) {
+                    hash_guids.entry(hash).or_default().insert(*guid);
+                }
+            }
+
+            let conn = open_db(&config.database_path)?;
+            let hashes = hash_guids.keys().copied().collect();
+            // a slot can point at the pack through its root level and its icon
+            let mut slots: BTreeMap<(&str, i64), (String, String, BTreeSet<u32>)> = BTreeMap::new();
+            for r in db::slots_referencing(&conn, &hashes)? {
+                let slot = slots
+                    .entry((r.table, r.slot_id))
+                    .or_insert_with(|| (r.name, r.np_handle, BTreeSet::new()));
+                slot.2.extend(&hash_guids[&r.hash]);
+            }
+            for ((_, id), (name, np_handle, guids)) in slots {
+                usage.add_level(&pack, id, Some(name), &np_handle, &guids);
+            }
+        }
+        None => {
+            let level_ids = match levels {
+                Some(path) => read_level_list(path)?,
+                None => Vec::new(),
+            };
+            failed = for_each_level(level_ids, parallel_levels, config, |level, resources| {
+                let guids = contents::guid_requirements(resources)
+                    .into_values()
+                    .flatten()
+                    .collect();
+                usage.add_level(&pack, level.id.into(), None, &level.np_handle, &guids);
+                Ok(())
+            })
+            .await?;
+        }
+    }
+
+    if json {
+        let report = DlcUsageReport {
+            pack: pack.name.clone(),
+            guids: pack.guids.len(),
+            creators: usage
+                .by_level_count()
+                .into_iter()
+                .map(|(np_handle, levels)| DlcCreator {
+                    creator: np_handle.clone(),
+                    levels: levels
+                        .iter()
+                        .map(|level| DlcLevel {
+                            id: level.id,
+                            name: level.name.clone(),
+                            guids: level.guids,
+                        })
+                        .collect(),
+                })
+                .collect(),
+            guid_levels: usage.guid_levels.clone(),
+            failed,
+        };
+        return JsonOutput::print("dlc usage", report);
+    }
+    if failed > 0 {
+        println!("⚠️ {}", Msg::LevelsNotCounted(failed));
+    }
+    if usage.creators.is_empty() {
+        println!("{}", Msg::NoLevelUses(&pack.name));
+        return Ok(());
+    }
+    let no_creator = Msg::NoCreator.to_string();
+    for (np_handle, levels) in usage.by_level_count() {
+        // story and moon slots have no creator
+        let creator = match np_handle.is_empty() {
+            true => no_creator.as_str(),
+            false => np_handle.as_str(),
+        };
+        println!(
+            "{}",
+            Msg::CreatorLevelCount {
+                creator,
+                levels: levels.len(),
+            }
+        );
+        for level in levels {
+            let name = level
+                .name
+                .as_ref()
+                .map(|name| format!("  {}", name))
+                .unwrap_or_default();
+            println!("{:>10}{}  ({} GUIDs)", level.id, name, level.guids);
+        }
+    }
+    println!(
+        "{}",
+        Msg::DlcUsage {
+            levels: usage.levels(),
+            creators: usage.creators.len(),
+            used: usage.guid_levels.len(),
+            guids: pack.guids.len(),
+            pack: &pack.name,
+        }
+    );
+    let mut guids: Vec<(&u32, &usize)> = usage.guid_levels.iter().collect();
+    guids.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+    println!("{}", Msg::MostUsed);
+    for &(&guid, &levels) in guids.iter().take(10) {
+        println!("{}", Msg::GuidLevels { guid, levels });
+    }
+    Ok(())
+}
+
+/// a similarity as a whole percentage, rounded down so 99.9% isn't 100%
+fn percent(similarity: f64) -> u32 {
+    (similarity * 100.0).floor() as u32
+}
+
+async fn find_duplicates(
+    min_similarity: f64,
+    levels: Option<&Path>,
+    parallel_levels: usize,
+    json_file: Option<&Path>,
+    json: bool,
+    config: &Config,
+) -> Result<()> {
+    if !(0.0..=1.0).contains(&min_similarity) {
+        bail!("--min-similarity is between 0 and 1");
+    }
+    let mut catalog = Catalog::default();
+    for (root, closure) in resource_dl::cached_closures()? {
+        catalog.add_content(root, closure.into_iter().map(|(hash, _)| hash));
+    }
+    if let Some(path) = levels {
+        let level_ids = read_level_list(path)?;
+        if level_ids.is_empty() {
+            bail!("{} lists no levels", path.display());
+        }
+        let failed = for_each_level(level_ids, parallel_levels, config, |level, resources| {
+            catalog.add_content(level.root_level, resources.keys().copied());
+            Ok(())
+        })
+        .await?;
+        if failed > 0 {
+            eprintln!("⚠️ {}", Msg::ComparedByRootOnly(failed));
+        }
+    }
+
+    let conn = open_db(&config.database_path)?;
+    let mut slots = db::slots_sharing_root(&conn)?;
+    let roots = catalog.content_roots();
+    if !roots.is_empty() {
+        slots.extend(
+            db::slots_referencing(&conn, &roots)?
+                .into_iter()
+                .filter(|r| r.table == "slot" && r.column == "rootLevel"),
+        );
+    }
+    for slot in slots {
+        let level = DuplicateLevel {
+            id: slot.slot_id,
+            name: slot.name,
+            creator: slot.np_handle,
+        };
+        catalog.add_level(slot.hash, level);
+    }
+
+    let report = catalog.report(min_similarity);
+    if let Some(path) = json_file {
+        report.write(path)?;
+        eprintln!("{}", Msg::FileWritten(path));
+    }
+    if json {
+        return JsonOutput::print("find-duplicates", report);
+    }
+    let no_creator = Msg::NoCreator.to_string();
+    for group in &report.groups {
+        let creators: BTreeSet<&str> = group.levels.iter().map(|l| l.creator.as_str()).collect();
+        match group.kind {
+            MatchKind::Identical => println!(
+                "{}",
+                Msg::SameRootLevel {
+                    root_level: &group.root_levels[0],
+                    levels: group.levels.len(),
+                    creators: creators.len(),
+                }
+            ),
+            MatchKind::Similar => println!(
+                "{}",
+                Msg::AlikeLevels {
+                    percent: percent(group.similarity),
+                    levels: group.levels.len(),
+                    creators: creators.len(),
+                }
+            ),
+        }
+        for level in &group.levels {
+            // story and moon slots have no creator
+            let creator = match level.creator.is_empty() {
+                true => no_creator.as_str(),
+                false => level.creator.as_str(),
+            };
+            println!(
+                "{}",
+                Msg::DuplicateLevel {
+                    id: level.id,
+                    name: &level.name,
+                    creator,
+                }
+            );
+        }
+        for pair in &group.pairs {
+            println!(
+                "    {} ~ {}: {}%",
+                &pair.a[..8],
+                &pair.b[..8],
+                percent(pair.similarity)
+            );
+        }
+    }
+    let identical = report
+        .groups
+        .iter()
+        .filter(|g| g.kind == MatchKind::Identical)
+        .count();
+    println!(
+        "{}",
+        Msg::DuplicatesSummary {
+            groups: report.groups.len(),
+            identical,
+            similar: report.groups.len() - identical,
+            compared: report.compared,
+        }
+    );
+    if report.compared == 0 {
+        println!("{}", Msg::NothingFetchedYet);
+    }
+    Ok(())
+}
+
+async fn level_stats(
+    mut level_ids: Vec<u32>,
+    levels: Option<&Path>,
+    parallel_levels: usize,
+    json: bool,
+    config: &Config,
+) -> Result<()> {
+    if let Some(path) = levels {
+        level_ids.extend(read_level_list(path)?);
+    }
+    if level_ids.is_empty() {
+        bail!("no levels to estimate");
+    }
+    let mut estimates: BTreeMap<&'static str, usize> = BTreeMap::new();
+    let mut report = StatsReport::default();
+    let failed = for_each_level(level_ids, parallel_levels, config, |level, resources| {
+        let complexity = match complexity::estimate(level.root_level, resources) {
+            Ok(complexity) => complexity,
+            Err(e) => {
+                eprintln!(
+                    "❌ {}",
+                    Msg::LevelUnreadable {
+                        id: level.id,
+                        error: &e.to_string(),
+                    }
+                );
+                report.failed += 1;
+                return Ok(());
+            }
+        };
+        *estimates.entry(complexity.estimate.name()).or_default() += 1;
+        if json {
+            report.levels.push(level_stats_entry(level, complexity));
+            return Ok(());
+        }
+        println!(
+            "Level {} by {} ({}): {}, {}",
+            level.id,
+            level.np_handle,
+            level_game_name(level.game),
+            complexity.estimate.name(),
+            complexity.estimate.hint()
+        );
+        println!(
+            "  level data  {} inflated",
+            contents::format_size(complexity.level_data)
+        );
+        for (name, tally) in [
+            ("meshes", &complexity.meshes),
+            ("textures", &complexity.textures),
+            ("materials", &complexity.materials),
+            ("plans", &complexity.plans),
+            ("resources", &complexity.resources),
+        ] {
+            println!(
+                "  {:<10}  {}, {}",
+                name,
+                tally.count,
+                contents::format_size(tally.bytes)
+            );
+        }
+        if !complexity.references.is_empty() {
+            let references: Vec<String> = complexity
+                .references
+                .iter()
+                .map(|(name, r)| match r.guid {
+                    0 => format!("{} {}", name, r.sha1),
+                    guid => format!("{} {} ({} from the game)", name, r.sha1 + guid, guid),
+                })
+                .collect();
+            println!("  references  {}", references.join(", "));
+        }
+        Ok(())
+    })
+    .await?;
+
+    if json {
+        report.failed += failed;
+        return JsonOutput::print("stats", report);
+    }
+    let counts: Vec<String> = estimates
+        .iter()
+        .map(|(name, count)| format!("{} {}", count, name))
+        .collect();
+    println!("{}", counts.join(", "));
+    if failed > 0 {
+        println!("{}", Msg::LevelsNotFetched(failed));
+    }
+    Ok(())
+}
+
+fn level_stats_entry(level: &LevelRecord, complexity: Complexity) -> LevelStats {
+    LevelStats {
+        id: level.id,
+        creator: level.np_handle.clone(),
+        game: level_game_name(level.game).to_string(),
+        estimate: complexity.estimate.name().to_string(),
+        level_data: complexity.level_data,
+        meshes: complexity.meshes,
+        textures: complexity.textures,
+        materials: complexity.materials,
+        plans: complexity.plans,
+        resources: complexity.resources,
+        references: complexity
+            .references
+            .into_iter()
+            .map(|(name, r)| {
+                let count = ReferenceCount {
+                    sha1: r.sha1,
+                    guid: r.guid,
+                };
+                (name.to_string(), count)
+            })
+            .collect(),
+    }
+}
+
+/// `bkp`'s options from the map a script passed to `backup`, and how many
+/// links deep to follow
+fn script_backup_options(
+    options: rhai::Map,
+    config: &Config,
+) -> ScriptResult<(BackupOptions, usize)> {
+    let mut backup = BackupOptions {
+        force_lbp3: config.force_lbp3_backups,
+        language: Language::default(),
+        publisher_name: None,
+        icon_overlay: false,
+        icon: None,
+        icon_texture: false,
+        strict: false,
+        partial_ok: false,
+        force_copyable: false,
+        output_dir: None,
+        existing: ExistingBackup::Fail,
+        dry_run: false,
+        title_id: None,
+        revision: None,
+    };
+    let mut follow_links = 0;
+    for (key, value) in options {
+        let flag = || {
+            value
+                .as_bool()
+                .map_err(|_| script_error(format!("`{}` is true or false", key)))
+        };
+        match key.as_str() {
+            "lbp3" => backup.force_lbp3 |= flag()?,
+            "icon_overlay" => backup.icon_overlay = flag()?,
+            "icon" => backup.icon = Some(value.to_string().into()),
+            "icon_texture" => backup.icon_texture = flag()?,
+            "strict" => backup.strict = flag()?,
+            "partial_ok" => backup.partial_ok = flag()?,
+            "force_copyable" => backup.force_copyable = flag()?,
+            "dry_run" => backup.dry_run = flag()?,
+            "overwrite" if flag()? => backup.existing = ExistingBackup::Overwrite,
+            "skip_existing" if flag()? => backup.existing = ExistingBackup::Skip,
+            "overwrite" | "skip_existing" => {}
+            "output_dir" => backup.output_dir = Some(value.to_string().into()),
+            "publisher_name" => backup.publisher_name = Some(value.to_string()),
+            "language" => {
+                backup.language =
+                    Language::from_str(&value.to_string(), true).map_err(script_error)?
+            }
+            "follow_links" => {
+                follow_links = value
+                    .as_int()
+                    .ok()
+                    .and_then(|n| usize::try_from(n).ok())
+                    .ok_or_else(|| script_error("`follow_links` is a number of links"))?
+            }
+            _ => return Err(script_error(format!("`backup` has no option `{}`", key))),
+        }
+    }
+    Ok((backup, follow_links))
+}
+
+/// Run `script` with the database functions of `script::engine` and these
+/// commands, each waiting for its work to finish:
+///
+/// - `fetch(id)`: like `fetch-level`
+/// - `backup(id)`, `backup(id, #{ partial_ok: true, ... })`: like `bkp`,
+///   the map takes its options in snake case
+/// - `export_xml(ids, path)`, `export_graph(ids, path)`: like `export-xml`
+///   and `export-graph`, a graph path ending in `.db` is a SQLite graph
+async fn run_script(path: &Path, args: Vec<String>, config: &Config) -> Result<()> {
+    let source = fs::read_to_string(path)
+        .map_err(|e| anyhow!("failed to open {}: {}", path.display(), e))?;
+    let config = Arc::new(config.clone());
+    let runtime = tokio::runtime::Handle::current();
+    let name = path.display().to_string();
+
+    // the script blocks on each command, so it gets a thread of its own
+    tokio::task::spawn_blocking(move || -> Result<()> {
+        let mut engine = script::engine(&config.database_path)?;
+
+        let (cfg, rt) = (config.clone(), runtime.clone());
+        engine.register_fn("fetch", move |id: i64| -> ScriptResult<()> {
+            let id = u32::try_from(id).map_err(script_error)?;
+            rt.block_on(fetch_single_level(id, &cfg, None))
+                .map_err(script_error)
+        });
+
+        let (cfg, rt) = (config.clone(), runtime.clone());
+        engine.register_fn("backup", move |id: i64| -> ScriptResult<()> {
+            let (options, follow_links) = script_backup_options(rhai::Map::new(), &cfg)?;
+            rt.block_on(dl_with_links(SlotRef::Id(id), &cfg, options, follow_links))
+                .map_err(script_error)
+        });
+        let (cfg, rt) = (config.clone(), runtime.clone());
+        engine.register_fn(
+            "backup",
+            move |id: i64, options: rhai::Map| -> ScriptResult<()> {
+                let (options, follow_links) = script_backup_options(options, &cfg)?;
+                rt.block_on(dl_with_links(SlotRef::Id(id), &cfg, options, follow_links))
+                    .map_err(script_error)
+            },
+        );
+
+        let (cfg, rt) = (config.clone(), runtime.clone());
+        engine.register_fn(
+            "export_xml",
+            move |ids: rhai::Array, output: &str| -> ScriptResult<()> {
+                let slots = script::level_ids(ids)?
+                    .into_iter()
+                    .map(|id| SlotRef::Id(id.into()))
+                    .collect();
+                rt.block_on(export_xml(
+                    slots,
+                    None,
+                    Language::default(),
+                    Some(Path::new(output)),
+                    &cfg,
+                ))
+                .map_err(script_error)
+            },
+        );
+
+        let (cfg, rt) = (config.clone(), runtime.clone());
+        engine.register_fn(
+            "export_graph",
+            move |ids: rhai::Array, output: &str| -> ScriptResult<()> {
+                let ids = script::level_ids(ids)?;
+                if ids.is_empty() {
+                    return Err(script_error("`export_graph` needs at least one level"));
+                }
+                let format = match output.ends_with(".db") {
+                    true => GraphFormat::Sqlite,
+                    false => GraphFormat::Neo4j,
+                };
+                rt.block_on(export_graph(ids, None, format, Path::new(output), 1, &cfg))
+                    .map_err(script_error)
+            },
+        );
+
+        let mut scope = rhai::Scope::new();
+        let args: rhai::Array = args.into_iter().map(rhai::Dynamic::from).collect();
+        scope.push_constant("ARGS", args);
+        engine
+            .run_with_scope(&mut scope, &source)
+            .map_err(|e| anyhow!("{}: {}", name, e))
+    })
+    .await?
+}
+
+/// Ask `question` on stdin, an empty answer keeps `default`
+fn ask(question: &str, default: &str) -> Result<String> {
+    read_answer(question, default)?
+        .ok_or_else(|| anyhow!("no answer on stdin, use --yes to take the defaults"))
+}
+
+/// `ask`, but None when stdin has ended
+fn read_answer(question: &str, default: &str) -> Result<Option<String>> {
+    print!("{} [{}]: ", question, default);
+    stdout().flush()?;
+    let mut answer = String::new();
+    if std::io::stdin().read_line(&mut answer)? == 0 {
+        return Ok(None);
+    }
+    let answer = answer.trim();
+    Ok(Some(match answer.is_empty() {
+        true => default.to_string(),
+        false => answer.to_string(),
+    }))
+}
+
+fn confirm(question: &str) -> Result<bool> {
+    Ok(ask(question, "y/N")?.eq_ignore_ascii_case("y"))
+}
+
+/// List `levels` numbered and ask which to keep, until the answer makes sense
+fn pick_levels(levels: Vec<LevelRecord>) -> Result<Vec<LevelRecord>> {
+    let no_name = Msg::NoName.to_string();
+    for (i, level) in levels.iter().enumerate() {
+        let name = match level.name.is_empty() {
+            true => no_name.as_str(),
+            false => level.name.as_str(),
+        };
+        println!(
+            "{:>4}. [{}] {} {}",
+            i + 1,
+            level_game_name(level.game),
+            name,
+            Msg::LevelNumber(level.id)
+        );
+    }
+    loop {
+        let answer = read_answer(&Msg::PickQuestion.to_string(), "none")?.ok_or_else(|| {
+            anyhow!("no answer on stdin, --pick needs someone to pick the levels")
+        })?;
+        match parse_picks(&answer, levels.len()) {
+            Ok(picked) => {
+                return Ok(levels
+                    .into_iter()
+                    .enumerate()
+                    .filter(|(i, _)| picked.contains(i))
+                    .map(|(_, level)| level)
+                    .collect());
+            }
+            Err(e) => println!("{}", e),
+        }
+    }
+}
+
+/// The indexes, from 0, of the items of a `count` long list an answer to
+/// `pick_levels` picks
+fn parse_picks(answer: &str, count: usize) -> Result<BTreeSet<usize>> {
+    if answer.eq_ignore_ascii_case("all") {
+        return Ok((0..count).collect());
+    }
+    let mut picked = BTreeSet::new();
+    if answer.eq_ignore_ascii_case("none") {
+        return Ok(picked);
+    }
+    let number = |n: &str| match n.trim().parse::<usize>() {
+        Ok(n) if (1..=count).contains(&n) => Ok(n - 1),
+        _ => Err(anyhow!("`{}` isn't a number from 1 to {}", n.trim(), count)),
+    };
+    for part in answer
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|part| !part.is_empty())
+    {
+        let (first, last) = part.split_once('-').unwrap_or((part, part));
+        let (first, last) = (number(first)?, number(last)?);
+        if first > last {
+            bail!("`{}` goes backwards", part);
+        }
+        picked.extend(first..=last);
+    }
+    Ok(picked)
+}
+
+/// Ask for a path until one passes `check`, or the user keeps one that
+/// doesn't. With `yes` the default is taken whatever `check` says
+fn ask_path(
+    question: &str,
+    default: &Path,
+    yes: bool,
+    check: impl Fn(&Path) -> Result<String>,
+) -> Result<PathBuf> {
+    let mut path = default.to_path_buf();
+    loop {
+        if !yes {
+            path = ask(question, &path.to_string_lossy())?.into();
+        }
+        match check(&path) {
+            Ok(found) => {
+                println!("  ✓ {}", found);
+                return Ok(path);
+            }
+            Err(e) => {
+                println!("  ⚠️ {}", e);
+                if yes || confirm(&format!("  {}", Msg::UseAnyway))? {
+                    return Ok(path);
+                }
+            }
+        }
+    }
+}
+
+fn init(given: InitAnswers, yes: bool, force: bool) -> Result<()> {
+    let config_path = Path::new(CONFIG_FILE);
+    if config_path.exists()
+        && !force
+        && (yes || !confirm(&Msg::ConfigExists(CONFIG_FILE).to_string())?)
+    {
+        bail!("{} already exists, use --force to replace it", CONFIG_FILE);
+    }
+
+    let database_path = ask_path(
+        &Msg::DatabaseQuestion.to_string(),
+        &given.database_path,
+        yes,
+        |path| {
+            let probe = setup::probe_database(path)?;
+            let extras: Vec<String> = probe
+                .extras
+                .iter()
+                .map(|(what, found)| format!("{} {}", if *found { "+" } else { "-" }, what))
+                .collect();
+            Ok(Msg::DatabaseProbed {
+                slots: probe.slots,
+                users: probe.users,
+                extras: &extras.join(", "),
+            }
+            .to_string())
+        },
+    )?;
+    let archive_path = ask_path(
+        &Msg::ArchiveQuestion.to_string(),
+        &given.archive_path,
+        yes,
+        |path| match setup::probe_archive(path) {
+            ArchiveProbe::Split { missing } if missing.is_empty() => {
+                Ok(Msg::ArchiveComplete.to_string())
+            }
+            ArchiveProbe::Split { missing } => {
+                let shown: Vec<&str> = missing.iter().take(8).map(String::as_str).collect();
+                let more = match missing.len() > shown.len() {
+                    true => ", …",
+                    false => "",
+                };
+                bail!(
+                    "{}",
+                    Msg::ArchiveZipsMissing {
+                        missing: missing.len(),
+                        shown: &format!("{}{}", shown.join(", "), more),
+                    }
+                )
+            }
+            ArchiveProbe::SingleZip(zip) => Ok(Msg::ArchiveSingleZip(&zip).to_string()),
+            ArchiveProbe::Tars(count) => Ok(Msg::ArchiveTars(count).to_string()),
+            ArchiveProbe::Remote => Ok(Msg::ArchiveRemote.to_string()),
+            ArchiveProbe::Missing => bail!("{}", Msg::PathMissing(path)),
+            ArchiveProbe::Unknown => bail!("{}", Msg::ArchiveUnknown(path)),
+        },
+    )?;
+    let backup_directory = ask_path(
+        &Msg::BackupFolderQuestion.to_string(),
+        &given.backup_directory,
+        yes,
+        |path| match path.is_dir() {
+            true => Ok(Msg::FolderExists.to_string()),
+            false if path.exists() => bail!("{}", Msg::NotAFolder(path)),
+            false => Ok(Msg::FolderCreatedWithConfig.to_string()),
+        },
+    )?;
+
+    let answers = InitAnswers {
+        database_path,
+        archive_path,
+        backup_directory,
+    };
+    // checked before it's written, so a bad answer leaves the old config alone
+    let config = setup::render_config(&answers);
+    Config::check(&config)?;
+    fs::create_dir_all(&answers.backup_directory).map_err(|e| {
+        anyhow!(
+            "couldn't create {}: {}",
+            answers.backup_directory.display(),
+            e
+        )
+    })?;
+    fs::write(config_path, config)?;
+    println!("{}", Msg::ConfigWritten(CONFIG_FILE));
+    Ok(())
+}
+
+fn compare_creators(old: &Path, new: &Path, json: bool) -> Result<()> {
+    let diff = db_diff::compare(&open_db(old)?, &open_db(new)?)?;
+    if json {
+        return JsonOutput::print("compare-creators", creators_report(&diff));
+    }
+    if diff.is_empty() {
+        println!("{}", Msg::NoDifferences);
+        return Ok(());
+    }
+
+    let day = |ms: Option<i64>| match ms.and_then(chrono::DateTime::from_timestamp_millis) {
+        Some(date) => date.format("%Y-%m-%d %H:%M").to_string(),
+        None => Msg::Never.to_string(),
+    };
+    let no_creator = Msg::NoCreator.to_string();
+    for (np_handle, creator) in &diff.creators {
+        let mark = match creator.change {
+            CreatorChange::Added => "+",
+            CreatorChange::Removed => "-",
+            CreatorChange::Kept => "~",
+        };
+        let name = match np_handle.is_empty() {
+            true => no_creator.as_str(),
+            false => np_handle,
+        };
+        println!("{} {}", mark, name);
+        for level in &creator.levels {
+            match level.change {
+                LevelChange::Added => println!("    + {:>10}  {}", level.id, level.name),
+                LevelChange::Removed => println!("    - {:>10}  {}", level.id, level.name),
+                LevelChange::Updated { old, new } => println!(
+                    "    ~ {:>10}  {} {}",
+                    level.id,
+                    level.name,
+                    Msg::LevelUpdated {
+                        old: &day(old),
+                        new: &day(new),
+                    }
+                ),
+            }
+        }
+    }
+
+    let creators = |change: CreatorChange| {
+        diff.creators
+            .values()
+            .filter(|c| c.change == change)
+            .count()
+    };
+    println!(
+        "{}",
+        Msg::CreatorsCompared {
+            creators_added: creators(CreatorChange::Added),
+            creators_removed: creators(CreatorChange::Removed),
+            added: diff.count(|c| *c == LevelChange::Added),
+            removed: diff.count(|c| *c == LevelChange::Removed),
+            updated: diff.count(|c| matches!(c, LevelChange::Updated { .. })),
+        }
+    );
+    Ok(())
+}
+
+fn creators_report(diff: &DbDiff) -> CreatorsReport {
+    let date = |ms: Option<i64>| ms.and_then(chrono::DateTime::from_timestamp_millis);
+    let creators = diff
+        .creators
+        .iter()
+        .map(|(np_handle, creator)| CreatorDiffEntry {
+            creator: np_handle.clone(),
+            change: match creator.change {
+                CreatorChange::Added => DiffChange::Added,
+                CreatorChange::Removed => DiffChange::Removed,
+                CreatorChange::Kept => DiffChange::Kept,
+            },
+            levels: creator
+                .levels
+                .iter()
+                .map(|level| {
+                    let (change, old_update, new_update) = match level.change {
+                        LevelChange::Added => (DiffChange::Added, None, None),
+                        LevelChange::Removed => (DiffChange::Removed, None, None),
+                        LevelChange::Updated { old, new } => {
+                            (DiffChange::Updated, date(old), date(new))
+                        }
+                    };
+                    LevelDiff {
+                        id: level.id,
+                        name: level.name.clone(),
+                        change,
+                        old_update,
+                        new_update,
+                    }
+                })
+                .collect(),
+        })
+        .collect();
+    CreatorsReport { creators }
+}
+
+/// Find a creator's planets hash in the DB. Users can have one per game, so
+/// each candidate's revision is checked to pick the requested game's planets.
+async fn resolve_creator_planets(
+    np_handle: &str,
+    game: Option<GameVersion>,
+    config: &Config,
+) -> Result<String> {
+    let db = Db::open(&config.database_path)?;
+    let handle = np_handle.to_string();
+    let planets = db
+        .call(move |conn| Ok(get_creator_record(conn, &handle)?.planets))
+        .await?;
+    let candidates: Vec<[u8; 20]> = planets
+        .chunks_exact(20)
+        .map(|c| c.try_into().unwrap())
+        .collect();
+
+    match (candidates.as_slice(), game) {
+        ([], _) => bail!("`{}` has no planets in the database", np_handle),
+        ([hash], None) => return Ok(hex_encode(hash)),
+        _ => {}
+    }
+
+    let mut found = Vec::new();
+    for hash in candidates {
+        let data =
+            match fetch_resource(hash, config.archive_path.to_string_lossy().into_owned()).await {
+                Ok(data) => data,
+                Err(e) => {
+                    eprintln!(
+                        "⚠️ {}",
+                        Msg::PlanetsUnreadable {
+                            hash: &hex_encode(hash),
+                            error: &e.to_string(),
+                        }
+                    );
+                    continue;
+                }
+            };
+        if let ResrcMethod::Binary { revision, .. } = ResrcData::new(&data, false)?.method {
+            found.push((hash, revision.get_gameversion()));
+        }
+    }
+
+    match game {
+        Some(game) => found
+            .iter()
+            .find(|(_, g)| *g == game)
+            .map(|(hash, _)| hex_encode(hash))
+            .ok_or_else(|| anyhow!("`{}` has no {} planets", np_handle, game.get_short_title())),
+        None => {
+            let options: Vec<&str> = found.iter().map(|(_, g)| g.get_short_title()).collect();
+            bail!(
+                "`{}` has planets for several games ({}), pick one with --game",
+                np_handle,
+                options.join(", ")
+            )
+        }
+    }
+}
+
+async fn fetch_planet_resources(hash: &str, config: &Config, format: DumpFormat) -> Result<()> {
+    // 1) hex → [u8;20]
+    let raw = hex::decode(hash)?;
+    if raw.len() != 20 {
+        bail!("hash must be 20 bytes (40 hex chars)");
+    }
+    let mut planet_hash = [0u8; 20];
+    planet_hash.copy_from_slice(&raw);
+
+    // 2) read the SLTb alone, its sub-levels are fetched one by one below
+    let slt_buf = fetch_resource(
+        planet_hash,
+        config.archive_path.to_string_lossy().into_owned(),
+    )
+    .await?;
+
+    // 3) pull every sub-level that isn't already complete in the output folder
+    let out_dir = config
+        .backup_directory
+        .join(format!("planet_{}", hash.to_uppercase()));
+    let mut sink = OutputSink::open(&out_dir, format)?;
+    let pool = DownloadPool::new(config.max_parallel_downloads);
+    let written = fetch_sub_levels(planet_hash, &slt_buf, &mut sink, config, &pool).await?;
+
+    // 4) and the SLTb itself as <hex>
+    sink.write_blob(&hex::encode(planet_hash), &slt_buf)?;
+    println!(
+        "{}",
+        Msg::PlanetFilesWritten {
+            files: written + 1,
+            dir: &out_dir,
+        }
+    );
+
+    // 5) write the planet root‐hash itself
+    let planet_hex = hex::encode(planet_hash);
+    sink.write_file(PLANET_HASH_FILE, &planet_hex)?;
+    println!(
+        "{}",
+        Msg::HashFileWritten {
+            file: PLANET_HASH_FILE,
+            hash: &planet_hex,
+        }
+    );
+
+    // 6) lookup & write the creator’s icon SHA1
+    let db = Db::open(&config.database_path)?;
+    let icon_blob: Option<Vec<u8>> = db
+        .call(move |conn| {
+            Ok(conn
+                .query_row(
+                    // find slot row whose rootLevel equals our planet hash
+                    "SELECT u.icon
+                       FROM slot AS s
+                       JOIN \"user\" AS u ON s.npHandle = u.npHandle
+                      WHERE s.rootLevel = ?1",
+                    [&planet_hash],
+                    |r| r.get::<_, Option<Vec<u8>>>(0),
+                )
+                .optional()?
+                .flatten())
+        })
+        .await?;
+    match icon_blob {
+        Some(icon_blob) => {
+            let icon_hex = hex::encode(&icon_blob);
+            sink.write_file(CREATOR_ICON_HASH_FILE, &icon_hex)?;
+            println!(
+                "{}",
+                Msg::HashFileWritten {
+                    file: CREATOR_ICON_HASH_FILE,
+                    hash: &icon_hex,
+                }
+            );
+        }
+        None => eprintln!(
+            "{}",
+            Msg::Warning(&Msg::NoCreatorIcon(CREATOR_ICON_HASH_FILE))
+        ),
+    }
+
+    sink.finish()
+}
+
+/// Fetch the sub-levels a planet's SLTb points at into `sink`. Sub-levels
+/// an earlier run left complete there are skipped, see `PlanetFetchState`.
+/// Returns how many files were written
+async fn fetch_sub_levels(
+    planet_hash: [u8; 20],
+    slt: &[u8],
+    sink: &mut OutputSink,
+    config: &Config,
+    pool: &DownloadPool,
+) -> Result<usize> {
+    let slt_meta = ResrcData::new(slt, false)?;
+    let mut sub_levels = Vec::new();
+    if let ResrcMethod::Binary { dependencies, .. } = slt_meta.method {
+        for dep in dependencies {
+            if let ResrcDescriptor::Sha1(h) = dep.desc {
+                sub_levels.push(h);
+            }
+        }
+    }
+
+    let state_path = sink.dir().join(PLANET_STATE_FILE);
+    let mut state = PlanetFetchState::read(&state_path)?;
+    let mut written = 0;
+    let mut skipped = 0;
+    for sub_level in sub_levels {
+        if state.is_complete(&planet_hash, &sub_level, |hex| sink.has_blob(hex)) {
+            skipped += 1;
+            continue;
+        }
+        let DownloadResult { resources, .. } = download_level_with_pool(
+            sub_level,
+            None,
+            config.archive_path.to_string_lossy().into_owned(),
+            pool,
+            false,
+        )
+        .await?;
+        for (sha, blob) in &resources {
+            sink.write_blob(&hex_encode(sha), blob)?;
+        }
+        written += resources.len();
+        // recorded once its files are written, an interrupted run never
+        // skips a sub-level it didn't finish
+        state.record(&planet_hash, &sub_level, resources.keys());
+        state.write(&state_path)?;
+        println!(
+            "{}  → {}",
+            pool.prefix(),
+            Msg::SubLevelAdded(&hex_encode(sub_level))
+        );
+    }
+    if skipped > 0 {
+        println!(
+            "{}  → {}",
+            pool.prefix(),
+            Msg::SubLevelsSkipped {
+                skipped,
+                dir: sink.dir(),
+            }
+        );
+    }
+    Ok(written)
+}
+
+async fn fetch_planet_resources_helper_function(
+    planet_hash_str: &str,
+    _creator_handle: &str,
+    config: &Config,
+    level_out_dir: &Path,
+    pool: &DownloadPool,
+) -> Result<()> {
+    // decode the planet‐hash
+    let raw = hex::decode(planet_hash_str)
+        .map_err(|e| anyhow!("invalid hex for planet {}: {}", planet_hash_str, e))?;
+    if raw.len() != 20 {
+        bail!("planet hash must be 20 bytes, got {}", raw.len());
+    }
+    let mut planet_hash = [0u8; 20];
+    planet_hash.copy_from_slice(&raw);
+
+    // 1) read the SLTb alone
+    let slt_buf = fetch_resource(
+        planet_hash,
+        config.archive_path.to_string_lossy().into_owned(),
+    )
+    .await?;
+    println!(
+        "{}{}",
+        pool.prefix(),
+        Msg::PlanetSltFetched(planet_hash_str)
+    );
+
+    // 2) fetch each sub‐level that isn't already complete in the output folder
+    let mut sink = OutputSink::open(level_out_dir, DumpFormat::Flat)?;
+    fetch_sub_levels(planet_hash, &slt_buf, &mut sink, config, pool).await?;
+    sink.finish()?;
+
+    // 3) write SLTb itself as `<hex>` and `<planet_hash>`
+    fs::write(level_out_dir.join(hex_encode(planet_hash)), &slt_buf)?;
+    fs::write(level_out_dir.join(planet_hash_str), &slt_buf)?;
+    println!(
+        "{}→ {}",
+        pool.prefix(),
+        Msg::PlanetSltWritten(planet_hash_str)
+    );
+
+    // 4) fetch the creator’s icon BLOB from the user table
+    // let (user_icon_blob,): (Vec<u8>,) = open_db(&config.database_path)?.query_row(
+    //     "SELECT icon FROM \"user\" WHERE npHandle = ?1",
+    //     [creator_handle],
+    //     |r| Ok((r.get(0)?,)),
+    // )?;
+    // // name it by its own SHA1 hex
+    // let icon_hex = hex_encode(&user_icon_blob);
+    // fs::write(level_out_dir.join(&icon_hex), &user_icon_blob)?;
+    // println!("→ wrote creator’s icon blob as {}", icon_hex);
+
+    Ok(())
+}
+
+/// `fetch-level` entrypoint: look the level up, then fetch it
+async fn fetch_single_level(
+    level_id: u32,
+    config: &Config,
+    publisher_name: Option<&str>,
+) -> Result<()> {
+    let db = Db::open(&config.database_path)?;
+    let (level, creator) = db
+        .call(move |conn| {
+            let level = get_level_record(conn, level_id)?;
+            let creator = get_creator_record(conn, &level.np_handle)?;
+            Ok((level, creator))
+        })
+        .await?;
+    let pool = DownloadPool::new(config.max_parallel_downloads);
+    fetch_level(&level, &creator, &db, config, publisher_name, &pool, true).await?;
+    Ok(())
+}
+
+/// import.json and the realm files are shared, so only one level at a time
+/// may run RealmImporter
+static REALM_IMPORT: AsyncMutex<()> = AsyncMutex::const_new(());
+
+async fn fetch_level(
+    level: &LevelRecord,
+    creator: &CreatorRecord,
+    db: &Db,
+    config: &Config,
+    publisher_name: Option<&str>,
+    pool: &DownloadPool,
+    realm_import: bool,
+) -> Result<ImportData> {
+    if let Some(name) = publisher_name {
+        check_publisher_name(name)?;
+    }
+    let prefix = pool.prefix();
+
+    // 1) DB metadata was prefetched by the caller
+    let level_id = level.id;
+    let root_hash = level.root_level;
+    let icon_sha1_opt = level.icon;
+    let np_handle = &level.np_handle;
+    let published_in = &level.published_in;
+
+    // 4) Download level blobs (including level-icon)
+    let DownloadResult {
+        mut resources,
+        success_count,
+        error_count,
+        ..
+    } = download_level_with_pool(
+        root_hash,
+        icon_sha1_opt,
+        config.archive_path.to_string_lossy().into_owned(),
+        pool,
+        false,
     )
     .await?;
 
@@ -556,275 +3544,2420 @@ async fn fetch_level(level_id: u32, config: &Config) -> Result<()> {
         fs::write(out_dir.join(hex_encode(sha)), data)?;
     }
     println!(
-        "Fetched {} blobs ({}/{}) → {}",
-        resources.len(),
-        success_count,
-        error_count,
-        out_dir.display()
+        "{}{}",
+        prefix,
+        Msg::BlobsFetched {
+            blobs: resources.len(),
+            fetched: success_count,
+            failed: error_count,
+            dir: &out_dir,
+        }
+    );
+
+    // 6) Recurse parent planet if any
+    if let Some(parent_hex) = published_in
+        && parent_hex.len() == 40
+        && parent_hex.chars().all(|c| c.is_ascii_hexdigit())
+    {
+        println!("{}→ {}", prefix, Msg::FetchingParentPlanet(parent_hex));
+        fetch_planet_resources_helper_function(parent_hex, np_handle, config, &out_dir, pool)
+            .await?;
+    }
+
+    // 7) Dump level’s icon (already in `resources`) by SHA1 filename
+    if let Some(icon_sha) = icon_sha1_opt {
+        if let Some(bytes) = resources.get(&icon_sha) {
+            let fname = hex_encode(icon_sha);
+            fs::write(out_dir.join(&fname), bytes)?;
+            println!("{}→ {}", prefix, Msg::LevelIconWritten(&fname));
+        } else {
+            eprintln!(
+                "{}⚠️ {}",
+                prefix,
+                Msg::IconNotDownloaded(&hex_encode(icon_sha))
+            );
+        }
+    }
+
+    // 8) creator.icon SHA1 + planets list, also prefetched
+    let creator_icon_blob = &creator.icon;
+    let planets_blob = &creator.planets;
+
+    // 9) **Read the creator’s icon directly from your archive**
+    // 8) Fetch the creator’s icon via download_level against your local archive
+    if creator_icon_blob.len() == 20 {
+        let mut creator_hash = [0u8; 20];
+        creator_hash.copy_from_slice(creator_icon_blob);
+        // ask download_level to grab exactly that one hash, a missing icon
+        // only costs the export its creator icon
+        let ci_res = match download_level_with_pool(
+            creator_hash,
+            None,
+            config.archive_path.to_string_lossy().into_owned(),
+            pool,
+            false,
+        )
+        .await
+        {
+            Ok(DownloadResult { resources, .. }) => resources,
+            Err(e) => {
+                eprintln!(
+                    "{}⚠️ {}",
+                    prefix,
+                    Msg::CreatorIconFailed {
+                        hash: &hex_encode(creator_hash),
+                        error: &e.to_string(),
+                    }
+                );
+                BTreeMap::new()
+            }
+        };
+        if let Some(ci_bytes) = ci_res.get(&creator_hash) {
+            let fname = hex_encode(creator_hash);
+            fs::write(out_dir.join(&fname), ci_bytes)?;
+            println!("{}→ {}", prefix, Msg::CreatorIconWritten(&fname));
+            // keep it around so it gets exported as an asset too
+            resources.insert(creator_hash, ci_bytes.clone());
+        } else {
+            eprintln!(
+                "{}⚠️ {}",
+                prefix,
+                Msg::CreatorIconNotInArchive(&hex_encode(creator_hash))
+            );
+        }
+    } else {
+        eprintln!(
+            "{}⚠️ {}",
+            prefix,
+            Msg::CreatorIconLength(creator_icon_blob.len())
+        );
+    }
+
+    // 10) Recurse creator’s planets
+    for chunk in planets_blob.chunks(20) {
+        if chunk.len() == 20 {
+            let h = hex_encode(chunk);
+            println!("{}→ {}", prefix, Msg::FetchingCreatorPlanet(&h));
+            fetch_planet_resources_helper_function(&h, np_handle, config, &out_dir, pool).await?;
+        }
+    }
+
+    // 11) Refresh serves icons as PNGs, convert the TEX/GTF level & creator icons
+    let mut converted_icons = BTreeMap::new();
+    let creator_icon_sha: Option<[u8; 20]> = creator_icon_blob.as_slice().try_into().ok();
+    for icon_sha in [icon_sha1_opt, creator_icon_sha].into_iter().flatten() {
+        let Some(icon_resrc) = resources.get(&icon_sha) else {
+            continue;
+        };
+        match icon_to_png(icon_resrc) {
+            Ok(Some(png)) => {
+                let png_sha: [u8; 20] = Sha1::digest(&png).into();
+                fs::write(out_dir.join(hex_encode(png_sha)), &png)?;
+                println!(
+                    "{}→ {}",
+                    prefix,
+                    Msg::IconConverted {
+                        icon: &hex_encode(icon_sha),
+                        png: &hex_encode(png_sha),
+                    }
+                );
+                converted_icons.insert(icon_sha, png);
+            }
+            Ok(None) => {}
+            Err(e) => eprintln!(
+                "{}⚠️ {}",
+                prefix,
+                Msg::IconConversionFailed {
+                    icon: &hex_encode(icon_sha),
+                    error: &e.to_string(),
+                }
+            ),
+        }
+    }
+
+    // 12) Serialize & RealmImporter
+    let publisher = publisher_name.map(str::to_string);
+    let (users, levels) = db
+        .call(move |conn| {
+            Ok((
+                fetch_all_users(conn, level_id)?,
+                fetch_all_levels(conn, level_id, publisher.as_deref())?,
+            ))
+        })
+        .await?;
+    let relations = fetch_all_relations(
+        &resources,
+        config.export_guid_dependencies,
+        &config.relation_filter,
+    )?;
+    let mut assets = fetch_all_assets(&resources, &converted_icons);
+    let mut dep_map: HashMap<String, Vec<String>> = HashMap::new();
+    // assets only list dependencies that are assets themselves, all of them
+    // whatever the relations leave out
+    for r in fetch_all_relations(&resources, false, &RelationFilter::default())? {
+        dep_map.entry(r.dependent).or_default().push(r.dependency);
+    }
+    for a in &mut assets {
+        if let Some(d) = dep_map.get(&a.asset_hash) {
+            a.dependencies = d.clone();
+        }
+    }
+    let dependency_edges = match config.export_dependency_types {
+        true => fetch_dependency_edges(&resources),
+        false => Vec::new(),
+    };
+    let import = ImportData {
+        users,
+        levels,
+        relations,
+        assets,
+        dependency_edges,
+    };
+    if !realm_import {
+        return Ok(import);
+    }
+    let _realm = REALM_IMPORT.lock().await;
+    fs::write("import.json", to_string_pretty(&import)?)?;
+
+    let exe_dir = std::env::current_exe()?.parent().unwrap().to_path_buf();
+    Command::new(exe_dir.join("RealmImporter.exe"))
+        .arg("template.realm")
+        .arg("refreshGameServer.realm")
+        .status()?;
+    println!("{}{}", prefix, Msg::RealmImportDone);
+
+    Ok(import)
+}
+
+/// Fetch every level created by `np_handle`
+/// by calling `fetch_level` on each slot.id
+/// Fetch every level for a creator and dump all blobs into one folder
+/// named after their npHandle, skipping duplicate hashes or missing levels.
+/// Fetch every level for a creator by calling `fetch_level`, but
+/// copy all dumped blobs into one folder named after np_handle.
+/// `fetch-entire-planet` settings
+struct PlanetOptions {
+    link_mode: LinkMode,
+    parallel_levels: usize,
+    export: Option<ExportFormat>,
+    by_game: bool,
+    filter_game: Option<GameVersion>,
+    /// ask which of the levels to fetch
+    pick: bool,
+    format: DumpFormat,
+}
+
+/// `LBP2`, … for a level's `slot.game`, `unknown` if it has none
+fn level_game_name(game: Option<GameVersion>) -> &'static str {
+    game.map_or("unknown", |game| game.get_short_title())
+}
+
+/// Fetch a creator's levels into a folder named after them. Returns false,
+/// without creating the folder, if they have no levels to fetch
+async fn fetch_entire_planet(
+    np_handle: &str,
+    config: &Config,
+    options: &PlanetOptions,
+) -> Result<bool> {
+    let PlanetOptions {
+        link_mode,
+        parallel_levels,
+        export,
+        by_game,
+        filter_game,
+        pick,
+        format,
+    } = *options;
+    // 1) Prefetch every level's metadata and the creator row in one go,
+    //    keeping the database thread around for the exports
+    let db = Db::open(&config.database_path)?;
+    let handle = np_handle.to_string();
+    let (levels, creator) = db
+        .call(move |conn| {
+            let levels = get_creator_levels(conn, &handle)?;
+            let creator = get_creator_record(conn, &handle)?;
+            Ok((levels, creator))
+        })
+        .await?;
+
+    if levels.is_empty() {
+        println!("{}", Msg::NoCreatorLevels(np_handle));
+        return Ok(false);
+    }
+    let mut per_game: BTreeMap<&str, usize> = BTreeMap::new();
+    for level in &levels {
+        *per_game.entry(level_game_name(level.game)).or_default() += 1;
+    }
+    let games = per_game
+        .iter()
+        .map(|(game, count)| format!("{} {}", count, game))
+        .collect::<Vec<_>>()
+        .join(", ");
+    println!(
+        "{}",
+        Msg::CreatorLevels {
+            creator: np_handle,
+            levels: levels.len(),
+            games: &games,
+        }
+    );
+    let mut levels = levels;
+    if let Some(game) = filter_game {
+        levels.retain(|level| level.game == Some(game));
+        if levels.is_empty() {
+            println!(
+                "{}",
+                Msg::NoGameLevels {
+                    game: game.get_short_title(),
+                    creator: np_handle,
+                }
+            );
+            return Ok(false);
+        }
+        println!(
+            "{}",
+            Msg::FetchingOnlyGame {
+                levels: levels.len(),
+                game: game.get_short_title(),
+            }
+        );
+    }
+    if pick {
+        levels = pick_levels(levels)?;
+        if levels.is_empty() {
+            println!("{}", Msg::NothingPicked);
+            return Ok(false);
+        }
+        println!("{}", Msg::FetchingPicked(levels.len()));
+    }
+
+    // 2) Create the user folder, only now so a creator without levels gets none
+    let base = config.backup_directory.join(np_handle);
+    fs::create_dir_all(&base)?;
+
+    // 3) Fetch up to `parallel_levels` levels at once, all sharing one
+    //    download budget, then copy each finished folder into `base`
+    let pool = DownloadPool::new(config.max_parallel_downloads);
+    let mut fetches = stream::iter(&levels)
+        .take_while(|_| future::ready(!budget::exceeded()))
+        .map(|level| {
+            let pool = pool.labeled(format!("[level {}] ", level.id));
+            let (creator, db) = (&creator, &db);
+            async move {
+                println!("\n{}", Msg::LevelHeading(level.id));
+                let result =
+                    fetch_level(level, creator, db, config, None, &pool, export.is_none()).await;
+                (level.id, result)
+            }
+        })
+        .buffer_unordered(parallel_levels.max(1));
+
+    let roots: HashMap<u32, [u8; 20]> = levels.iter().map(|l| (l.id, l.root_level)).collect();
+    let games: HashMap<u32, &str> = levels
+        .iter()
+        .map(|l| (l.id, level_game_name(l.game)))
+        .collect();
+    // game → (fetched, failed)
+    let mut fetched: BTreeMap<&str, (usize, usize)> = BTreeMap::new();
+    // imports and sinks by the folder they go in, `base` itself unless
+    // split by game
+    let mut combined: BTreeMap<PathBuf, ImportData> = BTreeMap::new();
+    let mut sinks: BTreeMap<PathBuf, OutputSink> = BTreeMap::new();
+    while let Some((lvl, result)) = fetches.next().await {
+        let lvl_dir = config.backup_directory.join(format!("level_{}", lvl));
+        let dst_dir = match by_game {
+            true => base.join(level_game_dir(&lvl_dir, roots[&lvl])),
+            false => base.clone(),
+        };
+
+        // 3a) `fetch_level` did the dump, and the Realm import unless we combine them
+        let counts = fetched.entry(games[&lvl]).or_default();
+        match result {
+            Ok(import) => {
+                counts.0 += 1;
+                combined.entry(dst_dir.clone()).or_default().merge(import)
+            }
+            Err(e) => {
+                counts.1 += 1;
+                eprintln!(
+                    "❌ {}",
+                    Msg::LevelSkipped {
+                        id: lvl,
+                        error: &e.to_string(),
+                    }
+                );
+                continue;
+            }
+        }
+
+        // 3b) copy files from `level_<id>` into `base`, or its game's
+        //     folder. Assets shared between games are linked into each
+        if !lvl_dir.exists() {
+            eprintln!("⚠️  {}", Msg::LevelFolderMissing(&lvl_dir));
+            continue;
+        }
+        let sink = match sinks.entry(dst_dir.clone()) {
+            btree_map::Entry::Occupied(e) => e.into_mut(),
+            btree_map::Entry::Vacant(e) => e.insert(OutputSink::open(&dst_dir, format)?),
+        };
+        for entry in fs::read_dir(&lvl_dir)? {
+            let entry = entry?;
+            let file_name = entry.file_name();
+            let src_path = entry.path();
+            if let Some(hex) = file_name.to_str().filter(|n| is_blob_name(n)) {
+                // duplicates are skipped by the sink
+                sink.place_blob(hex, &src_path, link_mode)?;
+                continue;
+            }
+            let dst_path = dst_dir.join(&file_name);
+
+            if file_name == PLANET_STATE_FILE && dst_path.exists() {
+                // every level's planets count, not just the first one's
+                merge_planet_state(&src_path, &dst_path)?;
+                continue;
+            }
+            if dst_path.exists() {
+                // skip duplicates
+                continue;
+            }
+            link_or_copy(&src_path, &dst_path, link_mode)?;
+        }
+    }
+    for sink in sinks.into_values() {
+        sink.finish()?;
+    }
+
+    if let Some(export) = export {
+        for (dir, import) in &combined {
+            let path = dir.join(export.file_name());
+            export.write(&path, import)?;
+            println!(
+                "{}",
+                Msg::ImportWritten {
+                    path: &path,
+                    levels: import.levels.len(),
+                    assets: import.assets.len(),
+                }
+            );
+        }
+    }
+
+    println!();
+    for (game, &(fetched, failed)) in &fetched {
+        println!(
+            "{}",
+            Msg::GameFetched {
+                game,
+                fetched,
+                failed,
+            }
+        );
+    }
+    println!(
+        "{}",
+        Msg::CreatorDumped {
+            creator: np_handle,
+            dir: &base,
+        }
+    );
+    Ok(true)
+}
+
+/// Add the sub-levels recorded in the `planets.json` at `src` to the one at `dst`
+fn merge_planet_state(src: &Path, dst: &Path) -> Result<()> {
+    let mut state = PlanetFetchState::read(dst)?;
+    state.merge(PlanetFetchState::read(src)?);
+    state.write(dst)
+}
+
+/// Extra outputs the creator dumps can write next to the blobs
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+enum ExportFormat {
+    /// one import.json with the users, levels, assets and relations of
+    /// every dumped level, for Refresh's RealmImporter
+    Refresh,
+    /// the same as one import.bson, ObjectIds and dates as native BSON types
+    RefreshBson,
+}
+
+impl ExportFormat {
+    fn file_name(self) -> &'static str {
+        match self {
+            ExportFormat::Refresh => REFRESH_IMPORT_FILE,
+            ExportFormat::RefreshBson => REFRESH_BSON_IMPORT_FILE,
+        }
+    }
+
+    fn write(self, path: &Path, import: &ImportData) -> Result<()> {
+        let data = match self {
+            ExportFormat::Refresh => to_string_pretty(import)?.into_bytes(),
+            ExportFormat::RefreshBson => import.to_bson()?,
+        };
+        // write-then-rename so an interrupted run never leaves half an import
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        fs::write(&tmp, data)?;
+        fs::rename(&tmp, path)?;
+        Ok(())
+    }
+}
+
+/// `read-from-file` settings that apply to every creator
+struct DumpOptions {
+    link_mode: LinkMode,
+    delay_ms: u64,
+    parallel_levels: usize,
+    export: Option<ExportFormat>,
+    by_game: bool,
+    filter_game: Option<GameVersion>,
+    format: DumpFormat,
+}
+
+async fn read_from_file(
+    config: &Config,
+    resume: Option<&Path>,
+    options: DumpOptions,
+) -> Result<()> {
+    // 1) load creators.txt
+    let file =
+        File::open("creators.txt").map_err(|e| anyhow!("failed to open creators.txt: {}", e))?;
+    let creators: Vec<String> = BufReader::new(file)
+        .lines()
+        .map(|line| {
+            let s = line.map_err(|e| anyhow!("read error: {}", e))?;
+            let t = s.trim().to_string();
+            if t.is_empty() {
+                Err(anyhow!("skipping empty line"))
+            } else {
+                Ok(t)
+            }
+        })
+        .filter_map(Result::ok)
+        .collect();
+
+    if creators.is_empty() {
+        bail!("creators.txt is empty");
+    }
+
+    // 2) reuse the folder we're resuming, or take the next free fileDumpN
+    //    in the backup directory
+    let out_dir: PathBuf = match resume {
+        Some(path) => {
+            if !path.is_dir() {
+                bail!("can't resume, {} is not a folder", path.display());
+            }
+            path.to_path_buf()
+        }
+        None => {
+            fs::create_dir_all(&config.backup_directory)?;
+            let mut idx = 0;
+            loop {
+                let path = config.backup_directory.join(format!("fileDump{}", idx));
+                // not create_dir_all, of two runs only one gets a folder
+                match fs::create_dir(&path) {
+                    Ok(()) => break path,
+                    Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => idx += 1,
+                    Err(e) => bail!("could not create {}: {}", path.display(), e),
+                }
+            }
+        }
+    };
+    // a resumed folder can be anywhere, and two runs resuming it would
+    // both write its status file and import
+    let _lock = RunLock::acquire(&out_dir, run_lock::forced())?;
+    let status_path = out_dir.join(DUMP_STATUS_FILE);
+    let mut status = DumpStatus::read(&status_path)?;
+    let filter_game = options.filter_game.map(|game| game.get_short_title());
+
+    // 3) for each creator: fetch + copy, recording how it went. The sinks
+    //    stay open for the run, by the folder they go in, so a ZIP isn't
+    //    reopened for every creator
+    let mut sinks: BTreeMap<PathBuf, OutputSink> = BTreeMap::new();
+    let mut first = true;
+    for creator in &creators {
+        if let Some(entry) = status.creators.get(creator)
+            && entry.is_finished(filter_game)
+        {
+            println!(
+                "⏭️  {}",
+                Msg::CreatorAlreadyDone {
+                    creator,
+                    state: &format!("{:?}", entry.state),
+                }
+            );
+            continue;
+        }
+        if budget::exceeded() {
+            break;
+        }
+        if !first && options.delay_ms > 0 {
+            tokio::time::sleep(Duration::from_millis(options.delay_ms)).await;
+        }
+        first = false;
+
+        println!("🔄 {}", Msg::FetchingCreator(creator));
+        let (state, error) =
+            match dump_creator(creator, &out_dir, &mut sinks, config, &options).await {
+                // levels the time limit cut off are fetched on `--resume`
+                Ok(_) if budget::was_exceeded() => (CreatorState::Partial, None),
+                Ok(true) => (CreatorState::Done, None),
+                Ok(false) => (CreatorState::Skipped, None),
+                Err(e) => {
+                    eprintln!(
+                        "❌ {}",
+                        Msg::CreatorFailed {
+                            creator,
+                            error: &e.to_string(),
+                        }
+                    );
+                    (CreatorState::Failed, Some(e.to_string()))
+                }
+            };
+        let entry = CreatorStatus {
+            state,
+            error,
+            filter_game: filter_game.map(str::to_string),
+        };
+        status.creators.insert(creator.clone(), entry);
+        status.write(&status_path)?;
+    }
+    for sink in sinks.into_values() {
+        sink.finish()?;
+    }
+
+    let failed = status
+        .creators
+        .values()
+        .filter(|c| c.state == CreatorState::Failed)
+        .count();
+    if budget::was_exceeded() {
+        println!("⏱️  {}", Msg::DumpStoppedEarly(&out_dir));
+        return Ok(());
+    }
+    println!("✅ {}", Msg::DumpDone(&out_dir));
+    if failed > 0 {
+        println!(
+            "⚠️  {}",
+            Msg::CreatorsFailed {
+                failed,
+                dir: &out_dir,
+            }
+        );
+    }
+    Ok(())
+}
+
+/// Game folder (`LBP2`, …) of a dumped level, from the revision of its
+/// rootLevel. `unknown` if that isn't in the dump or can't be read
+fn level_game_dir(lvl_dir: &Path, root: [u8; 20]) -> &'static str {
+    let Ok(data) = fs::read(lvl_dir.join(hex_encode(root))) else {
+        return "unknown";
+    };
+    match ResrcData::new(&data, false) {
+        Ok(ResrcData {
+            method: ResrcMethod::Binary { revision, .. },
+            ..
+        }) => revision.get_gameversion().get_short_title(),
+        _ => "unknown",
+    }
+}
+
+/// Fetch one creator's planet and place their blobs into `out_dir`, through
+/// the run's sink of each folder. Returns false if the creator had nothing
+/// to dump.
+async fn dump_creator(
+    creator: &str,
+    out_dir: &Path,
+    sinks: &mut BTreeMap<PathBuf, OutputSink>,
+    config: &Config,
+    options: &DumpOptions,
+) -> Result<bool> {
+    let planet_options = PlanetOptions {
+        link_mode: options.link_mode,
+        parallel_levels: options.parallel_levels,
+        export: options.export,
+        by_game: options.by_game,
+        filter_game: options.filter_game,
+        pick: false,
+        format: DumpFormat::Flat,
+    };
+    if !fetch_entire_planet(creator, config, &planet_options).await? {
+        return Ok(false);
+    }
+
+    let src = config.backup_directory.join(creator);
+    if !src.exists() {
+        eprintln!("⚠️  {}", Msg::NoCreatorFolder { creator, dir: &src });
+        return Ok(false);
+    }
+    place_creator_dump(&src, out_dir, sinks, options)?;
+    // game folders of `--by-game` go into the same game folder of the dump
+    for entry in fs::read_dir(&src)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            let dst = out_dir.join(entry.file_name());
+            fs::create_dir_all(&dst)?;
+            place_creator_dump(&entry.path(), &dst, sinks, options)?;
+        }
+    }
+    Ok(true)
+}
+
+/// Place the blobs of a creator folder into `out_dir`, and merge its import
+/// into the one there
+fn place_creator_dump(
+    src: &Path,
+    out_dir: &Path,
+    sinks: &mut BTreeMap<PathBuf, OutputSink>,
+    options: &DumpOptions,
+) -> Result<()> {
+    // fold the creator's import into the one for the whole dump, which is
+    // rewritten after every creator so a resumed dump keeps what it had
+    if let Some(export) = options.export
+        && src.join(export.file_name()).exists()
+    {
+        let creator_import = ImportData::read(&src.join(export.file_name()))?;
+        let dump_import_path = out_dir.join(export.file_name());
+        let mut dump_import = match dump_import_path.exists() {
+            true => ImportData::read(&dump_import_path)?,
+            false => ImportData::default(),
+        };
+        dump_import.merge(creator_import);
+        export.write(&dump_import_path, &dump_import)?;
+    }
+
+    let sink = match sinks.entry(out_dir.to_path_buf()) {
+        btree_map::Entry::Occupied(e) => e.into_mut(),
+        btree_map::Entry::Vacant(e) => e.insert(OutputSink::open(out_dir, options.format)?),
+    };
+    for entry in fs::read_dir(src)? {
+        let entry = entry?;
+        if entry.file_type()?.is_file()
+            && entry.file_name() != REFRESH_IMPORT_FILE
+            && entry.file_name() != REFRESH_BSON_IMPORT_FILE
+        {
+            if let Some(hex) = entry.file_name().to_str().filter(|n| is_blob_name(n)) {
+                // the sink skips blobs an earlier, interrupted run placed
+                sink.place_blob(hex, &entry.path(), options.link_mode)?;
+                continue;
+            }
+            let dst = out_dir.join(entry.file_name());
+            if entry.file_name() == PLANET_STATE_FILE && dst.exists() {
+                merge_planet_state(&entry.path(), &dst)?;
+                continue;
+            }
+            if dst.exists() {
+                // already placed by an earlier, interrupted run
+                continue;
+            }
+            link_or_copy(&entry.path(), &dst, options.link_mode)?;
+        }
+    }
+    Ok(())
+}
+
+/// Use the working copy `db upgrade` made, as long as it's current
+fn use_working_db(config: &mut Config) -> Result<()> {
+    let working = config.cache_dir()?.join(WORKING_DB_FILE);
+    match working_db::is_current(&config.database_path, &working) {
+        Some(true) => config.database_path = working,
+        Some(false) => eprintln!(
+            "{}",
+            Msg::Warning(&Msg::WorkingDbStale {
+                working: &working,
+                database: &config.database_path,
+            })
+        ),
+        None => {}
+    }
+    Ok(())
+}
+
+fn db_upgrade(output: Option<PathBuf>, config: &Config) -> Result<()> {
+    let dest = match output {
+        Some(path) => path,
+        None => config.cache_dir()?.join(WORKING_DB_FILE),
+    };
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let start = Instant::now();
+    let report = working_db::normalize(&config.database_path, &dest)?;
+    println!(
+        "{}",
+        Msg::DbUpgraded {
+            path: &dest,
+            slots: report.slots,
+            users: report.users,
+            elapsed: start.elapsed(),
+        }
     );
+    println!(
+        "  {}",
+        Msg::ValuesRetyped {
+            retyped: report.retyped,
+            filled: report.filled,
+        }
+    );
+    if report.unconvertible > 0 {
+        println!("  ⚠️ {}", Msg::ValuesUnconvertible(report.unconvertible));
+    }
+    println!(
+        "  {}",
+        Msg::RowsDropped {
+            duplicate_users: report.duplicate_users,
+            duplicate_slots: report.duplicate_slots,
+            dropped_users: report.dropped_users,
+            orphans: report.orphans,
+        }
+    );
+    if report.slots_without_user > 0 {
+        println!("  {}", Msg::SlotsWithoutUser(report.slots_without_user));
+    }
+    Ok(())
+}
 
-    // 6) Recurse parent planet if any
-    if let Some(ref parent_hex) = published_in {
-        if parent_hex.len() == 40 && parent_hex.chars().all(|c| c.is_ascii_hexdigit()) {
-            println!("→ Fetching parent planet {}", parent_hex);
-            fetch_planet_resources_helper_function(parent_hex, &np_handle, config, &out_dir)
-                .await?;
+fn report_cache_cleanup(cleanup: &resource_dl::CacheCleanup) {
+    if cleanup.temp_files + cleanup.empty_files > 0 {
+        eprintln!(
+            "{}",
+            Msg::CacheCleaned {
+                unfinished: cleanup.temp_files,
+                empty: cleanup.empty_files,
+            }
+        );
+    }
+}
+
+fn gc(dir: Option<&Path>, delete: bool, config: &Config) -> Result<()> {
+    // the cache is cleaned up at startup once a day, gc does it every time
+    if delete {
+        report_cache_cleanup(&resource_dl::clean_cache(&config.cache_dir()?)?);
+    }
+    let root = dir.unwrap_or(&config.backup_directory);
+    let report = dump_gc::analyze(root)?;
+
+    let (mut blobs, mut bytes) = (0, 0);
+    for dump in &report.dumps {
+        if dump.unreachable.is_empty() {
+            continue;
+        }
+        let size = dump.unreachable_bytes();
+        println!(
+            "{}",
+            Msg::UnreachableBlobs {
+                dir: &dump.dir,
+                unreachable: dump.unreachable.len(),
+                blobs: dump.blobs,
+                size: &contents::format_size(size),
+            }
+        );
+        if delete {
+            dump_gc::delete(dump)?;
+        } else {
+            for blob in &dump.unreachable {
+                println!("    {}", blob.path.display());
+            }
+        }
+        blobs += dump.unreachable.len();
+        bytes += size;
+    }
+    for dir in &report.without_manifest {
+        eprintln!("{}", Msg::Warning(&Msg::GcNoManifest(dir)));
+    }
+    for dir in &report.zipped {
+        eprintln!(
+            "{}",
+            Msg::Warning(&Msg::GcZipped(&dir.join(sink::ZIP_SINK_FILE)))
+        );
+    }
+
+    println!(
+        "{}",
+        Msg::GcDone {
+            deleted: delete,
+            blobs,
+            size: &contents::format_size(bytes),
+            dumps: report.dumps.len(),
+        }
+    );
+    if !delete && blobs > 0 {
+        println!("{}", Msg::GcDeleteHint);
+    }
+    Ok(())
+}
+
+fn ingest(dirs: &[PathBuf], config: &Config) -> Result<()> {
+    let ingest_dir = config.ingest_dir()?;
+    for dir in dirs {
+        let report = ingest::ingest_dir(dir, &ingest_dir)?;
+        println!(
+            "{}",
+            Msg::Ingested {
+                dir,
+                added: report.added,
+                known: report.known,
+                unreadable: report.unreadable,
+            }
+        );
+        if !report.mismatched.is_empty() {
+            println!("  ⚠️ {}", Msg::NameMismatch(report.mismatched.len()));
+            for path in &report.mismatched {
+                println!("    {}", path.display());
+            }
+        }
+    }
+    println!("{}", Msg::IngestDir(&ingest_dir));
+    Ok(())
+}
+
+/// Decrypt a save and pull out its resources, warning about damage
+fn open_save(path: &Path) -> Result<save::ExtractResult> {
+    let data = save::read_save(path)?;
+    let archive = save::parse_far(&data)?;
+    println!(
+        "{}",
+        Msg::FarArchive {
+            kind: &format!("{:?}", archive.kind),
+            entries: archive.entries.len(),
+        }
+    );
+    if archive.hashinate_ok == Some(false) {
+        eprintln!("{}", Msg::Warning(&Msg::SaveChecksumMismatch));
+    }
+
+    let extracted = save::extract(&data, &archive);
+    if extracted.out_of_range + extracted.bad_hash > 0 {
+        eprintln!(
+            "{}",
+            Msg::Warning(&Msg::DamagedEntriesSkipped {
+                out_of_range: extracted.out_of_range,
+                bad_hash: extracted.bad_hash,
+            })
+        );
+    }
+    Ok(extracted)
+}
+
+fn far_extract(path: &Path, output: Option<&Path>, format: DumpFormat) -> Result<()> {
+    let output = match output {
+        Some(dir) => dir.to_path_buf(),
+        None => {
+            let name = path
+                .file_stem()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_else(|| "archive".to_string());
+            path.with_file_name(format!("{}_extracted", name))
+        }
+    };
+    let mut sink = OutputSink::open(&output, format)?;
+
+    let (mut written, mut out_of_range, mut bad_hash) = (0, 0, 0);
+    if path.is_dir() {
+        // an encrypted save has to be decrypted as a whole anyway
+        let extracted = open_save(path)?;
+        out_of_range = extracted.out_of_range;
+        bad_hash = extracted.bad_hash;
+        for (sha1, blob) in &extracted.resources {
+            sink.write_blob(&hex_encode(sha1), blob)?;
+            written += 1;
+        }
+    } else {
+        let mut file =
+            File::open(path).map_err(|e| anyhow!("couldn't open {}: {}", path.display(), e))?;
+        let archive = save::read_far_table(&mut file)?;
+        println!(
+            "{}",
+            Msg::FarArchive {
+                kind: &format!("{:?}", archive.kind),
+                entries: archive.entries.len(),
+            }
+        );
+        for entry in &archive.entries {
+            let hex = hex_encode(entry.sha1);
+            if sink.has_blob(&hex) {
+                written += 1;
+                continue;
+            }
+            match save::read_entry(&mut file, entry)? {
+                Ok(blob) => {
+                    sink.write_blob(&hex, &blob)?;
+                    written += 1;
+                }
+                Err(EntryDamage::OutOfRange) => out_of_range += 1,
+                Err(EntryDamage::BadHash) => bad_hash += 1,
+            }
+        }
+    }
+    sink.finish()?;
+
+    if out_of_range + bad_hash > 0 {
+        eprintln!(
+            "{}",
+            Msg::Warning(&Msg::DamagedEntriesSkipped {
+                out_of_range,
+                bad_hash,
+            })
+        );
+    }
+    println!(
+        "{}",
+        Msg::BlobsWritten {
+            blobs: written,
+            path: &output,
+        }
+    );
+    Ok(())
+}
+
+/// `rebuild-from-save`: a backup for every level in the save. The save's
+/// blobs go into the resource cache, so the levels are fetched like any
+/// other and whatever the save lacks comes from the archive
+async fn rebuild_from_save(
+    path: &Path,
+    include_unnamed: bool,
+    options: BackupOptions,
+    config: &Config,
+) -> Result<()> {
+    if let Some(publisher_name) = &options.publisher_name {
+        check_publisher_name(publisher_name)?;
+    }
+    // the downloads take the save's resources before the archive's, with or
+    // without the disk cache
+    let resources = Arc::new(open_save(path)?.resources);
+    resource_dl::set_save_blobs(resources.clone());
+
+    let mut slots = save_slots::find_slots(&resources);
+    let named: BTreeSet<[u8; 20]> = slots.iter().map(|s| s.root_level).collect();
+    let unnamed: Vec<[u8; 20]> = resources
+        .iter()
+        .filter(|(hash, blob)| blob.starts_with(b"LVL") && !named.contains(*hash))
+        .map(|(hash, _)| *hash)
+        .collect();
+    println!(
+        "{}",
+        Msg::SaveLevelsFound {
+            named: slots.len(),
+            unnamed: unnamed.len(),
+        }
+    );
+    if include_unnamed {
+        slots.extend(unnamed.into_iter().map(|root_level| SaveSlot {
+            slot_id: None,
+            root_level,
+            icon: ResrcDescriptor::Guid(0),
+            name: format!("Recovered level {}", &hex_encode(root_level)[..8]),
+            description: String::new(),
+            author: String::new(),
+            background: None,
+        }));
+    } else if !unnamed.is_empty() {
+        println!("{}", Msg::IncludeUnnamedHint);
+    }
+
+    let db = Db::open(&config.database_path)?;
+    let mut written = 0;
+    let mut failed = 0;
+    for slot in slots {
+        let revision = match ResrcData::new(&resources[&slot.root_level], false) {
+            Ok(ResrcData {
+                method: ResrcMethod::Binary { revision, .. },
+                ..
+            }) => revision,
+            _ => {
+                eprintln!(
+                    "{}",
+                    Msg::Warning(&Msg::UnreadableSaveLevel(&hex_encode(slot.root_level)))
+                );
+                failed += 1;
+                continue;
+            }
+        };
+        let creator = slot.author.clone();
+        let mut np_handle = options.publisher_name.clone().unwrap_or(slot.author);
+        // the slot's author name can be longer than a PSN handle
+        if np_handle.len() > 16 {
+            np_handle.clear();
+        }
+        let slot_info = SlotInfo {
+            id: slot.slot_id.map_or(0, i64::from),
+            guid: None,
+            name: slot.name,
+            description: slot.description,
+            np_handle,
+            root_level: slot.root_level,
+            icon: slot.icon,
+            game: revision.get_gameversion(),
+            initially_locked: false,
+            is_sub_level: false,
+            background_guid: slot.background,
+            shareable: false,
+            author_labels: Vec::new(),
+            leveltype: LevelType::Cooperative,
+            min_players: None,
+            max_players: None,
+            enforce_min_max_players: false,
+            same_screen_game: false,
+            is_adventure_planet: false,
+            planet_decorations: None,
+            adventure_shared: Vec::new(),
+        };
+
+        println!();
+        println!("{}", Msg::LevelFound);
+        println!("{}", Msg::LevelName(&slot_info.name));
+        println!("{}", Msg::LevelCreator(&slot_info.np_handle));
+        println!("{}", Msg::LevelGame(slot_info.game.get_short_title()));
+        // published levels are named like a `bkp` of their slot, the ones
+        // only in the save after their rootLevel
+        let folder_id = match slot.slot_id {
+            Some(id) => hex::encode_upper(id.to_be_bytes()),
+            None => hex::encode_upper(&slot.root_level[..4]),
+        };
+        match backup_slot(slot_info, &creator, &folder_id, &db, config, &options).await {
+            Ok(_) => written += 1,
+            Err(e) => {
+                eprintln!(
+                    "{}",
+                    Msg::Warning(&Msg::SaveLevelFailed {
+                        root_level: &hex_encode(slot.root_level),
+                        error: &e.to_string(),
+                    })
+                );
+                failed += 1;
+            }
+        }
+    }
+
+    println!(
+        "\n{}",
+        Msg::SaveLevelsDone {
+            written,
+            failed,
+            dry_run: options.dry_run,
+        }
+    );
+    if written == 0 && failed > 0 {
+        bail!("none of the levels in the save could be backed up");
+    }
+    Ok(())
+}
+
+/// `refresh-params`: PARAM.SFO and PARAM.PFD of the backup in `dir` made
+/// again from the slot list in its save archive, for a fixed `sfo` setting
+/// or account ID without fetching the level again
+fn refresh_params(dir: &Path, language: Option<Language>, config: &Config) -> Result<()> {
+    let bkp_name = dir
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .ok_or_else(|| anyhow!("`{}` isn't a backup folder", dir.display()))?;
+    let meta = fs::read(dir.join(BACKUP_META_FILE))
+        .ok()
+        .and_then(|data| serde_json::from_slice::<BackupMeta>(&data).ok());
+    // a title in another language than the backup was made with would
+    // change it without anyone asking
+    let Some(language) = language.or(meta.as_ref().and_then(|meta| meta.language)) else {
+        bail!(
+            "{} doesn't say which language the backup was made with, pass --language",
+            dir.display()
+        );
+    };
+    let resources = open_save(dir)?.resources;
+    let slt = resources
+        .values()
+        .find(|blob| blob.starts_with(b"SLT"))
+        .ok_or_else(|| {
+            anyhow!(
+                "{} has no slot list, it isn't a level backup",
+                dir.display()
+            )
+        })?;
+    let revision = match ResrcData::new(slt, false)?.method {
+        ResrcMethod::Binary { revision, .. } => revision,
+        _ => bail!("the slot list of {} isn't a binary resource", dir.display()),
+    };
+    let slot = save_slots::find_slots(&resources)
+        .into_iter()
+        .next()
+        .ok_or_else(|| anyhow!("couldn't read the level's slot from {}", dir.display()))?;
+    // the slot ID isn't in the slot list as the backup has it
+    let slot_id = meta.map_or(0, |meta| meta.slot_id);
+
+    let gameversion = revision.get_gameversion();
+    let slot_info = SlotInfo {
+        id: slot_id,
+        guid: None,
+        name: slot.name,
+        description: slot.description,
+        np_handle: slot.author,
+        root_level: slot.root_level,
+        icon: slot.icon,
+        game: gameversion,
+        initially_locked: false,
+        is_sub_level: false,
+        background_guid: slot.background,
+        shareable: false,
+        author_labels: Vec::new(),
+        leveltype: LevelType::Cooperative,
+        min_players: None,
+        max_players: None,
+        enforce_min_max_players: false,
+        same_screen_game: false,
+        is_adventure_planet: bkp_name.contains("ADVLBP3AAZ"),
+        planet_decorations: None,
+        adventure_shared: Vec::new(),
+    };
+    println!("{}", Msg::LevelName(&slot_info.name));
+    println!("{}", Msg::LevelCreator(&slot_info.np_handle));
+    println!("{}", Msg::LevelGame(gameversion.get_short_title()));
+
+    let sfo = make_sfo(
+        &slot_info,
+        &bkp_name,
+        dir,
+        &gameversion,
+        language,
+        &config.sfo,
+        &config.text_limits,
+    )?;
+    let pfd_version = if gameversion == GameVersion::Lbp3 {
+        4
+    } else {
+        3
+    };
+    make_pfd(pfd_version, sfo, dir)?;
+    println!("{}", Msg::ParamsWritten(&dir.display().to_string()));
+    Ok(())
+}
+
+fn save_decrypt(
+    path: &Path,
+    output: Option<PathBuf>,
+    into_cache: bool,
+    config: &Config,
+) -> Result<()> {
+    let extracted = open_save(path)?;
+
+    let output = match output {
+        Some(dir) => dir,
+        None => {
+            let name = path
+                .file_stem()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_else(|| "save".to_string());
+            config.backup_directory.join(format!("save_{}", name))
+        }
+    };
+    fs::create_dir_all(&output)?;
+    let cache_dir = config.cache_dir()?;
+    if into_cache {
+        fs::create_dir_all(&cache_dir)?;
+    }
+
+    let mut levels = 0;
+    for (sha1, blob) in &extracted.resources {
+        let hex = hex_encode(sha1);
+        fs::write(output.join(&hex), blob)?;
+        if into_cache {
+            resource_dl::write_cache_file(&cache_dir, &hex, blob)?;
+        }
+        if blob.starts_with(b"LVL") {
+            levels += 1;
+        }
+    }
+
+    println!(
+        "{}",
+        Msg::SaveExtracted {
+            resources: extracted.resources.len(),
+            levels,
+            path: &output,
+        }
+    );
+    if into_cache {
+        println!("{}", Msg::AddedToCache);
+    }
+    Ok(())
+}
+
+async fn archive_verify(
+    shard: Option<archive_verify::Shard>,
+    prefix: Option<&str>,
+    state_path: &Path,
+    restart: bool,
+    config: &Config,
+) -> Result<()> {
+    let layout = resource_dl::archive_layout(&config.archive_path)?;
+    archive_verify::check_layout(&layout)?;
+    let (scope, prefixes) = match prefix {
+        Some(prefix) => (format!("prefix {}", prefix), repack::zip_prefixes(prefix)?),
+        None => {
+            let shard = shard.unwrap_or(archive_verify::Shard::ALL);
+            (shard.to_string(), shard.prefixes())
+        }
+    };
+    let mut state = match restart {
+        true => VerifyState::default(),
+        false => VerifyState::read(state_path)?,
+    };
+    if state.zips.is_empty() {
+        state.shard = scope.clone();
+    } else if state.shard != scope {
+        bail!(
+            "{} has the progress of {}, not {}. Use another --state for this one, or --restart to discard it",
+            state_path.display(),
+            state.shard,
+            scope
+        );
+    }
+
+    // ZIPs that couldn't be opened are tried again, a share may have been down
+    let todo: Vec<String> = prefixes
+        .iter()
+        .filter(|prefix| {
+            state
+                .zips
+                .get(*prefix)
+                .is_none_or(|zip| zip.error.is_some())
+        })
+        .cloned()
+        .collect();
+    match todo.len() == prefixes.len() {
+        true => println!(
+            "{}",
+            Msg::VerifyingZips {
+                zips: todo.len(),
+                scope: &scope,
+            }
+        ),
+        false => println!(
+            "{}",
+            Msg::VerifyingRemainingZips {
+                todo: todo.len(),
+                zips: prefixes.len(),
+                scope: &scope,
+            }
+        ),
+    }
+
+    let total = todo.len();
+    let mut done = 0;
+    let mut verifications = stream::iter(todo)
+        .take_while(|_| future::ready(!budget::exceeded()))
+        .map(|prefix| async move {
+            let path = config.archive_path.join(zip_location(&prefix));
+            let result = spawn_blocking(move || archive_verify::verify_zip(&path))
+                .await
+                .map_err(|e| anyhow!("verify task failed: {}", e));
+            (prefix, result)
+        })
+        .buffer_unordered(config.max_parallel_downloads.max(1));
+    while let Some((prefix, result)) = verifications.next().await {
+        let result = result?;
+        done += 1;
+        match &result.error {
+            Some(e) => eprintln!("[{}/{}] ⚠️ dry{}.zip: {}", done, total, prefix, e),
+            None => println!(
+                "[{}/{}] dry{}.zip: {}",
+                done,
+                total,
+                prefix,
+                Msg::ZipVerified {
+                    entries: result.entries,
+                    bad: result.bad_hash.len(),
+                    unreadable: result.unreadable.len(),
+                }
+            ),
+        }
+        state.zips.insert(prefix, result);
+        state.write(state_path)?;
+    }
+
+    let zips = state.zips.values();
+    let entries: u64 = zips.clone().map(|zip| zip.entries).sum();
+    let bytes: u64 = zips.clone().map(|zip| zip.bytes).sum();
+    let bad: Vec<&String> = zips.clone().flat_map(|zip| &zip.bad_hash).collect();
+    let unreadable: Vec<&String> = zips.clone().flat_map(|zip| &zip.unreadable).collect();
+    let failed = zips.filter(|zip| zip.error.is_some()).count();
+    println!(
+        "\n{}",
+        Msg::ZipsVerified {
+            verified: state.zips.len(),
+            zips: prefixes.len(),
+            entries,
+            size: &contents::format_size(bytes),
+            bad: bad.len(),
+            unreadable: unreadable.len(),
+            failed,
+            path: state_path,
+        }
+    );
+    for hex in &bad {
+        println!("  {}", Msg::BadHash(hex));
+    }
+    for what in &unreadable {
+        println!("  {}", Msg::UnreadableEntry(what));
+    }
+    if state.zips.len() < prefixes.len() {
+        println!("{}", Msg::RunAgain);
+    } else if !bad.is_empty() || !unreadable.is_empty() || failed > 0 {
+        bail!("the archive has damaged entries, `repack` can rebuild their ZIPs from good copies");
+    }
+    Ok(())
+}
+
+fn repack(
+    prefix: &str,
+    mut loose_dirs: Vec<PathBuf>,
+    output: &Path,
+    config: &Config,
+) -> Result<()> {
+    let prefixes = repack::zip_prefixes(prefix)?;
+    loose_dirs.insert(0, config.cache_dir()?);
+    loose_dirs.insert(1, config.ingest_dir()?);
+    println!("{}", Msg::LookingForLooseBlobs(loose_dirs.len()));
+    let loose = repack::find_loose_blobs(&loose_dirs, &prefixes);
+
+    let mut failed = 0;
+    for prefix in &prefixes {
+        match repack::repack_zip(prefix, &config.archive_path, &loose, output) {
+            Ok(report) => {
+                println!(
+                    "dry{}.zip: {}",
+                    prefix,
+                    Msg::ZipRepacked {
+                        written: report.written,
+                        from_zip: report.from_zip,
+                        from_loose: report.from_loose,
+                        bad_hash: report.bad_hash,
+                        unreadable: report.unreadable,
+                    }
+                );
+                if !report.lost.is_empty() {
+                    println!("  ⚠️ {}", Msg::NoGoodCopy(report.lost.len()));
+                    for hex in &report.lost {
+                        println!("    {}", hex);
+                    }
+                }
+            }
+            Err(e) => {
+                failed += 1;
+                eprintln!("⚠️ dry{}.zip: {}", prefix, e);
+            }
         }
     }
+    if failed == prefixes.len() {
+        bail!("nothing was repacked");
+    }
+    println!("{}", Msg::RebuiltZips(output));
+    Ok(())
+}
 
-    // 7) Dump level’s icon (already in `resources`) by SHA1 filename
-    if let Some(icon_sha) = icon_sha1_opt {
-        if let Some(bytes) = resources.get(&icon_sha) {
-            let fname = hex_encode(icon_sha);
-            fs::write(out_dir.join(&fname), bytes)?;
-            println!("→ wrote level icon blob as {}", fname);
-        } else {
-            eprintln!(
-                "⚠️ icon SHA1 {} not in downloaded resources",
-                hex_encode(icon_sha)
-            );
-        }
+async fn scan_types(sample_every: usize, output: &Path, config: &Config) -> Result<()> {
+    let zips = scan::find_zips(&config.archive_path);
+    if zips.is_empty() {
+        bail!("no ZIPs found under {}", config.archive_path.display());
     }
+    println!("{}", Msg::ScanningZips(zips.len()));
 
-    // 8) Pull creator.icon SHA1 + planets list
-    let (creator_icon_blob, planets_blob): (Vec<u8>, Vec<u8>) = conn.query_row(
-        r#"SELECT icon, planets FROM "user" WHERE npHandle = ?1"#,
-        [&np_handle],
-        |r| Ok((r.get(0)?, r.get(1)?)),
-    )?;
+    let total = zips.len();
+    let mut done = 0;
+    let mut results = Vec::with_capacity(total);
+    let mut scans = stream::iter(zips)
+        .take_while(|_| future::ready(!budget::exceeded()))
+        .map(|path| async move {
+            let p = path.clone();
+            let report = spawn_blocking(move || scan::scan_zip(&p, sample_every))
+                .await
+                .map_err(|e| anyhow!("scan task failed: {}", e))
+                .and_then(|r| r);
+            (path, report)
+        })
+        .buffer_unordered(config.max_parallel_downloads.max(1));
+    while let Some((path, report)) = scans.next().await {
+        done += 1;
+        println!("[{}/{}] {}", done, total, path.display());
+        results.push((path, report));
+    }
 
-    // 9) **Read the creator’s icon directly from your archive**
-    // 8) Fetch the creator’s icon via download_level against your local archive
-    if creator_icon_blob.len() == 20 {
-        let mut creator_hash = [0u8; 20];
-        creator_hash.copy_from_slice(&creator_icon_blob);
-        // ask download_level to grab exactly that one hash
-        let DownloadResult {
-            resources: ci_res,
-            success_count: _,
-            error_count: _,
-        } = download_level(
-            creator_hash,
-            None,
-            config.archive_path.to_string_lossy().into_owned(),
-            1, // just one
-        )
-        .await?;
-        if let Some(ci_bytes) = ci_res.get(&creator_hash) {
-            let fname = hex_encode(creator_hash);
-            fs::write(out_dir.join(&fname), ci_bytes)?;
-            println!("→ wrote creator icon blob as {}", fname);
-        } else {
-            eprintln!(
-                "⚠️ creator icon SHA1 {} not found in local archive",
-                hex_encode(creator_hash)
-            );
-        }
-    } else {
-        eprintln!(
-            "⚠️ Unexpected creator.icon length: {} bytes (expected 20)",
-            creator_icon_blob.len()
+    let report = scan::combine(results);
+    fs::write(output, to_string_pretty(&report)?)?;
+
+    let mut types: Vec<_> = report.types.values().collect();
+    types.sort_by_key(|t| std::cmp::Reverse(t.total.bytes));
+    println!(
+        "\n{:<8} {:<26} {:>12} {:>16}",
+        "type", "name", "count", "bytes"
+    );
+    for t in types {
+        println!(
+            "{:<8} {:<26} {:>12} {:>16}",
+            t.magic,
+            t.name.as_deref().unwrap_or(""),
+            t.total.count,
+            t.total.bytes
         );
     }
+    println!(
+        "\n{}",
+        Msg::ZipsScanned {
+            scanned: report.entries_scanned,
+            seen: report.entries_seen,
+            zips: report.zips_scanned,
+            failed: report.zips_failed,
+            path: output,
+        }
+    );
+    Ok(())
+}
 
-    // 10) Recurse creator’s planets
-    for chunk in planets_blob.chunks(20) {
-        if chunk.len() == 20 {
-            let h = hex_encode(chunk);
-            println!("→ fetching creator-planet {}", h);
-            fetch_planet_resources_helper_function(&h, &np_handle, config, &out_dir).await?;
+/// Parse one level ID per line, ignoring blank lines
+fn read_level_list(path: &Path) -> Result<Vec<u32>> {
+    let file = File::open(path).map_err(|e| anyhow!("failed to open {}: {}", path.display(), e))?;
+    let mut ids = Vec::new();
+    for line in BufReader::new(file).lines() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
         }
+        ids.push(
+            line.parse()
+                .map_err(|e| anyhow!("invalid level ID `{}`: {}", line, e))?,
+        );
     }
+    Ok(ids)
+}
 
-    // 11) Serialize & RealmImporter
-    let users = fetch_all_users(&conn, level_id)?;
-    let levels = fetch_all_levels(&conn, level_id)?;
-    let relations = fetch_all_relations(&resources);
-    let mut assets = fetch_all_assets(&resources);
-    let mut dep_map: HashMap<String, Vec<String>> = HashMap::new();
-    for r in &relations {
-        dep_map
-            .entry(r.dependent.clone())
-            .or_default()
-            .push(r.dependency.clone());
+/// `queue add`: queue the levels that aren't queued yet
+fn queue_add(mut level_ids: Vec<u32>, levels: Option<&Path>, config: &Config) -> Result<()> {
+    if let Some(path) = levels {
+        level_ids.extend(read_level_list(path)?);
     }
-    for a in &mut assets {
-        if let Some(d) = dep_map.get(&a.asset_hash) {
-            a.dependencies = d.clone();
+    let path = config.queue_path()?;
+    let (added, pending) = LevelQueue::update(&path, |queue| {
+        let added = level_ids.iter().filter(|&&id| queue.add(id)).count();
+        (added, queue.in_state(QueueState::Pending).count())
+    })?;
+    println!(
+        "{}",
+        Msg::Queued {
+            added,
+            already: level_ids.len() - added,
+            pending,
         }
-    }
-    let import = ImportData {
-        users,
-        levels,
-        relations,
-        assets,
-    };
-    fs::write("import.json", to_string_pretty(&import)?)?;
-
-    let exe_dir = std::env::current_exe()?.parent().unwrap().to_path_buf();
-    Command::new(exe_dir.join("RealmImporter.exe"))
-        .arg("template.realm")
-        .arg("refreshGameServer.realm")
-        .status()?;
-    println!("Wrote import.json and produced refreshGameServer.realm");
-
+    );
     Ok(())
 }
 
-/// Fetch every level created by `np_handle`
-/// by calling `fetch_level` on each slot.id
-/// Fetch every level for a creator and dump all blobs into one folder
-/// named after their npHandle, skipping duplicate hashes or missing levels.
-/// Fetch every level for a creator by calling `fetch_level`, but
-/// copy all dumped blobs into one folder named after np_handle.
-async fn fetch_entire_planet(np_handle: &str, config: &Config) -> Result<()> {
-    // 1) Create the user folder
-    let base = config.backup_directory.join(np_handle);
-    fs::create_dir_all(&base)?;
-
-    // 2) Query and dedupe level IDs
-    let conn = Connection::open(&config.database_path)?;
-    let mut stmt = conn.prepare("SELECT id FROM slot WHERE npHandle = ?1")?;
-    let mut level_ids: Vec<u32> = stmt
-        .query_map([np_handle], |r| r.get(0))?
-        .collect::<rusqlite::Result<_>>()?;
-    level_ids.sort_unstable();
-    level_ids.dedup();
+/// `queue list`: every queued level with its state, then the counts
+fn queue_list(config: &Config) -> Result<()> {
+    let queue = LevelQueue::read(&config.queue_path()?)?;
+    let date = |at: &DateTime<Utc>| at.format("%Y-%m-%d %H:%M").to_string();
+    for level in &queue.levels {
+        let state = match level.state {
+            QueueState::Pending => Msg::QueuePending,
+            QueueState::Done => Msg::QueueDone,
+            QueueState::Failed => Msg::QueueFailed,
+        };
+        let mut line = format!(
+            "{:>10}  {:<7}  {}",
+            level.id,
+            state.to_string(),
+            Msg::QueueAdded(&date(&level.added_at))
+        );
+        if let Some(run_at) = &level.run_at {
+            line.push_str(&format!(", {}", Msg::QueueRun(&date(run_at))));
+        }
+        if let Some(error) = &level.error {
+            line.push_str(&format!(": {}", error));
+        }
+        println!("{}", line);
+    }
+    println!(
+        "{}",
+        Msg::QueueCounts {
+            levels: queue.levels.len(),
+            pending: queue.in_state(QueueState::Pending).count(),
+            done: queue.in_state(QueueState::Done).count(),
+            failed: queue.in_state(QueueState::Failed).count(),
+        }
+    );
+    Ok(())
+}
 
-    if level_ids.is_empty() {
-        println!("No levels found for `{}`", np_handle);
+/// `queue run`: run `command` on each pending level in queue order, the
+/// queue is written after each one so an interrupted run loses nothing
+async fn queue_run(
+    command: QueueCommand,
+    retry_failed: bool,
+    overwrite: bool,
+    config: &Config,
+) -> Result<()> {
+    let path = config.queue_path()?;
+    let ids = LevelQueue::read(&path)?.runnable(retry_failed);
+    if ids.is_empty() {
+        println!("{}", Msg::NothingToRun);
         return Ok(());
     }
 
-    // 3) For each level: fetch, then copy its folder contents into `base`
-    for lvl in level_ids {
-        println!("\n=== Level {} ===", lvl);
-
-        // 3a) run your existing logic (dump + Realm import)
-        if let Err(e) = fetch_level(lvl, config).await {
-            eprintln!("❌ Skipped level {} due to error: {}", lvl, e);
+    let (mut done, mut failed) = (0, 0);
+    for (i, &id) in ids.iter().enumerate() {
+        if budget::exceeded() {
+            break;
+        }
+        // taken out, or run by another `queue run`, since this one started
+        if !LevelQueue::read(&path)?
+            .runnable(retry_failed)
+            .contains(&id)
+        {
             continue;
         }
+        eprintln!("▶ [{}/{}] level {}", i + 1, ids.len(), id);
+        let result = match command {
+            QueueCommand::Bkp => {
+                let options = BackupOptions {
+                    force_lbp3: config.force_lbp3_backups,
+                    language: Language::default(),
+                    publisher_name: None,
+                    icon_overlay: false,
+                    icon: None,
+                    icon_texture: false,
+                    strict: false,
+                    partial_ok: false,
+                    force_copyable: false,
+                    output_dir: None,
+                    existing: match overwrite {
+                        true => ExistingBackup::Overwrite,
+                        false => ExistingBackup::Skip,
+                    },
+                    dry_run: false,
+                    title_id: None,
+                    revision: None,
+                };
+                dl_with_links(SlotRef::Id(id.into()), config, options, 0).await
+            }
+            QueueCommand::FetchLevel => fetch_single_level(id, config, None).await,
+        };
+        // a level the time limit cut off stays pending for the next run
+        if result.is_err() && budget::was_exceeded() {
+            break;
+        }
+        match &result {
+            Ok(()) => done += 1,
+            Err(e) => {
+                eprintln!(
+                    "⚠️ {}",
+                    Msg::QueueLevelFailed {
+                        id,
+                        error: &e.to_string(),
+                    }
+                );
+                failed += 1;
+            }
+        }
+        // merged into the queue as it is now, levels added meanwhile stay
+        let result = result.map_err(|e| e.to_string());
+        LevelQueue::update(&path, |queue| queue.finish(id, result))?;
+    }
+    let pending = LevelQueue::read(&path)?
+        .in_state(QueueState::Pending)
+        .count();
+    println!(
+        "{}",
+        Msg::QueueRunDone {
+            done,
+            failed,
+            pending,
+        }
+    );
+    Ok(())
+}
 
-        // 3b) copy files from `level_<id>` into `base`
-        let lvl_dir = config.backup_directory.join(format!("level_{}", lvl));
-        if !lvl_dir.exists() {
-            eprintln!(
-                "⚠️  Expected folder {} missing, skipping copy",
-                lvl_dir.display()
-            );
+/// `queue remove`: take `level_ids` out, and every done level with `done`
+fn queue_remove(level_ids: Vec<u32>, done: bool, config: &Config) -> Result<()> {
+    let path = config.queue_path()?;
+    let (removed, left) = LevelQueue::update(&path, |queue| {
+        let mut removed = level_ids.iter().filter(|&&id| queue.remove(id)).count();
+        if done {
+            let before = queue.levels.len();
+            queue.levels.retain(|level| level.state != QueueState::Done);
+            removed += before - queue.levels.len();
+        }
+        (removed, queue.levels.len())
+    })?;
+    println!("{}", Msg::QueueRemoved { removed, left });
+    Ok(())
+}
+
+/// The SHA1s of `--seed-hashes`, blank lines and `#` comments skipped
+fn read_seed_hashes(path: &Path) -> Result<BTreeSet<[u8; 20]>> {
+    let file = File::open(path).map_err(|e| anyhow!("failed to open {}: {}", path.display(), e))?;
+    let mut hashes = BTreeSet::new();
+    for line in BufReader::new(file).lines() {
+        let line = line?;
+        let line = line.split('#').next().unwrap_or_default().trim();
+        if line.is_empty() {
             continue;
         }
-        for entry in fs::read_dir(&lvl_dir)? {
-            let entry = entry?;
-            let file_name = entry.file_name();
-            let src_path = entry.path();
-            let dst_path = base.join(&file_name);
+        let hash = line.strip_prefix('h').unwrap_or(line);
+        hashes
+            .insert(parse_sha1(hash).map_err(|e| anyhow!("invalid seed hash `{}`: {}", line, e))?);
+    }
+    Ok(hashes)
+}
 
-            if dst_path.exists() {
-                // skip duplicates
-                continue;
+async fn fetch_user_icons(levels: Option<&Path>, config: &Config) -> Result<()> {
+    let level_ids = levels.map(read_level_list).transpose()?;
+
+    let icons = Db::open(&config.database_path)?
+        .call(move |conn| get_user_icons(conn, level_ids.as_deref()))
+        .await?;
+    println!("{}", Msg::FetchingUserIcons(icons.len()));
+
+    let out_dir = config.backup_directory.join("user_icons");
+    fs::create_dir_all(&out_dir)?;
+
+    let archive_path = config.archive_path.to_string_lossy().into_owned();
+    let mut fetches = stream::iter(icons)
+        .map(|(np_handle, icon_hash)| {
+            let archive_path = archive_path.clone();
+            async move {
+                let result =
+                    download_level(icon_hash, None, archive_path, 1, false, progress::silent())
+                        .await;
+                (np_handle, icon_hash, result)
+            }
+        })
+        .buffer_unordered(config.max_parallel_downloads.max(1));
+
+    let mut written = 0;
+    let mut failed = 0;
+    while let Some((np_handle, icon_hash, result)) = fetches.next().await {
+        let icon_hex = hex_encode(icon_hash);
+        match result.map(|mut r| r.resources.remove(&icon_hash)) {
+            Ok(Some(data)) => {
+                fs::write(out_dir.join(format!("{}_{}", np_handle, icon_hex)), data)?;
+                written += 1;
+            }
+            Ok(None) => {
+                eprintln!(
+                    "⚠️ {}",
+                    Msg::UserIconNotInArchive {
+                        icon: &icon_hex,
+                        user: &np_handle,
+                    }
+                );
+                failed += 1;
+            }
+            Err(e) => {
+                eprintln!(
+                    "⚠️ {}",
+                    Msg::UserIconFailed {
+                        icon: &icon_hex,
+                        user: &np_handle,
+                        error: &e.to_string(),
+                    }
+                );
+                failed += 1;
             }
-            // copy the file
-            fs::copy(&src_path, &dst_path).map_err(|e| {
-                anyhow!(
-                    "failed to copy {} → {}: {}",
-                    src_path.display(),
-                    dst_path.display(),
-                    e
-                )
-            })?;
         }
     }
 
     println!(
-        "\nAll unique files for `{}` are now in `{}`",
-        np_handle,
-        base.display()
+        "{}",
+        Msg::UserIconsWritten {
+            written,
+            path: &out_dir,
+            failed,
+        }
     );
     Ok(())
 }
 
-async fn read_from_file(config: &Config) -> Result<()> {
-    // 1) load creators.txt
-    let file =
-        File::open("creators.txt").map_err(|e| anyhow!("failed to open creators.txt: {}", e))?;
-    let creators: Vec<String> = BufReader::new(file)
-        .lines()
-        .map(|line| {
-            let s = line.map_err(|e| anyhow!("read error: {}", e))?;
-            let t = s.trim().to_string();
-            if t.is_empty() {
-                Err(anyhow!("skipping empty line"))
-            } else {
-                Ok(t)
+/// `icon export`: fetch the icon of a level or user and write it as a PNG
+async fn icon_export(
+    target: &str,
+    user: bool,
+    size: Option<u32>,
+    output: Option<PathBuf>,
+    config: &Config,
+) -> Result<()> {
+    let db = Db::open(&config.database_path)?;
+    let slot = match user {
+        true => None,
+        false => target.parse::<SlotRef>().ok(),
+    };
+    let icon_hash = match slot {
+        Some(slot) => {
+            let info = db.call(move |conn| get_slot_info(slot, conn)).await?;
+            match info.icon {
+                ResrcDescriptor::Sha1(hash) => hash,
+                ResrcDescriptor::Guid(0) => bail!("level {} has no icon", slot),
+                ResrcDescriptor::Guid(guid) => bail!(
+                    "level {} uses the built-in icon g{}, which isn't in the archive",
+                    slot,
+                    guid
+                ),
             }
-        })
-        .filter_map(Result::ok)
-        .collect();
+        }
+        None => {
+            let np_handle = target.to_string();
+            db.call(move |conn| get_user_icon(&np_handle, conn))
+                .await?
+                .ok_or_else(|| anyhow!("user `{}` not found or has no icon", target))?
+        }
+    };
 
-    if creators.is_empty() {
-        bail!("creators.txt is empty");
-    }
+    let resource = fetch_resource(
+        icon_hash,
+        config.archive_path.to_string_lossy().into_owned(),
+    )
+    .await?;
+    let png = spawn_blocking(move || icon::icon_to_png_sized(&resource, size))
+        .await??
+        .ok_or_else(|| anyhow!("icon {} isn't a texture", hex_encode(icon_hash)))?;
 
-    // 2) find next available fileDumpN
-    let mut idx = 0;
-    let out_dir: PathBuf = loop {
-        let candidate = format!("fileDump{}", idx);
-        let path = PathBuf::from(&candidate);
-        if !path.exists() {
-            fs::create_dir_all(&path)
-                .map_err(|e| anyhow!("could not create {}: {}", candidate, e))?;
-            break path;
+    let path = match output {
+        Some(path) if path.as_os_str() == "-" => {
+            stdout().write_all(&png)?;
+            return Ok(());
+        }
+        Some(path) => path,
+        None => {
+            let dir = config.backup_directory.join("icons");
+            fs::create_dir_all(&dir)?;
+            dir.join(format!("{}.png", target.replace(['/', '\\', ':'], "_")))
         }
-        idx += 1;
     };
+    fs::write(&path, png)?;
+    println!(
+        "{}",
+        Msg::IconWritten {
+            icon: &hex_encode(icon_hash),
+            path: &path,
+        }
+    );
+    Ok(())
+}
 
-    // 3) for each creator: fetch + copy
-    for creator in &creators {
-        println!("🔄 Fetching entire planet for `{}`…", creator);
-        fetch_entire_planet(creator, config).await?;
-
-        let src = config.backup_directory.join(creator);
-        if !src.exists() {
-            eprintln!("⚠️  no folder for `{}` at {:?}", creator, src);
-            continue;
+/// `icon sheet`: the icons of many levels on one PNG, plus where each is
+async fn icon_sheet(
+    mut slots: Vec<SlotRef>,
+    levels: Option<&Path>,
+    size: u32,
+    columns: Option<u32>,
+    output: Option<PathBuf>,
+    config: &Config,
+) -> Result<()> {
+    if let Some(path) = levels {
+        slots.extend(
+            read_level_list(path)?
+                .into_iter()
+                .map(|id| SlotRef::Id(id as i64)),
+        );
+    }
+    let db = Db::open(&config.database_path)?;
+    let mut missing = Vec::new();
+    // levels by icon, so a shared icon is fetched and placed once
+    let mut by_icon: BTreeMap<[u8; 20], Vec<String>> = BTreeMap::new();
+    for slot in slots {
+        match db.call(move |conn| get_slot_info(slot, conn)).await {
+            Ok(SlotInfo {
+                icon: ResrcDescriptor::Sha1(hash),
+                ..
+            }) => by_icon.entry(hash).or_default().push(slot.to_string()),
+            Ok(_) => {
+                eprintln!("⚠️ {}", Msg::NoArchiveIcon(&slot.to_string()));
+                missing.push(slot.to_string());
+            }
+            Err(e) => {
+                eprintln!("⚠️ {}: {}", slot, e);
+                missing.push(slot.to_string());
+            }
         }
-        for entry in fs::read_dir(&src)? {
-            let entry = entry?;
-            if entry.file_type()?.is_file() {
-                let dst = out_dir.join(entry.file_name());
-                fs::copy(entry.path(), &dst)
-                    .map_err(|e| anyhow!("failed to copy {:?} → {:?}: {}", entry.path(), dst, e))?;
+    }
+
+    let archive_path = config.archive_path.to_string_lossy().into_owned();
+    let mut fetches = stream::iter(by_icon)
+        .map(|(icon_hash, slots)| {
+            let archive_path = archive_path.clone();
+            async move {
+                let thumbnail = match fetch_resource(icon_hash, archive_path).await {
+                    Ok(data) => spawn_blocking(move || icon::icon_thumbnail(&data, size))
+                        .await
+                        .map_err(Into::into)
+                        .and_then(|result| result),
+                    Err(e) => Err(e),
+                };
+                (icon_hash, slots, thumbnail)
+            }
+        })
+        .buffered(config.max_parallel_downloads.max(1));
+
+    let mut thumbnails = Vec::new();
+    let mut placed_slots = Vec::new();
+    while let Some((icon_hash, slots, thumbnail)) = fetches.next().await {
+        match thumbnail {
+            Ok(Some(thumbnail)) => {
+                thumbnails.push(thumbnail);
+                placed_slots.push(slots);
+            }
+            Ok(None) => {
+                eprintln!("⚠️ {}", Msg::NotATexture(&hex_encode(icon_hash)));
+                missing.extend(slots);
+            }
+            Err(e) => {
+                eprintln!(
+                    "⚠️ {}",
+                    Msg::IconFailed {
+                        icon: &hex_encode(icon_hash),
+                        error: &e.to_string(),
+                    }
+                );
+                missing.extend(slots);
             }
         }
     }
+    if thumbnails.is_empty() {
+        bail!("none of the icons could be read");
+    }
 
-    println!("✅ All files dumped into {:?}", out_dir);
+    let columns = columns.unwrap_or_else(|| (thumbnails.len() as f64).sqrt().ceil() as u32);
+    let sizes: Vec<(u32, u32)> = thumbnails.iter().map(|t| t.dimensions()).collect();
+    let icon::SpriteSheet { png, corners } =
+        spawn_blocking(move || icon::make_sprite_sheet(&thumbnails, size, columns)).await??;
+
+    let path = match output {
+        Some(path) => path,
+        None => {
+            let dir = config.backup_directory.join("icons");
+            fs::create_dir_all(&dir)?;
+            dir.join("sheet.png")
+        }
+    };
+    let rows = (corners.len() as u32).div_ceil(columns);
+    let mut map = SpriteSheetMap {
+        schema_version: SPRITE_SHEET_SCHEMA_VERSION,
+        image: path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        width: columns * size,
+        height: rows * size,
+        cell_size: size,
+        icons: BTreeMap::new(),
+        missing,
+    };
+    for ((slots, (x, y)), (width, height)) in placed_slots.into_iter().zip(corners).zip(&sizes) {
+        for slot in slots {
+            map.icons.insert(
+                slot,
+                SpriteRect {
+                    x,
+                    y,
+                    width: *width,
+                    height: *height,
+                },
+            );
+        }
+    }
+    fs::write(&path, png)?;
+    let map_path = path.with_extension("json");
+    fs::write(&map_path, serde_json::to_string_pretty(&map)?)?;
+    println!(
+        "{}",
+        Msg::SheetWritten {
+            icons: sizes.len(),
+            levels: map.icons.len(),
+            path: &path,
+            map: &map_path,
+        }
+    );
+    if !map.missing.is_empty() {
+        println!("  {}", Msg::NoUsableIcon(map.missing.len()));
+    }
     Ok(())
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    let config = Config::read()?;
     let cli = Cli::parse();
+    if cli.self_test {
+        return self_test::run();
+    }
+    let Some(command) = cli.command else {
+        Cli::command().print_help()?;
+        return Ok(());
+    };
+    if let Some(limit) = cli.time_limit {
+        budget::set_time_limit(limit);
+    }
+    if let Some(language) = cli.ui_language {
+        messages::set_ui_language(language);
+    }
+    if cli.json && !command.prints_json() {
+        bail!(
+            "--json only works with stats, levels-of-hash, dlc usage, find-duplicates, compare-creators and annotate-resource, the other commands only print text"
+        );
+    }
+
+    // these don't need a config.yml
+    match &command {
+        Commands::Completions { shell } => {
+            let mut cmd = Cli::command();
+            let name = cmd.get_name().to_string();
+            clap_complete::generate(*shell, &mut cmd, name, &mut stdout());
+            return Ok(());
+        }
+        Commands::Manpage { output_dir } => {
+            match output_dir {
+                Some(dir) => {
+                    fs::create_dir_all(dir)?;
+                    manpage::write_pages(&Cli::command(), dir)?;
+                }
+                None => stdout().write_all(&manpage::render(&Cli::command())?)?,
+            }
+            return Ok(());
+        }
+        Commands::CompareCreators { old, new } => return compare_creators(old, new, cli.json),
+        Commands::Init {
+            database_path,
+            archive_path,
+            backup_directory,
+            yes,
+        } => {
+            let given = InitAnswers {
+                database_path: database_path.clone().unwrap_or_else(|| "dry.db".into()),
+                archive_path: archive_path.clone().unwrap_or_else(|| "LBP Archive".into()),
+                backup_directory: backup_directory.clone().unwrap_or_else(|| "backups".into()),
+            };
+            return init(given, *yes, cli.force);
+        }
+        Commands::Far {
+            action:
+                FarAction::Extract {
+                    path,
+                    output,
+                    format,
+                },
+        } => return far_extract(path, output.as_deref(), *format),
+        _ => {}
+    }
+
+    let mut config = Config::read(cli.profile.as_deref())?;
+    messages::set_ui_language(config.ui_language.unwrap_or_default());
+    config.max_parallel_downloads = clamp_parallel(
+        cli.max_parallel_downloads
+            .unwrap_or(config.max_parallel_downloads),
+        cli.max_parallel_downloads.is_some(),
+    )?;
+    if let Some(dir) = &cli.cache_dir {
+        config.cache_directory = Some(std::path::absolute(dir)?);
+    }
+    config.disable_cache |= cli.no_cache;
+    if let Some(target) = cli.target_console {
+        config.sfo.target_console = Some(target);
+    }
+    if command.writes_sfo() {
+        config.sfo.validate()?;
+    }
+    resource_dl::set_cache_dir(config.cache_dir()?);
+    resource_dl::set_disk_cache(!config.disable_cache);
+    resource_dl::set_download_order(config.download_order);
+    if let Some(password) = &config.archive_password {
+        resource_dl::set_archive_password(password.clone());
+    }
+    match resource_dl::clean_cache_if_due(&config.cache_dir()?) {
+        Ok(Some(cleanup)) => report_cache_cleanup(&cleanup),
+        Ok(None) => {}
+        Err(e) => eprintln!("⚠️ {}", Msg::CacheCleanupFailed(&e.to_string())),
+    }
+    resource_dl::set_ingest_dir(config.ingest_dir()?);
+    if let Some(path) = &cli.seed_hashes {
+        resource_dl::set_seed_hashes(read_seed_hashes(path)?);
+    }
+    if let Some(path) = &cli.alternates {
+        if !command.takes_alternates() {
+            bail!(
+                "--alternates only works with bkp, planet-bkp, bundle, rebuild-from-save and `queue run --as bkp`, the other commands keep the archive's hashes"
+            );
+        }
+        resource_dl::set_alternates(AlternateTable::read(path)?);
+    }
+    db::set_open_options(DbOpenOptions {
+        immutable: config.database_immutable,
+    });
+    extract_pool::set_threads(config.extraction_threads()?);
+    if let Some(threads) = cli.threads {
+        config.threads = Some(threads);
+    }
+    extract_pool::set_cpu_threads(config.cpu_threads()?);
+    // `db upgrade` reads the database itself
+    if !matches!(command, Commands::Db { .. }) {
+        use_working_db(&mut config)?;
+    }
+    if cli.verbose {
+        eprint!("# effective config\n{}", config.effective()?);
+    }
+
+    // held until the run is done, `budget` exits without dropping it
+    run_lock::set_force(cli.force);
+    let lock = match command.writes_backups() {
+        true => Some(RunLock::acquire(&config.backup_directory, cli.force)?),
+        false => None,
+    };
+    let result = run(command, cli.json, &config).await;
+    drop(lock);
+    let stats = metrics::run_stats();
+    if !stats.is_empty() {
+        eprint!("{}", stats.render());
+    }
+    // a run that failed or hit the time limit still read what it read
+    if let Some(path) = &cli.metrics_file
+        && let Err(e) = fs::write(path, metrics::render())
+    {
+        eprintln!(
+            "{}",
+            Msg::Warning(&Msg::MetricsNotWritten {
+                path,
+                error: &e.to_string(),
+            })
+        );
+    }
+    if budget::was_exceeded() {
+        if let Err(e) = &result {
+            eprintln!("Error: {:?}", e);
+        }
+        eprintln!("⏱️ {}", Msg::TimeLimitReached);
+        std::process::exit(budget::PARTIAL_EXIT_CODE);
+    }
+    result
+}
 
-    match cli.command {
-        Commands::Bkp { level_id, lbp3 } => dl_as_backup(level_id, config, lbp3).await?,
-        // Commands::Planet { hash } => dl_as_planet(&hash, &config).await?,
-        Commands::Planet { hash } => fetch_planet_resources(&hash, &config).await?,
-        Commands::FetchLevel { level_id } => match level_id.try_into() {
-            Ok(id) => fetch_level(id, &config).await?,
+/// `json`: print the result of informational commands as `JsonOutput`
+async fn run(command: Commands, json: bool, config: &Config) -> Result<()> {
+    match command {
+        Commands::Bkp {
+            level_id,
+            lbp3,
+            language,
+            publisher_name,
+            icon_overlay,
+            icon,
+            icon_texture,
+            strict,
+            partial_ok,
+            force_copyable,
+            follow_links,
+            output_dir,
+            overwrite,
+            skip_existing,
+            dry_run,
+            title_id,
+            revision,
+        } => {
+            let existing = match (overwrite, skip_existing) {
+                (true, _) => ExistingBackup::Overwrite,
+                (_, true) => ExistingBackup::Skip,
+                _ => ExistingBackup::Fail,
+            };
+            let options = BackupOptions {
+                force_lbp3: lbp3 || config.force_lbp3_backups,
+                language,
+                publisher_name,
+                icon_overlay,
+                icon,
+                icon_texture,
+                strict,
+                partial_ok,
+                force_copyable,
+                output_dir,
+                existing,
+                dry_run,
+                title_id,
+                revision,
+            };
+            dl_with_links(level_id, config, options, follow_links).await?
+        }
+        Commands::Planet {
+            hash,
+            creator,
+            game,
+            format,
+        } => {
+            let hash = match (hash, creator) {
+                (Some(hash), _) => hash,
+                (None, Some(creator)) => {
+                    let hash = resolve_creator_planets(&creator, game, config).await?;
+                    println!("{}: {}", Msg::PlanetsOf(&creator), hash);
+                    hash
+                }
+                (None, None) => unreachable!("clap requires a hash or --creator"),
+            };
+            fetch_planet_resources(&hash, config, format).await?
+        }
+        Commands::PlanetBkp {
+            target,
+            game,
+            language,
+            overwrite,
+        } => {
+            // npHandles are at most 16 characters, so 40 of them are a hash
+            let hash = match target.len() == 40 && target.bytes().all(|b| b.is_ascii_hexdigit()) {
+                true => target,
+                false => resolve_creator_planets(&target, game, config).await?,
+            };
+            dl_as_planet(&hash, language, overwrite, config).await?
+        }
+        Commands::FetchLevel {
+            level_id,
+            publisher_name,
+        } => match level_id.try_into() {
+            Ok(id) => fetch_single_level(id, config, publisher_name.as_deref()).await?,
             Err(_) => {
                 eprintln!("error: level_id {} is out of range", level_id);
                 std::process::exit(1);
             }
         },
-        Commands::FetchEntirePlanet { np_handle } => {
-            fetch_entire_planet(&np_handle, &config).await?
+        Commands::FetchEntirePlanet {
+            np_handle,
+            link_mode,
+            parallel_levels,
+            export,
+            by_game,
+            filter_game,
+            pick,
+            format,
+        } => {
+            let options = PlanetOptions {
+                link_mode,
+                parallel_levels,
+                export,
+                by_game,
+                filter_game,
+                pick,
+                format,
+            };
+            fetch_entire_planet(&np_handle, config, &options).await?;
         }
 
-        Commands::ReadFromFile => read_from_file(&config).await?,
+        Commands::ReadFromFile {
+            link_mode,
+            resume,
+            delay_ms,
+            parallel_levels,
+            export,
+            by_game,
+            filter_game,
+            format,
+        } => {
+            let options = DumpOptions {
+                link_mode,
+                delay_ms,
+                parallel_levels,
+                export,
+                by_game,
+                filter_game,
+                format,
+            };
+            read_from_file(config, resume.as_deref(), options).await?
+        }
+        Commands::ScanTypes { sample, output } => {
+            scan_types(sample as usize, &output, config).await?
+        }
+        Commands::Repack {
+            prefix,
+            from,
+            output,
+        } => repack(&prefix, from, &output, config)?,
+        Commands::Archive {
+            action:
+                ArchiveAction::Verify {
+                    shard,
+                    prefix,
+                    state,
+                    restart,
+                },
+        } => archive_verify(shard, prefix.as_deref(), &state, restart, config).await?,
+        Commands::Ingest { dirs } => ingest(&dirs, config)?,
+        Commands::Gc { dir, delete } => gc(dir.as_deref(), delete, config)?,
+        Commands::Db {
+            action: DbAction::Upgrade { output },
+        } => db_upgrade(output, config)?,
+        Commands::Save {
+            action:
+                SaveAction::Decrypt {
+                    path,
+                    output,
+                    into_cache,
+                },
+        } => save_decrypt(&path, output, into_cache, config)?,
+        Commands::Bundle {
+            level_id,
+            language,
+            output,
+            partial_ok,
+        } => bundle_level(level_id, language, output, partial_ok, config).await?,
+        Commands::ExportXml {
+            level_ids,
+            levels,
+            language,
+            output,
+        } => {
+            export_xml(
+                level_ids,
+                levels.as_deref(),
+                language,
+                output.as_deref(),
+                config,
+            )
+            .await?
+        }
+        Commands::ExportGraph {
+            level_ids,
+            levels,
+            format,
+            output,
+            parallel_levels,
+        } => {
+            export_graph(
+                level_ids,
+                levels.as_deref(),
+                format,
+                &output,
+                parallel_levels,
+                config,
+            )
+            .await?
+        }
+        Commands::Dlc {
+            action:
+                DlcAction::Usage {
+                    pack,
+                    relations,
+                    levels,
+                    parallel_levels,
+                },
+        } => {
+            dlc_usage(
+                &pack,
+                relations.as_deref(),
+                levels.as_deref(),
+                parallel_levels,
+                json,
+                config,
+            )
+            .await?
+        }
+        Commands::Stats {
+            level_ids,
+            levels,
+            parallel_levels,
+        } => level_stats(level_ids, levels.as_deref(), parallel_levels, json, config).await?,
+        Commands::FindDuplicates {
+            min_similarity,
+            levels,
+            parallel_levels,
+            json_file,
+        } => {
+            find_duplicates(
+                min_similarity,
+                levels.as_deref(),
+                parallel_levels,
+                json_file.as_deref(),
+                json,
+                config,
+            )
+            .await?
+        }
+        Commands::LevelsOfHash { hash, relations } => {
+            levels_of_hash(hash, relations.as_deref(), json, config)?
+        }
+        Commands::AnnotateResource { resource } => {
+            annotate_resource(&resource, json, config).await?
+        }
+        Commands::Icon {
+            action:
+                IconAction::Export {
+                    target,
+                    user,
+                    size,
+                    output,
+                },
+        } => icon_export(&target, user, size, output, config).await?,
+        Commands::Icon {
+            action:
+                IconAction::Sheet {
+                    level_ids,
+                    levels,
+                    size,
+                    columns,
+                    output,
+                },
+        } => icon_sheet(level_ids, levels.as_deref(), size, columns, output, config).await?,
+        Commands::RebuildFromSave {
+            path,
+            lbp3,
+            language,
+            publisher_name,
+            icon_overlay,
+            strict,
+            partial_ok,
+            include_unnamed,
+            output_dir,
+            overwrite,
+            skip_existing,
+            dry_run,
+        } => {
+            let existing = match (overwrite, skip_existing) {
+                (true, _) => ExistingBackup::Overwrite,
+                (_, true) => ExistingBackup::Skip,
+                _ => ExistingBackup::Fail,
+            };
+            let options = BackupOptions {
+                force_lbp3: lbp3 || config.force_lbp3_backups,
+                language,
+                publisher_name,
+                icon_overlay,
+                icon: None,
+                icon_texture: false,
+                strict,
+                partial_ok,
+                force_copyable: false,
+                output_dir,
+                existing,
+                dry_run,
+                title_id: None,
+                revision: None,
+            };
+            rebuild_from_save(&path, include_unnamed, options, config).await?
+        }
+        Commands::RefreshParams {
+            backup_dir,
+            language,
+        } => refresh_params(&backup_dir, language, config)?,
+        Commands::RunScript { script, args } => run_script(&script, args, config).await?,
+        Commands::Queue { action } => match action {
+            QueueAction::Add { level_ids, levels } => {
+                queue_add(level_ids, levels.as_deref(), config)?
+            }
+            QueueAction::List => queue_list(config)?,
+            QueueAction::Run {
+                command,
+                retry_failed,
+                overwrite,
+            } => queue_run(command, retry_failed, overwrite, config).await?,
+            QueueAction::Remove { level_ids, done } => queue_remove(level_ids, done, config)?,
+        },
+        Commands::FetchUserIcons { levels } => fetch_user_icons(levels.as_deref(), config).await?,
+        Commands::Completions { .. }
+        | Commands::Init { .. }
+        | Commands::Manpage { .. }
+        | Commands::CompareCreators { .. }
+        | Commands::Far { .. } => {
+            unreachable!("handled before reading the config")
+        }
     }
 
     Ok(())
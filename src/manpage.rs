@@ -0,0 +1,21 @@
+// src/manpage.rs
+
+use std::io;
+use std::path::Path;
+
+use clap::Command;
+use clap_mangen::Man;
+
+/// The man page (section 1) of the command line itself, with its global
+/// flags and a list of the subcommands
+pub fn render(cmd: &Command) -> io::Result<Vec<u8>> {
+    let mut page = Vec::new();
+    Man::new(cmd.clone()).render(&mut page)?;
+    Ok(page)
+}
+
+/// Write the page of the command line and one of every subcommand into
+/// `dir`, nested ones named by their full path like `archive_dl-db-upgrade.1`
+pub fn write_pages(cmd: &Command, dir: &Path) -> io::Result<()> {
+    clap_mangen::generate_to(cmd.clone(), dir)
+}
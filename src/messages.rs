@@ -0,0 +1,4579 @@
+// src/messages.rs
+
+//! Messages shown to whoever runs the tool, in the language picked with
+//! `--ui-language` or `ui_language` in config.yml.
+//!
+//! Each message is a variant of `Msg` and is written in the UI language by
+//! its `Display` impl. A new message can start out English only, with a `_`
+//! arm, and be translated later. Everything the commands print goes through
+//! here except errors, lint findings, the `▶` log lines for debugging, the
+//! rows of reports that mirror their `--json` output, `--self-test` and the
+//! `--help` text, which stay English.
+
+use std::fmt;
+use std::path::Path;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use crate::language::Language;
+
+static UI_LANGUAGE: OnceLock<Language> = OnceLock::new();
+
+/// Pick the language of the messages, only the first call counts
+pub fn set_ui_language(language: Language) {
+    let _ = UI_LANGUAGE.set(language);
+}
+
+pub fn ui_language() -> Language {
+    UI_LANGUAGE.get().copied().unwrap_or_default()
+}
+
+pub enum Msg<'a> {
+    ConfigMissing,
+    LevelFound,
+    LevelName(&'a str),
+    LevelCreator(&'a str),
+    LevelGame(&'a str),
+    /// the level name comes from the translation table, in this language
+    UsingTranslation(&'a str),
+    Gathering,
+    Fetched {
+        fetched: usize,
+        missing: usize,
+    },
+    LintClean,
+    LintSummary {
+        errors: usize,
+        warnings: usize,
+    },
+    BackupExists(&'a Path),
+    SkippingExisting(&'a Path),
+    BackupWritten(&'a Path),
+    FollowingLink {
+        slot_id: i64,
+        name: &'a str,
+        creator: &'a str,
+    },
+    DryRun {
+        resources: usize,
+        size: &'a str,
+        from_cache: bool,
+        elapsed: Duration,
+    },
+    DryRunMissing(usize),
+    IconWritten {
+        icon: &'a str,
+        path: &'a Path,
+    },
+    /// `WARNING: ` in front of another message
+    Warning(&'a dyn fmt::Display),
+    PlanetsOf(&'a str),
+    PlanetFetched {
+        resources: usize,
+        fetched: usize,
+        failed: usize,
+    },
+    Decorations(&'a str),
+    /// the slots of this game have no field for the decorations
+    DecorationsUnsupported(&'a str),
+    NoDecorations,
+    Pod(&'a str),
+    DecorationsFailed {
+        hash: &'a str,
+        error: &'a str,
+    },
+    PodFailed {
+        hash: &'a str,
+        error: &'a str,
+    },
+    CreatorIconLookupFailed(&'a str),
+    CreatorIconFailed {
+        hash: &'a str,
+        error: &'a str,
+    },
+    /// the time limit ran out with these many linked levels left
+    LinksOutOfTime(usize),
+    LinkedLevelFailed {
+        slot: &'a str,
+        error: &'a str,
+    },
+    LinkedLevelsFailed(usize),
+    WritingWithRevision {
+        revision: &'a str,
+        game: &'a str,
+    },
+    ForcingLbp3,
+    LevelInFormat {
+        level_game: &'a str,
+        format: &'a str,
+    },
+    WritingBackupAs(&'a str),
+    /// written for the level's game when its format is another's
+    WritingForBackport(&'a str),
+    AdventureAsLbp3,
+    UnknownBranchId(u16),
+    NotABranchRevision {
+        branch: &'a str,
+        latest: &'a str,
+    },
+    RevisionTooNew {
+        revision: &'a str,
+        game: &'a str,
+        latest: u32,
+    },
+    AdventureSharedData {
+        resources: usize,
+        quests: usize,
+    },
+    AdventureResourceMissing(&'a str),
+    AdventureResourceUnreadable {
+        hash: &'a str,
+        error: &'a str,
+    },
+    AdventureSharedDataSkipped(&'a str),
+    TitleIdOfOtherGame {
+        title_id: &'a str,
+        game: &'a str,
+        backup: &'a str,
+    },
+    UnknownTitleId {
+        title_id: &'a str,
+        game: &'a str,
+    },
+    SlotIconFromImage(&'a str),
+    MarkingCopyable,
+    /// slot lists of the revision have no copyable flag
+    CopyableNotWritten(&'a str),
+    ResourcesLeftOut {
+        missing: usize,
+        meta: &'a str,
+    },
+    /// levels in a save with a slot there and without one
+    SaveLevelsFound {
+        named: usize,
+        unnamed: usize,
+    },
+    IncludeUnnamedHint,
+    UnreadableSaveLevel(&'a str),
+    SaveLevelFailed {
+        root_level: &'a str,
+        error: &'a str,
+    },
+    SaveLevelsDone {
+        written: usize,
+        failed: usize,
+        dry_run: bool,
+    },
+    /// `refresh-params` is done with a backup folder
+    ParamsWritten(&'a str),
+    /// `database_path` or `archive_path` isn't there
+    ConfigPathMissing {
+        key: &'a str,
+        path: &'a Path,
+    },
+    Bundling {
+        name: &'a str,
+        creator: &'a str,
+        game: &'a str,
+    },
+    IconNotConverted(&'a str),
+    BundleResourcesLeftOut {
+        missing: usize,
+        meta: &'a str,
+    },
+    BundleWritten {
+        resources: usize,
+        path: &'a Path,
+    },
+    CacheCleaned {
+        unfinished: usize,
+        empty: usize,
+    },
+    /// a dump `gc` found unreachable blobs in, `size` already formatted
+    UnreachableBlobs {
+        dir: &'a Path,
+        unreachable: usize,
+        blobs: usize,
+        size: &'a str,
+    },
+    GcNoManifest(&'a Path),
+    GcZipped(&'a Path),
+    GcDone {
+        deleted: bool,
+        blobs: usize,
+        size: &'a str,
+        dumps: usize,
+    },
+    GcDeleteHint,
+    /// more parallel downloads than CPUs were asked for in config.yml
+    ParallelCapped(usize),
+    /// a candidate planet of `--game` that couldn't be read
+    PlanetsUnreadable {
+        hash: &'a str,
+        error: &'a str,
+    },
+    PlanetFilesWritten {
+        files: usize,
+        dir: &'a Path,
+    },
+    /// a file holding a hash, like `planet_hash.txt`
+    HashFileWritten {
+        file: &'a str,
+        hash: &'a str,
+    },
+    NoCreatorIcon(&'a str),
+    SubLevelAdded(&'a str),
+    SubLevelsSkipped {
+        skipped: usize,
+        dir: &'a Path,
+    },
+    PlanetSltFetched(&'a str),
+    PlanetSltWritten(&'a str),
+    BlobsFetched {
+        blobs: usize,
+        fetched: usize,
+        failed: usize,
+        dir: &'a Path,
+    },
+    FetchingParentPlanet(&'a str),
+    FetchingCreatorPlanet(&'a str),
+    LevelIconWritten(&'a str),
+    IconNotDownloaded(&'a str),
+    CreatorIconWritten(&'a str),
+    CreatorIconNotInArchive(&'a str),
+    CreatorIconLength(usize),
+    IconConverted {
+        icon: &'a str,
+        png: &'a str,
+    },
+    IconConversionFailed {
+        icon: &'a str,
+        error: &'a str,
+    },
+    RealmImportDone,
+    NoCreatorLevels(&'a str),
+    /// `games` lists the counts per game, like `3 LBP2, 1 LBP3`
+    CreatorLevels {
+        creator: &'a str,
+        levels: usize,
+        games: &'a str,
+    },
+    NoGameLevels {
+        game: &'a str,
+        creator: &'a str,
+    },
+    FetchingOnlyGame {
+        levels: usize,
+        game: &'a str,
+    },
+    NothingPicked,
+    FetchingPicked(usize),
+    LevelHeading(u32),
+    LevelSkipped {
+        id: u32,
+        error: &'a str,
+    },
+    LevelFolderMissing(&'a Path),
+    ImportWritten {
+        path: &'a Path,
+        levels: usize,
+        assets: usize,
+    },
+    GameFetched {
+        game: &'a str,
+        fetched: usize,
+        failed: usize,
+    },
+    CreatorDumped {
+        creator: &'a str,
+        dir: &'a Path,
+    },
+    /// a creator of `read-from-file` an earlier run finished
+    CreatorAlreadyDone {
+        creator: &'a str,
+        state: &'a str,
+    },
+    FetchingCreator(&'a str),
+    CreatorFailed {
+        creator: &'a str,
+        error: &'a str,
+    },
+    NoCreatorFolder {
+        creator: &'a str,
+        dir: &'a Path,
+    },
+    DumpStoppedEarly(&'a Path),
+    DumpDone(&'a Path),
+    CreatorsFailed {
+        failed: usize,
+        dir: &'a Path,
+    },
+    /// `bkp --dry-run` of a level bigger than `max_backup_size`
+    OverMaxBackupSize(&'a str),
+    FileWritten(&'a Path),
+    SlotsWritten {
+        slots: usize,
+        path: &'a Path,
+    },
+    GraphWritten {
+        levels: usize,
+        nodes: usize,
+        edges: usize,
+        path: &'a Path,
+        elapsed: Duration,
+    },
+    LevelsUnreadableMissing(usize),
+    /// story and moon slots have no creator
+    NoCreator,
+    Dependents {
+        dependents: usize,
+        hash: &'a str,
+        path: &'a Path,
+    },
+    NoSlotUses(&'a str),
+    SlotCount(usize),
+    LevelsNotCounted(usize),
+    NoLevelUses(&'a str),
+    CreatorLevelCount {
+        creator: &'a str,
+        levels: usize,
+    },
+    DlcUsage {
+        levels: usize,
+        creators: usize,
+        used: usize,
+        guids: usize,
+        pack: &'a str,
+    },
+    MostUsed,
+    GuidLevels {
+        guid: u32,
+        levels: usize,
+    },
+    ComparedByRootOnly(usize),
+    SameRootLevel {
+        root_level: &'a str,
+        levels: usize,
+        creators: usize,
+    },
+    AlikeLevels {
+        percent: u32,
+        levels: usize,
+        creators: usize,
+    },
+    DuplicateLevel {
+        id: i64,
+        name: &'a str,
+        creator: &'a str,
+    },
+    DuplicatesSummary {
+        groups: usize,
+        identical: usize,
+        similar: usize,
+        compared: usize,
+    },
+    NothingFetchedYet,
+    LevelUnreadable {
+        id: u32,
+        error: &'a str,
+    },
+    LevelsNotFetched(usize),
+    /// `init` asking before it replaces config.yml
+    ConfigExists(&'a str),
+    /// `init`'s questions
+    DatabaseQuestion,
+    ArchiveQuestion,
+    BackupFolderQuestion,
+    UseAnyway,
+    /// what `init` found at an answer
+    DatabaseProbed {
+        slots: i64,
+        users: i64,
+        extras: &'a str,
+    },
+    ArchiveComplete,
+    ArchiveZipsMissing {
+        missing: usize,
+        shown: &'a str,
+    },
+    ArchiveSingleZip(&'a Path),
+    ArchiveTars(usize),
+    ArchiveRemote,
+    PathMissing(&'a Path),
+    ArchiveUnknown(&'a Path),
+    FolderExists,
+    NotAFolder(&'a Path),
+    FolderCreatedWithConfig,
+    ConfigWritten(&'a str),
+    /// a level without a name in `--pick`'s list
+    NoName,
+    PickQuestion,
+    NoDifferences,
+    /// a level that was never updated
+    Never,
+    LevelUpdated {
+        old: &'a str,
+        new: &'a str,
+    },
+    CreatorsCompared {
+        creators_added: usize,
+        creators_removed: usize,
+        added: usize,
+        removed: usize,
+        updated: usize,
+    },
+    WorkingDbStale {
+        working: &'a Path,
+        database: &'a Path,
+    },
+    DbUpgraded {
+        path: &'a Path,
+        slots: usize,
+        users: usize,
+        elapsed: Duration,
+    },
+    ValuesRetyped {
+        retyped: usize,
+        filled: usize,
+    },
+    ValuesUnconvertible(usize),
+    RowsDropped {
+        duplicate_users: usize,
+        duplicate_slots: usize,
+        dropped_users: usize,
+        orphans: usize,
+    },
+    SlotsWithoutUser(usize),
+    Ingested {
+        dir: &'a Path,
+        added: usize,
+        known: usize,
+        unreadable: usize,
+    },
+    NameMismatch(usize),
+    IngestDir(&'a Path),
+    /// a save or FAR archive's kind, as `{:?}`
+    FarArchive {
+        kind: &'a str,
+        entries: usize,
+    },
+    SaveChecksumMismatch,
+    DamagedEntriesSkipped {
+        out_of_range: usize,
+        bad_hash: usize,
+    },
+    BlobsWritten {
+        blobs: usize,
+        path: &'a Path,
+    },
+    SaveExtracted {
+        resources: usize,
+        levels: usize,
+        path: &'a Path,
+    },
+    AddedToCache,
+    /// `archive verify`, `scope` is the shard or prefix as the state file has it
+    VerifyingZips {
+        zips: usize,
+        scope: &'a str,
+    },
+    VerifyingRemainingZips {
+        todo: usize,
+        zips: usize,
+        scope: &'a str,
+    },
+    ZipVerified {
+        entries: u64,
+        bad: usize,
+        unreadable: usize,
+    },
+    ZipsVerified {
+        verified: usize,
+        zips: usize,
+        entries: u64,
+        size: &'a str,
+        bad: usize,
+        unreadable: usize,
+        failed: usize,
+        path: &'a Path,
+    },
+    BadHash(&'a str),
+    UnreadableEntry(&'a str),
+    RunAgain,
+    LookingForLooseBlobs(usize),
+    ZipRepacked {
+        written: usize,
+        from_zip: usize,
+        from_loose: usize,
+        bad_hash: usize,
+        unreadable: usize,
+    },
+    NoGoodCopy(usize),
+    RebuiltZips(&'a Path),
+    ScanningZips(usize),
+    ZipsScanned {
+        scanned: u64,
+        seen: u64,
+        zips: usize,
+        failed: usize,
+        path: &'a Path,
+    },
+    Queued {
+        added: usize,
+        already: usize,
+        pending: usize,
+    },
+    /// a queued level's state in `queue list`
+    QueuePending,
+    QueueDone,
+    QueueFailed,
+    QueueAdded(&'a str),
+    QueueRun(&'a str),
+    QueueCounts {
+        levels: usize,
+        pending: usize,
+        done: usize,
+        failed: usize,
+    },
+    NothingToRun,
+    QueueLevelFailed {
+        id: u32,
+        error: &'a str,
+    },
+    QueueRunDone {
+        done: usize,
+        failed: usize,
+        pending: usize,
+    },
+    QueueRemoved {
+        removed: usize,
+        left: usize,
+    },
+    FetchingUserIcons(usize),
+    UserIconNotInArchive {
+        icon: &'a str,
+        user: &'a str,
+    },
+    UserIconFailed {
+        icon: &'a str,
+        user: &'a str,
+        error: &'a str,
+    },
+    UserIconsWritten {
+        written: usize,
+        path: &'a Path,
+        failed: usize,
+    },
+    NoArchiveIcon(&'a str),
+    NotATexture(&'a str),
+    IconFailed {
+        icon: &'a str,
+        error: &'a str,
+    },
+    SheetWritten {
+        icons: usize,
+        levels: usize,
+        path: &'a Path,
+        map: &'a Path,
+    },
+    NoUsableIcon(usize),
+    CacheCleanupFailed(&'a str),
+    MetricsNotWritten {
+        path: &'a Path,
+        error: &'a str,
+    },
+    TimeLimitReached,
+    /// a level's ID in `--pick`'s list
+    LevelNumber(u32),
+    StaleLockRemoved(u32),
+    LockTakenOver(&'a Path),
+    CachedDamaged {
+        hash: &'a str,
+        error: &'a str,
+    },
+    ClosureCacheUnavailable(&'a str),
+    ResourceMissing {
+        hash: &'a str,
+        error: &'a str,
+    },
+    TryingAlternate {
+        hash: &'a str,
+        alternate: &'a str,
+        error: &'a str,
+    },
+    LeavingOut {
+        hash: &'a str,
+        error: &'a str,
+    },
+    SlotIconFallback {
+        hash: &'a str,
+        error: &'a str,
+    },
+    SeedHashLeftOut {
+        hash: &'a str,
+        error: &'a str,
+    },
+    AlternatesSwapped {
+        swapped: usize,
+        rewritten: usize,
+    },
+    ZipSkipped {
+        zip: &'a str,
+        resources: usize,
+        reason: &'a str,
+    },
+    ClosureNotRemembered {
+        hash: &'a str,
+        error: &'a str,
+    },
+    /// ICON0.PNG's sources, `source` is the name of the one used
+    IconFileUndecodable(&'a str),
+    SlotIconUnavailable(&'a str),
+    IconUndecodable {
+        source: &'a str,
+        error: &'a str,
+    },
+    PlaceholderIcon,
+    TimeLimitStopping,
+    ScanFailed {
+        path: &'a Path,
+        error: &'a str,
+    },
+    AlternatesNotPointed {
+        hash: &'a str,
+        error: &'a str,
+    },
+    DbOpenedImmutable {
+        path: &'a Path,
+        error: &'a str,
+    },
+    BackgroundNotGuid {
+        slot: i64,
+        background: i64,
+    },
+}
+
+impl fmt::Display for Msg<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use Language::*;
+        let lang = ui_language();
+        match *self {
+            Msg::ConfigMissing => f.write_str(match lang {
+                Fr => "config.yml est introuvable, écriture de la configuration par défaut",
+                De => "config.yml fehlt, Standardkonfiguration wird geschrieben",
+                Es => "Falta config.yml, escribiendo la configuración predeterminada",
+                It => "config.yml mancante, scrittura della configurazione predefinita",
+                Pt => "config.yml não encontrado, a escrever a configuração padrão",
+                Nl => "config.yml ontbreekt, standaardconfiguratie wordt geschreven",
+                Ja => "config.yml が見つからないため、既定の設定を書き込みます",
+                En => "config.yml is missing, writing default config",
+            }),
+            Msg::LevelFound => f.write_str(match lang {
+                Fr => "Niveau trouvé !",
+                De => "Level gefunden!",
+                Es => "¡Nivel encontrado!",
+                It => "Livello trovato!",
+                Pt => "Nível encontrado!",
+                Nl => "Level gevonden!",
+                Ja => "レベルが見つかりました！",
+                En => "Level found!",
+            }),
+            Msg::LevelName(name) => match lang {
+                Fr => write!(f, "  Nom :      {name}"),
+                De => write!(f, "  Name:     {name}"),
+                Es => write!(f, "  Nombre:   {name}"),
+                It => write!(f, "  Nome:     {name}"),
+                Pt => write!(f, "  Nome:     {name}"),
+                Nl => write!(f, "  Naam:     {name}"),
+                Ja => write!(f, "  名前:     {name}"),
+                En => write!(f, "  Name:    {name}"),
+            },
+            Msg::LevelCreator(creator) => match lang {
+                Fr => write!(f, "  Créateur : {creator}"),
+                De => write!(f, "  Ersteller: {creator}"),
+                Es => write!(f, "  Creador:  {creator}"),
+                It => write!(f, "  Creatore: {creator}"),
+                Pt => write!(f, "  Criador:  {creator}"),
+                Nl => write!(f, "  Maker:    {creator}"),
+                Ja => write!(f, "  作成者:   {creator}"),
+                En => write!(f, "  Creator: {creator}"),
+            },
+            Msg::LevelGame(game) => match lang {
+                Fr => write!(f, "  Jeu :      {game}"),
+                De => write!(f, "  Spiel:    {game}"),
+                Es => write!(f, "  Juego:    {game}"),
+                It => write!(f, "  Gioco:    {game}"),
+                Pt => write!(f, "  Jogo:     {game}"),
+                Nl => write!(f, "  Spel:     {game}"),
+                Ja => write!(f, "  ゲーム:   {game}"),
+                En => write!(f, "  Game:    {game}"),
+            },
+            Msg::UsingTranslation(code) => match lang {
+                Fr => write!(f, "Nom du niveau en {code} utilisé"),
+                De => write!(f, "Levelname auf {code} wird verwendet"),
+                Es => write!(f, "Usando el nombre del nivel en {code}"),
+                It => write!(f, "Uso del nome del livello in {code}"),
+                Pt => write!(f, "A usar o nome do nível em {code}"),
+                Nl => write!(f, "Levelnaam in {code} wordt gebruikt"),
+                Ja => write!(f, "{code} のレベル名を使用します"),
+                En => write!(f, "Using {code} level name"),
+            },
+            Msg::Gathering => f.write_str(match lang {
+                Fr => "Récupération des ressources depuis l'archive locale…",
+                De => "Ressourcen werden aus dem lokalen Archiv geholt…",
+                Es => "Reuniendo recursos del archivo local…",
+                It => "Raccolta delle risorse dall'archivio locale…",
+                Pt => "A reunir recursos do arquivo local…",
+                Nl => "Bronnen worden uit het lokale archief gehaald…",
+                Ja => "ローカルアーカイブからリソースを収集中…",
+                En => "Gathering resources from local archive…",
+            }),
+            Msg::Fetched { fetched, missing } => match lang {
+                Fr => write!(f, "Terminé !  {fetched} récupérées, {missing} manquantes."),
+                De => write!(f, "Fertig!  {fetched} geholt, {missing} fehlen."),
+                Es => write!(f, "¡Hecho!  {fetched} obtenidos, {missing} faltan."),
+                It => write!(f, "Fatto!  {fetched} recuperate, {missing} mancanti."),
+                Pt => write!(f, "Concluído!  {fetched} obtidos, {missing} em falta."),
+                Nl => write!(f, "Klaar!  {fetched} opgehaald, {missing} ontbreken."),
+                Ja => write!(f, "完了！  取得 {fetched} 件、不足 {missing} 件。"),
+                En => write!(f, "Done!  {fetched} fetched, {missing} missing."),
+            },
+            Msg::LintClean => f.write_str(match lang {
+                Fr => "Lint : aucun problème trouvé",
+                De => "Lint: keine Probleme gefunden",
+                Es => "Lint: no se encontraron problemas",
+                It => "Lint: nessun problema trovato",
+                Pt => "Lint: nenhum problema encontrado",
+                Nl => "Lint: geen problemen gevonden",
+                Ja => "Lint: 問題は見つかりませんでした",
+                En => "Lint: no problems found",
+            }),
+            Msg::LintSummary { errors, warnings } => match lang {
+                Fr => write!(f, "Lint : {errors} erreurs, {warnings} avertissements"),
+                De => write!(f, "Lint: {errors} Fehler, {warnings} Warnungen"),
+                Es => write!(f, "Lint: {errors} errores, {warnings} advertencias"),
+                It => write!(f, "Lint: {errors} errori, {warnings} avvisi"),
+                Pt => write!(f, "Lint: {errors} erros, {warnings} avisos"),
+                Nl => write!(f, "Lint: {errors} fouten, {warnings} waarschuwingen"),
+                Ja => write!(f, "Lint: エラー {errors} 件、警告 {warnings} 件"),
+                En => write!(f, "Lint: {errors} errors, {warnings} warnings"),
+            },
+            Msg::BackupExists(path) => {
+                let path = path.display();
+                match lang {
+                    Fr => write!(
+                        f,
+                        "{path} existe déjà, ajoutez --overwrite pour le remplacer ou --skip-existing pour le garder"
+                    ),
+                    De => write!(
+                        f,
+                        "{path} existiert bereits, --overwrite ersetzt es, --skip-existing behält es"
+                    ),
+                    Es => write!(
+                        f,
+                        "{path} ya existe, usa --overwrite para reemplazarlo o --skip-existing para conservarlo"
+                    ),
+                    It => write!(
+                        f,
+                        "{path} esiste già, usa --overwrite per sostituirlo o --skip-existing per tenerlo"
+                    ),
+                    Pt => write!(
+                        f,
+                        "{path} já existe, use --overwrite para o substituir ou --skip-existing para o manter"
+                    ),
+                    Nl => write!(
+                        f,
+                        "{path} bestaat al, gebruik --overwrite om het te vervangen of --skip-existing om het te houden"
+                    ),
+                    Ja => write!(
+                        f,
+                        "{path} は既に存在します。置き換えるには --overwrite、残すには --skip-existing を指定してください"
+                    ),
+                    En => write!(
+                        f,
+                        "{path} already exists, pass --overwrite to replace it or --skip-existing to keep it"
+                    ),
+                }
+            }
+            Msg::SkippingExisting(path) => {
+                let path = path.display();
+                match lang {
+                    Fr => write!(f, "Sauvegarde déjà présente dans {path}, ignorée"),
+                    De => write!(f, "Sicherung liegt bereits unter {path}, wird übersprungen"),
+                    Es => write!(f, "La copia ya está en {path}, se omite"),
+                    It => write!(f, "Backup già presente in {path}, saltato"),
+                    Pt => write!(f, "O backup já está em {path}, a ignorar"),
+                    Nl => write!(f, "Back-up staat al in {path}, wordt overgeslagen"),
+                    Ja => write!(f, "バックアップは既に {path} にあるため、スキップします"),
+                    En => write!(f, "Backup already at {path}, skipping"),
+                }
+            }
+            Msg::BackupWritten(path) => {
+                let path = path.display();
+                match lang {
+                    Fr => write!(f, "Sauvegarde écrite dans {path}"),
+                    De => write!(f, "Sicherung geschrieben nach {path}"),
+                    Es => write!(f, "Copia escrita en {path}"),
+                    It => write!(f, "Backup scritto in {path}"),
+                    Pt => write!(f, "Backup escrito em {path}"),
+                    Nl => write!(f, "Back-up geschreven naar {path}"),
+                    Ja => write!(f, "バックアップを {path} に書き込みました"),
+                    En => write!(f, "Backup written to {path}"),
+                }
+            }
+            Msg::FollowingLink {
+                slot_id,
+                name,
+                creator,
+            } => match lang {
+                Fr => write!(f, "Suivi du lien vers {slot_id} ({name} par {creator})"),
+                De => write!(
+                    f,
+                    "Levellink zu {slot_id} wird gefolgt ({name} von {creator})"
+                ),
+                Es => write!(f, "Siguiendo el enlace a {slot_id} ({name} por {creator})"),
+                It => write!(f, "Seguo il collegamento a {slot_id} ({name} di {creator})"),
+                Pt => write!(
+                    f,
+                    "A seguir a ligação para {slot_id} ({name} por {creator})"
+                ),
+                Nl => write!(
+                    f,
+                    "Levellink naar {slot_id} wordt gevolgd ({name} door {creator})"
+                ),
+                Ja => write!(
+                    f,
+                    "{slot_id} へのレベルリンクをたどります（{name}、作成者 {creator}）"
+                ),
+                En => write!(f, "Following level link to {slot_id} ({name} by {creator})"),
+            },
+            Msg::DryRun {
+                resources,
+                size,
+                from_cache,
+                elapsed,
+            } => {
+                let source = match (lang, from_cache) {
+                    (Fr, true) => "du cache des fermetures",
+                    (Fr, false) => "de l'archive",
+                    (De, true) => "aus dem Closure-Cache",
+                    (De, false) => "aus dem Archiv",
+                    (Es, true) => "de la caché de cierres",
+                    (Es, false) => "del archivo",
+                    (It, true) => "dalla cache delle chiusure",
+                    (It, false) => "dall'archivio",
+                    (Pt, true) => "da cache de fechos",
+                    (Pt, false) => "do arquivo",
+                    (Nl, true) => "uit de closure-cache",
+                    (Nl, false) => "uit het archief",
+                    (Ja, true) => "クロージャキャッシュから",
+                    (Ja, false) => "アーカイブから",
+                    (En, true) => "from the closure cache",
+                    (En, false) => "from the archive",
+                };
+                match lang {
+                    Fr => write!(
+                        f,
+                        "Simulation : {resources} ressources, {size} ({source} en {elapsed:.2?})"
+                    ),
+                    De => write!(
+                        f,
+                        "Probelauf: {resources} Ressourcen, {size} ({source} in {elapsed:.2?})"
+                    ),
+                    Es => write!(
+                        f,
+                        "Simulación: {resources} recursos, {size} ({source} en {elapsed:.2?})"
+                    ),
+                    It => write!(
+                        f,
+                        "Prova: {resources} risorse, {size} ({source} in {elapsed:.2?})"
+                    ),
+                    Pt => write!(
+                        f,
+                        "Simulação: {resources} recursos, {size} ({source} em {elapsed:.2?})"
+                    ),
+                    Nl => write!(
+                        f,
+                        "Proefrun: {resources} bronnen, {size} ({source} in {elapsed:.2?})"
+                    ),
+                    Ja => write!(
+                        f,
+                        "ドライラン: リソース {resources} 件、{size}（{source}、{elapsed:.2?}）"
+                    ),
+                    En => write!(
+                        f,
+                        "Dry run: {resources} resources, {size} ({source} in {elapsed:.2?})"
+                    ),
+                }
+            }
+            Msg::DryRunMissing(missing) => match lang {
+                Fr => write!(f, "  {missing} ressources manquent dans l'archive"),
+                De => write!(f, "  {missing} Ressourcen fehlen im Archiv"),
+                Es => write!(f, "  Faltan {missing} recursos en el archivo"),
+                It => write!(f, "  Mancano {missing} risorse nell'archivio"),
+                Pt => write!(f, "  Faltam {missing} recursos no arquivo"),
+                Nl => write!(f, "  {missing} bronnen ontbreken in het archief"),
+                Ja => write!(f, "  アーカイブにないリソースが {missing} 件あります"),
+                En => write!(f, "  {missing} resources are missing from the archive"),
+            },
+            Msg::IconWritten { icon, path } => {
+                let path = path.display();
+                match lang {
+                    Fr => write!(f, "Icône {icon} écrite dans {path}"),
+                    De => write!(f, "Icon {icon} geschrieben nach {path}"),
+                    Es => write!(f, "Icono {icon} escrito en {path}"),
+                    It => write!(f, "Icona {icon} scritta in {path}"),
+                    Pt => write!(f, "Ícone {icon} escrito em {path}"),
+                    Nl => write!(f, "Icoon {icon} geschreven naar {path}"),
+                    Ja => write!(f, "アイコン {icon} を {path} に書き込みました"),
+                    En => write!(f, "Icon {icon} written to {path}"),
+                }
+            }
+            Msg::Warning(message) => match lang {
+                Fr => write!(f, "ATTENTION : {message}"),
+                De => write!(f, "WARNUNG: {message}"),
+                Es => write!(f, "AVISO: {message}"),
+                It => write!(f, "ATTENZIONE: {message}"),
+                Pt => write!(f, "AVISO: {message}"),
+                Nl => write!(f, "WAARSCHUWING: {message}"),
+                Ja => write!(f, "警告: {message}"),
+                En => write!(f, "WARNING: {message}"),
+            },
+            Msg::PlanetsOf(owner) => match lang {
+                Fr => write!(f, "Planètes de `{owner}`"),
+                De => write!(f, "Planeten von `{owner}`"),
+                Es => write!(f, "Planetas de `{owner}`"),
+                It => write!(f, "Pianeti di `{owner}`"),
+                Pt => write!(f, "Planetas de `{owner}`"),
+                Nl => write!(f, "Planeten van `{owner}`"),
+                Ja => write!(f, "`{owner}` の惑星"),
+                En => write!(f, "Planets of `{owner}`"),
+            },
+            Msg::PlanetFetched {
+                resources,
+                fetched,
+                failed,
+            } => match lang {
+                Fr => write!(f, "{resources} ressources récupérées ({fetched}/{failed})"),
+                De => write!(f, "{resources} Ressourcen geholt ({fetched}/{failed})"),
+                Es => write!(f, "{resources} recursos obtenidos ({fetched}/{failed})"),
+                It => write!(f, "{resources} risorse recuperate ({fetched}/{failed})"),
+                Pt => write!(f, "{resources} recursos obtidos ({fetched}/{failed})"),
+                Nl => write!(f, "{resources} bronnen opgehaald ({fetched}/{failed})"),
+                Ja => write!(
+                    f,
+                    "リソース {resources} 件を取得しました（{fetched}/{failed}）"
+                ),
+                En => write!(
+                    f,
+                    "Done fetching {resources} resources ({fetched}/{failed})"
+                ),
+            },
+            Msg::Decorations(hash) => match lang {
+                Fr => write!(f, "Décorations : {hash}"),
+                De => write!(f, "Dekorationen: {hash}"),
+                Es => write!(f, "Decoraciones: {hash}"),
+                It => write!(f, "Decorazioni: {hash}"),
+                Pt => write!(f, "Decorações: {hash}"),
+                Nl => write!(f, "Decoraties: {hash}"),
+                Ja => write!(f, "デコレーション: {hash}"),
+                En => write!(f, "Decorations: {hash}"),
+            },
+            Msg::DecorationsUnsupported(game) => match lang {
+                Fr => write!(
+                    f,
+                    "Les emplacements {game} ne peuvent pas pointer vers les décorations, elles sont dans la sauvegarde mais pas sur la planète"
+                ),
+                De => write!(
+                    f,
+                    "{game}-Slots können nicht auf Dekorationen zeigen, sie sind im Backup, aber nicht auf dem Planeten"
+                ),
+                Es => write!(
+                    f,
+                    "Los slots de {game} no pueden apuntar a decoraciones, están en la copia pero no en el planeta"
+                ),
+                It => write!(
+                    f,
+                    "Gli slot di {game} non possono puntare alle decorazioni, sono nel backup ma non sul pianeta"
+                ),
+                Pt => write!(
+                    f,
+                    "Os slots de {game} não podem apontar para decorações, estão na cópia mas não no planeta"
+                ),
+                Nl => write!(
+                    f,
+                    "{game}-slots kunnen niet naar decoraties wijzen, ze staan in de back-up maar niet op de planeet"
+                ),
+                Ja => write!(
+                    f,
+                    "{game} のスロットはデコレーションを指せないため、バックアップには含まれますが惑星には表示されません"
+                ),
+                En => write!(
+                    f,
+                    "{game} slots can't point at decorations, they're in the backup but not on the planet"
+                ),
+            },
+            Msg::NoDecorations => f.write_str(match lang {
+                Fr => "Le profil de l'utilisateur ne nomme aucune décoration pour les planètes",
+                De => "Der Benutzereintrag nennt keine Dekorationen für die Planeten",
+                Es => "El registro del usuario no indica decoraciones para los planetas",
+                It => "Il record dell'utente non indica decorazioni per i pianeti",
+                Pt => "O registo do utilizador não indica decorações para os planetas",
+                Nl => "Het gebruikersrecord noemt geen decoraties voor de planeten",
+                Ja => "ユーザー情報に惑星のデコレーションがありません",
+                En => "The user record names no decorations for the planets",
+            }),
+            Msg::Pod(hash) => match lang {
+                Fr => write!(f, "Module : {hash}"),
+                De => write!(f, "Kapsel: {hash}"),
+                Es => write!(f, "Cápsula: {hash}"),
+                It => write!(f, "Capsula: {hash}"),
+                Pt => write!(f, "Cápsula: {hash}"),
+                Nl => write!(f, "Capsule: {hash}"),
+                Ja => write!(f, "ポッド: {hash}"),
+                En => write!(f, "Pod: {hash}"),
+            },
+            Msg::DecorationsFailed { hash, error } => match lang {
+                Fr => write!(
+                    f,
+                    "impossible de récupérer les décorations {hash} des planètes : {error}"
+                ),
+                De => write!(
+                    f,
+                    "Dekorationen {hash} der Planeten konnten nicht geholt werden: {error}"
+                ),
+                Es => write!(
+                    f,
+                    "no se pudieron obtener las decoraciones {hash} de los planetas: {error}"
+                ),
+                It => write!(
+                    f,
+                    "impossibile recuperare le decorazioni {hash} dei pianeti: {error}"
+                ),
+                Pt => write!(
+                    f,
+                    "não foi possível obter as decorações {hash} dos planetas: {error}"
+                ),
+                Nl => write!(
+                    f,
+                    "decoraties {hash} van de planeten konden niet worden opgehaald: {error}"
+                ),
+                Ja => write!(
+                    f,
+                    "惑星のデコレーション {hash} を取得できませんでした: {error}"
+                ),
+                En => write!(f, "couldn't fetch the planets' decorations {hash}: {error}"),
+            },
+            Msg::PodFailed { hash, error } => match lang {
+                Fr => write!(f, "impossible de récupérer le module {hash} : {error}"),
+                De => write!(f, "Kapsel {hash} konnte nicht geholt werden: {error}"),
+                Es => write!(f, "no se pudo obtener la cápsula {hash}: {error}"),
+                It => write!(f, "impossibile recuperare la capsula {hash}: {error}"),
+                Pt => write!(f, "não foi possível obter a cápsula {hash}: {error}"),
+                Nl => write!(f, "capsule {hash} kon niet worden opgehaald: {error}"),
+                Ja => write!(f, "ポッド {hash} を取得できませんでした: {error}"),
+                En => write!(f, "couldn't fetch the planets' pod {hash}: {error}"),
+            },
+            Msg::CreatorIconLookupFailed(error) => match lang {
+                Fr => write!(f, "impossible de trouver l'icône du créateur : {error}"),
+                De => write!(f, "Ersteller-Icon konnte nicht gesucht werden: {error}"),
+                Es => write!(f, "no se pudo buscar el icono del creador: {error}"),
+                It => write!(f, "impossibile cercare l'icona del creatore: {error}"),
+                Pt => write!(f, "não foi possível procurar o ícone do criador: {error}"),
+                Nl => write!(f, "makersicoon kon niet worden opgezocht: {error}"),
+                Ja => write!(f, "作成者アイコンを検索できませんでした: {error}"),
+                En => write!(f, "couldn't look up creator icon: {error}"),
+            },
+            Msg::CreatorIconFailed { hash, error } => match lang {
+                Fr => write!(
+                    f,
+                    "impossible de récupérer l'icône du créateur {hash} : {error}"
+                ),
+                De => write!(
+                    f,
+                    "Ersteller-Icon {hash} konnte nicht geholt werden: {error}"
+                ),
+                Es => write!(f, "no se pudo obtener el icono del creador {hash}: {error}"),
+                It => write!(
+                    f,
+                    "impossibile recuperare l'icona del creatore {hash}: {error}"
+                ),
+                Pt => write!(
+                    f,
+                    "não foi possível obter o ícone do criador {hash}: {error}"
+                ),
+                Nl => write!(f, "makersicoon {hash} kon niet worden opgehaald: {error}"),
+                Ja => write!(f, "作成者アイコン {hash} を取得できませんでした: {error}"),
+                En => write!(f, "couldn't fetch creator icon {hash}: {error}"),
+            },
+            Msg::LinksOutOfTime(left) => match lang {
+                Fr => write!(f, "temps écoulé, {left} niveau(x) lié(s) non sauvegardé(s)"),
+                De => write!(
+                    f,
+                    "Zeit abgelaufen, {left} verlinkte(s) Level nicht gesichert"
+                ),
+                Es => write!(f, "sin tiempo, {left} nivel(es) enlazado(s) sin copia"),
+                It => write!(
+                    f,
+                    "tempo scaduto, {left} livello/i collegato/i senza backup"
+                ),
+                Pt => write!(f, "sem tempo, {left} nível(is) ligado(s) sem cópia"),
+                Nl => write!(f, "tijd op, {left} gelinkte level(s) niet geback-upt"),
+                Ja => write!(
+                    f,
+                    "時間切れのため、リンク先のレベル {left} 件をバックアップしていません"
+                ),
+                En => write!(f, "out of time, {left} linked level(s) not backed up"),
+            },
+            Msg::LinkedLevelFailed { slot, error } => match lang {
+                Fr => write!(
+                    f,
+                    "impossible de sauvegarder le niveau lié {slot} : {error}"
+                ),
+                De => write!(
+                    f,
+                    "verlinktes Level {slot} konnte nicht gesichert werden: {error}"
+                ),
+                Es => write!(f, "no se pudo copiar el nivel enlazado {slot}: {error}"),
+                It => write!(
+                    f,
+                    "impossibile fare il backup del livello collegato {slot}: {error}"
+                ),
+                Pt => write!(f, "não foi possível copiar o nível ligado {slot}: {error}"),
+                Nl => write!(
+                    f,
+                    "gelinkt level {slot} kon niet worden geback-upt: {error}"
+                ),
+                Ja => write!(
+                    f,
+                    "リンク先のレベル {slot} をバックアップできませんでした: {error}"
+                ),
+                En => write!(f, "couldn't back up linked level {slot}: {error}"),
+            },
+            Msg::LinkedLevelsFailed(failed) => match lang {
+                Fr => write!(f, "{failed} niveaux liés n'ont pas pu être sauvegardés"),
+                De => write!(f, "{failed} verlinkte Level konnten nicht gesichert werden"),
+                Es => write!(f, "{failed} niveles enlazados no se pudieron copiar"),
+                It => write!(f, "{failed} livelli collegati non sono stati salvati"),
+                Pt => write!(f, "{failed} níveis ligados não puderam ser copiados"),
+                Nl => write!(f, "{failed} gelinkte levels konden niet worden geback-upt"),
+                Ja => write!(
+                    f,
+                    "リンク先のレベル {failed} 件をバックアップできませんでした"
+                ),
+                En => write!(f, "{failed} linked levels couldn't be backed up"),
+            },
+            Msg::WritingWithRevision { revision, game } => match lang {
+                Fr => write!(
+                    f,
+                    "écriture de la sauvegarde avec la révision {revision} pour {game}"
+                ),
+                De => write!(
+                    f,
+                    "Backup wird mit Revision {revision} für {game} geschrieben"
+                ),
+                Es => write!(
+                    f,
+                    "escribiendo la copia con la revisión {revision} para {game}"
+                ),
+                It => write!(
+                    f,
+                    "scrittura del backup con la revisione {revision} per {game}"
+                ),
+                Pt => write!(f, "a escrever a cópia com a revisão {revision} para {game}"),
+                Nl => write!(
+                    f,
+                    "back-up wordt geschreven met revisie {revision} voor {game}"
+                ),
+                Ja => write!(
+                    f,
+                    "リビジョン {revision} の {game} 用バックアップを書き込みます"
+                ),
+                En => write!(f, "writing the backup with revision {revision} for {game}"),
+            },
+            Msg::ForcingLbp3 => f.write_str(match lang {
+                Fr => "format de sauvegarde LBP3 forcé",
+                De => "LBP3-Backupformat wird erzwungen",
+                Es => "forzando el formato de copia de LBP3",
+                It => "formato di backup LBP3 forzato",
+                Pt => "a forçar o formato de cópia do LBP3",
+                Nl => "LBP3-back-upformaat wordt afgedwongen",
+                Ja => "LBP3 のバックアップ形式を強制します",
+                En => "forcing LBP3 backup format",
+            }),
+            Msg::LevelInFormat { level_game, format } => match lang {
+                Fr => write!(f, "ce niveau {level_game} est au format {format}"),
+                De => write!(f, "dies ist ein {level_game}-Level im {format}-Format"),
+                Es => write!(f, "este es un nivel de {level_game} en formato de {format}"),
+                It => write!(f, "questo è un livello di {level_game} in formato {format}"),
+                Pt => write!(f, "este é um nível de {level_game} no formato de {format}"),
+                Nl => write!(f, "dit is een {level_game}-level in {format}-formaat"),
+                Ja => write!(f, "これは {format} 形式の {level_game} レベルです"),
+                En => write!(f, "this is a {level_game} level in {format} format"),
+            },
+            Msg::WritingBackupAs(game) => match lang {
+                Fr => write!(f, "écriture de la sauvegarde pour {game}"),
+                De => write!(f, "Backup wird als {game} geschrieben"),
+                Es => write!(f, "escribiendo la copia como {game}"),
+                It => write!(f, "scrittura del backup come {game}"),
+                Pt => write!(f, "a escrever a cópia como {game}"),
+                Nl => write!(f, "back-up wordt geschreven als {game}"),
+                Ja => write!(f, "{game} としてバックアップを書き込みます"),
+                En => write!(f, "writing backup as {game}"),
+            },
+            Msg::WritingForBackport(game) => match lang {
+                Fr => write!(
+                    f,
+                    "écriture pour {game}, il faudra peut-être rétroporter ce niveau"
+                ),
+                De => write!(
+                    f,
+                    "wird als {game} geschrieben, das Level muss eventuell zurückportiert werden"
+                ),
+                Es => write!(
+                    f,
+                    "escribiendo como {game}, puede que haya que adaptar este nivel"
+                ),
+                It => write!(
+                    f,
+                    "scrittura come {game}, potrebbe servire un backport del livello"
+                ),
+                Pt => write!(
+                    f,
+                    "a escrever como {game}, pode ser preciso adaptar este nível"
+                ),
+                Nl => write!(
+                    f,
+                    "wordt geschreven als {game}, het level moet misschien teruggezet worden"
+                ),
+                Ja => write!(
+                    f,
+                    "{game} として書き込みます。このレベルのバックポートが必要な場合があります"
+                ),
+                En => write!(f, "writing as {game}, you may need to backport this level"),
+            },
+            Msg::AdventureAsLbp3 => f.write_str(match lang {
+                Fr => "seul LBP3 charge les aventures, écriture de la sauvegarde pour LBP3",
+                De => "nur LBP3 lädt Abenteuer, Backup wird als LBP3 geschrieben",
+                Es => "solo LBP3 carga aventuras, escribiendo la copia como LBP3",
+                It => "solo LBP3 carica le avventure, scrittura del backup come LBP3",
+                Pt => "só o LBP3 carrega aventuras, a escrever a cópia como LBP3",
+                Nl => "alleen LBP3 laadt avonturen, back-up wordt geschreven als LBP3",
+                Ja => "アドベンチャーは LBP3 でしか読み込めないため、LBP3 として書き込みます",
+                En => "adventures can only be loaded by LBP3, writing backup as LBP3",
+            }),
+            Msg::UnknownBranchId(id) => match lang {
+                Fr => write!(f, "l'id de branche {id:x} est inconnu de l'outil"),
+                De => write!(f, "Branch-ID {id:x} kennt das Tool nicht"),
+                Es => write!(f, "la herramienta no conoce el id de rama {id:x}"),
+                It => write!(f, "l'id di ramo {id:x} non è noto allo strumento"),
+                Pt => write!(f, "a ferramenta não conhece o id de ramo {id:x}"),
+                Nl => write!(f, "branch-id {id:x} kent de tool niet"),
+                Ja => write!(f, "ブランチ ID {id:x} はこのツールで認識できません"),
+                En => write!(f, "branch id {id:x} isn't a branch this tool knows"),
+            },
+            Msg::NotABranchRevision { branch, latest } => match lang {
+                Fr => write!(
+                    f,
+                    "les révisions de {branch} vont jusqu'à {latest}, celle-ci n'en fait pas partie"
+                ),
+                De => write!(
+                    f,
+                    "{branch}-Revisionen gehen bis {latest}, diese gehört nicht dazu"
+                ),
+                Es => write!(
+                    f,
+                    "las revisiones de {branch} llegan hasta {latest}, esta no es una de ellas"
+                ),
+                It => write!(
+                    f,
+                    "le revisioni di {branch} arrivano a {latest}, questa non è una di esse"
+                ),
+                Pt => write!(
+                    f,
+                    "as revisões de {branch} vão até {latest}, esta não é uma delas"
+                ),
+                Nl => write!(
+                    f,
+                    "{branch}-revisies gaan tot {latest}, deze hoort er niet bij"
+                ),
+                Ja => write!(
+                    f,
+                    "{branch} のリビジョンは {latest} までで、これは該当しません"
+                ),
+                En => write!(
+                    f,
+                    "{branch} revisions go up to {latest}, this isn't one of them"
+                ),
+            },
+            Msg::RevisionTooNew {
+                revision,
+                game,
+                latest,
+            } => match lang {
+                Fr => write!(
+                    f,
+                    "{revision} est plus récente que la révision {game} connue ({latest:#x}), le jeu peut refuser la sauvegarde"
+                ),
+                De => write!(
+                    f,
+                    "{revision} ist neuer als die bekannte {game}-Revision ({latest:#x}), das Spiel lehnt das Backup eventuell ab"
+                ),
+                Es => write!(
+                    f,
+                    "{revision} es más nueva que la revisión de {game} conocida ({latest:#x}), el juego puede rechazar la copia"
+                ),
+                It => write!(
+                    f,
+                    "{revision} è più recente della revisione {game} nota ({latest:#x}), il gioco potrebbe rifiutare il backup"
+                ),
+                Pt => write!(
+                    f,
+                    "{revision} é mais recente do que a revisão de {game} conhecida ({latest:#x}), o jogo pode recusar a cópia"
+                ),
+                Nl => write!(
+                    f,
+                    "{revision} is nieuwer dan de bekende {game}-revisie ({latest:#x}), het spel kan de back-up weigeren"
+                ),
+                Ja => write!(
+                    f,
+                    "{revision} は既知の {game} リビジョン（{latest:#x}）より新しいため、ゲームがバックアップを拒否する場合があります"
+                ),
+                En => write!(
+                    f,
+                    "{revision} is newer than the {game} revision this tool knows ({latest:#x}), the game may refuse the backup"
+                ),
+            },
+            Msg::AdventureSharedData { resources, quests } => match lang {
+                Fr => write!(
+                    f,
+                    "Données partagées de l'aventure : {resources} ressources ({quests} quêtes)"
+                ),
+                De => write!(
+                    f,
+                    "Gemeinsame Abenteuerdaten: {resources} Ressourcen ({quests} Quests)"
+                ),
+                Es => write!(
+                    f,
+                    "Datos compartidos de la aventura: {resources} recursos ({quests} misiones)"
+                ),
+                It => write!(
+                    f,
+                    "Dati condivisi dell'avventura: {resources} risorse ({quests} missioni)"
+                ),
+                Pt => write!(
+                    f,
+                    "Dados partilhados da aventura: {resources} recursos ({quests} missões)"
+                ),
+                Nl => write!(
+                    f,
+                    "Gedeelde avontuurdata: {resources} bronnen ({quests} quests)"
+                ),
+                Ja => write!(
+                    f,
+                    "アドベンチャーの共有データ: リソース {resources} 件（クエスト {quests} 件）"
+                ),
+                En => write!(
+                    f,
+                    "Adventure shared data: {resources} resources ({quests} quests)"
+                ),
+            },
+            Msg::AdventureResourceMissing(hash) => match lang {
+                Fr => write!(
+                    f,
+                    "la ressource d'aventure {hash} manque, les quêtes peuvent ne pas marcher"
+                ),
+                De => write!(
+                    f,
+                    "Abenteuer-Ressource {hash} fehlt, Quests funktionieren eventuell nicht"
+                ),
+                Es => write!(
+                    f,
+                    "falta el recurso de aventura {hash}, puede que las misiones no funcionen"
+                ),
+                It => write!(
+                    f,
+                    "manca la risorsa dell'avventura {hash}, le missioni potrebbero non funzionare"
+                ),
+                Pt => write!(
+                    f,
+                    "falta o recurso de aventura {hash}, as missões podem não funcionar"
+                ),
+                Nl => write!(
+                    f,
+                    "avontuurbron {hash} ontbreekt, quests werken misschien niet"
+                ),
+                Ja => write!(
+                    f,
+                    "アドベンチャーのリソース {hash} がないため、クエストが動かない場合があります"
+                ),
+                En => write!(
+                    f,
+                    "adventure resource {hash} is missing, quests may not work"
+                ),
+            },
+            Msg::AdventureResourceUnreadable { hash, error } => match lang {
+                Fr => write!(
+                    f,
+                    "la ressource d'aventure {hash} est illisible, ce qu'elle référence est ignoré : {error}"
+                ),
+                De => write!(
+                    f,
+                    "Abenteuer-Ressource {hash} ist unlesbar, was sie referenziert wird übersprungen: {error}"
+                ),
+                Es => write!(
+                    f,
+                    "el recurso de aventura {hash} no se puede leer, se omite lo que referencia: {error}"
+                ),
+                It => write!(
+                    f,
+                    "la risorsa dell'avventura {hash} non è leggibile, ciò a cui rimanda viene saltato: {error}"
+                ),
+                Pt => write!(
+                    f,
+                    "o recurso de aventura {hash} não pode ser lido, o que ele referencia é ignorado: {error}"
+                ),
+                Nl => write!(
+                    f,
+                    "avontuurbron {hash} is onleesbaar, waar die naar verwijst wordt overgeslagen: {error}"
+                ),
+                Ja => write!(
+                    f,
+                    "アドベンチャーのリソース {hash} を読めないため、その参照先は飛ばします: {error}"
+                ),
+                En => write!(
+                    f,
+                    "adventure resource {hash} can't be read, what it refers to is skipped: {error}"
+                ),
+            },
+            Msg::AdventureSharedDataSkipped(error) => match lang {
+                Fr => write!(
+                    f,
+                    "données partagées de l'aventure introuvables, la sauvegarde se fait sans : {error}"
+                ),
+                De => write!(
+                    f,
+                    "gemeinsame Abenteuerdaten nicht gefunden, das Backup wird ohne sie erstellt: {error}"
+                ),
+                Es => write!(
+                    f,
+                    "no se encontraron los datos compartidos de la aventura, la copia se hace sin ellos: {error}"
+                ),
+                It => write!(
+                    f,
+                    "dati condivisi dell'avventura non trovati, il backup viene fatto senza: {error}"
+                ),
+                Pt => write!(
+                    f,
+                    "dados compartilhados da aventura não encontrados, o backup é feito sem eles: {error}"
+                ),
+                Nl => write!(
+                    f,
+                    "gedeelde avontuurgegevens niet gevonden, de back-up wordt zonder gemaakt: {error}"
+                ),
+                Ja => write!(
+                    f,
+                    "アドベンチャーの共有データが見つからないため、なしでバックアップします: {error}"
+                ),
+                En => write!(
+                    f,
+                    "couldn't look for the adventure's shared data, backing it up without: {error}"
+                ),
+            },
+            Msg::TitleIdOfOtherGame {
+                title_id,
+                game,
+                backup,
+            } => match lang {
+                Fr => write!(
+                    f,
+                    "{title_id} est un ID de titre de {game}, mais la sauvegarde est pour {backup}"
+                ),
+                De => write!(
+                    f,
+                    "{title_id} ist eine Title-ID von {game}, das Backup ist aber für {backup}"
+                ),
+                Es => write!(
+                    f,
+                    "{title_id} es un ID de título de {game}, pero la copia es para {backup}"
+                ),
+                It => write!(
+                    f,
+                    "{title_id} è un ID titolo di {game}, ma il backup è per {backup}"
+                ),
+                Pt => write!(
+                    f,
+                    "{title_id} é um ID de título de {game}, mas a cópia é para {backup}"
+                ),
+                Nl => write!(
+                    f,
+                    "{title_id} is een title-ID van {game}, maar de back-up is voor {backup}"
+                ),
+                Ja => write!(
+                    f,
+                    "{title_id} は {game} のタイトル ID ですが、バックアップは {backup} 用です"
+                ),
+                En => write!(
+                    f,
+                    "{title_id} is a title ID of {game}, but the backup is for {backup}"
+                ),
+            },
+            Msg::UnknownTitleId { title_id, game } => match lang {
+                Fr => write!(
+                    f,
+                    "{title_id} n'est l'ID de titre d'aucune version de {game} connue, le jeu ne voit la sauvegarde que si c'est celui de votre copie"
+                ),
+                De => write!(
+                    f,
+                    "{title_id} ist keine bekannte Title-ID einer {game}-Version, das Spiel sieht das Backup nur, wenn es die deiner Kopie ist"
+                ),
+                Es => write!(
+                    f,
+                    "{title_id} no es el ID de título de ninguna versión de {game} conocida, el juego solo ve la copia si es el de tu copia"
+                ),
+                It => write!(
+                    f,
+                    "{title_id} non è l'ID titolo di nessuna versione di {game} nota, il gioco vede il backup solo se è quello della tua copia"
+                ),
+                Pt => write!(
+                    f,
+                    "{title_id} não é o ID de título de nenhuma versão de {game} conhecida, o jogo só vê a cópia se for o da tua cópia"
+                ),
+                Nl => write!(
+                    f,
+                    "{title_id} is geen bekende title-ID van een {game}-versie, het spel ziet de back-up alleen als het die van jouw exemplaar is"
+                ),
+                Ja => write!(
+                    f,
+                    "{title_id} は既知の {game} のタイトル ID ではありません。お持ちのソフトのものでなければゲームにバックアップは表示されません"
+                ),
+                En => write!(
+                    f,
+                    "{title_id} isn't a title ID of any {game} release known to this tool, the game only sees the backup if it's the one of your copy"
+                ),
+            },
+            Msg::SlotIconFromImage(hash) => match lang {
+                Fr => write!(f, "Icône de l'emplacement : {hash} depuis l'image --icon"),
+                De => write!(f, "Slot-Icon: {hash} aus dem --icon-Bild"),
+                Es => write!(f, "Icono del slot: {hash} de la imagen de --icon"),
+                It => write!(f, "Icona dello slot: {hash} dall'immagine di --icon"),
+                Pt => write!(f, "Ícone do slot: {hash} da imagem de --icon"),
+                Nl => write!(f, "Sloticoon: {hash} uit de --icon-afbeelding"),
+                Ja => write!(f, "スロットアイコン: --icon の画像から {hash}"),
+                En => write!(f, "Slot icon: {hash} from the --icon image"),
+            },
+            Msg::MarkingCopyable => f.write_str(match lang {
+                Fr => "Le niveau est marqué comme copiable (--force-copyable)",
+                De => "Level wird als kopierbar markiert (--force-copyable)",
+                Es => "Marcando el nivel como copiable (--force-copyable)",
+                It => "Il livello viene segnato come copiabile (--force-copyable)",
+                Pt => "A marcar o nível como copiável (--force-copyable)",
+                Nl => "Level wordt als kopieerbaar gemarkeerd (--force-copyable)",
+                Ja => "レベルをコピー可能にします（--force-copyable）",
+                En => "Marking the level as copyable (--force-copyable)",
+            }),
+            Msg::CopyableNotWritten(revision) => match lang {
+                Fr => write!(
+                    f,
+                    "--force-copyable est ignoré, les listes de slots de la révision {} n'ont pas d'option copiable",
+                    revision
+                ),
+                De => write!(
+                    f,
+                    "--force-copyable wird ignoriert, Slot-Listen der Revision {} haben kein Kopierbar-Feld",
+                    revision
+                ),
+                Es => write!(
+                    f,
+                    "se ignora --force-copyable, las listas de slots de la revisión {} no tienen el campo de copiable",
+                    revision
+                ),
+                It => write!(
+                    f,
+                    "--force-copyable viene ignorato, le liste di slot della revisione {} non hanno il campo copiabile",
+                    revision
+                ),
+                Pt => write!(
+                    f,
+                    "--force-copyable é ignorado, as listas de slots da revisão {} não têm o campo copiável",
+                    revision
+                ),
+                Nl => write!(
+                    f,
+                    "--force-copyable wordt genegeerd, slotlijsten van revisie {} hebben geen kopieerbaar-veld",
+                    revision
+                ),
+                Ja => write!(
+                    f,
+                    "リビジョン{}のスロットリストにはコピー可能の項目がないため、--force-copyableは無視されます",
+                    revision
+                ),
+                En => write!(
+                    f,
+                    "ignoring --force-copyable, slot lists of revision {} have no copyable flag",
+                    revision
+                ),
+            },
+            Msg::ResourcesLeftOut { missing, meta } => match lang {
+                Fr => write!(
+                    f,
+                    "{missing} ressources ont été laissées de côté, le niveau peut manquer de textures ou de modèles (voir {meta})"
+                ),
+                De => write!(
+                    f,
+                    "{missing} Ressourcen wurden ausgelassen, dem Level fehlen eventuell Texturen oder Modelle (siehe {meta})"
+                ),
+                Es => write!(
+                    f,
+                    "se omitieron {missing} recursos, al nivel pueden faltarle texturas o modelos (ver {meta})"
+                ),
+                It => write!(
+                    f,
+                    "{missing} risorse sono state omesse, al livello potrebbero mancare texture o modelli (vedi {meta})"
+                ),
+                Pt => write!(
+                    f,
+                    "{missing} recursos ficaram de fora, podem faltar texturas ou modelos ao nível (ver {meta})"
+                ),
+                Nl => write!(
+                    f,
+                    "{missing} bronnen zijn weggelaten, het level mist misschien texturen of modellen (zie {meta})"
+                ),
+                Ja => write!(
+                    f,
+                    "リソース {missing} 件を省いたため、テクスチャやモデルが欠けている場合があります（{meta} を参照）"
+                ),
+                En => write!(
+                    f,
+                    "{missing} resources were left out, the level may be missing textures or models (see {meta})"
+                ),
+            },
+            Msg::SaveLevelsFound { named, unnamed } => match lang {
+                Fr => write!(
+                    f,
+                    "{named} niveaux avec un emplacement trouvés dans la sauvegarde, {unnamed} sans"
+                ),
+                De => write!(
+                    f,
+                    "{named} Level mit Slot im Spielstand gefunden, {unnamed} ohne"
+                ),
+                Es => write!(
+                    f,
+                    "Se encontraron {named} niveles con slot en la partida, {unnamed} sin él"
+                ),
+                It => write!(
+                    f,
+                    "Trovati {named} livelli con uno slot nel salvataggio, {unnamed} senza"
+                ),
+                Pt => write!(
+                    f,
+                    "Encontrados {named} níveis com slot no save, {unnamed} sem"
+                ),
+                Nl => write!(
+                    f,
+                    "{named} levels met een slot in de save gevonden, {unnamed} zonder"
+                ),
+                Ja => write!(
+                    f,
+                    "セーブにスロットのあるレベルが {named} 件、ないレベルが {unnamed} 件見つかりました"
+                ),
+                En => write!(
+                    f,
+                    "Found {named} levels with a slot in the save, {unnamed} without"
+                ),
+            },
+            Msg::IncludeUnnamedHint => f.write_str(match lang {
+                Fr => "  (ajoutez --include-unnamed pour les sauvegarder aussi)",
+                De => "  (--include-unnamed sichert auch diese)",
+                Es => "  (usa --include-unnamed para copiarlos también)",
+                It => "  (usa --include-unnamed per salvare anche quelli)",
+                Pt => "  (use --include-unnamed para os copiar também)",
+                Nl => "  (gebruik --include-unnamed om die ook te back-uppen)",
+                Ja => "  (--include-unnamed を付けるとそれらもバックアップします)",
+                En => "  (pass --include-unnamed to back those up too)",
+            }),
+            Msg::UnreadableSaveLevel(root_level) => match lang {
+                Fr => write!(f, "le niveau {root_level} n'est pas lisible, il est ignoré"),
+                De => write!(
+                    f,
+                    "Level {root_level} ist nicht lesbar und wird übersprungen"
+                ),
+                Es => write!(f, "el nivel {root_level} no se puede leer, se omite"),
+                It => write!(f, "il livello {root_level} non è leggibile, viene saltato"),
+                Pt => write!(f, "o nível {root_level} não é legível, a ignorá-lo"),
+                Nl => write!(
+                    f,
+                    "level {root_level} is niet leesbaar en wordt overgeslagen"
+                ),
+                Ja => write!(f, "レベル {root_level} は読み込めないためスキップします"),
+                En => write!(f, "level {root_level} isn't a readable level, skipping it"),
+            },
+            Msg::SaveLevelFailed { root_level, error } => match lang {
+                Fr => write!(
+                    f,
+                    "impossible de sauvegarder le niveau {root_level} : {error}"
+                ),
+                De => write!(
+                    f,
+                    "Level {root_level} konnte nicht gesichert werden: {error}"
+                ),
+                Es => write!(f, "no se pudo copiar el nivel {root_level}: {error}"),
+                It => write!(
+                    f,
+                    "impossibile fare il backup del livello {root_level}: {error}"
+                ),
+                Pt => write!(f, "não foi possível copiar o nível {root_level}: {error}"),
+                Nl => write!(f, "level {root_level} kon niet worden geback-upt: {error}"),
+                Ja => write!(
+                    f,
+                    "レベル {root_level} をバックアップできませんでした: {error}"
+                ),
+                En => write!(f, "couldn't back up level {root_level}: {error}"),
+            },
+            Msg::SaveLevelsDone {
+                written,
+                failed,
+                dry_run,
+            } => match (lang, dry_run) {
+                (Fr, true) => write!(f, "{written} niveaux vérifiés, {failed} en échec"),
+                (Fr, false) => write!(f, "{written} niveaux sauvegardés, {failed} en échec"),
+                (De, true) => write!(f, "{written} Level geprüft, {failed} fehlgeschlagen"),
+                (De, false) => write!(f, "{written} Level gesichert, {failed} fehlgeschlagen"),
+                (Es, true) => write!(f, "{written} niveles comprobados, {failed} fallidos"),
+                (Es, false) => write!(f, "{written} niveles copiados, {failed} fallidos"),
+                (It, true) => write!(f, "{written} livelli controllati, {failed} falliti"),
+                (It, false) => write!(f, "{written} livelli salvati, {failed} falliti"),
+                (Pt, true) => write!(f, "{written} níveis verificados, {failed} falharam"),
+                (Pt, false) => write!(f, "{written} níveis copiados, {failed} falharam"),
+                (Nl, true) => write!(f, "{written} levels gecontroleerd, {failed} mislukt"),
+                (Nl, false) => write!(f, "{written} levels geback-upt, {failed} mislukt"),
+                (Ja, true) => write!(f, "{written} 件のレベルを確認、{failed} 件失敗"),
+                (Ja, false) => write!(f, "{written} 件のレベルをバックアップ、{failed} 件失敗"),
+                (En, true) => write!(f, "{written} levels checked, {failed} failed"),
+                (En, false) => write!(f, "{written} levels backed up, {failed} failed"),
+            },
+            Msg::ParamsWritten(dir) => match lang {
+                Fr => write!(f, "PARAM.SFO et PARAM.PFD de {dir} réécrits"),
+                De => write!(f, "PARAM.SFO und PARAM.PFD von {dir} neu geschrieben"),
+                Es => write!(f, "PARAM.SFO y PARAM.PFD de {dir} escritos de nuevo"),
+                It => write!(f, "PARAM.SFO e PARAM.PFD di {dir} riscritti"),
+                Pt => write!(f, "PARAM.SFO e PARAM.PFD de {dir} escritos de novo"),
+                Nl => write!(f, "PARAM.SFO en PARAM.PFD van {dir} opnieuw geschreven"),
+                Ja => write!(f, "{dir} の PARAM.SFO と PARAM.PFD を書き直しました"),
+                En => write!(f, "PARAM.SFO and PARAM.PFD of {dir} written again"),
+            },
+            Msg::ConfigPathMissing { key, path } => {
+                let path = path.display();
+                match lang {
+                    Fr => write!(
+                        f,
+                        "{key} `{path}` n'existe pas, les commandes qui le lisent échoueront"
+                    ),
+                    De => write!(
+                        f,
+                        "{key} `{path}` existiert nicht, Befehle, die es lesen, schlagen fehl"
+                    ),
+                    Es => write!(
+                        f,
+                        "{key} `{path}` no existe, los comandos que lo leen fallarán"
+                    ),
+                    It => write!(
+                        f,
+                        "{key} `{path}` non esiste, i comandi che lo leggono falliranno"
+                    ),
+                    Pt => write!(
+                        f,
+                        "{key} `{path}` não existe, os comandos que o leem vão falhar"
+                    ),
+                    Nl => write!(
+                        f,
+                        "{key} `{path}` bestaat niet, opdrachten die het lezen mislukken"
+                    ),
+                    Ja => write!(
+                        f,
+                        "{key} `{path}` が存在しないため、これを読むコマンドは失敗します"
+                    ),
+                    En => write!(
+                        f,
+                        "{key} `{path}` doesn't exist, commands that read it will fail"
+                    ),
+                }
+            }
+            Msg::Bundling {
+                name,
+                creator,
+                game,
+            } => match lang {
+                Fr => write!(f, "Empaquetage de {name} par {creator} ({game})"),
+                De => write!(f, "{name} von {creator} ({game}) wird gepackt"),
+                Es => write!(f, "Empaquetando {name} de {creator} ({game})"),
+                It => write!(f, "Impacchettamento di {name} di {creator} ({game})"),
+                Pt => write!(f, "A empacotar {name} de {creator} ({game})"),
+                Nl => write!(f, "{name} van {creator} ({game}) wordt gebundeld"),
+                Ja => write!(f, "{creator} の {name} ({game}) をバンドル中"),
+                En => write!(f, "Bundling {name} by {creator} ({game})"),
+            },
+            Msg::IconNotConverted(error) => match lang {
+                Fr => write!(f, "impossible de convertir l'icône du niveau : {error}"),
+                De => write!(f, "das Levelicon konnte nicht umgewandelt werden: {error}"),
+                Es => write!(f, "no se pudo convertir el icono del nivel: {error}"),
+                It => write!(f, "impossibile convertire l'icona del livello: {error}"),
+                Pt => write!(f, "não foi possível converter o ícone do nível: {error}"),
+                Nl => write!(f, "het levelicoon kon niet worden omgezet: {error}"),
+                Ja => write!(f, "レベルのアイコンを変換できませんでした: {error}"),
+                En => write!(f, "couldn't convert the level icon: {error}"),
+            },
+            Msg::BundleResourcesLeftOut { missing, meta } => match lang {
+                Fr => write!(
+                    f,
+                    "{missing} ressources ont été laissées de côté (listées dans {meta})"
+                ),
+                De => write!(
+                    f,
+                    "{missing} Ressourcen wurden ausgelassen (aufgeführt in {meta})"
+                ),
+                Es => write!(f, "se omitieron {missing} recursos (listados en {meta})"),
+                It => write!(
+                    f,
+                    "{missing} risorse sono state tralasciate (elencate in {meta})"
+                ),
+                Pt => write!(
+                    f,
+                    "{missing} recursos foram deixados de fora (listados em {meta})"
+                ),
+                Nl => write!(f, "{missing} bronnen zijn weggelaten (vermeld in {meta})"),
+                Ja => write!(f, "{missing} 件のリソースを省きました ({meta} に記載)"),
+                En => write!(f, "{missing} resources were left out (listed in {meta})"),
+            },
+            Msg::BundleWritten { resources, path } => {
+                let path = path.display();
+                match lang {
+                    Fr => write!(f, "Paquet de {resources} ressources écrit dans {path}"),
+                    De => write!(
+                        f,
+                        "Paket mit {resources} Ressourcen geschrieben nach {path}"
+                    ),
+                    Es => write!(f, "Paquete con {resources} recursos escrito en {path}"),
+                    It => write!(f, "Pacchetto con {resources} risorse scritto in {path}"),
+                    Pt => write!(f, "Pacote com {resources} recursos escrito em {path}"),
+                    Nl => write!(f, "Bundel met {resources} bronnen geschreven naar {path}"),
+                    Ja => write!(
+                        f,
+                        "{resources} 件のリソースのバンドルを {path} に書き込みました"
+                    ),
+                    En => write!(f, "Bundle with {resources} resources written to {path}"),
+                }
+            }
+            Msg::CacheCleaned { unfinished, empty } => match lang {
+                Fr => write!(
+                    f,
+                    "{unfinished} fichiers inachevés et {empty} fichiers vides supprimés du cache de ressources"
+                ),
+                De => write!(
+                    f,
+                    "{unfinished} unfertige und {empty} leere Dateien aus dem Ressourcen-Cache entfernt"
+                ),
+                Es => write!(
+                    f,
+                    "Se quitaron {unfinished} archivos sin terminar y {empty} vacíos de la caché de recursos"
+                ),
+                It => write!(
+                    f,
+                    "Rimossi {unfinished} file incompleti e {empty} vuoti dalla cache delle risorse"
+                ),
+                Pt => write!(
+                    f,
+                    "Removidos {unfinished} ficheiros incompletos e {empty} vazios da cache de recursos"
+                ),
+                Nl => write!(
+                    f,
+                    "{unfinished} onvoltooide en {empty} lege bestanden uit de broncache verwijderd"
+                ),
+                Ja => write!(
+                    f,
+                    "リソースキャッシュから未完了のファイル {unfinished} 件と空のファイル {empty} 件を削除しました"
+                ),
+                En => write!(
+                    f,
+                    "Removed {unfinished} unfinished and {empty} empty files from the resource cache"
+                ),
+            },
+            Msg::UnreachableBlobs {
+                dir,
+                unreachable,
+                blobs,
+                size,
+            } => {
+                let dir = dir.display();
+                match lang {
+                    Fr => write!(
+                        f,
+                        "{dir} : {unreachable} blobs sur {blobs} inaccessibles ({size})"
+                    ),
+                    De => write!(
+                        f,
+                        "{dir}: {unreachable} von {blobs} Blobs unerreichbar ({size})"
+                    ),
+                    Es => write!(
+                        f,
+                        "{dir}: {unreachable} de {blobs} blobs inalcanzables ({size})"
+                    ),
+                    It => write!(
+                        f,
+                        "{dir}: {unreachable} blob su {blobs} irraggiungibili ({size})"
+                    ),
+                    Pt => write!(
+                        f,
+                        "{dir}: {unreachable} de {blobs} blobs inalcançáveis ({size})"
+                    ),
+                    Nl => write!(
+                        f,
+                        "{dir}: {unreachable} van {blobs} blobs onbereikbaar ({size})"
+                    ),
+                    Ja => write!(
+                        f,
+                        "{dir}: {blobs} 件中 {unreachable} 件の blob が到達不能 ({size})"
+                    ),
+                    En => write!(
+                        f,
+                        "{dir}: {unreachable} of {blobs} blobs unreachable ({size})"
+                    ),
+                }
+            }
+            Msg::GcNoManifest(dir) => {
+                let dir = dir.display();
+                match lang {
+                    Fr => write!(
+                        f,
+                        "{dir} contient des blobs mais aucun import ni hash de planète qui dise à quoi ils servent, laissé tel quel"
+                    ),
+                    De => write!(
+                        f,
+                        "{dir} hat Blobs, aber keinen Import und keinen Planeten-Hash, der sagt, wofür sie sind, wird nicht angefasst"
+                    ),
+                    Es => write!(
+                        f,
+                        "{dir} tiene blobs pero ningún import ni hash de planeta que diga para qué son, se deja como está"
+                    ),
+                    It => write!(
+                        f,
+                        "{dir} ha dei blob ma nessun import o hash di pianeta che dica a cosa servono, lasciato com'è"
+                    ),
+                    Pt => write!(
+                        f,
+                        "{dir} tem blobs mas nenhum import nem hash de planeta que diga para que servem, deixado como está"
+                    ),
+                    Nl => write!(
+                        f,
+                        "{dir} heeft blobs maar geen import of planeethash die zegt waarvoor ze zijn, blijft ongemoeid"
+                    ),
+                    Ja => write!(
+                        f,
+                        "{dir} には blob がありますが、用途を示すインポートもプラネットのハッシュもないため、そのままにします"
+                    ),
+                    En => write!(
+                        f,
+                        "{dir} has blobs but no import or planet hash naming what they're for, left alone"
+                    ),
+                }
+            }
+            Msg::GcZipped(zip) => {
+                let zip = zip.display();
+                match lang {
+                    Fr => write!(
+                        f,
+                        "les blobs de {zip} ne sont pas vérifiés, on ne peut pas retirer des entrées d'un ZIP sur place"
+                    ),
+                    De => write!(
+                        f,
+                        "die Blobs in {zip} werden nicht geprüft, aus einem ZIP lassen sich keine Einträge an Ort und Stelle entfernen"
+                    ),
+                    Es => write!(
+                        f,
+                        "los blobs de {zip} no se comprueban, a un ZIP no se le pueden quitar entradas en el sitio"
+                    ),
+                    It => write!(
+                        f,
+                        "i blob in {zip} non vengono controllati, da uno ZIP non si possono togliere voci sul posto"
+                    ),
+                    Pt => write!(
+                        f,
+                        "os blobs em {zip} não são verificados, não se podem remover entradas de um ZIP no próprio sítio"
+                    ),
+                    Nl => write!(
+                        f,
+                        "de blobs in {zip} worden niet gecontroleerd, uit een ZIP kunnen geen items ter plekke worden verwijderd"
+                    ),
+                    Ja => write!(
+                        f,
+                        "{zip} の blob は確認しません。ZIP からはその場でエントリを削除できません"
+                    ),
+                    En => write!(
+                        f,
+                        "the blobs in {zip} aren't checked, a ZIP can't have entries removed in place"
+                    ),
+                }
+            }
+            Msg::GcDone {
+                deleted,
+                blobs,
+                size,
+                dumps,
+            } => match (lang, deleted) {
+                (Fr, true) => write!(
+                    f,
+                    "{blobs} blobs inaccessibles supprimés ({size}) dans {dumps} dumps"
+                ),
+                (Fr, false) => write!(
+                    f,
+                    "{blobs} blobs inaccessibles trouvés ({size}) dans {dumps} dumps"
+                ),
+                (De, true) => write!(
+                    f,
+                    "{blobs} unerreichbare Blobs gelöscht ({size}) in {dumps} Dumps"
+                ),
+                (De, false) => write!(
+                    f,
+                    "{blobs} unerreichbare Blobs gefunden ({size}) in {dumps} Dumps"
+                ),
+                (Es, true) => write!(
+                    f,
+                    "Se borraron {blobs} blobs inalcanzables ({size}) en {dumps} volcados"
+                ),
+                (Es, false) => write!(
+                    f,
+                    "Se encontraron {blobs} blobs inalcanzables ({size}) en {dumps} volcados"
+                ),
+                (It, true) => write!(
+                    f,
+                    "Eliminati {blobs} blob irraggiungibili ({size}) in {dumps} dump"
+                ),
+                (It, false) => write!(
+                    f,
+                    "Trovati {blobs} blob irraggiungibili ({size}) in {dumps} dump"
+                ),
+                (Pt, true) => write!(
+                    f,
+                    "Apagados {blobs} blobs inalcançáveis ({size}) em {dumps} dumps"
+                ),
+                (Pt, false) => write!(
+                    f,
+                    "Encontrados {blobs} blobs inalcançáveis ({size}) em {dumps} dumps"
+                ),
+                (Nl, true) => write!(
+                    f,
+                    "{blobs} onbereikbare blobs verwijderd ({size}) in {dumps} dumps"
+                ),
+                (Nl, false) => write!(
+                    f,
+                    "{blobs} onbereikbare blobs gevonden ({size}) in {dumps} dumps"
+                ),
+                (Ja, true) => write!(
+                    f,
+                    "{dumps} 件のダンプで到達不能な blob {blobs} 件 ({size}) を削除しました"
+                ),
+                (Ja, false) => write!(
+                    f,
+                    "{dumps} 件のダンプで到達不能な blob {blobs} 件 ({size}) が見つかりました"
+                ),
+                (En, true) => write!(
+                    f,
+                    "Deleted {blobs} unreachable blobs ({size}) in {dumps} dumps"
+                ),
+                (En, false) => write!(
+                    f,
+                    "Found {blobs} unreachable blobs ({size}) in {dumps} dumps"
+                ),
+            },
+            Msg::GcDeleteHint => f.write_str(match lang {
+                Fr => "  (ajoutez --delete pour les supprimer)",
+                De => "  (--delete entfernt sie)",
+                Es => "  (añada --delete para quitarlos)",
+                It => "  (aggiungi --delete per rimuoverli)",
+                Pt => "  (use --delete para os remover)",
+                Nl => "  (geef --delete mee om ze te verwijderen)",
+                Ja => "  (--delete を付けると削除します)",
+                En => "  (pass --delete to remove them)",
+            }),
+            Msg::ParallelCapped(cap) => match lang {
+                Fr => write!(
+                    f,
+                    "max_parallel_downloads dépasse les {cap} processeurs, ramené à {cap} (--max-parallel-downloads pour forcer)"
+                ),
+                De => write!(
+                    f,
+                    "max_parallel_downloads ist höher als die {cap} CPUs, zurück auf {cap} (--max-parallel-downloads erzwingt es)"
+                ),
+                Es => write!(
+                    f,
+                    "max_parallel_downloads supera las {cap} CPU, se vuelve a {cap} (use --max-parallel-downloads para forzarlo)"
+                ),
+                It => write!(
+                    f,
+                    "max_parallel_downloads supera le {cap} CPU, riportato a {cap} (usa --max-parallel-downloads per forzarlo)"
+                ),
+                Pt => write!(
+                    f,
+                    "max_parallel_downloads é maior que os {cap} CPUs, a voltar a {cap} (use --max-parallel-downloads para forçar)"
+                ),
+                Nl => write!(
+                    f,
+                    "max_parallel_downloads is hoger dan de {cap} CPU's, terug naar {cap} (--max-parallel-downloads dwingt het af)"
+                ),
+                Ja => write!(
+                    f,
+                    "max_parallel_downloads が CPU 数 {cap} を超えているため {cap} に戻します (--max-parallel-downloads で上書き)"
+                ),
+                En => write!(
+                    f,
+                    "max_parallel_downloads is higher than the {cap} CPUs, reverting to {cap} (use --max-parallel-downloads to override)"
+                ),
+            },
+            Msg::PlanetsUnreadable { hash, error } => match lang {
+                Fr => write!(f, "impossible de lire les planètes {hash} : {error}"),
+                De => write!(f, "Planeten {hash} konnten nicht gelesen werden: {error}"),
+                Es => write!(f, "no se pudieron leer los planetas {hash}: {error}"),
+                It => write!(f, "impossibile leggere i pianeti {hash}: {error}"),
+                Pt => write!(f, "não foi possível ler os planetas {hash}: {error}"),
+                Nl => write!(f, "planeten {hash} konden niet worden gelezen: {error}"),
+                Ja => write!(f, "プラネット {hash} を読み込めませんでした: {error}"),
+                En => write!(f, "couldn't read planets {hash}: {error}"),
+            },
+            Msg::PlanetFilesWritten { files, dir } => {
+                let dir = dir.display();
+                match lang {
+                    Fr => write!(f, "{files} fichiers écrits dans {dir}"),
+                    De => write!(f, "{files} Dateien geschrieben nach {dir}"),
+                    Es => write!(f, "{files} archivos escritos en {dir}"),
+                    It => write!(f, "{files} file scritti in {dir}"),
+                    Pt => write!(f, "{files} ficheiros escritos em {dir}"),
+                    Nl => write!(f, "{files} bestanden geschreven naar {dir}"),
+                    Ja => write!(f, "{files} 件のファイルを {dir} に書き込みました"),
+                    En => write!(f, "wrote {files} files to {dir}"),
+                }
+            }
+            Msg::HashFileWritten { file, hash } => match lang {
+                Fr => write!(f, "{file} écrit → {hash}"),
+                De => write!(f, "{file} geschrieben → {hash}"),
+                Es => write!(f, "{file} escrito → {hash}"),
+                It => write!(f, "{file} scritto → {hash}"),
+                Pt => write!(f, "{file} escrito → {hash}"),
+                Nl => write!(f, "{file} geschreven → {hash}"),
+                Ja => write!(f, "{file} を書き込みました → {hash}"),
+                En => write!(f, "wrote {file} → {hash}"),
+            },
+            Msg::NoCreatorIcon(file) => match lang {
+                Fr => write!(
+                    f,
+                    "la base de données n'a pas d'icône pour le créateur de la planète, {file} n'est pas écrit"
+                ),
+                De => write!(
+                    f,
+                    "die Datenbank hat kein Icon für den Ersteller des Planeten, {file} wird nicht geschrieben"
+                ),
+                Es => write!(
+                    f,
+                    "la base de datos no tiene icono para el creador del planeta, no se escribe {file}"
+                ),
+                It => write!(
+                    f,
+                    "il database non ha un'icona per il creatore del pianeta, {file} non viene scritto"
+                ),
+                Pt => write!(
+                    f,
+                    "a base de dados não tem ícone para o criador do planeta, {file} não é escrito"
+                ),
+                Nl => write!(
+                    f,
+                    "de database heeft geen icoon voor de maker van de planeet, {file} wordt niet geschreven"
+                ),
+                Ja => write!(
+                    f,
+                    "データベースにプラネットの作成者のアイコンがないため、{file} は書き込みません"
+                ),
+                En => write!(
+                    f,
+                    "the database has no icon for the planet's creator, {file} not written"
+                ),
+            },
+            Msg::SubLevelAdded(hash) => match lang {
+                Fr => write!(f, "sous-niveau {hash} ajouté"),
+                De => write!(f, "Unterlevel {hash} hinzugefügt"),
+                Es => write!(f, "subnivel {hash} añadido"),
+                It => write!(f, "sottolivello {hash} aggiunto"),
+                Pt => write!(f, "subnível {hash} adicionado"),
+                Nl => write!(f, "sublevel {hash} toegevoegd"),
+                Ja => write!(f, "サブレベル {hash} を追加しました"),
+                En => write!(f, "added sub‐level {hash}"),
+            },
+            Msg::SubLevelsSkipped { skipped, dir } => {
+                let dir = dir.display();
+                match lang {
+                    Fr => write!(
+                        f,
+                        "{skipped} sous-niveaux déjà récupérés dans {dir}, ignorés"
+                    ),
+                    De => write!(
+                        f,
+                        "{skipped} Unterlevel schon nach {dir} geholt, übersprungen"
+                    ),
+                    Es => write!(f, "{skipped} subniveles ya obtenidos en {dir}, omitidos"),
+                    It => write!(f, "{skipped} sottolivelli già recuperati in {dir}, saltati"),
+                    Pt => write!(f, "{skipped} subníveis já obtidos em {dir}, ignorados"),
+                    Nl => write!(f, "{skipped} sublevels al opgehaald in {dir}, overgeslagen"),
+                    Ja => write!(
+                        f,
+                        "{skipped} 件のサブレベルは {dir} に取得済みのためスキップしました"
+                    ),
+                    En => write!(
+                        f,
+                        "{skipped} sub-levels already fetched into {dir}, skipped"
+                    ),
+                }
+            }
+            Msg::PlanetSltFetched(hash) => match lang {
+                Fr => write!(f, "SLTb de la planète {hash} récupéré"),
+                De => write!(f, "SLTb des Planeten {hash} geholt"),
+                Es => write!(f, "SLTb del planeta {hash} obtenido"),
+                It => write!(f, "SLTb del pianeta {hash} recuperato"),
+                Pt => write!(f, "SLTb do planeta {hash} obtido"),
+                Nl => write!(f, "SLTb van planeet {hash} opgehaald"),
+                Ja => write!(f, "プラネット {hash} の SLTb を取得しました"),
+                En => write!(f, "Fetched planet {hash} SLTb"),
+            },
+            Msg::PlanetSltWritten(hash) => match lang {
+                Fr => write!(f, "blob SLTb de la planète écrit sous {hash}"),
+                De => write!(f, "SLTb-Blob des Planeten als {hash} geschrieben"),
+                Es => write!(f, "blob SLTb del planeta escrito como {hash}"),
+                It => write!(f, "blob SLTb del pianeta scritto come {hash}"),
+                Pt => write!(f, "blob SLTb do planeta escrito como {hash}"),
+                Nl => write!(f, "SLTb-blob van de planeet geschreven als {hash}"),
+                Ja => write!(f, "プラネットの SLTb blob を {hash} として書き込みました"),
+                En => write!(f, "wrote planet SLTb blob as {hash}"),
+            },
+            Msg::BlobsFetched {
+                blobs,
+                fetched,
+                failed,
+                dir,
+            } => {
+                let dir = dir.display();
+                match lang {
+                    Fr => write!(f, "{blobs} blobs récupérés ({fetched}/{failed}) → {dir}"),
+                    De => write!(f, "{blobs} Blobs geholt ({fetched}/{failed}) → {dir}"),
+                    Es => write!(f, "{blobs} blobs obtenidos ({fetched}/{failed}) → {dir}"),
+                    It => write!(f, "{blobs} blob recuperati ({fetched}/{failed}) → {dir}"),
+                    Pt => write!(f, "{blobs} blobs obtidos ({fetched}/{failed}) → {dir}"),
+                    Nl => write!(f, "{blobs} blobs opgehaald ({fetched}/{failed}) → {dir}"),
+                    Ja => write!(
+                        f,
+                        "{blobs} 件の blob を取得しました ({fetched}/{failed}) → {dir}"
+                    ),
+                    En => write!(f, "Fetched {blobs} blobs ({fetched}/{failed}) → {dir}"),
+                }
+            }
+            Msg::FetchingParentPlanet(hash) => match lang {
+                Fr => write!(f, "Récupération de la planète parente {hash}"),
+                De => write!(f, "Übergeordneter Planet {hash} wird geholt"),
+                Es => write!(f, "Obteniendo el planeta padre {hash}"),
+                It => write!(f, "Recupero del pianeta genitore {hash}"),
+                Pt => write!(f, "A obter o planeta pai {hash}"),
+                Nl => write!(f, "Bovenliggende planeet {hash} wordt opgehaald"),
+                Ja => write!(f, "親プラネット {hash} を取得中"),
+                En => write!(f, "Fetching parent planet {hash}"),
+            },
+            Msg::FetchingCreatorPlanet(hash) => match lang {
+                Fr => write!(f, "récupération de la planète du créateur {hash}"),
+                De => write!(f, "Planet des Erstellers {hash} wird geholt"),
+                Es => write!(f, "obteniendo el planeta del creador {hash}"),
+                It => write!(f, "recupero del pianeta del creatore {hash}"),
+                Pt => write!(f, "a obter o planeta do criador {hash}"),
+                Nl => write!(f, "planeet van de maker {hash} wordt opgehaald"),
+                Ja => write!(f, "作成者のプラネット {hash} を取得中"),
+                En => write!(f, "fetching creator-planet {hash}"),
+            },
+            Msg::LevelIconWritten(hash) => match lang {
+                Fr => write!(f, "blob de l'icône du niveau écrit sous {hash}"),
+                De => write!(f, "Levelicon-Blob als {hash} geschrieben"),
+                Es => write!(f, "blob del icono del nivel escrito como {hash}"),
+                It => write!(f, "blob dell'icona del livello scritto come {hash}"),
+                Pt => write!(f, "blob do ícone do nível escrito como {hash}"),
+                Nl => write!(f, "levelicoon-blob geschreven als {hash}"),
+                Ja => write!(f, "レベルアイコンの blob を {hash} として書き込みました"),
+                En => write!(f, "wrote level icon blob as {hash}"),
+            },
+            Msg::IconNotDownloaded(hash) => match lang {
+                Fr => write!(
+                    f,
+                    "le SHA1 d'icône {hash} n'est pas dans les ressources récupérées"
+                ),
+                De => write!(
+                    f,
+                    "Icon-SHA1 {hash} ist nicht unter den geholten Ressourcen"
+                ),
+                Es => write!(
+                    f,
+                    "el SHA1 de icono {hash} no está en los recursos obtenidos"
+                ),
+                It => write!(
+                    f,
+                    "lo SHA1 dell'icona {hash} non è tra le risorse recuperate"
+                ),
+                Pt => write!(f, "o SHA1 de ícone {hash} não está nos recursos obtidos"),
+                Nl => write!(f, "icoon-SHA1 {hash} zit niet in de opgehaalde bronnen"),
+                Ja => write!(f, "アイコンの SHA1 {hash} が取得したリソースにありません"),
+                En => write!(f, "icon SHA1 {hash} not in downloaded resources"),
+            },
+            Msg::CreatorIconWritten(hash) => match lang {
+                Fr => write!(f, "blob de l'icône du créateur écrit sous {hash}"),
+                De => write!(f, "Ersteller-Icon-Blob als {hash} geschrieben"),
+                Es => write!(f, "blob del icono del creador escrito como {hash}"),
+                It => write!(f, "blob dell'icona del creatore scritto come {hash}"),
+                Pt => write!(f, "blob do ícone do criador escrito como {hash}"),
+                Nl => write!(f, "icoon-blob van de maker geschreven als {hash}"),
+                Ja => write!(f, "作成者アイコンの blob を {hash} として書き込みました"),
+                En => write!(f, "wrote creator icon blob as {hash}"),
+            },
+            Msg::CreatorIconNotInArchive(hash) => match lang {
+                Fr => write!(
+                    f,
+                    "le SHA1 d'icône du créateur {hash} est introuvable dans l'archive locale"
+                ),
+                De => write!(
+                    f,
+                    "Ersteller-Icon-SHA1 {hash} nicht im lokalen Archiv gefunden"
+                ),
+                Es => write!(
+                    f,
+                    "el SHA1 del icono del creador {hash} no está en el archivo local"
+                ),
+                It => write!(
+                    f,
+                    "lo SHA1 dell'icona del creatore {hash} non è nell'archivio locale"
+                ),
+                Pt => write!(
+                    f,
+                    "o SHA1 do ícone do criador {hash} não está no arquivo local"
+                ),
+                Nl => write!(
+                    f,
+                    "icoon-SHA1 {hash} van de maker niet gevonden in het lokale archief"
+                ),
+                Ja => write!(
+                    f,
+                    "作成者アイコンの SHA1 {hash} がローカルアーカイブにありません"
+                ),
+                En => write!(f, "creator icon SHA1 {hash} not found in local archive"),
+            },
+            Msg::CreatorIconLength(len) => match lang {
+                Fr => write!(
+                    f,
+                    "longueur inattendue de creator.icon : {len} octets (20 attendus)"
+                ),
+                De => write!(
+                    f,
+                    "Unerwartete Länge von creator.icon: {len} Bytes (20 erwartet)"
+                ),
+                Es => write!(
+                    f,
+                    "Longitud inesperada de creator.icon: {len} bytes (se esperaban 20)"
+                ),
+                It => write!(
+                    f,
+                    "Lunghezza inattesa di creator.icon: {len} byte (attesi 20)"
+                ),
+                Pt => write!(
+                    f,
+                    "Comprimento inesperado de creator.icon: {len} bytes (esperados 20)"
+                ),
+                Nl => write!(
+                    f,
+                    "Onverwachte lengte van creator.icon: {len} bytes (20 verwacht)"
+                ),
+                Ja => write!(
+                    f,
+                    "creator.icon の長さが想定外です: {len} バイト (20 を想定)"
+                ),
+                En => write!(
+                    f,
+                    "Unexpected creator.icon length: {len} bytes (expected 20)"
+                ),
+            },
+            Msg::IconConverted { icon, png } => match lang {
+                Fr => write!(f, "icône {icon} convertie en PNG {png}"),
+                De => write!(f, "Icon {icon} in PNG {png} umgewandelt"),
+                Es => write!(f, "icono {icon} convertido a PNG {png}"),
+                It => write!(f, "icona {icon} convertita in PNG {png}"),
+                Pt => write!(f, "ícone {icon} convertido em PNG {png}"),
+                Nl => write!(f, "icoon {icon} omgezet naar PNG {png}"),
+                Ja => write!(f, "アイコン {icon} を PNG {png} に変換しました"),
+                En => write!(f, "converted icon {icon} to PNG {png}"),
+            },
+            Msg::IconConversionFailed { icon, error } => match lang {
+                Fr => write!(f, "impossible de convertir l'icône {icon} : {error}"),
+                De => write!(f, "Icon {icon} konnte nicht umgewandelt werden: {error}"),
+                Es => write!(f, "no se pudo convertir el icono {icon}: {error}"),
+                It => write!(f, "impossibile convertire l'icona {icon}: {error}"),
+                Pt => write!(f, "não foi possível converter o ícone {icon}: {error}"),
+                Nl => write!(f, "icoon {icon} kon niet worden omgezet: {error}"),
+                Ja => write!(f, "アイコン {icon} を変換できませんでした: {error}"),
+                En => write!(f, "couldn't convert icon {icon}: {error}"),
+            },
+            Msg::RealmImportDone => f.write_str(match lang {
+                Fr => "import.json écrit et refreshGameServer.realm produit",
+                De => "import.json geschrieben und refreshGameServer.realm erzeugt",
+                Es => "import.json escrito y refreshGameServer.realm generado",
+                It => "import.json scritto e refreshGameServer.realm prodotto",
+                Pt => "import.json escrito e refreshGameServer.realm gerado",
+                Nl => "import.json geschreven en refreshGameServer.realm gemaakt",
+                Ja => "import.json を書き込み、refreshGameServer.realm を生成しました",
+                En => "Wrote import.json and produced refreshGameServer.realm",
+            }),
+            Msg::NoCreatorLevels(creator) => match lang {
+                Fr => write!(f, "Aucun niveau trouvé pour `{creator}`"),
+                De => write!(f, "Keine Level für `{creator}` gefunden"),
+                Es => write!(f, "No se encontraron niveles de `{creator}`"),
+                It => write!(f, "Nessun livello trovato per `{creator}`"),
+                Pt => write!(f, "Nenhum nível encontrado para `{creator}`"),
+                Nl => write!(f, "Geen levels gevonden voor `{creator}`"),
+                Ja => write!(f, "`{creator}` のレベルが見つかりません"),
+                En => write!(f, "No levels found for `{creator}`"),
+            },
+            Msg::CreatorLevels {
+                creator,
+                levels,
+                games,
+            } => match lang {
+                Fr => write!(f, "`{creator}` a {levels} niveaux : {games}"),
+                De => write!(f, "`{creator}` hat {levels} Level: {games}"),
+                Es => write!(f, "`{creator}` tiene {levels} niveles: {games}"),
+                It => write!(f, "`{creator}` ha {levels} livelli: {games}"),
+                Pt => write!(f, "`{creator}` tem {levels} níveis: {games}"),
+                Nl => write!(f, "`{creator}` heeft {levels} levels: {games}"),
+                Ja => write!(f, "`{creator}` のレベルは {levels} 件: {games}"),
+                En => write!(f, "`{creator}` has {levels} levels: {games}"),
+            },
+            Msg::NoGameLevels { game, creator } => match lang {
+                Fr => write!(f, "Aucun niveau {game} trouvé pour `{creator}`"),
+                De => write!(f, "Keine {game}-Level für `{creator}` gefunden"),
+                Es => write!(f, "No se encontraron niveles de {game} de `{creator}`"),
+                It => write!(f, "Nessun livello di {game} trovato per `{creator}`"),
+                Pt => write!(f, "Nenhum nível de {game} encontrado para `{creator}`"),
+                Nl => write!(f, "Geen {game}-levels gevonden voor `{creator}`"),
+                Ja => write!(f, "`{creator}` の {game} のレベルが見つかりません"),
+                En => write!(f, "No {game} levels found for `{creator}`"),
+            },
+            Msg::FetchingOnlyGame { levels, game } => match lang {
+                Fr => write!(f, "Récupération des seuls {levels} niveaux {game}"),
+                De => write!(f, "Nur die {levels} {game}-Level werden geholt"),
+                Es => write!(f, "Obteniendo solo los {levels} niveles de {game}"),
+                It => write!(f, "Recupero dei soli {levels} livelli di {game}"),
+                Pt => write!(f, "A obter só os {levels} níveis de {game}"),
+                Nl => write!(f, "Alleen de {levels} {game}-levels worden opgehaald"),
+                Ja => write!(f, "{game} のレベル {levels} 件だけを取得します"),
+                En => write!(f, "Fetching only the {levels} {game} levels"),
+            },
+            Msg::NothingPicked => f.write_str(match lang {
+                Fr => "Aucun niveau choisi, rien à récupérer",
+                De => "Keine Level ausgewählt, nichts zu holen",
+                Es => "No se eligió ningún nivel, nada que obtener",
+                It => "Nessun livello scelto, niente da recuperare",
+                Pt => "Nenhum nível escolhido, nada a obter",
+                Nl => "Geen levels gekozen, niets op te halen",
+                Ja => "レベルが選ばれていないため、取得するものはありません",
+                En => "No levels picked, nothing to fetch",
+            }),
+            Msg::FetchingPicked(levels) => match lang {
+                Fr => write!(f, "Récupération des {levels} niveaux choisis"),
+                De => write!(f, "Die {levels} ausgewählten Level werden geholt"),
+                Es => write!(f, "Obteniendo los {levels} niveles elegidos"),
+                It => write!(f, "Recupero dei {levels} livelli scelti"),
+                Pt => write!(f, "A obter os {levels} níveis escolhidos"),
+                Nl => write!(f, "De {levels} gekozen levels worden opgehaald"),
+                Ja => write!(f, "選んだ {levels} 件のレベルを取得します"),
+                En => write!(f, "Fetching the {levels} picked levels"),
+            },
+            Msg::LevelHeading(id) => match lang {
+                Fr => write!(f, "=== Niveau {id} ==="),
+                De => write!(f, "=== Level {id} ==="),
+                Es => write!(f, "=== Nivel {id} ==="),
+                It => write!(f, "=== Livello {id} ==="),
+                Pt => write!(f, "=== Nível {id} ==="),
+                Nl => write!(f, "=== Level {id} ==="),
+                Ja => write!(f, "=== レベル {id} ==="),
+                En => write!(f, "=== Level {id} ==="),
+            },
+            Msg::LevelSkipped { id, error } => match lang {
+                Fr => write!(f, "Niveau {id} ignoré à cause d'une erreur : {error}"),
+                De => write!(f, "Level {id} wegen eines Fehlers übersprungen: {error}"),
+                Es => write!(f, "Nivel {id} omitido por un error: {error}"),
+                It => write!(f, "Livello {id} saltato per un errore: {error}"),
+                Pt => write!(f, "Nível {id} ignorado devido a um erro: {error}"),
+                Nl => write!(f, "Level {id} overgeslagen door een fout: {error}"),
+                Ja => write!(f, "エラーのためレベル {id} をスキップしました: {error}"),
+                En => write!(f, "Skipped level {id} due to error: {error}"),
+            },
+            Msg::LevelFolderMissing(dir) => {
+                let dir = dir.display();
+                match lang {
+                    Fr => write!(f, "Le dossier attendu {dir} manque, copie ignorée"),
+                    De => write!(f, "Erwarteter Ordner {dir} fehlt, Kopie übersprungen"),
+                    Es => write!(f, "Falta la carpeta esperada {dir}, se omite la copia"),
+                    It => write!(f, "Manca la cartella attesa {dir}, copia saltata"),
+                    Pt => write!(f, "Falta a pasta esperada {dir}, cópia ignorada"),
+                    Nl => write!(f, "Verwachte map {dir} ontbreekt, kopiëren overgeslagen"),
+                    Ja => write!(
+                        f,
+                        "想定したフォルダ {dir} がないため、コピーをスキップします"
+                    ),
+                    En => write!(f, "Expected folder {dir} missing, skipping copy"),
+                }
+            }
+            Msg::ImportWritten {
+                path,
+                levels,
+                assets,
+            } => {
+                let path = path.display();
+                match lang {
+                    Fr => write!(
+                        f,
+                        "{path} écrit avec {levels} niveaux et {assets} ressources"
+                    ),
+                    De => write!(
+                        f,
+                        "{path} mit {levels} Leveln und {assets} Assets geschrieben"
+                    ),
+                    Es => write!(f, "{path} escrito con {levels} niveles y {assets} recursos"),
+                    It => write!(f, "{path} scritto con {levels} livelli e {assets} risorse"),
+                    Pt => write!(f, "{path} escrito com {levels} níveis e {assets} recursos"),
+                    Nl => write!(
+                        f,
+                        "{path} geschreven met {levels} levels en {assets} assets"
+                    ),
+                    Ja => write!(
+                        f,
+                        "レベル {levels} 件とアセット {assets} 件で {path} を書き込みました"
+                    ),
+                    En => write!(f, "Wrote {path} with {levels} levels and {assets} assets"),
+                }
+            }
+            Msg::GameFetched {
+                game,
+                fetched,
+                failed,
+            } => match lang {
+                Fr => write!(f, "{game} : {fetched} niveaux récupérés, {failed} en échec"),
+                De => write!(f, "{game}: {fetched} Level geholt, {failed} fehlgeschlagen"),
+                Es => write!(f, "{game}: {fetched} niveles obtenidos, {failed} fallidos"),
+                It => write!(f, "{game}: {fetched} livelli recuperati, {failed} falliti"),
+                Pt => write!(f, "{game}: {fetched} níveis obtidos, {failed} falharam"),
+                Nl => write!(f, "{game}: {fetched} levels opgehaald, {failed} mislukt"),
+                Ja => write!(f, "{game}: {fetched} 件のレベルを取得、{failed} 件失敗"),
+                En => write!(f, "{game}: {fetched} levels fetched, {failed} failed"),
+            },
+            Msg::CreatorDumped { creator, dir } => {
+                let dir = dir.display();
+                match lang {
+                    Fr => write!(
+                        f,
+                        "Tous les fichiers uniques de `{creator}` sont dans `{dir}`"
+                    ),
+                    De => write!(
+                        f,
+                        "Alle eindeutigen Dateien von `{creator}` liegen jetzt in `{dir}`"
+                    ),
+                    Es => write!(
+                        f,
+                        "Todos los archivos únicos de `{creator}` están ya en `{dir}`"
+                    ),
+                    It => write!(f, "Tutti i file unici di `{creator}` ora sono in `{dir}`"),
+                    Pt => write!(
+                        f,
+                        "Todos os ficheiros únicos de `{creator}` estão agora em `{dir}`"
+                    ),
+                    Nl => write!(
+                        f,
+                        "Alle unieke bestanden van `{creator}` staan nu in `{dir}`"
+                    ),
+                    Ja => write!(
+                        f,
+                        "`{creator}` の重複しないファイルはすべて `{dir}` にあります"
+                    ),
+                    En => write!(f, "All unique files for `{creator}` are now in `{dir}`"),
+                }
+            }
+            Msg::CreatorAlreadyDone { creator, state } => match lang {
+                Fr => write!(f, "`{creator}` déjà {state}, ignoré"),
+                De => write!(f, "`{creator}` schon {state}, übersprungen"),
+                Es => write!(f, "`{creator}` ya {state}, se omite"),
+                It => write!(f, "`{creator}` già {state}, saltato"),
+                Pt => write!(f, "`{creator}` já {state}, ignorado"),
+                Nl => write!(f, "`{creator}` al {state}, overgeslagen"),
+                Ja => write!(f, "`{creator}` は {state} 済みのためスキップします"),
+                En => write!(f, "`{creator}` already {state}, skipping"),
+            },
+            Msg::FetchingCreator(creator) => match lang {
+                Fr => write!(f, "Récupération de toute la planète de `{creator}`…"),
+                De => write!(f, "Der ganze Planet von `{creator}` wird geholt…"),
+                Es => write!(f, "Obteniendo el planeta entero de `{creator}`…"),
+                It => write!(f, "Recupero dell'intero pianeta di `{creator}`…"),
+                Pt => write!(f, "A obter o planeta inteiro de `{creator}`…"),
+                Nl => write!(f, "De hele planeet van `{creator}` wordt opgehaald…"),
+                Ja => write!(f, "`{creator}` のプラネット全体を取得中…"),
+                En => write!(f, "Fetching entire planet for `{creator}`…"),
+            },
+            Msg::CreatorFailed { creator, error } => match lang {
+                Fr => write!(f, "`{creator}` a échoué : {error}"),
+                De => write!(f, "`{creator}` fehlgeschlagen: {error}"),
+                Es => write!(f, "`{creator}` falló: {error}"),
+                It => write!(f, "`{creator}` non riuscito: {error}"),
+                Pt => write!(f, "`{creator}` falhou: {error}"),
+                Nl => write!(f, "`{creator}` mislukt: {error}"),
+                Ja => write!(f, "`{creator}` は失敗しました: {error}"),
+                En => write!(f, "`{creator}` failed: {error}"),
+            },
+            Msg::NoCreatorFolder { creator, dir } => match lang {
+                Fr => write!(f, "pas de dossier pour `{creator}` à {dir:?}"),
+                De => write!(f, "kein Ordner für `{creator}` unter {dir:?}"),
+                Es => write!(f, "no hay carpeta de `{creator}` en {dir:?}"),
+                It => write!(f, "nessuna cartella per `{creator}` in {dir:?}"),
+                Pt => write!(f, "não há pasta para `{creator}` em {dir:?}"),
+                Nl => write!(f, "geen map voor `{creator}` op {dir:?}"),
+                Ja => write!(f, "{dir:?} に `{creator}` のフォルダがありません"),
+                En => write!(f, "no folder for `{creator}` at {dir:?}"),
+            },
+            Msg::DumpStoppedEarly(dir) => {
+                let dir = dir.display();
+                match lang {
+                    Fr => write!(
+                        f,
+                        "Arrêt anticipé, relancez avec `--resume {dir}` pour récupérer le reste"
+                    ),
+                    De => write!(
+                        f,
+                        "Vorzeitig gestoppt, mit `--resume {dir}` erneut starten, um den Rest zu holen"
+                    ),
+                    Es => write!(
+                        f,
+                        "Detenido antes de tiempo, vuelva a ejecutar con `--resume {dir}` para obtener el resto"
+                    ),
+                    It => write!(
+                        f,
+                        "Fermato in anticipo, rilancia con `--resume {dir}` per recuperare il resto"
+                    ),
+                    Pt => write!(
+                        f,
+                        "Parado mais cedo, volte a correr com `--resume {dir}` para obter o resto"
+                    ),
+                    Nl => write!(
+                        f,
+                        "Vroegtijdig gestopt, start opnieuw met `--resume {dir}` om de rest op te halen"
+                    ),
+                    Ja => write!(
+                        f,
+                        "途中で停止しました。残りは `--resume {dir}` で再実行して取得してください"
+                    ),
+                    En => write!(
+                        f,
+                        "Stopped early, rerun with `--resume {dir}` to fetch the rest"
+                    ),
+                }
+            }
+            Msg::DumpDone(dir) => match lang {
+                Fr => write!(f, "Tous les fichiers ont été copiés dans {dir:?}"),
+                De => write!(f, "Alle Dateien liegen in {dir:?}"),
+                Es => write!(f, "Todos los archivos se volcaron en {dir:?}"),
+                It => write!(f, "Tutti i file sono stati copiati in {dir:?}"),
+                Pt => write!(f, "Todos os ficheiros foram copiados para {dir:?}"),
+                Nl => write!(f, "Alle bestanden staan in {dir:?}"),
+                Ja => write!(f, "すべてのファイルを {dir:?} にダンプしました"),
+                En => write!(f, "All files dumped into {dir:?}"),
+            },
+            Msg::CreatorsFailed { failed, dir } => {
+                let dir = dir.display();
+                match lang {
+                    Fr => write!(
+                        f,
+                        "{failed} créateur(s) en échec, relancez avec `--resume {dir}` pour réessayer"
+                    ),
+                    De => write!(
+                        f,
+                        "{failed} Ersteller fehlgeschlagen, mit `--resume {dir}` erneut versuchen"
+                    ),
+                    Es => write!(
+                        f,
+                        "{failed} creador(es) fallaron, vuelva a ejecutar con `--resume {dir}` para reintentarlos"
+                    ),
+                    It => write!(
+                        f,
+                        "{failed} creatore/i non riusciti, rilancia con `--resume {dir}` per riprovare"
+                    ),
+                    Pt => write!(
+                        f,
+                        "{failed} criador(es) falharam, volte a correr com `--resume {dir}` para tentar de novo"
+                    ),
+                    Nl => write!(
+                        f,
+                        "{failed} maker(s) mislukt, start opnieuw met `--resume {dir}` om ze opnieuw te proberen"
+                    ),
+                    Ja => write!(
+                        f,
+                        "{failed} 人の作成者が失敗しました。`--resume {dir}` で再実行してやり直してください"
+                    ),
+                    En => write!(
+                        f,
+                        "{failed} creator(s) failed, rerun with `--resume {dir}` to retry them"
+                    ),
+                }
+            }
+            Msg::OverMaxBackupSize(limit) => match lang {
+                Fr => write!(f, "cela dépasse max_backup_size ({limit}), la sauvegarde échouerait"),
+                De => write!(f, "das ist mehr als max_backup_size ({limit}), die Sicherung würde fehlschlagen"),
+                Es => write!(f, "eso supera max_backup_size ({limit}), la copia fallaría"),
+                It => write!(f, "supera max_backup_size ({limit}), il backup fallirebbe"),
+                Pt => write!(f, "isso excede max_backup_size ({limit}), o backup falharia"),
+                Nl => write!(f, "dat is meer dan max_backup_size ({limit}), de back-up zou mislukken"),
+                Ja => write!(f, "max_backup_size ({limit}) を超えているため、バックアップは失敗します"),
+                En => write!(f, "that's over max_backup_size ({limit}), the backup would fail"),
+            },
+            Msg::FileWritten(path) => {
+                let path = path.display();
+                match lang {
+                    Fr => write!(f, "{path} écrit"),
+                    De => write!(f, "{path} geschrieben"),
+                    Es => write!(f, "{path} escrito"),
+                    It => write!(f, "{path} scritto"),
+                    Pt => write!(f, "{path} escrito"),
+                    Nl => write!(f, "{path} geschreven"),
+                    Ja => write!(f, "{path} を書き込みました"),
+                    En => write!(f, "Wrote {path}"),
+                }
+            }
+            Msg::SlotsWritten { slots, path } => {
+                let path = path.display();
+                match lang {
+                    Fr => write!(f, "{slots} slots écrits dans {path}"),
+                    De => write!(f, "{slots} Slots geschrieben nach {path}"),
+                    Es => write!(f, "{slots} slots escritos en {path}"),
+                    It => write!(f, "{slots} slot scritti in {path}"),
+                    Pt => write!(f, "{slots} slots escritos em {path}"),
+                    Nl => write!(f, "{slots} slots geschreven naar {path}"),
+                    Ja => write!(f, "{slots} 件のスロットを {path} に書き込みました"),
+                    En => write!(f, "Wrote {slots} slots to {path}"),
+                }
+            }
+            Msg::GraphWritten {
+                levels,
+                nodes,
+                edges,
+                path,
+                elapsed,
+            } => {
+                let path = path.display();
+                match lang {
+                    Fr => write!(
+                        f,
+                        "{levels} niveaux, {nodes} nœuds et {edges} arêtes écrits dans {path} en {elapsed:.2?}"
+                    ),
+                    De => write!(
+                        f,
+                        "{levels} Level, {nodes} Knoten und {edges} Kanten in {elapsed:.2?} nach {path} geschrieben"
+                    ),
+                    Es => write!(
+                        f,
+                        "{levels} niveles, {nodes} nodos y {edges} aristas escritos en {path} en {elapsed:.2?}"
+                    ),
+                    It => write!(
+                        f,
+                        "{levels} livelli, {nodes} nodi e {edges} archi scritti in {path} in {elapsed:.2?}"
+                    ),
+                    Pt => write!(
+                        f,
+                        "{levels} níveis, {nodes} nós e {edges} arestas escritos em {path} em {elapsed:.2?}"
+                    ),
+                    Nl => write!(
+                        f,
+                        "{levels} levels, {nodes} knopen en {edges} randen in {elapsed:.2?} geschreven naar {path}"
+                    ),
+                    Ja => write!(
+                        f,
+                        "レベル {levels} 件、ノード {nodes} 件、エッジ {edges} 件を {elapsed:.2?} で {path} に書き込みました"
+                    ),
+                    En => write!(
+                        f,
+                        "Wrote {levels} levels, {nodes} nodes and {edges} edges to {path} in {elapsed:.2?}"
+                    ),
+                }
+            }
+            Msg::LevelsUnreadableMissing(failed) => match lang {
+                Fr => write!(f, "  {failed} niveaux n'ont pas pu être lus et manquent"),
+                De => write!(f, "  {failed} Level konnten nicht gelesen werden und fehlen"),
+                Es => write!(f, "  {failed} niveles no se pudieron leer y faltan"),
+                It => write!(f, "  {failed} livelli non sono stati letti e mancano"),
+                Pt => write!(f, "  {failed} níveis não puderam ser lidos e faltam"),
+                Nl => write!(f, "  {failed} levels konden niet worden gelezen en ontbreken"),
+                Ja => write!(f, "  {failed} 件のレベルを読み込めず、含まれていません"),
+                En => write!(f, "  {failed} levels couldn't be read and are missing"),
+            },
+            Msg::NoCreator => f.write_str(match lang {
+                Fr => "(aucun créateur)",
+                De => "(kein Ersteller)",
+                Es => "(sin creador)",
+                It => "(nessun creatore)",
+                Pt => "(sem criador)",
+                Nl => "(geen maker)",
+                Ja => "(作成者なし)",
+                En => "(no creator)",
+            }),
+            Msg::Dependents {
+                dependents,
+                hash,
+                path,
+            } => {
+                let path = path.display();
+                match lang {
+                    Fr => write!(f, "{dependents} ressources dépendent de {hash} dans {path}"),
+                    De => write!(f, "{dependents} Ressourcen hängen in {path} von {hash} ab"),
+                    Es => write!(f, "{dependents} recursos dependen de {hash} en {path}"),
+                    It => write!(f, "{dependents} risorse dipendono da {hash} in {path}"),
+                    Pt => write!(f, "{dependents} recursos dependem de {hash} em {path}"),
+                    Nl => write!(f, "{dependents} bronnen hangen af van {hash} in {path}"),
+                    Ja => write!(f, "{path} では {dependents} 件のリソースが {hash} に依存しています"),
+                    En => write!(f, "{dependents} resources depend on {hash} in {path}"),
+                }
+            }
+            Msg::NoSlotUses(hash) => match lang {
+                Fr => write!(f, "Aucun slot n'utilise {hash}"),
+                De => write!(f, "Kein Slot verwendet {hash}"),
+                Es => write!(f, "Ningún slot usa {hash}"),
+                It => write!(f, "Nessuno slot usa {hash}"),
+                Pt => write!(f, "Nenhum slot usa {hash}"),
+                Nl => write!(f, "Geen slot gebruikt {hash}"),
+                Ja => write!(f, "{hash} を使うスロットはありません"),
+                En => write!(f, "No slot uses {hash}"),
+            },
+            Msg::SlotCount(slots) => match lang {
+                Fr => write!(f, "{slots} slots"),
+                De => write!(f, "{slots} Slots"),
+                Es => write!(f, "{slots} slots"),
+                It => write!(f, "{slots} slot"),
+                Pt => write!(f, "{slots} slots"),
+                Nl => write!(f, "{slots} slots"),
+                Ja => write!(f, "スロット {slots} 件"),
+                En => write!(f, "{slots} slots"),
+            },
+            Msg::LevelsNotCounted(failed) => match lang {
+                Fr => write!(f, "{failed} niveaux n'ont pas pu être lus et ne sont pas comptés"),
+                De => write!(f, "{failed} Level konnten nicht gelesen werden und zählen nicht mit"),
+                Es => write!(f, "{failed} niveles no se pudieron leer y no se cuentan"),
+                It => write!(f, "{failed} livelli non sono stati letti e non sono contati"),
+                Pt => write!(f, "{failed} níveis não puderam ser lidos e não contam"),
+                Nl => write!(f, "{failed} levels konden niet worden gelezen en tellen niet mee"),
+                Ja => write!(f, "{failed} 件のレベルを読み込めず、数に入っていません"),
+                En => write!(f, "{failed} levels couldn't be read and aren't counted"),
+            },
+            Msg::NoLevelUses(pack) => match lang {
+                Fr => write!(f, "Aucun niveau n'utilise {pack}"),
+                De => write!(f, "Kein Level verwendet {pack}"),
+                Es => write!(f, "Ningún nivel usa {pack}"),
+                It => write!(f, "Nessun livello usa {pack}"),
+                Pt => write!(f, "Nenhum nível usa {pack}"),
+                Nl => write!(f, "Geen level gebruikt {pack}"),
+                Ja => write!(f, "{pack} を使うレベルはありません"),
+                En => write!(f, "No level uses {pack}"),
+            },
+            Msg::CreatorLevelCount { creator, levels } => match lang {
+                Fr => write!(f, "{creator} : {levels} niveaux"),
+                De => write!(f, "{creator}: {levels} Level"),
+                Es => write!(f, "{creator}: {levels} niveles"),
+                It => write!(f, "{creator}: {levels} livelli"),
+                Pt => write!(f, "{creator}: {levels} níveis"),
+                Nl => write!(f, "{creator}: {levels} levels"),
+                Ja => write!(f, "{creator}: レベル {levels} 件"),
+                En => write!(f, "{creator}: {levels} levels"),
+            },
+            Msg::DlcUsage {
+                levels,
+                creators,
+                used,
+                guids,
+                pack,
+            } => match lang {
+                Fr => write!(
+                    f,
+                    "{levels} niveaux de {creators} créateurs utilisent {used} des {guids} GUID de {pack}"
+                ),
+                De => write!(
+                    f,
+                    "{levels} Level von {creators} Erstellern verwenden {used} der {guids} GUIDs in {pack}"
+                ),
+                Es => write!(
+                    f,
+                    "{levels} niveles de {creators} creadores usan {used} de los {guids} GUID de {pack}"
+                ),
+                It => write!(
+                    f,
+                    "{levels} livelli di {creators} creatori usano {used} dei {guids} GUID di {pack}"
+                ),
+                Pt => write!(
+                    f,
+                    "{levels} níveis de {creators} criadores usam {used} dos {guids} GUIDs de {pack}"
+                ),
+                Nl => write!(
+                    f,
+                    "{levels} levels van {creators} makers gebruiken {used} van de {guids} GUID's in {pack}"
+                ),
+                Ja => write!(
+                    f,
+                    "{creators} 人の作成者の {levels} 件のレベルが {pack} の GUID {guids} 件中 {used} 件を使っています"
+                ),
+                En => write!(
+                    f,
+                    "{levels} levels by {creators} creators use {used} of the {guids} GUIDs in {pack}"
+                ),
+            },
+            Msg::MostUsed => f.write_str(match lang {
+                Fr => "Les plus utilisés :",
+                De => "Am häufigsten verwendet:",
+                Es => "Los más usados:",
+                It => "I più usati:",
+                Pt => "Os mais usados:",
+                Nl => "Meest gebruikt:",
+                Ja => "よく使われているもの:",
+                En => "Most used:",
+            }),
+            Msg::GuidLevels { guid, levels } => match lang {
+                Fr => write!(f, "  g{guid} : {levels} niveaux"),
+                De => write!(f, "  g{guid}: {levels} Level"),
+                Es => write!(f, "  g{guid}: {levels} niveles"),
+                It => write!(f, "  g{guid}: {levels} livelli"),
+                Pt => write!(f, "  g{guid}: {levels} níveis"),
+                Nl => write!(f, "  g{guid}: {levels} levels"),
+                Ja => write!(f, "  g{guid}: レベル {levels} 件"),
+                En => write!(f, "  g{guid}: {levels} levels"),
+            },
+            Msg::ComparedByRootOnly(failed) => match lang {
+                Fr => write!(
+                    f,
+                    "{failed} niveaux n'ont pas pu être récupérés et ne sont comparés que par rootLevel"
+                ),
+                De => write!(
+                    f,
+                    "{failed} Level konnten nicht geholt werden und werden nur über rootLevel verglichen"
+                ),
+                Es => write!(
+                    f,
+                    "{failed} niveles no se pudieron obtener y solo se comparan por rootLevel"
+                ),
+                It => write!(
+                    f,
+                    "{failed} livelli non sono stati recuperati e si confrontano solo per rootLevel"
+                ),
+                Pt => write!(
+                    f,
+                    "{failed} níveis não puderam ser obtidos e só são comparados pelo rootLevel"
+                ),
+                Nl => write!(
+                    f,
+                    "{failed} levels konden niet worden opgehaald en worden alleen op rootLevel vergeleken"
+                ),
+                Ja => write!(
+                    f,
+                    "{failed} 件のレベルを取得できなかったため、rootLevel だけで比較します"
+                ),
+                En => write!(
+                    f,
+                    "{failed} levels couldn't be fetched and are only compared by rootLevel"
+                ),
+            },
+            Msg::SameRootLevel {
+                root_level,
+                levels,
+                creators,
+            } => match lang {
+                Fr => write!(f, "Même rootLevel {root_level} : {levels} niveaux de {creators} créateurs"),
+                De => write!(f, "Gleiches rootLevel {root_level}: {levels} Level von {creators} Erstellern"),
+                Es => write!(f, "Mismo rootLevel {root_level}: {levels} niveles de {creators} creadores"),
+                It => write!(f, "Stesso rootLevel {root_level}: {levels} livelli di {creators} creatori"),
+                Pt => write!(f, "Mesmo rootLevel {root_level}: {levels} níveis de {creators} criadores"),
+                Nl => write!(f, "Zelfde rootLevel {root_level}: {levels} levels van {creators} makers"),
+                Ja => write!(f, "同じ rootLevel {root_level}: {creators} 人の作成者のレベル {levels} 件"),
+                En => write!(f, "Same rootLevel {root_level}: {levels} levels by {creators} creators"),
+            },
+            Msg::AlikeLevels {
+                percent,
+                levels,
+                creators,
+            } => match lang {
+                Fr => write!(f, "Au moins {percent} % semblables : {levels} niveaux de {creators} créateurs"),
+                De => write!(f, "Mindestens {percent} % ähnlich: {levels} Level von {creators} Erstellern"),
+                Es => write!(f, "{percent}% parecidos o más: {levels} niveles de {creators} creadores"),
+                It => write!(f, "Simili almeno al {percent}%: {levels} livelli di {creators} creatori"),
+                Pt => write!(f, "{percent}% parecidos ou mais: {levels} níveis de {creators} criadores"),
+                Nl => write!(f, "{percent}% gelijk of meer: {levels} levels van {creators} makers"),
+                Ja => write!(f, "{percent}% 以上類似: {creators} 人の作成者のレベル {levels} 件"),
+                En => write!(f, "{percent}% alike or more: {levels} levels by {creators} creators"),
+            },
+            Msg::DuplicateLevel { id, name, creator } => match lang {
+                Fr => write!(f, "{id:>10}  {name}  par {creator}"),
+                De => write!(f, "{id:>10}  {name}  von {creator}"),
+                Es => write!(f, "{id:>10}  {name}  de {creator}"),
+                It => write!(f, "{id:>10}  {name}  di {creator}"),
+                Pt => write!(f, "{id:>10}  {name}  de {creator}"),
+                Nl => write!(f, "{id:>10}  {name}  van {creator}"),
+                Ja => write!(f, "{id:>10}  {name}  ({creator})"),
+                En => write!(f, "{id:>10}  {name}  by {creator}"),
+            },
+            Msg::DuplicatesSummary {
+                groups,
+                identical,
+                similar,
+                compared,
+            } => match lang {
+                Fr => write!(
+                    f,
+                    "{groups} groupes, {identical} de même rootLevel et {similar} au contenu semblable ; {compared} rootLevels comparés par contenu"
+                ),
+                De => write!(
+                    f,
+                    "{groups} Gruppen, {identical} mit gleichem rootLevel und {similar} mit ähnlichem Inhalt; {compared} rootLevels nach Inhalt verglichen"
+                ),
+                Es => write!(
+                    f,
+                    "{groups} grupos, {identical} con el mismo rootLevel y {similar} con contenido parecido; {compared} rootLevels comparados por contenido"
+                ),
+                It => write!(
+                    f,
+                    "{groups} gruppi, {identical} con lo stesso rootLevel e {similar} con contenuto simile; {compared} rootLevel confrontati per contenuto"
+                ),
+                Pt => write!(
+                    f,
+                    "{groups} grupos, {identical} com o mesmo rootLevel e {similar} com conteúdo parecido; {compared} rootLevels comparados pelo conteúdo"
+                ),
+                Nl => write!(
+                    f,
+                    "{groups} groepen, {identical} met hetzelfde rootLevel en {similar} met gelijke inhoud; {compared} rootLevels op inhoud vergeleken"
+                ),
+                Ja => write!(
+                    f,
+                    "グループ {groups} 件 (同じ rootLevel {identical} 件、内容が類似 {similar} 件)。内容で比較した rootLevel は {compared} 件"
+                ),
+                En => write!(
+                    f,
+                    "{groups} groups, {identical} with the same rootLevel and {similar} with alike content; {compared} rootLevels compared by content"
+                ),
+            },
+            Msg::NothingFetchedYet => f.write_str(match lang {
+                Fr => {
+                    "Aucun niveau n'a encore été récupéré, les niveaux n'ont donc été comparés que par rootLevel. Récupérez-en ou ajoutez --levels pour comparer leur contenu"
+                }
+                De => {
+                    "Noch kein Level wurde geholt, daher wurden Level nur über rootLevel verglichen. Hole welche oder gib --levels an, um ihren Inhalt zu vergleichen"
+                }
+                Es => {
+                    "Aún no se ha obtenido ningún nivel, así que solo se compararon por rootLevel. Obtenga algunos o use --levels para comparar su contenido"
+                }
+                It => {
+                    "Nessun livello è stato ancora recuperato, quindi i livelli sono stati confrontati solo per rootLevel. Recuperane qualcuno o passa --levels per confrontarne il contenuto"
+                }
+                Pt => {
+                    "Ainda nenhum nível foi obtido, por isso só foram comparados pelo rootLevel. Obtenha alguns ou use --levels para comparar o conteúdo"
+                }
+                Nl => {
+                    "Er is nog geen level opgehaald, dus levels zijn alleen op rootLevel vergeleken. Haal er een paar op of geef --levels mee om hun inhoud te vergelijken"
+                }
+                Ja => {
+                    "まだレベルを取得していないため、rootLevel だけで比較しました。内容を比較するには、いくつか取得するか --levels を指定してください"
+                }
+                En => {
+                    "No level has been fetched yet, so levels were only compared by rootLevel. Fetch some or pass --levels to compare their content"
+                }
+            }),
+            Msg::LevelUnreadable { id, error } => match lang {
+                Fr => write!(f, "Impossible de lire le niveau {id} : {error}"),
+                De => write!(f, "Level {id} konnte nicht gelesen werden: {error}"),
+                Es => write!(f, "No se pudo leer el nivel {id}: {error}"),
+                It => write!(f, "Impossibile leggere il livello {id}: {error}"),
+                Pt => write!(f, "Não foi possível ler o nível {id}: {error}"),
+                Nl => write!(f, "Level {id} kon niet worden gelezen: {error}"),
+                Ja => write!(f, "レベル {id} を読み込めませんでした: {error}"),
+                En => write!(f, "Couldn't read level {id}: {error}"),
+            },
+            Msg::LevelsNotFetched(failed) => match lang {
+                Fr => write!(f, "  {failed} niveaux n'ont pas pu être récupérés"),
+                De => write!(f, "  {failed} Level konnten nicht geholt werden"),
+                Es => write!(f, "  {failed} niveles no se pudieron obtener"),
+                It => write!(f, "  {failed} livelli non sono stati recuperati"),
+                Pt => write!(f, "  {failed} níveis não puderam ser obtidos"),
+                Nl => write!(f, "  {failed} levels konden niet worden opgehaald"),
+                Ja => write!(f, "  {failed} 件のレベルを取得できませんでした"),
+                En => write!(f, "  {failed} levels couldn't be fetched"),
+            },
+            Msg::ConfigExists(file) => match lang {
+                Fr => write!(f, "{file} existe déjà, le remplacer ?"),
+                De => write!(f, "{file} existiert bereits, ersetzen?"),
+                Es => write!(f, "{file} ya existe, ¿reemplazarlo?"),
+                It => write!(f, "{file} esiste già, sostituirlo?"),
+                Pt => write!(f, "{file} já existe, substituí-lo?"),
+                Nl => write!(f, "{file} bestaat al, vervangen?"),
+                Ja => write!(f, "{file} は既にあります。置き換えますか?"),
+                En => write!(f, "{file} already exists, replace it?"),
+            },
+            Msg::DatabaseQuestion => match lang {
+                Fr => write!(f, "Fichier de la base de données"),
+                De => write!(f, "Datenbankdatei"),
+                Es => write!(f, "Archivo de la base de datos"),
+                It => write!(f, "File del database"),
+                Pt => write!(f, "Arquivo do banco de dados"),
+                Nl => write!(f, "Databasebestand"),
+                Ja => write!(f, "データベースファイル"),
+                En => write!(f, "Database file"),
+            },
+            Msg::ArchiveQuestion => match lang {
+                Fr => write!(f, "Archive"),
+                De => write!(f, "Archiv"),
+                Es => write!(f, "Archivo"),
+                It => write!(f, "Archivio"),
+                Pt => write!(f, "Arquivo"),
+                Nl => write!(f, "Archief"),
+                Ja => write!(f, "アーカイブ"),
+                En => write!(f, "Archive"),
+            },
+            Msg::BackupFolderQuestion => match lang {
+                Fr => write!(f, "Dossier des sauvegardes"),
+                De => write!(f, "Sicherungsordner"),
+                Es => write!(f, "Carpeta de copias"),
+                It => write!(f, "Cartella dei backup"),
+                Pt => write!(f, "Pasta de backups"),
+                Nl => write!(f, "Back-upmap"),
+                Ja => write!(f, "バックアップフォルダー"),
+                En => write!(f, "Backup folder"),
+            },
+            Msg::UseAnyway => match lang {
+                Fr => write!(f, "L'utiliser quand même ?"),
+                De => write!(f, "Trotzdem verwenden?"),
+                Es => write!(f, "¿Usarlo de todos modos?"),
+                It => write!(f, "Usarlo comunque?"),
+                Pt => write!(f, "Usar mesmo assim?"),
+                Nl => write!(f, "Toch gebruiken?"),
+                Ja => write!(f, "それでも使いますか?"),
+                En => write!(f, "Use it anyway?"),
+            },
+            Msg::DatabaseProbed {
+                slots,
+                users,
+                extras,
+            } => match lang {
+                Fr => write!(f, "{slots} slots et {users} utilisateurs ({extras})"),
+                De => write!(f, "{slots} Slots und {users} Benutzer ({extras})"),
+                Es => write!(f, "{slots} slots y {users} usuarios ({extras})"),
+                It => write!(f, "{slots} slot e {users} utenti ({extras})"),
+                Pt => write!(f, "{slots} slots e {users} usuários ({extras})"),
+                Nl => write!(f, "{slots} slots en {users} gebruikers ({extras})"),
+                Ja => write!(f, "{slots} 件のスロットと {users} 人のユーザー ({extras})"),
+                En => write!(f, "{slots} slots and {users} users ({extras})"),
+            },
+            Msg::ArchiveComplete => match lang {
+                Fr => write!(f, "les 256 ZIP de l'archive sont là"),
+                De => write!(f, "alle 256 Archiv-ZIPs sind vorhanden"),
+                Es => write!(f, "están los 256 ZIP del archivo"),
+                It => write!(f, "ci sono tutti i 256 ZIP dell'archivio"),
+                Pt => write!(f, "todos os 256 ZIPs do arquivo estão lá"),
+                Nl => write!(f, "alle 256 archief-ZIP's zijn er"),
+                Ja => write!(f, "アーカイブの ZIP が 256 個すべてあります"),
+                En => write!(f, "all 256 archive ZIPs are there"),
+            },
+            Msg::ArchiveZipsMissing { missing, shown } => match lang {
+                Fr => write!(
+                    f,
+                    "{missing} des 256 ZIP de l'archive manquent ({shown}), les niveaux dont les blobs y sont ne peuvent pas être récupérés"
+                ),
+                De => write!(
+                    f,
+                    "{missing} der 256 Archiv-ZIPs fehlen ({shown}), Level mit Blobs darin können nicht abgerufen werden"
+                ),
+                Es => write!(
+                    f,
+                    "faltan {missing} de los 256 ZIP del archivo ({shown}), los niveles con blobs en ellos no se pueden obtener"
+                ),
+                It => write!(
+                    f,
+                    "mancano {missing} dei 256 ZIP dell'archivio ({shown}), i livelli con blob al loro interno non si possono scaricare"
+                ),
+                Pt => write!(
+                    f,
+                    "faltam {missing} dos 256 ZIPs do arquivo ({shown}), níveis com blobs neles não podem ser obtidos"
+                ),
+                Nl => write!(
+                    f,
+                    "{missing} van de 256 archief-ZIP's ontbreken ({shown}), levels met blobs daarin kunnen niet worden opgehaald"
+                ),
+                Ja => write!(
+                    f,
+                    "アーカイブの ZIP 256 個のうち {missing} 個がありません ({shown})。そこに blob があるレベルは取得できません"
+                ),
+                En => write!(
+                    f,
+                    "{missing} of the 256 archive ZIPs are missing ({shown}), levels with blobs in them can't be fetched"
+                ),
+            },
+            Msg::ArchiveSingleZip(zip) => {
+                let zip = zip.display();
+                match lang {
+                    Fr => write!(f, "un seul ZIP, {zip}"),
+                    De => write!(f, "ein einzelnes ZIP, {zip}"),
+                    Es => write!(f, "un único ZIP, {zip}"),
+                    It => write!(f, "un unico ZIP, {zip}"),
+                    Pt => write!(f, "um único ZIP, {zip}"),
+                    Nl => write!(f, "één ZIP, {zip}"),
+                    Ja => write!(f, "単一の ZIP、{zip}"),
+                    En => write!(f, "a single ZIP, {zip}"),
+                }
+            }
+            Msg::ArchiveTars(count) => match lang {
+                Fr => write!(f, "{count} tar(s), indexés à la première utilisation"),
+                De => write!(f, "{count} Tar(s), bei der ersten Nutzung indiziert"),
+                Es => write!(f, "{count} tar(s), indexados en el primer uso"),
+                It => write!(f, "{count} tar, indicizzati al primo uso"),
+                Pt => write!(f, "{count} tar(s), indexados no primeiro uso"),
+                Nl => write!(f, "{count} tar(s), geïndexeerd bij het eerste gebruik"),
+                Ja => write!(f, "{count} 個の tar、初回使用時にインデックス化"),
+                En => write!(f, "{count} tar(s), indexed on first use"),
+            },
+            Msg::ArchiveRemote => match lang {
+                Fr => write!(f, "un miroir distant, non vérifié"),
+                De => write!(f, "ein entfernter Spiegel, nicht geprüft"),
+                Es => write!(f, "un espejo remoto, sin comprobar"),
+                It => write!(f, "un mirror remoto, non controllato"),
+                Pt => write!(f, "um espelho remoto, não verificado"),
+                Nl => write!(f, "een externe mirror, niet gecontroleerd"),
+                Ja => write!(f, "リモートミラー、未確認"),
+                En => write!(f, "a remote mirror, not checked"),
+            },
+            Msg::PathMissing(path) => {
+                let path = path.display();
+                match lang {
+                    Fr => write!(f, "{path} n'existe pas"),
+                    De => write!(f, "{path} existiert nicht"),
+                    Es => write!(f, "{path} no existe"),
+                    It => write!(f, "{path} non esiste"),
+                    Pt => write!(f, "{path} não existe"),
+                    Nl => write!(f, "{path} bestaat niet"),
+                    Ja => write!(f, "{path} は存在しません"),
+                    En => write!(f, "{path} doesn't exist"),
+                }
+            }
+            Msg::ArchiveUnknown(path) => {
+                let path = path.display();
+                match lang {
+                    Fr => write!(
+                        f,
+                        "{path} ne contient ni ZIP d'archive, ni tar, ni dossier `LBP online levels 2023`"
+                    ),
+                    De => write!(
+                        f,
+                        "{path} enthält keine Archiv-ZIPs, Tars oder `LBP online levels 2023`-Ordner"
+                    ),
+                    Es => write!(
+                        f,
+                        "{path} no contiene ZIP del archivo, tars ni carpetas `LBP online levels 2023`"
+                    ),
+                    It => write!(
+                        f,
+                        "{path} non contiene ZIP dell'archivio, tar o cartelle `LBP online levels 2023`"
+                    ),
+                    Pt => write!(
+                        f,
+                        "{path} não contém ZIPs do arquivo, tars nem pastas `LBP online levels 2023`"
+                    ),
+                    Nl => write!(
+                        f,
+                        "{path} bevat geen archief-ZIP's, tars of `LBP online levels 2023`-mappen"
+                    ),
+                    Ja => write!(
+                        f,
+                        "{path} にはアーカイブの ZIP も tar も `LBP online levels 2023` フォルダーもありません"
+                    ),
+                    En => write!(
+                        f,
+                        "{path} holds no archive ZIPs, tars or `LBP online levels 2023` folders"
+                    ),
+                }
+            }
+            Msg::FolderExists => match lang {
+                Fr => write!(f, "existe"),
+                De => write!(f, "existiert"),
+                Es => write!(f, "existe"),
+                It => write!(f, "esiste"),
+                Pt => write!(f, "existe"),
+                Nl => write!(f, "bestaat"),
+                Ja => write!(f, "存在します"),
+                En => write!(f, "exists"),
+            },
+            Msg::NotAFolder(path) => {
+                let path = path.display();
+                match lang {
+                    Fr => write!(f, "{path} est un fichier, pas un dossier"),
+                    De => write!(f, "{path} ist eine Datei, kein Ordner"),
+                    Es => write!(f, "{path} es un archivo, no una carpeta"),
+                    It => write!(f, "{path} è un file, non una cartella"),
+                    Pt => write!(f, "{path} é um arquivo, não uma pasta"),
+                    Nl => write!(f, "{path} is een bestand, geen map"),
+                    Ja => write!(f, "{path} はフォルダーではなくファイルです"),
+                    En => write!(f, "{path} is a file, not a folder"),
+                }
+            }
+            Msg::FolderCreatedWithConfig => match lang {
+                Fr => write!(f, "n'existe pas encore, il est créé avec la configuration"),
+                De => write!(f, "existiert noch nicht, er wird mit der Konfiguration angelegt"),
+                Es => write!(f, "aún no existe, se crea con la configuración"),
+                It => write!(f, "non esiste ancora, viene creata con la configurazione"),
+                Pt => write!(f, "ainda não existe, é criada com a configuração"),
+                Nl => write!(f, "bestaat nog niet, hij wordt met de configuratie aangemaakt"),
+                Ja => write!(f, "まだありません。設定と一緒に作成されます"),
+                En => write!(f, "doesn't exist yet, it's created with the config"),
+            },
+            Msg::ConfigWritten(file) => match lang {
+                Fr => write!(f, "{file} écrit, essayez `archive_dl bkp <level id>` ensuite"),
+                De => write!(f, "{file} geschrieben, als Nächstes `archive_dl bkp <level id>` versuchen"),
+                Es => write!(f, "{file} escrito, prueba `archive_dl bkp <level id>` a continuación"),
+                It => write!(f, "{file} scritto, prova poi `archive_dl bkp <level id>`"),
+                Pt => write!(f, "{file} escrito, tente `archive_dl bkp <level id>` em seguida"),
+                Nl => write!(f, "{file} geschreven, probeer hierna `archive_dl bkp <level id>`"),
+                Ja => write!(f, "{file} を書き込みました。次は `archive_dl bkp <level id>` を試してください"),
+                En => write!(f, "Wrote {file}, try `archive_dl bkp <level id>` next"),
+            },
+            Msg::NoName => match lang {
+                Fr => write!(f, "(sans nom)"),
+                De => write!(f, "(ohne Namen)"),
+                Es => write!(f, "(sin nombre)"),
+                It => write!(f, "(senza nome)"),
+                Pt => write!(f, "(sem nome)"),
+                Nl => write!(f, "(geen naam)"),
+                Ja => write!(f, "(名前なし)"),
+                En => write!(f, "(no name)"),
+            },
+            Msg::PickQuestion => match lang {
+                Fr => write!(
+                    f,
+                    "Niveaux à récupérer, en numéros et plages comme `1 3-5`, ou `all`"
+                ),
+                De => write!(
+                    f,
+                    "Abzurufende Level, als Nummern und Bereiche wie `1 3-5`, oder `all`"
+                ),
+                Es => write!(
+                    f,
+                    "Niveles a obtener, como números y rangos como `1 3-5`, o `all`"
+                ),
+                It => write!(
+                    f,
+                    "Livelli da scaricare, come numeri e intervalli tipo `1 3-5`, o `all`"
+                ),
+                Pt => write!(
+                    f,
+                    "Níveis a obter, como números e intervalos tipo `1 3-5`, ou `all`"
+                ),
+                Nl => write!(
+                    f,
+                    "Op te halen levels, als nummers en reeksen zoals `1 3-5`, of `all`"
+                ),
+                Ja => write!(f, "取得するレベル (`1 3-5` のような番号と範囲、または `all`)"),
+                En => write!(
+                    f,
+                    "Levels to fetch, as numbers and ranges like `1 3-5`, or `all`"
+                ),
+            },
+            Msg::NoDifferences => match lang {
+                Fr => write!(f, "Aucun créateur ni niveau ne diffère entre les deux bases"),
+                De => write!(f, "Kein Ersteller und kein Level unterscheidet sich zwischen den beiden Datenbanken"),
+                Es => write!(f, "Ningún creador ni nivel difiere entre las dos bases de datos"),
+                It => write!(f, "Nessun creatore o livello differisce tra i due database"),
+                Pt => write!(f, "Nenhum criador ou nível difere entre os dois bancos de dados"),
+                Nl => write!(f, "Geen maker of level verschilt tussen de twee databases"),
+                Ja => write!(f, "2 つのデータベースで違うクリエイターもレベルもありません"),
+                En => write!(f, "No creator or level differs between the two databases"),
+            },
+            Msg::Never => match lang {
+                Fr => write!(f, "jamais"),
+                De => write!(f, "nie"),
+                Es => write!(f, "nunca"),
+                It => write!(f, "mai"),
+                Pt => write!(f, "nunca"),
+                Nl => write!(f, "nooit"),
+                Ja => write!(f, "なし"),
+                En => write!(f, "never"),
+            },
+            Msg::LevelUpdated { old, new } => match lang {
+                Fr => write!(f, "(mis à jour {old} -> {new})"),
+                De => write!(f, "(aktualisiert {old} -> {new})"),
+                Es => write!(f, "(actualizado {old} -> {new})"),
+                It => write!(f, "(aggiornato {old} -> {new})"),
+                Pt => write!(f, "(atualizado {old} -> {new})"),
+                Nl => write!(f, "(bijgewerkt {old} -> {new})"),
+                Ja => write!(f, "(更新 {old} -> {new})"),
+                En => write!(f, "(updated {old} -> {new})"),
+            },
+            Msg::CreatorsCompared {
+                creators_added,
+                creators_removed,
+                added,
+                removed,
+                updated,
+            } => match lang {
+                Fr => write!(
+                    f,
+                    "{creators_added} créateurs ajoutés, {creators_removed} retirés, {added} niveaux ajoutés, {removed} retirés, {updated} mis à jour"
+                ),
+                De => write!(
+                    f,
+                    "{creators_added} Ersteller hinzugefügt, {creators_removed} entfernt, {added} Level hinzugefügt, {removed} entfernt, {updated} aktualisiert"
+                ),
+                Es => write!(
+                    f,
+                    "{creators_added} creadores añadidos, {creators_removed} eliminados, {added} niveles añadidos, {removed} eliminados, {updated} actualizados"
+                ),
+                It => write!(
+                    f,
+                    "{creators_added} creatori aggiunti, {creators_removed} rimossi, {added} livelli aggiunti, {removed} rimossi, {updated} aggiornati"
+                ),
+                Pt => write!(
+                    f,
+                    "{creators_added} criadores adicionados, {creators_removed} removidos, {added} níveis adicionados, {removed} removidos, {updated} atualizados"
+                ),
+                Nl => write!(
+                    f,
+                    "{creators_added} makers toegevoegd, {creators_removed} verwijderd, {added} levels toegevoegd, {removed} verwijderd, {updated} bijgewerkt"
+                ),
+                Ja => write!(
+                    f,
+                    "クリエイター {creators_added} 人を追加、{creators_removed} 人を削除、レベル {added} 件を追加、{removed} 件を削除、{updated} 件を更新"
+                ),
+                En => write!(
+                    f,
+                    "{creators_added} creators added, {creators_removed} removed, {added} levels added, {removed} removed, {updated} updated"
+                ),
+            },
+            Msg::WorkingDbStale { working, database } => {
+                let (working, database) = (working.display(), database.display());
+                match lang {
+                    Fr => write!(
+                        f,
+                        "{working} n'a pas été fait à partir de {database} tel qu'il est maintenant, la base elle-même est utilisée. Lancez `db upgrade` pour le mettre à jour"
+                    ),
+                    De => write!(
+                        f,
+                        "{working} wurde nicht aus {database} in seinem jetzigen Stand erstellt, die Datenbank selbst wird verwendet. `db upgrade` ausführen, um es zu aktualisieren"
+                    ),
+                    Es => write!(
+                        f,
+                        "{working} no se hizo a partir de {database} tal como está ahora, se usa la base de datos misma. Ejecuta `db upgrade` para actualizarlo"
+                    ),
+                    It => write!(
+                        f,
+                        "{working} non è stato creato da {database} com'è adesso, viene usato il database stesso. Esegui `db upgrade` per aggiornarlo"
+                    ),
+                    Pt => write!(
+                        f,
+                        "{working} não foi feito de {database} como ele está agora, o próprio banco de dados é usado. Execute `db upgrade` para atualizá-lo"
+                    ),
+                    Nl => write!(
+                        f,
+                        "{working} is niet gemaakt van {database} zoals die nu is, de database zelf wordt gebruikt. Voer `db upgrade` uit om het bij te werken"
+                    ),
+                    Ja => write!(
+                        f,
+                        "{working} は現在の {database} から作られていないため、データベース自体を使います。更新するには `db upgrade` を実行してください"
+                    ),
+                    En => write!(
+                        f,
+                        "{working} wasn't made from {database} as it is now, using the database itself. Run `db upgrade` to update it"
+                    ),
+                }
+            }
+            Msg::DbUpgraded {
+                path,
+                slots,
+                users,
+                elapsed,
+            } => {
+                let path = path.display();
+                match lang {
+                    Fr => write!(
+                        f,
+                        "{path} écrit avec {slots} slots et {users} utilisateurs en {elapsed:.2?}"
+                    ),
+                    De => write!(
+                        f,
+                        "{path} mit {slots} Slots und {users} Benutzern in {elapsed:.2?} geschrieben"
+                    ),
+                    Es => write!(
+                        f,
+                        "{path} escrito con {slots} slots y {users} usuarios en {elapsed:.2?}"
+                    ),
+                    It => write!(
+                        f,
+                        "{path} scritto con {slots} slot e {users} utenti in {elapsed:.2?}"
+                    ),
+                    Pt => write!(
+                        f,
+                        "{path} escrito com {slots} slots e {users} usuários em {elapsed:.2?}"
+                    ),
+                    Nl => write!(
+                        f,
+                        "{path} geschreven met {slots} slots en {users} gebruikers in {elapsed:.2?}"
+                    ),
+                    Ja => write!(
+                        f,
+                        "{slots} 件のスロットと {users} 人のユーザーで {path} を {elapsed:.2?} で書き込みました"
+                    ),
+                    En => write!(
+                        f,
+                        "Wrote {path} with {slots} slots and {users} users in {elapsed:.2?}"
+                    ),
+                }
+            }
+            Msg::ValuesRetyped { retyped, filled } => match lang {
+                Fr => write!(f, "{retyped} valeurs converties au type de leur colonne, {filled} NULL remplis"),
+                De => write!(f, "{retyped} Werte in den Typ ihrer Spalte umgewandelt, {filled} NULLs ausgefüllt"),
+                Es => write!(f, "{retyped} valores convertidos al tipo de su columna, {filled} NULL rellenados"),
+                It => write!(f, "{retyped} valori convertiti al tipo della loro colonna, {filled} NULL riempiti"),
+                Pt => write!(f, "{retyped} valores convertidos para o tipo da coluna, {filled} NULLs preenchidos"),
+                Nl => write!(f, "{retyped} waarden omgezet naar het type van hun kolom, {filled} NULLs ingevuld"),
+                Ja => write!(f, "{retyped} 個の値を列の型に変換し、{filled} 個の NULL を埋めました"),
+                En => write!(f, "{retyped} values converted to their column's type, {filled} NULLs filled in"),
+            },
+            Msg::ValuesUnconvertible(count) => match lang {
+                Fr => write!(f, "{count} valeurs n'ont pas pu être converties, gardées telles quelles"),
+                De => write!(f, "{count} Werte konnten nicht umgewandelt werden, unverändert behalten"),
+                Es => write!(f, "{count} valores no se pudieron convertir, se dejaron como estaban"),
+                It => write!(f, "{count} valori non sono stati convertiti, lasciati com'erano"),
+                Pt => write!(f, "{count} valores não puderam ser convertidos, mantidos como estavam"),
+                Nl => write!(f, "{count} waarden konden niet worden omgezet, ongewijzigd gelaten"),
+                Ja => write!(f, "{count} 個の値は変換できず、そのまま残しました"),
+                En => write!(f, "{count} values couldn't be converted, kept as they were"),
+            },
+            Msg::RowsDropped {
+                duplicate_users,
+                duplicate_slots,
+                dropped_users,
+                orphans,
+            } => match lang {
+                Fr => write!(
+                    f,
+                    "{duplicate_users} utilisateurs en double fusionnés, {duplicate_slots} slots en double, {dropped_users} utilisateurs sans npHandle et {orphans} lignes orphelines supprimés"
+                ),
+                De => write!(
+                    f,
+                    "{duplicate_users} doppelte Benutzer zusammengeführt, {duplicate_slots} doppelte Slots, {dropped_users} Benutzer ohne npHandle und {orphans} verwaiste Zeilen entfernt"
+                ),
+                Es => write!(
+                    f,
+                    "{duplicate_users} usuarios duplicados fusionados, {duplicate_slots} slots duplicados, {dropped_users} usuarios sin npHandle y {orphans} filas huérfanas eliminados"
+                ),
+                It => write!(
+                    f,
+                    "{duplicate_users} utenti duplicati uniti, {duplicate_slots} slot duplicati, {dropped_users} utenti senza npHandle e {orphans} righe orfane eliminati"
+                ),
+                Pt => write!(
+                    f,
+                    "{duplicate_users} usuários duplicados mesclados, {duplicate_slots} slots duplicados, {dropped_users} usuários sem npHandle e {orphans} linhas órfãs removidos"
+                ),
+                Nl => write!(
+                    f,
+                    "{duplicate_users} dubbele gebruikers samengevoegd, {duplicate_slots} dubbele slots, {dropped_users} gebruikers zonder npHandle en {orphans} verweesde rijen verwijderd"
+                ),
+                Ja => write!(
+                    f,
+                    "重複ユーザー {duplicate_users} 人を統合し、重複スロット {duplicate_slots} 件、npHandle のないユーザー {dropped_users} 人、孤立した行 {orphans} 件を削除しました"
+                ),
+                En => write!(
+                    f,
+                    "{duplicate_users} duplicate users merged, {duplicate_slots} duplicate slots, {dropped_users} users without an npHandle and {orphans} orphaned rows dropped"
+                ),
+            },
+            Msg::SlotsWithoutUser(count) => match lang {
+                Fr => write!(f, "{count} slots n'ont pas de ligne utilisateur, les icônes de leur créateur sont introuvables"),
+                De => write!(f, "{count} Slots haben keine Benutzerzeile, die Icons ihrer Ersteller sind nicht auffindbar"),
+                Es => write!(f, "{count} slots no tienen fila de usuario, los iconos de su creador no se pueden encontrar"),
+                It => write!(f, "{count} slot non hanno una riga utente, le icone del loro creatore non si trovano"),
+                Pt => write!(f, "{count} slots não têm linha de usuário, os ícones do criador não podem ser encontrados"),
+                Nl => write!(f, "{count} slots hebben geen gebruikersrij, de iconen van hun maker zijn niet te vinden"),
+                Ja => write!(f, "{count} 件のスロットにユーザー行がなく、クリエイターのアイコンが見つかりません"),
+                En => write!(f, "{count} slots have no user row, their creator icons can't be found"),
+            },
+            Msg::Ingested {
+                dir,
+                added,
+                known,
+                unreadable,
+            } => {
+                let dir = dir.display();
+                match lang {
+                    Fr => write!(f, "{dir} : {added} ajoutés, {known} déjà importés, {unreadable} illisibles"),
+                    De => write!(f, "{dir}: {added} hinzugefügt, {known} bereits übernommen, {unreadable} unlesbar"),
+                    Es => write!(f, "{dir}: {added} añadidos, {known} ya importados, {unreadable} ilegibles"),
+                    It => write!(f, "{dir}: {added} aggiunti, {known} già importati, {unreadable} illeggibili"),
+                    Pt => write!(f, "{dir}: {added} adicionados, {known} já importados, {unreadable} ilegíveis"),
+                    Nl => write!(f, "{dir}: {added} toegevoegd, {known} al opgenomen, {unreadable} onleesbaar"),
+                    Ja => write!(f, "{dir}: {added} 件追加、{known} 件は取り込み済み、{unreadable} 件は読めません"),
+                    En => write!(f, "{dir}: {added} added, {known} already ingested, {unreadable} unreadable"),
+                }
+            }
+            Msg::NameMismatch(count) => match lang {
+                Fr => write!(f, "{count} fichiers ne correspondent pas au SHA1 de leur nom, ignorés :"),
+                De => write!(f, "{count} Dateien passen nicht zum SHA1 in ihrem Namen, übersprungen:"),
+                Es => write!(f, "{count} archivos no coinciden con el SHA1 de su nombre, omitidos:"),
+                It => write!(f, "{count} file non corrispondono allo SHA1 nel loro nome, saltati:"),
+                Pt => write!(f, "{count} arquivos não correspondem ao SHA1 no nome, ignorados:"),
+                Nl => write!(f, "{count} bestanden komen niet overeen met de SHA1 in hun naam, overgeslagen:"),
+                Ja => write!(f, "{count} 個のファイルが名前の SHA1 と一致しないため、スキップしました:"),
+                En => write!(f, "{count} files don't match the SHA1 in their name, skipped:"),
+            },
+            Msg::IngestDir(dir) => {
+                let dir = dir.display();
+                match lang {
+                    Fr => write!(f, "Les blobs importés sont dans {dir}"),
+                    De => write!(f, "Übernommene Blobs liegen in {dir}"),
+                    Es => write!(f, "Los blobs importados están en {dir}"),
+                    It => write!(f, "I blob importati sono in {dir}"),
+                    Pt => write!(f, "Os blobs importados estão em {dir}"),
+                    Nl => write!(f, "Opgenomen blobs staan in {dir}"),
+                    Ja => write!(f, "取り込んだ blob は {dir} にあります"),
+                    En => write!(f, "Ingested blobs are in {dir}"),
+                }
+            }
+            Msg::FarArchive { kind, entries } => match lang {
+                Fr => write!(f, "Archive {kind} avec {entries} entrées"),
+                De => write!(f, "{kind}-Archiv mit {entries} Einträgen"),
+                Es => write!(f, "Archivo {kind} con {entries} entradas"),
+                It => write!(f, "Archivio {kind} con {entries} voci"),
+                Pt => write!(f, "Arquivo {kind} com {entries} entradas"),
+                Nl => write!(f, "{kind}-archief met {entries} items"),
+                Ja => write!(f, "{entries} 個のエントリがある {kind} アーカイブ"),
+                En => write!(f, "{kind} archive with {entries} entries"),
+            },
+            Msg::SaveChecksumMismatch => match lang {
+                Fr => write!(f, "la somme de contrôle de la sauvegarde ne correspond pas, elle est abîmée ou a été modifiée"),
+                De => write!(f, "die Prüfsumme des Spielstands stimmt nicht, er ist beschädigt oder wurde bearbeitet"),
+                Es => write!(f, "la suma de comprobación de la partida no coincide, está dañada o fue editada"),
+                It => write!(f, "il checksum del salvataggio non corrisponde, è danneggiato o è stato modificato"),
+                Pt => write!(f, "a soma de verificação do save não confere, ele está danificado ou foi editado"),
+                Nl => write!(f, "de checksum van de save klopt niet, hij is beschadigd of bewerkt"),
+                Ja => write!(f, "セーブのチェックサムが一致しません。破損しているか編集されています"),
+                En => write!(f, "the save's checksum doesn't match, it's damaged or was edited"),
+            },
+            Msg::DamagedEntriesSkipped {
+                out_of_range,
+                bad_hash,
+            } => {
+                let skipped = out_of_range + bad_hash;
+                match lang {
+                    Fr => write!(
+                        f,
+                        "{skipped} entrées abîmées ignorées ({out_of_range} hors limites, {bad_hash} avec un mauvais hash)"
+                    ),
+                    De => write!(
+                        f,
+                        "{skipped} beschädigte Einträge übersprungen ({out_of_range} außerhalb des Bereichs, {bad_hash} mit falschem Hash)"
+                    ),
+                    Es => write!(
+                        f,
+                        "{skipped} entradas dañadas omitidas ({out_of_range} fuera de rango, {bad_hash} con un hash incorrecto)"
+                    ),
+                    It => write!(
+                        f,
+                        "{skipped} voci danneggiate saltate ({out_of_range} fuori intervallo, {bad_hash} con un hash errato)"
+                    ),
+                    Pt => write!(
+                        f,
+                        "{skipped} entradas danificadas ignoradas ({out_of_range} fora do intervalo, {bad_hash} com hash errado)"
+                    ),
+                    Nl => write!(
+                        f,
+                        "{skipped} beschadigde items overgeslagen ({out_of_range} buiten bereik, {bad_hash} met een verkeerde hash)"
+                    ),
+                    Ja => write!(
+                        f,
+                        "破損したエントリ {skipped} 個をスキップしました (範囲外 {out_of_range} 個、ハッシュ不一致 {bad_hash} 個)"
+                    ),
+                    En => write!(
+                        f,
+                        "skipped {skipped} damaged entries ({out_of_range} out of range, {bad_hash} with a bad hash)"
+                    ),
+                }
+            }
+            Msg::BlobsWritten { blobs, path } => {
+                let path = path.display();
+                match lang {
+                    Fr => write!(f, "{blobs} blobs écrits dans {path}"),
+                    De => write!(f, "{blobs} Blobs nach {path} geschrieben"),
+                    Es => write!(f, "{blobs} blobs escritos en {path}"),
+                    It => write!(f, "{blobs} blob scritti in {path}"),
+                    Pt => write!(f, "{blobs} blobs escritos em {path}"),
+                    Nl => write!(f, "{blobs} blobs geschreven naar {path}"),
+                    Ja => write!(f, "{blobs} 個の blob を {path} に書き込みました"),
+                    En => write!(f, "Wrote {blobs} blobs to {path}"),
+                }
+            }
+            Msg::SaveExtracted {
+                resources,
+                levels,
+                path,
+            } => {
+                let path = path.display();
+                match lang {
+                    Fr => write!(f, "{resources} ressources ({levels} niveaux) extraites dans {path}"),
+                    De => write!(f, "{resources} Ressourcen ({levels} Level) nach {path} entpackt"),
+                    Es => write!(f, "{resources} recursos ({levels} niveles) extraídos en {path}"),
+                    It => write!(f, "{resources} risorse ({levels} livelli) estratte in {path}"),
+                    Pt => write!(f, "{resources} recursos ({levels} níveis) extraídos em {path}"),
+                    Nl => write!(f, "{resources} resources ({levels} levels) uitgepakt naar {path}"),
+                    Ja => write!(f, "{resources} 個のリソース ({levels} 件のレベル) を {path} に展開しました"),
+                    En => write!(f, "Extracted {resources} resources ({levels} levels) to {path}"),
+                }
+            }
+            Msg::AddedToCache => match lang {
+                Fr => write!(f, "et ajoutées au cache des ressources"),
+                De => write!(f, "und zum Ressourcen-Cache hinzugefügt"),
+                Es => write!(f, "y añadidos a la caché de recursos"),
+                It => write!(f, "e aggiunte alla cache delle risorse"),
+                Pt => write!(f, "e adicionados ao cache de recursos"),
+                Nl => write!(f, "en toegevoegd aan de resourcecache"),
+                Ja => write!(f, "リソースキャッシュにも追加しました"),
+                En => write!(f, "and added them to the resource cache"),
+            },
+            Msg::VerifyingZips { zips, scope } => match lang {
+                Fr => write!(f, "Vérification de {zips} ZIP ({scope})…"),
+                De => write!(f, "{zips} ZIPs werden geprüft ({scope})…"),
+                Es => write!(f, "Verificando {zips} ZIP ({scope})…"),
+                It => write!(f, "Verifica di {zips} ZIP ({scope})…"),
+                Pt => write!(f, "Verificando {zips} ZIPs ({scope})…"),
+                Nl => write!(f, "{zips} ZIP's controleren ({scope})…"),
+                Ja => write!(f, "{zips} 個の ZIP を検証しています ({scope})…"),
+                En => write!(f, "Verifying {zips} ZIPs ({scope})…"),
+            },
+            Msg::VerifyingRemainingZips { todo, zips, scope } => match lang {
+                Fr => write!(
+                    f,
+                    "Vérification de {todo} ZIP sur {zips} ({scope}), les autres ont été faits avant"
+                ),
+                De => write!(
+                    f,
+                    "{todo} von {zips} ZIPs werden geprüft ({scope}), die anderen wurden schon geprüft"
+                ),
+                Es => write!(
+                    f,
+                    "Verificando {todo} de {zips} ZIP ({scope}), los demás se hicieron antes"
+                ),
+                It => write!(
+                    f,
+                    "Verifica di {todo} ZIP su {zips} ({scope}), gli altri sono stati fatti prima"
+                ),
+                Pt => write!(
+                    f,
+                    "Verificando {todo} de {zips} ZIPs ({scope}), os outros foram feitos antes"
+                ),
+                Nl => write!(
+                    f,
+                    "{todo} van {zips} ZIP's controleren ({scope}), de andere zijn al gedaan"
+                ),
+                Ja => write!(
+                    f,
+                    "{zips} 個中 {todo} 個の ZIP を検証しています ({scope})。残りは検証済みです"
+                ),
+                En => write!(
+                    f,
+                    "Verifying {todo} of {zips} ZIPs ({scope}), the others were done before"
+                ),
+            },
+            Msg::ZipVerified {
+                entries,
+                bad,
+                unreadable,
+            } => match lang {
+                Fr => write!(f, "{entries} entrées, {bad} mauvaises, {unreadable} illisibles"),
+                De => write!(f, "{entries} Einträge, {bad} fehlerhaft, {unreadable} unlesbar"),
+                Es => write!(f, "{entries} entradas, {bad} erróneas, {unreadable} ilegibles"),
+                It => write!(f, "{entries} voci, {bad} errate, {unreadable} illeggibili"),
+                Pt => write!(f, "{entries} entradas, {bad} erradas, {unreadable} ilegíveis"),
+                Nl => write!(f, "{entries} items, {bad} fout, {unreadable} onleesbaar"),
+                Ja => write!(f, "エントリ {entries} 個、不正 {bad} 個、読めない {unreadable} 個"),
+                En => write!(f, "{entries} entries, {bad} bad, {unreadable} unreadable"),
+            },
+            Msg::ZipsVerified {
+                verified,
+                zips,
+                entries,
+                size,
+                bad,
+                unreadable,
+                failed,
+                path,
+            } => {
+                let path = path.display();
+                match lang {
+                    Fr => write!(
+                        f,
+                        "{verified} ZIP sur {zips} vérifiés : {entries} entrées, {size}, {bad} mauvaises, {unreadable} illisibles, {failed} ZIP illisibles. Les résultats sont dans {path}"
+                    ),
+                    De => write!(
+                        f,
+                        "{verified} von {zips} ZIPs geprüft: {entries} Einträge, {size}, {bad} fehlerhaft, {unreadable} unlesbar, {failed} ZIPs unlesbar. Die Befunde stehen in {path}"
+                    ),
+                    Es => write!(
+                        f,
+                        "{verified} de {zips} ZIP verificados: {entries} entradas, {size}, {bad} erróneas, {unreadable} ilegibles, {failed} ZIP ilegibles. Los resultados están en {path}"
+                    ),
+                    It => write!(
+                        f,
+                        "{verified} ZIP su {zips} verificati: {entries} voci, {size}, {bad} errate, {unreadable} illeggibili, {failed} ZIP illeggibili. I risultati sono in {path}"
+                    ),
+                    Pt => write!(
+                        f,
+                        "{verified} de {zips} ZIPs verificados: {entries} entradas, {size}, {bad} erradas, {unreadable} ilegíveis, {failed} ZIPs ilegíveis. Os resultados estão em {path}"
+                    ),
+                    Nl => write!(
+                        f,
+                        "{verified} van {zips} ZIP's gecontroleerd: {entries} items, {size}, {bad} fout, {unreadable} onleesbaar, {failed} ZIP's onleesbaar. De bevindingen staan in {path}"
+                    ),
+                    Ja => write!(
+                        f,
+                        "{zips} 個中 {verified} 個の ZIP を検証しました: エントリ {entries} 個、{size}、不正 {bad} 個、読めない {unreadable} 個、読めない ZIP {failed} 個。結果は {path} にあります"
+                    ),
+                    En => write!(
+                        f,
+                        "{verified} of {zips} ZIPs verified: {entries} entries, {size}, {bad} bad, {unreadable} unreadable, {failed} ZIPs unreadable. Findings are in {path}"
+                    ),
+                }
+            }
+            Msg::BadHash(hex) => match lang {
+                Fr => write!(f, "mauvais hash : {hex}"),
+                De => write!(f, "falscher Hash: {hex}"),
+                Es => write!(f, "hash incorrecto: {hex}"),
+                It => write!(f, "hash errato: {hex}"),
+                Pt => write!(f, "hash errado: {hex}"),
+                Nl => write!(f, "verkeerde hash: {hex}"),
+                Ja => write!(f, "ハッシュ不一致: {hex}"),
+                En => write!(f, "bad hash: {hex}"),
+            },
+            Msg::UnreadableEntry(what) => match lang {
+                Fr => write!(f, "illisible : {what}"),
+                De => write!(f, "unlesbar: {what}"),
+                Es => write!(f, "ilegible: {what}"),
+                It => write!(f, "illeggibile: {what}"),
+                Pt => write!(f, "ilegível: {what}"),
+                Nl => write!(f, "onleesbaar: {what}"),
+                Ja => write!(f, "読めません: {what}"),
+                En => write!(f, "unreadable: {what}"),
+            },
+            Msg::RunAgain => match lang {
+                Fr => write!(f, "Relancez-le pour vérifier le reste"),
+                De => write!(f, "Erneut ausführen, um den Rest zu prüfen"),
+                Es => write!(f, "Ejecútalo de nuevo para verificar el resto"),
+                It => write!(f, "Eseguilo di nuovo per verificare il resto"),
+                Pt => write!(f, "Execute de novo para verificar o resto"),
+                Nl => write!(f, "Voer het opnieuw uit om de rest te controleren"),
+                Ja => write!(f, "残りを検証するにはもう一度実行してください"),
+                En => write!(f, "Run it again to verify the rest"),
+            },
+            Msg::LookingForLooseBlobs(dirs) => match lang {
+                Fr => write!(f, "Recherche de blobs isolés dans {dirs} dossiers…"),
+                De => write!(f, "Suche nach losen Blobs in {dirs} Ordnern…"),
+                Es => write!(f, "Buscando blobs sueltos en {dirs} carpetas…"),
+                It => write!(f, "Ricerca di blob sciolti in {dirs} cartelle…"),
+                Pt => write!(f, "Procurando blobs soltos em {dirs} pastas…"),
+                Nl => write!(f, "Losse blobs zoeken in {dirs} mappen…"),
+                Ja => write!(f, "{dirs} 個のフォルダーでばらの blob を探しています…"),
+                En => write!(f, "Looking for loose blobs in {dirs} folders…"),
+            },
+            Msg::ZipRepacked {
+                written,
+                from_zip,
+                from_loose,
+                bad_hash,
+                unreadable,
+            } => match lang {
+                Fr => write!(
+                    f,
+                    "{written} blobs ({from_zip} du zip, {from_loose} isolés), {bad_hash} mauvaises copies, {unreadable} illisibles"
+                ),
+                De => write!(
+                    f,
+                    "{written} Blobs ({from_zip} aus dem ZIP, {from_loose} lose), {bad_hash} fehlerhafte Kopien, {unreadable} unlesbar"
+                ),
+                Es => write!(
+                    f,
+                    "{written} blobs ({from_zip} del zip, {from_loose} sueltos), {bad_hash} copias erróneas, {unreadable} ilegibles"
+                ),
+                It => write!(
+                    f,
+                    "{written} blob ({from_zip} dallo zip, {from_loose} sciolti), {bad_hash} copie errate, {unreadable} illeggibili"
+                ),
+                Pt => write!(
+                    f,
+                    "{written} blobs ({from_zip} do zip, {from_loose} soltos), {bad_hash} cópias erradas, {unreadable} ilegíveis"
+                ),
+                Nl => write!(
+                    f,
+                    "{written} blobs ({from_zip} uit de zip, {from_loose} los), {bad_hash} foute kopieën, {unreadable} onleesbaar"
+                ),
+                Ja => write!(
+                    f,
+                    "blob {written} 個 (zip から {from_zip} 個、ばらから {from_loose} 個)、不正なコピー {bad_hash} 個、読めない {unreadable} 個"
+                ),
+                En => write!(
+                    f,
+                    "{written} blobs ({from_zip} from zip, {from_loose} loose), {bad_hash} bad copies, {unreadable} unreadable"
+                ),
+            },
+            Msg::NoGoodCopy(lost) => match lang {
+                Fr => write!(f, "{lost} blobs n'avaient aucune bonne copie :"),
+                De => write!(f, "{lost} Blobs hatten keine gute Kopie:"),
+                Es => write!(f, "{lost} blobs no tenían ninguna copia buena:"),
+                It => write!(f, "{lost} blob non avevano nessuna copia buona:"),
+                Pt => write!(f, "{lost} blobs não tinham nenhuma cópia boa:"),
+                Nl => write!(f, "{lost} blobs hadden geen goede kopie:"),
+                Ja => write!(f, "{lost} 個の blob には正しいコピーがありません:"),
+                En => write!(f, "{lost} blobs had no good copy:"),
+            },
+            Msg::RebuiltZips(dir) => {
+                let dir = dir.display();
+                match lang {
+                    Fr => write!(f, "Les ZIP reconstruits sont dans {dir}"),
+                    De => write!(f, "Die neu gebauten ZIPs liegen unter {dir}"),
+                    Es => write!(f, "Los ZIP reconstruidos están en {dir}"),
+                    It => write!(f, "Gli ZIP ricostruiti sono in {dir}"),
+                    Pt => write!(f, "Os ZIPs reconstruídos estão em {dir}"),
+                    Nl => write!(f, "De herbouwde ZIP's staan in {dir}"),
+                    Ja => write!(f, "再構築した ZIP は {dir} にあります"),
+                    En => write!(f, "Rebuilt ZIPs are under {dir}"),
+                }
+            }
+            Msg::ScanningZips(zips) => match lang {
+                Fr => write!(f, "Analyse de {zips} ZIP…"),
+                De => write!(f, "{zips} ZIPs werden durchsucht…"),
+                Es => write!(f, "Escaneando {zips} ZIP…"),
+                It => write!(f, "Scansione di {zips} ZIP…"),
+                Pt => write!(f, "Escaneando {zips} ZIPs…"),
+                Nl => write!(f, "{zips} ZIP's scannen…"),
+                Ja => write!(f, "{zips} 個の ZIP をスキャンしています…"),
+                En => write!(f, "Scanning {zips} ZIPs…"),
+            },
+            Msg::ZipsScanned {
+                scanned,
+                seen,
+                zips,
+                failed,
+                path,
+            } => {
+                let path = path.display();
+                match lang {
+                    Fr => write!(
+                        f,
+                        "{scanned} entrées sur {seen} analysées dans {zips} ZIP ({failed} en échec), rapport écrit dans {path}"
+                    ),
+                    De => write!(
+                        f,
+                        "{scanned} von {seen} Einträgen in {zips} ZIPs durchsucht ({failed} fehlgeschlagen), Bericht nach {path} geschrieben"
+                    ),
+                    Es => write!(
+                        f,
+                        "{scanned} de {seen} entradas escaneadas en {zips} ZIP ({failed} fallidos), informe escrito en {path}"
+                    ),
+                    It => write!(
+                        f,
+                        "{scanned} voci su {seen} scansionate in {zips} ZIP ({failed} fallite), rapporto scritto in {path}"
+                    ),
+                    Pt => write!(
+                        f,
+                        "{scanned} de {seen} entradas escaneadas em {zips} ZIPs ({failed} falharam), relatório escrito em {path}"
+                    ),
+                    Nl => write!(
+                        f,
+                        "{scanned} van {seen} items gescand in {zips} ZIP's ({failed} mislukt), rapport geschreven naar {path}"
+                    ),
+                    Ja => write!(
+                        f,
+                        "{zips} 個の ZIP で {seen} 個中 {scanned} 個のエントリをスキャンしました ({failed} 個失敗)。レポートを {path} に書き込みました"
+                    ),
+                    En => write!(
+                        f,
+                        "{scanned} of {seen} entries scanned in {zips} ZIPs ({failed} failed), report written to {path}"
+                    ),
+                }
+            }
+            Msg::Queued {
+                added,
+                already,
+                pending,
+            } => match lang {
+                Fr => write!(
+                    f,
+                    "{added} niveaux mis en file, {already} y étaient déjà, {pending} en attente"
+                ),
+                De => write!(
+                    f,
+                    "{added} Level eingereiht, {already} waren schon in der Warteschlange, {pending} ausstehend"
+                ),
+                Es => write!(
+                    f,
+                    "{added} niveles encolados, {already} ya estaban en la cola, {pending} pendientes"
+                ),
+                It => write!(
+                    f,
+                    "{added} livelli in coda, {already} erano già in coda, {pending} in attesa"
+                ),
+                Pt => write!(
+                    f,
+                    "{added} níveis enfileirados, {already} já estavam na fila, {pending} pendentes"
+                ),
+                Nl => write!(
+                    f,
+                    "{added} levels in de wachtrij gezet, {already} stonden er al in, {pending} wachtend"
+                ),
+                Ja => write!(
+                    f,
+                    "{added} 件のレベルをキューに追加しました ({already} 件は追加済み)。保留 {pending} 件"
+                ),
+                En => write!(
+                    f,
+                    "Queued {added} levels, {already} were already in the queue, {pending} pending"
+                ),
+            },
+            Msg::QueuePending => match lang {
+                Fr => write!(f, "attente"),
+                De => write!(f, "offen"),
+                Es => write!(f, "pendiente"),
+                It => write!(f, "attesa"),
+                Pt => write!(f, "pendente"),
+                Nl => write!(f, "wachtend"),
+                Ja => write!(f, "保留"),
+                En => write!(f, "pending"),
+            },
+            Msg::QueueDone => match lang {
+                Fr => write!(f, "fait"),
+                De => write!(f, "fertig"),
+                Es => write!(f, "hecho"),
+                It => write!(f, "fatto"),
+                Pt => write!(f, "feito"),
+                Nl => write!(f, "klaar"),
+                Ja => write!(f, "完了"),
+                En => write!(f, "done"),
+            },
+            Msg::QueueFailed => match lang {
+                Fr => write!(f, "échec"),
+                De => write!(f, "fehler"),
+                Es => write!(f, "fallido"),
+                It => write!(f, "fallito"),
+                Pt => write!(f, "falhou"),
+                Nl => write!(f, "mislukt"),
+                Ja => write!(f, "失敗"),
+                En => write!(f, "failed"),
+            },
+            Msg::QueueAdded(date) => match lang {
+                Fr => write!(f, "ajouté {date}"),
+                De => write!(f, "hinzugefügt {date}"),
+                Es => write!(f, "añadido {date}"),
+                It => write!(f, "aggiunto {date}"),
+                Pt => write!(f, "adicionado {date}"),
+                Nl => write!(f, "toegevoegd {date}"),
+                Ja => write!(f, "追加 {date}"),
+                En => write!(f, "added {date}"),
+            },
+            Msg::QueueRun(date) => match lang {
+                Fr => write!(f, "lancé {date}"),
+                De => write!(f, "ausgeführt {date}"),
+                Es => write!(f, "ejecutado {date}"),
+                It => write!(f, "eseguito {date}"),
+                Pt => write!(f, "executado {date}"),
+                Nl => write!(f, "uitgevoerd {date}"),
+                Ja => write!(f, "実行 {date}"),
+                En => write!(f, "run {date}"),
+            },
+            Msg::QueueCounts {
+                levels,
+                pending,
+                done,
+                failed,
+            } => match lang {
+                Fr => write!(f, "{levels} niveaux : {pending} en attente, {done} faits, {failed} en échec"),
+                De => write!(f, "{levels} Level: {pending} ausstehend, {done} fertig, {failed} fehlgeschlagen"),
+                Es => write!(f, "{levels} niveles: {pending} pendientes, {done} hechos, {failed} fallidos"),
+                It => write!(f, "{levels} livelli: {pending} in attesa, {done} fatti, {failed} falliti"),
+                Pt => write!(f, "{levels} níveis: {pending} pendentes, {done} feitos, {failed} falharam"),
+                Nl => write!(f, "{levels} levels: {pending} wachtend, {done} klaar, {failed} mislukt"),
+                Ja => write!(f, "レベル {levels} 件: 保留 {pending} 件、完了 {done} 件、失敗 {failed} 件"),
+                En => write!(f, "{levels} levels: {pending} pending, {done} done, {failed} failed"),
+            },
+            Msg::NothingToRun => match lang {
+                Fr => write!(f, "Rien à lancer, la file n'a aucun niveau en attente"),
+                De => write!(f, "Nichts auszuführen, die Warteschlange hat keine ausstehenden Level"),
+                Es => write!(f, "Nada que ejecutar, la cola no tiene niveles pendientes"),
+                It => write!(f, "Niente da eseguire, la coda non ha livelli in attesa"),
+                Pt => write!(f, "Nada para executar, a fila não tem níveis pendentes"),
+                Nl => write!(f, "Niets uit te voeren, de wachtrij heeft geen wachtende levels"),
+                Ja => write!(f, "実行するものはありません。キューに保留中のレベルはありません"),
+                En => write!(f, "Nothing to run, the queue has no pending levels"),
+            },
+            Msg::QueueLevelFailed { id, error } => match lang {
+                Fr => write!(f, "le niveau {id} a échoué : {error}"),
+                De => write!(f, "Level {id} fehlgeschlagen: {error}"),
+                Es => write!(f, "el nivel {id} falló: {error}"),
+                It => write!(f, "il livello {id} è fallito: {error}"),
+                Pt => write!(f, "o nível {id} falhou: {error}"),
+                Nl => write!(f, "level {id} mislukt: {error}"),
+                Ja => write!(f, "レベル {id} が失敗しました: {error}"),
+                En => write!(f, "level {id} failed: {error}"),
+            },
+            Msg::QueueRunDone {
+                done,
+                failed,
+                pending,
+            } => match lang {
+                Fr => write!(f, "File lancée : {done} faits, {failed} en échec, {pending} encore en attente"),
+                De => write!(f, "Warteschlange ausgeführt: {done} fertig, {failed} fehlgeschlagen, {pending} noch ausstehend"),
+                Es => write!(f, "Cola ejecutada: {done} hechos, {failed} fallidos, {pending} aún pendientes"),
+                It => write!(f, "Coda eseguita: {done} fatti, {failed} falliti, {pending} ancora in attesa"),
+                Pt => write!(f, "Fila executada: {done} feitos, {failed} falharam, {pending} ainda pendentes"),
+                Nl => write!(f, "Wachtrij uitgevoerd: {done} klaar, {failed} mislukt, {pending} nog wachtend"),
+                Ja => write!(f, "キューを実行しました: 完了 {done} 件、失敗 {failed} 件、保留中 {pending} 件"),
+                En => write!(f, "Queue run: {done} done, {failed} failed, {pending} still pending"),
+            },
+            Msg::QueueRemoved { removed, left } => match lang {
+                Fr => write!(f, "{removed} niveaux retirés, {left} restent dans la file"),
+                De => write!(f, "{removed} Level entfernt, {left} bleiben in der Warteschlange"),
+                Es => write!(f, "{removed} niveles quitados, quedan {left} en la cola"),
+                It => write!(f, "{removed} livelli rimossi, {left} restano in coda"),
+                Pt => write!(f, "{removed} níveis removidos, {left} restam na fila"),
+                Nl => write!(f, "{removed} levels verwijderd, {left} over in de wachtrij"),
+                Ja => write!(f, "{removed} 件のレベルを削除しました。キューには {left} 件残っています"),
+                En => write!(f, "Removed {removed} levels, {left} left in the queue"),
+            },
+            Msg::FetchingUserIcons(users) => match lang {
+                Fr => write!(f, "Récupération des icônes de {users} utilisateurs"),
+                De => write!(f, "Icons von {users} Benutzern werden abgerufen"),
+                Es => write!(f, "Obteniendo los iconos de {users} usuarios"),
+                It => write!(f, "Scaricamento delle icone di {users} utenti"),
+                Pt => write!(f, "Obtendo os ícones de {users} usuários"),
+                Nl => write!(f, "Iconen van {users} gebruikers ophalen"),
+                Ja => write!(f, "{users} 人のユーザーのアイコンを取得しています"),
+                En => write!(f, "Fetching icons of {users} users"),
+            },
+            Msg::UserIconNotInArchive { icon, user } => match lang {
+                Fr => write!(f, "l'icône {icon} de `{user}` n'est pas dans l'archive"),
+                De => write!(f, "Icon {icon} von `{user}` nicht im Archiv"),
+                Es => write!(f, "el icono {icon} de `{user}` no está en el archivo"),
+                It => write!(f, "l'icona {icon} di `{user}` non è nell'archivio"),
+                Pt => write!(f, "o ícone {icon} de `{user}` não está no arquivo"),
+                Nl => write!(f, "icoon {icon} van `{user}` niet in het archief"),
+                Ja => write!(f, "`{user}` のアイコン {icon} はアーカイブにありません"),
+                En => write!(f, "icon {icon} of `{user}` not in archive"),
+            },
+            Msg::UserIconFailed { icon, user, error } => match lang {
+                Fr => write!(f, "l'icône {icon} de `{user}` a échoué : {error}"),
+                De => write!(f, "Icon {icon} von `{user}` fehlgeschlagen: {error}"),
+                Es => write!(f, "el icono {icon} de `{user}` falló: {error}"),
+                It => write!(f, "l'icona {icon} di `{user}` è fallita: {error}"),
+                Pt => write!(f, "o ícone {icon} de `{user}` falhou: {error}"),
+                Nl => write!(f, "icoon {icon} van `{user}` mislukt: {error}"),
+                Ja => write!(f, "`{user}` のアイコン {icon} が失敗しました: {error}"),
+                En => write!(f, "icon {icon} of `{user}` failed: {error}"),
+            },
+            Msg::UserIconsWritten {
+                written,
+                path,
+                failed,
+            } => {
+                let path = path.display();
+                match lang {
+                    Fr => write!(f, "{written} icônes d'utilisateur écrites dans {path} ({failed} en échec)"),
+                    De => write!(f, "{written} Benutzer-Icons nach {path} geschrieben ({failed} fehlgeschlagen)"),
+                    Es => write!(f, "{written} iconos de usuario escritos en {path} ({failed} fallidos)"),
+                    It => write!(f, "{written} icone utente scritte in {path} ({failed} fallite)"),
+                    Pt => write!(f, "{written} ícones de usuário escritos em {path} ({failed} falharam)"),
+                    Nl => write!(f, "{written} gebruikersiconen geschreven naar {path} ({failed} mislukt)"),
+                    Ja => write!(f, "{written} 個のユーザーアイコンを {path} に書き込みました ({failed} 個失敗)"),
+                    En => write!(f, "Wrote {written} user icons to {path} ({failed} failed)"),
+                }
+            }
+            Msg::NoArchiveIcon(slot) => match lang {
+                Fr => write!(f, "{slot} n'a pas d'icône dans l'archive"),
+                De => write!(f, "{slot} hat kein Icon im Archiv"),
+                Es => write!(f, "{slot} no tiene icono en el archivo"),
+                It => write!(f, "{slot} non ha un'icona nell'archivio"),
+                Pt => write!(f, "{slot} não tem ícone no arquivo"),
+                Nl => write!(f, "{slot} heeft geen icoon in het archief"),
+                Ja => write!(f, "{slot} にはアーカイブ内のアイコンがありません"),
+                En => write!(f, "{slot} has no icon in the archive"),
+            },
+            Msg::NotATexture(icon) => match lang {
+                Fr => write!(f, "l'icône {icon} n'est pas une texture"),
+                De => write!(f, "Icon {icon} ist keine Textur"),
+                Es => write!(f, "el icono {icon} no es una textura"),
+                It => write!(f, "l'icona {icon} non è una texture"),
+                Pt => write!(f, "o ícone {icon} não é uma textura"),
+                Nl => write!(f, "icoon {icon} is geen textuur"),
+                Ja => write!(f, "アイコン {icon} はテクスチャではありません"),
+                En => write!(f, "icon {icon} isn't a texture"),
+            },
+            Msg::IconFailed { icon, error } => match lang {
+                Fr => write!(f, "l'icône {icon} a échoué : {error}"),
+                De => write!(f, "Icon {icon} fehlgeschlagen: {error}"),
+                Es => write!(f, "el icono {icon} falló: {error}"),
+                It => write!(f, "l'icona {icon} è fallita: {error}"),
+                Pt => write!(f, "o ícone {icon} falhou: {error}"),
+                Nl => write!(f, "icoon {icon} mislukt: {error}"),
+                Ja => write!(f, "アイコン {icon} が失敗しました: {error}"),
+                En => write!(f, "icon {icon} failed: {error}"),
+            },
+            Msg::SheetWritten {
+                icons,
+                levels,
+                path,
+                map,
+            } => {
+                let (path, map) = (path.display(), map.display());
+                match lang {
+                    Fr => write!(
+                        f,
+                        "{icons} icônes pour {levels} niveaux écrites dans {path} et leurs places dans {map}"
+                    ),
+                    De => write!(
+                        f,
+                        "{icons} Icons für {levels} Level nach {path} und ihre Positionen nach {map} geschrieben"
+                    ),
+                    Es => write!(
+                        f,
+                        "{icons} iconos de {levels} niveles escritos en {path} y sus posiciones en {map}"
+                    ),
+                    It => write!(
+                        f,
+                        "{icons} icone per {levels} livelli scritte in {path} e le loro posizioni in {map}"
+                    ),
+                    Pt => write!(
+                        f,
+                        "{icons} ícones de {levels} níveis escritos em {path} e suas posições em {map}"
+                    ),
+                    Nl => write!(
+                        f,
+                        "{icons} iconen voor {levels} levels geschreven naar {path} en hun plaatsen naar {map}"
+                    ),
+                    Ja => write!(
+                        f,
+                        "{levels} 件のレベルのアイコン {icons} 個を {path} に、その位置を {map} に書き込みました"
+                    ),
+                    En => write!(
+                        f,
+                        "Wrote {icons} icons for {levels} levels to {path} and their places to {map}"
+                    ),
+                }
+            }
+            Msg::NoUsableIcon(levels) => match lang {
+                Fr => write!(f, "{levels} niveaux n'ont pas d'icône utilisable"),
+                De => write!(f, "{levels} Level haben kein brauchbares Icon"),
+                Es => write!(f, "{levels} niveles no tienen un icono utilizable"),
+                It => write!(f, "{levels} livelli non hanno un'icona utilizzabile"),
+                Pt => write!(f, "{levels} níveis não têm ícone utilizável"),
+                Nl => write!(f, "{levels} levels hebben geen bruikbaar icoon"),
+                Ja => write!(f, "{levels} 件のレベルには使えるアイコンがありません"),
+                En => write!(f, "{levels} levels have no usable icon"),
+            },
+            Msg::CacheCleanupFailed(error) => match lang {
+                Fr => write!(f, "impossible de nettoyer le cache des ressources : {error}"),
+                De => write!(f, "der Ressourcen-Cache konnte nicht aufgeräumt werden: {error}"),
+                Es => write!(f, "no se pudo limpiar la caché de recursos: {error}"),
+                It => write!(f, "impossibile ripulire la cache delle risorse: {error}"),
+                Pt => write!(f, "não foi possível limpar o cache de recursos: {error}"),
+                Nl => write!(f, "de resourcecache kon niet worden opgeruimd: {error}"),
+                Ja => write!(f, "リソースキャッシュを整理できませんでした: {error}"),
+                En => write!(f, "couldn't clean up the resource cache: {error}"),
+            },
+            Msg::MetricsNotWritten { path, error } => {
+                let path = path.display();
+                match lang {
+                    Fr => write!(f, "impossible d'écrire {path} : {error}"),
+                    De => write!(f, "{path} konnte nicht geschrieben werden: {error}"),
+                    Es => write!(f, "no se pudo escribir {path}: {error}"),
+                    It => write!(f, "impossibile scrivere {path}: {error}"),
+                    Pt => write!(f, "não foi possível escrever {path}: {error}"),
+                    Nl => write!(f, "{path} kon niet worden geschreven: {error}"),
+                    Ja => write!(f, "{path} を書き込めませんでした: {error}"),
+                    En => write!(f, "couldn't write {path}: {error}"),
+                }
+            }
+            Msg::TimeLimitReached => match lang {
+                Fr => write!(f, "Limite de temps atteinte, l'exécution est partielle"),
+                De => write!(f, "Zeitlimit erreicht, der Lauf ist unvollständig"),
+                Es => write!(f, "Límite de tiempo alcanzado, la ejecución es parcial"),
+                It => write!(f, "Limite di tempo raggiunto, l'esecuzione è parziale"),
+                Pt => write!(f, "Limite de tempo atingido, a execução é parcial"),
+                Nl => write!(f, "Tijdslimiet bereikt, de run is gedeeltelijk"),
+                Ja => write!(f, "時間制限に達したため、実行は途中までです"),
+                En => write!(f, "Time limit reached, the run is partial"),
+            },
+            Msg::LevelNumber(id) => match lang {
+                Fr => write!(f, "(niveau {id})"),
+                De => write!(f, "(Level {id})"),
+                Es => write!(f, "(nivel {id})"),
+                It => write!(f, "(livello {id})"),
+                Pt => write!(f, "(nível {id})"),
+                Nl => write!(f, "(level {id})"),
+                Ja => write!(f, "(レベル {id})"),
+                En => write!(f, "(level {id})"),
+            },
+            Msg::StaleLockRemoved(pid) => match lang {
+                Fr => write!(f, "Suppression du verrou du PID {pid}, qui ne tourne plus"),
+                De => write!(f, "Die Sperre von PID {pid}, die nicht mehr läuft, wird entfernt"),
+                Es => write!(f, "Quitando el bloqueo del PID {pid}, que ya no se ejecuta"),
+                It => write!(f, "Rimozione del blocco del PID {pid}, che non è più in esecuzione"),
+                Pt => write!(f, "Removendo o bloqueio do PID {pid}, que não está mais rodando"),
+                Nl => write!(f, "De vergrendeling van PID {pid}, die niet meer draait, wordt verwijderd"),
+                Ja => write!(f, "実行されていない PID {pid} のロックを削除します"),
+                En => write!(f, "Removing the lock of PID {pid}, which isn't running anymore"),
+            },
+            Msg::LockTakenOver(dir) => {
+                let dir = dir.display();
+                match lang {
+                    Fr => write!(f, "reprise du verrou de `{dir}` (--force)"),
+                    De => write!(f, "die Sperre von `{dir}` wird übernommen (--force)"),
+                    Es => write!(f, "tomando el bloqueo de `{dir}` (--force)"),
+                    It => write!(f, "presa in carico del blocco di `{dir}` (--force)"),
+                    Pt => write!(f, "assumindo o bloqueio de `{dir}` (--force)"),
+                    Nl => write!(f, "de vergrendeling van `{dir}` wordt overgenomen (--force)"),
+                    Ja => write!(f, "`{dir}` のロックを引き継ぎます (--force)"),
+                    En => write!(f, "taking over the lock of `{dir}` (--force)"),
+                }
+            }
+            Msg::CachedDamaged { hash, error } => match lang {
+                Fr => write!(f, "{hash} en cache est abîmé, nouvelle récupération : {error}"),
+                De => write!(f, "zwischengespeichertes {hash} ist beschädigt, wird neu abgerufen: {error}"),
+                Es => write!(f, "{hash} en caché está dañado, se obtiene de nuevo: {error}"),
+                It => write!(f, "{hash} in cache è danneggiato, viene riscaricato: {error}"),
+                Pt => write!(f, "{hash} em cache está danificado, obtendo de novo: {error}"),
+                Nl => write!(f, "gecachte {hash} is beschadigd, opnieuw ophalen: {error}"),
+                Ja => write!(f, "キャッシュの {hash} が破損しているため再取得します: {error}"),
+                En => write!(f, "cached {hash} is damaged, fetching it again: {error}"),
+            },
+            Msg::ClosureCacheUnavailable(error) => match lang {
+                Fr => write!(f, "cache des fermetures indisponible, les niveaux sont parcourus à chaque fois : {error}"),
+                De => write!(f, "Closure-Cache nicht verfügbar, Level werden jedes Mal durchlaufen: {error}"),
+                Es => write!(f, "caché de cierres no disponible, los niveles se recorren cada vez: {error}"),
+                It => write!(f, "cache delle chiusure non disponibile, i livelli vengono percorsi ogni volta: {error}"),
+                Pt => write!(f, "cache de fechamentos indisponível, os níveis são percorridos toda vez: {error}"),
+                Nl => write!(f, "closure-cache niet beschikbaar, levels worden elke keer doorlopen: {error}"),
+                Ja => write!(f, "クロージャーキャッシュが使えないため、毎回レベルをたどります: {error}"),
+                En => write!(f, "closure cache unavailable, levels are walked every time: {error}"),
+            },
+            Msg::ResourceMissing { hash, error } => match lang {
+                Fr => write!(f, "{hash} manque : {error}"),
+                De => write!(f, "{hash} fehlt: {error}"),
+                Es => write!(f, "falta {hash}: {error}"),
+                It => write!(f, "manca {hash}: {error}"),
+                Pt => write!(f, "falta {hash}: {error}"),
+                Nl => write!(f, "{hash} ontbreekt: {error}"),
+                Ja => write!(f, "{hash} がありません: {error}"),
+                En => write!(f, "{hash} is missing: {error}"),
+            },
+            Msg::TryingAlternate {
+                hash,
+                alternate,
+                error,
+            } => match lang {
+                Fr => write!(f, "{hash} manque, essai de son remplaçant {alternate} : {error}"),
+                De => write!(f, "{hash} fehlt, sein Ersatz {alternate} wird versucht: {error}"),
+                Es => write!(f, "falta {hash}, probando su alternativo {alternate}: {error}"),
+                It => write!(f, "manca {hash}, si prova il suo sostituto {alternate}: {error}"),
+                Pt => write!(f, "falta {hash}, tentando seu alternativo {alternate}: {error}"),
+                Nl => write!(f, "{hash} ontbreekt, zijn alternatief {alternate} wordt geprobeerd: {error}"),
+                Ja => write!(f, "{hash} がないため、代替の {alternate} を試します: {error}"),
+                En => write!(f, "{hash} is missing, trying its alternate {alternate}: {error}"),
+            },
+            Msg::LeavingOut { hash, error } => match lang {
+                Fr => write!(f, "{hash} laissé de côté : {error}"),
+                De => write!(f, "{hash} wird weggelassen: {error}"),
+                Es => write!(f, "se deja fuera {hash}: {error}"),
+                It => write!(f, "{hash} viene escluso: {error}"),
+                Pt => write!(f, "deixando {hash} de fora: {error}"),
+                Nl => write!(f, "{hash} wordt weggelaten: {error}"),
+                Ja => write!(f, "{hash} を除外します: {error}"),
+                En => write!(f, "leaving out {hash}: {error}"),
+            },
+            Msg::SlotIconFallback { hash, error } => match lang {
+                Fr => write!(f, "impossible de récupérer l'icône du slot {hash}, repli : {error}"),
+                De => write!(f, "Slot-Icon {hash} konnte nicht abgerufen werden, Ausweichlösung: {error}"),
+                Es => write!(f, "no se pudo obtener el icono del slot {hash}, se usa una alternativa: {error}"),
+                It => write!(f, "impossibile scaricare l'icona dello slot {hash}, si ripiega: {error}"),
+                Pt => write!(f, "não foi possível obter o ícone do slot {hash}, usando alternativa: {error}"),
+                Nl => write!(f, "slot-icoon {hash} kon niet worden opgehaald, terugvallen: {error}"),
+                Ja => write!(f, "スロットアイコン {hash} を取得できないため、代わりを使います: {error}"),
+                En => write!(f, "couldn't fetch the slot icon {hash}, falling back: {error}"),
+            },
+            Msg::SeedHashLeftOut { hash, error } => match lang {
+                Fr => write!(f, "impossible de récupérer le hash de départ {hash}, laissé de côté : {error}"),
+                De => write!(f, "Seed-Hash {hash} konnte nicht abgerufen werden, wird weggelassen: {error}"),
+                Es => write!(f, "no se pudo obtener el hash semilla {hash}, se deja fuera: {error}"),
+                It => write!(f, "impossibile scaricare l'hash seed {hash}, viene escluso: {error}"),
+                Pt => write!(f, "não foi possível obter o hash semente {hash}, deixado de fora: {error}"),
+                Nl => write!(f, "seed-hash {hash} kon niet worden opgehaald, weggelaten: {error}"),
+                Ja => write!(f, "シードハッシュ {hash} を取得できないため除外します: {error}"),
+                En => write!(f, "couldn't fetch the seed hash {hash}, leaving it out: {error}"),
+            },
+            Msg::AlternatesSwapped { swapped, rewritten } => match lang {
+                Fr => write!(f, "{swapped} ressources manquantes remplacées, {rewritten} ressources réécrites pour les utiliser"),
+                De => write!(f, "{swapped} fehlende Ressourcen gegen Ersatz getauscht, {rewritten} Ressourcen umgeschrieben, um ihn zu verwenden"),
+                Es => write!(f, "{swapped} recursos que faltan cambiados por alternativos, {rewritten} recursos reescritos para usarlos"),
+                It => write!(f, "{swapped} risorse mancanti sostituite, {rewritten} risorse riscritte per usarle"),
+                Pt => write!(f, "{swapped} recursos ausentes trocados por alternativos, {rewritten} recursos reescritos para usá-los"),
+                Nl => write!(f, "{swapped} ontbrekende resources vervangen door alternatieven, {rewritten} resources herschreven om ze te gebruiken"),
+                Ja => write!(f, "不足しているリソース {swapped} 個を代替に置き換え、それを使うようにリソース {rewritten} 個を書き換えました"),
+                En => write!(f, "{swapped} missing resources swapped for alternates, {rewritten} resources rewritten to use them"),
+            },
+            Msg::ZipSkipped {
+                zip,
+                resources,
+                reason,
+            } => match lang {
+                Fr => write!(f, "{zip} manquant ou illisible — {resources} ressources ignorées ({reason})"),
+                De => write!(f, "{zip} fehlt oder ist unlesbar — {resources} Ressourcen übersprungen ({reason})"),
+                Es => write!(f, "{zip} falta o es ilegible — {resources} recursos omitidos ({reason})"),
+                It => write!(f, "{zip} mancante o illeggibile — {resources} risorse saltate ({reason})"),
+                Pt => write!(f, "{zip} ausente ou ilegível — {resources} recursos ignorados ({reason})"),
+                Nl => write!(f, "{zip} ontbreekt of is onleesbaar — {resources} resources overgeslagen ({reason})"),
+                Ja => write!(f, "{zip} がないか読めません — リソース {resources} 個をスキップしました ({reason})"),
+                En => write!(f, "{zip} missing or unreadable — {resources} resources skipped ({reason})"),
+            },
+            Msg::ClosureNotRemembered { hash, error } => match lang {
+                Fr => write!(f, "impossible de retenir la fermeture de {hash} : {error}"),
+                De => write!(f, "die Closure von {hash} konnte nicht gespeichert werden: {error}"),
+                Es => write!(f, "no se pudo recordar el cierre de {hash}: {error}"),
+                It => write!(f, "impossibile memorizzare la chiusura di {hash}: {error}"),
+                Pt => write!(f, "não foi possível lembrar o fechamento de {hash}: {error}"),
+                Nl => write!(f, "de closure van {hash} kon niet worden onthouden: {error}"),
+                Ja => write!(f, "{hash} のクロージャーを記録できませんでした: {error}"),
+                En => write!(f, "couldn't remember the closure of {hash}: {error}"),
+            },
+            Msg::IconFileUndecodable(error) => match lang {
+                Fr => write!(f, "impossible de décoder le fichier d'icône : {error}"),
+                De => write!(f, "die Icon-Datei konnte nicht dekodiert werden: {error}"),
+                Es => write!(f, "no se pudo decodificar el archivo de icono: {error}"),
+                It => write!(f, "impossibile decodificare il file dell'icona: {error}"),
+                Pt => write!(f, "não foi possível decodificar o arquivo de ícone: {error}"),
+                Nl => write!(f, "het iconenbestand kon niet worden gedecodeerd: {error}"),
+                Ja => write!(f, "アイコンファイルをデコードできませんでした: {error}"),
+                En => write!(f, "couldn't decode the icon file: {error}"),
+            },
+            Msg::SlotIconUnavailable(source) => match lang {
+                Fr => write!(f, "icône du slot indisponible, {source} est utilisé pour ICON0.PNG"),
+                De => write!(f, "Slot-Icon nicht verfügbar, {source} wird für ICON0.PNG verwendet"),
+                Es => write!(f, "icono del slot no disponible, se usa {source} para ICON0.PNG"),
+                It => write!(f, "icona dello slot non disponibile, si usa {source} per ICON0.PNG"),
+                Pt => write!(f, "ícone do slot indisponível, usando {source} para ICON0.PNG"),
+                Nl => write!(f, "slot-icoon niet beschikbaar, {source} wordt gebruikt voor ICON0.PNG"),
+                Ja => write!(f, "スロットアイコンが使えないため、ICON0.PNG に {source} を使います"),
+                En => write!(f, "slot icon unavailable, using {source} for ICON0.PNG"),
+            },
+            Msg::IconUndecodable { source, error } => match lang {
+                Fr => write!(f, "impossible de décoder {source} : {error}"),
+                De => write!(f, "{source} konnte nicht dekodiert werden: {error}"),
+                Es => write!(f, "no se pudo decodificar {source}: {error}"),
+                It => write!(f, "impossibile decodificare {source}: {error}"),
+                Pt => write!(f, "não foi possível decodificar {source}: {error}"),
+                Nl => write!(f, "{source} kon niet worden gedecodeerd: {error}"),
+                Ja => write!(f, "{source} をデコードできませんでした: {error}"),
+                En => write!(f, "couldn't decode {source}: {error}"),
+            },
+            Msg::PlaceholderIcon => match lang {
+                Fr => write!(f, "aucune icône utilisable, ICON0.PNG générique utilisé"),
+                De => write!(f, "kein brauchbares Icon gefunden, Platzhalter-ICON0.PNG wird verwendet"),
+                Es => write!(f, "no se encontró ningún icono utilizable, se usa un ICON0.PNG genérico"),
+                It => write!(f, "nessuna icona utilizzabile trovata, si usa un ICON0.PNG segnaposto"),
+                Pt => write!(f, "nenhum ícone utilizável encontrado, usando um ICON0.PNG genérico"),
+                Nl => write!(f, "geen bruikbaar icoon gevonden, tijdelijke ICON0.PNG wordt gebruikt"),
+                Ja => write!(f, "使えるアイコンがないため、仮の ICON0.PNG を使います"),
+                En => write!(f, "no usable icon found, using placeholder ICON0.PNG"),
+            },
+            Msg::TimeLimitStopping => match lang {
+                Fr => write!(f, "Limite de temps atteinte, fin de ce qui est en cours puis arrêt"),
+                De => write!(f, "Zeitlimit erreicht, Laufendes wird beendet, dann wird gestoppt"),
+                Es => write!(f, "Límite de tiempo alcanzado, se termina lo que está en curso y se para"),
+                It => write!(f, "Limite di tempo raggiunto, si finisce ciò che è in corso e ci si ferma"),
+                Pt => write!(f, "Limite de tempo atingido, terminando o que está em andamento e parando"),
+                Nl => write!(f, "Tijdslimiet bereikt, wat bezig is wordt afgemaakt en dan wordt er gestopt"),
+                Ja => write!(f, "時間制限に達しました。処理中のものを終えてから停止します"),
+                En => write!(f, "Time limit reached, finishing what's in flight and stopping"),
+            },
+            Msg::ScanFailed { path, error } => {
+                let path = path.display();
+                match lang {
+                    Fr => write!(f, "impossible d'analyser {path} : {error}"),
+                    De => write!(f, "{path} konnte nicht durchsucht werden: {error}"),
+                    Es => write!(f, "no se pudo escanear {path}: {error}"),
+                    It => write!(f, "impossibile scansionare {path}: {error}"),
+                    Pt => write!(f, "não foi possível escanear {path}: {error}"),
+                    Nl => write!(f, "{path} kon niet worden gescand: {error}"),
+                    Ja => write!(f, "{path} をスキャンできませんでした: {error}"),
+                    En => write!(f, "couldn't scan {path}: {error}"),
+                }
+            }
+            Msg::AlternatesNotPointed { hash, error } => match lang {
+                Fr => write!(f, "impossible de faire pointer {hash} vers les remplaçants de ses dépendances manquantes : {error}"),
+                De => write!(f, "{hash} konnte nicht auf den Ersatz seiner fehlenden Abhängigkeiten verweisen: {error}"),
+                Es => write!(f, "no se pudo apuntar {hash} a los alternativos de sus dependencias que faltan: {error}"),
+                It => write!(f, "impossibile far puntare {hash} ai sostituti delle sue dipendenze mancanti: {error}"),
+                Pt => write!(f, "não foi possível apontar {hash} para os alternativos das dependências ausentes: {error}"),
+                Nl => write!(f, "{hash} kon niet naar de alternatieven van zijn ontbrekende afhankelijkheden wijzen: {error}"),
+                Ja => write!(f, "{hash} を不足している依存関係の代替に向けられませんでした: {error}"),
+                En => write!(f, "couldn't point {hash} at the alternates of its missing dependencies: {error}"),
+            },
+            Msg::DbOpenedImmutable { path, error } => {
+                let path = path.display();
+                match lang {
+                    Fr => write!(
+                        f,
+                        "impossible d'ouvrir {path} normalement ({error}), c'est sans doute une base WAL dans un emplacement en lecture seule. Ouverture en immuable, définissez database_immutable dans config.yml pour éviter cela"
+                    ),
+                    De => write!(
+                        f,
+                        "{path} konnte nicht normal geöffnet werden ({error}), wahrscheinlich eine WAL-Datenbank an einem schreibgeschützten Ort. Sie wird unveränderlich geöffnet, database_immutable in config.yml setzen, um das zu überspringen"
+                    ),
+                    Es => write!(
+                        f,
+                        "no se pudo abrir {path} normalmente ({error}), probablemente es una base WAL en una ubicación de solo lectura. Se abre como inmutable, pon database_immutable en config.yml para evitarlo"
+                    ),
+                    It => write!(
+                        f,
+                        "impossibile aprire {path} normalmente ({error}), probabilmente è un database WAL in una posizione di sola lettura. Viene aperto come immutabile, imposta database_immutable in config.yml per evitarlo"
+                    ),
+                    Pt => write!(
+                        f,
+                        "não foi possível abrir {path} normalmente ({error}), provavelmente é um banco WAL num local somente leitura. Abrindo como imutável, defina database_immutable no config.yml para pular isso"
+                    ),
+                    Nl => write!(
+                        f,
+                        "{path} kon niet normaal worden geopend ({error}), waarschijnlijk een WAL-database op een alleen-lezen locatie. Wordt onveranderlijk geopend, zet database_immutable in config.yml om dit over te slaan"
+                    ),
+                    Ja => write!(
+                        f,
+                        "{path} を通常どおり開けませんでした ({error})。読み取り専用の場所にある WAL データベースのようです。immutable で開きます。これを省くには config.yml で database_immutable を設定してください"
+                    ),
+                    En => write!(
+                        f,
+                        "couldn't open {path} normally ({error}), it's probably a WAL database on a read-only location. Opening it as immutable, set database_immutable in config.yml to skip this"
+                    ),
+                }
+            }
+            Msg::BackgroundNotGuid { slot, background } => match lang {
+                Fr => write!(f, "le slot {slot} a le fond {background}, qui n'est pas un GUID, le fond par défaut est utilisé"),
+                De => write!(f, "Slot {slot} hat den Hintergrund {background}, der keine GUID ist, der Standardhintergrund wird verwendet"),
+                Es => write!(f, "el slot {slot} tiene el fondo {background}, que no es un GUID, se usa el fondo por defecto"),
+                It => write!(f, "lo slot {slot} ha lo sfondo {background}, che non è un GUID, si usa lo sfondo predefinito"),
+                Pt => write!(f, "o slot {slot} tem o fundo {background}, que não é um GUID, usando o fundo padrão"),
+                Nl => write!(f, "slot {slot} heeft achtergrond {background}, wat geen GUID is, de standaardachtergrond wordt gebruikt"),
+                Ja => write!(f, "スロット {slot} の背景 {background} は GUID ではないため、既定の背景を使います"),
+                En => write!(f, "slot {slot} has background {background}, which isn't a GUID, using the default background"),
+            },
+        }
+    }
+}
@@ -0,0 +1,220 @@
+// src/metrics.rs
+
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::contents::format_size;
+use crate::models::{RunStats, StageTimes};
+use crate::scan::Tally;
+
+/// A counter that only ever goes up, for the whole process
+pub struct Counter {
+    name: &'static str,
+    help: &'static str,
+    value: AtomicU64,
+}
+
+impl Counter {
+    const fn new(name: &'static str, help: &'static str) -> Self {
+        Self {
+            name,
+            help,
+            value: AtomicU64::new(0),
+        }
+    }
+
+    pub fn add(&self, n: u64) {
+        self.value.fetch_add(n, Ordering::Relaxed);
+    }
+
+    pub fn inc(&self) {
+        self.add(1);
+    }
+
+    pub fn get(&self) -> u64 {
+        self.value.load(Ordering::Relaxed)
+    }
+
+    /// Count `elapsed` in a `_microseconds` counter
+    pub fn add_elapsed(&self, elapsed: Duration) {
+        self.add(elapsed.as_micros() as u64);
+    }
+}
+
+pub static RESOURCES_SERVED: Counter = Counter::new(
+    "archive_resources_served",
+    "Resources handed out, from the cache or the archive",
+);
+pub static CACHE_HITS: Counter = Counter::new(
+    "archive_cache_hits",
+    "Resources found in the resource cache or among ingested blobs",
+);
+pub static CACHE_MISSES: Counter = Counter::new(
+    "archive_cache_misses",
+    "Resources that had to be read from the archive",
+);
+pub static OPEN_FAILURES: Counter = Counter::new(
+    "archive_open_failures",
+    "Archive ZIPs or tars that couldn't be opened",
+);
+pub static BYTES_READ: Counter = Counter::new(
+    "archive_read_bytes",
+    "Bytes read out of the archive, as stored",
+);
+
+pub static CONTAINER_SWITCHES: Counter = Counter::new(
+    "archive_container_switches",
+    "Reads from a different archive ZIP or tar than the read before, seeks on a hard disk",
+);
+
+pub static DISK_CACHE_HITS: Counter = Counter::new(
+    "archive_disk_cache_hits",
+    "Resources read back from the resource cache on disk",
+);
+pub static DISK_CACHE_BYTES: Counter = Counter::new(
+    "archive_disk_cache_bytes",
+    "Bytes of the resources read back from the resource cache",
+);
+pub static INGESTED_HITS: Counter = Counter::new(
+    "archive_ingested_hits",
+    "Resources taken from ingested blobs",
+);
+pub static INGESTED_BYTES: Counter = Counter::new(
+    "archive_ingested_bytes",
+    "Bytes of the resources taken from ingested blobs",
+);
+pub static MEMORY_HITS: Counter = Counter::new(
+    "archive_memory_hits",
+    "Resources asked for again while already in memory",
+);
+pub static MEMORY_BYTES: Counter = Counter::new(
+    "archive_memory_bytes",
+    "Bytes of the resources asked for again while already in memory",
+);
+pub static EXTRACTIONS: Counter = Counter::new(
+    "archive_extractions",
+    "Resources read out of an archive ZIP or tar",
+);
+pub static EXTRACTED_BYTES: Counter = Counter::new(
+    "archive_extracted_bytes",
+    "Bytes of the resources read out of the archive, inflated",
+);
+
+pub static FETCH_TIME: Counter = Counter::new(
+    "archive_fetch_microseconds",
+    "Time spent fetching levels, from the first resource to the last",
+);
+pub static READ_TIME: Counter = Counter::new(
+    "archive_read_microseconds",
+    "Time the I/O threads spent reading from the archive, summed over threads",
+);
+pub static INFLATE_TIME: Counter = Counter::new(
+    "archive_inflate_microseconds",
+    "Time the CPU pool spent inflating and checking resources, summed over threads",
+);
+pub static WRITE_TIME: Counter = Counter::new(
+    "archive_write_microseconds",
+    "Time spent writing save archives",
+);
+
+const COUNTERS: [&Counter; 18] = [
+    &RESOURCES_SERVED,
+    &CACHE_HITS,
+    &CACHE_MISSES,
+    &OPEN_FAILURES,
+    &BYTES_READ,
+    &CONTAINER_SWITCHES,
+    &DISK_CACHE_HITS,
+    &DISK_CACHE_BYTES,
+    &INGESTED_HITS,
+    &INGESTED_BYTES,
+    &MEMORY_HITS,
+    &MEMORY_BYTES,
+    &EXTRACTIONS,
+    &EXTRACTED_BYTES,
+    &FETCH_TIME,
+    &READ_TIME,
+    &INFLATE_TIME,
+    &WRITE_TIME,
+];
+
+/// Run `f`, counting how long it took in the `_microseconds` counter `counter`
+pub fn timed<T>(counter: &Counter, f: impl FnOnce() -> T) -> T {
+    let start = Instant::now();
+    let out = f();
+    counter.add_elapsed(start.elapsed());
+    out
+}
+
+fn tally(count: &Counter, bytes: &Counter) -> Tally {
+    Tally {
+        count: count.get(),
+        bytes: bytes.get(),
+    }
+}
+
+fn millis(counter: &Counter) -> u64 {
+    counter.get() / 1000
+}
+
+/// Where the resources of the run came from and where its time went so far
+pub fn run_stats() -> RunStats {
+    RunStats {
+        disk_cache: tally(&DISK_CACHE_HITS, &DISK_CACHE_BYTES),
+        ingested: tally(&INGESTED_HITS, &INGESTED_BYTES),
+        memory: tally(&MEMORY_HITS, &MEMORY_BYTES),
+        archive: tally(&EXTRACTIONS, &EXTRACTED_BYTES),
+        archive_read_bytes: BYTES_READ.get(),
+        container_switches: CONTAINER_SWITCHES.get(),
+        stages: StageTimes {
+            fetch_ms: millis(&FETCH_TIME),
+            read_ms: millis(&READ_TIME),
+            inflate_ms: millis(&INFLATE_TIME),
+            write_ms: millis(&WRITE_TIME),
+        },
+    }
+}
+
+impl RunStats {
+    /// Whether the run fetched anything at all
+    pub fn is_empty(&self) -> bool {
+        self.disk_cache.count + self.ingested.count + self.memory.count + self.archive.count == 0
+    }
+
+    /// The lines printed at the end of a run
+    pub fn render(&self) -> String {
+        let seconds = |ms: u64| Duration::from_millis(ms).as_secs_f64();
+        format!(
+            "▶ Resources: {} from the disk cache ({}), {} ingested ({}), {} from memory ({}), {} extracted from the archive ({}, {} read)\n\
+             ▶ Time: fetching {:.2}s, reading the archive {:.2}s, inflating and checking {:.2}s, writing backups {:.2}s, archive files switched: {}\n",
+            self.disk_cache.count,
+            format_size(self.disk_cache.bytes),
+            self.ingested.count,
+            format_size(self.ingested.bytes),
+            self.memory.count,
+            format_size(self.memory.bytes),
+            self.archive.count,
+            format_size(self.archive.bytes),
+            format_size(self.archive_read_bytes),
+            seconds(self.stages.fetch_ms),
+            seconds(self.stages.read_ms),
+            seconds(self.stages.inflate_ms),
+            seconds(self.stages.write_ms),
+            self.container_switches,
+        )
+    }
+}
+
+/// Every counter in the OpenMetrics text format, which Prometheus scrapes
+/// too. What `--metrics-file` gets
+pub fn render() -> String {
+    let mut out = String::new();
+    for counter in COUNTERS {
+        let _ = writeln!(out, "# TYPE {} counter", counter.name);
+        let _ = writeln!(out, "# HELP {} {}", counter.name, counter.help);
+        let _ = writeln!(out, "{}_total {}", counter.name, counter.get());
+    }
+    out.push_str("# EOF\n");
+    out
+}
@@ -1,21 +1,182 @@
 // src/models.rs
 
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs::{self, File};
+use std::path::Path;
+
+use anyhow::{Result, anyhow};
 use bson::oid::ObjectId;
-use chrono::{DateTime, Utc};
+use bson::{Bson, Document};
+use chrono::{DateTime, SecondsFormat, TimeZone, Utc};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use crate::language::Language;
+use crate::metrics;
+use crate::scan::Tally;
+
+/// name of the combined Refresh import written by `--export refresh`
+pub const REFRESH_IMPORT_FILE: &str = "import.json";
+/// name of the combined Refresh import written by `--export refresh-bson`
+pub const REFRESH_BSON_IMPORT_FILE: &str = "import.bson";
+
 /// Top‐level wrapper for your import.json
-#[derive(Serialize)]
+#[derive(Default, Serialize, Deserialize)]
 pub struct ImportData {
     pub users: Vec<GameUser>,
     pub levels: Vec<GameLevel>,
     pub relations: Vec<AssetDependencyRelation>,
     pub assets: Vec<GameAsset>,
+    /// typed dependency edges, only with `export_dependency_types`, Refresh ignores them
+    #[serde(
+        rename = "DependencyEdges",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub dependency_edges: Vec<DependencyEdge>,
+}
+
+/// date fields of each `ImportData` collection, BSON dates in `import.bson`
+/// but RFC 3339 strings everywhere else
+const BSON_DATE_FIELDS: [(&str, &[&str]); 3] = [
+    ("users", &["JoinDate", "BanExpiryDate", "LastLoginDate"]),
+    ("levels", &["PublishDate", "UpdateDate", "DateTeamPicked"]),
+    ("assets", &["UploadDate"]),
+];
+
+/// Rewrite the date fields of every record in `doc` with `convert`
+fn convert_dates(
+    doc: &mut Document,
+    convert: impl Fn(&Bson) -> Result<Option<Bson>>,
+) -> Result<()> {
+    for (collection, fields) in BSON_DATE_FIELDS {
+        let Some(Bson::Array(records)) = doc.get_mut(collection) else {
+            continue;
+        };
+        for record in records {
+            let Bson::Document(record) = record else {
+                continue;
+            };
+            for field in fields {
+                if let Some(value) = record.get_mut(*field)
+                    && let Some(converted) =
+                        convert(value).map_err(|e| anyhow!("{}.{}: {}", collection, field, e))?
+                {
+                    *value = converted;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+impl ImportData {
+    /// The import as one BSON document, with the types Realm stores: ObjectIds
+    /// stay ObjectIds and dates become BSON dates instead of strings
+    pub fn to_bson(&self) -> Result<Vec<u8>> {
+        let mut doc = bson::to_document(self)?;
+        convert_dates(&mut doc, |value| match value {
+            Bson::String(text) => {
+                let date = DateTime::parse_from_rfc3339(text)?;
+                Ok(Some(Bson::DateTime(bson::DateTime::from_millis(
+                    date.timestamp_millis(),
+                ))))
+            }
+            _ => Ok(None),
+        })?;
+        Ok(bson::to_vec(&doc)?)
+    }
+
+    /// Read an import.json or import.bson, going by the extension
+    pub fn read(path: &Path) -> Result<Self> {
+        let data =
+            fs::read(path).map_err(|e| anyhow!("couldn't open {}: {}", path.display(), e))?;
+        let import = match path.extension().and_then(|e| e.to_str()) {
+            Some("bson") => Self::from_bson(&data),
+            _ => serde_json::from_slice(&data).map_err(Into::into),
+        };
+        import.map_err(|e| anyhow!("couldn't parse {}: {}", path.display(), e))
+    }
+
+    /// Read an import written by `to_bson`
+    pub fn from_bson(bytes: &[u8]) -> Result<Self> {
+        let mut doc = Document::from_reader(bytes)?;
+        convert_dates(&mut doc, |value| match value {
+            Bson::DateTime(date) => {
+                let date = Utc
+                    .timestamp_millis_opt(date.timestamp_millis())
+                    .single()
+                    .ok_or_else(|| anyhow!("date {} is out of range", date))?;
+                Ok(Some(Bson::String(
+                    date.to_rfc3339_opts(SecondsFormat::AutoSi, true),
+                )))
+            }
+            _ => Ok(None),
+        })?;
+        Ok(bson::from_document(doc)?)
+    }
+
+    /// Add another import's records, skipping users, levels, relations and
+    /// assets that are already in here (the same asset's dependencies are merged)
+    pub fn merge(&mut self, other: ImportData) {
+        for user in other.users {
+            if !self.users.iter().any(|u| u.username == user.username) {
+                self.users.push(user);
+            }
+        }
+        for level in other.levels {
+            if !self.levels.iter().any(|l| l.level_id == level.level_id) {
+                self.levels.push(level);
+            }
+        }
+        let mut relations: HashSet<(String, String)> = self
+            .relations
+            .iter()
+            .map(|r| (r.dependent.clone(), r.dependency.clone()))
+            .collect();
+        for relation in other.relations {
+            if relations.insert((relation.dependent.clone(), relation.dependency.clone())) {
+                self.relations.push(relation);
+            }
+        }
+        let mut edges: HashSet<(String, String)> = self
+            .dependency_edges
+            .iter()
+            .map(|e| (e.dependent.clone(), e.dependency.clone()))
+            .collect();
+        for edge in other.dependency_edges {
+            if edges.insert((edge.dependent.clone(), edge.dependency.clone())) {
+                self.dependency_edges.push(edge);
+            }
+        }
+        let mut assets: HashMap<String, usize> = self
+            .assets
+            .iter()
+            .enumerate()
+            .map(|(i, a)| (a.asset_hash.clone(), i))
+            .collect();
+        for asset in other.assets {
+            match assets.get(&asset.asset_hash) {
+                Some(&i) => {
+                    let deps = &mut self.assets[i].dependencies;
+                    for dep in asset.dependencies {
+                        if !deps.contains(&dep) {
+                            deps.push(dep);
+                        }
+                    }
+                }
+                None => {
+                    assets.insert(asset.asset_hash.clone(), self.assets.len());
+                    self.assets.push(asset);
+                }
+            }
+        }
+    }
 }
 
 /// Mirrors your C# GameUser
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize)]
 pub struct GameUser {
     #[serde(rename = "UserId")]
     pub user_id: ObjectId,
@@ -62,8 +223,9 @@ pub struct GameUser {
     #[serde(rename = "LocationY")]
     pub location_y: i64,
 
+    /// None if the dump doesn't have it
     #[serde(rename = "JoinDate")]
-    pub join_date: DateTime<Utc>,
+    pub join_date: Option<DateTime<Utc>>,
 
     #[serde(rename = "Pins")]
     pub pins: Value, // placeholder for UserPins
@@ -98,8 +260,9 @@ pub struct GameUser {
     #[serde(rename = "BanExpiryDate")]
     pub ban_expiry_date: Option<DateTime<Utc>>,
 
+    /// None if the dump doesn't have it
     #[serde(rename = "LastLoginDate")]
-    pub last_login_date: DateTime<Utc>,
+    pub last_login_date: Option<DateTime<Utc>>,
 
     #[serde(rename = "RpcnAuthenticationAllowed")]
     pub rpcn_authentication_allowed: bool,
@@ -130,7 +293,7 @@ pub struct GameUser {
 }
 
 /// Mirrors your C# GameLevel
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize)]
 pub struct GameLevel {
     #[serde(rename = "LevelId")]
     pub level_id: i64,
@@ -218,14 +381,10 @@ pub struct GameLevel {
 
     #[serde(rename = "IsReUpload")]
     pub is_re_upload: bool,
-    
-
-
-    
 }
 
 /// Mirrors your C# AssetDependencyRelation
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize)]
 pub struct AssetDependencyRelation {
     #[serde(rename = "Dependent")]
     pub dependent: String,
@@ -234,8 +393,37 @@ pub struct AssetDependencyRelation {
     pub dependency: String,
 }
 
+/// An `AssetDependencyRelation` with what the dependency table says about it
+#[derive(Serialize, Deserialize)]
+pub struct DependencyEdge {
+    #[serde(rename = "Dependent")]
+    pub dependent: String,
+
+    /// SHA1, or `g<guid>` when `IsGuid`
+    #[serde(rename = "Dependency")]
+    pub dependency: String,
+
+    #[serde(rename = "IsGuid")]
+    pub is_guid: bool,
+
+    /// resource type id the dependent declares for it
+    #[serde(rename = "DeclaredType")]
+    pub declared_type: u32,
+
+    #[serde(rename = "DeclaredTypeName")]
+    pub declared_type_name: Option<String>,
+
+    /// what the downloaded blob turned out to be, None for GUIDs and missing blobs
+    #[serde(rename = "ClassifiedAs")]
+    pub classified_as: Option<String>,
+
+    /// the blob doesn't look like its declared type
+    #[serde(rename = "TypeMismatch")]
+    pub type_mismatch: bool,
+}
+
 /// Mirrors your C# GameAsset
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize)]
 pub struct GameAsset {
     #[serde(rename = "AssetHash")]
     pub asset_hash: String,
@@ -270,3 +458,653 @@ pub struct GameAsset {
     #[serde(rename = "AsMainlinePhotoHash")]
     pub as_mainline_photo_hash: Option<String>,
 }
+
+// The JSON files below are read by catalog software, so they're versioned.
+// Fields are only ever added, and need a default so files written before
+// them still parse. Renaming, removing or changing the meaning of a field
+// bumps the file's schema version. tests/schema.rs checks old files still
+// parse and that no field goes missing.
+
+/// layout of files written before they had a `schema_version`, the
+/// default of every versioned file's `schema_version` field
+pub(crate) fn first_schema_version() -> u32 {
+    1
+}
+
+/// Written into every `bkp` backup folder
+pub const BACKUP_META_FILE: &str = "backup.meta.json";
+pub const BACKUP_META_SCHEMA_VERSION: u32 = 1;
+
+/// What a backup was made from and what had to be changed to make it
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BackupMeta {
+    #[serde(default = "first_schema_version")]
+    pub schema_version: u32,
+    pub slot_id: i64,
+    pub name: String,
+    pub creator: String,
+    /// game the backup was written for
+    pub game: String,
+    pub written_at: DateTime<Utc>,
+    /// resources left out with `--partial-ok`
+    pub omitted_resources: Vec<OmittedResource>,
+    /// slot list fields written differently from the published slot
+    #[serde(default)]
+    pub modifications: Vec<SlotModification>,
+    /// `--language` of the backup title, reused by `refresh-params`. Not in
+    /// backups made before it was recorded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<Language>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OmittedResource {
+    pub sha1: String,
+    pub error: String,
+}
+
+/// A slot list field a backup option changed, like `shareable` for
+/// `--force-copyable`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SlotModification {
+    pub field: String,
+    pub original: String,
+    pub written: String,
+    /// the option that changed it
+    pub reason: String,
+}
+
+/// `level.json` of a `bundle`, everything a server needs to list the level
+/// without parsing its resources. Hashes are hex, GUIDs are `g<guid>`.
+/// Versioned by `format_version`, which predates `schema_version` elsewhere
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BundleMeta {
+    /// bumped when fields change meaning, new fields keep the version
+    pub format_version: u32,
+    pub slot_id: i64,
+    /// GUID slot number of story/DLC slots
+    pub guid: Option<u32>,
+    pub name: String,
+    pub description: String,
+    pub creator: String,
+    pub game: String,
+    pub root_level: String,
+    /// the level's icon resource as the game references it
+    pub icon: Option<String>,
+    /// SHA1 of `icon.png`, when the icon could be converted
+    pub icon_png: Option<String>,
+    /// `LABEL_*` translation keys of the author labels
+    pub labels: Vec<String>,
+    /// `cooperative`, `versus` or `cutscene`
+    pub level_type: String,
+    pub min_players: Option<u8>,
+    pub max_players: Option<u8>,
+    #[serde(default)]
+    pub enforce_min_max_players: bool,
+    #[serde(default)]
+    pub same_screen_game: bool,
+    pub is_sub_level: bool,
+    pub is_adventure: bool,
+    pub initially_locked: bool,
+    pub shareable: bool,
+    /// every file under `resources/`
+    pub resources: Vec<String>,
+    /// resources the level needs that weren't in the archive
+    pub missing_resources: Vec<OmittedResource>,
+    pub written_at: DateTime<Utc>,
+}
+
+/// name of the per-creator status file kept inside a fileDump folder
+pub const DUMP_STATUS_FILE: &str = "status.json";
+pub const DUMP_STATUS_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CreatorState {
+    Done,
+    Failed,
+    Skipped,
+    /// the time limit stopped the dump before all their levels were fetched
+    Partial,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreatorStatus {
+    pub state: CreatorState,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// `--filter-game` of the run that recorded this, like `LBP2`. Without
+    /// one every game was dumped
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter_game: Option<String>,
+}
+
+impl CreatorStatus {
+    /// Whether `--resume` with `filter_game` can skip the creator. Failed
+    /// and partial ones are fetched again, and so are ones done for only
+    /// one game when the resumed run wants another, or all of them
+    pub fn is_finished(&self, filter_game: Option<&str>) -> bool {
+        matches!(self.state, CreatorState::Done | CreatorState::Skipped)
+            && (self.filter_game.is_none() || self.filter_game.as_deref() == filter_game)
+    }
+}
+
+/// How each creator of a `read-from-file` dump went, for `--resume`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DumpStatus {
+    #[serde(default = "first_schema_version")]
+    pub schema_version: u32,
+    pub creators: BTreeMap<String, CreatorStatus>,
+}
+
+impl Default for DumpStatus {
+    fn default() -> Self {
+        Self {
+            schema_version: DUMP_STATUS_SCHEMA_VERSION,
+            creators: BTreeMap::new(),
+        }
+    }
+}
+
+/// a state file, or a fresh state if there's none yet
+fn read_state<T: DeserializeOwned + Default>(path: &Path) -> Result<T> {
+    if !path.exists() {
+        return Ok(T::default());
+    }
+    let data = fs::read(path)?;
+    serde_json::from_slice(&data).map_err(|e| anyhow!("couldn't parse {}: {}", path.display(), e))
+}
+
+/// replaces a state file in one go, so an interrupted write doesn't leave half of one
+fn write_state<T: Serialize>(state: &T, path: &Path) -> Result<()> {
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, serde_json::to_string_pretty(state)?)?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+impl DumpStatus {
+    pub fn read(path: &Path) -> Result<Self> {
+        read_state(path)
+    }
+
+    /// written after every creator so an interrupted run can be resumed
+    pub fn write(&self, path: &Path) -> Result<()> {
+        write_state(self, path)
+    }
+}
+
+/// the planet hash, hex, in a `planet` output folder
+pub const PLANET_HASH_FILE: &str = "planet_hash.txt";
+/// the hash of the planet creator's icon, hex, in a `planet` output folder
+pub const CREATOR_ICON_HASH_FILE: &str = "creator_icon_hash.txt";
+
+/// the level queue of `queue`, next to the exe
+pub const LEVEL_QUEUE_FILE: &str = "queue.json";
+pub const LEVEL_QUEUE_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QueueState {
+    Pending,
+    Done,
+    Failed,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct QueuedLevel {
+    pub id: u32,
+    pub state: QueueState,
+    pub added_at: DateTime<Utc>,
+    /// when `queue run` last got to it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Level IDs collected with `queue add` for a later `queue run`, in the
+/// order they were added, each once
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LevelQueue {
+    #[serde(default = "first_schema_version")]
+    pub schema_version: u32,
+    pub levels: Vec<QueuedLevel>,
+}
+
+impl Default for LevelQueue {
+    fn default() -> Self {
+        Self {
+            schema_version: LEVEL_QUEUE_SCHEMA_VERSION,
+            levels: Vec::new(),
+        }
+    }
+}
+
+impl LevelQueue {
+    pub fn read(path: &Path) -> Result<Self> {
+        read_state(path)
+    }
+
+    pub fn write(&self, path: &Path) -> Result<()> {
+        write_state(self, path)
+    }
+
+    /// Read the queue at `path`, change it with `f` and write it back, with
+    /// `queue.lock` next to it locked meanwhile. A `queue add` while a
+    /// `queue run` is going then doesn't lose its levels to the run's write
+    pub fn update<R>(path: &Path, f: impl FnOnce(&mut Self) -> R) -> Result<R> {
+        let lock_path = path.with_extension("lock");
+        let lock = File::create(&lock_path)
+            .map_err(|e| anyhow!("couldn't create {}: {}", lock_path.display(), e))?;
+        lock.lock()
+            .map_err(|e| anyhow!("couldn't lock {}: {}", lock_path.display(), e))?;
+        let mut queue = Self::read(path)?;
+        let result = f(&mut queue);
+        queue.write(path)?;
+        Ok(result)
+    }
+
+    /// What a `queue run` does, in queue order: the pending levels, and the
+    /// failed ones too with `retry_failed`
+    pub fn runnable(&self, retry_failed: bool) -> Vec<u32> {
+        self.levels
+            .iter()
+            .filter(|level| {
+                level.state == QueueState::Pending
+                    || (retry_failed && level.state == QueueState::Failed)
+            })
+            .map(|level| level.id)
+            .collect()
+    }
+
+    /// Queue `id` unless it's there already, whatever its state. Returns
+    /// whether it was added
+    pub fn add(&mut self, id: u32) -> bool {
+        if self.levels.iter().any(|level| level.id == id) {
+            return false;
+        }
+        self.levels.push(QueuedLevel {
+            id,
+            state: QueueState::Pending,
+            added_at: Utc::now(),
+            run_at: None,
+            error: None,
+        });
+        true
+    }
+
+    /// Take `id` out of the queue, returns whether it was there
+    pub fn remove(&mut self, id: u32) -> bool {
+        let before = self.levels.len();
+        self.levels.retain(|level| level.id != id);
+        self.levels.len() != before
+    }
+
+    /// The levels in `state`, in queue order
+    pub fn in_state(&self, state: QueueState) -> impl Iterator<Item = &QueuedLevel> {
+        self.levels.iter().filter(move |level| level.state == state)
+    }
+
+    /// Record how running `id` went, `Err` with why it failed
+    pub fn finish(&mut self, id: u32, result: std::result::Result<(), String>) {
+        if let Some(level) = self.levels.iter_mut().find(|level| level.id == id) {
+            level.run_at = Some(Utc::now());
+            (level.state, level.error) = match result {
+                Ok(()) => (QueueState::Done, None),
+                Err(e) => (QueueState::Failed, Some(e)),
+            };
+        }
+    }
+}
+
+/// name of the file in a planet output folder listing the sub-levels fetched into it
+pub const PLANET_STATE_FILE: &str = "planets.json";
+pub const PLANET_STATE_SCHEMA_VERSION: u32 = 1;
+
+/// Which sub-levels of each planet were fetched completely into a folder,
+/// so fetching the planet again only downloads the rest. Hashes are hex
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PlanetFetchState {
+    #[serde(default = "first_schema_version")]
+    pub schema_version: u32,
+    /// planet hash → sub-level root hash → every resource of the sub-level
+    pub planets: BTreeMap<String, BTreeMap<String, Vec<String>>>,
+}
+
+impl Default for PlanetFetchState {
+    fn default() -> Self {
+        Self {
+            schema_version: PLANET_STATE_SCHEMA_VERSION,
+            planets: BTreeMap::new(),
+        }
+    }
+}
+
+impl PlanetFetchState {
+    pub fn read(path: &Path) -> Result<Self> {
+        read_state(path)
+    }
+
+    pub fn write(&self, path: &Path) -> Result<()> {
+        write_state(self, path)
+    }
+
+    /// Whether `sub_level` was fetched before and `has_blob` still finds all
+    /// of its resources
+    pub fn is_complete(
+        &self,
+        planet: &[u8; 20],
+        sub_level: &[u8; 20],
+        has_blob: impl Fn(&str) -> bool,
+    ) -> bool {
+        self.planets
+            .get(&hex::encode(planet))
+            .and_then(|sub_levels| sub_levels.get(&hex::encode(sub_level)))
+            .is_some_and(|closure| closure.iter().all(|hash| has_blob(hash)))
+    }
+
+    /// Remember that every resource of `sub_level` has been written
+    pub fn record<'a>(
+        &mut self,
+        planet: &[u8; 20],
+        sub_level: &[u8; 20],
+        closure: impl IntoIterator<Item = &'a [u8; 20]>,
+    ) {
+        self.planets.entry(hex::encode(planet)).or_default().insert(
+            hex::encode(sub_level),
+            closure.into_iter().map(hex::encode).collect(),
+        );
+    }
+
+    /// Add the sub-levels `other` recorded, for folders copied into one
+    pub fn merge(&mut self, other: Self) {
+        for (planet, sub_levels) in other.planets {
+            self.planets.entry(planet).or_default().extend(sub_levels);
+        }
+    }
+}
+
+/// where `archive verify` keeps its progress unless told otherwise
+pub const ARCHIVE_VERIFY_FILE: &str = "archive_verify.json";
+pub const ARCHIVE_VERIFY_SCHEMA_VERSION: u32 = 1;
+
+/// What `archive verify` found in one archive ZIP
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct ZipVerification {
+    /// entries named by a SHA1 that were hashed
+    pub entries: u64,
+    pub bytes: u64,
+    /// entries whose data doesn't hash to their name
+    pub bad_hash: Vec<String>,
+    /// entries that couldn't be read or inflated
+    pub unreadable: Vec<String>,
+    /// why the ZIP couldn't be read at all, like it missing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Progress of an `archive verify` run, written after every ZIP so the run
+/// can be resumed. Each shard has its own file, and the files of all shards
+/// together cover the archive
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VerifyState {
+    #[serde(default = "first_schema_version")]
+    pub schema_version: u32,
+    /// which ZIPs the run covers, `<index>/<count>` or `prefix <hex>`
+    pub shard: String,
+    /// ZIP prefix → what was found in it, for the ZIPs done so far
+    pub zips: BTreeMap<String, ZipVerification>,
+}
+
+impl Default for VerifyState {
+    fn default() -> Self {
+        Self {
+            schema_version: ARCHIVE_VERIFY_SCHEMA_VERSION,
+            shard: String::new(),
+            zips: BTreeMap::new(),
+        }
+    }
+}
+
+impl VerifyState {
+    pub fn read(path: &Path) -> Result<Self> {
+        read_state(path)
+    }
+
+    pub fn write(&self, path: &Path) -> Result<()> {
+        write_state(self, path)
+    }
+}
+
+pub const SPRITE_SHEET_SCHEMA_VERSION: u32 = 1;
+
+/// Where an icon is on an `icon sheet` PNG, in pixels from its top left
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct SpriteRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// The coordinates map written next to an `icon sheet` PNG, as
+/// `<sheet>.json`. Levels sharing an icon share its place on the sheet
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SpriteSheetMap {
+    #[serde(default = "first_schema_version")]
+    pub schema_version: u32,
+    /// file name of the PNG, in the same folder
+    pub image: String,
+    pub width: u32,
+    pub height: u32,
+    /// every icon is scaled to fit a square this wide and centered in it
+    pub cell_size: u32,
+    /// by level id, or `guid:<n>` for story/DLC slots
+    pub icons: BTreeMap<String, SpriteRect>,
+    /// levels without an icon or whose icon couldn't be read
+    #[serde(default)]
+    pub missing: Vec<String>,
+}
+
+pub const JSON_OUTPUT_SCHEMA_VERSION: u32 = 1;
+
+/// What an informational command prints with `--json` instead of its text,
+/// one document on stdout. `result` is one of the structs below, or the
+/// report of `find-duplicates`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JsonOutput<T> {
+    #[serde(default = "first_schema_version")]
+    pub schema_version: u32,
+    /// the subcommand, like `levels-of-hash`
+    pub command: String,
+    pub result: T,
+    /// how the resources the command needed were fetched, left out if it
+    /// didn't fetch any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stats: Option<RunStats>,
+}
+
+impl<T: Default> Default for JsonOutput<T> {
+    fn default() -> Self {
+        Self {
+            schema_version: JSON_OUTPUT_SCHEMA_VERSION,
+            command: String::new(),
+            result: T::default(),
+            stats: None,
+        }
+    }
+}
+
+impl<T: Serialize> JsonOutput<T> {
+    pub fn print(command: &str, result: T) -> Result<()> {
+        let output = Self {
+            schema_version: JSON_OUTPUT_SCHEMA_VERSION,
+            command: command.to_string(),
+            result,
+            stats: Some(metrics::run_stats()).filter(|stats| !stats.is_empty()),
+        };
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        Ok(())
+    }
+}
+
+/// Where the resources of a run came from, each a count with its bytes, and
+/// where its time went, printed at the end of the run
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct RunStats {
+    pub disk_cache: Tally,
+    pub ingested: Tally,
+    /// asked for again while already fetched
+    pub memory: Tally,
+    /// read out of archive ZIPs and tars, inflated
+    pub archive: Tally,
+    /// bytes read out of the archive as stored
+    pub archive_read_bytes: u64,
+    /// reads from another ZIP or tar than the one before
+    pub container_switches: u64,
+    pub stages: StageTimes,
+}
+
+/// Milliseconds spent in each stage. Reading and inflating happen on
+/// several threads at once and are summed over them, so they can add up to
+/// more than the fetch took
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct StageTimes {
+    /// fetching levels, start to end
+    pub fetch_ms: u64,
+    /// reading entries out of the archive
+    pub read_ms: u64,
+    /// inflating resources and checking their hashes
+    pub inflate_ms: u64,
+    /// writing save archives
+    pub write_ms: u64,
+}
+
+/// A slot that uses a resource, for `levels-of-hash`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SlotUse {
+    pub id: i64,
+    pub name: String,
+    /// npHandle, empty for story slots
+    pub creator: String,
+    /// `slot`, or the table of story and DLC slots
+    pub table: String,
+    /// `rootLevel` or `icon`
+    pub column: String,
+    /// the resource the slot uses that depends on the hash, none if it
+    /// uses the hash itself
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub through: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct HashUsers {
+    pub hash: String,
+    /// resources depending on `hash` in `--relations`, `hash` not included
+    pub dependents: usize,
+    pub slots: Vec<SlotUse>,
+}
+
+/// References of one resource type in a rootLevel's dependency table
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ReferenceCount {
+    pub sha1: usize,
+    /// to the games' own assets
+    pub guid: usize,
+}
+
+/// One level of `stats`, see `complexity`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LevelStats {
+    pub id: u32,
+    pub creator: String,
+    /// `LBP2`, … or `unknown`
+    pub game: String,
+    /// `light`, `moderate`, `heavy` or `extreme`
+    pub estimate: String,
+    /// bytes of the rootLevel's data once inflated
+    pub level_data: u64,
+    pub meshes: Tally,
+    pub textures: Tally,
+    /// not in stats written before materials were counted
+    #[serde(default)]
+    pub materials: Tally,
+    pub plans: Tally,
+    pub resources: Tally,
+    /// by resource type name
+    pub references: BTreeMap<String, ReferenceCount>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct StatsReport {
+    pub levels: Vec<LevelStats>,
+    /// levels that couldn't be fetched or read
+    pub failed: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DlcLevel {
+    pub id: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// how many of the pack's GUIDs it uses
+    pub guids: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DlcCreator {
+    /// npHandle, empty for story slots
+    pub creator: String,
+    pub levels: Vec<DlcLevel>,
+}
+
+/// `dlc usage`, creators with the most levels needing the pack first
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct DlcUsageReport {
+    pub pack: String,
+    /// GUIDs in the pack
+    pub guids: usize,
+    pub creators: Vec<DlcCreator>,
+    /// GUID → how many levels use it
+    pub guid_levels: BTreeMap<u32, usize>,
+    /// levels that couldn't be read and aren't counted
+    pub failed: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffChange {
+    Added,
+    Removed,
+    /// a creator in both databases, or only known from their slots
+    Kept,
+    /// a level whose `lastUpdated` differs
+    Updated,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LevelDiff {
+    pub id: i64,
+    pub name: String,
+    pub change: DiffChange,
+    /// `lastUpdated` in each database for `updated`, RFC 3339
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub old_update: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub new_update: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreatorDiffEntry {
+    /// npHandle, empty for story slots
+    pub creator: String,
+    pub change: DiffChange,
+    pub levels: Vec<LevelDiff>,
+}
+
+/// `compare-creators`, by npHandle
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct CreatorsReport {
+    pub creators: Vec<CreatorDiffEntry>,
+}
@@ -0,0 +1,53 @@
+// src/progress.rs
+
+//! Progress of a backup as calls, for frontends that show it themselves.
+//! The command line prints its own lines to stderr and passes `silent()`,
+//! a GUI implements `ProgressObserver` and hands it to `download_level`
+//! and `make_savearchive` instead of parsing that output.
+
+use std::sync::Arc;
+
+/// What a backup is doing, reported to `on_stage_changed`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    /// fetching the level's resources. With the breadth-first download
+    /// order every depth of the dependency tree is a stage of its own,
+    /// `resources` is how many are fetched in it. Otherwise there's one
+    /// stage at depth 0, `resources` is what was known to be needed when
+    /// it started
+    Fetching { depth: usize, resources: usize },
+    /// checking every resource still hashes to its SHA1
+    Verifying,
+    /// putting the resources and their table into the save archive
+    Packing,
+    /// encrypting the archive as the game does
+    Encrypting,
+    /// writing chunk file `chunk` (from 0) of `chunks`
+    WritingChunk { chunk: usize, chunks: usize },
+}
+
+/// Gets told what a download or serializer is doing. Every method does
+/// nothing by default, implement the ones you need. Calls come from the
+/// task running the download or the thread writing the archive, keep them
+/// short
+pub trait ProgressObserver: Send + Sync {
+    /// a fetch of `sha1` was started
+    fn on_resource_started(&self, _sha1: [u8; 20]) {}
+    /// `sha1` was fetched, `size` bytes of it. 0 when it was in memory
+    /// already, as part of the same level
+    fn on_resource_completed(&self, _sha1: [u8; 20], _size: usize) {}
+    /// `sha1` couldn't be fetched. Whether that fails the level depends on
+    /// the resource, an alternate may be tried next
+    fn on_resource_failed(&self, _sha1: [u8; 20], _error: &str) {}
+    fn on_stage_changed(&self, _stage: Stage) {}
+}
+
+/// Ignores everything
+pub struct Silent;
+
+impl ProgressObserver for Silent {}
+
+/// An observer for callers that don't show progress
+pub fn silent() -> Arc<dyn ProgressObserver> {
+    Arc::new(Silent)
+}
@@ -0,0 +1,189 @@
+// src/repack.rs
+
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Result, anyhow, bail};
+use sha1::{Digest, Sha1};
+use walkdir::WalkDir;
+use zip::write::FileOptions;
+use zip::{CompressionMethod, DateTime, ZipArchive, ZipWriter};
+
+use crate::resource_dl::{zip_entry_name, zip_location};
+
+/// Where a copy of a blob can be read from, tried in order until one hashes right
+enum BlobSource {
+    /// entry `index` of the existing zip
+    Zip(usize),
+    Loose(PathBuf),
+}
+
+#[derive(Debug, Default)]
+pub struct RepackReport {
+    /// blobs written to the new zip
+    pub written: usize,
+    pub from_zip: usize,
+    pub from_loose: usize,
+    /// copies that were read but didn't match their hash
+    pub bad_hash: usize,
+    /// copies that couldn't be read at all, including a damaged zip entry
+    pub unreadable: usize,
+    /// hashes with no good copy anywhere, left out of the new zip
+    pub lost: Vec<String>,
+}
+
+/// Expand a 1 or 2 hex digit `prefix` to the 2 digit prefixes of the zips it covers
+pub fn zip_prefixes(prefix: &str) -> Result<Vec<String>> {
+    let prefix = prefix.to_ascii_lowercase();
+    if prefix.is_empty() || prefix.len() > 2 || !prefix.chars().all(|c| c.is_ascii_hexdigit()) {
+        bail!("prefix must be 1 or 2 hex digits, got `{}`", prefix);
+    }
+    if prefix.len() == 2 {
+        return Ok(vec![prefix]);
+    }
+    Ok((0..16).map(|i| format!("{}{:x}", prefix, i)).collect())
+}
+
+fn is_sha1_hex(name: &str) -> bool {
+    name.len() == 40 && name.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+}
+
+/// Loose blobs below `dirs` whose file name is their SHA1, keyed by that SHA1
+pub fn find_loose_blobs(dirs: &[PathBuf], prefixes: &[String]) -> BTreeMap<String, Vec<PathBuf>> {
+    let mut blobs: BTreeMap<String, Vec<PathBuf>> = BTreeMap::new();
+    for dir in dirs {
+        for entry in WalkDir::new(dir).into_iter().filter_map(|e| e.ok()) {
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let name = entry.file_name().to_string_lossy().to_ascii_lowercase();
+            if is_sha1_hex(&name) && prefixes.iter().any(|p| name.starts_with(p.as_str())) {
+                blobs.entry(name).or_default().push(entry.into_path());
+            }
+        }
+    }
+    blobs
+}
+
+/// Read every copy of the blob until one hashes to `hex`
+fn read_verified(
+    hex: &str,
+    sources: &[BlobSource],
+    archive: &mut Option<ZipArchive<File>>,
+    report: &mut RepackReport,
+) -> Option<(Vec<u8>, bool)> {
+    for source in sources {
+        let (data, from_zip) = match source {
+            BlobSource::Zip(index) => {
+                let Some(archive) = archive.as_mut() else {
+                    continue;
+                };
+                let mut buf = Vec::new();
+                match archive
+                    .by_index(*index)
+                    .map_err(anyhow::Error::from)
+                    .and_then(|mut f| Ok(f.read_to_end(&mut buf)?))
+                {
+                    Ok(_) => (buf, true),
+                    Err(_) => {
+                        report.unreadable += 1;
+                        continue;
+                    }
+                }
+            }
+            BlobSource::Loose(path) => match fs::read(path) {
+                Ok(buf) => (buf, false),
+                Err(_) => {
+                    report.unreadable += 1;
+                    continue;
+                }
+            },
+        };
+        if hex::encode(Sha1::digest(&data)) == hex {
+            return Some((data, from_zip));
+        }
+        report.bad_hash += 1;
+    }
+    None
+}
+
+/// Rebuild `dry<prefix>.zip` under `output_root` from the existing zip under
+/// `archive_root` (if readable) and `loose` blobs. Every blob is re-hashed,
+/// entries are sorted and timestamps fixed, so the same blobs always give a
+/// byte-identical zip.
+pub fn repack_zip(
+    prefix: &str,
+    archive_root: &Path,
+    loose: &BTreeMap<String, Vec<PathBuf>>,
+    output_root: &Path,
+) -> Result<RepackReport> {
+    let relative = zip_location(prefix);
+    let mut report = RepackReport::default();
+
+    let mut sources: BTreeMap<String, Vec<BlobSource>> = BTreeMap::new();
+    let mut archive = File::open(archive_root.join(&relative))
+        .ok()
+        .and_then(|f| ZipArchive::new(f).ok());
+    if let Some(archive) = archive.as_mut() {
+        for i in 0..archive.len() {
+            let Ok(entry) = archive.by_index_raw(i) else {
+                report.unreadable += 1;
+                continue;
+            };
+            let hex = entry
+                .name()
+                .rsplit('/')
+                .next()
+                .unwrap_or("")
+                .to_ascii_lowercase();
+            if is_sha1_hex(&hex) && hex.starts_with(prefix) {
+                sources.entry(hex).or_default().push(BlobSource::Zip(i));
+            }
+        }
+    }
+    for (hex, paths) in loose.range(prefix.to_string()..) {
+        if !hex.starts_with(prefix) {
+            break;
+        }
+        let entry = sources.entry(hex.clone()).or_default();
+        entry.extend(paths.iter().cloned().map(BlobSource::Loose));
+    }
+    if sources.is_empty() {
+        bail!("no blobs found for prefix {}", prefix);
+    }
+
+    let out_path = output_root.join(&relative);
+    let out_dir = out_path
+        .parent()
+        .ok_or_else(|| anyhow!("{} has no parent directory", out_path.display()))?;
+    fs::create_dir_all(out_dir)?;
+    let tmp_path = out_path.with_extension(format!("zip.{}.tmp", std::process::id()));
+
+    let options = FileOptions::default()
+        .compression_method(CompressionMethod::Deflated)
+        .last_modified_time(DateTime::default())
+        .unix_permissions(0o644);
+    let mut writer = ZipWriter::new(File::create(&tmp_path)?);
+    for (hex, blob_sources) in &sources {
+        let Some((data, from_zip)) = read_verified(hex, blob_sources, &mut archive, &mut report)
+        else {
+            report.lost.push(hex.clone());
+            continue;
+        };
+        writer.start_file(zip_entry_name(hex), options)?;
+        writer.write_all(&data)?;
+        report.written += 1;
+        match from_zip {
+            true => report.from_zip += 1,
+            false => report.from_loose += 1,
+        }
+    }
+    writer.finish()?;
+
+    // the source zip may be the one we replace
+    drop(archive);
+    fs::rename(&tmp_path, &out_path)?;
+    Ok(report)
+}
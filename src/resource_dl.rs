@@ -1,223 +1,1112 @@
 // src/resource_dl.rs
 
 use anyhow::{Result, anyhow};
-use dashmap::DashMap;
+use dashmap::{DashMap, DashSet};
 use sha1::{Digest, Sha1};
 use std::{
     collections::{BTreeMap, BTreeSet},
     fs::{self, File},
+    io::{Read, Seek, SeekFrom, Write},
     path::{Path, PathBuf},
-    sync::{Arc, Mutex as StdMutex},
-    time::Instant,
-};
-use tokio::{
-    sync::{Mutex as AsyncMutex, Semaphore},
-    task::{JoinSet, spawn_blocking},
+    sync::{Arc, Mutex as StdMutex, OnceLock, atomic::{AtomicU64, Ordering}},
+    time::{Duration, Instant, SystemTime},
 };
+use tokio::{sync::Semaphore, task::JoinSet};
+use serde::Deserialize;
 use zip::ZipArchive;
-use crate::resource_parse::{ResrcData, ResrcDescriptor, ResrcMethod};
+use crate::alternates::{self, AlternateTable};
+use crate::archive_layout::{self, ArchiveLayout, Location};
+use crate::closure_cache::{Closure, ClosureCache};
+use crate::fetch_queue::{Batches, Frontier};
+use crate::metrics::Counter;
+use crate::messages::Msg;
+use crate::progress::{self, ProgressObserver, Stage};
+use crate::{budget, extract_pool, metrics};
+use crate::zip_index::{self, PartsReader, ZipIndex, ZipParts};
+use crate::resource_parse::{ResrcData, ResrcDependency, ResrcDescriptor, ResrcMethod};
 
 pub struct DownloadResult {
     pub resources: BTreeMap<[u8; 20], Vec<u8>>,
     pub success_count: usize,
     pub error_count: usize,
+    /// bulk resources that couldn't be fetched, with why, only with `partial_ok`
+    pub missing: Vec<([u8; 20], String)>,
+    /// hashes that changed, old → new: missing resources swapped for an
+    /// alternate and the resources rewritten to point at it
+    pub replaced: BTreeMap<[u8; 20], [u8; 20]>,
+}
+
+impl DownloadResult {
+    /// What `hash` is called in `resources`, a different hash if it was replaced
+    pub fn hash_of(&self, hash: [u8; 20]) -> [u8; 20] {
+        self.replaced.get(&hash).copied().unwrap_or(hash)
+    }
 }
 
+/// Download slots and open ZIPs, shared by every download started with the
+/// same pool so several levels in flight stay within one concurrency budget
 #[derive(Clone)]
-struct Downloader {
-    seen: Arc<AsyncMutex<BTreeSet<[u8; 20]>>>,
-    cache: Arc<AsyncMutex<BTreeMap<[u8; 20], Vec<u8>>>>,
+pub struct DownloadPool {
     sem: Arc<Semaphore>,
-    zip_pool: Arc<DashMap<PathBuf, StdMutex<ZipArchive<File>>>>,
-    cache_dir: PathBuf,
+    zip_pool: Arc<DashMap<PathBuf, Arc<StdMutex<OpenZip>>>>,
+    /// open tars of a consolidated archive
+    tar_pool: Arc<DashMap<PathBuf, Arc<StdMutex<File>>>>,
+    /// ZIPs that couldn't be opened and why, they aren't tried again
+    failed_zips: Arc<DashMap<PathBuf, String>>,
+    max_parallel: usize,
+    prefix: String,
+    observer: Arc<dyn ProgressObserver>,
+    /// the disk cache is only read, see `read_only`
+    read_only: bool,
 }
 
-impl Downloader {
-    /// Build a new Downloader.
-    pub fn new(max_parallel: usize, cache_dir: PathBuf) -> Result<Self> {
-        fs::create_dir_all(&cache_dir)
-            .map_err(|e| anyhow!("couldn't create cache dir `{}`: {}", cache_dir.display(), e))?;
-        Ok(Self {
-            seen: Arc::new(AsyncMutex::new(BTreeSet::new())),
-            cache: Arc::new(AsyncMutex::new(BTreeMap::new())),
+impl DownloadPool {
+    pub fn new(max_parallel: usize) -> Self {
+        Self {
             sem: Arc::new(Semaphore::new(max_parallel)),
             zip_pool: Arc::new(DashMap::new()),
+            tar_pool: Arc::new(DashMap::new()),
+            failed_zips: Arc::new(DashMap::new()),
+            max_parallel,
+            prefix: String::new(),
+            observer: progress::silent(),
+            read_only: false,
+        }
+    }
+
+    /// Same budget, but progress lines are prefixed with `prefix` so
+    /// interleaved output of concurrent levels can be told apart
+    pub fn labeled(&self, prefix: String) -> Self {
+        Self { prefix, ..self.clone() }
+    }
+
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// Same budget, with the progress of its downloads reported to `observer`
+    pub fn observed(&self, observer: Arc<dyn ProgressObserver>) -> Self {
+        Self { observer, ..self.clone() }
+    }
+
+    /// Same budget, but its downloads leave the disk cache as it is: what's
+    /// read from the archive isn't copied there and no closure is recorded.
+    /// For commands that read many levels once and keep nothing
+    pub fn read_only(&self) -> Self {
+        Self { read_only: true, ..self.clone() }
+    }
+
+    fn zip_failed(&self, zip_path: &Path) -> bool {
+        self.failed_zips.contains_key(zip_path)
+    }
+
+    /// Open `zip` on an I/O thread before its first fetch, so reading its
+    /// index overlaps with the batch before it. Failing is left to the fetch
+    fn open_ahead(&self, zip: PathBuf) {
+        if self.zip_pool.contains_key(&zip) || self.zip_failed(&zip) {
+            return;
+        }
+        let zips = self.zip_pool.clone();
+        tokio::spawn(extract_pool::shared().run(move || {
+            let _ = zips.entry(zip.clone())
+                .or_try_insert_with(|| OpenZip::open(&zip).map(|z| Arc::new(StdMutex::new(z))));
+        }));
+    }
+}
+
+/// An open archive ZIP and the index of its entries, so entries are read
+/// without inflating them on the I/O threads
+struct OpenZip {
+    index: Arc<ZipIndex>,
+    file: PartsReader,
+    parts: ZipParts,
+    /// for the entries only the zip crate can read, opened the first time
+    archive: Option<ZipArchive<PartsReader>>,
+    path: PathBuf,
+}
+
+impl OpenZip {
+    fn open(path: &Path) -> Result<Self> {
+        let index = zip_index::index(path, &cache_dir()?.join(ZIP_INDEX_DIR))?;
+        let parts = ZipParts::find(path)?;
+        Ok(Self {
+            index,
+            file: parts.open()?,
+            parts,
+            archive: None,
+            path: path.to_path_buf(),
+        })
+    }
+
+    /// The entry's bytes as stored, and whether they still need inflating.
+    /// Encrypted entries and anything but stored or deflated entries are
+    /// decoded right away
+    fn read_raw(&mut self, name: &str) -> Result<(Vec<u8>, bool)> {
+        let entry = self.index.get(name).ok_or_else(|| anyhow!("not in the zip"))?;
+        if entry.encrypted && archive_password().is_none() {
+            return Err(anyhow!("it's encrypted, set archive_password in config.yml"));
+        }
+        let deflated = match (entry.method, entry.encrypted) {
+            (0, false) => false,
+            (8, false) => true,
+            // the zip crate reads the pieces of a ZIP put together, but
+            // knows nothing of parts with their own offsets
+            _ if self.parts.is_spanned() => {
+                return match entry.encrypted {
+                    true => Err(anyhow!("encrypted entries of ZIPs split into .z01 parts aren't supported, join the parts into one ZIP first")),
+                    false => Err(anyhow!("compression method {} isn't supported in ZIPs split into .z01 parts", entry.method)),
+                };
+            }
+            _ => {
+                let archive = match &mut self.archive {
+                    Some(archive) => archive,
+                    None => self.archive.insert(ZipArchive::new(self.parts.open()?)?),
+                };
+                let mut entry = match archive_password() {
+                    Some(password) if entry.encrypted => archive
+                        .by_name_decrypt(name, password.as_bytes())?
+                        .map_err(|_| anyhow!("archive_password isn't the password of {}", self.path.display()))?,
+                    _ => archive.by_name(name)?,
+                };
+                let mut buf = Vec::with_capacity(entry.size() as usize);
+                entry.read_to_end(&mut buf)?;
+                return Ok((buf, false));
+            }
+        };
+        let start = zip_index::data_start(&mut self.file, entry)?;
+        let mut buf = vec![0u8; entry.compressed_size as usize];
+        self.file.seek(SeekFrom::Start(start))?;
+        self.file.read_exact(&mut buf)?;
+        Ok((buf, deflated))
+    }
+}
+
+/// Check a fetched blob against its SHA1 and list its dependencies, this
+/// is the CPU side of a fetch
+fn verify_and_parse(sha1: [u8; 20], buf: &[u8]) -> Result<Vec<Dependency>> {
+    let mut hasher = Sha1::new(); hasher.update(buf);
+    if hasher.finalize().as_slice() != sha1 {
+        return Err(anyhow!("SHA1 mismatch for {}", hex::encode(sha1)));
+    }
+    let meta = ResrcData::new(buf, false)?;
+    if let ResrcMethod::Binary { dependencies, .. } = meta.method {
+        Ok(sha1_dependencies(dependencies))
+    } else {
+        Ok(Vec::new())
+    }
+}
+
+/// Cap for parallel reads of a local archive, the CPU count
+pub fn parallel_cap() -> usize {
+    std::thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+/// Clamp `requested` parallel downloads to the CPU count, unless `forced`
+pub fn clamp_parallel(requested: usize, forced: bool) -> Result<usize> {
+    if requested == 0 {
+        return Err(anyhow!("max_parallel_downloads cannot be zero"));
+    }
+    let cap = parallel_cap();
+    if requested > cap && !forced {
+        eprintln!("{}", Msg::Warning(&Msg::ParallelCapped(cap)));
+        return Ok(cap);
+    }
+    Ok(requested)
+}
+
+/// dependency types that are leaves of a level (textures, meshes, animations),
+/// they're fetched after everything the slotlist and the level's structure need
+const BULK_RESRC_TYPES: [u32; 3] = [1, 2, 5];
+
+/// A SHA1 dependency and the resource type it was declared with
+type Dependency = ([u8; 20], u32);
+
+fn sha1_dependencies(dependencies: Vec<ResrcDependency>) -> Vec<Dependency> {
+    dependencies.into_iter()
+        .filter_map(|d| if let ResrcDescriptor::Sha1(s) = d.desc { Some((s, d.resrc_type)) } else { None })
+        .collect()
+}
+
+/// `seen` and `cache` are touched by every task, they're sharded so tasks
+/// don't queue up behind one lock at higher parallelism
+struct Downloader {
+    seen: DashSet<[u8; 20]>,
+    cache: DashMap<[u8; 20], Vec<u8>>,
+    /// resources not fetched because their ZIP failed to open, per ZIP
+    skipped: DashMap<PathBuf, usize>,
+    /// the container read last and how often that changed
+    last_container: StdMutex<(Option<PathBuf>, usize)>,
+    pool: DownloadPool,
+    /// None with the disk cache off, blobs are then only kept in memory
+    cache_dir: Option<PathBuf>,
+    ingest_dir: Option<PathBuf>,
+}
+
+impl Downloader {
+    /// Build a new Downloader, caching fetched blobs in `cache_dir` if given
+    pub fn new(pool: DownloadPool, cache_dir: Option<PathBuf>) -> Result<Self> {
+        if let Some(cache_dir) = &cache_dir {
+            fs::create_dir_all(cache_dir)
+                .map_err(|e| anyhow!("couldn't create cache dir `{}`: {}", cache_dir.display(), e))?;
+        }
+        Ok(Self {
+            seen: DashSet::new(),
+            cache: DashMap::new(),
+            skipped: DashMap::new(),
+            last_container: StdMutex::new((None, 0)),
+            pool,
             cache_dir,
+            ingest_dir: INGEST_DIR.get().cloned(),
         })
     }
 
+    /// Take a blob from a local file instead of the archive, counting it
+    /// in `hits` and `bytes`
+    async fn use_local_copy(
+        &self,
+        sha1: [u8; 20],
+        path: &Path,
+        (hits, bytes): (&Counter, &Counter),
+    ) -> Result<Vec<Dependency>> {
+        let buf = fs::read(path)?;
+        self.use_blob(sha1, buf, &path.display().to_string(), (hits, bytes)).await
+    }
+
+    /// Take a blob the caller has at hand, `source` says where it's from if
+    /// it doesn't check out
+    async fn use_blob(
+        &self,
+        sha1: [u8; 20],
+        buf: Vec<u8>,
+        source: &str,
+        (hits, bytes): (&Counter, &Counter),
+    ) -> Result<Vec<Dependency>> {
+        let (buf, deps) = extract_pool::cpu().run(move || {
+            let deps = metrics::timed(&metrics::INFLATE_TIME, || verify_and_parse(sha1, &buf));
+            (buf, deps)
+        }).await?;
+        let deps = deps.map_err(|e| anyhow!("{} ({})", e, source))?;
+        if !self.seen.insert(sha1) {
+            self.count_memory_hit(buf.len());
+            return Ok(vec![]);
+        }
+        hits.inc();
+        bytes.add(buf.len() as u64);
+        self.cache.insert(sha1, buf);
+        metrics::RESOURCES_SERVED.inc();
+        Ok(deps)
+    }
+
+    /// A resource asked for again while it's in memory already
+    fn count_memory_hit(&self, len: usize) {
+        metrics::MEMORY_HITS.inc();
+        metrics::MEMORY_BYTES.add(len as u64);
+    }
+
     /// Fetch one SHA1, using on‐disk cache, in‐memory cache, or opening the right ZIP.
     pub async fn fetch_one_cached(
         self: Arc<Self>,
         sha1: [u8; 20],
         archive_root: PathBuf,
-    ) -> Result<Vec<[u8; 20]>> {
+    ) -> Result<Vec<Dependency>> {
         // hex string for logging & cache filename
         let hex = hex::encode(sha1);
-        let cache_file = self.cache_dir.join(&hex);
-
-        // 1) on‐disk cache hit?
-        if cache_file.exists() {
-            eprintln!("▶ [cache hit] {}", hex);
-            let buf = fs::read(&cache_file)?;
-            let mut hasher = Sha1::new(); hasher.update(&buf);
-            if hasher.finalize().as_slice() != sha1 {
-                return Err(anyhow!("SHA1 mismatch on cache {}", hex));
-            }
-            {
-                let mut seen = self.seen.lock().await;
-                if !seen.insert(sha1) {
-                    return Ok(vec![]);
+        let cache_file = self.cache_dir.as_ref().map(|dir| dir.join(&hex));
+
+        // 0) fetched before by this downloader, its dependencies went out then
+        if let Some(buf) = self.cache.get(&sha1) {
+            self.count_memory_hit(buf.len());
+            return Ok(vec![]);
+        }
+
+        // 0b) in the save being rebuilt? Those are there with or without the disk cache
+        if let Some(blob) = SAVE_BLOBS.get().and_then(|blobs| blobs.get(&sha1)) {
+            eprintln!("{}▶ [from save] {}", self.pool.prefix, hex);
+            metrics::CACHE_HITS.inc();
+            return self.use_blob(sha1, blob.clone(), "the save", (&metrics::INGESTED_HITS, &metrics::INGESTED_BYTES)).await;
+        }
+
+        // 1) on‐disk cache hit? One that doesn't check out is fetched again
+        if let Some(cache_file) = cache_file.filter(|file| file.exists()) {
+            eprintln!("{}▶ [cache hit] {}", self.pool.prefix, hex);
+            match self.use_local_copy(sha1, &cache_file, (&metrics::DISK_CACHE_HITS, &metrics::DISK_CACHE_BYTES)).await {
+                Ok(deps) => {
+                    metrics::CACHE_HITS.inc();
+                    return Ok(deps);
+                }
+                Err(e) => {
+                    eprintln!("{}⚠️ {}", self.pool.prefix, Msg::CachedDamaged { hash: &hex, error: &e.to_string() });
+                    let _ = fs::remove_file(&cache_file);
                 }
             }
-            {
-                let mut mem = self.cache.lock().await;
-                mem.insert(sha1, buf.clone());
+        }
+
+        // 1b) blob ingested by the user?
+        if let Some(ingest_dir) = &self.ingest_dir {
+            let ingested = ingest_dir.join(zip_entry_name(&hex));
+            if ingested.exists() {
+                eprintln!("{}▶ [ingested] {}", self.pool.prefix, hex);
+                metrics::CACHE_HITS.inc();
+                return self.use_local_copy(sha1, &ingested, (&metrics::INGESTED_HITS, &metrics::INGESTED_BYTES)).await;
             }
-            let meta = ResrcData::new(&buf, false)?;
-            if let ResrcMethod::Binary { dependencies, .. } = meta.method {
-                return Ok(dependencies.into_iter()
-                    .filter_map(|d| if let ResrcDescriptor::Sha1(s) = d.desc { Some(s) } else { None })
-                    .collect());
-            } else {
-                return Ok(vec![]);
-            }
-        }
-
-        // 2) otherwise: derive the ZIP path & entry
-        let first       = u8::from_str_radix(&hex[0..2], 16).unwrap();
-        let range_start = first & 0xF0;
-        let range_end   = range_start | 0x0F;
-        let res_folder  = format!("LBP online levels 2023 (res {:02x}-{:02x})", range_start, range_end);
-        let subfolder   = format!("dry23r{}", &hex[0..1]);
-        let zipname     = format!("dry{}.zip", &hex[0..2]);
-        let zip_path    = archive_root.join(&res_folder).join(&subfolder).join(&zipname);
-        let entry_name  = format!("{}/{}/{}", &hex[0..2], &hex[2..4], hex);
-
-        eprintln!("▶ Fetching resources from {}", zipname);
-        let _permit = self.sem.acquire().await?;
-
-        // clone hex so we don't move the original
-        let hex_for_spawn = hex.clone();
-        let (buf, deps) = spawn_blocking({
-            let pool = self.zip_pool.clone();
-            move || -> Result<(Vec<u8>, Vec<[u8; 20]>)> {
-                // open or reuse the zip
-                if pool.get(&zip_path).is_none() {
-                    let f = File::open(&zip_path)
-                        .map_err(|e| anyhow!("couldn't open {}: {}", zip_path.display(), e))?;
-                    let arch = ZipArchive::new(f)
-                        .map_err(|e| anyhow!("{} not a zip: {}", zip_path.display(), e))?;
-                    pool.insert(zip_path.clone(), StdMutex::new(arch));
-                }
-                let mutex = pool.get(&zip_path).unwrap();
-                let mut archive = mutex.lock()
-                    .map_err(|e| anyhow!("mutex poisoned for {}: {}", zip_path.display(), e))?;
-
-                // extract entry
-                let mut zf = archive
-                    .by_name(&entry_name)
-                    .map_err(|e| anyhow!("{} missing {}: {}", zip_path.display(), entry_name, e))?;
-                let mut buf = Vec::with_capacity(zf.size() as usize);
-                std::io::copy(&mut zf, &mut buf)?;
-
-                // verify & parse deps
-                let mut hasher = Sha1::new(); hasher.update(&buf);
-                if hasher.finalize().as_slice() != sha1 {
-                    return Err(anyhow!("SHA1 mismatch for {}", hex_for_spawn));
-                }
-                let meta = ResrcData::new(&buf, false)?;
-                let deps = if let ResrcMethod::Binary { dependencies, .. } = meta.method {
-                    dependencies.into_iter()
-                        .filter_map(|d| if let ResrcDescriptor::Sha1(s) = d.desc { Some(s) } else { None })
-                        .collect()
-                } else {
-                    Vec::new()
+        }
+
+        // 2) otherwise: find it in the archive, however that's packaged
+        metrics::CACHE_MISSES.inc();
+        let layout = archive_layout(&archive_root)?;
+        let location = layout.locate(&hex)
+            .ok_or_else(|| anyhow!("{} isn't in any of the archive's tars", hex))?;
+        let container = location.container().to_path_buf();
+        let container_name = container.file_name().unwrap_or_default().to_string_lossy().into_owned();
+
+        // a missing ZIP is reported once at the end, not for every resource in it
+        if let Some(reason) = self.pool.failed_zips.get(&container) {
+            *self.skipped.entry(container.clone()).or_insert(0) += 1;
+            return Err(anyhow!("{} is unusable: {}", container_name, *reason));
+        }
+
+        eprintln!("{}▶ Fetching resources from {}", self.pool.prefix, container_name);
+        let permit = self.pool.sem.acquire().await?;
+        if let Ok(mut last) = self.last_container.lock()
+            && last.0.as_ref() != Some(&container)
+        {
+            *last = (Some(container.clone()), last.1 + 1);
+            metrics::CONTAINER_SWITCHES.inc();
+        }
+
+        // reading the entry is all the I/O threads do, inflating and
+        // checking it happens on the CPU pool so neither waits on the other
+        let raw = extract_pool::shared().run({
+            let zips = self.pool.zip_pool.clone();
+            let tars = self.pool.tar_pool.clone();
+            let failed_zips = self.pool.failed_zips.clone();
+            move || metrics::timed(&metrics::READ_TIME, || -> Result<(Vec<u8>, bool)> {
+                let container = location.container().to_path_buf();
+                let mark_failed = |e: &anyhow::Error| {
+                    failed_zips.entry(container.clone()).or_insert_with(|| {
+                        metrics::OPEN_FAILURES.inc();
+                        e.to_string()
+                    });
                 };
-                Ok((buf, deps))
-            }
+                match location {
+                    Location::Zip { path, entry } => {
+                        // open or reuse the zip, the map entry is only held while
+                        // opening so extracting doesn't block other zips in its shard
+                        let mutex = zips
+                            .entry(path.clone())
+                            .or_try_insert_with(|| OpenZip::open(&path).map(|z| Arc::new(StdMutex::new(z))))
+                            .inspect_err(mark_failed)?
+                            .clone();
+                        let mut zip = mutex.lock()
+                            .map_err(|e| anyhow!("mutex poisoned for {}: {}", path.display(), e))?;
+                        zip.read_raw(&entry)
+                            .map_err(|e| anyhow!("couldn't read {} from {}: {}", entry, path.display(), e))
+                    }
+                    Location::Tar { path, offset, size } => {
+                        let mutex = tars
+                            .entry(path.clone())
+                            .or_try_insert_with(|| {
+                                File::open(&path)
+                                    .map(|f| Arc::new(StdMutex::new(f)))
+                                    .map_err(|e| anyhow!("couldn't open {}: {}", path.display(), e))
+                            })
+                            .inspect_err(mark_failed)?
+                            .clone();
+                        let mut tar = mutex.lock()
+                            .map_err(|e| anyhow!("mutex poisoned for {}: {}", path.display(), e))?;
+                        let mut buf = vec![0u8; size as usize];
+                        tar.seek(SeekFrom::Start(offset))?;
+                        tar.read_exact(&mut buf)
+                            .map_err(|e| anyhow!("couldn't read {} at {}: {}", path.display(), offset, e))?;
+                        Ok((buf, false))
+                    }
+                }
+            })
         })
+        .await?
+        .inspect_err(|_| {
+            if self.pool.zip_failed(&container) {
+                *self.skipped.entry(container.clone()).or_insert(0) += 1;
+            }
+        });
+        drop(permit);
+        let (raw, deflated) = raw?;
+        metrics::BYTES_READ.add(raw.len() as u64);
+
+        let (buf, deps) = extract_pool::cpu().run(move || metrics::timed(&metrics::INFLATE_TIME, || -> Result<(Vec<u8>, Vec<Dependency>)> {
+            let buf = match deflated {
+                true => miniz_oxide::inflate::decompress_to_vec(&raw)
+                    .map_err(|e| anyhow!("couldn't inflate {}: {:?}", hex::encode(sha1), e))?,
+                false => raw,
+            };
+            let deps = verify_and_parse(sha1, &buf)?;
+            Ok((buf, deps))
+        }))
         .await??;
 
         // 3) cache to disk
-        fs::write(&cache_file, &buf)?;
+        if let Some(cache_dir) = self.cache_dir.as_ref().filter(|_| !self.pool.read_only) {
+            write_cache_file(cache_dir, &hex, &buf)?;
+        }
 
         // 4) in‐memory record & return deps
-        {
-            let mut seen = self.seen.lock().await;
-            if !seen.insert(sha1) {
-                return Ok(vec![]);
-            }
+        if !self.seen.insert(sha1) {
+            self.count_memory_hit(buf.len());
+            return Ok(vec![]);
         }
-        {
-            let mut mem = self.cache.lock().await;
-            mem.insert(sha1, buf.clone());
-        }
-        eprintln!("\tgot file: {}", hex);
+        metrics::EXTRACTIONS.inc();
+        metrics::EXTRACTED_BYTES.add(buf.len() as u64);
+        self.cache.insert(sha1, buf);
+        metrics::RESOURCES_SERVED.inc();
+        eprintln!("{}\tgot file: {}", self.pool.prefix, hex);
 
         Ok(deps)
     }
 }
 
-/// Public entrypoint
+/// How the archive at `archive_root` is packaged, detected once per run.
+/// Tar indexes are kept in the cache dir
+pub fn archive_layout(archive_root: &Path) -> Result<Arc<ArchiveLayout>> {
+    archive_layout::layout(archive_root, &cache_dir()?.join(TAR_INDEX_DIR))
+}
+
+/// folder in the resource cache holding the indexes of archive ZIPs
+const ZIP_INDEX_DIR: &str = "zip_index";
+
+/// folder in the resource cache holding the indexes of consolidated tars
+const TAR_INDEX_DIR: &str = "tar_index";
+
+/// Path of the ZIP holding hashes starting with the 2 hex digits `prefix`,
+/// relative to the archive root
+pub fn zip_location(prefix: &str) -> PathBuf {
+    let first       = u8::from_str_radix(prefix, 16).unwrap();
+    let range_start = first & 0xF0;
+    let range_end   = range_start | 0x0F;
+    let res_folder  = format!("LBP online levels 2023 (res {:02x}-{:02x})", range_start, range_end);
+    let subfolder   = format!("dry23r{}", &prefix[0..1]);
+    let zipname     = format!("dry{}.zip", prefix);
+    PathBuf::from(res_folder).join(subfolder).join(zipname)
+}
+
+/// Name of a blob inside its ZIP, `ab/cd/abcd…`
+pub fn zip_entry_name(hex: &str) -> String {
+    format!("{}/{}/{}", &hex[0..2], &hex[2..4], hex)
+}
+
+static CACHE_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Set where downloaded resources are cached, call once at startup
+pub fn set_cache_dir(dir: PathBuf) {
+    let _ = CACHE_DIR.set(dir);
+}
+
+static DISK_CACHE: OnceLock<bool> = OnceLock::new();
+
+/// Whether fetched blobs are read from and written to the cache dir, on
+/// unless set. The indexes and `closures.db` stay in the cache dir either
+/// way. Call once at startup
+pub fn set_disk_cache(enabled: bool) {
+    let _ = DISK_CACHE.set(enabled);
+}
+
+fn disk_cache() -> bool {
+    DISK_CACHE.get().copied().unwrap_or(true)
+}
+
+/// the cache dir for fetched blobs, None with the disk cache off
+fn blob_cache_dir() -> Result<Option<PathBuf>> {
+    match disk_cache() {
+        true => cache_dir().map(Some),
+        false => Ok(None),
+    }
+}
+
+/// In what order a level's resources are fetched, `download_order` in
+/// config.yml. Either way the rootLevel, icon and the level's structure come
+/// before the bulk data
+#[derive(Debug, Default, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DownloadOrder {
+    /// in the order they're found, whatever finished first
+    #[default]
+    Discovered,
+    /// a depth of the dependency tree at a time, each started with its
+    /// size known so the progress of it is a real percentage
+    Breadth,
+    /// the newest found first, down one branch before the next
+    Depth,
+}
+
+impl DownloadOrder {
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            DownloadOrder::Discovered => "discovered",
+            DownloadOrder::Breadth => "breadth",
+            DownloadOrder::Depth => "depth",
+        }
+    }
+}
+
+static DOWNLOAD_ORDER: OnceLock<DownloadOrder> = OnceLock::new();
+
+/// Set the order resources are fetched in, call once at startup
+pub fn set_download_order(order: DownloadOrder) {
+    let _ = DOWNLOAD_ORDER.set(order);
+}
+
+fn download_order() -> DownloadOrder {
+    DOWNLOAD_ORDER.get().copied().unwrap_or_default()
+}
+
+static ARCHIVE_PASSWORD: OnceLock<String> = OnceLock::new();
+
+/// Set the password encrypted archive ZIPs are opened with, call once at startup
+pub fn set_archive_password(password: String) {
+    let _ = ARCHIVE_PASSWORD.set(password);
+}
+
+fn archive_password() -> Option<&'static str> {
+    ARCHIVE_PASSWORD.get().map(String::as_str)
+}
+
+static INGEST_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Set where blobs added with `ingest` live, they're used before the archive
+pub fn set_ingest_dir(dir: PathBuf) {
+    let _ = INGEST_DIR.set(dir);
+}
+
+static SAVE_BLOBS: OnceLock<Arc<BTreeMap<[u8; 20], Vec<u8>>>> = OnceLock::new();
+
+/// Set the resources of the save `rebuild-from-save` reads, they're used
+/// before the archive and counted as ingested
+pub fn set_save_blobs(blobs: Arc<BTreeMap<[u8; 20], Vec<u8>>>) {
+    let _ = SAVE_BLOBS.set(blobs);
+}
+
+fn cache_dir() -> Result<PathBuf> {
+    CACHE_DIR
+        .get()
+        .cloned()
+        .ok_or_else(|| anyhow!("resource cache directory wasn't set"))
+}
+
+/// numbers the temp files of cache writes, concurrent levels can fetch the same resource
+static CACHE_WRITE_SEQ: AtomicU64 = AtomicU64::new(0);
+
+/// Put a blob into the resource cache under its hash. It's written to a temp
+/// file and synced before it's renamed, so after a crash or power loss the
+/// cache has either all of the blob or nothing under that name, and nobody
+/// reading the cache meanwhile sees half of it
+pub fn write_cache_file(cache_dir: &Path, hex: &str, blob: &[u8]) -> Result<()> {
+    let seq = CACHE_WRITE_SEQ.fetch_add(1, Ordering::Relaxed);
+    let tmp_file = cache_dir.join(format!("{}.{}.{}.tmp", hex, std::process::id(), seq));
+    let written = File::create(&tmp_file).and_then(|mut file| {
+        file.write_all(blob)?;
+        file.sync_all()
+    });
+    if let Err(e) = written.and_then(|_| fs::rename(&tmp_file, cache_dir.join(hex))) {
+        let _ = fs::remove_file(&tmp_file);
+        return Err(anyhow!("couldn't write {} to the cache: {}", hex, e));
+    }
+    Ok(())
+}
+
+/// temp files older than this are left over from writes that never finished,
+/// younger ones may be another run's writes in progress
+const STALE_TEMP_AGE: Duration = Duration::from_secs(10 * 60);
+
+/// What `clean_cache` removed
+#[derive(Debug, Default)]
+pub struct CacheCleanup {
+    pub temp_files: usize,
+    pub empty_files: usize,
+}
+
+/// how often `clean_cache_if_due` looks through the cache
+const CACHE_CLEANUP_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// file in the cache dir whose mtime is when it was last cleaned up
+const CACHE_CLEANUP_MARKER: &str = "last_cleanup";
+
+/// `clean_cache` if the cache wasn't cleaned up in the last day, so a large
+/// cache isn't listed at every start. None if it's not due or there's no cache
+pub fn clean_cache_if_due(cache_dir: &Path) -> Result<Option<CacheCleanup>> {
+    if !cache_dir.is_dir() {
+        return Ok(None);
+    }
+    let marker = cache_dir.join(CACHE_CLEANUP_MARKER);
+    let due = fs::metadata(&marker).and_then(|meta| meta.modified()).ok()
+        .and_then(|cleaned| SystemTime::now().duration_since(cleaned).ok())
+        .is_none_or(|age| age > CACHE_CLEANUP_INTERVAL);
+    if !due {
+        return Ok(None);
+    }
+    let cleanup = clean_cache(cache_dir)?;
+    fs::write(&marker, b"")
+        .map_err(|e| anyhow!("couldn't write `{}`: {}", marker.display(), e))?;
+    Ok(Some(cleanup))
+}
+
+/// Remove what interrupted writes left in the resource cache: temp files and
+/// empty blobs, which no resource is. Damaged blobs that aren't empty are
+/// found and fetched again when they're used
+pub fn clean_cache(cache_dir: &Path) -> Result<CacheCleanup> {
+    let mut cleanup = CacheCleanup::default();
+    let entries = match fs::read_dir(cache_dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(cleanup),
+        Err(e) => return Err(anyhow!("couldn't read the cache dir `{}`: {}", cache_dir.display(), e)),
+    };
+    let now = SystemTime::now();
+    for entry in entries {
+        let entry = entry?;
+        let name = entry.file_name();
+        let Some(name) = name.to_str() else { continue };
+        let meta = entry.metadata()?;
+        if !meta.is_file() {
+            continue;
+        }
+        let stale_temp = name.ends_with(".tmp") && meta.modified()
+            .ok()
+            .and_then(|modified| now.duration_since(modified).ok())
+            .is_some_and(|age| age > STALE_TEMP_AGE);
+        let empty_blob = meta.len() == 0 && name.len() == 40 && name.bytes().all(|b| b.is_ascii_hexdigit());
+        if !stale_temp && !empty_blob {
+            continue;
+        }
+        fs::remove_file(entry.path())?;
+        match stale_temp {
+            true => cleanup.temp_files += 1,
+            false => cleanup.empty_files += 1,
+        }
+    }
+    Ok(cleanup)
+}
+
+/// where a blob is on disk already, in the cache or among the ingested blobs
+fn local_copy(hex: &str) -> Option<PathBuf> {
+    if let Some(cached) = blob_cache_dir().ok().flatten().map(|dir| dir.join(hex))
+        && cached.exists() {
+        return Some(cached);
+    }
+    let ingested = INGEST_DIR.get()?.join(zip_entry_name(hex));
+    ingested.exists().then_some(ingested)
+}
+
+static SEED_HASHES: OnceLock<BTreeSet<[u8; 20]>> = OnceLock::new();
+
+/// Set hashes every level fetch includes along with its own closure, for
+/// resources the level reaches in ways its dependencies don't record or
+/// replacements for missing ones. Call once at startup
+pub fn set_seed_hashes(hashes: BTreeSet<[u8; 20]>) {
+    let _ = SEED_HASHES.set(hashes);
+}
+
+fn seed_hashes() -> Option<&'static BTreeSet<[u8; 20]>> {
+    SEED_HASHES.get().filter(|seeds| !seeds.is_empty())
+}
+
+static ALTERNATES: OnceLock<AlternateTable> = OnceLock::new();
+
+/// Set the hashes that can stand in for each other, a missing resource is
+/// then swapped for one of its alternates. Call once at startup
+pub fn set_alternates(table: AlternateTable) {
+    let _ = ALTERNATES.set(table);
+}
+
+fn alternate_table() -> Option<&'static AlternateTable> {
+    ALTERNATES.get().filter(|table| !table.is_empty())
+}
+
+static CLOSURES: OnceLock<Option<ClosureCache>> = OnceLock::new();
+
+/// closures of levels fetched before, `None` if the cache can't be opened
+fn closure_cache() -> Option<&'static ClosureCache> {
+    CLOSURES.get_or_init(|| match cache_dir().and_then(|dir| ClosureCache::open(&dir)) {
+        Ok(cache) => Some(cache),
+        Err(e) => {
+            eprintln!("⚠️ {}", Msg::ClosureCacheUnavailable(&e.to_string()));
+            None
+        }
+    }).as_ref()
+}
+
+/// Every closure earlier fetches recorded, by rootLevel, none if there's no closure cache
+pub fn cached_closures() -> Result<BTreeMap<[u8; 20], Closure>> {
+    match closure_cache() {
+        Some(cache) => cache.by_root(),
+        None => Ok(BTreeMap::new()),
+    }
+}
+
+/// The closure an earlier run recorded for this root and icon, if every
+/// resource of it is still on disk. Otherwise the record is dropped.
+/// Never with seed hashes, the recorded closure doesn't have them, or with
+/// the disk cache off, which would drop every record
+pub fn cached_closure(root: [u8; 20], icon_sha1: Option<[u8; 20]>) -> Result<Option<Closure>> {
+    let Some(cache) = closure_cache().filter(|_| seed_hashes().is_none() && disk_cache()) else {
+        return Ok(None);
+    };
+    let Some(closure) = cache.get(root, icon_sha1)? else {
+        return Ok(None);
+    };
+    if closure.iter().all(|(hash, _)| local_copy(&hex::encode(hash)).is_some()) {
+        return Ok(Some(closure));
+    }
+    cache.forget(root, icon_sha1)?;
+    Ok(None)
+}
+
+/// Read a cached closure back from disk, `None` if a blob went missing or
+/// doesn't match its hash anymore
+async fn read_closure(closure: &Closure) -> Option<BTreeMap<[u8; 20], Vec<u8>>> {
+    let mut js = JoinSet::new();
+    for &(hash, _) in closure {
+        js.spawn(async move {
+            let buf = fs::read(local_copy(&hex::encode(hash))?).ok()?;
+            extract_pool::cpu()
+                .run(move || {
+                    let ok = metrics::timed(&metrics::INFLATE_TIME, || Sha1::digest(&buf).as_slice() == hash);
+                    ok.then_some((hash, buf))
+                })
+                .await
+                .ok()
+                .flatten()
+        });
+    }
+    let mut resources = BTreeMap::new();
+    while let Some(res) = js.join_next().await {
+        let (hash, buf) = res.ok()??;
+        resources.insert(hash, buf);
+    }
+    metrics::CACHE_HITS.add(resources.len() as u64);
+    metrics::DISK_CACHE_HITS.add(resources.len() as u64);
+    metrics::DISK_CACHE_BYTES.add(resources.values().map(|b| b.len() as u64).sum());
+    metrics::RESOURCES_SERVED.add(resources.len() as u64);
+    Some(resources)
+}
+
+/// dependency type of textures, which never have dependencies of their own
+const TEXTURE_RESRC_TYPE: u32 = 1;
+
+/// Size of a blob without reading it, from the disk cache, the tar index
+/// or its ZIP's central directory. `None` if only reading it would tell
+fn blob_size(layout: &ArchiveLayout, hash: [u8; 20]) -> Option<u64> {
+    let hex = hex::encode(hash);
+    if let Some(local) = local_copy(&hex) {
+        return fs::metadata(local).ok().map(|m| m.len());
+    }
+    if let Some(blob) = SAVE_BLOBS.get().and_then(|blobs| blobs.get(&hash)) {
+        return Some(blob.len() as u64);
+    }
+    match layout.locate(&hex)? {
+        Location::Tar { size, .. } => Some(size),
+        Location::Zip { path, entry } => {
+            let index = zip_index::index(&path, &cache_dir().ok()?.join(ZIP_INDEX_DIR)).ok()?;
+            index.get(&entry).map(|e| e.size)
+        }
+    }
+}
+
+/// The closure of a level as `bkp --dry-run` shows it, without fetching all
+/// of it: only what can have dependencies is read, textures are only sized
+/// with `blob_size`. Returns the closure and how many resources couldn't be
+/// found, a rootLevel that can't be fetched fails. Nothing is recorded in
+/// the closure cache, the textures weren't checked against their hash
+pub async fn closure_sizes(
+    root: [u8; 20],
+    icon_sha1: Option<[u8; 20]>,
+    archive_root: String,
+    max_parallel: usize,
+) -> Result<(Closure, usize)> {
+    let root_dir = PathBuf::from(&archive_root);
+    let dl = Arc::new(Downloader::new(DownloadPool::new(max_parallel), blob_cache_dir()?)?);
+    let layout = archive_layout(&root_dir)?;
+    let mut js = JoinSet::new();
+    let fetch = |js: &mut JoinSet<_>, hash: [u8; 20]| {
+        let (dl, root_dir) = (dl.clone(), root_dir.clone());
+        js.spawn(async move { (hash, dl.fetch_one_cached(hash, root_dir).await) });
+    };
+
+    let mut pending = BTreeSet::from([root]);
+    pending.extend(icon_sha1);
+    pending.extend(seed_hashes().into_iter().flatten());
+    for &hash in &pending {
+        fetch(&mut js, hash);
+    }
+    let mut closure = Closure::new();
+    let mut missing = 0;
+    while let Some(joined) = js.join_next().await {
+        let (hash, deps) = joined?;
+        let deps = match deps {
+            Ok(deps) => deps,
+            Err(e) if hash == root => return Err(e),
+            Err(e) => {
+                eprintln!("⚠️ {}", Msg::ResourceMissing { hash: &hex::encode(hash), error: &e.to_string() });
+                missing += 1;
+                continue;
+            }
+        };
+        if let Some((_, blob)) = dl.cache.remove(&hash) {
+            closure.push((hash, blob.len() as u64));
+        }
+        for (child, resrc_type) in deps {
+            if !pending.insert(child) {
+                continue;
+            }
+            if resrc_type == TEXTURE_RESRC_TYPE && let Some(size) = blob_size(&layout, child) {
+                closure.push((child, size));
+            } else {
+                fetch(&mut js, child);
+            }
+        }
+    }
+    Ok((closure, missing))
+}
+
+/// Fetch a single resource without following its dependencies
+pub async fn fetch_resource(sha1: [u8; 20], archive_root: String) -> Result<Vec<u8>> {
+    let dl = Arc::new(Downloader::new(DownloadPool::new(1), blob_cache_dir()?)?);
+    dl.clone().fetch_one_cached(sha1, PathBuf::from(&archive_root)).await?;
+
+    dl.cache.remove(&sha1)
+        .map(|(_, data)| data)
+        .ok_or_else(|| anyhow!("resource {} wasn't fetched", hex::encode(sha1)))
+}
+
+/// Public entrypoint, reporting its progress to `observer`
 pub async fn download_level(
     root: [u8; 20],
     icon_sha1: Option<[u8; 20]>,
     archive_root: String,
     max_parallel: usize,
+    partial_ok: bool,
+    observer: Arc<dyn ProgressObserver>,
+) -> Result<DownloadResult> {
+    let pool = DownloadPool::new(max_parallel).observed(observer);
+    download_level_with_pool(root, icon_sha1, archive_root, &pool, partial_ok).await
+}
+
+/// Like `download_level`, but taking its download slots from `pool`.
+/// With `partial_ok`, bulk leaf resources that fail are recorded in
+/// `missing` instead of failing the level, critical ones still fail it.
+/// A slot icon that can't be fetched only warns and is left out of `resources`,
+/// same for the seed hashes, which are fetched with their dependencies too.
+pub async fn download_level_with_pool(
+    root: [u8; 20],
+    icon_sha1: Option<[u8; 20]>,
+    archive_root: String,
+    pool: &DownloadPool,
+    partial_ok: bool,
 ) -> Result<DownloadResult> {
     let start = Instant::now();
     let root_dir = PathBuf::from(&archive_root);
+    let progress = &pool.observer;
 
-    // cache next to exe
-    let exe_path = std::env::current_exe()
-        .map_err(|e| anyhow!("couldn't find exe path: {}", e))?;
-    let exe_dir = exe_path
-        .parent()
-        .ok_or_else(|| anyhow!("exe has no parent directory"))?;
-    let cache_dir = exe_dir.join("resource_cache");
+    // a level fetched completely before is read back without walking it again
+    if let Some(closure) = cached_closure(root, icon_sha1)?
+        && let Some(resources) = read_closure(&closure).await
+    {
+        progress.on_stage_changed(Stage::Fetching { depth: 0, resources: resources.len() });
+        for (hash, blob) in &resources {
+            progress.on_resource_completed(*hash, blob.len());
+        }
+        metrics::FETCH_TIME.add_elapsed(start.elapsed());
+        eprintln!("{}▶ All {} resources read from the cache in {:.2?}", pool.prefix, resources.len(), start.elapsed());
+        return Ok(DownloadResult {
+            success_count: resources.len(),
+            error_count: 0,
+            resources,
+            missing: Vec::new(),
+            replaced: BTreeMap::new(),
+        });
+    }
 
-    let dl = Arc::new(Downloader::new(max_parallel, cache_dir)?);
+    let dl = Arc::new(Downloader::new(pool.clone(), blob_cache_dir()?)?);
     let mut js = JoinSet::new();
 
-    // enqueue root
-    {
-        let dl0 = dl.clone();
-        let rd0 = root_dir.clone();
-        js.spawn(async move { dl0.fetch_one_cached(root, rd0).await });
+    // what the slotlist needs (rootLevel, icon) and the level's structure are
+    // fetched first, so a broken level fails before the bulk data is read.
+    // only `max_parallel` fetches are started at a time so the order holds
+    let order = download_order();
+    let layout = archive_layout(&root_dir)?;
+    let mut critical = Batches::default();
+    let mut bulk = Batches::default();
+    // breadth first: what the current depth finds waits here for the next,
+    // the first depth is the rootLevel, icon and seeds
+    let mut next_critical = Batches::default();
+    next_critical.push(root, layout.locate(&hex::encode(root)));
+    let mut next_bulk = Batches::default();
+    let mut depth = 0;
+    let mut frontier = Frontier::default();
+    let mut pending = BTreeSet::from([root]);
+    if let Some(ic) = icon_sha1
+        && pending.insert(ic) {
+        next_critical.push(ic, layout.locate(&hex::encode(ic)));
     }
-    // optionally icon
-    if let Some(ic) = icon_sha1 {
-        let dl1 = dl.clone();
-        let rd1 = root_dir.clone();
-        js.spawn(async move { dl1.fetch_one_cached(ic, rd1).await });
+    let seeds = seed_hashes();
+    for &seed in seeds.into_iter().flatten() {
+        if pending.insert(seed) {
+            next_critical.push(seed, layout.locate(&hex::encode(seed)));
+        }
     }
-
-    // process deps
-    let mut pending = BTreeSet::new();
-    pending.insert(root);
-    if let Some(ic) = icon_sha1 {
-        pending.insert(ic);
+    if order != DownloadOrder::Breadth {
+        std::mem::swap(&mut critical, &mut next_critical);
+        progress.on_stage_changed(Stage::Fetching { depth: 0, resources: critical.len() });
     }
-    while let Some(res) = js.join_next().await {
-        let deps = res??;
-        for child in deps {
+    let mut missing = Vec::new();
+    let mut out_of_time = false;
+    let alternates = alternate_table();
+    // alternate being tried → the missing hash it stands in for
+    let mut alternate_of = BTreeMap::new();
+    // missing hash → the alternate that was fetched instead
+    let mut swapped = BTreeMap::new();
+
+    loop {
+        if order == DownloadOrder::Breadth && js.is_empty() && critical.is_empty() && bulk.is_empty() {
+            if next_critical.is_empty() && next_bulk.is_empty() {
+                break;
+            }
+            std::mem::swap(&mut critical, &mut next_critical);
+            std::mem::swap(&mut bulk, &mut next_bulk);
+            depth += 1;
+            frontier = Frontier { total: critical.len() + bulk.len(), done: 0 };
+            eprintln!("{}▶ depth {}: {} resources", pool.prefix, depth, frontier.total);
+            progress.on_stage_changed(Stage::Fetching { depth, resources: frontier.total });
+        }
+        while js.len() < pool.max_parallel && !out_of_time {
+            if budget::exceeded() {
+                out_of_time = true;
+                break;
+            }
+            let ((next, open_ahead), is_bulk) = match critical.pop(order) {
+                Some(next) => (next, false),
+                None => match bulk.pop(order) {
+                    Some(next) => (next, true),
+                    None => break,
+                },
+            };
+            if let Some(zip) = open_ahead {
+                pool.open_ahead(zip);
+            }
+            progress.on_resource_started(next);
+            let dln = dl.clone();
+            let rdn = root_dir.clone();
+            js.spawn(async move { (next, is_bulk, dln.fetch_one_cached(next, rdn).await) });
+        }
+        let Some(res) = js.join_next().await else {
+            break;
+        };
+
+        let (hash, is_bulk, result) = res?;
+        match &result {
+            Ok(_) => progress.on_resource_completed(hash, dl.cache.get(&hash).map_or(0, |blob| blob.len())),
+            Err(e) => progress.on_resource_failed(hash, &e.to_string()),
+        }
+        if order == DownloadOrder::Breadth && let Some(percent) = frontier.advance() {
+            eprintln!("{}▶ depth {}: {}/{} ({}%)", pool.prefix, depth, frontier.done, frontier.total, percent);
+        }
+        let original = alternate_of.get(&hash).copied().unwrap_or(hash);
+        if result.is_ok() && original != hash {
+            swapped.insert(original, hash);
+        }
+        if let Err(e) = &result
+            && let Some(alternate) = alternates.and_then(|t| t.alternates(&original).find(|a| !pending.contains(*a)))
+        {
+            eprintln!("{}↪ {}", pool.prefix, Msg::TryingAlternate { hash: &hex::encode(hash), alternate: &hex::encode(alternate), error: &e.to_string() });
+            alternate_of.insert(*alternate, original);
+            pending.insert(*alternate);
+            // it's fetched in this depth, in place of the missing one
+            frontier.total += 1;
+            match is_bulk {
+                true => bulk.push(*alternate, layout.locate(&hex::encode(alternate))),
+                false => critical.push(*alternate, layout.locate(&hex::encode(alternate))),
+            }
+            continue;
+        }
+        let deps = match (original, is_bulk, result) {
+            (_, _, Ok(deps)) => deps,
+            (hash, true, Err(e)) if partial_ok => {
+                let container = layout
+                    .locate(&hex::encode(hash))
+                    .map(|l| l.container().to_path_buf());
+                if !container.is_some_and(|c| pool.zip_failed(&c)) {
+                    eprintln!("{}⚠️ {}", pool.prefix, Msg::LeavingOut { hash: &hex::encode(hash), error: &e.to_string() });
+                }
+                missing.push((hash, e.to_string()));
+                continue;
+            }
+            // the slot icon is only needed for ICON0.PNG, which has fallbacks
+            (hash, _, Err(e)) if Some(hash) == icon_sha1 && hash != root => {
+                eprintln!("{}⚠️ {}", pool.prefix, Msg::SlotIconFallback { hash: &hex::encode(hash), error: &e.to_string() });
+                continue;
+            }
+            (hash, _, Err(e)) if seeds.is_some_and(|s| s.contains(&hash)) && hash != root => {
+                eprintln!("{}⚠️ {}", pool.prefix, Msg::SeedHashLeftOut { hash: &hex::encode(hash), error: &e.to_string() });
+                continue;
+            }
+            (_, _, Err(e)) => return Err(e),
+        };
+
+        // process deps
+        let (critical, bulk) = match order {
+            DownloadOrder::Breadth => (&mut next_critical, &mut next_bulk),
+            DownloadOrder::Discovered | DownloadOrder::Depth => (&mut critical, &mut bulk),
+        };
+        for (child, resrc_type) in deps {
             if pending.insert(child) {
-                let dlc = dl.clone();
-                let rdc = root_dir.clone();
-                js.spawn(async move { dlc.fetch_one_cached(child, rdc).await });
+                match BULK_RESRC_TYPES.contains(&resrc_type) {
+                    true => bulk.push(child, layout.locate(&hex::encode(child))),
+                    false => critical.push(child, layout.locate(&hex::encode(child))),
+                }
             }
         }
     }
 
+    let not_fetched = critical.len() + bulk.len() + next_critical.len() + next_bulk.len();
+    if out_of_time && not_fetched > 0 {
+        return Err(anyhow!("{}time limit reached, {} resources not fetched", pool.prefix, not_fetched));
+    }
+
     // collect
-    let mut guard = dl.cache.lock().await;
-    let resources = std::mem::take(&mut *guard);
+    let mut resources: BTreeMap<_, _> = pending.iter().filter_map(|h| dl.cache.remove(h)).collect();
+    let replaced = match swapped.is_empty() {
+        true => BTreeMap::new(),
+        false => {
+            let replaced = alternates::patch_references(&mut resources, &swapped);
+            eprintln!("{}↪ {}", pool.prefix, Msg::AlternatesSwapped { swapped: swapped.len(), rewritten: replaced.len() - swapped.len() });
+            replaced
+        }
+    };
+
+    let skipped: BTreeMap<PathBuf, usize> = dl.skipped.iter().map(|e| (e.key().clone(), *e.value())).collect();
+    for (zip_path, count) in skipped {
+        let reason = pool.failed_zips.get(&zip_path).map(|r| r.clone()).unwrap_or_default();
+        let zip = zip_path.file_name().unwrap_or_default().to_string_lossy();
+        eprintln!("{}⚠️ {}", pool.prefix, Msg::ZipSkipped { zip: &zip, resources: count, reason: &reason });
+    }
 
-    eprintln!("▶ All resources fetched in {:.2?}", start.elapsed());
+    let switches = dl.last_container.lock().map(|last| last.1).unwrap_or_default();
+    metrics::FETCH_TIME.add_elapsed(start.elapsed());
+    eprintln!("{}▶ All resources fetched in {:.2?}, archive files switched: {}", pool.prefix, start.elapsed(), switches);
+    if missing.is_empty()
+        && seeds.is_none()
+        && replaced.is_empty()
+        && disk_cache()
+        && !pool.read_only
+        && let Some(cache) = closure_cache()
+    {
+        let closure: Closure = resources.iter().map(|(h, b)| (*h, b.len() as u64)).collect();
+        if let Err(e) = cache.put(root, icon_sha1, &closure) {
+            eprintln!("{}⚠️ {}", pool.prefix, Msg::ClosureNotRemembered { hash: &hex::encode(root), error: &e.to_string() });
+        }
+    }
     Ok(DownloadResult {
         success_count: resources.len(),
-        error_count: 0,
+        error_count: missing.len(),
         resources,
+        missing,
+        replaced,
     })
 }
@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::io::{Cursor, Read, Seek, SeekFrom, Write};
 
 use crate::{db::GameVersion, gtf_texture::{CellGcmEnumForGtf, CellGcmTexture}};
@@ -13,7 +14,7 @@ pub struct ResrcData {
     pub method: ResrcMethod,
 }
 
-#[derive(Debug, PartialEq, Eq, Hash)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
 pub struct ResrcRevision {
     pub head: u32,
     pub branch_id: u16,
@@ -42,6 +43,84 @@ impl ResrcRevision {
             GameVersion::Lbp2
         }
     }
+    /// The branch this revision is on, `None` for mainline and branches we don't know
+    pub fn get_branch(&self) -> Option<Branch> {
+        Branch::from_id(self.branch_id)
+    }
+    pub fn is_mainline(&self) -> bool {
+        self.branch_id == 0
+    }
+    /// The revision to write a slot list or save key for this resource with
+    /// when restoring it as `game`. A branched revision is kept as far as
+    /// `game` is the game of the branch and the PS3 game loads it, so the
+    /// slot is serialized the way the branch expects. Anything else gets the
+    /// mainline revision of `game`, D1 slots have fields we don't write
+    pub fn compatible_revision(&self, game: GameVersion) -> ResrcRevision {
+        match self.get_branch() {
+            Some(branch) if branch.get_gameversion() == game && branch.loads_on_ps3() => *self,
+            _ => game.get_latest_revision(),
+        }
+    }
+}
+
+/// Branches of the resource format next to mainline, named after their
+/// `branch_id` tags
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Branch {
+    /// "LD", the last LBP1 updates
+    Leerdammer,
+    /// "D1", LBP PS Vita and the cross-controller content made with it
+    Double11,
+}
+
+impl Branch {
+    pub fn from_id(id: u16) -> Option<Self> {
+        match id {
+            0x4c44 => Some(Self::Leerdammer),
+            0x4431 => Some(Self::Double11),
+            _ => None,
+        }
+    }
+    pub fn get_id(&self) -> u16 {
+        match self {
+            Self::Leerdammer => 0x4c44,
+            Self::Double11 => 0x4431,
+        }
+    }
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Self::Leerdammer => "Leerdammer (LD)",
+            Self::Double11 => "Double11 (D1, PS Vita)",
+        }
+    }
+    /// The game whose head revision the branch forks off
+    pub fn get_gameversion(&self) -> GameVersion {
+        match self {
+            Self::Leerdammer => GameVersion::Lbp1,
+            Self::Double11 => GameVersion::Lbp2,
+        }
+    }
+    /// Whether the PS3 game of `get_gameversion` loads resources of this branch
+    pub fn loads_on_ps3(&self) -> bool {
+        match self {
+            Self::Leerdammer => true,
+            Self::Double11 => false,
+        }
+    }
+    pub fn get_latest_revision(&self) -> ResrcRevision {
+        match self {
+            Self::Leerdammer => ResrcRevision {
+                head: 0x272,
+                branch_id: 0x4c44,
+                branch_revision: 0x17,
+            },
+            Self::Double11 => ResrcRevision {
+                head: 0x3e2,
+                branch_id: 0x4431,
+                branch_revision: 0x87,
+            },
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Hash)]
@@ -61,7 +140,7 @@ pub enum ResrcMethod {
 #[derive(Debug, PartialEq, Eq, Hash)]
 pub struct ResrcDependency {
     pub desc: ResrcDescriptor,
-    resrc_type: u32,
+    pub resrc_type: u32,
 }
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
@@ -107,6 +186,244 @@ impl ResrcDependency {
     }
 }
 
+/// Inflate the zlib chunks that follow a compressed resource header
+fn decompress_chunks(res: &mut Cursor<&[u8]>) -> Result<Vec<u8>> {
+    res.seek(SeekFrom::Current(2))?; // unused i16, always 0x0001
+    let num_chunks = res.read_u16::<BigEndian>()?;
+
+    let mut chunk_infos = Vec::with_capacity(num_chunks as usize);
+    let mut total_decompressed_size = 0;
+
+    #[derive(Debug)]
+    struct ChunkInfo {
+        compressed_size: u16,
+        decompressed_size: u16,
+    }
+
+    for _ in 0..num_chunks {
+        let info = ChunkInfo {
+            compressed_size: res.read_u16::<BigEndian>()?,
+            decompressed_size: res.read_u16::<BigEndian>()?,
+        };
+        total_decompressed_size += info.decompressed_size as usize;
+        chunk_infos.push(info);
+    }
+
+    let mut final_data = vec![0u8; total_decompressed_size];
+
+    let mut decompressor = DecompressorOxide::new();
+
+    let mut final_pos = 0;
+    for info in chunk_infos {
+        let mut deflated_data = vec![0u8; info.compressed_size as usize];
+        res.read_exact(&mut deflated_data[..info.compressed_size as usize])?;
+
+        if info.compressed_size == info.decompressed_size {
+            (&mut final_data[final_pos..]).write_all(&deflated_data)?;
+        } else {
+            let flags = TINFL_FLAG_PARSE_ZLIB_HEADER | TINFL_FLAG_USING_NON_WRAPPING_OUTPUT_BUF;
+            decompress(&mut decompressor, &deflated_data, &mut final_data, final_pos, flags);
+            decompressor.init();
+        }
+
+        final_pos += info.decompressed_size as usize;
+    }
+
+    Ok(final_data)
+}
+
+/// `USE_COMPRESSED_INTEGERS` in a resource's compression flags, ints are LEB128 then
+pub const COMPRESSED_INTEGERS: u8 = 1;
+
+/// Where the serialized data of a binary resource is
+struct PayloadSpan {
+    start: usize,
+    end: usize,
+    /// where the dependency table is, its offset is at byte 8 then
+    table: Option<usize>,
+    is_compressed: bool,
+    flags: u8,
+}
+
+/// None for encrypted and non-binary resources
+fn payload_span(res: &[u8]) -> Result<Option<PayloadSpan>> {
+    let mut cur = Cursor::new(res);
+    let mut resrc_type = [0u8; 3];
+    cur.read_exact(&mut resrc_type)?;
+    if cur.read_u8()? != b'b' {
+        return Ok(None);
+    }
+    let head = cur.read_u32::<BigEndian>()?;
+    let mut data_end = res.len();
+    if head >= 0x109 {
+        data_end = cur.read_u32::<BigEndian>()? as usize;
+    }
+
+    let mut flags = 0;
+    // resources before 0x189 have no flag, they're always compressed
+    let mut is_compressed = true;
+    if head >= 0x189 && resrc_type != *b"SMH" {
+        let (mut branch_id, mut branch_revision) = (0, 0);
+        if head >= 0x271 {
+            branch_id = cur.read_u16::<BigEndian>()?;
+            branch_revision = cur.read_u16::<BigEndian>()?;
+        }
+        if head >= 0x297 || (head == 0x272 && Branch::from_id(branch_id) == Some(Branch::Leerdammer) && branch_revision >= 0x2) {
+            flags = cur.read_u8()?;
+        }
+        is_compressed = cur.read_u8()? != 0;
+    } else if resrc_type == *b"SMH" {
+        return Ok(None);
+    }
+
+    let start = cur.position() as usize;
+    Ok(Some(PayloadSpan { start, end: data_end.max(start).min(res.len()), table: (head >= 0x109).then_some(data_end), is_compressed, flags }))
+}
+
+/// The serialized data of a binary resource, inflated if needed, and its
+/// compression flags. None for encrypted and non-binary resources
+pub fn binary_payload(res: &[u8]) -> Result<Option<(Vec<u8>, u8)>> {
+    let Some(span) = payload_span(res)? else { return Ok(None) };
+    let data = res.get(span.start..span.end)
+        .ok_or_else(|| anyhow!("resource data is out of bounds"))?;
+    match span.is_compressed {
+        true => Ok(Some((decompress_chunks(&mut Cursor::new(data))?, span.flags))),
+        false => Ok(Some((data.to_vec(), span.flags))),
+    }
+}
+
+/// largest chunk the game inflates at once
+const CHUNK_SIZE: usize = 0x8000;
+
+/// The counterpart of `decompress_chunks`, chunks that don't get smaller are stored
+pub(crate) fn compress_chunks(data: &[u8]) -> Vec<u8> {
+    let chunks: Vec<(&[u8], Vec<u8>)> = data.chunks(CHUNK_SIZE)
+        .map(|chunk| (chunk, miniz_oxide::deflate::compress_to_vec_zlib(chunk, 6)))
+        .collect();
+    let mut out = vec![0, 1];
+    out.extend((chunks.len() as u16).to_be_bytes());
+    let mut bodies = Vec::new();
+    for (chunk, deflated) in &chunks {
+        let body: &[u8] = if deflated.len() < chunk.len() { deflated } else { chunk };
+        out.extend((body.len() as u16).to_be_bytes());
+        out.extend((chunk.len() as u16).to_be_bytes());
+        bodies.extend_from_slice(body);
+    }
+    out.extend(bodies);
+    out
+}
+
+fn replace_all(data: &mut [u8], from: &[u8; 20], to: &[u8; 20]) {
+    let mut i = 0;
+    while i + 20 <= data.len() {
+        if data[i..i + 20] == from[..] {
+            data[i..i + 20].copy_from_slice(to);
+            i += 20;
+        } else {
+            i += 1;
+        }
+    }
+}
+
+/// Point the SHA1 references of a binary resource somewhere else, `map` is
+/// old → new. Both the dependency table and the serialized data are rewritten,
+/// the data is compressed again if it was. None if nothing in `map` is referenced
+pub fn replace_sha1_references(res: &[u8], map: &BTreeMap<[u8; 20], [u8; 20]>) -> Result<Option<Vec<u8>>> {
+    let ResrcMethod::Binary { is_encrypted, dependencies, .. } = ResrcData::new(res, false)?.method else { return Ok(None) };
+    let used: Vec<(&[u8; 20], &[u8; 20])> = map.iter()
+        .filter(|(old, _)| dependencies.iter().any(|d| d.desc == ResrcDescriptor::Sha1(**old)))
+        .collect();
+    if used.is_empty() {
+        return Ok(None);
+    }
+    if is_encrypted {
+        return Err(anyhow!("it's encrypted"));
+    }
+    let span = payload_span(res)?.ok_or_else(|| anyhow!("its layout isn't supported"))?;
+    let table_at = span.table
+        .filter(|&at| at >= span.start && at <= res.len())
+        .ok_or_else(|| anyhow!("its dependency table isn't after its data"))?;
+
+    let (mut data, _) = binary_payload(res)?.ok_or_else(|| anyhow!("its layout isn't supported"))?;
+    for (old, new) in &used {
+        replace_all(&mut data, old, new);
+    }
+    if span.is_compressed {
+        data = compress_chunks(&data);
+    }
+
+    let mut table = res[table_at..].to_vec();
+    let mut cur = Cursor::new(&table[..]);
+    let mut sha1_at = Vec::new();
+    for _ in 0..cur.read_u32::<BigEndian>()? {
+        match cur.read_u8()? {
+            0 => {},
+            1 => { sha1_at.push(cur.position() as usize); cur.seek(SeekFrom::Current(20))?; },
+            2 => { cur.seek(SeekFrom::Current(4))?; },
+            _ => return Err(anyhow!("invalid type in dependency table")),
+        }
+        cur.seek(SeekFrom::Current(4))?; // resrc_type
+    }
+    for at in sha1_at {
+        let sha1: [u8; 20] = table[at..at + 20].try_into()?;
+        if let Some(new) = map.get(&sha1) {
+            table[at..at + 20].copy_from_slice(new);
+        }
+    }
+
+    let mut out = res[..span.start].to_vec();
+    out[8..12].copy_from_slice(&((span.start + data.len()) as u32).to_be_bytes());
+    out.extend(data);
+    out.extend(table);
+    Ok(Some(out))
+}
+
+/// names of the resource types declared in dependency tables, indexed by type id
+const RESRC_TYPE_NAMES: [&str; 45] = [
+    "invalid", "texture", "mesh", "pixel shader", "vertex shader", "animation",
+    "guid substitution", "gfx material", "spu elf", "level", "filename", "script",
+    "character settings", "file of bytes", "soft physics settings", "font face",
+    "physics material", "downloadable content", "editor settings", "joint",
+    "game constants", "poppet settings", "cached level data", "synced profile",
+    "bevel", "game", "network settings", "packs", "big profile", "slot list",
+    "translation", "particle settings", "local profile", "limits settings",
+    "tutorials", "guid list", "audio materials", "fluid settings", "plan",
+    "texture list", "music settings", "mixer settings", "replay config",
+    "palette", "static mesh",
+];
+
+pub fn resrc_type_name(resrc_type: u32) -> Option<&'static str> {
+    RESRC_TYPE_NAMES.get(resrc_type as usize).copied()
+}
+
+/// The type id of a resource type name, the other way around
+pub fn resrc_type_id(name: &str) -> Option<u32> {
+    RESRC_TYPE_NAMES.iter().position(|n| *n == name).map(|id| id as u32)
+}
+
+/// What a blob of a declared type starts with, empty when we don't know
+pub fn resrc_type_magics(resrc_type: u32) -> &'static [&'static [u8]] {
+    match resrc_type {
+        1 => &[b"TEX", b"GTF", b"\x89PNG", b"\xff\xd8"],
+        2 => &[b"MSH"],
+        5 => &[b"ANM"],
+        7 => &[b"GMT"],
+        9 => &[b"LVL"],
+        11 => &[b"FSH"],
+        15 => &[b"FNT"],
+        16 => &[b"MAT"],
+        24 => &[b"BEV"],
+        28 => &[b"BPR"],
+        29 => &[b"SLT"],
+        32 => &[b"IPR"],
+        38 => &[b"PLN"],
+        40 => &[b"MUS"],
+        43 => &[b"PAL"],
+        44 => &[b"SMH"],
+        _ => &[],
+    }
+}
+
 impl ResrcData {
     pub fn new(res: &[u8], parse_texture: bool) -> Result<Self> {
         let mut res = Cursor::new(res);
@@ -168,46 +485,7 @@ impl ResrcData {
                         });
                     }
 
-                    res.seek(SeekFrom::Current(2))?; // unused i16, always 0x0001
-                    let num_chunks = res.read_u16::<BigEndian>()?;
-
-                    let mut chunk_infos = Vec::with_capacity(num_chunks as usize);
-                    let mut total_decompressed_size = 0;
-
-                    #[derive(Debug)]
-                    struct ChunkInfo {
-                        compressed_size: u16,
-                        decompressed_size: u16,
-                    }
-
-                    for _ in 0..num_chunks {
-                        let info = ChunkInfo {
-                            compressed_size: res.read_u16::<BigEndian>()?,
-                            decompressed_size: res.read_u16::<BigEndian>()?,
-                        };
-                        total_decompressed_size += info.decompressed_size as usize;
-                        chunk_infos.push(info);
-                    }
-
-                    let mut final_data = vec![0u8; total_decompressed_size];
-
-                    let mut decompressor = DecompressorOxide::new();
-
-                    let mut final_pos = 0;
-                    for info in chunk_infos {
-                        let mut deflated_data = vec![0u8; info.compressed_size as usize];
-                        res.read_exact(&mut deflated_data[..info.compressed_size as usize])?;
-
-                        if info.compressed_size == info.decompressed_size {
-                            (&mut final_data[final_pos..]).write_all(&deflated_data)?;
-                        } else {
-                            let flags = TINFL_FLAG_PARSE_ZLIB_HEADER | TINFL_FLAG_USING_NON_WRAPPING_OUTPUT_BUF;
-                            decompress(&mut decompressor, &deflated_data, &mut final_data, final_pos, flags);
-                            decompressor.init();
-                        }
-
-                        final_pos += info.decompressed_size as usize;
-                    }
+                    let final_data = decompress_chunks(&mut res)?;
 
                     ResrcMethod::Texture { data: final_data, gcm_info: gcm }
                 }
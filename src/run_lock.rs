@@ -0,0 +1,206 @@
+// src/run_lock.rs
+
+//! One run at a time per backup_directory. Two runs writing the same
+//! backups or fileDump interleave their files, so a run that writes there
+//! first takes a lock file naming its PID, and a second run stops with
+//! whose it is. It's advisory, nothing stops a tool that doesn't look.
+
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use anyhow::{Result, anyhow, bail};
+use chrono::Utc;
+
+use crate::messages::Msg;
+
+/// in the locked folder
+pub const LOCK_FILE: &str = ".archive_dl.lock";
+
+static FORCE: OnceLock<bool> = OnceLock::new();
+
+/// `--force`, for the locks commands take on folders of their own
+pub fn set_force(force: bool) {
+    let _ = FORCE.set(force);
+}
+
+pub fn forced() -> bool {
+    FORCE.get().copied().unwrap_or(false)
+}
+
+/// The run holding a lock, as its lock file names it
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Holder {
+    pub pid: u32,
+    pub host: String,
+    pub started: String,
+}
+
+impl Holder {
+    fn this_run() -> Self {
+        Self {
+            pid: std::process::id(),
+            host: hostname(),
+            started: Utc::now().to_rfc3339(),
+        }
+    }
+
+    fn parse(text: &str) -> Option<Self> {
+        let mut holder = Self::default();
+        for line in text.lines() {
+            match line.split_once(": ")? {
+                ("pid", pid) => holder.pid = pid.parse().ok()?,
+                ("host", host) => holder.host = host.to_string(),
+                ("started", started) => holder.started = started.to_string(),
+                _ => {}
+            }
+        }
+        (holder.pid != 0).then_some(holder)
+    }
+
+    fn render(&self) -> String {
+        format!(
+            "pid: {}\nhost: {}\nstarted: {}\n",
+            self.pid, self.host, self.started
+        )
+    }
+
+    /// Whether the run is gone for sure, only known for runs on this machine
+    fn is_gone(&self) -> bool {
+        self.host == hostname() && process_alive(self.pid) == Some(false)
+    }
+}
+
+/// Held while the run writes to the folder, the lock file goes with it
+/// unless another run took it over
+#[derive(Debug)]
+pub struct RunLock {
+    path: PathBuf,
+    holder: Holder,
+}
+
+impl RunLock {
+    /// Lock `dir`, failing if another run holds it. `force` takes the lock
+    /// anyway, for a run that's gone without cleaning up on another machine.
+    /// A lock left by a run on this machine that isn't running anymore is
+    /// taken over by itself
+    pub fn acquire(dir: &Path, force: bool) -> Result<Self> {
+        fs::create_dir_all(dir)
+            .map_err(|e| anyhow!("couldn't create `{}`: {}", dir.display(), e))?;
+        let path = dir.join(LOCK_FILE);
+        // a few retries, after taking over the lock of a run that's gone or
+        // losing a race for it to another run
+        for _ in 0..3 {
+            let this_run = Holder::this_run();
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(mut file) => {
+                    file.write_all(this_run.render().as_bytes())?;
+                    file.sync_all()?;
+                    drop(file);
+                    // a run taking over a stale lock at the same time may
+                    // have moved this one aside, it's only ours if it's there
+                    if RunLock::holder(dir).as_ref() == Some(&this_run) {
+                        return Ok(Self {
+                            path,
+                            holder: this_run,
+                        });
+                    }
+                    continue;
+                }
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => {}
+                Err(e) => bail!("couldn't create the lock `{}`: {}", path.display(), e),
+            }
+
+            // gone again already, its run just ended
+            let Ok(text) = fs::read_to_string(&path) else {
+                continue;
+            };
+            match Holder::parse(&text) {
+                Some(holder) if holder.is_gone() => {
+                    eprintln!("{}", Msg::StaleLockRemoved(holder.pid));
+                }
+                _ if force => {
+                    eprintln!("⚠️ {}", Msg::LockTakenOver(dir));
+                }
+                Some(holder) => bail!(
+                    "another run is active in `{}`: PID {} on {}, since {}. Wait for it to finish, or use --force if it's gone",
+                    dir.display(),
+                    holder.pid,
+                    holder.host,
+                    holder.started
+                ),
+                None => bail!(
+                    "another run is active in `{}`, or left `{}` behind. Wait for it to finish, or use --force if it's gone",
+                    dir.display(),
+                    path.display()
+                ),
+            }
+            move_aside(&path, &text)?;
+        }
+        bail!(
+            "couldn't take the lock `{}`, other runs are starting at the same time",
+            path.display()
+        )
+    }
+
+    /// Who holds the lock of `dir`, if anyone
+    pub fn holder(dir: &Path) -> Option<Holder> {
+        let text = fs::read_to_string(dir.join(LOCK_FILE)).ok()?;
+        Holder::parse(&text)
+    }
+}
+
+impl Drop for RunLock {
+    fn drop(&mut self) {
+        // after a --force takeover the lock is the new holder's
+        let text = fs::read_to_string(&self.path).ok();
+        if text.as_deref().and_then(Holder::parse).as_ref() == Some(&self.holder) {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
+
+/// Take the lock file out of the way of a new one, if it still says `seen`.
+/// Renaming is atomic, so of two runs taking over the same stale lock only
+/// one moves it. If what was moved isn't the stale lock anymore but a new
+/// run's, it's put back, which fails rather than replace a lock made since
+fn move_aside(path: &Path, seen: &str) -> Result<()> {
+    static MOVES: AtomicU32 = AtomicU32::new(0);
+    let aside = path.with_extension(format!(
+        "lock.{}-{}.old",
+        std::process::id(),
+        MOVES.fetch_add(1, Ordering::Relaxed)
+    ));
+    match fs::rename(path, &aside) {
+        Ok(()) => {}
+        // another run moved it first
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(()),
+        Err(e) => bail!("couldn't remove the lock `{}`: {}", path.display(), e),
+    }
+    if fs::read_to_string(&aside).ok().as_deref() != Some(seen) {
+        let _ = fs::hard_link(&aside, path);
+    }
+    let _ = fs::remove_file(&aside);
+    Ok(())
+}
+
+/// The machine's name, to tell runs on a shared folder apart
+fn hostname() -> String {
+    fs::read_to_string("/etc/hostname")
+        .ok()
+        .or_else(|| std::env::var("COMPUTERNAME").ok())
+        .or_else(|| std::env::var("HOSTNAME").ok())
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Whether process `pid` is running, `None` where that can't be told
+fn process_alive(pid: u32) -> Option<bool> {
+    match cfg!(target_os = "linux") {
+        true => Some(Path::new("/proc").join(pid.to_string()).exists()),
+        false => None,
+    }
+}
@@ -0,0 +1,257 @@
+// src/save.rs
+
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+use anyhow::{Result, anyhow, bail};
+use byteorder::{BigEndian, ByteOrder};
+use hmac::{Hmac, Mac};
+use sha1::{Digest, Sha1};
+
+use crate::serializers::lbp::{HASHINATE_KEY, TEA_KEY};
+use crate::xxtea;
+
+const FAT_ENTRY_SIZE: usize = 28;
+const HASHINATE_SIZE: usize = 20;
+
+/// The footers we can read, see `make_savearchive` for how FAR4 is laid out
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FarKind {
+    /// plain `.farc`, no save key or hashinate
+    Farc,
+    /// save archive, hashinate before the entry count
+    Far4,
+    Far5,
+}
+
+/// Why an entry couldn't be extracted
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EntryDamage {
+    /// it points outside the archive
+    OutOfRange,
+    /// its data doesn't hash to its SHA1
+    BadHash,
+}
+
+pub struct FarEntry {
+    pub sha1: [u8; 20],
+    pub offset: u32,
+    pub size: u32,
+}
+
+pub struct FarArchive {
+    pub kind: FarKind,
+    pub entries: Vec<FarEntry>,
+    /// whether the archive's HMAC matched, None for archives without one
+    pub hashinate_ok: Option<bool>,
+}
+
+#[derive(Default)]
+pub struct ExtractResult {
+    pub resources: BTreeMap<[u8; 20], Vec<u8>>,
+    /// entries pointing outside the archive
+    pub out_of_range: usize,
+    /// entries whose data doesn't hash to their SHA1
+    pub bad_hash: usize,
+}
+
+/// Read a save, either a backup folder of xxtea-encrypted chunks (`0`, `1`, …)
+/// or a single unencrypted archive file, and return the plain archive
+pub fn read_save(path: &Path) -> Result<Vec<u8>> {
+    if !path.is_dir() {
+        return fs::read(path).map_err(|e| anyhow!("couldn't read {}: {}", path.display(), e));
+    }
+
+    let mut chunks: Vec<(usize, Vec<u8>)> = Vec::new();
+    for entry in fs::read_dir(path)? {
+        let entry = entry?;
+        if let Some(index) = entry.file_name().to_str().and_then(|n| n.parse().ok()) {
+            chunks.push((index, fs::read(entry.path())?));
+        }
+    }
+    chunks.sort_by_key(|(i, _)| *i);
+    if chunks.is_empty() {
+        bail!(
+            "{} has no save chunks (files named 0, 1, …)",
+            path.display()
+        );
+    }
+    if chunks.iter().enumerate().any(|(i, (index, _))| i != *index) {
+        bail!(
+            "{} is missing save chunks, expected 0 to {}",
+            path.display(),
+            chunks.len() - 1
+        );
+    }
+
+    Ok(join_chunks(
+        chunks.into_iter().map(|(_, chunk)| chunk).collect(),
+    ))
+}
+
+/// Decrypt the chunks of a backup, in order, into the plain archive
+pub fn join_chunks(chunks: Vec<Vec<u8>>) -> Vec<u8> {
+    let last = chunks.len().saturating_sub(1);
+    let mut archive = Vec::with_capacity(chunks.iter().map(Vec::len).sum());
+    for (i, mut chunk) in chunks.into_iter().enumerate() {
+        // the footer magic of the last chunk is left in the clear
+        let mut xxtea_end = chunk.len() & !3;
+        if i == last {
+            xxtea_end = xxtea_end.saturating_sub(4);
+        }
+        xxtea::decrypt(&TEA_KEY, &mut chunk[..xxtea_end]);
+        archive.extend_from_slice(&chunk);
+    }
+    archive
+}
+
+/// Where an archive of `len` bytes keeps its entry table and hashinate,
+/// going by its last 8 bytes
+struct Layout {
+    kind: FarKind,
+    count: usize,
+    fat_start: usize,
+    /// start of the hashinate, which ends where the footer begins
+    fat_end: usize,
+    hashinate_size: usize,
+}
+
+fn far_layout(len: usize, footer: &[u8]) -> Result<Layout> {
+    if len < 8 {
+        bail!("file is too short to be an archive");
+    }
+    let kind = match &footer[4..] {
+        b"FARC" => FarKind::Farc,
+        b"FAR4" => FarKind::Far4,
+        b"FAR5" => FarKind::Far5,
+        // its entry table and hashinate aren't laid out like FAR4's, reading
+        // it as one would extract garbage
+        b"FAR2" => bail!("FAR2 archives aren't supported, only FARC, FAR4 and FAR5"),
+        magic => bail!(
+            "unknown archive footer {:02x?}, is this an LBP save?",
+            magic
+        ),
+    };
+    let count = BigEndian::read_u32(&footer[..4]) as usize;
+    let hashinate_size = match kind {
+        FarKind::Farc => 0,
+        FarKind::Far4 | FarKind::Far5 => HASHINATE_SIZE,
+    };
+
+    let fat_size = count
+        .checked_mul(FAT_ENTRY_SIZE)
+        .ok_or_else(|| anyhow!("entry count {} is corrupt", count))?;
+    let fat_end = len
+        .checked_sub(8 + hashinate_size)
+        .ok_or_else(|| anyhow!("archive footer is truncated"))?;
+    let fat_start = fat_end
+        .checked_sub(fat_size)
+        .ok_or_else(|| anyhow!("entry count {} doesn't fit in the archive", count))?;
+    Ok(Layout {
+        kind,
+        count,
+        fat_start,
+        fat_end,
+        hashinate_size,
+    })
+}
+
+fn parse_entries(fat: &[u8]) -> Vec<FarEntry> {
+    fat.chunks_exact(FAT_ENTRY_SIZE)
+        .map(|e| FarEntry {
+            sha1: e[..20].try_into().unwrap(),
+            offset: BigEndian::read_u32(&e[20..24]),
+            size: BigEndian::read_u32(&e[24..28]),
+        })
+        .collect()
+}
+
+/// Find the entry table from the footer at the end of `data`
+pub fn parse_far(data: &[u8]) -> Result<FarArchive> {
+    let layout = far_layout(data.len(), &data[data.len().saturating_sub(8)..])?;
+    let Layout {
+        fat_start, fat_end, ..
+    } = layout;
+    let entries = parse_entries(&data[fat_start..fat_end]);
+
+    let hashinate_ok = match layout.hashinate_size {
+        0 => None,
+        _ => {
+            let mut copy = data.to_vec();
+            copy[fat_end..fat_end + HASHINATE_SIZE].fill(0);
+            let mut mac = Hmac::<Sha1>::new_from_slice(&HASHINATE_KEY)?;
+            mac.update(&copy);
+            Some(
+                mac.verify_slice(&data[fat_end..fat_end + HASHINATE_SIZE])
+                    .is_ok(),
+            )
+        }
+    };
+
+    Ok(FarArchive {
+        kind: layout.kind,
+        entries,
+        hashinate_ok,
+    })
+}
+
+/// Like `parse_far` for an unencrypted archive file, reading only its
+/// footer and entry table so game caches of several gigabytes don't have
+/// to fit in memory. The hashinate isn't checked, that needs all of it
+pub fn read_far_table(file: &mut File) -> Result<FarArchive> {
+    let len = file.metadata()?.len() as usize;
+    let mut footer = [0u8; 8];
+    if len >= 8 {
+        file.seek(SeekFrom::Start((len - 8) as u64))?;
+        file.read_exact(&mut footer)?;
+    }
+    let layout = far_layout(len, &footer)?;
+    let mut fat = vec![0u8; layout.count * FAT_ENTRY_SIZE];
+    file.seek(SeekFrom::Start(layout.fat_start as u64))?;
+    file.read_exact(&mut fat)?;
+    Ok(FarArchive {
+        kind: layout.kind,
+        entries: parse_entries(&fat),
+        hashinate_ok: None,
+    })
+}
+
+/// The data of `entry` in an archive file if it's inside the file and
+/// hashes to its SHA1, what's wrong with it otherwise
+pub fn read_entry(file: &mut File, entry: &FarEntry) -> Result<Result<Vec<u8>, EntryDamage>> {
+    let len = file.metadata()?.len();
+    if entry.offset as u64 + entry.size as u64 > len {
+        return Ok(Err(EntryDamage::OutOfRange));
+    }
+    let mut blob = vec![0u8; entry.size as usize];
+    file.seek(SeekFrom::Start(entry.offset as u64))?;
+    file.read_exact(&mut blob)?;
+    if Sha1::digest(&blob).as_slice() != entry.sha1 {
+        return Ok(Err(EntryDamage::BadHash));
+    }
+    Ok(Ok(blob))
+}
+
+/// Pull every entry out of the archive, skipping (and counting) the ones
+/// that are damaged instead of giving up on the whole save
+pub fn extract(data: &[u8], archive: &FarArchive) -> ExtractResult {
+    let mut result = ExtractResult::default();
+    for entry in &archive.entries {
+        let start = entry.offset as usize;
+        let Some(blob) = start
+            .checked_add(entry.size as usize)
+            .and_then(|end| data.get(start..end))
+        else {
+            result.out_of_range += 1;
+            continue;
+        };
+        if Sha1::digest(blob).as_slice() != entry.sha1 {
+            result.bad_hash += 1;
+            continue;
+        }
+        result.resources.insert(entry.sha1, blob.to_vec());
+    }
+    result
+}
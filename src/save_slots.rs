@@ -0,0 +1,263 @@
+// src/save_slots.rs
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::level_links::SLOT_TYPE_USER_CREATED_ON_SERVER;
+use crate::resource_parse::{
+    COMPRESSED_INTEGERS, ResrcData, ResrcDescriptor, ResrcMethod, ResrcRevision, binary_payload,
+};
+
+/// A level of a player's own save and what its slot says about it
+pub struct SaveSlot {
+    /// the slot ID of a level published on the server, `None` for a level
+    /// that's only in the save
+    pub slot_id: Option<u32>,
+    pub root_level: [u8; 20],
+    pub icon: ResrcDescriptor,
+    pub name: String,
+    pub description: String,
+    pub author: String,
+    /// the background GUID, `None` for the default one or if the slot
+    /// ends before it
+    pub background: Option<u32>,
+}
+
+/// longest name or description we believe, anything longer is a misparse
+const MAX_TEXT_LEN: usize = 4096;
+
+/// Reads a `Slot` struct the way `make_slot_struct` writes it, up to the
+/// background. Ints are LEB128 in resources with compressed integers
+struct SlotReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+    compressed: bool,
+    version: u16,
+}
+
+impl SlotReader<'_> {
+    fn bytes(&mut self, len: usize) -> Option<&[u8]> {
+        let bytes = self.data.get(self.pos..self.pos.checked_add(len)?)?;
+        self.pos += len;
+        Some(bytes)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.bytes(1).map(|b| b[0])
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        if !self.compressed {
+            return self
+                .bytes(4)
+                .map(|b| u32::from_be_bytes(b.try_into().unwrap()));
+        }
+        let mut value: u32 = 0;
+        for i in 0..5 {
+            let byte = self.u8()?;
+            value |= ((byte & 0x7f) as u32).checked_shl(7 * i)?;
+            if byte & 0x80 == 0 {
+                return Some(value);
+            }
+        }
+        None
+    }
+
+    fn len(&mut self) -> Option<usize> {
+        let len = self.u32()? as usize;
+        (len <= MAX_TEXT_LEN).then_some(len)
+    }
+
+    fn str(&mut self) -> Option<String> {
+        let len = self.len()?;
+        String::from_utf8(self.bytes(len)?.to_vec()).ok()
+    }
+
+    fn wstr(&mut self) -> Option<String> {
+        let len = self.len()?;
+        let units: Vec<u16> = self
+            .bytes(len * 2)?
+            .chunks_exact(2)
+            .map(|c| u16::from_be_bytes([c[0], c[1]]))
+            .collect();
+        String::from_utf16(&units).ok()
+    }
+
+    fn descriptor(&mut self) -> Option<Option<ResrcDescriptor>> {
+        let (hash, guid) = match self.version < 0x191 {
+            true => (2, 1),
+            false => (1, 2),
+        };
+        match self.u8()? {
+            0 => Some(None),
+            t if t == hash => Some(Some(ResrcDescriptor::Sha1(
+                self.bytes(20)?.try_into().ok()?,
+            ))),
+            t if t == guid => Some(Some(ResrcDescriptor::Guid(self.u32()?))),
+            _ => None,
+        }
+    }
+
+    /// the handle of a NetworkOnlineID, see `make_onlineid`
+    fn online_id(&mut self) -> Option<String> {
+        let length_prefixed = self.version < 0x234;
+        if length_prefixed && self.u32()? != 16 {
+            return None;
+        }
+        let data = self.bytes(16)?;
+        let end = data.iter().position(|&b| b == 0).unwrap_or(data.len());
+        let handle = std::str::from_utf8(&data[..end]).ok()?.to_string();
+        if !handle.chars().all(|c| c.is_ascii_graphic()) {
+            return None;
+        }
+        self.u8()?; // term
+        if length_prefixed && self.u32()? != 3 {
+            return None;
+        }
+        self.bytes(3)?;
+        Some(handle)
+    }
+}
+
+/// The fields after the description, up to the background
+fn read_background(reader: &mut SlotReader) -> Option<u32> {
+    // primaryLinkLevel and group, both SlotIDs
+    let slot_ids = match reader.version >= 0x134 {
+        true => 2,
+        false => 1,
+    };
+    for _ in 0..slot_ids * 2 {
+        reader.u32()?;
+    }
+    reader.u8()?; // initiallyLocked
+    if reader.version <= 0x237 {
+        return None;
+    }
+    reader.u8()?; // shareable
+    reader.u32()
+}
+
+/// The `SlotID {type, number}` every slot starts with, right before its
+/// rootLevel descriptor at `pos`. The number if it's a published level
+fn published_slot_id(data: &[u8], pos: usize, compressed: bool) -> Option<u32> {
+    let (slot_type, number) = match compressed {
+        // two LEB128s, only the last byte of a value has no continuation bit
+        true => {
+            let mut ends = (0..pos).rev().filter(|&i| data[i] & 0x80 == 0);
+            if ends.next()? != pos - 1 {
+                return None;
+            }
+            let type_end = ends.next()?;
+            let type_start = ends.next().map_or(0, |i| i + 1);
+            let leb128 = |bytes: &[u8]| {
+                let mut reader = SlotReader {
+                    data: bytes,
+                    pos: 0,
+                    compressed,
+                    version: 0,
+                };
+                reader.u32().filter(|_| reader.pos == bytes.len())
+            };
+            (
+                leb128(&data[type_start..=type_end])?,
+                leb128(&data[type_end + 1..pos])?,
+            )
+        }
+        false => {
+            let ids = data.get(pos.checked_sub(8)?..pos)?;
+            (
+                u32::from_be_bytes(ids[..4].try_into().ok()?),
+                u32::from_be_bytes(ids[4..].try_into().ok()?),
+            )
+        }
+    };
+    (slot_type == SLOT_TYPE_USER_CREATED_ON_SERVER && number != 0).then_some(number)
+}
+
+/// Parse a slot whose rootLevel descriptor starts at `pos`
+fn read_slot(
+    data: &[u8],
+    pos: usize,
+    compressed: bool,
+    revision: &ResrcRevision,
+) -> Option<SaveSlot> {
+    let mut reader = SlotReader {
+        data,
+        pos,
+        compressed,
+        version: revision.get_version(),
+    };
+    let Some(ResrcDescriptor::Sha1(root_level)) = reader.descriptor()? else {
+        return None;
+    };
+    if revision.get_subversion() >= 0x145 {
+        reader.descriptor()?; // adventure
+    }
+    let icon = reader.descriptor()?.unwrap_or(ResrcDescriptor::Guid(0));
+    reader.bytes(16)?; // location
+    let mut author = reader.online_id()?;
+    if reader.version >= 0x13b {
+        let name = reader.wstr()?;
+        if author.is_empty() {
+            author = name;
+        }
+    }
+    reader.str()?; // translationTag
+    let name = reader.wstr()?;
+    let description = reader.wstr()?;
+    // the slot is usable without it, a slot that ends early just keeps the
+    // default background
+    let background = read_background(&mut reader).filter(|&guid| guid != 0);
+    Some(SaveSlot {
+        slot_id: published_slot_id(data, pos, compressed),
+        root_level,
+        icon,
+        name,
+        description,
+        author,
+        background,
+    })
+}
+
+/// The slots a save describes its levels with, taken from its profile or
+/// slot lists. The big profile can't be fully decoded, so this looks for
+/// the descriptor of every level in the save inside them and reads a slot
+/// from there, keeping the ones that parse. A level that turns up in
+/// several slots (the profile and a slot list) keeps the first one.
+pub fn find_slots(resources: &BTreeMap<[u8; 20], Vec<u8>>) -> Vec<SaveSlot> {
+    let levels: BTreeSet<[u8; 20]> = resources
+        .iter()
+        .filter(|(_, blob)| blob.starts_with(b"LVL"))
+        .map(|(hash, _)| *hash)
+        .collect();
+
+    let mut slots: BTreeMap<[u8; 20], SaveSlot> = BTreeMap::new();
+    for blob in resources.values() {
+        if !(blob.starts_with(b"BPR") || blob.starts_with(b"SLT")) {
+            continue;
+        }
+        let Ok(ResrcData {
+            method: ResrcMethod::Binary { revision, .. },
+            ..
+        }) = ResrcData::new(blob, false)
+        else {
+            continue;
+        };
+        let Ok(Some((data, flags))) = binary_payload(blob) else {
+            continue;
+        };
+        let compressed = flags & COMPRESSED_INTEGERS != 0;
+
+        for (i, window) in data.windows(21).enumerate() {
+            let Ok(hash) = <[u8; 20]>::try_from(&window[1..]) else {
+                continue;
+            };
+            if slots.contains_key(&hash) || !levels.contains(&hash) {
+                continue;
+            }
+            if let Some(slot) = read_slot(&data, i, compressed, &revision) {
+                slots.insert(hash, slot);
+            }
+        }
+    }
+    slots.into_values().collect()
+}
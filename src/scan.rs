@@ -0,0 +1,246 @@
+// src/scan.rs
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+use walkdir::WalkDir;
+use zip::ZipArchive;
+
+use crate::messages::Msg;
+
+/// names of the resource magics we know about, anything else is reported by magic only
+const RESOURCE_NAMES: [(&[u8; 3], &str); 21] = [
+    (b"LVL", "level"),
+    (b"PLN", "plan"),
+    (b"SLT", "slot list"),
+    (b"ADC", "adventure create profile"),
+    (b"ADS", "adventure shared data"),
+    (b"QST", "quest"),
+    (b"TEX", "texture"),
+    (b"GTF", "gtf texture"),
+    (b"MSH", "mesh"),
+    (b"SMH", "static mesh"),
+    (b"GMT", "gfx material"),
+    (b"MAT", "physics material"),
+    (b"ANM", "animation"),
+    (b"BEV", "bevel"),
+    (b"FSH", "script"),
+    (b"PAL", "palette"),
+    (b"FNT", "font"),
+    (b"BPR", "big profile"),
+    (b"IPR", "local profile"),
+    (b"MUS", "music settings"),
+    (b"INS", "instrument"),
+];
+
+pub(crate) fn resource_name(magic: &[u8]) -> Option<&'static str> {
+    RESOURCE_NAMES
+        .iter()
+        .find(|(m, _)| magic.starts_with(m.as_slice()))
+        .map(|(_, name)| *name)
+}
+
+/// Classify a blob from its first 16 bytes, returns its type key and, for
+/// binary resources, its revision
+pub(crate) fn classify(header: &[u8]) -> (String, Option<String>) {
+    if header.starts_with(b"\x89PNG") {
+        return ("PNG".into(), None);
+    }
+    if header.starts_with(&[0xff, 0xd8]) {
+        return ("JPEG".into(), None);
+    }
+    if header.starts_with(b"FSB") {
+        return ("FSB".into(), None);
+    }
+    let Some(magic) = header.get(..4) else {
+        return ("unknown".into(), None);
+    };
+    if !magic[..3].iter().all(|b| b.is_ascii_alphanumeric()) {
+        return ("unknown".into(), None);
+    }
+
+    let key = String::from_utf8_lossy(magic).into_owned();
+    let revision = match (magic[3], header.get(4..8)) {
+        (b'b' | b'e', Some(head)) => {
+            let head = u32::from_be_bytes(head.try_into().unwrap());
+            // branch info sits after the dependency table offset, see ResrcData::new
+            match header.get(12..16) {
+                Some(branch) if head >= 0x271 && &magic[..3] != b"SMH" => Some(format!(
+                    "0x{:x} ({:02x}{:02x}.{:02x}{:02x})",
+                    head, branch[0], branch[1], branch[2], branch[3]
+                )),
+                _ => Some(format!("0x{:x}", head)),
+            }
+        }
+        _ => None,
+    };
+    (key, revision)
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Tally {
+    pub count: u64,
+    pub bytes: u64,
+}
+
+impl Tally {
+    pub(crate) fn add(&mut self, bytes: u64) {
+        self.count += 1;
+        self.bytes += bytes;
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TypeStats {
+    pub magic: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(flatten)]
+    pub total: Tally,
+    /// binary resources only, keyed by revision
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub revisions: BTreeMap<String, Tally>,
+}
+
+/// see the note on versioning in `models`
+pub const SCAN_REPORT_SCHEMA_VERSION: u32 = 1;
+
+/// The `scan-types` report
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ScanReport {
+    #[serde(default = "crate::models::first_schema_version")]
+    pub schema_version: u32,
+    pub zips_scanned: usize,
+    pub zips_failed: usize,
+    pub entries_seen: u64,
+    pub entries_scanned: u64,
+    /// only every `sample_every`th entry of each zip was looked at
+    pub sample_every: usize,
+    pub types: BTreeMap<String, TypeStats>,
+}
+
+impl Default for ScanReport {
+    fn default() -> Self {
+        Self {
+            schema_version: SCAN_REPORT_SCHEMA_VERSION,
+            zips_scanned: 0,
+            zips_failed: 0,
+            entries_seen: 0,
+            entries_scanned: 0,
+            sample_every: 0,
+            types: BTreeMap::new(),
+        }
+    }
+}
+
+impl ScanReport {
+    fn merge(&mut self, other: ScanReport) {
+        self.zips_scanned += other.zips_scanned;
+        self.zips_failed += other.zips_failed;
+        self.entries_seen += other.entries_seen;
+        self.entries_scanned += other.entries_scanned;
+        for (key, stats) in other.types {
+            let mine = self.types.entry(key).or_insert_with(|| TypeStats {
+                magic: stats.magic.clone(),
+                name: stats.name.clone(),
+                total: Tally::default(),
+                revisions: BTreeMap::new(),
+            });
+            mine.total.count += stats.total.count;
+            mine.total.bytes += stats.total.bytes;
+            for (rev, tally) in stats.revisions {
+                let t = mine.revisions.entry(rev).or_default();
+                t.count += tally.count;
+                t.bytes += tally.bytes;
+            }
+        }
+    }
+}
+
+/// Every resource ZIP below the archive root
+pub fn find_zips(archive_root: &Path) -> Vec<PathBuf> {
+    let mut zips: Vec<PathBuf> = WalkDir::new(archive_root)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .map(|e| e.into_path())
+        .filter(|p| {
+            p.extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("zip"))
+        })
+        .collect();
+    zips.sort();
+    zips
+}
+
+/// Classify every `sample_every`th entry of one ZIP, only the entry headers are decompressed
+pub fn scan_zip(path: &Path, sample_every: usize) -> Result<ScanReport> {
+    let file = File::open(path).map_err(|e| anyhow!("couldn't open {}: {}", path.display(), e))?;
+    let mut archive =
+        ZipArchive::new(file).map_err(|e| anyhow!("{} not a zip: {}", path.display(), e))?;
+
+    let mut report = ScanReport {
+        zips_scanned: 1,
+        sample_every,
+        ..Default::default()
+    };
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i)?;
+        if entry.is_dir() {
+            continue;
+        }
+        report.entries_seen += 1;
+        if !(report.entries_seen - 1).is_multiple_of(sample_every as u64) {
+            continue;
+        }
+
+        let size = entry.size();
+        let mut header = Vec::with_capacity(16);
+        (&mut entry).take(16).read_to_end(&mut header)?;
+        let (key, revision) = classify(&header);
+
+        let stats = report
+            .types
+            .entry(key.clone())
+            .or_insert_with(|| TypeStats {
+                name: resource_name(key.as_bytes()).map(str::to_string),
+                magic: key,
+                total: Tally::default(),
+                revisions: BTreeMap::new(),
+            });
+        stats.total.add(size);
+        if let Some(revision) = revision {
+            stats.revisions.entry(revision).or_default().add(size);
+        }
+        report.entries_scanned += 1;
+    }
+    Ok(report)
+}
+
+/// Fold per-zip reports into one, counting failed zips instead of stopping
+pub fn combine(reports: impl IntoIterator<Item = (PathBuf, Result<ScanReport>)>) -> ScanReport {
+    let mut total = ScanReport::default();
+    for (path, report) in reports {
+        match report {
+            Ok(report) => {
+                total.sample_every = report.sample_every;
+                total.merge(report);
+            }
+            Err(e) => {
+                eprintln!(
+                    "⚠️ {}",
+                    Msg::ScanFailed {
+                        path: &path,
+                        error: &e.to_string(),
+                    }
+                );
+                total.zips_failed += 1;
+            }
+        }
+    }
+    total
+}
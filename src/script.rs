@@ -0,0 +1,86 @@
+// src/script.rs
+
+use std::path::Path;
+use std::rc::Rc;
+
+use anyhow::Result;
+use rhai::{Array, Dynamic, Engine, EvalAltResult, Map};
+
+use crate::db::{
+    GUID_DEPENDENCY_PREFIX, SlotRef, get_creator_levels, get_slot_info, open_db, search_slots,
+};
+use crate::resource_parse::ResrcDescriptor;
+
+/// most levels `search` returns
+pub const SEARCH_LIMIT: usize = 1000;
+
+pub type ScriptResult<T> = Result<T, Box<EvalAltResult>>;
+
+/// An error of a command, for the script to catch or to end it with
+pub fn script_error(e: impl std::fmt::Display) -> Box<EvalAltResult> {
+    e.to_string().into()
+}
+
+/// The level IDs in an array a script passed
+pub fn level_ids(ids: Array) -> ScriptResult<Vec<u32>> {
+    ids.into_iter()
+        .map(|id| {
+            id.as_int()
+                .ok()
+                .and_then(|id| u32::try_from(id).ok())
+                .ok_or_else(|| script_error("level IDs are whole numbers"))
+        })
+        .collect()
+}
+
+fn level_id(id: i64) -> ScriptResult<u32> {
+    u32::try_from(id).map_err(|_| script_error(format!("{} isn't a level ID", id)))
+}
+
+/// A script engine with the functions that read the database:
+///
+/// - `search(text)`: IDs of levels whose name has `text` in it
+/// - `levels_by(np_handle)`: IDs of a creator's levels
+/// - `level(id)`: a map of a level's name, creator, game, root level and icon
+///
+/// The commands that fetch and write are added by the caller
+pub fn engine(database_path: &Path) -> Result<Engine> {
+    let conn = Rc::new(open_db(database_path)?);
+    let mut engine = Engine::new();
+
+    let db = conn.clone();
+    engine.register_fn("search", move |text: &str| -> ScriptResult<Array> {
+        let ids = search_slots(&db, text, SEARCH_LIMIT).map_err(script_error)?;
+        Ok(ids.into_iter().map(Dynamic::from).collect())
+    });
+
+    let db = conn.clone();
+    engine.register_fn("levels_by", move |np_handle: &str| -> ScriptResult<Array> {
+        let levels = get_creator_levels(&db, np_handle).map_err(script_error)?;
+        Ok(levels
+            .into_iter()
+            .map(|level| Dynamic::from(level.id as i64))
+            .collect())
+    });
+
+    let db = conn;
+    engine.register_fn("level", move |id: i64| -> ScriptResult<Map> {
+        let info = get_slot_info(SlotRef::Id(level_id(id)?.into()), &db).map_err(script_error)?;
+        let mut level = Map::new();
+        level.insert("id".into(), info.id.into());
+        level.insert("name".into(), info.name.into());
+        level.insert("creator".into(), info.np_handle.into());
+        level.insert("game".into(), info.game.get_short_title().into());
+        level.insert("root_level".into(), hex::encode(info.root_level).into());
+        level.insert(
+            "icon".into(),
+            match info.icon {
+                ResrcDescriptor::Sha1(sha1) => hex::encode(sha1).into(),
+                ResrcDescriptor::Guid(guid) => format!("{}{}", GUID_DEPENDENCY_PREFIX, guid).into(),
+            },
+        );
+        Ok(level)
+    });
+
+    Ok(engine)
+}
@@ -0,0 +1,290 @@
+// src/self_test.rs
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use anyhow::{Result, bail};
+use sha1::{Digest, Sha1};
+
+use crate::db::{GameVersion, LevelType, SlotInfo};
+use crate::language::Language;
+use crate::progress;
+use crate::resource_parse::{ResrcDescriptor, ResrcRevision};
+use crate::serializers::lbp::{make_savearchive, make_slotlist};
+use crate::serializers::ps3::{SfoFields, make_pfd, make_sfo};
+use crate::serializers::text::TextLimits;
+
+/// files every case is expected to produce, `slotlist.bin` is the raw
+/// slotlist before it goes into the save archive
+pub const OUTPUT_FILES: [&str; 4] = ["slotlist.bin", "0", "PARAM.SFO", "PARAM.PFD"];
+
+macro_rules! golden {
+    ($case:literal) => {
+        [
+            include_bytes!(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/golden/",
+                $case,
+                "/slotlist.bin"
+            ))
+            .as_slice(),
+            include_bytes!(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/golden/",
+                $case,
+                "/0"
+            ))
+            .as_slice(),
+            include_bytes!(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/golden/",
+                $case,
+                "/PARAM.SFO"
+            ))
+            .as_slice(),
+            include_bytes!(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/golden/",
+                $case,
+                "/PARAM.PFD"
+            ))
+            .as_slice(),
+        ]
+    };
+}
+
+/// One synthetic backup, serialized the same way `bkp` does it
+pub struct GoldenCase {
+    pub name: &'static str,
+    revision: ResrcRevision,
+    game: GameVersion,
+    adventure: bool,
+    /// recorded outputs, in `OUTPUT_FILES` order
+    golden: [&'static [u8]; 4],
+}
+
+pub const CASES: [GoldenCase; 4] = [
+    GoldenCase {
+        name: "lbp1",
+        revision: ResrcRevision {
+            head: 0x272,
+            branch_id: 0x4c44,
+            branch_revision: 0x17,
+        },
+        game: GameVersion::Lbp1,
+        adventure: false,
+        golden: golden!("lbp1"),
+    },
+    GoldenCase {
+        name: "lbp2",
+        revision: ResrcRevision {
+            head: 0x3f8,
+            branch_id: 0,
+            branch_revision: 0,
+        },
+        game: GameVersion::Lbp2,
+        adventure: false,
+        golden: golden!("lbp2"),
+    },
+    GoldenCase {
+        name: "lbp3",
+        revision: ResrcRevision {
+            head: 0x021803f9,
+            branch_id: 0,
+            branch_revision: 0,
+        },
+        game: GameVersion::Lbp3,
+        adventure: false,
+        golden: golden!("lbp3"),
+    },
+    GoldenCase {
+        name: "lbp3_adventure",
+        revision: ResrcRevision {
+            head: 0x021803f9,
+            branch_id: 0,
+            branch_revision: 0,
+        },
+        game: GameVersion::Lbp3,
+        adventure: true,
+        golden: golden!("lbp3_adventure"),
+    },
+];
+
+struct Fixture {
+    resources: BTreeMap<[u8; 20], Vec<u8>>,
+    root: [u8; 20],
+    icon: [u8; 20],
+}
+
+/// Small fake resources, the serializers only care about their hashes and sizes
+fn fixture_resources(case: &GoldenCase) -> Fixture {
+    let root_magic: &[u8] = if case.adventure { b"ADCb" } else { b"LVLb" };
+    let root = [
+        root_magic,
+        &case.revision.head.to_be_bytes(),
+        b"synthetic root level",
+    ]
+    .concat();
+    let icon = b"TEX synthetic icon".to_vec();
+    let sticker = [
+        b"PLNb".as_slice(),
+        &case.revision.head.to_be_bytes(),
+        b"synthetic sticker",
+    ]
+    .concat();
+
+    let root_hash: [u8; 20] = Sha1::digest(&root).into();
+    let icon_hash: [u8; 20] = Sha1::digest(&icon).into();
+    let resources = [root, icon, sticker]
+        .into_iter()
+        .map(|blob| (Sha1::digest(&blob).into(), blob))
+        .collect();
+    Fixture {
+        resources,
+        root: root_hash,
+        icon: icon_hash,
+    }
+}
+
+fn fixture_slot(case: &GoldenCase, root: [u8; 20], icon: [u8; 20]) -> SlotInfo {
+    SlotInfo {
+        id: 1234,
+        guid: None,
+        name: "Self Test Level".to_string(),
+        description: "A synthetic level used to check the backup serializers".to_string(),
+        np_handle: "selftest".to_string(),
+        root_level: root,
+        icon: ResrcDescriptor::Sha1(icon),
+        game: case.game,
+        initially_locked: false,
+        is_sub_level: false,
+        background_guid: Some(0x1234),
+        shareable: true,
+        author_labels: Vec::new(),
+        leveltype: LevelType::Versus,
+        min_players: Some(1),
+        max_players: Some(4),
+        enforce_min_max_players: false,
+        same_screen_game: false,
+        is_adventure_planet: case.adventure,
+        planet_decorations: None,
+        adventure_shared: match case.adventure {
+            true => vec![(ResrcDescriptor::Guid(0x5202), 31)],
+            false => Vec::new(),
+        },
+    }
+}
+
+/// Serialize a case into a scratch folder and return its outputs, in `OUTPUT_FILES` order
+pub fn render(case: &GoldenCase) -> Result<Vec<Vec<u8>>> {
+    static RUN: AtomicUsize = AtomicUsize::new(0);
+    let dir: PathBuf = std::env::temp_dir().join(format!(
+        "archive_dl_self_test_{}_{}_{}",
+        std::process::id(),
+        RUN.fetch_add(1, Ordering::Relaxed),
+        case.name
+    ));
+    fs::create_dir_all(&dir)?;
+
+    let result = (|| -> Result<Vec<Vec<u8>>> {
+        let Fixture {
+            mut resources,
+            root,
+            icon,
+        } = fixture_resources(case);
+        let slot_info = fixture_slot(case, root, icon);
+        let bkp_name = format!("{}LEVEL{:08X}", case.game.get_titleid(), slot_info.id);
+
+        let slt = make_slotlist(&case.revision, &slot_info, &TextLimits::default())?;
+        let slt_hash: [u8; 20] = Sha1::digest(&slt).into();
+        resources.insert(slt_hash, slt.clone());
+
+        make_savearchive(&case.revision, slt_hash, resources, &dir, &progress::Silent)?;
+        let sfo = make_sfo(
+            &slot_info,
+            &bkp_name,
+            &dir,
+            &case.game,
+            Language::default(),
+            &SfoFields::default(),
+            &TextLimits::default(),
+        )?;
+        let pfd_version = if case.game == GameVersion::Lbp3 { 4 } else { 3 };
+        make_pfd(pfd_version, sfo, &dir)?;
+
+        let mut names: Vec<String> = fs::read_dir(&dir)?
+            .map(|e| Ok(e?.file_name().to_string_lossy().into_owned()))
+            .collect::<Result<_>>()?;
+        names.sort();
+        let mut expected: Vec<String> = OUTPUT_FILES[1..].iter().map(|s| s.to_string()).collect();
+        expected.sort();
+        if names != expected {
+            bail!("expected files {:?}, got {:?}", expected, names);
+        }
+
+        let mut outputs = vec![slt];
+        for file in &OUTPUT_FILES[1..] {
+            outputs.push(fs::read(dir.join(file))?);
+        }
+        Ok(outputs)
+    })();
+
+    let _ = fs::remove_dir_all(&dir);
+    result
+}
+
+/// Describe how `actual` differs from `expected`, None if they match
+pub fn diff(file: &str, expected: &[u8], actual: &[u8]) -> Option<String> {
+    if expected == actual {
+        return None;
+    }
+    let at = expected
+        .iter()
+        .zip(actual)
+        .position(|(a, b)| a != b)
+        .unwrap_or(expected.len().min(actual.len()));
+    Some(format!(
+        "{} differs at byte 0x{:x} (expected {} bytes, got {})",
+        file,
+        at,
+        expected.len(),
+        actual.len()
+    ))
+}
+
+/// Check every case against the outputs recorded when this build was made
+pub fn run() -> Result<()> {
+    let mut failed = 0;
+    for case in &CASES {
+        let problems = match render(case) {
+            Ok(outputs) => OUTPUT_FILES
+                .iter()
+                .zip(case.golden.iter().zip(&outputs))
+                .filter_map(|(file, (expected, actual))| diff(file, expected, actual))
+                .collect(),
+            Err(e) => vec![format!("failed to serialize: {}", e)],
+        };
+
+        if problems.is_empty() {
+            println!("ok      {}", case.name);
+        } else {
+            failed += 1;
+            println!("FAILED  {}", case.name);
+            for problem in problems {
+                println!("        {}", problem);
+            }
+        }
+    }
+
+    if failed > 0 {
+        bail!(
+            "{} of {} self-test cases failed, backups made with this build may not load",
+            failed,
+            CASES.len()
+        );
+    }
+    println!("All {} self-test cases passed", CASES.len());
+    Ok(())
+}
@@ -1,5 +1,9 @@
 mod save_archive;
 mod slot_list;
 
-pub use save_archive::make_savearchive;
+pub use save_archive::{
+    archive_size, build_archive, make_savearchive, seal_chunks, write_index, write_resources, ArchiveEntry, CHUNK_SIZE,
+    MAX_ARCHIVE_SIZE,
+};
+pub(crate) use save_archive::{HASHINATE_KEY, TEA_KEY};
 pub use slot_list::make_slotlist;
\ No newline at end of file
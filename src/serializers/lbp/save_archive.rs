@@ -2,12 +2,14 @@ use std::{collections::BTreeMap, fs::File, io::Write, path::Path};
 
 use byteorder::{BigEndian, WriteBytesExt};
 use hmac::Mac;
-use anyhow::Result;
+use anyhow::{bail, Result};
+use sha1::{Digest, Sha1};
 
-use crate::{resource_parse::ResrcRevision, serializers::HmacSha1, xxtea};
+use crate::progress::{ProgressObserver, Stage};
+use crate::{extract_pool, metrics, resource_parse::ResrcRevision, serializers::HmacSha1, xxtea};
 
-const TEA_KEY: [u32; 4] = [0x1B70CBD, 0x149607D6, 0x7F94DD5, 0x10DB8CA0];
-const HASHINATE_KEY: [u8; 64] = [
+pub(crate) const TEA_KEY: [u32; 4] = [0x1B70CBD, 0x149607D6, 0x7F94DD5, 0x10DB8CA0];
+pub(crate) const HASHINATE_KEY: [u8; 64] = [
     0x2A, 0xFD, 0xA3, 0xCA, 0x86, 0x02, 0x19, 0xB3,
     0xE6, 0x8A, 0xFF, 0xCC, 0x82, 0xC7, 0x6B, 0x8A,
     0xFE, 0x0A, 0xD8, 0x13, 0x5F, 0x60, 0x47, 0x5B,
@@ -17,43 +19,75 @@ const HASHINATE_KEY: [u8; 64] = [
     0xE7, 0x42, 0x45, 0x3B, 0x2B, 0xB5, 0x3E, 0x16,
     0xC9, 0x58, 0x19, 0x7B, 0xE7, 0x18, 0xC0, 0x80
 ];
-const CHUNK_SIZE: usize = 0x240000;
+/// size of the encrypted chunks (`0`, `1`, …) a backup is split into
+pub const CHUNK_SIZE: usize = 0x240000;
+/// FAR4 offsets and sizes are 32-bit, nothing past this can be addressed
+pub const MAX_ARCHIVE_SIZE: u64 = u32::MAX as u64;
+
+/// save key, then FAT entries of `sha1, offset, size`, then hashinate, count and magic
+const SAVE_KEY_SIZE: u64 = 0x84;
+const FAT_ENTRY_SIZE: u64 = 0x1c;
+const FOOTER_SIZE: u64 = 0x1c;
+
+/// Where a resource is in the archive, one entry of the FAT
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArchiveEntry {
+    pub sha1: [u8; 20],
+    pub offset: u32,
+    pub size: u32,
+}
 
-struct ArchiveEntry {
-    sha1: [u8; 20],
-    offset: u32,
-    size: u32,
+/// Check that every blob still hashes to its key. A mismatch means something
+/// changed a blob after it was fetched, and the game would load the wrong
+/// data under that hash, so the backup isn't written at all
+fn verify_hashes(hashes: &BTreeMap<[u8; 20], Vec<u8>>) -> Result<()> {
+    let entries: Vec<_> = hashes.iter().collect();
+    let per_thread = entries.len().div_ceil(extract_pool::cpu().threads()).max(1);
+    let changed: Vec<[u8; 20]> = std::thread::scope(|s| {
+        let workers: Vec<_> = entries.chunks(per_thread).map(|part| s.spawn(move || {
+            part.iter()
+                .filter(|(hash, blob)| Sha1::digest(blob).as_slice() != hash.as_slice())
+                .map(|(hash, _)| **hash)
+                .collect::<Vec<_>>()
+        })).collect();
+        workers.into_iter().flat_map(|w| w.join().expect("hashing thread panicked")).collect()
+    });
+
+    if let Some(first) = changed.first() {
+        bail!(
+            "refusing to write the backup: {} resources no longer match their SHA1 (first {}), they were changed after being fetched. This is a bug, please report it",
+            changed.len(), hex::encode(first)
+        );
+    }
+    Ok(())
 }
 
-pub fn make_savearchive(
-    rev: &ResrcRevision,
-    slt_hash: [u8; 20],
-    hashes: BTreeMap<[u8; 20], Vec<u8>>,
-    bkp_dir: &Path
-) -> Result<()> {
-    let mut arc = Vec::new();
-    let mut entries = Vec::new();
+/// How big the save archive of these resources comes out, all chunks together
+pub fn archive_size(hashes: &BTreeMap<[u8; 20], Vec<u8>>) -> u64 {
+    let data: u64 = hashes.values().map(|blob| blob.len() as u64).sum();
+    data.next_multiple_of(4) + SAVE_KEY_SIZE + FAT_ENTRY_SIZE * hashes.len() as u64 + FOOTER_SIZE
+}
 
+/// Write the resources one after the other, padded to 4 bytes, and return their FAT entries
+pub fn write_resources(arc: &mut Vec<u8>, hashes: &BTreeMap<[u8; 20], Vec<u8>>) -> Vec<ArchiveEntry> {
+    let mut entries = Vec::with_capacity(hashes.len());
     for (hash, resource) in hashes {
-        let offset = arc.len();
-
-        arc.write_all(&resource)?;
-
         entries.push(ArchiveEntry {
-            sha1: hash,
-            offset: offset as u32,
+            sha1: *hash,
+            offset: arc.len() as u32,
             size: resource.len() as u32,
         });
+        arc.extend_from_slice(resource);
     }
 
     // align to 4 byte boundary
-    let mut pad = arc.len() % 4;
-    if pad != 0 {
-        pad = 4 - pad;
-    }
-
-    arc.write_all(&b"\0".repeat(pad))?;
+    arc.resize(arc.len().next_multiple_of(4), 0);
+    entries
+}
 
+/// Append the save key of a slot list backup, the FAT of `entries` and the
+/// FAR4 footer, then sign everything with the hashinate
+pub fn write_index(arc: &mut Vec<u8>, rev: &ResrcRevision, slt_hash: [u8; 20], entries: &[ArchiveEntry]) -> Result<()> {
     // save key
     arc.write_u32::<BigEndian>(rev.head)?;
     arc.write_u16::<BigEndian>(rev.branch_id)?;
@@ -67,7 +101,7 @@ pub fn make_savearchive(
     arc.write_all(&[0u8; 0x4 * 0xa])?; // deprecated3 int[10]
 
     // fat entries
-    for entry in &entries {
+    for entry in entries {
         arc.write_all(&entry.sha1)?;
         arc.write_u32::<BigEndian>(entry.offset)?;
         arc.write_u32::<BigEndian>(entry.size)?;
@@ -80,20 +114,71 @@ pub fn make_savearchive(
     arc.write_all(b"FAR4")?;
 
     let mut mac = HmacSha1::new_from_slice(&HASHINATE_KEY)?;
-    mac.update(&arc);
-    (&mut arc[hashinate_offset..hashinate_offset + 0x14]).write_all(&mac.finalize().into_bytes())?;
+    mac.update(arc);
+    arc[hashinate_offset..hashinate_offset + 0x14].copy_from_slice(&mac.finalize().into_bytes());
+    Ok(())
+}
+
+/// The unencrypted save archive of these resources, as `make_savearchive` writes it
+pub fn build_archive(rev: &ResrcRevision, slt_hash: [u8; 20], hashes: &BTreeMap<[u8; 20], Vec<u8>>) -> Result<Vec<u8>> {
+    let size = archive_size(hashes);
+    if size > MAX_ARCHIVE_SIZE {
+        bail!("the save archive would be {} bytes, FAR4 can't address more than {}", size, MAX_ARCHIVE_SIZE);
+    }
 
-    let last_chunk_idx = arc.len() / CHUNK_SIZE;
-    for (i, chunk) in arc.chunks_mut(CHUNK_SIZE).enumerate() {
+    let mut arc = Vec::with_capacity(size as usize);
+    let entries = write_resources(&mut arc, hashes);
+    write_index(&mut arc, rev, slt_hash, &entries)?;
+    Ok(arc)
+}
+
+/// xxtea-encrypt an archive in place as chunks of `chunk_size` bytes, the
+/// game's are `CHUNK_SIZE`, leaving the footer magic of the last chunk in the
+/// clear. `chunk_size` has to be a multiple of 4, like the archive's length
+pub fn seal_chunks(arc: &mut [u8], chunk_size: usize) {
+    let last_chunk_idx = arc.len().div_ceil(chunk_size).saturating_sub(1);
+    for (i, chunk) in arc.chunks_mut(chunk_size).enumerate() {
         let mut xxtea_end = chunk.len();
         if i == last_chunk_idx {
             xxtea_end -= 4;
         }
         xxtea::encrypt(&TEA_KEY, &mut chunk[..xxtea_end]);
+    }
+}
 
+/// Write the chunk files of the save archive into `bkp_dir`, telling
+/// `progress` which stage it's in
+pub fn make_savearchive(
+    rev: &ResrcRevision,
+    slt_hash: [u8; 20],
+    hashes: BTreeMap<[u8; 20], Vec<u8>>,
+    bkp_dir: &Path,
+    progress: &dyn ProgressObserver
+) -> Result<()> {
+    metrics::timed(&metrics::WRITE_TIME, || write_savearchive(rev, slt_hash, hashes, bkp_dir, progress))
+}
+
+fn write_savearchive(
+    rev: &ResrcRevision,
+    slt_hash: [u8; 20],
+    hashes: BTreeMap<[u8; 20], Vec<u8>>,
+    bkp_dir: &Path,
+    progress: &dyn ProgressObserver
+) -> Result<()> {
+    progress.on_stage_changed(Stage::Verifying);
+    verify_hashes(&hashes)?;
+    progress.on_stage_changed(Stage::Packing);
+    let mut arc = build_archive(rev, slt_hash, &hashes)?;
+    drop(hashes); // the blobs are in `arc` now
+
+    progress.on_stage_changed(Stage::Encrypting);
+    seal_chunks(&mut arc, CHUNK_SIZE);
+    let chunks = arc.len().div_ceil(CHUNK_SIZE);
+    for (i, chunk) in arc.chunks(CHUNK_SIZE).enumerate() {
+        progress.on_stage_changed(Stage::WritingChunk { chunk: i, chunks });
         let mut file = File::create(bkp_dir.join(i.to_string()))?;
         file.write_all(chunk)?;
     }
-    
+
     Ok(())
-}
\ No newline at end of file
+}
@@ -3,7 +3,10 @@ use std::io::Write;
 use byteorder::{BigEndian, WriteBytesExt};
 use anyhow::Result;
 
-use crate::{db::{GameVersion, LevelType, SlotInfo}, labels::LBP2_LABELS, resource_parse::ResrcRevision, ResrcDescriptor};
+use crate::{db::{GameVersion, LevelType, SlotInfo}, labels::LBP2_LABELS, resource_parse::{Branch, ResrcRevision}, serializers::text::{sanitize, TextLimits}, ResrcDescriptor};
+
+/// NetworkOnlineID holds this many bytes of the npHandle
+const ONLINE_ID_LEN: usize = 16;
 
 fn make_wstr(slt: &mut Vec<u8>, string: &str) -> Result<()> {
     let wide_string: Vec<u16> = string.encode_utf16().collect();
@@ -27,8 +30,10 @@ fn make_onlineid(slt: &mut Vec<u8>, rev: &ResrcRevision, np_handle: &str) -> Res
         slt.write_u32::<BigEndian>(16)?;
     }
 
-    let mut data = [0u8; 16];
-    data[..np_handle.len()].copy_from_slice(np_handle.as_bytes());
+    // PSN handles fit, handles of other servers may not
+    let mut data = [0u8; ONLINE_ID_LEN];
+    let handle = &np_handle.as_bytes()[..np_handle.len().min(ONLINE_ID_LEN)];
+    data[..handle.len()].copy_from_slice(handle);
     slt.write_all(&data)?;
 
     slt.write_u8(0)?; // term
@@ -78,7 +83,8 @@ fn make_res_descriptor(
 fn make_slot_struct(
     slt: &mut Vec<u8>,
     rev: &ResrcRevision,
-    slot_info: &SlotInfo
+    slot_info: &SlotInfo,
+    limits: &TextLimits
 ) -> Result<Vec<(ResrcDescriptor, u32)>> {
     let mut dependencies = Vec::new();
     let version = rev.get_version();
@@ -110,17 +116,18 @@ fn make_slot_struct(
     }
 
     // authorID, NetworkOnlineID struct
-    make_onlineid(slt, rev, &slot_info.np_handle)?;
+    let np_handle = sanitize(&slot_info.np_handle, false);
+    make_onlineid(slt, rev, &np_handle)?;
 
     // authorName
     if version >= 0x13b {
-        make_wstr(slt, &slot_info.np_handle)?;
+        make_wstr(slt, &np_handle)?;
     }
 
     make_str(slt, "")?; // translationTag
 
-    make_wstr(slt, &slot_info.name)?;
-    make_wstr(slt, &slot_info.description)?;
+    make_wstr(slt, &limits.name(&slot_info.name))?;
+    make_wstr(slt, &limits.description(&slot_info.description))?;
 
     // primaryLinkLevel, SlotID, shouldn't matter?
     slt.write_u32::<BigEndian>(0)?; // DEVELOPER
@@ -140,7 +147,8 @@ fn make_slot_struct(
     }
 
     if version > 0x333 {
-        make_res_descriptor(slt, rev, &mut dependencies, None, 38)?; // planetDecorations
+        let decorations = slot_info.planet_decorations.map(ResrcDescriptor::Sha1);
+        make_res_descriptor(slt, rev, &mut dependencies, decorations, 38)?; // planetDecorations
     }
 
     if version < 0x188 {
@@ -210,7 +218,7 @@ fn make_slot_struct(
     slt.write_u8(slot_info.max_players.unwrap_or(4))?;
 
     if subversion >= 0x215 {
-        slt.write_u8(false as u8)?; // enforceMinMaxPlayers
+        slt.write_u8(slot_info.enforce_min_max_players as u8)?;
     }
 
     if version >= 0x3d0 {
@@ -233,12 +241,13 @@ fn make_slot_struct(
     }
 
     if subversion >= 0x12 {
-        // gameMode
-        slt.write_u8(match slot_info.leveltype {
-            LevelType::Cooperative => 0,
-            LevelType::Versus => 1,
-            LevelType::Cutscene => 2,
-        })?; 
+        // gameMode, an adventure's levels have their own, the adventure itself has none
+        slt.write_u8(match (slot_info.is_adventure_planet, &slot_info.leveltype) {
+            (true, _) => 0,
+            (false, LevelType::Cooperative) => 0,
+            (false, LevelType::Versus) => 1,
+            (false, LevelType::Cutscene) => 2,
+        })?;
     }
 
     if subversion >= 0xd2 {
@@ -248,8 +257,10 @@ fn make_slot_struct(
     if subversion >= 0x11b {
         make_wstr(slt, "")?; // entranceName
         // originalSlotID, SlotID struct
+        // story/DLC slots point back at the developer slot they came from, the
+        // backup itself stays a FAKE slot so it doesn't replace the real one
         slt.write_u32::<BigEndian>(0)?; // slot type, DEVELOPER
-        slt.write_u32::<BigEndian>(0)?; // slot id
+        slt.write_u32::<BigEndian>(slot_info.guid.unwrap_or(0))?; // slot id
     }
 
     if subversion >= 0x153 {
@@ -266,7 +277,7 @@ fn make_slot_struct(
     Ok(dependencies)
 }
 
-pub fn make_slotlist(rev: &ResrcRevision, slot_info: &SlotInfo) -> Result<Vec<u8>> {
+pub fn make_slotlist(rev: &ResrcRevision, slot_info: &SlotInfo, limits: &TextLimits) -> Result<Vec<u8>> {
     let mut slt = Vec::new();
 
     // resource header crap
@@ -284,7 +295,7 @@ pub fn make_slotlist(rev: &ResrcRevision, slot_info: &SlotInfo) -> Result<Vec<u8
                 slt.write_u16::<BigEndian>(rev.branch_revision)?;
             }
 
-            if rev.head >= 0x297 || (rev.head == 0x272 && rev.branch_id == 0x4c44) && rev.branch_revision >= 0x2 {
+            if rev.head >= 0x297 || (rev.head == 0x272 && rev.get_branch() == Some(Branch::Leerdammer)) && rev.branch_revision >= 0x2 {
                 // compression flags
                 slt.write_u8(0)?;
             }
@@ -300,7 +311,15 @@ pub fn make_slotlist(rev: &ResrcRevision, slot_info: &SlotInfo) -> Result<Vec<u8
     // slot struct count, we just need one
     slt.write_u32::<BigEndian>(1)?;
 
-    let dependencies = make_slot_struct(&mut slt, rev, slot_info)?;
+    let mut dependencies = make_slot_struct(&mut slt, rev, slot_info, limits)?;
+
+    // adventure shared data (quests etc) isn't referenced by the slot struct itself,
+    // but it has to be in the dependency table so it gets loaded with the adventure
+    for (desc, resrc_type) in &slot_info.adventure_shared {
+        if !dependencies.iter().any(|(d, _)| d == desc) {
+            dependencies.push((*desc, *resrc_type));
+        }
+    }
 
     if rev.get_version() >= 0x3b6 {
         slt.write_u8(true as u8)?; // fromProductionBuild
@@ -3,5 +3,6 @@ use sha1::Sha1;
 
 pub mod lbp;
 pub mod ps3;
+pub mod text;
 
 type HmacSha1 = Hmac<Sha1>;
\ No newline at end of file
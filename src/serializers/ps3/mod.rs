@@ -1,5 +1,5 @@
 mod sfo;
 mod pfd;
 
-pub use sfo::make_sfo;
+pub use sfo::{make_sfo, SfoFields, TargetConsole};
 pub use pfd::make_pfd;
\ No newline at end of file
@@ -1,9 +1,149 @@
 use std::{fs::File, io::Write, path::Path};
 
-use crate::db::{GameVersion, SlotInfo};
+use crate::{db::{GameVersion, SlotInfo}, language::Language, serializers::text::{sanitize, truncate_bytes, TextLimits}};
 
 use byteorder::{LittleEndian, WriteBytesExt};
-use anyhow::Result;
+use anyhow::{anyhow, bail, Result};
+use chrono::Utc;
+use clap::ValueEnum;
+use serde::Deserialize;
+
+const SUB_TITLE_MAX: u32 = 128;
+const DETAIL_MAX: u32 = 1024;
+/// PARENTAL_LEVEL goes from 0 (everyone) to 11
+const PARENTAL_LEVEL_MAX: u32 = 11;
+
+/// ATTRIBUTE bit that stops the XMB from copying the save
+const ATTRIBUTE_COPY_PROTECTED: u32 = 1;
+/// ACCOUNT_ID of a save that isn't bound to a PSN account
+const UNBOUND_ACCOUNT_ID: &str = "0000000000000000";
+
+/// Where the backups are going to be imported, for the ATTRIBUTE and
+/// ACCOUNT_ID that setup wants. The consoles copy saves through the XMB,
+/// which refuses copy protected ones
+#[derive(Debug, PartialEq, Clone, Copy, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TargetConsole {
+    /// RPCS3, which takes any save, `copy_protected` ones included
+    Rpcs3,
+    /// a PS3 with custom firmware, copying unbound saves from USB
+    Cfw,
+    /// a PS3 on official firmware with HEN, which wants saves bound to the
+    /// account they're copied to, `sfo.account_id`
+    Hen,
+}
+
+impl TargetConsole {
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Self::Rpcs3 => "rpcs3",
+            Self::Cfw => "cfw",
+            Self::Hen => "hen",
+        }
+    }
+}
+
+/// Placeholders the templates can use
+const PLACEHOLDERS: [&str; 6] = ["name", "creator", "description", "game", "id", "date"];
+
+/// SFO fields from config.yml, so backups can be branded without rebuilding.
+/// The templates fill in `{name}`, `{creator}`, `{description}`, `{game}`,
+/// `{id}` and `{date}` (the day of the backup), `{{` and `}}` are literal braces
+#[derive(Debug, Default, Clone, Deserialize)]
+pub struct SfoFields {
+    /// SUB_TITLE, defaults to "<name> by <creator>" in the backup language
+    #[serde(default)]
+    pub sub_title: Option<String>,
+    /// DETAIL, defaults to the level description
+    #[serde(default)]
+    pub detail: Option<String>,
+    /// PARENTAL_LEVEL, left out unless set
+    #[serde(default)]
+    pub parental_level: Option<u32>,
+    /// whether the save is bound to `account_id` and can be `copy_protected`,
+    /// `--target-console` overrides it
+    #[serde(default)]
+    pub target_console: Option<TargetConsole>,
+    /// set the ATTRIBUTE bit that stops the XMB from copying the save, off
+    /// unless set. Only RPCS3 imports such saves
+    #[serde(default)]
+    pub copy_protected: Option<bool>,
+    /// ACCOUNT_ID to bind the saves to, the 16 hex digits of the PSN account.
+    /// Saves are only bound for `hen` or when this is set without a target console
+    #[serde(default)]
+    pub account_id: Option<String>,
+}
+
+impl SfoFields {
+    /// Check the templates, parental level and target console, so a typo
+    /// fails before any download
+    pub fn validate(&self) -> Result<()> {
+        for (key, template) in [("sub_title", &self.sub_title), ("detail", &self.detail)] {
+            if let Some(template) = template {
+                fill_template(template, |_| Some(String::new()))
+                    .map_err(|e| anyhow!("sfo.{}: {}", key, e))?;
+            }
+        }
+        if let Some(level) = self.parental_level && level > PARENTAL_LEVEL_MAX {
+            bail!("sfo.parental_level must be 0 to {}, not {}", PARENTAL_LEVEL_MAX, level);
+        }
+        if let Some(id) = &self.account_id && (id.len() != 16 || !id.bytes().all(|b| b.is_ascii_hexdigit())) {
+            bail!("sfo.account_id must be 16 hex digits, not `{}`", id);
+        }
+        if let Some(target @ (TargetConsole::Cfw | TargetConsole::Hen)) = self.target_console && self.copy_protected == Some(true) {
+            bail!("a {} PS3 copies saves through the XMB, which refuses copy protected ones, unset sfo.copy_protected", target.get_name());
+        }
+        self.account_binding()?;
+        Ok(())
+    }
+
+    /// The ATTRIBUTE the backups get
+    pub fn attribute(&self) -> u32 {
+        match self.copy_protected {
+            Some(true) => ATTRIBUTE_COPY_PROTECTED,
+            Some(false) | None => 0,
+        }
+    }
+
+    /// The ACCOUNT_ID the backups get, unbound unless the target console
+    /// wants them bound
+    pub fn account_binding(&self) -> Result<&str> {
+        match (self.target_console, &self.account_id) {
+            (Some(TargetConsole::Hen), None) => bail!("target console hen binds the saves to a PSN account, set sfo.account_id"),
+            (Some(TargetConsole::Hen) | None, Some(id)) => Ok(id),
+            (Some(TargetConsole::Rpcs3 | TargetConsole::Cfw) | None, _) => Ok(UNBOUND_ACCOUNT_ID),
+        }
+    }
+}
+
+/// Replace `{placeholder}`s in `template` with what `value` gives for them
+fn fill_template(template: &str, value: impl Fn(&str) -> Option<String>) -> Result<String> {
+    let mut out = String::new();
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => { chars.next(); out.push('{'); },
+            '}' if chars.peek() == Some(&'}') => { chars.next(); out.push('}'); },
+            '{' => {
+                let mut name = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => name.push(c),
+                        None => bail!("unclosed `{{` in `{}`, write `{{{{` for a literal one", template),
+                    }
+                }
+                match value(&name) {
+                    Some(v) if PLACEHOLDERS.contains(&name.as_str()) => out.push_str(&v),
+                    _ => bail!("unknown placeholder `{{{}}}` in `{}`, use one of {{{}}}", name, template, PLACEHOLDERS.join("}, {")),
+                }
+            },
+            '}' => bail!("unmatched `}}` in `{}`, write `}}}}` for a literal one", template),
+            c => out.push(c),
+        }
+    }
+    Ok(out)
+}
 
 enum DataFormat<'a> {
     Array(u32, &'a [u8]),
@@ -26,13 +166,8 @@ impl DataFormat<'_> {
                 assert!(a.len() as u32 <= *max);
                 a.to_vec()
             },
-            Self::String(max, s) => {
-                if s.len() >= *max as usize {
-                    format!("{}...\0", s.split_at(*max as usize - 4).0)
-                } else {
-                    format!("{s}\0")
-                }.as_bytes().to_vec()
-            },
+            // room for the NUL
+            Self::String(max, s) => format!("{}\0", truncate_bytes(s, *max as usize - 1)).into_bytes(),
             Self::Integer(i) => i.to_le_bytes().to_vec(),
         }
     }
@@ -51,24 +186,43 @@ struct IndexEntry<'a> {
     data: DataFormat<'a>,
 }
 
-const ENTRIES_LEN: usize = 10;
-
-pub fn make_sfo(slot_info: &SlotInfo, bkp_name: &str, dir: &Path, gamever: &GameVersion) -> Result<Vec<u8>> {
+pub fn make_sfo(slot_info: &SlotInfo, bkp_name: &str, dir: &Path, gamever: &GameVersion, language: Language, fields: &SfoFields, limits: &TextLimits) -> Result<Vec<u8>> {
+    let level_name = limits.name(&slot_info.name);
+    let description = limits.description(&slot_info.description);
+    let np_handle = sanitize(&slot_info.np_handle, false);
     let title = match slot_info.is_adventure_planet {
-        false => format!("{} Dry Archive Level Backup", gamever.get_title()),
-        true => format!("{} Dry Archive Adventure Backup", gamever.get_title()),
+        false => format!("{} {}", gamever.get_title(), language.get_level_backup_title()),
+        true => format!("{} {}", gamever.get_title(), language.get_adventure_backup_title()),
+    };
+    let value = |name: &str| Some(match name {
+        "name" => level_name.clone(),
+        "creator" => np_handle.clone(),
+        "description" => description.clone(),
+        "game" => gamever.get_title().to_string(),
+        "id" => slot_info.id.to_string(),
+        "date" => Utc::now().format("%Y-%m-%d").to_string(),
+        _ => return None,
+    });
+    let subtitle = match &fields.sub_title {
+        Some(template) => sanitize(&fill_template(template, value)?, false),
+        None => language.get_subtitle(&level_name, &np_handle),
+    };
+    let detail = match &fields.detail {
+        Some(template) => sanitize(&fill_template(template, value)?, true),
+        None => description.clone(),
     };
-    let subtitle = format!("{} by {}", slot_info.name, slot_info.np_handle);
+
+    let account_id = fields.account_binding()?;
 
     // these need to be in alphabetical order
-    let entries: [IndexEntry; ENTRIES_LEN] = [
+    let mut entries: Vec<IndexEntry> = vec![
         IndexEntry {
             key: "ACCOUNT_ID",
-            data: DataFormat::Array(16, b"0000000000000000")
+            data: DataFormat::Array(16, account_id.as_bytes())
         },
         IndexEntry {
             key: "ATTRIBUTE",
-            data: DataFormat::Integer(0)
+            data: DataFormat::Integer(fields.attribute())
         },
         IndexEntry {
             key: "CATEGORY",
@@ -76,7 +230,7 @@ pub fn make_sfo(slot_info: &SlotInfo, bkp_name: &str, dir: &Path, gamever: &Game
         },
         IndexEntry {
             key: "DETAIL",
-            data: DataFormat::String(1024, &slot_info.description)
+            data: DataFormat::String(DETAIL_MAX, &detail)
         },
         IndexEntry {
             key: "PARAMS",
@@ -96,15 +250,22 @@ pub fn make_sfo(slot_info: &SlotInfo, bkp_name: &str, dir: &Path, gamever: &Game
         },
         IndexEntry {
             key: "SUB_TITLE",
-            data: DataFormat::String(128, &subtitle)
+            data: DataFormat::String(SUB_TITLE_MAX, &subtitle)
         },
         IndexEntry {
             key: "TITLE",
             data: DataFormat::String(128, &title)
         },
     ];
+    if let Some(level) = fields.parental_level {
+        // right after PARAMS2
+        entries.insert(6, IndexEntry {
+            key: "PARENTAL_LEVEL",
+            data: DataFormat::Integer(level)
+        });
+    }
 
-    let mut key_offsets = [0; ENTRIES_LEN];
+    let mut key_offsets = vec![0; entries.len()];
     let mut key_table = Vec::new();
 
     for (i, entry) in entries.iter().enumerate() {
@@ -113,7 +274,7 @@ pub fn make_sfo(slot_info: &SlotInfo, bkp_name: &str, dir: &Path, gamever: &Game
         key_table.write_u8(0)?; // null terminator
     }
 
-    let mut data_info = [(0, 0); ENTRIES_LEN];
+    let mut data_info = vec![(0, 0); entries.len()];
     let mut data_table = Vec::new();
     for (i, entry) in entries.iter().enumerate() {
         let data = entry.data.get_data();
@@ -136,7 +297,7 @@ pub fn make_sfo(slot_info: &SlotInfo, bkp_name: &str, dir: &Path, gamever: &Game
     sfo.write_all(&[0x01, 0x01, 0x00, 0x00])?; // version 1.1
     sfo.write_u32::<LittleEndian>(0)?; // key table offset, to be written later
     sfo.write_u32::<LittleEndian>(0)?; // data table offset, to be written later
-    sfo.write_u32::<LittleEndian>(ENTRIES_LEN as u32)?;
+    sfo.write_u32::<LittleEndian>(entries.len() as u32)?;
 
     // index table
     for (i, entry) in entries.iter().enumerate() {
@@ -0,0 +1,83 @@
+use anyhow::{bail, Result};
+use serde::Deserialize;
+
+/// what a cut text ends in
+const ELLIPSIS: &str = "...";
+
+/// Longest level name and description the backups get, in characters,
+/// `text_limits` in config.yml. Unset keeps them whole, the SFO's own field
+/// sizes always apply
+#[derive(Debug, Default, Clone, Deserialize)]
+pub struct TextLimits {
+    #[serde(default)]
+    pub name_max: Option<usize>,
+    #[serde(default)]
+    pub description_max: Option<usize>,
+}
+
+impl TextLimits {
+    pub fn validate(&self) -> Result<()> {
+        for (key, max) in [("name_max", self.name_max), ("description_max", self.description_max)] {
+            if let Some(max) = max && max <= ELLIPSIS.len() {
+                bail!("text_limits.{} must be more than {}, not {}", key, ELLIPSIS.len(), max);
+            }
+        }
+        Ok(())
+    }
+
+    /// A level name as the SFO and slot list get it
+    pub fn name(&self, name: &str) -> String {
+        let name = sanitize(name, false);
+        match self.name_max {
+            Some(max) => truncate_chars(&name, max),
+            None => name,
+        }
+    }
+
+    /// A level description as the SFO and slot list get it, line breaks included
+    pub fn description(&self, description: &str) -> String {
+        let description = sanitize(description, true);
+        match self.description_max {
+            Some(max) => truncate_chars(&description, max),
+            None => description,
+        }
+    }
+}
+
+/// Zero width characters that only glue emoji together or pick how they look
+fn is_emoji_joiner(c: char) -> bool {
+    matches!(c, '\u{200d}' | '\u{20e3}' | '\u{fe0e}' | '\u{fe0f}')
+}
+
+/// Text the XMB and the games can show. They stop at NUL and show other
+/// control characters as garbage, so those become spaces (a description may
+/// keep its line breaks). Their fonts stop at the Basic Multilingual Plane,
+/// so emoji and everything else past it are dropped, Japanese and the like stay
+pub fn sanitize(text: &str, keep_newlines: bool) -> String {
+    text.chars()
+        .filter(|&c| c != '\0' && (c as u32) <= 0xffff && !is_emoji_joiner(c))
+        .map(|c| if c.is_control() && !(keep_newlines && c == '\n') { ' ' } else { c })
+        .collect()
+}
+
+/// `text` cut to `max` characters, ending in `...` if it was cut
+pub fn truncate_chars(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    let kept: String = text.chars().take(max.saturating_sub(ELLIPSIS.len())).collect();
+    format!("{}{}", kept, ELLIPSIS)
+}
+
+/// `text` cut to fit `max` bytes of UTF-8 on a character boundary, so it
+/// stays valid, ending in `...` if it was cut
+pub fn truncate_bytes(text: &str, max: usize) -> String {
+    if text.len() <= max {
+        return text.to_string();
+    }
+    let mut end = max.saturating_sub(ELLIPSIS.len());
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}{}", &text[..end], ELLIPSIS)
+}
@@ -0,0 +1,158 @@
+// src/setup.rs
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Result, bail};
+
+use crate::config::DEFAULT_CONFIG;
+use crate::db::{
+    USER_JOIN_DATE_COLUMN, USER_LAST_LOGIN_COLUMN, column_exists, open_db, table_exists,
+};
+use crate::resource_dl::zip_location;
+
+/// What `init` found in a database file
+pub struct DbProbe {
+    pub slots: i64,
+    pub users: i64,
+    /// optional tables and columns the other commands use if they're there,
+    /// with whether they are
+    pub extras: Vec<(&'static str, bool)>,
+}
+
+/// Check that `path` is a database with the tables the tool needs
+pub fn probe_database(path: &Path) -> Result<DbProbe> {
+    let conn = open_db(path)?;
+    for table in ["slot", "user"] {
+        if !table_exists(&conn, table)? {
+            bail!(
+                "{} has no `{}` table, it isn't a dump database",
+                path.display(),
+                table
+            );
+        }
+    }
+    let count = |table: &str| -> Result<i64> {
+        Ok(
+            conn.query_row(&format!(r#"SELECT count(*) FROM "{}""#, table), [], |r| {
+                r.get(0)
+            })?,
+        )
+    };
+    Ok(DbProbe {
+        slots: count("slot")?,
+        users: count("user")?,
+        extras: vec![
+            (
+                "slot_translation (translated names)",
+                table_exists(&conn, "slot_translation")?,
+            ),
+            (
+                "slot.guid (guid:<n> slots)",
+                column_exists(&conn, "slot", "guid")?,
+            ),
+            (
+                "slot.lastUpdated (compare-creators)",
+                column_exists(&conn, "slot", "lastUpdated")?,
+            ),
+            (
+                "user.joinDate and user.lastLogin (Refresh export)",
+                column_exists(&conn, "user", USER_JOIN_DATE_COLUMN)?
+                    && column_exists(&conn, "user", USER_LAST_LOGIN_COLUMN)?,
+            ),
+        ],
+    })
+}
+
+/// What `init` found at an archive path
+pub enum ArchiveProbe {
+    /// the original layout, with the prefixes of the `dry<xx>.zip` files
+    /// that aren't there
+    Split {
+        missing: Vec<String>,
+    },
+    SingleZip(PathBuf),
+    Tars(usize),
+    Remote,
+    Missing,
+    /// a folder with none of the known layouts in it
+    Unknown,
+}
+
+fn has_extension(path: &Path, ext: &str) -> bool {
+    path.extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case(ext))
+}
+
+/// See which archive layout is at `path` without indexing anything
+pub fn probe_archive(path: &Path) -> ArchiveProbe {
+    let text = path.to_string_lossy();
+    if text.starts_with("http://") || text.starts_with("https://") {
+        return ArchiveProbe::Remote;
+    }
+    if path.is_file() {
+        return match has_extension(path, "tar") {
+            true => ArchiveProbe::Tars(1),
+            false => ArchiveProbe::SingleZip(path.to_path_buf()),
+        };
+    }
+    let Ok(dir) = path.read_dir() else {
+        return ArchiveProbe::Missing;
+    };
+
+    let missing: Vec<String> = (0..=255u8)
+        .map(|prefix| format!("{:02x}", prefix))
+        .filter(|prefix| !path.join(zip_location(prefix)).is_file())
+        .collect();
+    if missing.len() < 256 {
+        return ArchiveProbe::Split { missing };
+    }
+    let files: Vec<PathBuf> = dir
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|p| p.is_file())
+        .collect();
+    let tars = files.iter().filter(|p| has_extension(p, "tar")).count();
+    if tars > 0 {
+        return ArchiveProbe::Tars(tars);
+    }
+    let zips: Vec<&PathBuf> = files.iter().filter(|p| has_extension(p, "zip")).collect();
+    match zips[..] {
+        [zip] => ArchiveProbe::SingleZip(zip.clone()),
+        _ => ArchiveProbe::Unknown,
+    }
+}
+
+/// The settings `init` asks for
+pub struct InitAnswers {
+    pub database_path: PathBuf,
+    pub archive_path: PathBuf,
+    pub backup_directory: PathBuf,
+}
+
+/// YAML strings are quoted, so Windows paths and spaces need no care
+fn yaml_string(path: &Path) -> String {
+    let text = path.to_string_lossy();
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// The default config.yml with the answers in place of its paths, the
+/// other settings and their comments as they ship
+pub fn render_config(answers: &InitAnswers) -> String {
+    let mut config =
+        String::from("# Written by `archive_dl init`, see the README for every setting.\n");
+    for line in DEFAULT_CONFIG.lines() {
+        let key = line.split_once(':').map_or("", |(key, _)| key);
+        let answer = match key {
+            "database_path" => Some(&answers.database_path),
+            "backup_directory" => Some(&answers.backup_directory),
+            "archive_path" => Some(&answers.archive_path),
+            _ => None,
+        };
+        match answer {
+            Some(path) => config.push_str(&format!("{}: {}", key, yaml_string(path))),
+            None => config.push_str(line),
+        }
+        config.push('\n');
+    }
+    config
+}
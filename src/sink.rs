@@ -0,0 +1,194 @@
+// src/sink.rs
+
+use std::collections::HashSet;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Result, anyhow};
+use clap::ValueEnum;
+use zip::write::FileOptions;
+use zip::{CompressionMethod, DateTime, ZipArchive, ZipWriter};
+
+use crate::link::{LinkMode, link_or_copy};
+use crate::resource_dl::zip_entry_name;
+
+/// How the blobs of a dump are laid out in its output folder
+#[derive(Debug, Default, PartialEq, Clone, Copy, ValueEnum)]
+pub enum DumpFormat {
+    /// every blob as `<sha1>` in the folder itself
+    #[default]
+    Flat,
+    /// git-like, every blob as `objects/ab/cd/<sha1>`
+    Cas,
+    /// every blob in one `resources.zip`, as `ab/cd/<sha1>` the way a
+    /// single-ZIP archive stores them
+    Zip,
+}
+
+/// the ZIP `DumpFormat::Zip` writes the blobs into
+pub const ZIP_SINK_FILE: &str = "resources.zip";
+/// the folder `DumpFormat::Cas` writes the blobs below
+pub const CAS_OBJECTS_DIR: &str = "objects";
+
+/// Whether a file in a dump is a blob, named by its SHA1 in hex
+pub fn is_blob_name(name: &str) -> bool {
+    name.len() == 40 && name.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+enum Store {
+    Flat,
+    Cas,
+    Zip {
+        writer: Box<ZipWriter<File>>,
+        /// entries already in the ZIP, from this run or an earlier one
+        names: HashSet<String>,
+    },
+}
+
+/// Where a dump writes its blobs, in the layout of a `DumpFormat`. Other
+/// files (hash lists, state, imports) always go into the folder itself
+pub struct OutputSink {
+    dir: PathBuf,
+    store: Store,
+}
+
+impl OutputSink {
+    /// Open the sink in `dir`. A ZIP left by an earlier run is appended to
+    pub fn open(dir: &Path, format: DumpFormat) -> Result<Self> {
+        fs::create_dir_all(dir)
+            .map_err(|e| anyhow!("couldn't create output dir `{}`: {}", dir.display(), e))?;
+        let store = match format {
+            DumpFormat::Flat => Store::Flat,
+            DumpFormat::Cas => Store::Cas,
+            DumpFormat::Zip => open_zip(&dir.join(ZIP_SINK_FILE))?,
+        };
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            store,
+        })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn blob_path(&self, hex: &str) -> PathBuf {
+        match self.store {
+            Store::Cas => self
+                .dir
+                .join(CAS_OBJECTS_DIR)
+                .join(&hex[0..2])
+                .join(&hex[2..4])
+                .join(hex),
+            _ => self.dir.join(hex),
+        }
+    }
+
+    /// Whether the blob `hex` is in the sink already
+    pub fn has_blob(&self, hex: &str) -> bool {
+        match &self.store {
+            Store::Zip { names, .. } => names.contains(&zip_entry_name(hex)),
+            _ => self.blob_path(hex).exists(),
+        }
+    }
+
+    /// Write the blob `hex`, replacing any copy of it in a folder layout.
+    /// A ZIP keeps the copy it has, blobs never change
+    pub fn write_blob(&mut self, hex: &str, data: &[u8]) -> Result<()> {
+        match &mut self.store {
+            Store::Zip { writer, names } => {
+                let entry = zip_entry_name(hex);
+                if names.contains(&entry) {
+                    return Ok(());
+                }
+                writer.start_file(entry.as_str(), zip_options())?;
+                writer.write_all(data)?;
+                names.insert(entry);
+            }
+            _ => {
+                let path = self.blob_path(hex);
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::write(&path, data)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Place the blob file `src` as `hex` unless the sink has it already.
+    /// Folder layouts link it according to `mode`, a ZIP copies it in
+    pub fn place_blob(&mut self, hex: &str, src: &Path, mode: LinkMode) -> Result<()> {
+        if self.has_blob(hex) {
+            return Ok(());
+        }
+        match self.store {
+            Store::Zip { .. } => {
+                let data =
+                    fs::read(src).map_err(|e| anyhow!("couldn't read {}: {}", src.display(), e))?;
+                self.write_blob(hex, &data)
+            }
+            _ => {
+                let dst = self.blob_path(hex);
+                if let Some(parent) = dst.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                link_or_copy(src, &dst, mode)
+            }
+        }
+    }
+
+    /// Write a file that isn't a blob into the folder itself
+    pub fn write_file(&self, name: &str, data: impl AsRef<[u8]>) -> Result<()> {
+        fs::write(self.dir.join(name), data)?;
+        Ok(())
+    }
+
+    /// Finish writing, a ZIP is unreadable until this wrote its central
+    /// directory
+    pub fn finish(self) -> Result<()> {
+        if let Store::Zip { mut writer, .. } = self.store {
+            writer.finish()?;
+        }
+        Ok(())
+    }
+}
+
+fn zip_options() -> FileOptions {
+    FileOptions::default()
+        .compression_method(CompressionMethod::Deflated)
+        .last_modified_time(DateTime::default())
+        .unix_permissions(0o644)
+}
+
+fn open_zip(path: &Path) -> Result<Store> {
+    if !path.exists() {
+        let file =
+            File::create(path).map_err(|e| anyhow!("couldn't create {}: {}", path.display(), e))?;
+        return Ok(Store::Zip {
+            writer: Box::new(ZipWriter::new(file)),
+            names: HashSet::new(),
+        });
+    }
+    let file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(path)
+        .map_err(|e| anyhow!("couldn't open {}: {}", path.display(), e))?;
+    let names = ZipArchive::new(&file)
+        .map_err(|e| {
+            anyhow!(
+                "{} is damaged, probably by an interrupted run ({}). Move it away to start over",
+                path.display(),
+                e
+            )
+        })?
+        .file_names()
+        .map(str::to_string)
+        .collect();
+    Ok(Store::Zip {
+        writer: Box::new(ZipWriter::new_append(file)?),
+        names,
+    })
+}
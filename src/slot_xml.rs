@@ -0,0 +1,111 @@
+// src/slot_xml.rs
+
+use std::fmt::Write as _;
+
+use crate::db::{GameVersion, SlotInfo};
+use crate::labels::label_name;
+use crate::resource_parse::ResrcDescriptor;
+
+/// A slot to export and the SHA1 of its creator's icon, if they have one
+pub struct XmlSlot {
+    pub info: SlotInfo,
+    pub creator_icon: Option<[u8; 20]>,
+}
+
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            // not allowed in XML 1.0 at all
+            c if c.is_control() && !matches!(c, '\t' | '\n' | '\r') => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// how the server XML writes a resource, its SHA1 or `g<guid>`
+fn descriptor(desc: &ResrcDescriptor) -> String {
+    match desc {
+        ResrcDescriptor::Sha1(h) => hex::encode(h),
+        ResrcDescriptor::Guid(g) => format!("g{}", g),
+    }
+}
+
+fn game_number(game: GameVersion) -> u8 {
+    match game {
+        GameVersion::Lbp1 => 0,
+        GameVersion::Lbp2 => 1,
+        GameVersion::Lbp3 => 2,
+    }
+}
+
+fn render_slot(out: &mut String, slot: &XmlSlot) {
+    let info = &slot.info;
+    let element = |out: &mut String, name: &str, value: &str| {
+        writeln!(out, "  <{name}>{value}</{name}>").unwrap();
+    };
+
+    writeln!(out, "<slot type=\"user\">").unwrap();
+    element(out, "id", &info.id.to_string());
+    match slot.creator_icon {
+        Some(icon) => writeln!(
+            out,
+            "  <npHandle icon=\"{}\">{}</npHandle>",
+            hex::encode(icon),
+            escape(&info.np_handle)
+        )
+        .unwrap(),
+        None => element(out, "npHandle", &escape(&info.np_handle)),
+    }
+    element(out, "game", &game_number(info.game).to_string());
+    element(out, "name", &escape(&info.name));
+    element(out, "description", &escape(&info.description));
+    element(out, "rootLevel", &hex::encode(info.root_level));
+    // GUID 0 is no icon at all
+    if info.icon != ResrcDescriptor::Guid(0) {
+        element(out, "icon", &descriptor(&info.icon));
+    }
+    if let Some(guid) = info.background_guid {
+        element(out, "background", &format!("g{}", guid));
+    }
+    element(out, "initiallyLocked", &info.initially_locked.to_string());
+    element(out, "isSubLevel", &info.is_sub_level.to_string());
+    element(
+        out,
+        "isAdventurePlanet",
+        &info.is_adventure_planet.to_string(),
+    );
+    element(out, "shareable", &(info.shareable as u8).to_string());
+    let labels: Vec<&str> = info
+        .author_labels
+        .iter()
+        .filter_map(|id| label_name(*id))
+        .collect();
+    element(out, "authorLabels", &labels.join(","));
+    element(out, "leveltype", info.leveltype.get_name());
+    if let Some(min) = info.min_players {
+        element(out, "minPlayers", &min.to_string());
+    }
+    if let Some(max) = info.max_players {
+        element(out, "maxPlayers", &max.to_string());
+    }
+    writeln!(out, "</slot>").unwrap();
+}
+
+/// The slots as one `<slots>` document, the way an LBP server lists them
+pub fn render_slots(slots: &[XmlSlot]) -> String {
+    let mut out = String::new();
+    writeln!(out, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>").unwrap();
+    writeln!(out, "<slots total=\"{}\" hint_start=\"0\">", slots.len()).unwrap();
+    for slot in slots {
+        render_slot(&mut out, slot);
+    }
+    writeln!(out, "</slots>").unwrap();
+    out
+}
@@ -0,0 +1,456 @@
+// src/working_db.rs
+
+use std::fs;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+use anyhow::{Result, anyhow};
+use rusqlite::types::Value;
+use rusqlite::{Connection, params_from_iter};
+
+use crate::db::open_db;
+
+/// Kept in the cache folder, `db upgrade` writes it there and the other
+/// commands read it instead of `database_path` while it's current
+pub const WORKING_DB_FILE: &str = "working.db";
+
+/// table of the working copy recording which database it was made from
+const SOURCE_TABLE: &str = "working_db_source";
+
+/// tables whose `id` column points at `slot.id`
+const SLOT_CHILD_TABLES: [&str; 1] = ["slot_translation"];
+
+#[derive(Clone, Copy)]
+enum Kind {
+    Text,
+    Int,
+    /// SHA1s (or a GUID for icons) as a BLOB
+    Hash,
+    Blob,
+    /// `slot.leveltype`, lowercase and NULL for cooperative
+    LevelType,
+}
+
+/// What a NULL becomes
+#[derive(Clone, Copy, PartialEq)]
+enum Fill {
+    Null,
+    Zero,
+    Empty,
+}
+
+struct Column {
+    name: &'static str,
+    decl: &'static str,
+    kind: Kind,
+    fill: Fill,
+}
+
+const fn col(name: &'static str, decl: &'static str, kind: Kind, fill: Fill) -> Column {
+    Column {
+        name,
+        decl,
+        kind,
+        fill,
+    }
+}
+
+/// a flag the readers take NULL as false for
+const FLAG: &str = "INTEGER NOT NULL DEFAULT 0";
+/// text the readers take NULL as empty for
+const TEXT: &str = "TEXT NOT NULL DEFAULT ''";
+
+/// The slot columns the tool reads, NULLs filled the way the readers
+/// treat them
+const SLOT_COLUMNS: &[Column] = &[
+    col("id", "INTEGER PRIMARY KEY", Kind::Int, Fill::Null),
+    col("name", TEXT, Kind::Text, Fill::Empty),
+    col("description", TEXT, Kind::Text, Fill::Empty),
+    col("npHandle", TEXT, Kind::Text, Fill::Empty),
+    col("rootLevel", "BLOB", Kind::Hash, Fill::Null),
+    col("icon", "BLOB", Kind::Hash, Fill::Null),
+    col("game", "INTEGER", Kind::Int, Fill::Null),
+    col("initiallyLocked", FLAG, Kind::Int, Fill::Zero),
+    col("isSubLevel", FLAG, Kind::Int, Fill::Zero),
+    col("background", "INTEGER", Kind::Int, Fill::Null),
+    col("shareable", FLAG, Kind::Int, Fill::Zero),
+    col("authorLabels", "BLOB", Kind::Blob, Fill::Null),
+    col("leveltype", "TEXT", Kind::LevelType, Fill::Null),
+    col("minPlayers", "INTEGER", Kind::Int, Fill::Null),
+    col("maxPlayers", "INTEGER", Kind::Int, Fill::Null),
+    col("isAdventurePlanet", FLAG, Kind::Int, Fill::Zero),
+    col("publishedIn", "TEXT", Kind::Text, Fill::Null),
+    col("locationX", "INTEGER", Kind::Int, Fill::Null),
+    col("locationY", "INTEGER", Kind::Int, Fill::Null),
+    col("firstPublished", "INTEGER", Kind::Int, Fill::Null),
+    col("lastUpdated", "INTEGER", Kind::Int, Fill::Null),
+    col("enforceMinMaxPlayers", FLAG, Kind::Int, Fill::Zero),
+    col("sameScreenGame", FLAG, Kind::Int, Fill::Zero),
+    col("guid", "INTEGER", Kind::Int, Fill::Null),
+];
+
+const USER_COLUMNS: &[Column] = &[
+    col("npHandle", "TEXT PRIMARY KEY", Kind::Text, Fill::Null),
+    col("icon", "BLOB", Kind::Hash, Fill::Null),
+    col("planets", "BLOB", Kind::Hash, Fill::Null),
+    col("locationX", "INTEGER", Kind::Int, Fill::Null),
+    col("locationY", "INTEGER", Kind::Int, Fill::Null),
+    col("commentsEnabled", "INTEGER", Kind::Int, Fill::Null),
+];
+
+/// indexes of the working copy, on the columns levels and users are
+/// looked up by
+const INDEXES: &[(&str, &str, &str)] = &[
+    ("slot_npHandle", "slot", "npHandle"),
+    ("slot_rootLevel", "slot", "rootLevel"),
+    ("slot_guid", "slot", "guid"),
+    ("slot_translation_id", "slot_translation", "id, language"),
+];
+
+/// What `normalize` changed on the way
+#[derive(Debug, Default)]
+pub struct NormalizeReport {
+    pub slots: usize,
+    pub users: usize,
+    /// values stored as the wrong type or encoding, converted
+    pub retyped: usize,
+    /// NULLs replaced by the default the tool reads them as
+    pub filled: usize,
+    /// values that couldn't be converted and were kept as they were
+    pub unconvertible: usize,
+    /// slot rows with the id of an earlier row, dropped
+    pub duplicate_slots: usize,
+    /// user rows merged into an earlier row of the same npHandle
+    pub duplicate_users: usize,
+    /// user rows without an npHandle, dropped
+    pub dropped_users: usize,
+    /// rows of other tables pointing at slots that don't exist, dropped
+    pub orphans: usize,
+    /// slots whose creator has no user row, kept
+    pub slots_without_user: usize,
+}
+
+fn is_hex(text: &str) -> bool {
+    text.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+/// `value` as `column` wants it
+fn normalize_value(value: Value, column: &Column, report: &mut NormalizeReport) -> Value {
+    let converted = match (column.kind, value) {
+        (_, Value::Null) => {
+            return match column.fill {
+                Fill::Null => Value::Null,
+                fill => {
+                    report.filled += 1;
+                    match fill {
+                        Fill::Zero => Value::Integer(0),
+                        _ => Value::Text(String::new()),
+                    }
+                }
+            };
+        }
+        (Kind::Text, Value::Blob(b)) => {
+            let text = match String::from_utf8(b) {
+                Ok(text) => text,
+                Err(e) => String::from_utf8_lossy(e.as_bytes()).into_owned(),
+            };
+            Ok(Value::Text(text))
+        }
+        (Kind::Text, Value::Integer(i)) => Ok(Value::Text(i.to_string())),
+        (Kind::Int, Value::Text(t)) => match t.trim() {
+            "true" => Ok(Value::Integer(1)),
+            "false" => Ok(Value::Integer(0)),
+            trimmed => trimmed
+                .parse()
+                .map(Value::Integer)
+                .map_err(|_| Value::Text(t)),
+        },
+        (Kind::Int, Value::Real(r)) if r.fract() == 0.0 => Ok(Value::Integer(r as i64)),
+        (Kind::Hash, Value::Text(t)) => match (t.len() % 40 == 0 || t.len() == 8) && is_hex(&t) {
+            true => Ok(Value::Blob(hex::decode(&t).unwrap())),
+            false => Err(Value::Text(t)),
+        },
+        (Kind::LevelType, Value::Text(t)) => {
+            let lower = t.trim().to_lowercase();
+            match lower.as_str() {
+                "" | "cooperative" => Ok(Value::Null),
+                _ if lower == t => return Value::Text(t),
+                _ => Ok(Value::Text(lower)),
+            }
+        }
+        (Kind::Text, v @ Value::Text(_))
+        | (Kind::Int, v @ Value::Integer(_))
+        | (Kind::Hash | Kind::Blob, v @ Value::Blob(_)) => return v,
+        (_, v) => Err(v),
+    };
+    match converted {
+        Ok(v) => {
+            report.retyped += 1;
+            v
+        }
+        Err(v) => {
+            report.unconvertible += 1;
+            v
+        }
+    }
+}
+
+fn source_columns(conn: &Connection, table: &str) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare("SELECT name FROM pragma_table_info(?1)")?;
+    let names = stmt
+        .query_map([table], |r| r.get(0))?
+        .collect::<rusqlite::Result<_>>()?;
+    Ok(names)
+}
+
+fn quote(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// The columns of `table` in the working copy: the known ones the source
+/// has, typed, then any others as they were
+fn table_layout<'a>(
+    source: &[String],
+    known: &'a [Column],
+) -> (Vec<(String, Option<&'a Column>)>, String) {
+    let mut layout = Vec::new();
+    let mut decls = Vec::new();
+    for name in source {
+        let column = known.iter().find(|c| c.name == name);
+        decls.push(match column {
+            Some(c) => format!("{} {}", quote(name), c.decl),
+            None => quote(name),
+        });
+        layout.push((name.clone(), column));
+    }
+    (layout, decls.join(", "))
+}
+
+/// Create `table` in the working copy with the types of the `known` columns
+fn create_table(src: &Connection, dst: &Connection, table: &str, known: &[Column]) -> Result<()> {
+    let names = source_columns(src, table)?;
+    if names.is_empty() {
+        return Err(anyhow!("the database has no `{}` table", table));
+    }
+    let (_, decls) = table_layout(&names, known);
+    dst.execute_batch(&format!("CREATE TABLE {} ({})", quote(table), decls))?;
+    Ok(())
+}
+
+/// Copy the rows of `table` into the working copy one by one, the `known`
+/// columns normalized. `on_conflict` is what a row with the key of an
+/// earlier one does, rows `keep` turns down are left out. Returns how many
+/// rows were kept
+fn copy_rows(
+    src: &Connection,
+    dst: &Connection,
+    table: &str,
+    known: &[Column],
+    on_conflict: &str,
+    mut keep: impl FnMut(&[Value]) -> bool,
+    report: &mut NormalizeReport,
+) -> Result<usize> {
+    let names = source_columns(src, table)?;
+    let (layout, _) = table_layout(&names, known);
+
+    let select = names
+        .iter()
+        .map(|n| quote(n))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let mut stmt = src.prepare(&format!(
+        "SELECT {} FROM {} ORDER BY rowid",
+        select,
+        quote(table)
+    ))?;
+    let mut insert = dst.prepare(&format!(
+        "INSERT INTO {} VALUES ({}) {}",
+        quote(table),
+        vec!["?"; names.len()].join(", "),
+        on_conflict
+    ))?;
+    let mut rows = stmt.query([])?;
+    let mut kept = 0;
+    let mut values = Vec::with_capacity(layout.len());
+    while let Some(row) = rows.next()? {
+        values.clear();
+        for (i, (_, column)) in layout.iter().enumerate() {
+            let value: Value = row.get(i)?;
+            values.push(match column {
+                Some(column) => normalize_value(value, column, report),
+                None => value,
+            });
+        }
+        if keep(&values) {
+            insert.execute(params_from_iter(&values))?;
+            kept += 1;
+        }
+    }
+    Ok(kept)
+}
+
+fn count_rows(conn: &Connection, table: &str) -> Result<usize> {
+    let count: i64 =
+        conn.query_row(&format!("SELECT count(*) FROM {}", quote(table)), [], |r| {
+            r.get(0)
+        })?;
+    Ok(count as usize)
+}
+
+/// The source's size and modification time, to tell if a working copy is
+/// still current
+fn source_stamp(source: &Path) -> Result<(i64, i64)> {
+    let meta =
+        fs::metadata(source).map_err(|e| anyhow!("couldn't open {}: {}", source.display(), e))?;
+    let modified = meta
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |d| d.as_secs() as i64);
+    Ok((meta.len() as i64, modified))
+}
+
+/// Copy the database at `source` into a cleaned up working copy at `dest`:
+/// - slot and user columns get their types, values stored as another type
+///   or as BLOB text are converted, NULLs the tool reads as a default get it
+/// - duplicate users are merged, users without an npHandle dropped
+/// - rows pointing at slots that don't exist are dropped
+/// - every other table is copied as it is
+/// - npHandle, rootLevel and guid get indexes
+pub fn normalize(source: &Path, dest: &Path) -> Result<NormalizeReport> {
+    let src = open_db(source)?;
+    let tmp = dest.with_extension(format!("db.{}.tmp", std::process::id()));
+    let _ = fs::remove_file(&tmp);
+    let mut dst =
+        Connection::open(&tmp).map_err(|e| anyhow!("couldn't create {}: {}", tmp.display(), e))?;
+    dst.execute_batch("PRAGMA journal_mode = OFF; PRAGMA synchronous = OFF;")?;
+    let tx = dst.transaction()?;
+    let mut report = NormalizeReport::default();
+
+    // the first of two slots with the same id is kept
+    create_table(&src, &tx, "slot", SLOT_COLUMNS)?;
+    let slots = copy_rows(
+        &src,
+        &tx,
+        "slot",
+        SLOT_COLUMNS,
+        "ON CONFLICT DO NOTHING",
+        |_| true,
+        &mut report,
+    )?;
+    report.slots = count_rows(&tx, "slot")?;
+    report.duplicate_slots = slots - report.slots;
+
+    // there's no key on npHandle in the dumps, merge its duplicates: the
+    // first row keeps its values and gets the ones it's missing
+    let user_names = source_columns(&src, "user")?;
+    let handle_index = user_names
+        .iter()
+        .position(|n| n == "npHandle")
+        .ok_or_else(|| anyhow!("the user table has no npHandle column"))?;
+    let merged: Vec<String> = user_names
+        .iter()
+        .filter(|n| *n != "npHandle")
+        .map(|n| format!("{0} = coalesce(\"user\".{0}, excluded.{0})", quote(n)))
+        .collect();
+    let on_conflict = match merged.is_empty() {
+        true => "ON CONFLICT DO NOTHING".to_string(),
+        false => format!("ON CONFLICT (npHandle) DO UPDATE SET {}", merged.join(", ")),
+    };
+    create_table(&src, &tx, "user", USER_COLUMNS)?;
+    let mut dropped = 0;
+    let users = copy_rows(
+        &src,
+        &tx,
+        "user",
+        USER_COLUMNS,
+        &on_conflict,
+        |row| match row[handle_index] {
+            Value::Text(_) => true,
+            _ => {
+                dropped += 1;
+                false
+            }
+        },
+        &mut report,
+    )?;
+    report.dropped_users = dropped;
+    report.users = count_rows(&tx, "user")?;
+    report.duplicate_users = users - report.users;
+
+    // everything else as it is, its schema included
+    let other_tables: Vec<(String, String)> = {
+        let mut stmt = src.prepare(
+            "SELECT name, sql FROM sqlite_master
+              WHERE type = 'table' AND name NOT IN ('slot', 'user')
+                AND name NOT LIKE 'sqlite_%' AND sql IS NOT NULL",
+        )?;
+        stmt.query_map([], |r| Ok((r.get(0)?, r.get(1)?)))?
+            .collect::<rusqlite::Result<_>>()?
+    };
+    for (table, sql) in &other_tables {
+        tx.execute_batch(sql)?;
+        copy_rows(&src, &tx, table, &[], "", |_| true, &mut report)?;
+    }
+    for table in SLOT_CHILD_TABLES {
+        if other_tables.iter().any(|(t, _)| t == table) {
+            report.orphans += tx.execute(
+                &format!(
+                    "DELETE FROM {} WHERE id NOT IN (SELECT id FROM slot)",
+                    quote(table)
+                ),
+                [],
+            )?;
+        }
+    }
+    report.slots_without_user = tx.query_row(
+        r#"SELECT count(*) FROM slot
+            WHERE npHandle NOT IN (SELECT npHandle FROM "user")"#,
+        [],
+        |r| r.get::<_, i64>(0),
+    )? as usize;
+
+    for (name, table, columns) in INDEXES {
+        let present = source_columns(&tx, table)?;
+        if columns.split(", ").all(|c| present.iter().any(|p| p == c)) {
+            tx.execute_batch(&format!(
+                "CREATE INDEX IF NOT EXISTS {} ON {} ({})",
+                quote(name),
+                quote(table),
+                columns
+            ))?;
+        }
+    }
+
+    let (size, modified) = source_stamp(source)?;
+    tx.execute_batch(&format!(
+        "CREATE TABLE {SOURCE_TABLE} (path TEXT NOT NULL, size INTEGER NOT NULL, modified INTEGER NOT NULL)"
+    ))?;
+    tx.execute(
+        &format!("INSERT INTO {SOURCE_TABLE} VALUES (?1, ?2, ?3)"),
+        rusqlite::params![source.to_string_lossy(), size, modified],
+    )?;
+    tx.commit()?;
+    dst.execute_batch("ANALYZE")?;
+    drop(dst);
+    fs::rename(&tmp, dest)?;
+    Ok(report)
+}
+
+/// Whether the working copy at `working` was made from `source` as it is
+/// now. `None` if there's no working copy
+pub fn is_current(source: &Path, working: &Path) -> Option<bool> {
+    if !working.exists() {
+        return None;
+    }
+    let current = || -> Result<bool> {
+        let conn = open_db(working)?;
+        let (path, size, modified): (String, i64, i64) = conn.query_row(
+            &format!("SELECT path, size, modified FROM {SOURCE_TABLE}"),
+            [],
+            |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)),
+        )?;
+        Ok(Path::new(&path) == source && source_stamp(source)? == (size, modified))
+    };
+    Some(current().unwrap_or(false))
+}
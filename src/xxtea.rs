@@ -1,38 +1,79 @@
-use core::slice;
-
 // modified code from https://github.com/mgottschlag/xxtea-nostd
 
 // The code is based on the public domain implementation at
 // https://github.com/mycelium-com/entropy/blob/master/lib/xxtea.c
 
-fn as_u32_slice_mut(x: &mut [u8]) -> &mut [u32] {
-    // Safe, because the length is rounded down.
-    unsafe { slice::from_raw_parts_mut(x.as_mut_ptr() as *mut u32, x.len() / 4) }
+/// The big-endian words of a block, copied so the block needn't be aligned
+fn load_words(x: &[u8]) -> Vec<u32> {
+    x.chunks_exact(4)
+        .map(|w| u32::from_be_bytes(w.try_into().unwrap()))
+        .collect()
+}
+
+fn store_words(words: &[u32], x: &mut [u8]) {
+    for (w, out) in words.iter().zip(x.chunks_exact_mut(4)) {
+        out.copy_from_slice(&w.to_be_bytes());
+    }
 }
 
 pub fn encrypt(key: &[u32], block: &mut [u8]) {
     assert_eq!(key.len(), 4);
     assert_eq!(block.len() & 3, 0);
 
-    let block = as_u32_slice_mut(block);
+    let mut v = load_words(block);
+    // like `decrypt`, a single word is left as it is
+    if v.len() < 2 {
+        return;
+    }
 
-    let rounds = 6 + 52 / block.len();
-    let n = block.len() - 1;
+    let rounds = 6 + 52 / v.len();
+    let n = v.len() - 1;
 
     let mut sum = 0u32;
-    let mut z = u32::from_be(block[n]); // left neighbour for the first round
+    let mut z = v[n]; // left neighbour for the first round
     for _ in 0..rounds {
         // cycle
         sum = sum.wrapping_add(0x9e3779b9);
         let e = sum >> 2;
-        for r in 0..block.len() {
+        for r in 0..v.len() {
             // round
-            let y = u32::from_be(block[(r + 1) % block.len()]); // right neighbour
-            block[r] = u32::to_be(u32::from_be(block[r]).wrapping_add(
+            let y = v[(r + 1) % v.len()]; // right neighbour
+            v[r] = v[r].wrapping_add(
+                (((z >> 5) ^ (y << 2)).wrapping_add((y >> 3) ^ (z << 4)))
+                    ^ ((sum ^ y).wrapping_add(key[(r ^ e as usize) & 3] ^ z)),
+            );
+            z = v[r]; // left neighbour for the next round
+        }
+    }
+    store_words(&v, block);
+}
+
+pub fn decrypt(key: &[u32], block: &mut [u8]) {
+    assert_eq!(key.len(), 4);
+    assert_eq!(block.len() & 3, 0);
+
+    let mut v = load_words(block);
+    if v.len() < 2 {
+        return;
+    }
+
+    let rounds = 6 + 52 / v.len();
+    let n = v.len() - 1;
+
+    let mut sum = (rounds as u32).wrapping_mul(0x9e3779b9);
+    let mut y = v[0]; // right neighbour for the first round
+    for _ in 0..rounds {
+        // cycle, undoing the rounds of `encrypt` back to front
+        let e = sum >> 2;
+        for r in (0..v.len()).rev() {
+            let z = v[if r == 0 { n } else { r - 1 }]; // left neighbour
+            v[r] = v[r].wrapping_sub(
                 (((z >> 5) ^ (y << 2)).wrapping_add((y >> 3) ^ (z << 4)))
                     ^ ((sum ^ y).wrapping_add(key[(r ^ e as usize) & 3] ^ z)),
-            ));
-            z = u32::from_be(block[r]); // left neighbour for the next round
+            );
+            y = v[r]; // right neighbour for the next round
         }
+        sum = sum.wrapping_sub(0x9e3779b9);
     }
-}
\ No newline at end of file
+    store_words(&v, block);
+}
@@ -0,0 +1,412 @@
+// src/zip_index.rs
+
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use anyhow::{Result, anyhow, bail};
+
+use crate::archive_layout::index_name;
+
+/// Where an entry of a ZIP is, as its central directory says
+#[derive(Debug, Clone, Copy)]
+pub struct ZipEntry {
+    /// offset of the entry's local header
+    pub header_offset: u64,
+    pub compressed_size: u64,
+    /// size once inflated
+    pub size: u64,
+    /// 0 stored, 8 deflated
+    pub method: u16,
+    /// needs a password, ZipCrypto or AES
+    pub encrypted: bool,
+}
+
+/// The entries of one ZIP by name, read from its central directory the
+/// first time the ZIP is opened and saved so later runs don't read it
+/// again
+pub struct ZipIndex {
+    entries: HashMap<String, ZipEntry>,
+}
+
+/// start of a saved ZIP index, bumped if the format changes
+const INDEX_MAGIC: &[u8; 8] = b"ZIPIDX03";
+
+const EOCD_SIG: u32 = 0x06054b50;
+const ZIP64_LOCATOR_SIG: u32 = 0x07064b50;
+const ZIP64_EOCD_SIG: u32 = 0x06064b50;
+const CENTRAL_SIG: u32 = 0x02014b50;
+const LOCAL_SIG: u32 = 0x04034b50;
+
+impl ZipIndex {
+    pub fn get(&self, name: &str) -> Option<ZipEntry> {
+        self.entries.get(name).copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// The files a ZIP is stored in, just the ZIP unless a mirror split it.
+/// Split ZIPs are either `.z01`, `.z02`, … parts ending in the `.zip`, with
+/// offsets counted per part, or `.zip.001`, `.zip.002`, … pieces that are
+/// one ZIP once put back together
+pub struct ZipParts {
+    paths: Vec<PathBuf>,
+    /// where each part starts in the parts put together
+    starts: Vec<u64>,
+    len: u64,
+    /// offsets in the ZIP are per part, as in `.z01` parts
+    spanned: bool,
+}
+
+impl ZipParts {
+    /// The parts of the ZIP at `zip`, which may only exist as pieces
+    pub fn find(zip: &Path) -> Result<Self> {
+        let numbered = |name: &dyn Fn(usize) -> PathBuf| -> Vec<PathBuf> {
+            (1..).map(name).take_while(|path| path.is_file()).collect()
+        };
+        let (paths, spanned) = if zip.is_file() {
+            let mut paths = numbered(&|n| zip.with_extension(format!("z{:02}", n)));
+            let spanned = !paths.is_empty();
+            paths.push(zip.to_path_buf());
+            (paths, spanned)
+        } else {
+            let paths = numbered(&|n| {
+                let mut piece = zip.as_os_str().to_owned();
+                piece.push(format!(".{:03}", n));
+                PathBuf::from(piece)
+            });
+            if paths.is_empty() {
+                bail!("couldn't open {}: no such file", zip.display());
+            }
+            (paths, false)
+        };
+
+        let mut starts = Vec::with_capacity(paths.len());
+        let mut len = 0;
+        for path in &paths {
+            starts.push(len);
+            len += fs::metadata(path)
+                .map_err(|e| anyhow!("couldn't open {}: {}", path.display(), e))?
+                .len();
+        }
+        Ok(Self { paths, starts, len, spanned })
+    }
+
+    pub fn is_split(&self) -> bool {
+        self.paths.len() > 1
+    }
+
+    /// split into `.z01`, `.z02`, … parts, which only this module can read
+    pub fn is_spanned(&self) -> bool {
+        self.spanned
+    }
+
+    /// the part with the central directory, saved indexes are named after it
+    pub fn last(&self) -> &Path {
+        &self.paths[self.paths.len() - 1]
+    }
+
+    /// Where `offset` of part `disk` is in the parts put together
+    fn offset(&self, disk: u32, offset: u64) -> Result<u64> {
+        match (self.spanned, disk) {
+            (true, _) => match self.starts.get(disk as usize) {
+                Some(start) => Ok(start + offset),
+                None => bail!(
+                    "{} has {} parts but refers to part {}, is one missing?",
+                    self.last().display(),
+                    self.paths.len(),
+                    disk + 1
+                ),
+            },
+            (false, 0) => Ok(offset),
+            (false, _) => bail!(
+                "{} is the last part of a split ZIP, but its .z01, .z02, … parts are missing",
+                self.last().display()
+            ),
+        }
+    }
+
+    pub fn open(&self) -> Result<PartsReader> {
+        let files = self
+            .paths
+            .iter()
+            .map(|path| File::open(path).map_err(|e| anyhow!("couldn't open {}: {}", path.display(), e)))
+            .collect::<Result<_>>()?;
+        Ok(PartsReader {
+            files,
+            starts: self.starts.clone(),
+            len: self.len,
+            pos: 0,
+        })
+    }
+}
+
+/// The parts of a ZIP read as one file
+pub struct PartsReader {
+    files: Vec<File>,
+    starts: Vec<u64>,
+    len: u64,
+    pos: u64,
+}
+
+impl Read for PartsReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.pos >= self.len || buf.is_empty() {
+            return Ok(0);
+        }
+        // the last part starting at `pos`, empty ones are skipped
+        let part = self.starts.partition_point(|&start| start <= self.pos) - 1;
+        let end = self.starts.get(part + 1).copied().unwrap_or(self.len);
+        let want = buf.len().min((end - self.pos) as usize);
+        let file = &mut self.files[part];
+        file.seek(SeekFrom::Start(self.pos - self.starts[part]))?;
+        let read = file.read(&mut buf[..want])?;
+        self.pos += read as u64;
+        Ok(read)
+    }
+}
+
+impl Seek for PartsReader {
+    fn seek(&mut self, to: SeekFrom) -> io::Result<u64> {
+        let pos = match to {
+            SeekFrom::Start(pos) => Some(pos),
+            SeekFrom::End(delta) => self.len.checked_add_signed(delta),
+            SeekFrom::Current(delta) => self.pos.checked_add_signed(delta),
+        };
+        self.pos = pos.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "seek before the start"))?;
+        Ok(self.pos)
+    }
+}
+
+static INDEXES: OnceLock<Mutex<HashMap<PathBuf, Arc<ZipIndex>>>> = OnceLock::new();
+
+/// The index of the ZIP at `zip`, from memory, from `index_dir` if an
+/// earlier run saved it and the ZIP hasn't changed since, or read from the
+/// ZIP and saved there
+pub fn index(zip: &Path, index_dir: &Path) -> Result<Arc<ZipIndex>> {
+    let indexes = INDEXES.get_or_init(Default::default);
+    if let Some(index) = indexes
+        .lock()
+        .map_err(|_| anyhow!("zip index lock poisoned"))?
+        .get(zip)
+    {
+        return Ok(index.clone());
+    }
+
+    let parts = ZipParts::find(zip)?;
+    let saved = index_dir.join(index_name(parts.last())?);
+    let index = Arc::new(match read_saved_index(&saved) {
+        Some(index) => index,
+        None => {
+            let index = scan_central_directory(zip, &parts)?;
+            fs::create_dir_all(index_dir).map_err(|e| {
+                anyhow!(
+                    "couldn't create zip index dir `{}`: {}",
+                    index_dir.display(),
+                    e
+                )
+            })?;
+            write_saved_index(&saved, &index)?;
+            index
+        }
+    });
+    indexes
+        .lock()
+        .map_err(|_| anyhow!("zip index lock poisoned"))?
+        .insert(zip.to_path_buf(), index.clone());
+    Ok(index)
+}
+
+/// Where the data of `entry` starts, its local header has to be read for
+/// that since its extra field can differ from the central directory's
+pub fn data_start(file: &mut impl ReadSeek, entry: ZipEntry) -> Result<u64> {
+    let mut header = [0u8; 30];
+    file.seek(SeekFrom::Start(entry.header_offset))?;
+    file.read_exact(&mut header)?;
+    if u32_at(&header, 0) != LOCAL_SIG {
+        bail!("no local header at {}", entry.header_offset);
+    }
+    let name_len = u16_at(&header, 26) as u64;
+    let extra_len = u16_at(&header, 28) as u64;
+    Ok(entry.header_offset + 30 + name_len + extra_len)
+}
+
+fn u16_at(data: &[u8], at: usize) -> u16 {
+    u16::from_le_bytes(data[at..at + 2].try_into().unwrap())
+}
+
+fn u32_at(data: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes(data[at..at + 4].try_into().unwrap())
+}
+
+fn u64_at(data: &[u8], at: usize) -> u64 {
+    u64::from_le_bytes(data[at..at + 8].try_into().unwrap())
+}
+
+/// a ZIP file or the parts of one
+pub trait ReadSeek: Read + Seek {}
+
+impl<T: Read + Seek> ReadSeek for T {}
+
+fn read_at(file: &mut impl ReadSeek, offset: u64, len: usize) -> Result<Vec<u8>> {
+    let mut buf = vec![0u8; len];
+    file.seek(SeekFrom::Start(offset))?;
+    file.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+/// Every entry of a ZIP, from its central directory. Only the end of the
+/// file and the directory itself are read
+fn scan_central_directory(path: &Path, parts: &ZipParts) -> Result<ZipIndex> {
+    let not_a_zip = |why: &str| anyhow!("{} not a zip: {}", path.display(), why);
+    let mut file = parts.open()?;
+    let file_len = parts.len;
+
+    // the end of central directory record is followed by a comment of up to 64k
+    let tail_len = file_len.min(22 + 0xffff);
+    let tail = read_at(&mut file, file_len - tail_len, tail_len as usize)?;
+    let eocd = (0..tail.len().saturating_sub(21))
+        .rev()
+        .find(|&i| u32_at(&tail, i) == EOCD_SIG)
+        .ok_or_else(|| not_a_zip("no end of central directory"))?;
+    let mut cd_disk = u16_at(&tail, eocd + 6) as u32;
+    let mut count = u16_at(&tail, eocd + 10) as u64;
+    let mut cd_size = u32_at(&tail, eocd + 12) as u64;
+    let mut cd_offset = u32_at(&tail, eocd + 16) as u64;
+
+    if (count == 0xffff || cd_size == 0xffff_ffff || cd_offset == 0xffff_ffff)
+        && eocd >= 20
+        && u32_at(&tail, eocd - 20) == ZIP64_LOCATOR_SIG
+    {
+        let zip64_at = parts.offset(u32_at(&tail, eocd - 16), u64_at(&tail, eocd - 12))?;
+        let zip64_eocd = read_at(&mut file, zip64_at, 56)?;
+        if u32_at(&zip64_eocd, 0) != ZIP64_EOCD_SIG {
+            return Err(not_a_zip("broken zip64 end of central directory"));
+        }
+        cd_disk = u32_at(&zip64_eocd, 20);
+        count = u64_at(&zip64_eocd, 32);
+        cd_size = u64_at(&zip64_eocd, 40);
+        cd_offset = u64_at(&zip64_eocd, 48);
+    }
+    let cd_offset = parts.offset(cd_disk, cd_offset)?;
+    if cd_offset
+        .checked_add(cd_size)
+        .is_none_or(|end| end > file_len)
+    {
+        return Err(not_a_zip("central directory is past the end of the file"));
+    }
+
+    let cd = read_at(&mut file, cd_offset, cd_size as usize)?;
+    let mut entries = HashMap::with_capacity(count as usize);
+    let mut pos = 0;
+    while pos + 46 <= cd.len() && u32_at(&cd, pos) == CENTRAL_SIG {
+        let flags = u16_at(&cd, pos + 8);
+        let method = u16_at(&cd, pos + 10);
+        let mut compressed_size = u32_at(&cd, pos + 20) as u64;
+        let mut size = u32_at(&cd, pos + 24) as u64;
+        let name_len = u16_at(&cd, pos + 28) as usize;
+        let extra_len = u16_at(&cd, pos + 30) as usize;
+        let comment_len = u16_at(&cd, pos + 32) as usize;
+        let mut disk = u16_at(&cd, pos + 34) as u32;
+        let mut header_offset = u32_at(&cd, pos + 42) as u64;
+        let name_end = pos + 46 + name_len;
+        let extra_end = name_end + extra_len;
+        if extra_end + comment_len > cd.len() {
+            return Err(not_a_zip("truncated central directory"));
+        }
+        let name = String::from_utf8_lossy(&cd[pos + 46..name_end]).into_owned();
+
+        // zip64 sizes, offsets and the part are in an extra field, in this
+        // order, each only if the 32-bit (16 for the part) field is maxed out
+        let mut extra = &cd[name_end..extra_end];
+        while extra.len() >= 4 {
+            let (id, len) = (u16_at(extra, 0), u16_at(extra, 2) as usize);
+            let Some(field) = extra.get(4..4 + len) else {
+                break;
+            };
+            if id == 0x0001 {
+                let mut values = field.chunks_exact(8).map(|v| u64_at(v, 0));
+                if size == 0xffff_ffff {
+                    size = values.next().unwrap_or(size);
+                }
+                if compressed_size == 0xffff_ffff {
+                    compressed_size = values.next().unwrap_or(compressed_size);
+                }
+                if header_offset == 0xffff_ffff {
+                    header_offset = values.next().unwrap_or(header_offset);
+                }
+                if disk == 0xffff
+                    && let Some(part) = field.len().checked_sub(4).and_then(|at| field.get(at..))
+                {
+                    disk = u32_at(part, 0);
+                }
+            }
+            extra = &extra[4 + len..];
+        }
+
+        entries.insert(
+            name,
+            ZipEntry {
+                header_offset: parts.offset(disk, header_offset)?,
+                compressed_size,
+                size,
+                method,
+                encrypted: flags & 1 != 0,
+            },
+        );
+        pos = extra_end + comment_len;
+    }
+    Ok(ZipIndex { entries })
+}
+
+fn read_saved_index(path: &Path) -> Option<ZipIndex> {
+    let data = fs::read(path).ok()?;
+    let mut body = data.strip_prefix(INDEX_MAGIC)?;
+    let mut entries = HashMap::new();
+    while !body.is_empty() {
+        let name_len = u16_at(body.get(..2)?, 0) as usize;
+        let record = body.get(2..2 + name_len + 27)?;
+        let name = String::from_utf8(record[..name_len].to_vec()).ok()?;
+        let fields = &record[name_len..];
+        entries.insert(
+            name,
+            ZipEntry {
+                header_offset: u64_at(fields, 0),
+                compressed_size: u64_at(fields, 8),
+                size: u64_at(fields, 16),
+                method: u16_at(fields, 24),
+                encrypted: fields[26] != 0,
+            },
+        );
+        body = &body[2 + name_len + 27..];
+    }
+    Some(ZipIndex { entries })
+}
+
+fn write_saved_index(path: &Path, index: &ZipIndex) -> Result<()> {
+    let mut data = Vec::with_capacity(INDEX_MAGIC.len() + index.entries.len() * 64);
+    data.extend_from_slice(INDEX_MAGIC);
+    for (name, entry) in &index.entries {
+        // entry names are at most 64k in a ZIP, the length always fits
+        data.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        data.extend_from_slice(name.as_bytes());
+        data.extend_from_slice(&entry.header_offset.to_le_bytes());
+        data.extend_from_slice(&entry.compressed_size.to_le_bytes());
+        data.extend_from_slice(&entry.size.to_le_bytes());
+        data.extend_from_slice(&entry.method.to_le_bytes());
+        data.push(entry.encrypted as u8);
+    }
+    let tmp = path.with_extension(format!("zidx.{}.tmp", std::process::id()));
+    fs::write(&tmp, data)?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
@@ -0,0 +1,218 @@
+//! An adventure's shared data, the quests and adventure-wide state the game
+//! loads with the adventure slot: what's found under the adventure root,
+//! what's only warned about, and the slot list dependency table it ends up
+//! in.
+
+use std::collections::BTreeMap;
+
+use archive_dl::ResrcDescriptor;
+use archive_dl::adventure::collect_shared_data;
+use archive_dl::db::{GameVersion, LevelType, SlotInfo};
+use archive_dl::resource_parse::{ResrcData, ResrcMethod, ResrcRevision};
+use archive_dl::serializers::lbp::make_slotlist;
+use archive_dl::serializers::text::TextLimits;
+
+mod common;
+use common::{resource, sha1};
+
+/// A resource depending on `deps` as `(sha1, type)`, and its SHA1
+fn hashed(magic: &[u8; 4], deps: &[([u8; 20], u32)]) -> ([u8; 20], Vec<u8>) {
+    let res = resource(magic, deps);
+    (sha1(&res), res)
+}
+
+const LEVEL: u32 = 9;
+const QUEST: u32 = 35;
+const SHARED: u32 = 36;
+
+struct Adventure {
+    root: [u8; 20],
+    resources: BTreeMap<[u8; 20], Vec<u8>>,
+    /// the shared data under the root, `broken` among it
+    shared: Vec<[u8; 20]>,
+    broken: [u8; 20],
+    missing: [u8; 20],
+    quest_in_level: [u8; 20],
+}
+
+/// An adventure with a quest of its own, shared data with another quest
+/// whose texture wasn't downloaded, shared data that doesn't parse and a
+/// level with a quest of the level's own
+fn adventure() -> Adventure {
+    let missing = [0xee; 20];
+    let (quest1, quest1_data) = hashed(b"QSTb", &[]);
+    let (quest2, quest2_data) = hashed(b"QSTb", &[(missing, 1)]);
+    let (shared, shared_data) = hashed(b"ADSb", &[(quest2, QUEST)]);
+    let broken_data = b"ADSb\0\0".to_vec();
+    let broken = sha1(&broken_data);
+    let (quest_in_level, quest_in_level_data) = hashed(b"QSTb", &[([0xdd; 20], 1)]);
+    let (level, level_data) = hashed(b"LVLb", &[(quest_in_level, QUEST)]);
+    let (root, root_data) = hashed(
+        b"ADCb",
+        &[
+            (level, LEVEL),
+            (shared, SHARED),
+            (quest1, QUEST),
+            (broken, SHARED),
+        ],
+    );
+    let resources = BTreeMap::from([
+        (root, root_data),
+        (level, level_data),
+        (quest_in_level, quest_in_level_data),
+        (shared, shared_data),
+        (quest1, quest1_data),
+        (quest2, quest2_data),
+        (broken, broken_data),
+    ]);
+    Adventure {
+        root,
+        resources,
+        shared: vec![shared, quest1, broken, quest2],
+        broken,
+        missing,
+        quest_in_level,
+    }
+}
+
+fn sha1s<T>(deps: &[(ResrcDescriptor, T)]) -> Vec<[u8; 20]> {
+    deps.iter()
+        .filter_map(|(desc, _)| match desc {
+            ResrcDescriptor::Sha1(sha1) => Some(*sha1),
+            ResrcDescriptor::Guid(_) => None,
+        })
+        .collect()
+}
+
+#[test]
+fn shared_data_and_quests_are_found_under_the_root() {
+    let adventure = adventure();
+    let found = collect_shared_data(adventure.root, &adventure.resources).unwrap();
+
+    let mut expected = adventure.shared.clone();
+    expected.sort();
+    let mut shared = sha1s(&found.dependencies);
+    shared.sort();
+    assert_eq!(shared, expected);
+    assert_eq!(found.quest_count, 2);
+    // levels aren't walked, their quests are theirs
+    assert!(!shared.contains(&adventure.quest_in_level));
+    assert_eq!(found.missing, [adventure.missing]);
+}
+
+#[test]
+fn unreadable_shared_data_is_skipped() {
+    let adventure = adventure();
+    let found = collect_shared_data(adventure.root, &adventure.resources).unwrap();
+    assert_eq!(found.unreadable.len(), 1);
+    assert_eq!(found.unreadable[0].0, adventure.broken);
+}
+
+#[test]
+fn only_adventures_have_shared_data() {
+    let adventure = adventure();
+    let (level, level_data) = hashed(b"LVLb", &[]);
+    let resources = BTreeMap::from([(level, level_data)]);
+    let e = collect_shared_data(level, &resources).err().unwrap();
+    assert!(e.to_string().contains("not an ADC resource"), "{e}");
+    let e = collect_shared_data([0; 20], &adventure.resources)
+        .err()
+        .unwrap();
+    assert!(e.to_string().contains("missing"), "{e}");
+}
+
+/// The adventure's slot, with the shared data `collect_shared_data` found
+fn adventure_slot(adventure: &Adventure, shared: Vec<(ResrcDescriptor, u32)>) -> SlotInfo {
+    SlotInfo {
+        id: 0,
+        guid: None,
+        name: "Adventure".to_string(),
+        description: String::new(),
+        np_handle: "adventurer".to_string(),
+        root_level: adventure.root,
+        icon: ResrcDescriptor::Guid(0),
+        game: GameVersion::Lbp3,
+        initially_locked: false,
+        is_sub_level: false,
+        background_guid: None,
+        shareable: false,
+        author_labels: Vec::new(),
+        leveltype: LevelType::Cooperative,
+        min_players: None,
+        max_players: None,
+        enforce_min_max_players: false,
+        same_screen_game: false,
+        is_adventure_planet: true,
+        planet_decorations: None,
+        adventure_shared: shared,
+    }
+}
+
+/// The slot list's dependency table as `(descriptor, type)`
+fn dependency_table(slt: &[u8]) -> Vec<(ResrcDescriptor, u32)> {
+    let ResrcMethod::Binary { dependencies, .. } = ResrcData::new(slt, false).unwrap().method
+    else {
+        panic!("the slot list isn't a binary resource");
+    };
+    dependencies
+        .iter()
+        .map(|d| (d.desc, d.resrc_type))
+        .collect()
+}
+
+#[test]
+fn the_slot_list_depends_on_the_shared_data() {
+    let adventure = adventure();
+    let found = collect_shared_data(adventure.root, &adventure.resources).unwrap();
+    let slot_info = adventure_slot(&adventure, found.dependencies.clone());
+    let revision = ResrcRevision {
+        head: 0x3f8,
+        branch_id: 0,
+        branch_revision: 0,
+    };
+    let slt = make_slotlist(&revision, &slot_info, &TextLimits::default()).unwrap();
+
+    let table = dependency_table(&slt);
+    for shared in &found.dependencies {
+        assert!(table.contains(shared), "{shared:?} isn't in {table:?}");
+    }
+}
+
+#[test]
+fn the_lbp3_slot_points_at_the_adventure() {
+    let adventure = adventure();
+    let found = collect_shared_data(adventure.root, &adventure.resources).unwrap();
+    let mut slot_info = adventure_slot(&adventure, found.dependencies.clone());
+    // the adventure's levels have game modes, the adventure doesn't
+    slot_info.leveltype = LevelType::Versus;
+    slot_info.min_players = Some(2);
+    slot_info.max_players = Some(3);
+    let revision = GameVersion::Lbp3.get_latest_revision();
+    let slt = make_slotlist(&revision, &slot_info, &TextLimits::default()).unwrap();
+
+    let table = dependency_table(&slt);
+    assert!(table.contains(&(ResrcDescriptor::Sha1(adventure.root), 31)));
+    assert!(!table.contains(&(ResrcDescriptor::Sha1(adventure.root), LEVEL)));
+    for shared in &found.dependencies {
+        assert!(table.contains(shared), "{shared:?} isn't in {table:?}");
+    }
+
+    // header, slot count and SlotID, then no root level and the adventure
+    let slot = 18 + 4 + 8;
+    assert_eq!(slt[slot], 0);
+    assert_eq!(slt[slot + 1], 1);
+    assert_eq!(slt[slot + 2..slot + 22], adventure.root);
+
+    // the LBP3 fields end the slot, right before fromProductionBuild
+    let end = u32::from_be_bytes(slt[8..12].try_into().unwrap()) as usize - 1;
+    assert_eq!(slt[end], 1);
+    let lbp3 = &slt[end - 30..end];
+    assert_eq!(lbp3[..2], [2, 3]); // minPlayers, maxPlayers
+    assert_eq!(lbp3[5], 1); // showOnPlanet
+    assert_eq!(lbp3[7], 0); // gameMode
+    assert_eq!(lbp3[8], 0); // isGameKit
+    assert_eq!(lbp3[9..13], [0; 4]); // entranceName
+    assert_eq!(lbp3[13..21], [0; 8]); // originalSlotID, no story slot
+    assert_eq!(lbp3[21], 1); // customBadgeSize
+    assert_eq!(lbp3[22..], [0; 8]); // localPath, thumbPath
+}
@@ -0,0 +1,106 @@
+//! Rewriting the SHA1 references of resources, what `--alternates` does to
+//! a level when a missing resource is swapped for one of its group. The
+//! rewritten resource must parse again and name the new hash in both its
+//! dependency table and its data.
+
+use std::collections::BTreeMap;
+
+use archive_dl::ResrcDescriptor;
+use archive_dl::alternates::patch_references;
+use archive_dl::resource_parse::{ResrcData, ResrcMethod, binary_payload, replace_sha1_references};
+
+mod common;
+use common::{binary_resource, sha1};
+
+/// A binary resource whose data and dependency table name `deps`, with the
+/// data compressed as the game does when `compressed`
+fn resource(magic: &[u8; 4], deps: &[[u8; 20]], compressed: bool) -> Vec<u8> {
+    let mut data = b"before".to_vec();
+    for dep in deps {
+        data.extend(dep);
+        data.extend(b"between");
+    }
+    if compressed {
+        let deflated = miniz_oxide::deflate::compress_to_vec_zlib(&data, 6);
+        let mut chunks = vec![0, 1, 0, 1];
+        chunks.extend((deflated.len() as u16).to_be_bytes());
+        chunks.extend((data.len() as u16).to_be_bytes());
+        chunks.extend(deflated);
+        data = chunks;
+    }
+
+    let deps: Vec<_> = deps
+        .iter()
+        .map(|&dep| (ResrcDescriptor::Sha1(dep), 1))
+        .collect();
+    binary_resource(magic, compressed, &data, &deps)
+}
+
+fn dependencies(res: &[u8]) -> Vec<[u8; 20]> {
+    let ResrcMethod::Binary { dependencies, .. } = ResrcData::new(res, false).unwrap().method
+    else {
+        panic!("not a binary resource");
+    };
+    dependencies
+        .iter()
+        .map(|d| match d.desc {
+            ResrcDescriptor::Sha1(sha1) => sha1,
+            ResrcDescriptor::Guid(guid) => panic!("guid {guid}"),
+        })
+        .collect()
+}
+
+fn contains(data: &[u8], hash: &[u8; 20]) -> bool {
+    data.windows(20).any(|w| w == hash)
+}
+
+#[test]
+fn references_are_rewritten_in_the_table_and_the_data() {
+    let (kept, old, new) = ([1; 20], [2; 20], [3; 20]);
+    for compressed in [false, true] {
+        let res = resource(b"LVLb", &[kept, old], compressed);
+        let patched = replace_sha1_references(&res, &BTreeMap::from([(old, new)]))
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(
+            dependencies(&patched),
+            [kept, new],
+            "compressed: {compressed}"
+        );
+        let (data, flags) = binary_payload(&patched).unwrap().unwrap();
+        assert_eq!(flags, 0);
+        assert!(contains(&data, &kept) && contains(&data, &new));
+        assert!(!contains(&data, &old), "compressed: {compressed}");
+        assert!(data.starts_with(b"before") && data.ends_with(b"between"));
+    }
+}
+
+#[test]
+fn resources_without_the_hash_are_left_alone() {
+    let res = resource(b"LVLb", &[[1; 20]], false);
+    let map = BTreeMap::from([([2; 20], [3; 20])]);
+    assert!(replace_sha1_references(&res, &map).unwrap().is_none());
+}
+
+#[test]
+fn rewrites_go_up_to_the_root() {
+    let (missing, alternate) = ([2; 20], [3; 20]);
+    let texture = [9; 20];
+    let plan = resource(b"PLNb", &[missing, texture], false);
+    let plan_hash = sha1(&plan);
+    let root = resource(b"LVLb", &[plan_hash], true);
+    let root_hash = sha1(&root);
+    let mut resources = BTreeMap::from([(plan_hash, plan), (root_hash, root)]);
+
+    let replaced = patch_references(&mut resources, &BTreeMap::from([(missing, alternate)]));
+
+    assert_eq!(replaced[&missing], alternate);
+    let (new_plan, new_root) = (replaced[&plan_hash], replaced[&root_hash]);
+    assert_eq!(resources.len(), 2);
+    for (hash, blob) in &resources {
+        assert_eq!(sha1(blob), *hash);
+    }
+    assert_eq!(dependencies(&resources[&new_plan]), [alternate, texture]);
+    assert_eq!(dependencies(&resources[&new_root]), [new_plan]);
+}
@@ -0,0 +1,77 @@
+//! `annotate-resource` decoding, checked against the slot lists the backup
+//! serializers write. A field the decoder reads at the wrong revision shifts
+//! everything after it, so the names and the end of the data tell.
+
+use archive_dl::annotate::{Field, Section, annotate};
+use archive_dl::self_test::{CASES, render};
+
+mod common;
+use common::binary_resource;
+
+fn section<'a>(sections: &'a [Section], name: &str) -> &'a Section {
+    sections.iter().find(|s| s.name == name).unwrap()
+}
+
+fn find<'a>(fields: &'a [Field], name: &str) -> Option<&'a Field> {
+    fields.iter().find_map(|f| match f.name == name {
+        true => Some(f),
+        false => find(&f.fields, name),
+    })
+}
+
+#[test]
+fn slot_lists_decode_to_the_end() {
+    for case in &CASES {
+        let slotlist = render(case).unwrap().remove(0);
+        let annotation = annotate(&slotlist).unwrap();
+        assert_eq!(annotation.magic, "SLTb");
+        for section in &annotation.sections {
+            assert!(
+                section.error.is_none(),
+                "{}: {} {:?}",
+                case.name,
+                section.name,
+                section.error
+            );
+        }
+
+        let data = &section(&annotation.sections, "data").fields;
+        let value = |name: &str| find(data, name).unwrap().value.as_str();
+        assert_eq!(value("name"), "\"Self Test Level\"", "{}", case.name);
+        assert_eq!(value("handle"), "\"selftest\"", "{}", case.name);
+        assert!(value("description").starts_with("\"A synthetic level"));
+    }
+}
+
+#[test]
+fn plans_keep_what_they_dont_decode() {
+    let mut data = 0x3f8u32.to_be_bytes().to_vec();
+    data.extend(3u32.to_be_bytes());
+    data.extend(b"abc");
+    data.extend(b"inven");
+    let pln = binary_resource(b"PLNb", false, &data, &[]);
+    let table = 18 + data.len();
+
+    let annotation = annotate(&pln).unwrap();
+    let data = section(&annotation.sections, "data");
+    assert!(data.error.is_none(), "{:?}", data.error);
+    let names: Vec<&str> = data.fields.iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(names, ["revision", "thingData", "inventoryData"]);
+    assert_eq!(data.fields[2].offset, table - 5);
+    assert_eq!(data.fields[2].size, 5);
+}
+
+#[test]
+fn truncated_resources_keep_the_fields_before() {
+    let slotlist = render(&CASES[1]).unwrap().remove(0);
+    let table = u32::from_be_bytes(slotlist[8..12].try_into().unwrap()) as usize;
+    // cut in the middle of the description
+    let mut cut = slotlist[..0xb0].to_vec();
+    cut.extend(&slotlist[table..]);
+    cut[8..12].copy_from_slice(&0xb0u32.to_be_bytes());
+
+    let annotation = annotate(&cut).unwrap();
+    let data = section(&annotation.sections, "data");
+    assert!(data.error.as_deref().unwrap().contains("description"));
+    assert!(find(&data.fields, "name").is_some());
+}
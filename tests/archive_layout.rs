@@ -0,0 +1,61 @@
+//! Finding blobs in tar archives, and not trusting what a corrupt one says.
+
+use std::fs;
+
+use archive_dl::archive_layout::{ArchiveLayout, Location};
+
+mod common;
+use common::temp_dir;
+
+const SHA1: &str = "0123456789abcdef0123456789abcdef01234567";
+
+/// a ustar header, the checksum isn't checked by the reader
+fn header(name: &str, size: u64, kind: u8) -> Vec<u8> {
+    let mut header = vec![0u8; 512];
+    header[..name.len()].copy_from_slice(name.as_bytes());
+    header[124..136].copy_from_slice(format!("{:011o}\0", size).as_bytes());
+    header[156] = kind;
+    header[257..262].copy_from_slice(b"ustar");
+    header
+}
+
+fn padded(data: &[u8]) -> Vec<u8> {
+    let mut data = data.to_vec();
+    data.resize(data.len().div_ceil(512) * 512, 0);
+    data
+}
+
+#[test]
+fn tars_are_indexed_with_long_names() {
+    let dir = temp_dir("tar");
+    let long_name = format!("{}/{}", "sub/".repeat(40), SHA1);
+    let mut tar = header("././@LongLink", long_name.len() as u64, b'L');
+    tar.extend(padded(long_name.as_bytes()));
+    tar.extend(header("ignored", 4, b'0'));
+    tar.extend(padded(b"LVLb"));
+    tar.extend([0; 1024]);
+    fs::write(dir.join("blobs.tar"), &tar).unwrap();
+
+    let layout = ArchiveLayout::detect(&dir, &dir.join("index")).unwrap();
+    match layout.locate(SHA1) {
+        Some(Location::Tar { offset, size, .. }) => {
+            assert_eq!(&tar[offset as usize..(offset + size) as usize], b"LVLb")
+        }
+        _ => panic!("the blob wasn't found in the tar"),
+    }
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn huge_long_name_headers_are_refused() {
+    let dir = temp_dir("bad_tar");
+    let mut tar = header("././@LongLink", 1 << 30, b'L');
+    tar.extend([0; 1024]);
+    fs::write(dir.join("blobs.tar"), &tar).unwrap();
+
+    let e = ArchiveLayout::detect(&dir, &dir.join("index"))
+        .err()
+        .unwrap();
+    assert!(e.to_string().contains("corrupt"), "{}", e);
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,99 @@
+//! `archive verify`: which ZIPs a shard covers, what's found in a ZIP and
+//! which archives can be verified at all.
+
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+
+use archive_dl::archive_layout::ArchiveLayout;
+use archive_dl::archive_verify::{Shard, check_layout, verify_zip};
+use sha1::{Digest, Sha1};
+use zip::ZipWriter;
+use zip::write::FileOptions;
+
+mod common;
+use common::temp_dir;
+
+fn write_zip(path: &Path, entries: &[(String, &[u8])]) {
+    let mut zip = ZipWriter::new(File::create(path).unwrap());
+    for (name, data) in entries {
+        zip.start_file(name.as_str(), FileOptions::default())
+            .unwrap();
+        zip.write_all(data).unwrap();
+    }
+    zip.finish().unwrap();
+}
+
+#[test]
+fn shards_split_the_prefixes_into_runs() {
+    assert_eq!(Shard::ALL.prefixes().len(), 256);
+    let mut all = Vec::new();
+    for index in 1..=3 {
+        let prefixes = Shard { index, count: 3 }.prefixes();
+        let numbers: Vec<usize> = prefixes
+            .iter()
+            .map(|p| usize::from_str_radix(p, 16).unwrap())
+            .collect();
+        assert!(numbers.windows(2).all(|w| w[1] == w[0] + 1), "{index}/3");
+        assert!((85..=86).contains(&prefixes.len()), "{index}/3");
+        all.extend(prefixes);
+    }
+    assert_eq!(all, Shard::ALL.prefixes());
+    assert_eq!(
+        Shard::parse("1/4").unwrap().prefixes().last().unwrap(),
+        "3f"
+    );
+    assert_eq!(
+        Shard {
+            index: 256,
+            count: 256
+        }
+        .prefixes(),
+        ["ff"]
+    );
+}
+
+#[test]
+fn shards_that_dont_exist_are_refused() {
+    for text in ["0/4", "5/4", "1/0", "1/257", "3", "a/b"] {
+        assert!(Shard::parse(text).is_err(), "{text}");
+    }
+}
+
+#[test]
+fn zips_are_checked_entry_by_entry() {
+    let dir = temp_dir("zip");
+    let good = b"good blob";
+    let good_name = hex::encode(Sha1::digest(good));
+    let bad_name = hex::encode(Sha1::digest(b"what it was"));
+    let path = dir.join("dry00.zip");
+    write_zip(
+        &path,
+        &[
+            (format!("00/ab/{}", good_name), good),
+            (format!("00/cd/{}", bad_name), b"what it is now"),
+            ("readme.txt".to_string(), b"not a blob"),
+        ],
+    );
+
+    let result = verify_zip(&path);
+    assert!(result.error.is_none(), "{:?}", result.error);
+    assert_eq!(result.entries, 2);
+    assert_eq!(result.bytes, (good.len() + b"what it is now".len()) as u64);
+    assert_eq!(result.bad_hash, [bad_name]);
+    assert!(result.unreadable.is_empty());
+
+    let missing = verify_zip(&dir.join("dry01.zip"));
+    assert!(missing.error.unwrap().contains("couldn't open"));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn only_split_archives_are_verified() {
+    let dir = temp_dir("layout");
+    assert!(check_layout(&ArchiveLayout::Split(dir.clone())).is_ok());
+    let single = ArchiveLayout::SingleZip(dir.join("all.zip"));
+    let error = check_layout(&single).unwrap_err().to_string();
+    assert!(error.contains("a single ZIP"), "{error}");
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,171 @@
+//! The command line itself: flags that only some commands support are
+//! refused by the others rather than ignored, and options without a safe
+//! default must be given.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+mod common;
+use common::temp_dir;
+
+/// runs the tool in `dir`, which gets the default config.yml
+fn run(dir: &PathBuf, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_archive_dl"))
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn json_is_refused_by_commands_that_print_text() {
+    let dir = temp_dir("json");
+    let output = run(&dir, &["--json", "queue", "list"]);
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("--json only works with"), "{stderr}");
+    assert!(output.stdout.is_empty());
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn refresh_params_needs_the_language_of_old_backups() {
+    let dir = temp_dir("refresh");
+    let backup = dir.join("BCES00850LEVEL0000002A");
+    fs::create_dir_all(&backup).unwrap();
+    let meta = fs::read_to_string(
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/schema/backup.meta.v1.json"),
+    )
+    .unwrap();
+    fs::write(backup.join("backup.meta.json"), meta).unwrap();
+
+    let output = run(&dir, &["refresh-params", "BCES00850LEVEL0000002A"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("pass --language"), "{stderr}");
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn metrics_file_has_every_counter() {
+    let dir = temp_dir("metrics");
+    run(&dir, &["--metrics-file", "run.prom", "queue", "list"]);
+
+    let metrics = fs::read_to_string(dir.join("run.prom")).unwrap();
+    assert!(metrics.ends_with("# EOF\n"), "{metrics}");
+    assert!(
+        metrics.contains("# TYPE archive_cache_hits counter\n"),
+        "{metrics}"
+    );
+    assert!(
+        metrics.contains("archive_cache_hits_total 0\n"),
+        "{metrics}"
+    );
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn manpage_writes_a_page_per_command() {
+    let dir = temp_dir("manpage");
+    let output = run(&dir, &["manpage", "--output-dir", "man"]);
+    assert!(output.status.success());
+
+    let page = |name: &str| fs::read_to_string(dir.join("man").join(name)).unwrap();
+    assert!(page("archive_dl.1").contains(".SH SUBCOMMANDS"));
+    assert!(page("archive_dl-bkp.1").contains("force\\-copyable"));
+    assert!(page("archive_dl-db-upgrade.1").starts_with(".ie"));
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn init_writes_the_default_config_with_the_answers() {
+    let dir = temp_dir("init");
+    let output = run(&dir, &["init", "--yes", "--backup-directory", "my backups"]);
+    assert!(output.status.success(), "{output:?}");
+
+    let config = fs::read_to_string(dir.join("config.yml")).unwrap();
+    assert!(config.starts_with("# Written by `archive_dl init`"));
+    assert!(
+        config.contains("backup_directory: \"my backups\"\n"),
+        "{config}"
+    );
+    // the rest is the default config, comments included
+    let default = include_str!("../src/assets/default_config.yml");
+    for line in default
+        .lines()
+        .filter(|l| !l.starts_with("backup_directory:"))
+    {
+        assert!(config.contains(line), "{line}");
+    }
+    assert!(dir.join("my backups").is_dir());
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn init_writes_nothing_when_the_config_fails_its_check() {
+    let dir = temp_dir("init_bad");
+    let output = run(
+        &dir,
+        &[
+            "init",
+            "--yes",
+            "--backup-directory",
+            "new",
+            "--database-path",
+            ".",
+        ],
+    );
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("is a folder, not a database file"),
+        "{stderr}"
+    );
+    assert!(!dir.join("config.yml").exists());
+    assert!(!dir.join("new").exists());
+    fs::remove_dir_all(dir).unwrap();
+}
+
+/// the default config with `profile` under `profiles.test`
+fn write_profile(dir: &Path, profile: &str) {
+    let default = include_str!("../src/assets/default_config.yml");
+    fs::write(
+        dir.join("config.yml"),
+        format!("{default}\nprofiles:\n  test:\n{profile}"),
+    )
+    .unwrap();
+}
+
+#[test]
+fn profiles_set_the_export_settings() {
+    let dir = temp_dir("profile");
+    write_profile(
+        &dir,
+        "    export_guid_dependencies: true\n    export_dependency_types: true\n    relation_filter:\n      skip_types: [texture]\n",
+    );
+    let output = run(&dir, &["--profile", "test", "--verbose", "queue", "list"]);
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    for line in [
+        "export_guid_dependencies: true",
+        "export_dependency_types: true",
+        "relation_filter.skip_types: texture",
+    ] {
+        assert!(stderr.contains(line), "{line} isn't in {stderr}");
+    }
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn unknown_profile_keys_are_refused() {
+    let dir = temp_dir("profile_typo");
+    write_profile(&dir, "    relation_filtr:\n      collapse_textures: true\n");
+    let output = run(&dir, &["--profile", "test", "queue", "list"]);
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("relation_filtr"), "{stderr}");
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,71 @@
+//! Fixtures shared by the integration tests: scratch folders and the
+//! smallest binary resources the parser takes.
+
+// each test binary only uses some of them
+#![allow(dead_code)]
+
+use std::fs;
+use std::path::PathBuf;
+
+use archive_dl::ResrcDescriptor;
+use sha1::{Digest, Sha1};
+
+/// An empty folder for the test `name`, unique to the test binary and the
+/// run. Tests remove it when they pass
+pub fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "archive_dl_{}_{}_{}",
+        env!("CARGO_CRATE_NAME"),
+        name,
+        std::process::id()
+    ));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+pub fn sha1(data: &[u8]) -> [u8; 20] {
+    Sha1::digest(data).into()
+}
+
+/// A binary resource with `data` before its dependency table of `deps`,
+/// each with its declared type. `compressed` is the header's compression
+/// flag, `data` is written as given either way
+pub fn binary_resource(
+    magic: &[u8; 4],
+    compressed: bool,
+    data: &[u8],
+    deps: &[(ResrcDescriptor, u32)],
+) -> Vec<u8> {
+    let mut res = magic.to_vec();
+    res.extend(0x3f8u32.to_be_bytes());
+    res.extend(((res.len() + 4 + 6 + data.len()) as u32).to_be_bytes());
+    // mainline, no flags
+    res.extend([0, 0, 0, 0, 0, compressed as u8]);
+    res.extend(data);
+    res.extend((deps.len() as u32).to_be_bytes());
+    for (desc, resrc_type) in deps {
+        match desc {
+            ResrcDescriptor::Sha1(sha1) => {
+                res.push(1);
+                res.extend(sha1);
+            }
+            ResrcDescriptor::Guid(guid) => {
+                res.push(2);
+                res.extend(guid.to_be_bytes());
+            }
+        }
+        res.extend(resrc_type.to_be_bytes());
+    }
+    res
+}
+
+/// An uncompressed binary resource without data, depending on the SHA1s
+/// `deps` declared as their type
+pub fn resource(magic: &[u8; 4], deps: &[([u8; 20], u32)]) -> Vec<u8> {
+    let deps: Vec<_> = deps
+        .iter()
+        .map(|&(sha1, resrc_type)| (ResrcDescriptor::Sha1(sha1), resrc_type))
+        .collect();
+    binary_resource(magic, false, &[], &deps)
+}
@@ -0,0 +1,69 @@
+//! The `stats` estimate: what's counted from a level's resources and which
+//! of its sizes and counts decides how demanding it is taken to be.
+
+use std::collections::BTreeMap;
+
+use archive_dl::ResrcDescriptor;
+use archive_dl::complexity::{Estimate, estimate};
+
+mod common;
+use common::binary_resource;
+
+/// An uncompressed binary resource with `data` and SHA1 dependencies of
+/// `resrc_type`
+fn resource(magic: &[u8; 4], data: &[u8], deps: &[(u32, [u8; 20])]) -> Vec<u8> {
+    let deps: Vec<_> = deps
+        .iter()
+        .map(|&(resrc_type, dep)| (ResrcDescriptor::Sha1(dep), resrc_type))
+        .collect();
+    binary_resource(magic, false, data, &deps)
+}
+
+fn hash(n: u32) -> [u8; 20] {
+    let mut hash = [0xaa; 20];
+    hash[..4].copy_from_slice(&n.to_be_bytes());
+    hash
+}
+
+const ROOT: [u8; 20] = [1; 20];
+
+/// A level placing `meshes` meshes and `materials` gfx materials of its own
+fn level(level_data: usize, meshes: u32, materials: u32) -> BTreeMap<[u8; 20], Vec<u8>> {
+    let mut deps: Vec<(u32, [u8; 20])> = (0..meshes).map(|i| (2, hash(i))).collect();
+    deps.extend((0..materials).map(|i| (7, hash(1 << 20 | i))));
+    let mut resources = BTreeMap::from([(ROOT, resource(b"LVLb", &vec![0; level_data], &deps))]);
+    for &(resrc_type, dep) in &deps {
+        let magic = if resrc_type == 2 { b"MSHb" } else { b"GMTb" };
+        resources.insert(dep, resource(magic, &[0; 16], &[]));
+    }
+    resources
+}
+
+#[test]
+fn resources_are_counted_by_kind() {
+    let complexity = estimate(ROOT, &level(100, 3, 2)).unwrap();
+    assert_eq!(complexity.level_data, 100);
+    assert_eq!(complexity.meshes.count, 3);
+    assert_eq!(complexity.materials.count, 2);
+    assert_eq!(complexity.resources.count, 6);
+    assert_eq!(complexity.references["mesh"].sha1, 3);
+    assert_eq!(complexity.references["gfx material"].sha1, 2);
+    assert_eq!(complexity.estimate, Estimate::Light);
+}
+
+#[test]
+fn many_distinct_meshes_and_materials_make_a_level_heavy() {
+    let complexity = estimate(ROOT, &level(100, 200, 100)).unwrap();
+    assert_eq!(complexity.estimate, Estimate::Heavy);
+}
+
+#[test]
+fn big_level_data_makes_a_level_extreme() {
+    let complexity = estimate(ROOT, &level(6 << 20, 1, 0)).unwrap();
+    assert_eq!(complexity.estimate, Estimate::Extreme);
+}
+
+#[test]
+fn a_missing_root_level_is_an_error() {
+    assert!(estimate([9; 20], &level(100, 1, 1)).is_err());
+}
@@ -0,0 +1,45 @@
+//! Lookups on the archive database that depend on columns not every dump has.
+
+use archive_dl::db::{PlanetExtras, get_planet_extras};
+use rusqlite::Connection;
+
+#[test]
+fn planet_extras_come_from_the_user_record() {
+    let conn = Connection::open_in_memory().unwrap();
+    conn.execute_batch(
+        r#"CREATE TABLE "user" (npHandle TEXT, icon BLOB, planets BLOB, planetDecorations BLOB, pod BLOB);
+           INSERT INTO "user" VALUES ('dressed', NULL, NULL, x'0101010101010101010101010101010101010101', x'0202020202020202020202020202020202020202');
+           INSERT INTO "user" VALUES ('odd', NULL, NULL, x'01', NULL);"#,
+    )
+    .unwrap();
+    assert_eq!(
+        get_planet_extras(&conn, "dressed").unwrap(),
+        PlanetExtras {
+            decorations: Some([1; 20]),
+            pod: Some([2; 20])
+        }
+    );
+    // not a SHA1, or no such user
+    assert_eq!(
+        get_planet_extras(&conn, "odd").unwrap(),
+        PlanetExtras::default()
+    );
+    assert_eq!(
+        get_planet_extras(&conn, "nobody").unwrap(),
+        PlanetExtras::default()
+    );
+}
+
+#[test]
+fn planet_extras_are_none_without_the_columns() {
+    let conn = Connection::open_in_memory().unwrap();
+    conn.execute_batch(
+        r#"CREATE TABLE "user" (npHandle TEXT, icon BLOB, planets BLOB);
+           INSERT INTO "user" VALUES ('bare', NULL, x'03');"#,
+    )
+    .unwrap();
+    assert_eq!(
+        get_planet_extras(&conn, "bare").unwrap(),
+        PlanetExtras::default()
+    );
+}
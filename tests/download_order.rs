@@ -0,0 +1,110 @@
+//! A level fetched breadth first, one depth of its dependency tree at a
+//! time, each a stage counted before it starts. The download order is set
+//! once per process, the other orders are only covered by `fetch_queue`.
+
+use std::fs;
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+use archive_dl::progress::{ProgressObserver, Stage};
+use archive_dl::resource_dl::{self, DownloadOrder, zip_entry_name};
+use zip::ZipWriter;
+use zip::write::FileOptions;
+
+mod common;
+use common::{resource, sha1, temp_dir};
+
+/// A binary resource depending on `deps`, as a plan would, and its SHA1
+fn plan(magic: &[u8; 4], deps: &[[u8; 20]]) -> (Vec<u8>, [u8; 20]) {
+    let deps: Vec<_> = deps.iter().map(|&dep| (dep, 9)).collect();
+    let res = resource(magic, &deps);
+    let hash = sha1(&res);
+    (res, hash)
+}
+
+#[derive(Debug, PartialEq)]
+enum Event {
+    Stage(usize, usize),
+    Started(u8),
+}
+
+/// What the download reported, hashes by the letter `names` gives them
+#[derive(Default)]
+struct Recorder {
+    events: Mutex<Vec<Event>>,
+    names: Mutex<Vec<([u8; 20], u8)>>,
+}
+
+impl ProgressObserver for Recorder {
+    fn on_resource_started(&self, sha1: [u8; 20]) {
+        let names = self.names.lock().unwrap();
+        let name = names.iter().find(|(hash, _)| *hash == sha1).unwrap().1;
+        self.events.lock().unwrap().push(Event::Started(name));
+    }
+
+    fn on_stage_changed(&self, stage: Stage) {
+        if let Stage::Fetching { depth, resources } = stage {
+            self.events
+                .lock()
+                .unwrap()
+                .push(Event::Stage(depth, resources));
+        }
+    }
+}
+
+#[test]
+fn breadth_first_fetches_a_depth_at_a_time() {
+    let dir = temp_dir("breadth");
+    resource_dl::set_cache_dir(dir.join("cache"));
+    resource_dl::set_disk_cache(false);
+    resource_dl::set_download_order(DownloadOrder::Breadth);
+
+    // root → a, b; a → c. c is found before b is fetched, but waits for
+    // the next depth
+    let c = plan(b"GMTb", &[]);
+    let b = plan(b"PLNb", &[]);
+    let a = plan(b"PLNb", &[c.1]);
+    let root = plan(b"LVLb", &[a.1, b.1]);
+    let archive = dir.join("archive");
+    fs::create_dir_all(&archive).unwrap();
+    let mut zip = ZipWriter::new(fs::File::create(archive.join("blobs.zip")).unwrap());
+    for (data, hash) in [&root, &a, &b, &c] {
+        zip.start_file(zip_entry_name(&hex::encode(hash)), FileOptions::default())
+            .unwrap();
+        zip.write_all(data).unwrap();
+    }
+    zip.finish().unwrap();
+
+    let recorder = Arc::new(Recorder::default());
+    *recorder.names.lock().unwrap() = vec![(root.1, b'r'), (a.1, b'a'), (b.1, b'b'), (c.1, b'c')];
+    let result = tokio::runtime::Runtime::new()
+        .unwrap()
+        .block_on(resource_dl::download_level(
+            root.1,
+            None,
+            archive.to_string_lossy().into_owned(),
+            1,
+            false,
+            recorder.clone(),
+        ))
+        .unwrap();
+    assert_eq!(result.resources.len(), 4);
+
+    let events = recorder.events.lock().unwrap();
+    let Event::Stage(first, _) = events[0] else {
+        panic!("{events:?}");
+    };
+    assert_eq!(
+        *events,
+        [
+            Event::Stage(first, 1),
+            Event::Started(b'r'),
+            Event::Stage(first + 1, 2),
+            Event::Started(b'a'),
+            Event::Started(b'b'),
+            Event::Stage(first + 2, 1),
+            Event::Started(b'c'),
+        ]
+    );
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,159 @@
+//! `gc`: which blobs of a dump are garbage. Only the ones no manifest
+//! reaches, through the dependency tables of the blobs it does reach, and
+//! nothing in a folder without a manifest.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use archive_dl::db::fetch_all_assets;
+use archive_dl::dump_gc::{analyze, delete};
+use archive_dl::models::{
+    ImportData, PLANET_HASH_FILE, PLANET_STATE_FILE, PlanetFetchState, REFRESH_IMPORT_FILE,
+};
+use archive_dl::sink::CAS_OBJECTS_DIR;
+
+mod common;
+use common::{resource, sha1, temp_dir};
+
+/// Write `blob` into `dir` by its SHA1, below `objects/` like a CAS dump
+/// if `cas`
+fn write_blob(dir: &Path, blob: &[u8], cas: bool) -> [u8; 20] {
+    let hash = sha1(blob);
+    let name = hex::encode(hash);
+    let path = match cas {
+        true => dir.join(CAS_OBJECTS_DIR).join(&name[..2]).join(&name[2..4]),
+        false => dir.to_path_buf(),
+    };
+    fs::create_dir_all(&path).unwrap();
+    fs::write(path.join(name), blob).unwrap();
+    hash
+}
+
+#[test]
+fn only_unreferenced_blobs_are_removed() {
+    let root = temp_dir("dump");
+    let dump = root.join("planet");
+    fs::create_dir_all(&dump).unwrap();
+
+    // planet -> level -> texture, in a flat dump and a CAS tree
+    let texture = write_blob(&dump, b"TEX texture data", true);
+    let level = write_blob(&dump, &resource(b"LVLb", &[(texture, 1)]), false);
+    let planet = write_blob(&dump, &resource(b"LVLb", &[(level, 9)]), false);
+    fs::write(dump.join(PLANET_HASH_FILE), hex::encode(planet)).unwrap();
+    let orphan_level = write_blob(&dump, &resource(b"LVLb", &[]), false);
+    let orphan_texture = write_blob(&dump, b"TEX nobody uses this", true);
+    // not a blob, whatever its content
+    fs::write(dump.join("notes.txt"), b"TEX").unwrap();
+
+    // blobs without a manifest aren't known to be garbage
+    let unknown = root.join("unknown");
+    fs::create_dir_all(&unknown).unwrap();
+    write_blob(&unknown, b"TEX who knows", false);
+
+    let report = analyze(&root).unwrap();
+    assert_eq!(report.without_manifest, [unknown.as_path()]);
+    assert!(report.zipped.is_empty());
+    assert_eq!(report.dumps.len(), 1);
+    let garbage = &report.dumps[0];
+    assert_eq!(garbage.dir, dump);
+    assert_eq!(garbage.blobs, 5);
+    let mut names: Vec<String> = garbage
+        .unreachable
+        .iter()
+        .map(|blob| {
+            blob.path
+                .file_name()
+                .unwrap()
+                .to_string_lossy()
+                .into_owned()
+        })
+        .collect();
+    names.sort();
+    let mut expected = [hex::encode(orphan_level), hex::encode(orphan_texture)];
+    expected.sort();
+    assert_eq!(names, expected);
+
+    let freed = delete(garbage).unwrap();
+    assert_eq!(freed, garbage.unreachable_bytes());
+    let left = analyze(&root).unwrap();
+    assert_eq!(left.dumps[0].blobs, 3);
+    assert!(left.dumps[0].unreachable.is_empty());
+    assert_eq!(fs::read_dir(&unknown).unwrap().count(), 1);
+    assert!(dump.join("notes.txt").exists());
+    fs::remove_dir_all(root).unwrap();
+}
+
+/// The file names of the blobs `gc` found unreachable in its only dump
+fn unreachable_names(root: &Path) -> Vec<String> {
+    let report = analyze(root).unwrap();
+    assert_eq!(report.dumps.len(), 1);
+    report.dumps[0]
+        .unreachable
+        .iter()
+        .map(|blob| {
+            blob.path
+                .file_name()
+                .unwrap()
+                .to_string_lossy()
+                .into_owned()
+        })
+        .collect()
+}
+
+#[test]
+fn converted_icons_are_kept_through_their_asset() {
+    let dump = temp_dir("png_icon");
+    let icon_data = b"TEX icon".to_vec();
+    let level_data = resource(b"LVLb", &[]);
+    let png = b"\x89PNG converted icon".to_vec();
+    let icon = write_blob(&dump, &icon_data, false);
+    let level = write_blob(&dump, &level_data, false);
+    let png_sha = write_blob(&dump, &png, false);
+    let orphan = write_blob(&dump, b"TEX nobody uses this", false);
+
+    // the level's assets as `fetch-level` exports them, the PNG only
+    // referenced as the icon's AsMainlineIconHash and as its own asset
+    let resources = BTreeMap::from([(level, level_data), (icon, icon_data)]);
+    let import = ImportData {
+        assets: fetch_all_assets(&resources, &BTreeMap::from([(icon, png)])),
+        ..Default::default()
+    };
+    fs::write(
+        dump.join(REFRESH_IMPORT_FILE),
+        serde_json::to_string(&import).unwrap(),
+    )
+    .unwrap();
+    let icon_asset = import
+        .assets
+        .iter()
+        .find(|asset| asset.asset_hash == hex::encode(icon))
+        .unwrap();
+    assert_eq!(
+        icon_asset.as_mainline_icon_hash.as_deref(),
+        Some(hex::encode(png_sha).as_str())
+    );
+
+    assert_eq!(unreachable_names(&dump), [hex::encode(orphan)]);
+    fs::remove_dir_all(dump).unwrap();
+}
+
+#[test]
+fn planets_json_alone_is_not_a_manifest() {
+    let dump = temp_dir("planets_only");
+    let sub_level = write_blob(&dump, &resource(b"LVLb", &[]), false);
+    // the level `fetch-level` fetched, its planet's sub-level next to it
+    write_blob(&dump, &resource(b"LVLb", &[([9; 20], 9)]), false);
+    let mut state = PlanetFetchState::default();
+    state.record(&[7; 20], &sub_level, [&sub_level]);
+    state.write(&dump.join(PLANET_STATE_FILE)).unwrap();
+
+    let report = analyze(&dump).unwrap();
+    assert!(report.dumps.is_empty());
+    assert_eq!(report.without_manifest, [dump.as_path()]);
+
+    // with a planet's hash file it's a planet dump, whose sub-levels are kept
+    fs::write(dump.join(PLANET_HASH_FILE), hex::encode([7; 20])).unwrap();
+    assert_eq!(unreachable_names(&dump).len(), 1);
+    fs::remove_dir_all(dump).unwrap();
+}
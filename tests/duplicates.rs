@@ -0,0 +1,87 @@
+//! `find-duplicates`: which levels end up in a group, and what keeps
+//! levels that only share common resources or a creator out of one.
+
+use archive_dl::duplicates::{COMMON_RESOURCE_LEVELS, Catalog, DuplicateLevel, MatchKind};
+
+fn level(id: i64, creator: &str) -> DuplicateLevel {
+    DuplicateLevel {
+        id,
+        name: format!("level {id}"),
+        creator: creator.to_string(),
+    }
+}
+
+fn hash(n: u32) -> [u8; 20] {
+    let mut hash = [0; 20];
+    hash[..4].copy_from_slice(&n.to_be_bytes());
+    hash
+}
+
+#[test]
+fn the_same_root_level_by_two_creators_is_identical() {
+    let mut catalog = Catalog::default();
+    catalog.add_level(hash(1), level(2, "copier"));
+    catalog.add_level(hash(1), level(1, "original"));
+    // the same slot twice isn't a second level
+    catalog.add_level(hash(1), level(1, "original"));
+    // one creator uploading twice isn't a copy
+    catalog.add_level(hash(2), level(3, "original"));
+    catalog.add_level(hash(2), level(4, "original"));
+
+    let report = catalog.report(0.8);
+    assert_eq!(report.groups.len(), 1);
+    let group = &report.groups[0];
+    assert_eq!(group.kind, MatchKind::Identical);
+    assert_eq!(group.similarity, 1.0);
+    let ids: Vec<i64> = group.levels.iter().map(|l| l.id).collect();
+    assert_eq!(ids, [1, 2]);
+}
+
+#[test]
+fn similar_content_is_grouped_across_pairs() {
+    let mut catalog = Catalog::default();
+    let (a, b, c, other) = (hash(1), hash(2), hash(3), hash(4));
+    catalog.add_level(a, level(1, "original"));
+    catalog.add_level(b, level(2, "copier"));
+    catalog.add_level(c, level(3, "another copier"));
+    catalog.add_level(other, level(4, "someone else"));
+    // b has 9 of a's 10 resources (0.9), c 8 of b's 9 (0.89) and 8 of
+    // a's 10 (0.8)
+    catalog.add_content(a, (100..110).map(hash).chain([a]));
+    catalog.add_content(b, (100..109).map(hash));
+    catalog.add_content(c, (100..108).map(hash));
+    catalog.add_content(other, (200..210).map(hash));
+
+    let report = catalog.report(0.85);
+    assert_eq!(report.compared, 4);
+    assert_eq!(report.groups.len(), 1);
+    let group = &report.groups[0];
+    assert_eq!(group.kind, MatchKind::Similar);
+    assert_eq!(group.root_levels.len(), 3);
+    assert_eq!(group.pairs.len(), 2);
+    assert!(
+        (group.similarity - 8.0 / 9.0).abs() < 1e-9,
+        "{}",
+        group.similarity
+    );
+    let ids: Vec<i64> = group.levels.iter().map(|l| l.id).collect();
+    assert_eq!(ids, [1, 2, 3]);
+}
+
+#[test]
+fn common_resources_and_tiny_levels_dont_make_copies() {
+    let mut catalog = Catalog::default();
+    // every level uses the same stickers, which are too common to count
+    let stickers: Vec<[u8; 20]> = (1000..1010).map(hash).collect();
+    for i in 0..=COMMON_RESOURCE_LEVELS as u32 {
+        catalog.add_level(hash(i), level(i.into(), &format!("creator{i}")));
+        catalog.add_content(hash(i), stickers.iter().copied().chain([hash(5000 + i)]));
+    }
+    // two resources of their own aren't enough to tell levels apart
+    catalog.add_level(hash(100), level(100, "small"));
+    catalog.add_level(hash(101), level(101, "smaller"));
+    catalog.add_content(hash(100), [hash(7000), hash(7001)]);
+    catalog.add_content(hash(101), [hash(7000), hash(7001)]);
+
+    assert!(catalog.report(0.5).groups.is_empty());
+}
@@ -0,0 +1,119 @@
+//! The order a level's resources are fetched in: a container at a time in
+//! name order, wrapping around to the ones resources were added to behind
+//! the sweep, with the next ZIP named to be opened ahead. And the progress
+//! of a breadth first depth.
+
+use std::path::PathBuf;
+
+use archive_dl::archive_layout::Location;
+use archive_dl::fetch_queue::{Batches, Frontier};
+use archive_dl::resource_dl::DownloadOrder;
+
+fn zip(name: &str) -> Option<Location> {
+    Some(Location::Zip {
+        path: PathBuf::from(name),
+        entry: String::new(),
+    })
+}
+
+fn tar(name: &str) -> Option<Location> {
+    Some(Location::Tar {
+        path: PathBuf::from(name),
+        offset: 0,
+        size: 0,
+    })
+}
+
+/// The next hash's first byte and the ZIP to open ahead
+fn pop(batches: &mut Batches, order: DownloadOrder) -> Option<(u8, Option<String>)> {
+    batches.pop(order).map(|(hash, ahead)| {
+        (
+            hash[0],
+            ahead.map(|path| path.to_string_lossy().into_owned()),
+        )
+    })
+}
+
+#[test]
+fn the_sweep_wraps_around_to_containers_behind_it() {
+    let mut batches = Batches::default();
+    batches.push([1; 20], zip("a.zip"));
+    batches.push([2; 20], zip("b.zip"));
+    batches.push([3; 20], zip("c.zip"));
+    let order = DownloadOrder::Discovered;
+    assert_eq!(pop(&mut batches, order), Some((1, Some("b.zip".into()))));
+    assert_eq!(pop(&mut batches, order), Some((2, Some("c.zip".into()))));
+
+    // found while b.zip was read, in a ZIP the sweep is past
+    batches.push([4; 20], zip("a.zip"));
+    assert_eq!(batches.len(), 2);
+    assert_eq!(pop(&mut batches, order), Some((3, Some("a.zip".into()))));
+    // the last container left isn't opened ahead of itself
+    assert_eq!(pop(&mut batches, order), Some((4, None)));
+    assert_eq!(pop(&mut batches, order), None);
+    assert!(batches.is_empty());
+}
+
+#[test]
+fn a_batch_is_finished_before_the_next() {
+    let mut batches = Batches::default();
+    batches.push([1; 20], zip("a.zip"));
+    batches.push([2; 20], zip("a.zip"));
+    batches.push([9; 20], zip("b.zip"));
+    assert_eq!(pop(&mut batches, DownloadOrder::Discovered).unwrap().0, 1);
+    // added to the current batch while it's read, so it's read first too
+    batches.push([3; 20], zip("a.zip"));
+    assert_eq!(pop(&mut batches, DownloadOrder::Discovered).unwrap().0, 2);
+    assert_eq!(pop(&mut batches, DownloadOrder::Discovered).unwrap().0, 3);
+    assert_eq!(pop(&mut batches, DownloadOrder::Discovered).unwrap().0, 9);
+}
+
+#[test]
+fn depth_first_takes_the_newest_of_a_batch() {
+    let mut batches = Batches::default();
+    for hash in 1..=3 {
+        batches.push([hash; 20], zip("a.zip"));
+    }
+    let popped: Vec<_> = std::iter::from_fn(|| pop(&mut batches, DownloadOrder::Depth))
+        .map(|(hash, _)| hash)
+        .collect();
+    assert_eq!(popped, [3, 2, 1]);
+}
+
+#[test]
+fn only_zips_are_opened_ahead() {
+    let mut batches = Batches::default();
+    batches.push([1; 20], tar("a.tar"));
+    batches.push([2; 20], tar("b.tar"));
+    // not in the archive, it fails when it's fetched and sorts first
+    batches.push([3; 20], None);
+    let order = DownloadOrder::Breadth;
+    assert_eq!(pop(&mut batches, order), Some((3, None)));
+    assert_eq!(pop(&mut batches, order), Some((1, None)));
+    assert_eq!(pop(&mut batches, order), Some((2, None)));
+}
+
+#[test]
+fn progress_is_reported_every_tenth() {
+    let mut frontier = Frontier { total: 25, done: 0 };
+    let reported: Vec<_> = (0..25).filter_map(|_| frontier.advance()).collect();
+    assert_eq!(reported, [12, 20, 32, 40, 52, 60, 72, 80, 92, 100]);
+}
+
+#[test]
+fn small_depths_report_every_fetch() {
+    let mut frontier = Frontier { total: 3, done: 0 };
+    assert_eq!(frontier.advance(), Some(33));
+    assert_eq!(frontier.advance(), Some(66));
+    assert_eq!(frontier.advance(), Some(100));
+}
+
+#[test]
+fn alternates_tried_in_a_depth_keep_it_at_most_100() {
+    let mut frontier = Frontier { total: 1, done: 0 };
+    assert_eq!(frontier.advance(), Some(100));
+    // the one fetch failed and its alternate was added to the depth
+    frontier.total += 1;
+    assert_eq!(frontier.advance(), Some(100));
+    assert_eq!(frontier.done, 2);
+}
@@ -0,0 +1,56 @@
+//! Golden-file regression tests for the backup serializers.
+//!
+//! After an intentional format change, re-record the outputs with
+//! `BLESS_GOLDEN=1 cargo test --test golden` and review the diff.
+
+use std::fs;
+use std::path::PathBuf;
+
+use archive_dl::self_test::{CASES, OUTPUT_FILES, diff, render};
+
+fn golden_dir(case: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden")
+        .join(case)
+}
+
+#[test]
+fn serializers_match_golden_files() {
+    let bless = std::env::var_os("BLESS_GOLDEN").is_some();
+    let mut problems = Vec::new();
+
+    for case in &CASES {
+        let outputs = render(case).unwrap();
+        let dir = golden_dir(case.name);
+        for (file, actual) in OUTPUT_FILES.iter().zip(&outputs) {
+            let path = dir.join(file);
+            if bless {
+                fs::create_dir_all(&dir).unwrap();
+                fs::write(&path, actual).unwrap();
+                continue;
+            }
+            let expected = fs::read(&path).unwrap();
+            if let Some(problem) = diff(file, &expected, actual) {
+                problems.push(format!("{}: {}", case.name, problem));
+            }
+        }
+    }
+
+    assert!(
+        problems.is_empty(),
+        "serializer output changed:\n{}\nrerun with BLESS_GOLDEN=1 if this is intended",
+        problems.join("\n")
+    );
+}
+
+#[test]
+fn cases_serialize_differently() {
+    // a serializer ignoring the revision would still match stale goldens
+    // recorded from that same bug, so make sure game versions actually differ
+    let slotlists: Vec<Vec<u8>> = CASES.iter().map(|c| render(c).unwrap().remove(0)).collect();
+    for (i, a) in slotlists.iter().enumerate() {
+        for b in &slotlists[i + 1..] {
+            assert_ne!(a, b);
+        }
+    }
+}
@@ -0,0 +1,87 @@
+//! `export-graph` output: what's written once, what gets its type later and
+//! what's left of a level added twice, for both formats.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use archive_dl::db::LevelRecord;
+use archive_dl::graph_export::{GraphFormat, GraphWriter};
+use rusqlite::Connection;
+
+mod common;
+use common::{resource, temp_dir};
+
+fn level(id: u32, root_level: [u8; 20]) -> LevelRecord {
+    LevelRecord {
+        id,
+        root_level,
+        published_in: None,
+        np_handle: format!("creator{id}"),
+        icon: None,
+        game: None,
+        name: String::new(),
+    }
+}
+
+/// Two levels sharing a plan, the first without the plan's blob, and the
+/// first added again
+fn write_graph(output: &Path, format: GraphFormat) -> archive_dl::graph_export::GraphStats {
+    let (root1, root2, plan) = ([1; 20], [2; 20], [3; 20]);
+    let first = BTreeMap::from([(root1, resource(b"LVLb", &[(plan, 9)]))]);
+    let second = BTreeMap::from([
+        (root2, resource(b"LVLb", &[(plan, 9)])),
+        (plan, resource(b"PLNb", &[])),
+    ]);
+    let mut graph = GraphWriter::create(output, format).unwrap();
+    graph.add_level(&level(1, root1), &first).unwrap();
+    graph.add_level(&level(2, root2), &second).unwrap();
+    graph.add_level(&level(1, root1), &first).unwrap();
+    graph.finish().unwrap()
+}
+
+#[test]
+fn sqlite_graphs_have_each_node_and_level_once() {
+    let dir = temp_dir("sqlite");
+    let output = dir.join("graph.sqlite");
+    let stats = write_graph(&output, GraphFormat::Sqlite);
+    assert_eq!((stats.levels, stats.nodes, stats.edges), (2, 3, 2));
+
+    let conn = Connection::open(&output).unwrap();
+    let count = |sql: &str| conn.query_row(sql, [], |r| r.get::<_, i64>(0)).unwrap();
+    assert_eq!(count("SELECT count(*) FROM level"), 2);
+    assert_eq!(count("SELECT count(*) FROM node WHERE type IS NULL"), 0);
+    let plan_type: String = conn
+        .query_row(
+            "SELECT type FROM node WHERE sha1 = ?1",
+            [&[3u8; 20][..]],
+            |r| r.get(0),
+        )
+        .unwrap();
+    assert!(plan_type.starts_with("PLN"), "{plan_type}");
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn neo4j_csvs_come_from_the_staging_tables() {
+    let dir = temp_dir("neo4j");
+    let stats = write_graph(&dir, GraphFormat::Neo4j);
+    assert_eq!((stats.levels, stats.nodes, stats.edges), (2, 3, 2));
+    assert!(!dir.join("graph.staging.sqlite").exists());
+
+    let read = |name: &str| fs::read_to_string(dir.join(name)).unwrap();
+    assert_eq!(
+        read("levels.csv"),
+        "slot:ID(Level),creator,:LABEL\n1,creator1,Level\n2,creator2,Level\n"
+    );
+    let resources = read("resources.csv");
+    assert_eq!(resources.lines().count(), 4);
+    let plan_row = resources
+        .lines()
+        .find(|line| line.starts_with(&hex::encode([3u8; 20])))
+        .unwrap();
+    assert!(!plan_row.contains(",,"), "{plan_row}");
+    assert_eq!(read("depends_on.csv").lines().count(), 3);
+    assert_eq!(read("level_resources.csv").lines().count(), 3);
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,130 @@
+//! Level icons: packing them into a sprite sheet for `icon sheet`, and
+//! images turned into the DXT5 icon textures `bkp --icon-texture` writes.
+
+use std::io::Cursor;
+
+use archive_dl::icon::{
+    decode_image, icon_thumbnail, icon_to_png, image_to_texture, make_sprite_sheet,
+};
+use image::{DynamicImage, ImageFormat, Rgba, RgbaImage};
+
+mod common;
+use common::resource;
+
+fn solid(width: u32, height: u32, color: [u8; 4]) -> RgbaImage {
+    RgbaImage::from_pixel(width, height, Rgba(color))
+}
+
+#[test]
+fn thumbnails_are_centered_in_their_cells_row_by_row() {
+    let (red, green, blue) = ([255, 0, 0, 255], [0, 255, 0, 255], [0, 0, 255, 255]);
+    let thumbnails = [solid(8, 8, red), solid(8, 4, green), solid(4, 8, blue)];
+    let sheet = make_sprite_sheet(&thumbnails, 8, 2).unwrap();
+
+    assert_eq!(sheet.corners, [(0, 0), (8, 2), (2, 8)]);
+    let png = decode_image(&sheet.png).unwrap().to_rgba8();
+    assert_eq!(png.dimensions(), (16, 16));
+    assert_eq!(png.get_pixel(4, 4).0, red);
+    assert_eq!(png.get_pixel(12, 4).0, green);
+    assert_eq!(png.get_pixel(4, 12).0, blue);
+    // around the wide and the tall one and in the empty cell, transparent
+    for (x, y) in [(12, 0), (12, 7), (0, 12), (7, 12), (12, 12)] {
+        assert_eq!(png.get_pixel(x, y).0[3], 0, "{x},{y}");
+    }
+}
+
+#[test]
+fn translucent_thumbnails_keep_their_color() {
+    let half = [200, 100, 50, 128];
+    let sheet = make_sprite_sheet(&[solid(4, 4, half)], 4, 1).unwrap();
+    let png = decode_image(&sheet.png).unwrap().to_rgba8();
+    assert_eq!(png.get_pixel(1, 1).0, half);
+}
+
+#[test]
+fn an_empty_sheet_is_one_empty_row() {
+    let sheet = make_sprite_sheet(&[], 16, 0).unwrap();
+    assert!(sheet.corners.is_empty());
+    let png = decode_image(&sheet.png).unwrap();
+    assert_eq!((png.width(), png.height()), (16, 16));
+}
+
+#[test]
+fn thumbnails_are_only_made_of_textures() {
+    let plan = resource(b"PLNb", &[]);
+    assert!(icon_thumbnail(&plan, 32).unwrap().is_none());
+}
+
+fn png(image: RgbaImage) -> Vec<u8> {
+    let mut png = Vec::new();
+    DynamicImage::ImageRgba8(image)
+        .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .unwrap();
+    png
+}
+
+/// what an image looks like after going through the icon texture
+fn round_trip(image: RgbaImage) -> RgbaImage {
+    let texture = image_to_texture(&png(image)).unwrap();
+    assert!(texture.starts_with(b"TEX "));
+    decode_image(&icon_to_png(&texture).unwrap().unwrap())
+        .unwrap()
+        .to_rgba8()
+}
+
+fn assert_close(got: [u8; 4], expected: [u8; 4], tolerance: u8, at: (u32, u32)) {
+    let off = got
+        .iter()
+        .zip(expected)
+        .any(|(g, e)| g.abs_diff(e) > tolerance);
+    assert!(!off, "{got:?} instead of {expected:?} at {at:?}");
+}
+
+#[test]
+fn icon_textures_keep_colors_and_alpha() {
+    let quadrants = [
+        [255, 0, 0, 255],
+        [0, 255, 0, 255],
+        [0, 0, 255, 128],
+        [0, 0, 0, 0],
+    ];
+    let image = RgbaImage::from_fn(128, 128, |x, y| {
+        Rgba(quadrants[(x / 64 + 2 * (y / 64)) as usize])
+    });
+    let decoded = round_trip(image);
+    assert_eq!(decoded.dimensions(), (128, 128));
+    for (i, color) in quadrants.iter().enumerate() {
+        let at = (32 + 64 * (i as u32 % 2), 32 + 64 * (i as u32 / 2));
+        let got = decoded.get_pixel(at.0, at.1).0;
+        // alpha of a flat block is exact, colors are RGB565
+        assert_eq!(got[3], color[3], "{at:?}");
+        if color[3] > 0 {
+            assert_close(got, *color, 8, at);
+        }
+    }
+}
+
+#[test]
+fn icon_textures_interpolate_within_a_block() {
+    // every 4×4 block has a ramp of gray from black to white, on the four
+    // color steps of DXT5, and of alpha between two of its eight steps
+    let image = RgbaImage::from_fn(128, 128, |x, _| {
+        let v = (x % 4 * 85) as u8;
+        Rgba([v, v, v, 255 - v / 2])
+    });
+    let decoded = round_trip(image.clone());
+    for (x, y) in [(0, 0), (1, 5), (2, 70), (3, 127), (65, 9)] {
+        let expected = image.get_pixel(x, y).0;
+        assert_close(decoded.get_pixel(x, y).0, expected, 8, (x, y));
+    }
+}
+
+#[test]
+fn wide_images_are_padded_to_a_square() {
+    let decoded = round_trip(RgbaImage::from_pixel(64, 32, Rgba([255, 255, 255, 255])));
+    assert_eq!(decoded.dimensions(), (128, 128));
+    // scaled to 128×64 in the middle, transparent above and below
+    assert_eq!(decoded.get_pixel(64, 10).0[3], 0);
+    assert_close(decoded.get_pixel(64, 64).0, [255; 4], 8, (64, 64));
+    assert_eq!(decoded.get_pixel(64, 120).0[3], 0);
+}
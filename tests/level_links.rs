@@ -0,0 +1,73 @@
+//! Finding the slot IDs a level may link to: whole `SlotID`s of published
+//! levels in its data count, local slots, small numbers and values that only
+//! look like one in the middle of another don't.
+
+use std::collections::BTreeSet;
+
+use archive_dl::level_links::candidate_slot_ids;
+
+/// A level of revision `head` and no dependencies, with `flags` as its
+/// compression flags
+fn level(head: u32, flags: u8, data: &[u8]) -> Vec<u8> {
+    let mut res = b"LVLb".to_vec();
+    res.extend(head.to_be_bytes());
+    let table = res.len() + 4 + 6 + data.len();
+    res.extend((table as u32).to_be_bytes());
+    // mainline branch, not compressed
+    res.extend([0, 0, 0, 0, flags, 0]);
+    res.extend(data);
+    res.extend(0u32.to_be_bytes());
+    res
+}
+
+fn leb128(mut value: u32) -> Vec<u8> {
+    let mut out = Vec::new();
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            out.push(byte);
+            return out;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+fn ids(found: BTreeSet<u32>) -> Vec<u32> {
+    found.into_iter().collect()
+}
+
+#[test]
+fn uncompressed_slot_ids_of_published_levels_are_found() {
+    let mut data = vec![0xff; 3];
+    // a link to a published level
+    data.extend([2u32, 12345].iter().flat_map(|v| v.to_be_bytes()));
+    // a local slot and a developer one
+    data.extend([1u32, 23456, 0, 34567].iter().flat_map(|v| v.to_be_bytes()));
+    // a published slot with a number too small for the archive
+    data.extend([2u32, 12].iter().flat_map(|v| v.to_be_bytes()));
+    assert_eq!(ids(candidate_slot_ids(&level(0x3f8, 0, &data))), [12345]);
+}
+
+#[test]
+fn compressed_slot_ids_start_a_value_of_their_own() {
+    let mut data = leb128(7);
+    data.extend(leb128(2));
+    data.extend(leb128(54321));
+    // the last byte of 0x102 is a 2, but it ends the value before
+    data.extend(leb128(0x102));
+    data.extend(leb128(20000));
+    // not the shortest encoding of 2
+    data.extend([0x82, 0x00]);
+    data.extend(leb128(30000));
+    data.extend(leb128(1));
+    data.extend(leb128(40000));
+    assert_eq!(ids(candidate_slot_ids(&level(0x3f8, 1, &data))), [54321]);
+}
+
+#[test]
+fn lbp1_levels_have_no_links() {
+    let data: Vec<u8> = [2u32, 12345].iter().flat_map(|v| v.to_be_bytes()).collect();
+    assert!(candidate_slot_ids(&level(0x272, 0, &data)).is_empty());
+    assert!(candidate_slot_ids(b"not a resource").is_empty());
+}
@@ -0,0 +1,119 @@
+//! The relations of a Refresh export: which `relation_filter` leaves out,
+//! and the one relation into a texture `collapse_textures` keeps.
+
+use std::collections::BTreeMap;
+
+use archive_dl::ResrcDescriptor;
+use archive_dl::db::{RelationFilter, fetch_all_relations};
+use archive_dl::models::AssetDependencyRelation;
+
+mod common;
+use common::binary_resource;
+
+/// A binary resource whose dependency table has `deps`, SHA1s with their
+/// declared type and GUIDs as `Err`
+fn resource(magic: &[u8; 4], deps: &[Result<([u8; 20], u32), u32>]) -> Vec<u8> {
+    let deps: Vec<_> = deps
+        .iter()
+        .map(|dep| match *dep {
+            Ok((sha1, resrc_type)) => (ResrcDescriptor::Sha1(sha1), resrc_type),
+            Err(guid) => (ResrcDescriptor::Guid(guid), 1),
+        })
+        .collect();
+    binary_resource(magic, false, &[], &deps)
+}
+
+const TEXTURE: u32 = 1;
+const ANIMATION: u32 = 5;
+const MATERIAL: u32 = 7;
+
+/// Two materials sharing a texture, one of them animated too, used by a
+/// level that also uses an official texture by GUID
+fn level() -> BTreeMap<[u8; 20], Vec<u8>> {
+    let (root, first, second, texture, anim) = ([1; 20], [2; 20], [3; 20], [9; 20], [8; 20]);
+    BTreeMap::from([
+        (
+            root,
+            resource(
+                b"LVLb",
+                &[Ok((first, MATERIAL)), Ok((second, MATERIAL)), Err(1234)],
+            ),
+        ),
+        (first, resource(b"GMTb", &[Ok((texture, TEXTURE))])),
+        (
+            second,
+            resource(b"GMTb", &[Ok((texture, TEXTURE)), Ok((anim, ANIMATION))]),
+        ),
+    ])
+}
+
+/// Each relation as the first byte of its dependent and the first hex
+/// digits of its dependency, GUIDs whole
+fn pairs(relations: &[AssetDependencyRelation]) -> Vec<(u8, String)> {
+    relations
+        .iter()
+        .map(|r| {
+            let dependency = match r.dependency.starts_with('g') {
+                true => r.dependency.clone(),
+                false => r.dependency[..2].to_string(),
+            };
+            (hex::decode(&r.dependent).unwrap()[0], dependency)
+        })
+        .collect()
+}
+
+fn relations(include_guids: bool, filter: &RelationFilter) -> Vec<(u8, String)> {
+    pairs(&fetch_all_relations(&level(), include_guids, filter).unwrap())
+}
+
+fn edge(from: u8, to: &str) -> (u8, String) {
+    (from, to.to_string())
+}
+
+#[test]
+fn every_relation_without_a_filter() {
+    let all = [
+        edge(1, "02"),
+        edge(1, "03"),
+        edge(2, "09"),
+        edge(3, "09"),
+        edge(3, "08"),
+    ];
+    assert_eq!(relations(false, &RelationFilter::default()), all);
+    let with_guids = relations(true, &RelationFilter::default());
+    assert_eq!(with_guids.len(), 6);
+    assert!(with_guids.contains(&edge(1, "g1234")));
+}
+
+#[test]
+fn skipped_types_lose_the_relations_into_them() {
+    let filter = RelationFilter {
+        skip_types: vec!["animation".into(), "gfx material".into()],
+        collapse_textures: false,
+    };
+    assert_eq!(relations(false, &filter), [edge(2, "09"), edge(3, "09")]);
+}
+
+#[test]
+fn collapsed_textures_keep_the_relation_of_the_lowest_user() {
+    let filter = RelationFilter {
+        skip_types: Vec::new(),
+        collapse_textures: true,
+    };
+    // the second material still reaches its animation, not the texture
+    assert_eq!(
+        relations(false, &filter),
+        [edge(1, "02"), edge(1, "03"), edge(2, "09"), edge(3, "08")]
+    );
+}
+
+#[test]
+fn unknown_types_are_refused() {
+    let filter = RelationFilter {
+        skip_types: vec!["textures".into()],
+        collapse_textures: false,
+    };
+    let e = filter.validate().unwrap_err().to_string();
+    assert!(e.contains("`textures` isn't a resource type"), "{e}");
+    assert!(fetch_all_relations(&level(), false, &filter).is_err());
+}
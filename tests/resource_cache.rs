@@ -0,0 +1,40 @@
+//! Cleaning up the resource cache: what interrupted writes leave behind is
+//! removed, blobs stay, and the startup cleanup runs once a day.
+
+use std::fs;
+
+use archive_dl::resource_dl::{clean_cache, clean_cache_if_due};
+
+mod common;
+use common::temp_dir;
+
+#[test]
+fn cleanup_removes_empty_blobs_once_a_day() {
+    let dir = temp_dir("cleanup");
+    let (empty, blob) = (hex::encode([1u8; 20]), hex::encode([2u8; 20]));
+    fs::write(dir.join(&empty), b"").unwrap();
+    fs::write(dir.join(&blob), b"data").unwrap();
+    // a write in progress, too young to be left over
+    fs::write(dir.join(format!("{blob}.tmp")), b"da").unwrap();
+
+    let cleanup = clean_cache_if_due(&dir).unwrap().unwrap();
+    assert_eq!((cleanup.temp_files, cleanup.empty_files), (0, 1));
+    assert!(!dir.join(&empty).exists());
+    assert!(dir.join(&blob).exists() && dir.join(format!("{blob}.tmp")).exists());
+
+    // not due again, even with something to clean up
+    fs::write(dir.join(&empty), b"").unwrap();
+    assert!(clean_cache_if_due(&dir).unwrap().is_none());
+    assert!(dir.join(&empty).exists());
+    assert_eq!(clean_cache(&dir).unwrap().empty_files, 1);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn no_cache_dir_is_nothing_to_clean_up() {
+    let parent = temp_dir("missing");
+    let dir = parent.join("resource_cache");
+    assert!(clean_cache_if_due(&dir).unwrap().is_none());
+    assert!(!dir.exists());
+    fs::remove_dir_all(parent).unwrap();
+}
@@ -0,0 +1,32 @@
+//! The revision a backup's slot list is written with: branches the PS3 game
+//! of the backup loads are kept, everything else is its mainline revision.
+
+use archive_dl::db::GameVersion;
+use archive_dl::resource_parse::Branch;
+
+#[test]
+fn leerdammer_is_kept_for_lbp1() {
+    let ld = Branch::Leerdammer.get_latest_revision();
+    assert_eq!(ld.compatible_revision(GameVersion::Lbp1), ld);
+    assert_eq!(
+        ld.compatible_revision(GameVersion::Lbp2),
+        GameVersion::Lbp2.get_latest_revision()
+    );
+}
+
+#[test]
+fn double11_falls_back_to_mainline() {
+    let d1 = Branch::Double11.get_latest_revision();
+    let revision = d1.compatible_revision(GameVersion::Lbp2);
+    assert!(revision.is_mainline());
+    assert_eq!(revision, GameVersion::Lbp2.get_latest_revision());
+}
+
+#[test]
+fn mainline_gets_the_latest_revision_of_the_game() {
+    let lbp2 = GameVersion::Lbp2.get_latest_revision();
+    assert_eq!(
+        lbp2.compatible_revision(GameVersion::Lbp3),
+        GameVersion::Lbp3.get_latest_revision()
+    );
+}
@@ -0,0 +1,69 @@
+//! The lock that keeps two runs out of the same backup_directory.
+
+use std::fs;
+use std::sync::{Arc, Barrier};
+use std::thread;
+
+use archive_dl::run_lock::{LOCK_FILE, RunLock};
+
+mod common;
+use common::temp_dir;
+
+#[test]
+fn a_second_run_waits_for_the_first() {
+    let dir = temp_dir("lock");
+
+    let lock = RunLock::acquire(&dir, false).unwrap();
+    let holder = RunLock::holder(&dir).unwrap();
+    assert_eq!(holder.pid, std::process::id());
+    let e = RunLock::acquire(&dir, false).unwrap_err();
+    assert!(e.to_string().contains("another run is active"), "{}", e);
+
+    // the first run is still going, --force takes the lock anyway
+    let forced = RunLock::acquire(&dir, true).unwrap();
+    let new_holder = RunLock::holder(&dir).unwrap();
+    assert_ne!(new_holder, holder);
+    // the first run ending leaves the lock of the one that took it over
+    drop(lock);
+    assert_eq!(RunLock::holder(&dir), Some(new_holder));
+    drop(forced);
+    assert!(!dir.join(LOCK_FILE).exists());
+
+    // a lock nobody can read is only taken with --force
+    fs::write(dir.join(LOCK_FILE), "").unwrap();
+    assert!(RunLock::acquire(&dir, false).is_err());
+    drop(RunLock::acquire(&dir, true).unwrap());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn one_run_takes_over_a_stale_lock() {
+    let dir = temp_dir("stale");
+    let host = {
+        let _lock = RunLock::acquire(&dir, false).unwrap();
+        RunLock::holder(&dir).unwrap().host
+    };
+
+    for _ in 0..20 {
+        // left behind by a run on this machine that's gone
+        let stale = format!("pid: {}\nhost: {}\nstarted: then\n", u32::MAX - 1, host);
+        fs::write(dir.join(LOCK_FILE), stale).unwrap();
+
+        let start = Arc::new(Barrier::new(8));
+        let runs: Vec<_> = (0..8)
+            .map(|_| {
+                let (dir, start) = (dir.clone(), start.clone());
+                thread::spawn(move || {
+                    start.wait();
+                    RunLock::acquire(&dir, false).ok()
+                })
+            })
+            .collect();
+        let locks: Vec<RunLock> = runs.into_iter().filter_map(|r| r.join().unwrap()).collect();
+        assert_eq!(locks.len(), 1);
+        drop(locks);
+        assert!(!dir.join(LOCK_FILE).exists());
+    }
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,165 @@
+//! Round trips of the save archive writer through the save reader.
+//!
+//! `fuzz/` runs the same round trip on random resources, add the inputs it
+//! finds problems with here once they're fixed.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::sync::Mutex;
+
+use archive_dl::progress::{ProgressObserver, Stage};
+use archive_dl::resource_parse::ResrcRevision;
+use archive_dl::save::{FarKind, extract, join_chunks, parse_far};
+use archive_dl::serializers::lbp::{
+    CHUNK_SIZE, build_archive, make_savearchive, seal_chunks, write_index, write_resources,
+};
+use archive_dl::xxtea;
+use sha1::{Digest, Sha1};
+
+mod common;
+use common::temp_dir;
+
+const REVISION: ResrcRevision = ResrcRevision {
+    head: 0x3f8,
+    branch_id: 0,
+    branch_revision: 0,
+};
+
+fn resources(blobs: &[&[u8]]) -> BTreeMap<[u8; 20], Vec<u8>> {
+    blobs
+        .iter()
+        .map(|blob| (Sha1::digest(blob).into(), blob.to_vec()))
+        .collect()
+}
+
+/// write, seal into chunks of `chunk_size`, join and parse back
+fn round_trip(resources: &BTreeMap<[u8; 20], Vec<u8>>, chunk_size: usize) {
+    let slt_hash = [0x5a; 20];
+    let plain = build_archive(&REVISION, slt_hash, resources).unwrap();
+    let mut sealed = plain.clone();
+    seal_chunks(&mut sealed, chunk_size);
+    let chunks: Vec<Vec<u8>> = sealed.chunks(chunk_size).map(<[u8]>::to_vec).collect();
+    assert!(chunks.last().unwrap().ends_with(b"FAR4"));
+
+    let joined = join_chunks(chunks);
+    assert!(
+        joined == plain,
+        "chunks of {} didn't decrypt back",
+        chunk_size
+    );
+    let archive = parse_far(&joined).unwrap();
+    assert_eq!(archive.kind, FarKind::Far4);
+    assert_eq!(archive.hashinate_ok, Some(true));
+    let extracted = extract(&joined, &archive);
+    assert_eq!((extracted.out_of_range, extracted.bad_hash), (0, 0));
+    assert!(&extracted.resources == resources);
+}
+
+#[test]
+fn resources_survive_every_chunk_size() {
+    let resources = resources(&[b"LVLb", b"odd length", &[7; 1000], b""]);
+    for chunk_size in [4, 8, 12, 64, 1024, CHUNK_SIZE] {
+        round_trip(&resources, chunk_size);
+    }
+}
+
+#[test]
+fn archive_ending_on_a_chunk_boundary_keeps_its_magic() {
+    let resources = resources(&[&[1; 60]]);
+    let len = build_archive(&REVISION, [0; 20], &resources).unwrap().len();
+    // the footer magic is the whole last chunk, or the end of a full one
+    round_trip(&resources, 4);
+    round_trip(&resources, len / 2);
+    round_trip(&resources, len);
+}
+
+#[test]
+fn empty_archive_round_trips() {
+    round_trip(&BTreeMap::new(), CHUNK_SIZE);
+}
+
+#[test]
+fn index_points_at_resources() {
+    let resources = resources(&[b"abc", b"defgh"]);
+    let mut arc = Vec::new();
+    let entries = write_resources(&mut arc, &resources);
+    assert_eq!(arc.len() % 4, 0);
+    for (entry, (sha1, blob)) in entries.iter().zip(&resources) {
+        assert_eq!(&entry.sha1, sha1);
+        let start = entry.offset as usize;
+        assert_eq!(&arc[start..start + entry.size as usize], &blob[..]);
+    }
+
+    write_index(&mut arc, &REVISION, [0; 20], &entries).unwrap();
+    let archive = parse_far(&arc).unwrap();
+    assert_eq!(archive.entries.len(), entries.len());
+    for (parsed, written) in archive.entries.iter().zip(&entries) {
+        assert_eq!(
+            (parsed.sha1, parsed.offset, parsed.size),
+            (written.sha1, written.offset, written.size)
+        );
+    }
+}
+
+#[test]
+fn odd_chunks_dont_crash_the_reader() {
+    // an empty chunk's buffer isn't aligned for u32, found by
+    // `fuzz/fuzz_targets/save_parse.rs`
+    let chunks = vec![Vec::new(), vec![0; 12], vec![0xff, 0x0a]];
+    let data = join_chunks(chunks);
+    assert!(parse_far(&data).is_err());
+}
+
+#[test]
+fn xxtea_works_on_unaligned_blocks() {
+    let key = [1, 2, 3, 4];
+    let mut buf = [0x42u8; 17];
+    xxtea::encrypt(&key, &mut buf[1..]);
+    xxtea::decrypt(&key, &mut buf[1..]);
+    assert_eq!(buf, [0x42; 17]);
+}
+
+#[derive(Default)]
+struct Stages(Mutex<Vec<Stage>>);
+
+impl ProgressObserver for Stages {
+    fn on_stage_changed(&self, stage: Stage) {
+        self.0.lock().unwrap().push(stage);
+    }
+}
+
+#[test]
+fn writing_reports_every_chunk() {
+    let dir = temp_dir("progress");
+    let resources = resources(&[&vec![3; CHUNK_SIZE + 10]]);
+
+    let stages = Stages::default();
+    make_savearchive(&REVISION, [0; 20], resources, &dir, &stages).unwrap();
+    assert_eq!(
+        stages.0.into_inner().unwrap(),
+        [
+            Stage::Verifying,
+            Stage::Packing,
+            Stage::Encrypting,
+            Stage::WritingChunk {
+                chunk: 0,
+                chunks: 2
+            },
+            Stage::WritingChunk {
+                chunk: 1,
+                chunks: 2
+            },
+        ]
+    );
+    assert!(dir.join("1").exists());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn far2_archives_are_refused() {
+    let mut archive = vec![0u8; 64];
+    archive.extend(1u32.to_be_bytes());
+    archive.extend(b"FAR2");
+    let error = parse_far(&archive).err().unwrap().to_string();
+    assert!(error.contains("FAR2 archives aren't supported"), "{error}");
+}
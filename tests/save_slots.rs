@@ -0,0 +1,112 @@
+//! Reading the slots of a save back: what `rebuild-from-save` names and
+//! credits its levels after, the slot ID of the ones that are published and
+//! the background the slot list was written with.
+
+use std::collections::BTreeMap;
+
+use archive_dl::ResrcDescriptor;
+use archive_dl::db::{GameVersion, LevelType, SlotInfo};
+use archive_dl::resource_parse::ResrcRevision;
+use archive_dl::save_slots::find_slots;
+use archive_dl::serializers::lbp::make_slotlist;
+use archive_dl::serializers::text::TextLimits;
+
+const ROOT: [u8; 20] = [0x42; 20];
+
+fn slot_info() -> SlotInfo {
+    SlotInfo {
+        id: 0,
+        guid: None,
+        name: "Saved Level".to_string(),
+        description: "from a save".to_string(),
+        np_handle: "saver".to_string(),
+        root_level: ROOT,
+        icon: ResrcDescriptor::Guid(0),
+        game: GameVersion::Lbp2,
+        initially_locked: false,
+        is_sub_level: false,
+        background_guid: None,
+        shareable: false,
+        author_labels: Vec::new(),
+        leveltype: LevelType::Cooperative,
+        min_players: None,
+        max_players: None,
+        enforce_min_max_players: false,
+        same_screen_game: false,
+        is_adventure_planet: false,
+        planet_decorations: None,
+        adventure_shared: Vec::new(),
+    }
+}
+
+/// A save with the level and a slot list for it, whose `SlotID` is
+/// `slot_id` as (type, number)
+fn save(slot_id: Option<(u32, u32)>) -> BTreeMap<[u8; 20], Vec<u8>> {
+    let revision = ResrcRevision {
+        head: 0x3f8,
+        branch_id: 0,
+        branch_revision: 0,
+    };
+    let mut slt = make_slotlist(&revision, &slot_info(), &TextLimits::default()).unwrap();
+    if let Some((slot_type, number)) = slot_id {
+        // the slot list is written as a FAKE slot 0, right before the rootLevel
+        let mut written = [0, 0, 0, 6, 0, 0, 0, 0, 1].to_vec();
+        written.extend(ROOT);
+        let at = slt
+            .windows(written.len())
+            .position(|w| w == written)
+            .unwrap();
+        slt[at..at + 4].copy_from_slice(&slot_type.to_be_bytes());
+        slt[at + 4..at + 8].copy_from_slice(&number.to_be_bytes());
+    }
+    BTreeMap::from([([1; 20], slt), (ROOT, b"LVLb\0\0\x03\xf8".to_vec())])
+}
+
+#[test]
+fn published_levels_keep_their_slot_id() {
+    let slots = find_slots(&save(Some((2, 12345))));
+    assert_eq!(slots.len(), 1);
+    assert_eq!(slots[0].slot_id, Some(12345));
+    assert_eq!(slots[0].root_level, ROOT);
+    assert_eq!(
+        (slots[0].name.as_str(), slots[0].author.as_str()),
+        ("Saved Level", "saver")
+    );
+}
+
+#[test]
+fn local_and_fake_slots_have_no_slot_id() {
+    for slot_id in [None, Some((1, 12345))] {
+        let slots = find_slots(&save(slot_id));
+        assert_eq!(slots.len(), 1);
+        assert_eq!(slots[0].slot_id, None, "{slot_id:?}");
+    }
+}
+
+#[test]
+fn backgrounds_round_trip_where_the_slot_list_has_them() {
+    // slot lists up to 0x237, from before LBP1 shipped, end before the
+    // background, every game after writes it
+    for (head, expected) in [
+        (0x1f1, None),
+        (0x272, Some(0x1234)),
+        (0x3f8, Some(0x1234)),
+        (0x21803f9, Some(0x1234)),
+    ] {
+        let revision = ResrcRevision {
+            head,
+            branch_id: 0,
+            branch_revision: 0,
+        };
+        let info = SlotInfo {
+            background_guid: Some(0x1234),
+            ..slot_info()
+        };
+        let slt = make_slotlist(&revision, &info, &TextLimits::default()).unwrap();
+        let mut level = b"LVLb".to_vec();
+        level.extend(head.to_be_bytes());
+        let slots = find_slots(&BTreeMap::from([([1; 20], slt), (ROOT, level)]));
+        assert_eq!(slots.len(), 1, "{head:#x}");
+        assert_eq!(slots[0].background, expected, "{head:#x}");
+    }
+}
@@ -0,0 +1,244 @@
+//! Compatibility tests for the machine-readable files we write.
+//!
+//! The fixtures in tests/schema are files as earlier versions wrote them.
+//! They must keep parsing, and writing them back out must keep every field
+//! they had with the same value. Never edit a fixture to make a test pass,
+//! add one for the new version instead.
+
+use std::fs;
+use std::path::PathBuf;
+
+use archive_dl::annotate::Annotation;
+use archive_dl::duplicates::{DUPLICATES_SCHEMA_VERSION, DuplicateReport, MatchKind};
+use archive_dl::language::Language;
+use archive_dl::models::{
+    ARCHIVE_VERIFY_SCHEMA_VERSION, BACKUP_META_SCHEMA_VERSION, BackupMeta, BundleMeta,
+    CreatorState, CreatorsReport, DUMP_STATUS_SCHEMA_VERSION, DiffChange, DlcUsageReport,
+    DumpStatus, HashUsers, JSON_OUTPUT_SCHEMA_VERSION, JsonOutput, LEVEL_QUEUE_SCHEMA_VERSION,
+    LevelQueue, PLANET_STATE_SCHEMA_VERSION, PlanetFetchState, QueueState,
+    SPRITE_SHEET_SCHEMA_VERSION, SpriteSheetMap, StatsReport, VerifyState,
+};
+use archive_dl::scan::{SCAN_REPORT_SCHEMA_VERSION, ScanReport};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+fn fixture(name: &str) -> Value {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/schema")
+        .join(name);
+    serde_json::from_slice(&fs::read(&path).unwrap()).unwrap()
+}
+
+/// every field of `old` that `new` lost or changed, as JSON paths
+fn missing_fields(old: &Value, new: &Value, path: &str, problems: &mut Vec<String>) {
+    match (old, new) {
+        (Value::Object(old), Value::Object(new)) => {
+            for (key, value) in old {
+                let path = format!("{}.{}", path, key);
+                match new.get(key) {
+                    Some(new_value) => missing_fields(value, new_value, &path, problems),
+                    None => problems.push(format!("{} is gone", path)),
+                }
+            }
+        }
+        (Value::Array(old), Value::Array(new)) if old.len() == new.len() => {
+            for (i, (old, new)) in old.iter().zip(new).enumerate() {
+                missing_fields(old, new, &format!("{}[{}]", path, i), problems);
+            }
+        }
+        _ if old != new => problems.push(format!("{} changed from {} to {}", path, old, new)),
+        _ => {}
+    }
+}
+
+/// parses a fixture and checks writing it out again keeps all of it
+fn round_trip<T: Serialize + DeserializeOwned>(name: &str) -> T {
+    let old = fixture(name);
+    let parsed: T = serde_json::from_value(old.clone()).unwrap();
+    let new = serde_json::to_value(&parsed).unwrap();
+    let mut problems = Vec::new();
+    missing_fields(&old, &new, "", &mut problems);
+    assert!(problems.is_empty(), "{}: {}", name, problems.join(", "));
+    parsed
+}
+
+#[test]
+fn backup_meta_reads_old_files() {
+    let meta: BackupMeta = round_trip("backup.meta.unversioned.json");
+    assert_eq!(meta.schema_version, 1);
+    assert_eq!(meta.slot_id, 42);
+
+    let meta: BackupMeta = round_trip("backup.meta.v1.json");
+    assert_eq!(meta.schema_version, 1);
+    assert_eq!(meta.omitted_resources.len(), 1);
+    assert!(meta.modifications.is_empty());
+
+    let meta: BackupMeta = round_trip("backup.meta.v1.modifications.json");
+    assert_eq!(meta.modifications[0].field, "shareable");
+    assert_eq!(meta.language, None);
+
+    let meta: BackupMeta = round_trip("backup.meta.v1.language.json");
+    assert_eq!(meta.language, Some(Language::Fr));
+}
+
+#[test]
+fn dump_status_reads_old_files() {
+    let status: DumpStatus = round_trip("status.unversioned.json");
+    assert_eq!(status.schema_version, 1);
+    assert_eq!(status.creators["tester"].state, CreatorState::Done);
+    assert_eq!(status.creators["other"].state, CreatorState::Failed);
+
+    let status: DumpStatus = round_trip("status.v1.json");
+    assert_eq!(status.creators["skipped"].state, CreatorState::Skipped);
+    assert_eq!(status.creators["skipped"].error, None);
+
+    let status: DumpStatus = round_trip("status.v1.partial.json");
+    assert_eq!(status.creators["cut_off"].state, CreatorState::Partial);
+
+    let status: DumpStatus = round_trip("status.v1.filter_game.json");
+    assert_eq!(
+        status.creators["tester"].filter_game.as_deref(),
+        Some("LBP2")
+    );
+}
+
+#[test]
+fn scan_report_reads_old_files() {
+    let report: ScanReport = round_trip("scan.unversioned.json");
+    assert_eq!(report.schema_version, 1);
+    assert_eq!(report.types["LVLb"].total.bytes, 1024);
+
+    let report: ScanReport = round_trip("scan.v1.json");
+    assert_eq!(report.types["LVLb"].revisions["0x3f8"].count, 1);
+    assert_eq!(report.types["PNG"].name, None);
+}
+
+#[test]
+fn planet_state_reads_old_files() {
+    let state: PlanetFetchState = round_trip("planets.v1.json");
+    let planet = &state.planets["c8bb3a5234ef77181f3aec6d9f6257b62b8275de"];
+    assert_eq!(planet["eafdb7ae9e4086ac2b5c09ef3fd7808fbcbc686a"].len(), 2);
+}
+
+#[test]
+fn level_queue_reads_old_files() {
+    let mut queue: LevelQueue = round_trip("queue.v1.json");
+    assert_eq!(queue.levels[1].state, QueueState::Failed);
+    assert_eq!(queue.in_state(QueueState::Pending).count(), 1);
+    // a level is queued once, whatever state it's in
+    assert!(!queue.add(42));
+    assert!(queue.add(44));
+}
+
+#[test]
+fn bundle_meta_reads_old_files() {
+    let meta: BundleMeta = round_trip("level.v1.json");
+    assert_eq!(meta.format_version, 1);
+}
+
+#[test]
+fn sprite_sheet_map_reads_old_files() {
+    let map: SpriteSheetMap = round_trip("sheet.v1.json");
+    assert_eq!(map.icons["42"].y, map.icons["43"].y);
+    assert_eq!(map.icons["guid:5"].x, 64);
+    assert_eq!(map.missing, ["44"]);
+}
+
+#[test]
+fn verify_state_reads_old_files() {
+    let state: VerifyState = round_trip("verify.v1.json");
+    assert_eq!(state.shard, "1/64");
+    assert_eq!(state.zips["00"].bad_hash.len(), 1);
+    assert!(state.zips["01"].error.is_some());
+}
+
+#[test]
+fn duplicate_report_reads_old_files() {
+    let report: DuplicateReport = round_trip("duplicates.v1.json");
+    assert_eq!(report.groups[0].kind, MatchKind::Identical);
+    assert!(report.groups[0].pairs.is_empty());
+    assert_eq!(report.groups[1].pairs.len(), 1);
+    assert_eq!(report.groups[1].levels[2].creator, "thief");
+}
+
+#[test]
+fn json_output_reads_old_files() {
+    let users: JsonOutput<HashUsers> = round_trip("levels_of_hash.v1.json");
+    assert_eq!(users.command, "levels-of-hash");
+    assert_eq!(users.result.slots[0].through, None);
+    assert!(users.result.slots[1].through.is_some());
+    assert!(users.stats.is_none());
+
+    let stats: JsonOutput<StatsReport> = round_trip("stats.v1.json");
+    assert_eq!(stats.result.levels[0].references["mesh"].guid, 3);
+    assert_eq!(stats.result.levels[0].textures.count, 30);
+    assert!(stats.stats.is_none());
+
+    let stats: JsonOutput<StatsReport> = round_trip("stats.v1.run_stats.json");
+    assert_eq!(stats.stats.unwrap().archive.count, 20);
+
+    let usage: JsonOutput<DlcUsageReport> = round_trip("dlc_usage.v1.json");
+    assert_eq!(usage.result.creators[0].levels[1].name, None);
+    assert_eq!(usage.result.guid_levels[&31337], 2);
+
+    let diff: JsonOutput<CreatorsReport> = round_trip("compare_creators.v1.json");
+    let levels = &diff.result.creators[0].levels;
+    assert_eq!(levels[0].change, DiffChange::Added);
+    assert!(levels[1].old_update < levels[1].new_update);
+
+    let annotation: JsonOutput<Annotation> = round_trip("annotate_resource.v1.json");
+    let data = &annotation.result.sections[1];
+    assert_eq!(data.fields[1].fields[0].fields[0].value, "6 (FAKE)");
+    assert!(data.error.is_some());
+}
+
+#[test]
+fn new_files_are_the_current_version() {
+    assert_eq!(
+        DumpStatus::default().schema_version,
+        DUMP_STATUS_SCHEMA_VERSION
+    );
+    assert_eq!(
+        ScanReport::default().schema_version,
+        SCAN_REPORT_SCHEMA_VERSION
+    );
+    assert_eq!(
+        PlanetFetchState::default().schema_version,
+        PLANET_STATE_SCHEMA_VERSION
+    );
+    assert_eq!(
+        LevelQueue::default().schema_version,
+        LEVEL_QUEUE_SCHEMA_VERSION
+    );
+    assert_eq!(
+        VerifyState::default().schema_version,
+        ARCHIVE_VERIFY_SCHEMA_VERSION
+    );
+    assert_eq!(
+        DuplicateReport::default().schema_version,
+        DUPLICATES_SCHEMA_VERSION
+    );
+    assert_eq!(
+        JsonOutput::<StatsReport>::default().schema_version,
+        JSON_OUTPUT_SCHEMA_VERSION
+    );
+    // a version bump needs a fixture written by the new version
+    for (name, version) in [
+        ("backup.meta", BACKUP_META_SCHEMA_VERSION),
+        ("status", DUMP_STATUS_SCHEMA_VERSION),
+        ("scan", SCAN_REPORT_SCHEMA_VERSION),
+        ("planets", PLANET_STATE_SCHEMA_VERSION),
+        ("queue", LEVEL_QUEUE_SCHEMA_VERSION),
+        ("sheet", SPRITE_SHEET_SCHEMA_VERSION),
+        ("verify", ARCHIVE_VERIFY_SCHEMA_VERSION),
+        ("duplicates", DUPLICATES_SCHEMA_VERSION),
+        ("levels_of_hash", JSON_OUTPUT_SCHEMA_VERSION),
+        ("stats", JSON_OUTPUT_SCHEMA_VERSION),
+        ("dlc_usage", JSON_OUTPUT_SCHEMA_VERSION),
+        ("compare_creators", JSON_OUTPUT_SCHEMA_VERSION),
+    ] {
+        let file = format!("{}.v{}.json", name, version);
+        assert_eq!(fixture(&file)["schema_version"], version, "{}", file);
+    }
+}
@@ -0,0 +1,36 @@
+//! What the target consoles accept of the `sfo` settings: RPCS3 takes a
+//! copy protected save, the consoles copying through the XMB don't, and
+//! `hen` needs the account to bind the save to.
+
+use archive_dl::serializers::ps3::{SfoFields, TargetConsole};
+
+fn fields(target: TargetConsole, copy_protected: bool, account_id: Option<&str>) -> SfoFields {
+    SfoFields {
+        target_console: Some(target),
+        copy_protected: Some(copy_protected),
+        account_id: account_id.map(str::to_string),
+        ..SfoFields::default()
+    }
+}
+
+#[test]
+fn only_rpcs3_takes_copy_protected_saves() {
+    assert!(fields(TargetConsole::Rpcs3, true, None).validate().is_ok());
+    assert!(fields(TargetConsole::Cfw, true, None).validate().is_err());
+    assert!(fields(TargetConsole::Cfw, false, None).validate().is_ok());
+    let account = Some("0123456789abcdef");
+    assert!(
+        fields(TargetConsole::Hen, true, account)
+            .validate()
+            .is_err()
+    );
+}
+
+#[test]
+fn hen_binds_saves_to_the_account() {
+    assert!(fields(TargetConsole::Hen, false, None).validate().is_err());
+    let hen = fields(TargetConsole::Hen, false, Some("0123456789abcdef"));
+    assert_eq!(hen.account_binding().unwrap(), "0123456789abcdef");
+    let cfw = fields(TargetConsole::Cfw, false, Some("0123456789abcdef"));
+    assert_eq!(cfw.account_binding().unwrap(), "0000000000000000");
+}
@@ -0,0 +1,39 @@
+//! The ZIP layout of `OutputSink`: blobs written during a run are there once
+//! it's finished, and a later run adds to the ZIP instead of replacing it.
+
+use std::fs::{self, File};
+
+use archive_dl::sink::{DumpFormat, OutputSink, ZIP_SINK_FILE};
+use zip::ZipArchive;
+
+mod common;
+use common::temp_dir;
+
+#[test]
+fn zip_sinks_keep_earlier_runs_blobs() {
+    let dir = temp_dir("zip");
+    let (first, second, third) = (
+        hex::encode([1u8; 20]),
+        hex::encode([2u8; 20]),
+        hex::encode([3u8; 20]),
+    );
+
+    let mut sink = OutputSink::open(&dir, DumpFormat::Zip).unwrap();
+    sink.write_blob(&first, b"first").unwrap();
+    sink.write_blob(&second, b"second").unwrap();
+    // blobs never change, the copy it has is kept
+    sink.write_blob(&first, b"again").unwrap();
+    sink.finish().unwrap();
+
+    let mut sink = OutputSink::open(&dir, DumpFormat::Zip).unwrap();
+    assert!(sink.has_blob(&first) && sink.has_blob(&second) && !sink.has_blob(&third));
+    sink.write_blob(&third, b"third").unwrap();
+    sink.finish().unwrap();
+
+    let zip = ZipArchive::new(File::open(dir.join(ZIP_SINK_FILE)).unwrap()).unwrap();
+    let mut names: Vec<&str> = zip.file_names().collect();
+    names.sort();
+    assert_eq!(names.len(), 3);
+    assert!(names[0].ends_with(&first) && names[2].ends_with(&third));
+    fs::remove_dir_all(dir).unwrap();
+}
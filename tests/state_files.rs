@@ -0,0 +1,123 @@
+//! What the state files decide on a rerun: which creators `--resume`
+//! fetches again, which levels `queue run` does and what concurrent queue
+//! changes leave behind.
+
+use std::fs;
+use std::path::PathBuf;
+
+use archive_dl::models::{CreatorState, CreatorStatus, LevelQueue, PlanetFetchState, QueueState};
+
+mod common;
+use common::temp_dir;
+
+fn temp_queue(name: &str) -> PathBuf {
+    temp_dir(name).join("queue.json")
+}
+
+fn status(state: CreatorState, filter_game: Option<&str>) -> CreatorStatus {
+    CreatorStatus {
+        state,
+        error: None,
+        filter_game: filter_game.map(str::to_string),
+    }
+}
+
+#[test]
+fn resume_fetches_unfinished_creators_again() {
+    assert!(status(CreatorState::Done, None).is_finished(None));
+    assert!(status(CreatorState::Skipped, None).is_finished(None));
+    assert!(!status(CreatorState::Failed, None).is_finished(None));
+    // stopped by the time limit, some levels were never fetched
+    assert!(!status(CreatorState::Partial, None).is_finished(None));
+}
+
+#[test]
+fn resume_counts_creators_done_for_its_game_only() {
+    let lbp2 = status(CreatorState::Done, Some("LBP2"));
+    assert!(lbp2.is_finished(Some("LBP2")));
+    assert!(!lbp2.is_finished(Some("LBP1")));
+    // the other games of the creator were never dumped
+    assert!(!lbp2.is_finished(None));
+    // nothing in LBP2 says nothing about their LBP3 levels
+    assert!(!status(CreatorState::Skipped, Some("LBP2")).is_finished(Some("LBP3")));
+
+    // a dump of every game covers any one of them
+    assert!(status(CreatorState::Done, None).is_finished(Some("LBP3")));
+}
+
+#[test]
+fn queue_runs_pending_levels_and_failed_ones_on_retry() {
+    let mut queue = LevelQueue::default();
+    for id in [1, 2, 3, 4] {
+        assert!(queue.add(id));
+    }
+    assert!(!queue.add(2));
+    queue.finish(1, Ok(()));
+    queue.finish(3, Err("no such level".into()));
+    // a level that isn't queued is left alone
+    queue.finish(9, Ok(()));
+
+    assert_eq!(queue.runnable(false), [2, 4]);
+    assert_eq!(queue.runnable(true), [2, 3, 4]);
+    let failed: Vec<_> = queue.in_state(QueueState::Failed).collect();
+    assert_eq!(failed.len(), 1);
+    assert_eq!(failed[0].error.as_deref(), Some("no such level"));
+    assert!(failed[0].run_at.is_some());
+
+    // a retry that works clears the error
+    queue.finish(3, Ok(()));
+    assert_eq!(queue.runnable(true), [2, 4]);
+    assert!(queue.levels.iter().all(|level| level.error.is_none()));
+}
+
+#[test]
+fn queue_updates_keep_levels_added_meanwhile() {
+    let path = temp_queue("merge");
+    LevelQueue::update(&path, |queue| queue.add(1)).unwrap();
+    // a run took its levels from here…
+    let ids = LevelQueue::read(&path).unwrap().runnable(false);
+    // …a `queue add` came in while it worked…
+    LevelQueue::update(&path, |queue| queue.add(2)).unwrap();
+    // …and the run records its level
+    LevelQueue::update(&path, |queue| queue.finish(ids[0], Ok(()))).unwrap();
+
+    let queue = LevelQueue::read(&path).unwrap();
+    assert_eq!(queue.levels.len(), 2);
+    assert_eq!(queue.runnable(false), [2]);
+    fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn concurrent_queue_updates_all_land() {
+    let path = temp_queue("threads");
+    let threads: Vec<_> = (0..8)
+        .map(|t| {
+            let path = path.clone();
+            std::thread::spawn(move || {
+                for i in 0..10 {
+                    LevelQueue::update(&path, |queue| queue.add(t * 100 + i)).unwrap();
+                }
+            })
+        })
+        .collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    assert_eq!(LevelQueue::read(&path).unwrap().levels.len(), 80);
+    fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn merged_planet_states_keep_every_planet() {
+    let mut first = PlanetFetchState::default();
+    first.record(&[1; 20], &[2; 20], [&[3; 20]]);
+    let mut second = PlanetFetchState::default();
+    second.record(&[1; 20], &[4; 20], [&[5; 20]]);
+    second.record(&[6; 20], &[7; 20], []);
+
+    first.merge(second);
+    let has_all = |_: &str| true;
+    assert!(first.is_complete(&[1; 20], &[2; 20], has_all));
+    assert!(first.is_complete(&[1; 20], &[4; 20], has_all));
+    assert!(first.is_complete(&[6; 20], &[7; 20], has_all));
+}
@@ -0,0 +1,63 @@
+//! Level names and descriptions as the SFO and slot list get them: what's
+//! dropped, what becomes a space and where long ones are cut.
+
+use archive_dl::serializers::text::{TextLimits, sanitize, truncate_bytes, truncate_chars};
+
+#[test]
+fn control_characters_become_spaces_and_nul_is_dropped() {
+    assert_eq!(sanitize("a\tb\0c\u{7f}d", false), "a bc d");
+    assert_eq!(sanitize("line\r\nnext", false), "line  next");
+    // only descriptions keep their line breaks
+    assert_eq!(sanitize("line\r\nnext", true), "line \nnext");
+}
+
+#[test]
+fn emoji_are_dropped_and_japanese_stays() {
+    assert_eq!(sanitize("レベル 🎮 one", false), "レベル  one");
+    // a family emoji, its joiners and a variation selector leave nothing
+    assert_eq!(sanitize("👨\u{200d}👩\u{200d}👧 ❤\u{fe0f}", false), " ❤");
+    assert_eq!(sanitize("1\u{fe0f}\u{20e3}", false), "1");
+}
+
+#[test]
+fn names_are_cut_by_characters() {
+    assert_eq!(truncate_chars("short", 5), "short");
+    assert_eq!(truncate_chars("longer name", 8), "longe...");
+    // characters, not bytes: each of these is three
+    assert_eq!(truncate_chars("ステージです", 6), "ステージです");
+    assert_eq!(truncate_chars("ステージです", 5), "ステ...");
+}
+
+#[test]
+fn bytes_are_cut_on_a_character_boundary() {
+    assert_eq!(truncate_bytes("abc", 3), "abc");
+    assert_eq!(truncate_bytes("abcdefgh", 7), "abcd...");
+    // 7 bytes leave room for 4, which is inside the second character
+    let cut = truncate_bytes("ステージ", 7);
+    assert_eq!(cut, "ス...");
+    assert!(cut.len() <= 7);
+}
+
+#[test]
+fn limits_apply_after_sanitizing() {
+    let limits = TextLimits {
+        name_max: Some(6),
+        description_max: Some(10),
+    };
+    assert!(limits.validate().is_ok());
+    // the emoji is gone before the name is measured
+    assert_eq!(limits.name("🎮abcdef"), "abcdef");
+    assert_eq!(limits.name("abcdefg"), "abc...");
+    assert_eq!(limits.description("one\ntwo\tthree"), "one\ntwo...");
+    assert_eq!(TextLimits::default().name("a\u{1}b"), "a b");
+}
+
+#[test]
+fn limits_too_short_for_the_ellipsis_are_refused() {
+    let limits = TextLimits {
+        name_max: None,
+        description_max: Some(3),
+    };
+    let e = limits.validate().unwrap_err().to_string();
+    assert!(e.contains("text_limits.description_max"), "{e}");
+}
@@ -0,0 +1,71 @@
+//! `db upgrade`: what the working copy keeps of a messy database, with
+//! duplicate slots and users, rows of the wrong type and orphans.
+
+use std::fs;
+
+use archive_dl::working_db::{is_current, normalize};
+use rusqlite::Connection;
+
+mod common;
+use common::temp_dir;
+
+#[test]
+fn duplicates_are_merged_or_dropped() {
+    let dir = temp_dir("working_db");
+    let (source, dest) = (dir.join("dump.db"), dir.join("working.db"));
+    let conn = Connection::open(&source).unwrap();
+    conn.execute_batch(
+        r#"CREATE TABLE slot (id, name, npHandle, rootLevel, game, shareable);
+           INSERT INTO slot VALUES
+             (1, 'first', 'alice', 'eafdb7ae0000000000000000000000000000aaaa', '1', NULL),
+             (1, 'again', 'alice', NULL, 1, 1),
+             (2, x'6e616d65', 'nobody', NULL, 2, 0);
+           CREATE TABLE "user" (npHandle, icon, locationX);
+           INSERT INTO "user" VALUES
+             ('alice', NULL, 5), ('alice', x'0102', 7), (NULL, NULL, 1);
+           CREATE TABLE slot_translation (id, language, name);
+           INSERT INTO slot_translation VALUES (1, 'de', 'erste'), (9, 'de', 'weg');
+           CREATE TABLE extra (value UNIQUE);
+           INSERT INTO extra VALUES ('kept');"#,
+    )
+    .unwrap();
+    drop(conn);
+
+    let report = normalize(&source, &dest).unwrap();
+    assert_eq!((report.slots, report.duplicate_slots), (2, 1));
+    assert_eq!(
+        (report.users, report.duplicate_users, report.dropped_users),
+        (1, 1, 1)
+    );
+    assert_eq!((report.orphans, report.slots_without_user), (1, 1));
+    assert_eq!(is_current(&source, &dest), Some(true));
+
+    let conn = Connection::open(&dest).unwrap();
+    let (name, root, game, shareable): (String, Vec<u8>, i64, i64) = conn
+        .query_row(
+            "SELECT name, rootLevel, game, shareable FROM slot WHERE id = 1",
+            [],
+            |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?)),
+        )
+        .unwrap();
+    assert_eq!(
+        (name.as_str(), root.len(), game, shareable),
+        ("first", 20, 1, 0)
+    );
+    let name: String = conn
+        .query_row("SELECT name FROM slot WHERE id = 2", [], |r| r.get(0))
+        .unwrap();
+    assert_eq!(name, "name");
+    let (icon, x): (Vec<u8>, i64) = conn
+        .query_row(r#"SELECT icon, locationX FROM "user""#, [], |r| {
+            Ok((r.get(0)?, r.get(1)?))
+        })
+        .unwrap();
+    assert_eq!((icon, x), (vec![1, 2], 5));
+    let extra: String = conn
+        .query_row("SELECT value FROM extra", [], |r| r.get(0))
+        .unwrap();
+    assert_eq!(extra, "kept");
+    drop(conn);
+    fs::remove_dir_all(dir).unwrap();
+}
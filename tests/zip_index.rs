@@ -0,0 +1,262 @@
+//! Reading archive ZIPs through their central directory: what an entry's
+//! index says about it, and blobs fetched from ZIPs a mirror split into
+//! `.z01` parts or `.zip.001` pieces, encrypted with ZipCrypto or not.
+
+use std::fs;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Once;
+
+use archive_dl::resource_dl::{self, zip_entry_name};
+use archive_dl::zip_index::{self, ZipParts};
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+mod common;
+use common::{resource, sha1, temp_dir};
+
+const PASSWORD: &str = "secret";
+
+/// A ZIP of `entries`, deflated as the archive's are
+fn zip_bytes(entries: &[(&str, &[u8])]) -> Vec<u8> {
+    let mut zip = ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+    for (name, data) in entries {
+        zip.start_file(*name, options).unwrap();
+        zip.write_all(data).unwrap();
+    }
+    zip.finish().unwrap().into_inner()
+}
+
+/// An uncompressed binary resource without dependencies, and its SHA1
+fn blob(magic: &[u8; 4]) -> (Vec<u8>, [u8; 20]) {
+    let res = resource(magic, &[]);
+    let hash = sha1(&res);
+    (res, hash)
+}
+
+/// Fetch `hash` from the archive at `archive`, with the disk cache off so
+/// every test reads its own ZIP. The cache dir only holds ZIP indexes, the
+/// tests share it, so each names its ZIP differently: saved indexes go by
+/// the ZIP's name, size and modification time
+fn fetch(hash: [u8; 20], archive: &Path) -> anyhow::Result<Vec<u8>> {
+    static SETUP: Once = Once::new();
+    SETUP.call_once(|| {
+        resource_dl::set_cache_dir(temp_dir("cache"));
+        resource_dl::set_disk_cache(false);
+        resource_dl::set_archive_password(PASSWORD.to_string());
+    });
+    tokio::runtime::Runtime::new()
+        .unwrap()
+        .block_on(resource_dl::fetch_resource(
+            hash,
+            archive.to_string_lossy().into_owned(),
+        ))
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(!0, |crc, &b| crc32_byte(crc, b))
+}
+
+fn crc32_byte(crc: u32, byte: u8) -> u32 {
+    (0..8).fold(crc ^ byte as u32, |c, _| match c & 1 {
+        1 => (c >> 1) ^ 0xedb8_8320,
+        _ => c >> 1,
+    })
+}
+
+/// `data` encrypted with ZipCrypto, its 12-byte header ending in the CRC's
+/// high byte as readers check
+fn zipcrypto(data: &[u8], crc: u32) -> Vec<u8> {
+    let mut keys = [0x1234_5678u32, 0x2345_6789, 0x3456_7890];
+    let update = |keys: &mut [u32; 3], byte: u8| {
+        keys[0] = crc32_byte(keys[0], byte);
+        keys[1] = keys[1]
+            .wrapping_add(keys[0] & 0xff)
+            .wrapping_mul(134_775_813)
+            .wrapping_add(1);
+        keys[2] = crc32_byte(keys[2], (keys[1] >> 24) as u8);
+    };
+    for &b in PASSWORD.as_bytes() {
+        update(&mut keys, b);
+    }
+    let mut plain = vec![0x5a; 11];
+    plain.push((crc >> 24) as u8);
+    plain.extend(data);
+    plain
+        .into_iter()
+        .map(|b| {
+            let temp = (keys[2] | 2) as u16;
+            let c = b ^ (temp.wrapping_mul(temp ^ 1) >> 8) as u8;
+            update(&mut keys, b);
+            c
+        })
+        .collect()
+}
+
+/// A stored entry, ZipCrypto encrypted with `encrypted`, as its local header
+/// and data, and the central directory record pointing at `disk`, `offset`
+fn stored_entry(
+    name: &str,
+    data: &[u8],
+    encrypted: bool,
+    disk: u16,
+    offset: u32,
+) -> (Vec<u8>, Vec<u8>) {
+    let crc = crc32(data);
+    let stored = match encrypted {
+        true => zipcrypto(data, crc),
+        false => data.to_vec(),
+    };
+    let fields = |header: &mut Vec<u8>| {
+        header.extend(20u16.to_le_bytes());
+        header.extend((encrypted as u16).to_le_bytes());
+        // stored, no time or date
+        header.extend([0; 6]);
+        header.extend(crc.to_le_bytes());
+        header.extend((stored.len() as u32).to_le_bytes());
+        header.extend((data.len() as u32).to_le_bytes());
+        header.extend((name.len() as u16).to_le_bytes());
+        header.extend(0u16.to_le_bytes());
+    };
+    let mut local = 0x0403_4b50u32.to_le_bytes().to_vec();
+    fields(&mut local);
+    local.extend(name.as_bytes());
+    local.extend(&stored);
+
+    let mut central = 0x0201_4b50u32.to_le_bytes().to_vec();
+    central.extend(20u16.to_le_bytes());
+    fields(&mut central);
+    central.extend(0u16.to_le_bytes());
+    central.extend(disk.to_le_bytes());
+    central.extend([0; 6]);
+    central.extend(offset.to_le_bytes());
+    central.extend(name.as_bytes());
+    (local, central)
+}
+
+/// The end of central directory record of a ZIP whose last part is `disk`
+fn end_of_directory(disk: u16, entries: u16, cd_size: usize, cd_offset: usize) -> Vec<u8> {
+    let mut end = 0x0605_4b50u32.to_le_bytes().to_vec();
+    end.extend(disk.to_le_bytes());
+    end.extend(disk.to_le_bytes());
+    end.extend(entries.to_le_bytes());
+    end.extend(entries.to_le_bytes());
+    end.extend((cd_size as u32).to_le_bytes());
+    end.extend((cd_offset as u32).to_le_bytes());
+    end.extend(0u16.to_le_bytes());
+    end
+}
+
+/// A ZIP with one stored entry in a `.z01` part and its central directory
+/// in the `.zip`, offsets counted per part
+fn write_spanned(zip: &Path, name: &str, data: &[u8], encrypted: bool) {
+    let (local, central) = stored_entry(name, data, encrypted, 0, 0);
+    fs::write(zip.with_extension("z01"), local).unwrap();
+    let mut last = central.clone();
+    last.extend(end_of_directory(1, 1, central.len(), 0));
+    fs::write(zip, last).unwrap();
+}
+
+/// A ZIP with one stored entry, in one file
+fn single_zip(name: &str, data: &[u8], encrypted: bool) -> Vec<u8> {
+    let (mut zip, central) = stored_entry(name, data, encrypted, 0, 0);
+    let cd_offset = zip.len();
+    zip.extend(&central);
+    zip.extend(end_of_directory(0, 1, central.len(), cd_offset));
+    zip
+}
+
+fn piece(zip: &Path, n: usize) -> PathBuf {
+    let mut piece = zip.as_os_str().to_owned();
+    piece.push(format!(".{:03}", n));
+    PathBuf::from(piece)
+}
+
+#[test]
+fn entries_know_their_inflated_size() {
+    let dir = temp_dir("size");
+    let texture = vec![7u8; 5000];
+    let zip = dir.join("blobs.zip");
+    fs::write(&zip, zip_bytes(&[("tex", &texture), ("empty", b"")])).unwrap();
+
+    let index = zip_index::index(&zip, &dir.join("index")).unwrap();
+    let entry = index.get("tex").unwrap();
+    assert_eq!(entry.size, 5000);
+    assert!(entry.compressed_size < entry.size);
+    assert_eq!((entry.method, entry.encrypted), (8, false));
+    assert_eq!(index.get("empty").unwrap().size, 0);
+    assert!(index.get("missing").is_none());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn pieces_are_read_as_one_file() {
+    let dir = temp_dir("pieces");
+    let zip = dir.join("blobs.zip");
+    let whole = zip_bytes(&[("a", &[1; 3000]), ("b", &[2; 3000])]);
+    // three pieces, the middle one empty
+    let cut = whole.len() / 2;
+    for (n, part) in [&whole[..cut], &[], &whole[cut..]].into_iter().enumerate() {
+        fs::write(piece(&zip, n + 1), part).unwrap();
+    }
+
+    let parts = ZipParts::find(&zip).unwrap();
+    assert!(parts.is_split() && !parts.is_spanned());
+    assert_eq!(parts.last(), piece(&zip, 3));
+    let mut reader = parts.open().unwrap();
+    let mut read = Vec::new();
+    reader.read_to_end(&mut read).unwrap();
+    assert_eq!(read, whole);
+    // a read across the cut only returns what's left of the piece it starts in
+    reader.seek(SeekFrom::Start(cut as u64 - 2)).unwrap();
+    let mut buf = [0; 4];
+    assert_eq!(reader.read(&mut buf).unwrap(), 2);
+    reader.read_exact(&mut buf[2..]).unwrap();
+    assert_eq!(buf, whole[cut - 2..cut + 2]);
+    assert!(reader.seek(SeekFrom::Current(-(cut as i64) - 10)).is_err());
+
+    let index = zip_index::index(&zip, &dir.join("index")).unwrap();
+    assert_eq!(index.get("b").unwrap().size, 3000);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn blobs_are_fetched_from_z01_parts() {
+    let dir = temp_dir("spanned");
+    let zip = dir.join("spanned.zip");
+    let (data, hash) = blob(b"PLNb");
+    write_spanned(&zip, &zip_entry_name(&hex::encode(hash)), &data, false);
+
+    let parts = ZipParts::find(&zip).unwrap();
+    assert!(parts.is_split() && parts.is_spanned());
+    assert_eq!(parts.last(), zip);
+    assert_eq!(fetch(hash, &zip).unwrap(), data);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn encrypted_blobs_are_fetched_from_a_single_piece() {
+    let dir = temp_dir("encrypted_piece");
+    let zip = dir.join("encrypted_piece.zip");
+    let (data, hash) = blob(b"LVLb");
+    let name = zip_entry_name(&hex::encode(hash));
+    fs::write(piece(&zip, 1), single_zip(&name, &data, true)).unwrap();
+
+    let index = zip_index::index(&zip, &dir.join("index")).unwrap();
+    assert!(index.get(&name).unwrap().encrypted);
+    assert_eq!(fetch(hash, &zip).unwrap(), data);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn encrypted_blobs_in_z01_parts_are_refused() {
+    let dir = temp_dir("encrypted_spanned");
+    let zip = dir.join("encrypted_spanned.zip");
+    let (data, hash) = blob(b"GMTb");
+    write_spanned(&zip, &zip_entry_name(&hex::encode(hash)), &data, true);
+
+    let e = fetch(hash, &zip).unwrap_err().to_string();
+    assert!(e.contains("split into .z01 parts"), "{e}");
+    fs::remove_dir_all(&dir).unwrap();
+}